@@ -638,6 +638,31 @@ pub fn remove_crates_except(&mut self, to_keep: &[CrateId]) -> Vec<Option<CrateI
         id_map
     }
 
+    /// Reorders the crates of this graph so that the ones in `first` come before all others,
+    /// keeping the relative order within both groups, and fixes up the dependencies.
+    /// Returns a mapping from old crate ids to new crate ids.
+    pub fn move_to_front(&mut self, first: &FxHashSet<CrateId>) -> Vec<CrateId> {
+        let (front, back): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.arena).into_iter().partition(|(id, _)| first.contains(id));
+        let mut id_map = vec![CrateId::from_raw(RawIdx::from_u32(0)); front.len() + back.len()];
+        self.arena = front
+            .into_iter()
+            .chain(back)
+            .enumerate()
+            .map(|(new_id, (id, data))| {
+                id_map[id.into_raw().into_u32() as usize] =
+                    CrateId::from_raw(RawIdx::from_u32(new_id as u32));
+                data
+            })
+            .collect();
+        for (_, data) in self.arena.iter_mut() {
+            data.dependencies
+                .iter_mut()
+                .for_each(|dep| dep.crate_id = id_map[dep.crate_id.into_raw().into_u32() as usize]);
+        }
+        id_map
+    }
+
     pub fn shrink_to_fit(&mut self) {
         self.arena.shrink_to_fit();
     }
@@ -889,4 +914,54 @@ fn dashes_are_normalized() {
             vec![Dependency::new(CrateName::new("crate_name_with_dashes").unwrap(), crate2,)]
         );
     }
+
+    #[test]
+    fn move_to_front_remaps_dependencies() {
+        let mut graph = CrateGraph::default();
+        let crate1 = graph.add_crate_root(
+            FileId::from_raw(1u32),
+            Edition2018,
+            None,
+            None,
+            Default::default(),
+            Default::default(),
+            Env::default(),
+            false,
+            CrateOrigin::Local { repo: None, name: None },
+        );
+        let crate2 = graph.add_crate_root(
+            FileId::from_raw(2u32),
+            Edition2018,
+            None,
+            None,
+            Default::default(),
+            Default::default(),
+            Env::default(),
+            false,
+            CrateOrigin::Local { repo: None, name: None },
+        );
+        let crate3 = graph.add_crate_root(
+            FileId::from_raw(3u32),
+            Edition2018,
+            None,
+            None,
+            Default::default(),
+            Default::default(),
+            Env::default(),
+            false,
+            CrateOrigin::Local { repo: None, name: None },
+        );
+        assert!(graph
+            .add_dep(crate3, Dependency::new(CrateName::new("crate2").unwrap(), crate2,))
+            .is_ok());
+        let id_map = graph.move_to_front(&[crate2, crate3].into_iter().collect());
+        assert_eq!(id_map, vec![crate3, crate1, crate2]);
+        assert_eq!(graph[crate1].root_file_id, FileId::from_raw(2u32));
+        assert_eq!(graph[crate2].root_file_id, FileId::from_raw(3u32));
+        assert_eq!(graph[crate3].root_file_id, FileId::from_raw(1u32));
+        assert_eq!(
+            graph[crate2].dependencies,
+            vec![Dependency::new(CrateName::new("crate2").unwrap(), crate1,)]
+        );
+    }
 }
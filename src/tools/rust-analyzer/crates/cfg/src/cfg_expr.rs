@@ -4,6 +4,7 @@
 
 use std::{fmt, slice::Iter as SliceIter};
 
+use syntax::{ast, AstNode, NodeOrToken, SyntaxElement, SyntaxKind, T};
 use tt::SmolStr;
 
 /// A simple configuration value passed in from the outside.
@@ -48,6 +49,12 @@ pub fn parse<S>(tt: &tt::Subtree<S>) -> CfgExpr {
         next_cfg_expr(&mut tt.token_trees.iter()).unwrap_or(CfgExpr::Invalid)
     }
 
+    /// Parses the predicate of a `#[cfg]` attribute straight from the syntax tree, for when there
+    /// is no [`tt::Subtree`] of it at hand.
+    pub fn parse_from_ast(tt: &ast::TokenTree) -> CfgExpr {
+        next_cfg_expr_from_ast(&mut token_tree_children(tt).iter()).unwrap_or(CfgExpr::Invalid)
+    }
+
     /// Fold the cfg by querying all basic `Atom` and `KeyValue` predicates.
     pub fn fold(&self, query: &dyn Fn(&CfgAtom) -> bool) -> Option<bool> {
         match self {
@@ -108,6 +115,67 @@ fn next_cfg_expr<S>(it: &mut SliceIter<'_, tt::TokenTree<S>>) -> Option<CfgExpr>
     Some(ret)
 }
 
+/// The tokens and subtrees of `tt`, without its delimiters.
+fn token_tree_children(tt: &ast::TokenTree) -> Vec<SyntaxElement> {
+    let mut children: Vec<_> =
+        tt.syntax().children_with_tokens().filter(|it| !it.kind().is_trivia()).collect();
+    if tt.left_delimiter_token().is_some() {
+        children.remove(0);
+    }
+    if tt.right_delimiter_token().is_some() {
+        children.pop();
+    }
+    children
+}
+
+fn next_cfg_expr_from_ast(it: &mut SliceIter<'_, SyntaxElement>) -> Option<CfgExpr> {
+    let name = match it.next() {
+        None => return None,
+        Some(NodeOrToken::Token(ident)) if ident.kind() == SyntaxKind::IDENT => {
+            SmolStr::new(ident.text())
+        }
+        Some(_) => return Some(CfgExpr::Invalid),
+    };
+
+    // Peek
+    let ret = match it.as_slice().first() {
+        Some(NodeOrToken::Token(punct)) if punct.kind() == T![=] => match it.as_slice().get(1) {
+            Some(NodeOrToken::Token(literal)) if literal.kind().is_literal() => {
+                let value =
+                    SmolStr::new(literal.text().trim_start_matches('"').trim_end_matches('"'));
+                it.next();
+                it.next();
+                CfgAtom::KeyValue { key: name, value }.into()
+            }
+            _ => return Some(CfgExpr::Invalid),
+        },
+        Some(NodeOrToken::Node(subtree)) => {
+            let Some(subtree) = ast::TokenTree::cast(subtree.clone()) else {
+                return Some(CfgExpr::Invalid);
+            };
+            it.next();
+            let children = token_tree_children(&subtree);
+            let mut sub_it = children.iter();
+            let mut subs = std::iter::from_fn(|| next_cfg_expr_from_ast(&mut sub_it)).collect();
+            match name.as_str() {
+                "all" => CfgExpr::All(subs),
+                "any" => CfgExpr::Any(subs),
+                "not" => CfgExpr::Not(Box::new(subs.pop().unwrap_or(CfgExpr::Invalid))),
+                _ => CfgExpr::Invalid,
+            }
+        }
+        _ => CfgAtom::Flag(name).into(),
+    };
+
+    // Eat comma separator
+    if let Some(NodeOrToken::Token(punct)) = it.as_slice().first() {
+        if punct.kind() == T![,] {
+            it.next();
+        }
+    }
+    Some(ret)
+}
+
 #[cfg(test)]
 impl arbitrary::Arbitrary<'_> for CfgAtom {
     fn arbitrary(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Self> {
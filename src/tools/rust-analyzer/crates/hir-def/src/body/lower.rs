@@ -206,7 +206,12 @@ fn collect(
                         let syntax_ptr = AstPtr::new(&e);
                         let expr = this.collect_expr(e);
                         this.alloc_expr_desugared_with_ptr(
-                            Expr::Async { id: None, statements: Box::new([]), tail: Some(expr) },
+                            Expr::Async {
+                                id: None,
+                                statements: Box::new([]),
+                                tail: Some(expr),
+                                capture_by: CaptureBy::Value,
+                            },
                             syntax_ptr,
                         )
                     }
@@ -276,11 +281,21 @@ fn maybe_collect_expr(&mut self, expr: ast::Expr) -> Option<ExprId> {
                     })
                 }
                 Some(ast::BlockModifier::Async(_)) => {
+                    let capture_by = if e
+                        .syntax()
+                        .children_with_tokens()
+                        .any(|it| it.kind() == syntax::T![move])
+                    {
+                        CaptureBy::Value
+                    } else {
+                        CaptureBy::Ref
+                    };
                     self.with_label_rib(RibKind::Closure, |this| {
                         this.collect_block_(e, |id, statements, tail| Expr::Async {
                             id,
                             statements,
                             tail,
+                            capture_by,
                         })
                     })
                 }
@@ -462,8 +462,12 @@ fn print_expr(&mut self, expr: ExprId) {
             Expr::Unsafe { id: _, statements, tail } => {
                 self.print_block(Some("unsafe "), statements, tail);
             }
-            Expr::Async { id: _, statements, tail } => {
-                self.print_block(Some("async "), statements, tail);
+            Expr::Async { id: _, statements, tail, capture_by } => {
+                let prefix = match capture_by {
+                    CaptureBy::Value => "async move ",
+                    CaptureBy::Ref => "async ",
+                };
+                self.print_block(Some(prefix), statements, tail);
             }
             Expr::Const(id) => {
                 w!(self, "const {{ /* {id:?} */ }}");
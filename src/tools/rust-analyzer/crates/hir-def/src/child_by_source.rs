@@ -209,8 +209,8 @@ fn child_by_source_to(&self, db: &dyn DefDatabase, res: &mut DynMap, file_id: Hi
         let mut toc_idx_iter = generic_params.type_or_consts.iter().map(|(idx, _)| idx);
         let lts_idx_iter = generic_params.lifetimes.iter().map(|(idx, _)| idx);
 
-        // For traits the first type index is `Self`, skip it.
-        if let GenericDefId::TraitId(_) = *self {
+        // For traits and trait aliases the first type index is `Self`, skip it.
+        if let GenericDefId::TraitId(_) | GenericDefId::TraitAliasId(_) = *self {
             toc_idx_iter.next().unwrap(); // advance_by(1);
         }
 
@@ -180,6 +180,7 @@ pub enum Expr {
         id: Option<BlockId>,
         statements: Box<[Statement]>,
         tail: Option<ExprId>,
+        capture_by: CaptureBy,
     },
     Const(ConstBlockId),
     // FIXME: Fold this into Block with an unsafe flag?
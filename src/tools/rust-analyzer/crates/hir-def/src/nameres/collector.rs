@@ -58,7 +58,6 @@
 };
 
 static GLOB_RECURSION_LIMIT: Limit = Limit::new(100);
-static EXPANSION_DEPTH_LIMIT: Limit = Limit::new(128);
 static FIXED_POINT_LIMIT: Limit = Limit::new(8192);
 
 pub(super) fn collect_defs(db: &dyn DefDatabase, def_map: DefMap, tree_id: TreeId) -> DefMap {
@@ -1382,9 +1381,18 @@ fn collect_macro_expansion(
         depth: usize,
         container: ItemContainerId,
     ) {
-        if EXPANSION_DEPTH_LIMIT.check(depth).is_err() {
+        let depth_limit = Limit::new(self.db.macro_expansion_limits().depth);
+        if depth_limit.check(depth).is_err() {
             cov_mark::hit!(macro_expansion_overflow);
-            tracing::warn!("macro expansion is too deep");
+            let loc: MacroCallLoc = self.db.lookup_intern_macro_call(macro_call_id);
+            self.def_map.diagnostics.push(DefDiagnostic::macro_error(
+                module_id,
+                loc.kind,
+                format!(
+                    "macro expansion is too deep: exceeds the limit of {}",
+                    depth_limit.inner()
+                ),
+            ));
             return;
         }
         let file_id = macro_call_id.as_file();
@@ -1,8 +1,11 @@
+use base_db::salsa::Durability;
 use expect_test::expect;
+use hir_expand::db::{ExpandDatabase, MacroExpansionLimits};
 
 use itertools::Itertools;
 
 use super::*;
+use crate::nameres::diagnostics::DefDiagnosticKind;
 
 #[test]
 fn macro_rules_are_globally_visible() {
@@ -978,6 +981,34 @@ macro_rules! b {
     );
 }
 
+#[test]
+fn macro_expansion_overflow_is_reported() {
+    let mut db = TestDB::with_files(
+        r#"
+macro_rules! m {
+    () => { m!(); };
+}
+
+m!();
+"#,
+    );
+    db.set_macro_expansion_limits_with_durability(
+        MacroExpansionLimits { depth: 4, ..Default::default() },
+        Durability::HIGH,
+    );
+    let krate = db.crate_graph().iter().next().unwrap();
+    let def_map = db.crate_def_map(krate);
+    let messages = def_map
+        .diagnostics()
+        .iter()
+        .filter_map(|diag| match &diag.kind {
+            DefDiagnosticKind::MacroError { message, .. } => Some(message.as_str()),
+            _ => None,
+        })
+        .collect_vec();
+    assert_eq!(messages, ["macro expansion is too deep: exceeds the limit of 4"]);
+}
+
 #[test]
 fn macros_defining_macros() {
     check(
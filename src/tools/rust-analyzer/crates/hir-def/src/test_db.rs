@@ -35,6 +35,7 @@ fn default() -> Self {
         let mut this = Self { storage: Default::default(), events: Default::default() };
         this.setup_syntax_context_root();
         this.set_expand_proc_attr_macros_with_durability(true, Durability::HIGH);
+        this.set_macro_expansion_limits_with_durability(Default::default(), Durability::HIGH);
         this
     }
 }
@@ -26,13 +26,25 @@
 };
 /// This is just to ensure the types of smart_macro_arg and macro_arg are the same
 type MacroArgResult = (Arc<tt::Subtree>, SyntaxFixupUndoInfo, Span);
-/// Total limit on the number of tokens produced by any macro invocation.
-///
-/// If an invocation produces more tokens than this limit, it will not be stored in the database and
-/// an error will be emitted.
-///
-/// Actual max for `analysis-stats .` at some point: 30672.
-static TOKEN_LIMIT: Limit = Limit::new(1_048_576);
+
+/// Limits that keep pathological macros from hanging analysis. Macro calls exceeding them are
+/// reported as errors and not expanded any further.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MacroExpansionLimits {
+    /// How deeply macro calls in item position may be nested in each other's expansions. Macro
+    /// calls in expressions are limited by the crate's `#![recursion_limit]` instead.
+    pub depth: usize,
+    /// Total limit on the number of tokens produced by any macro invocation.
+    ///
+    /// Actual max for `analysis-stats .` at some point: 30672.
+    pub token_count: usize,
+}
+
+impl Default for MacroExpansionLimits {
+    fn default() -> Self {
+        MacroExpansionLimits { depth: 128, token_count: 1_048_576 }
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TokenExpander {
@@ -56,6 +68,10 @@ pub trait ExpandDatabase: SourceDatabase {
     #[salsa::input]
     fn proc_macros(&self) -> Arc<ProcMacros>;
 
+    /// The limits macro expansion is subject to.
+    #[salsa::input]
+    fn macro_expansion_limits(&self) -> MacroExpansionLimits;
+
     fn ast_id_map(&self, file_id: HirFileId) -> Arc<AstIdMap>;
 
     /// Main public API -- parses a hir file, not caring whether it's a real
@@ -607,7 +623,7 @@ fn macro_expand(
     // Skip checking token tree limit for include! macro call
     if !loc.def.is_include() {
         // Set a hard limit for the expanded tt
-        if let Err(value) = check_tt_count(&tt) {
+        if let Err(value) = check_tt_count(db, &tt) {
             return value
                 .map(|()| {
                     CowArc::Owned(tt::Subtree {
@@ -662,7 +678,7 @@ fn expand_proc_macro(db: &dyn ExpandDatabase, id: MacroCallId) -> ExpandResult<A
     };
 
     // Set a hard limit for the expanded tt
-    if let Err(value) = check_tt_count(&tt) {
+    if let Err(value) = check_tt_count(db, &tt) {
         return value.map(|()| {
             Arc::new(tt::Subtree {
                 delimiter: tt::Delimiter::invisible_spanned(span),
@@ -691,15 +707,16 @@ fn token_tree_to_syntax_node(
     mbe::token_tree_to_syntax_node(tt, entry_point, edition)
 }
 
-fn check_tt_count(tt: &tt::Subtree) -> Result<(), ExpandResult<()>> {
+fn check_tt_count(db: &dyn ExpandDatabase, tt: &tt::Subtree) -> Result<(), ExpandResult<()>> {
+    let token_limit = Limit::new(db.macro_expansion_limits().token_count);
     let count = tt.count();
-    if TOKEN_LIMIT.check(count).is_err() {
+    if token_limit.check(count).is_err() {
         Err(ExpandResult {
             value: (),
             err: Some(ExpandError::other(format!(
                 "macro invocation exceeds token limit: produced {} tokens, limit is {}",
                 count,
-                TOKEN_LIMIT.inner(),
+                token_limit.inner(),
             ))),
         })
     } else {
@@ -294,6 +294,7 @@ macro_rules! known_names {
         iter,
         ops,
         fmt,
+        default,
         future,
         result,
         string,
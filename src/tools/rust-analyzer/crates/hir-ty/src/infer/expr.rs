@@ -178,7 +178,7 @@ fn infer_expr_inner(&mut self, tgt_expr: ExprId, expected: &Expectation) -> Ty {
                 })
                 .1
             }
-            Expr::Async { id, statements, tail } => {
+            Expr::Async { id, statements, tail, .. } => {
                 self.infer_async_block(tgt_expr, id, statements, tail)
             }
             &Expr::Loop { body, label } => {
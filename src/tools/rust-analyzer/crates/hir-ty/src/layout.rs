@@ -33,6 +33,7 @@
 };
 
 mod adt;
+mod coroutine;
 mod target;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -196,6 +197,62 @@ fn layout_of_simd_ty(
     }))
 }
 
+/// Like `layout_of_ty`, but also estimates the layout of the futures generated for async blocks and
+/// functions. The estimate is an upper bound of the actual size, so it must not be shown as exact.
+pub fn layout_upper_bound_of_ty(
+    db: &dyn HirDatabase,
+    ty: Ty,
+    trait_env: Arc<TraitEnvironment>,
+) -> Result<Arc<Layout>, LayoutError> {
+    layout_upper_bound_of_ty_in(db, ty, trait_env, &mut Vec::new())
+}
+
+/// `layout_upper_bound_of_ty` for a type nested in the `impl Trait` types in `visiting`, whose
+/// layouts are being estimated. These estimates don't go through a query, so a future containing
+/// itself, like the one of `async fn f() { f().await }`, has to be caught here.
+fn layout_upper_bound_of_ty_in(
+    db: &dyn HirDatabase,
+    ty: Ty,
+    trait_env: Arc<TraitEnvironment>,
+    visiting: &mut Vec<crate::ImplTraitId>,
+) -> Result<Arc<Layout>, LayoutError> {
+    let ty = normalize(db, trait_env.clone(), ty);
+    if let TyKind::OpaqueType(opaque_ty_id, _) = ty.kind(Interner) {
+        let impl_trait_id = db.lookup_intern_impl_trait_id((*opaque_ty_id).into());
+        if visiting.contains(&impl_trait_id) {
+            return Err(LayoutError::RecursiveTypeWithoutIndirection);
+        }
+        let result = match impl_trait_id {
+            crate::ImplTraitId::ReturnTypeImplTrait(func, idx) => {
+                let infer = db.infer(func.into());
+                visiting.push(impl_trait_id);
+                layout_upper_bound_of_ty_in(
+                    db,
+                    infer.type_of_rpit[idx].clone(),
+                    trait_env,
+                    visiting,
+                )
+            }
+            crate::ImplTraitId::AsyncBlockTypeImplTrait(owner, expr) => {
+                let Ok(target) = db.target_data_layout(trait_env.krate) else {
+                    return Err(LayoutError::TargetLayoutNotAvailable);
+                };
+                let cx = LayoutCx { target: &target };
+                let dl = cx.current_data_layout();
+                visiting.push(impl_trait_id);
+                coroutine::layout_of_async_block(db, &cx, dl, owner, expr, trait_env, visiting)
+                    .map(Arc::new)
+            }
+            crate::ImplTraitId::AssociatedTypeImplTrait(..) => {
+                return db.layout_of_ty(ty, trait_env)
+            }
+        };
+        visiting.pop();
+        return result;
+    }
+    db.layout_of_ty(ty, trait_env)
+}
+
 pub fn layout_of_ty_query(
     db: &dyn HirDatabase,
     ty: Ty,
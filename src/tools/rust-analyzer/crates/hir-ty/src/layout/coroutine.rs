@@ -0,0 +1,145 @@
+//! Estimate the binary representation of the futures generated for async blocks and functions
+
+use std::iter;
+
+use chalk_ir::{Mutability, TyKind};
+use hir_def::{
+    body::Body,
+    hir::{BindingId, CaptureBy, Expr, ExprId},
+    layout::{Integer, LayoutCalculator, Primitive, ReprOptions, StructKind, TargetDataLayout},
+    resolver::{resolver_for_expr, ResolveValueResult, ValueNs},
+    DefWithBodyId,
+};
+use rustc_index::IndexVec;
+use triomphe::Arc;
+
+use crate::{
+    db::HirDatabase,
+    error_lifetime,
+    layout::{layout_upper_bound_of_ty_in, scalar, Layout, LayoutError},
+    ImplTraitId, Interner, TraitEnvironment,
+};
+
+use super::LayoutCx;
+
+/// Estimates the layout of the future generated for the async block `async_expr`, which is the
+/// body expression of `owner` for async functions.
+///
+/// Like in rustc, the captured variables and the state tag form a prefix shared by all states,
+/// and the locals of each suspension point are laid out after it. Instead of computing which
+/// locals are live across each `.await`, every binding in scope at a suspension point is assumed
+/// to be held there together with the awaited future, so the result is an upper bound of the size
+/// rustc computes. `visiting` holds the `impl Trait` types whose layouts are being estimated,
+/// including the one of this async block.
+pub(super) fn layout_of_async_block(
+    db: &dyn HirDatabase,
+    cx: &LayoutCx<'_>,
+    dl: &TargetDataLayout,
+    owner: DefWithBodyId,
+    async_expr: ExprId,
+    trait_env: Arc<TraitEnvironment>,
+    visiting: &mut Vec<ImplTraitId>,
+) -> Result<Layout, LayoutError> {
+    let body = db.body(owner);
+    let infer = db.infer(owner);
+    let scopes = db.expr_scopes(owner);
+    let Expr::Async { capture_by, .. } = body[async_expr] else {
+        return Err(LayoutError::Unknown);
+    };
+    let async_scope = scopes.scope_for(async_expr);
+    let outer_bindings = scopes
+        .scope_chain(async_scope)
+        .flat_map(|scope| scopes.entries(scope))
+        .map(|entry| entry.binding())
+        .collect::<Vec<_>>();
+
+    let mut paths = Vec::new();
+    let mut awaits = Vec::new();
+    body[async_expr].walk_child_exprs(|expr| {
+        visit_exprs(&body, expr, true, &mut |expr, own| match body[expr] {
+            Expr::Path(_) => paths.push(expr),
+            Expr::Await { expr: future } if own => awaits.push((future, scopes.scope_for(expr))),
+            _ => (),
+        })
+    });
+
+    let captures = if body.body_expr == async_expr {
+        // The arguments of an async function are all moved into its future.
+        outer_bindings
+    } else {
+        let mut captures = Vec::<BindingId>::new();
+        for expr in paths {
+            let Expr::Path(path) = &body[expr] else { continue };
+            let resolver = resolver_for_expr(db.upcast(), owner, expr);
+            if let Some(ResolveValueResult::ValueNs(ValueNs::LocalBinding(binding), _)) =
+                resolver.resolve_path_in_value_ns(db.upcast(), path)
+            {
+                if outer_bindings.contains(&binding) && !captures.contains(&binding) {
+                    captures.push(binding);
+                }
+            }
+        }
+        captures
+    };
+    let mut fields = captures
+        .into_iter()
+        .map(|binding| {
+            let ty = infer.type_of_binding[binding].clone();
+            let ty = match capture_by {
+                CaptureBy::Value => ty,
+                CaptureBy::Ref => {
+                    TyKind::Ref(Mutability::Not, error_lifetime(), ty).intern(Interner)
+                }
+            };
+            layout_upper_bound_of_ty_in(db, ty, trait_env.clone(), visiting)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The unresumed, returned and poisoned states come before the suspension points.
+    let states = awaits.len() as u128 + 3;
+    fields.push(Arc::new(scalar(dl, Primitive::Int(Integer::fit_unsigned(states - 1), false))));
+    let prefix = univariant(cx, dl, &fields, StructKind::AlwaysSized)?;
+
+    let kind = StructKind::Prefixed(prefix.size, prefix.align.abi);
+
+    let mut align = prefix.align;
+    let mut layout = prefix;
+    for (future, scope) in awaits {
+        let held = scopes
+            .scope_chain(scope)
+            .take_while(|&scope| Some(scope) != async_scope)
+            .flat_map(|scope| scopes.entries(scope))
+            .map(|entry| infer.type_of_binding[entry.binding()].clone())
+            .chain(iter::once(infer[future].clone()))
+            .map(|ty| layout_upper_bound_of_ty_in(db, ty, trait_env.clone(), visiting))
+            .collect::<Result<Vec<_>, _>>()?;
+        let suspension_point = univariant(cx, dl, &held, kind)?;
+        align = align.max(suspension_point.align);
+        if suspension_point.size > layout.size {
+            layout = suspension_point;
+        }
+    }
+    layout.align = align;
+    layout.size = layout.size.align_to(align.abi);
+
+    Ok(layout)
+}
+
+/// Calls `f` on `expr` and all of its descendants, telling whether they belong to the async block
+/// being visited rather than to a closure or async block nested in it.
+fn visit_exprs(body: &Body, expr: ExprId, own: bool, f: &mut impl FnMut(ExprId, bool)) {
+    f(expr, own);
+    let own = own && !matches!(body[expr], Expr::Async { .. } | Expr::Closure { .. });
+    body[expr].walk_child_exprs(|child| visit_exprs(body, child, own, f));
+}
+
+fn univariant(
+    cx: &LayoutCx<'_>,
+    dl: &TargetDataLayout,
+    fields: &[Arc<Layout>],
+    kind: StructKind,
+) -> Result<Layout, LayoutError> {
+    let fields = fields.iter().map(|it| &**it).collect::<Vec<_>>();
+    let fields = fields.iter().collect::<IndexVec<_, _>>();
+    cx.univariant(dl, &fields, &ReprOptions::default(), kind).ok_or(LayoutError::Unknown)
+}
@@ -8,9 +8,9 @@
 
 use crate::{
     db::HirDatabase,
-    layout::{Layout, LayoutError},
+    layout::{layout_upper_bound_of_ty, Layout, LayoutError},
     test_db::TestDB,
-    Interner, Substitution,
+    Interner, Substitution, TraitEnvironment, Ty,
 };
 
 mod closure;
@@ -74,6 +74,14 @@ fn eval_goal(ra_fixture: &str, minicore: &str) -> Result<Arc<Layout>, LayoutErro
 
 /// A version of `eval_goal` for types that can not be expressed in ADTs, like closures and `impl Trait`
 fn eval_expr(ra_fixture: &str, minicore: &str) -> Result<Arc<Layout>, LayoutError> {
+    eval_expr_with(ra_fixture, minicore, |db, ty, env| db.layout_of_ty(ty, env))
+}
+
+fn eval_expr_with(
+    ra_fixture: &str,
+    minicore: &str,
+    layout_of: impl FnOnce(&TestDB, Ty, Arc<TraitEnvironment>) -> Result<Arc<Layout>, LayoutError>,
+) -> Result<Arc<Layout>, LayoutError> {
     let target_data_layout = current_machine_data_layout();
     let ra_fixture = format!(
         "//- target_data_layout: {target_data_layout}\n{minicore}//- /main.rs crate:test\nfn main(){{let goal = {{{ra_fixture}}};}}",
@@ -97,7 +105,7 @@ fn eval_expr(ra_fixture: &str, minicore: &str) -> Result<Arc<Layout>, LayoutErro
     let b = hir_body.bindings.iter().find(|x| x.1.name.to_smol_str() == "goal").unwrap().0;
     let infer = db.infer(function_id.into());
     let goal_ty = infer.type_of_binding[b].clone();
-    db.layout_of_ty(goal_ty, db.trait_environment(function_id.into()))
+    layout_of(&db, goal_ty, db.trait_environment(function_id.into()))
 }
 
 #[track_caller]
@@ -114,6 +122,15 @@ fn check_size_and_align_expr(ra_fixture: &str, minicore: &str, size: u64, align:
     assert_eq!(l.align.abi.bytes(), align, "align mismatch");
 }
 
+#[track_caller]
+fn check_size_and_align_upper_bound_expr(ra_fixture: &str, minicore: &str, size: u64, align: u64) {
+    let l =
+        eval_expr_with(ra_fixture, minicore, |db, ty, env| layout_upper_bound_of_ty(db, ty, env))
+            .unwrap();
+    assert_eq!(l.size.bytes(), size, "size mismatch");
+    assert_eq!(l.align.abi.bytes(), align, "align mismatch");
+}
+
 #[track_caller]
 fn check_fail(ra_fixture: &str, e: LayoutError) {
     let r = eval_goal(ra_fixture, "");
@@ -182,6 +199,25 @@ macro_rules! size_and_align_expr {
     };
 }
 
+/// Like `size_and_align_expr`, but for futures, whose layout is only estimated.
+macro_rules! size_and_align_upper_bound_expr {
+    (minicore: $($x:tt),*; stmts: [$($s:tt)*] $($t:tt)*) => {
+        {
+            #[allow(dead_code)]
+            {
+                $($s)*
+                let val = { $($t)* };
+                check_size_and_align_upper_bound_expr(
+                    &format!("{{ {} let val = {{ {} }}; val }}", stringify!($($s)*), stringify!($($t)*)),
+                    &format!("//- minicore: {}\n", stringify!($($x),*)),
+                    ::std::mem::size_of_val(&val) as u64,
+                    ::std::mem::align_of_val(&val) as u64,
+                );
+            }
+        }
+    };
+}
+
 #[test]
 fn hello_world() {
     size_and_align! {
@@ -395,6 +431,58 @@ fn foo() -> Foo<impl T> { Foo(
     }
 }
 
+#[test]
+fn async_block() {
+    size_and_align_upper_bound_expr! {
+        minicore: future;
+        stmts: [
+            let x: i32 = 5;
+        ]
+        async { x }
+    }
+    size_and_align_upper_bound_expr! {
+        minicore: future;
+        stmts: [
+            let x: i32 = 5;
+        ]
+        async move { x }
+    }
+    size_and_align_upper_bound_expr! {
+        minicore: future;
+        stmts: []
+        async fn f(x: i64) -> i64 { x }
+        f(5)
+    }
+    size_and_align_upper_bound_expr! {
+        minicore: future;
+        stmts: []
+        async {
+            let y: i64 = 5;
+            async {}.await;
+            y
+        }
+    }
+    // The estimate is not exact in general, so `layout_of_ty` doesn't compute it.
+    let r = eval_expr("async {}", "//- minicore: future\n");
+    assert_eq!(r, Err(LayoutError::NotImplemented));
+}
+
+#[test]
+fn recursive_async_fn() {
+    let r = eval_expr_with(
+        "async fn f() { f().await } f()",
+        "//- minicore: future\n",
+        |db, ty, env| layout_upper_bound_of_ty(db, ty, env),
+    );
+    assert_eq!(r, Err(LayoutError::RecursiveTypeWithoutIndirection));
+    let r = eval_expr_with(
+        "async fn f() { g().await } async fn g() { let x = 5; f().await; } f()",
+        "//- minicore: future\n",
+        |db, ty, env| layout_upper_bound_of_ty(db, ty, env),
+    );
+    assert_eq!(r, Err(LayoutError::RecursiveTypeWithoutIndirection));
+}
+
 #[test]
 fn unsized_ref() {
     size_and_align! {
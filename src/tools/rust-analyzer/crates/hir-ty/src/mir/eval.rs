@@ -11,8 +11,8 @@
     lang_item::LangItem,
     layout::{TagEncoding, Variants},
     resolver::{HasResolver, TypeNs, ValueNs},
-    AdtId, ConstId, DefWithBodyId, EnumVariantId, FunctionId, HasModule, ItemContainerId, Lookup,
-    StaticId, VariantId,
+    AdtId, DefWithBodyId, EnumVariantId, FunctionId, HasModule, ItemContainerId, Lookup, StaticId,
+    VariantId,
 };
 use hir_expand::{mod_path::ModPath, HirFileIdExt, InFile};
 use intern::Interned;
@@ -533,6 +533,36 @@ pub fn interpret_mir(
     // (and probably should) do better here, for example by excluding bindings outside of the target expression.
     assert_placeholder_ty_is_unused: bool,
     trait_env: Option<Arc<TraitEnvironment>>,
+) -> (Result<Const>, MirOutput) {
+    interpret_mir_with_args(
+        db,
+        body,
+        assert_placeholder_ty_is_unused,
+        trait_env,
+        |_| Ok(Vec::new()),
+    )
+}
+
+/// Like [`interpret_mir`], but the arguments of `body` are created with `Default::default` first.
+pub fn interpret_mir_with_default_args(
+    db: &dyn HirDatabase,
+    body: Arc<MirBody>,
+    trait_env: Option<Arc<TraitEnvironment>>,
+) -> (Result<Const>, MirOutput) {
+    let owner = body.owner;
+    let param_tys =
+        body.param_locals.iter().map(|&it| body.locals[it].ty.clone()).collect::<Vec<_>>();
+    interpret_mir_with_args(db, body, false, trait_env, |evaluator| {
+        param_tys.into_iter().map(|ty| evaluator.default_value(owner, ty)).collect()
+    })
+}
+
+fn interpret_mir_with_args(
+    db: &dyn HirDatabase,
+    body: Arc<MirBody>,
+    assert_placeholder_ty_is_unused: bool,
+    trait_env: Option<Arc<TraitEnvironment>>,
+    make_args: impl FnOnce(&mut Evaluator<'_>) -> Result<Vec<IntervalOrOwned>>,
 ) -> (Result<Const>, MirOutput) {
     let ty = body.locals[return_slot()].ty.clone();
     let mut evaluator =
@@ -544,7 +574,8 @@ pub fn interpret_mir(
         if evaluator.ptr_size() != std::mem::size_of::<usize>() {
             not_supported!("targets with different pointer size from host");
         }
-        let interval = evaluator.interpret_mir(body.clone(), None.into_iter())?;
+        let args = make_args(&mut evaluator)?;
+        let interval = evaluator.interpret_mir(body.clone(), args.into_iter())?;
         let bytes = interval.get(&evaluator)?;
         let mut memory_map = evaluator.create_memory_map(
             bytes,
@@ -991,6 +1022,41 @@ fn interpret_mir(
         }
     }
 
+    /// Evaluates `<ty as Default>::default()`, with `Default` resolved from `owner`.
+    fn default_value(&mut self, owner: DefWithBodyId, ty: Ty) -> Result<IntervalOrOwned> {
+        let resolver = owner.resolver(self.db.upcast());
+        let Some(TypeNs::TraitId(default_trait)) = resolver.resolve_path_in_type_ns_fully(
+            self.db.upcast(),
+            &hir_def::path::Path::from_known_path_with_no_generic(ModPath::from_segments(
+                hir_expand::mod_path::PathKind::Abs,
+                [name![core], name![default], name![Default]],
+            )),
+        ) else {
+            not_supported!("core::default::Default not found");
+        };
+        let Some(default_fn) = self.db.trait_data(default_trait).method_by_name(&name![default])
+        else {
+            not_supported!("core::default::Default::default not found");
+        };
+        let (imp, generic_args) = self.db.lookup_impl_method(
+            self.trait_env.clone(),
+            default_fn,
+            Substitution::from1(Interner, ty.clone()),
+        );
+        if imp == default_fn {
+            return Err(MirEvalError::NotSupported(format!(
+                "argument of type `{}` without a `Default` implementation",
+                ty.display(self.db)
+            )));
+        }
+        let body = self
+            .db
+            .monomorphized_mir_body(imp.into(), generic_args, self.trait_env.clone())
+            .map_err(|e| MirEvalError::MirLowerError(imp, e))?;
+        let interval = self.interpret_mir(body, iter::empty())?;
+        Ok(IntervalOrOwned::Owned(interval.get(self)?.to_vec()))
+    }
+
     fn fill_locals_for_body(
         &mut self,
         body: &MirBody,
@@ -2691,14 +2757,14 @@ fn write_to_stderr(&mut self, interval: Interval) -> Result<()> {
 
 pub fn render_const_using_debug_impl(
     db: &dyn HirDatabase,
-    owner: ConstId,
+    owner: DefWithBodyId,
     c: &Const,
 ) -> Result<String> {
-    let mut evaluator = Evaluator::new(db, owner.into(), false, None)?;
+    let mut evaluator = Evaluator::new(db, owner, false, None)?;
     let locals = &Locals {
         ptr: ArenaMap::new(),
         body: db
-            .mir_body(owner.into())
+            .mir_body(owner)
             .map_err(|_| MirEvalError::NotSupported("unreachable".to_owned()))?,
         drop_flags: DropFlags::default(),
     };
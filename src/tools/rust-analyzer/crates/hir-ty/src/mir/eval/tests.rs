@@ -2,12 +2,27 @@
 use hir_def::db::DefDatabase;
 use syntax::{TextRange, TextSize};
 use test_fixture::WithFixture;
+use triomphe::Arc;
 
-use crate::{db::HirDatabase, test_db::TestDB, Interner, Substitution};
+use crate::{db::HirDatabase, mir::MirBody, test_db::TestDB, Interner, Substitution};
 
-use super::{interpret_mir, MirEvalError};
+use super::{interpret_mir, interpret_mir_with_default_args, MirEvalError};
 
 fn eval_main(db: &TestDB, file_id: FileId) -> Result<(String, String), MirEvalError> {
+    let body = main_body(db, file_id)?;
+    let (result, output) = interpret_mir(db, body, false, None);
+    result?;
+    Ok((output.stdout().into_owned(), output.stderr().into_owned()))
+}
+
+/// Like `eval_main`, but creates the arguments of `main` with `Default::default()`.
+fn eval_main_with_default_args(db: &TestDB, file_id: FileId) -> Result<(), MirEvalError> {
+    let body = main_body(db, file_id)?;
+    interpret_mir_with_default_args(db, body, None).0?;
+    Ok(())
+}
+
+fn main_body(db: &TestDB, file_id: FileId) -> Result<Arc<MirBody>, MirEvalError> {
     let module_id = db.module_for_file(file_id);
     let def_map = module_id.def_map(db);
     let scope = &def_map[module_id.local_id].scope;
@@ -24,22 +39,29 @@ fn eval_main(db: &TestDB, file_id: FileId) -> Result<(String, String), MirEvalEr
             _ => None,
         })
         .expect("no main function found");
-    let body = db
-        .monomorphized_mir_body(
-            func_id.into(),
-            Substitution::empty(Interner),
-            db.trait_environment(func_id.into()),
-        )
-        .map_err(|e| MirEvalError::MirLowerError(func_id, e))?;
-    let (result, output) = interpret_mir(db, body, false, None);
-    result?;
-    Ok((output.stdout().into_owned(), output.stderr().into_owned()))
+    db.monomorphized_mir_body(
+        func_id.into(),
+        Substitution::empty(Interner),
+        db.trait_environment(func_id.into()),
+    )
+    .map_err(|e| MirEvalError::MirLowerError(func_id, e))
 }
 
 fn check_pass(ra_fixture: &str) {
     check_pass_and_stdio(ra_fixture, "", "");
 }
 
+fn check_pass_with_default_args(ra_fixture: &str) {
+    let (db, file_ids) = TestDB::with_many_files(ra_fixture);
+    let file_id = *file_ids.last().unwrap();
+    if let Err(e) = eval_main_with_default_args(&db, file_id) {
+        let mut err = String::new();
+        let span_formatter = |file, range: TextRange| format!("{file:?} {range:?}");
+        e.pretty_print(&mut err, &db, span_formatter).unwrap();
+        panic!("Error in interpreting: {err}");
+    }
+}
+
 fn check_pass_and_stdio(ra_fixture: &str, expected_stdout: &str, expected_stderr: &str) {
     let (db, file_ids) = TestDB::with_many_files(ra_fixture);
     let file_id = *file_ids.last().unwrap();
@@ -87,6 +109,80 @@ fn main() {
     );
 }
 
+#[test]
+fn arguments_from_default() {
+    check_pass_with_default_args(
+        r#"
+//- minicore: default, derive, builtin_impls
+#[derive(Default)]
+struct Config {
+    level: i32,
+    retries: u8,
+}
+
+fn should_not_reach() {
+    _ // FIXME: replace this function with panic when that works
+}
+
+fn main(config: Config, n: usize) {
+    if config.level != 0 || config.retries != 0 || n != 0 {
+        should_not_reach();
+    }
+}
+        "#,
+    );
+}
+
+#[test]
+fn arguments_from_manual_default_impl() {
+    check_pass_with_default_args(
+        r#"
+//- minicore: default
+struct Port(u16);
+
+impl Default for Port {
+    fn default() -> Self {
+        Port(8080)
+    }
+}
+
+fn should_not_reach() {
+    _ // FIXME: replace this function with panic when that works
+}
+
+fn main(port: Port) {
+    if port.0 != 8080 {
+        should_not_reach();
+    }
+}
+        "#,
+    );
+}
+
+#[test]
+fn arguments_without_default_impl() {
+    let (db, file_ids) = TestDB::with_many_files(
+        r#"
+//- minicore: default
+struct NoDefault;
+
+fn main(x: NoDefault) {}
+        "#,
+    );
+    let e = eval_main_with_default_args(&db, *file_ids.last().unwrap()).unwrap_err();
+    assert!(matches!(e, MirEvalError::NotSupported(it) if it.contains("NoDefault")));
+}
+
+#[test]
+fn main_with_arguments_needs_default_args() {
+    let (db, file_ids) = TestDB::with_many_files(
+        r#"
+fn main(n: usize) {}
+        "#,
+    );
+    assert!(eval_main(&db, *file_ids.last().unwrap()).is_err());
+}
+
 #[test]
 fn drop_basic() {
     check_pass(
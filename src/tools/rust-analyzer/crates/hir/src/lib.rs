@@ -68,7 +68,7 @@
     error_lifetime, known_const_to_ast,
     layout::{Layout as TyLayout, RustcEnumVariantIdx, RustcFieldIdx, TagEncoding},
     method_resolution::{self, TyFingerprint},
-    mir::{interpret_mir, MutBorrowKind},
+    mir::{interpret_mir_with_default_args, MutBorrowKind},
     primitive::UintTy,
     traits::FnTrait,
     AliasTy, CallableDefId, CallableSig, Canonical, CanonicalVarKinds, Cast, ClosureId, GenericArg,
@@ -234,6 +234,12 @@ pub fn display_name(self, db: &dyn HirDatabase) -> Option<CrateDisplayName> {
         db.crate_graph()[self.id].display_name.clone()
     }
 
+    /// Whether the build system compiles this crate into an executable, like binary targets and
+    /// examples of Cargo packages.
+    pub fn is_executable(self, db: &dyn HirDatabase) -> bool {
+        db.crate_graph()[self.id].env.get("CARGO_BIN_NAME").is_some()
+    }
+
     pub fn query_external_importables(
         self,
         db: &dyn DefDatabase,
@@ -2075,9 +2081,14 @@ pub fn eval(
                 return r;
             }
         };
-        let (result, output) = interpret_mir(db, body, false, None);
+        let (result, output) = interpret_mir_with_default_args(db, body, None);
         let mut text = match result {
-            Ok(_) => "pass".to_owned(),
+            Ok(value) if value.data(Interner).ty.is_unit() => "pass".to_owned(),
+            Ok(value) => {
+                let value = mir::render_const_using_debug_impl(db, self.id.into(), &value)
+                    .unwrap_or_else(|_| value.display(db).to_string());
+                format!("pass\n--------- result ---------\n{value}")
+            }
             Err(e) => {
                 let mut r = String::new();
                 _ = e.pretty_print(&mut r, db, &span_formatter);
@@ -2089,7 +2100,7 @@ pub fn eval(
             text += "\n--------- stdout ---------\n";
             text += &stdout;
         }
-        let stderr = output.stdout().into_owned();
+        let stderr = output.stderr().into_owned();
         if !stderr.is_empty() {
             text += "\n--------- stderr ---------\n";
             text += &stderr;
@@ -2341,7 +2352,7 @@ pub fn render_eval(self, db: &dyn HirDatabase) -> Result<String, ConstEvalError>
                 }
             }
         }
-        if let Ok(s) = mir::render_const_using_debug_impl(db, self.id, &c) {
+        if let Ok(s) = mir::render_const_using_debug_impl(db, self.id.into(), &c) {
             Ok(s)
         } else {
             Ok(format!("{}", c.display(db)))
@@ -4830,6 +4841,13 @@ pub fn layout(&self, db: &dyn HirDatabase) -> Result<Layout, LayoutError> {
         db.layout_of_ty(self.ty.clone(), self.env.clone())
             .map(|layout| Layout(layout, db.target_data_layout(self.env.krate).unwrap()))
     }
+
+    /// Like [`Type::layout`], but also estimates the layout of async fn and block futures. The
+    /// result is an upper bound of the actual size.
+    pub fn layout_upper_bound(&self, db: &dyn HirDatabase) -> Result<Layout, LayoutError> {
+        hir_ty::layout::layout_upper_bound_of_ty(db, self.ty.clone(), self.env.clone())
+            .map(|layout| Layout(layout, db.target_data_layout(self.env.krate).unwrap()))
+    }
 }
 
 // FIXME: Document this
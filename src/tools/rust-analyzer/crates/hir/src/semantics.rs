@@ -220,6 +220,10 @@ pub fn resolve_try_expr(&self, try_expr: &ast::TryExpr) -> Option<Function> {
         self.imp.resolve_try_expr(try_expr).map(Function::from)
     }
 
+    pub fn resolve_for_loop_into_iter(&self, for_expr: &ast::ForExpr) -> Option<Function> {
+        self.imp.resolve_for_loop_into_iter(for_expr).map(Function::from)
+    }
+
     pub fn resolve_variant(&self, record_lit: ast::RecordExpr) -> Option<VariantDef> {
         self.imp.resolve_variant(record_lit).map(VariantDef::from)
     }
@@ -363,6 +367,14 @@ pub fn expand_derive_macro(&self, attr: &ast::Attr) -> Option<Vec<SyntaxNode>> {
         Some(res)
     }
 
+    /// Expands the derive at `index` in the derive list of `attr`.
+    pub fn expand_derive_macro_at(&self, attr: &ast::Attr, index: usize) -> Option<SyntaxNode> {
+        let file_id = self.derive_macro_calls(attr)?.get(index).copied().flatten()?.as_file();
+        let node = self.db.parse_or_expand(file_id);
+        self.cache(node.clone(), file_id);
+        Some(node)
+    }
+
     fn derive_macro_calls(&self, attr: &ast::Attr) -> Option<Vec<Option<MacroCallId>>> {
         let adt = attr.syntax().parent().and_then(ast::Adt::cast)?;
         let file_id = self.find_file(adt.syntax()).file_id;
@@ -1209,6 +1221,10 @@ fn resolve_try_expr(&self, try_expr: &ast::TryExpr) -> Option<FunctionId> {
         self.analyze(try_expr.syntax())?.resolve_try_expr(self.db, try_expr)
     }
 
+    fn resolve_for_loop_into_iter(&self, for_expr: &ast::ForExpr) -> Option<FunctionId> {
+        self.analyze(for_expr.syntax())?.resolve_for_loop_into_iter(self.db, for_expr)
+    }
+
     pub fn resolve_method_call_as_callable(&self, call: &ast::MethodCallExpr) -> Option<Callable> {
         self.analyze(call.syntax())?.resolve_method_call_as_callable(self.db, call)
     }
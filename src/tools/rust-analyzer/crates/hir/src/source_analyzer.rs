@@ -492,6 +492,26 @@ pub(crate) fn resolve_try_expr(
         Some(self.resolve_impl_method_or_trait_def(db, op_fn, substs))
     }
 
+    pub(crate) fn resolve_for_loop_into_iter(
+        &self,
+        db: &dyn HirDatabase,
+        for_expr: &ast::ForExpr,
+    ) -> Option<FunctionId> {
+        let ty = self.ty_of_expr(db, &for_expr.iterable()?)?;
+
+        let op_fn =
+            db.lang_item(self.resolver.krate(), LangItem::IntoIterIntoIter)?.as_function()?;
+        let op_trait = match op_fn.lookup(db.upcast()).container {
+            ItemContainerId::TraitId(id) => id,
+            _ => return None,
+        };
+        // HACK: subst for `into_iter()` coincides with that for `IntoIterator` because
+        // `into_iter()` itself doesn't have any generic parameters.
+        let substs = hir_ty::TyBuilder::subst_for_def(db, op_trait, None).push(ty.clone()).build();
+
+        Some(self.resolve_impl_method_or_trait_def(db, op_fn, substs))
+    }
+
     pub(crate) fn resolve_record_field(
         &self,
         db: &dyn HirDatabase,
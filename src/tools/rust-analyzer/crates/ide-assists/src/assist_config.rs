@@ -15,5 +15,42 @@ pub struct AssistConfig {
     pub insert_use: InsertUseConfig,
     pub prefer_no_std: bool,
     pub prefer_prelude: bool,
+    pub prefer_reexports: bool,
     pub assist_emit_must_use: bool,
+    pub extract_module_visibility: ExtractModuleVisibility,
+    pub match_arm_fields: MatchArmFields,
+    pub impl_members_copy_docs: bool,
+    pub module_file_layout: ModuleFileLayout,
+}
+
+/// The visibility the "Extract Module" assist gives to moved items that are used outside of the
+/// new module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtractModuleVisibility {
+    /// `pub(super)`, or `pub(crate)` for items used outside of the parent module as well.
+    PubSuper,
+    PubCrate,
+    Pub,
+    /// Re-export the items next to the new module, so that their usages don't change.
+    Reexport,
+}
+
+/// How the "Fill match arms" assist writes the fields of the variants in the arms it adds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchArmFields {
+    /// Bind the fields of record variants, and match the ones of tuple variants with `_`.
+    BindRecordFields,
+    /// Bind all fields, naming the ones of tuple variants after their types.
+    BindAll,
+    /// Ignore the fields with `..`, without expanding nested patterns.
+    Ignore,
+}
+
+/// The file the "Extract module to file" assist moves a module `foo` into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModuleFileLayout {
+    /// `foo.rs`, next to the directory of its child modules.
+    File,
+    /// `foo/mod.rs`, in the directory of its child modules.
+    ModRs,
 }
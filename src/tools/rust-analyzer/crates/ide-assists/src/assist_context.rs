@@ -1,8 +1,18 @@
 //! See [`AssistContext`].
 
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+
 use hir::Semantics;
 use ide_db::base_db::{FileId, FileRange};
-use ide_db::{label::Label, RootDatabase};
+use ide_db::{
+    defs::Definition,
+    label::Label,
+    search::{usages_of_all, SearchScope, UsageSearchResult},
+    FxHashMap, RootDatabase,
+};
 use syntax::{
     algo::{self, find_node_at_offset, find_node_at_range},
     AstNode, AstToken, Direction, SourceFile, SyntaxElement, SyntaxKind, SyntaxToken, TextRange,
@@ -51,13 +61,34 @@ pub(crate) struct AssistContext<'a> {
     frange: FileRange,
     trimmed_range: TextRange,
     source_file: SourceFile,
+    /// Usage searches done so far, keyed by the definition and the file they were limited to.
+    usages: RefCell<FxHashMap<(Definition, Option<FileId>), UsageSearchResult>>,
+    shared_usages: UsageCache,
 }
 
+/// The files containing usages of the definitions searched for everywhere, shared by the contexts
+/// created for one request. The found references hold syntax nodes, which can't be sent to the
+/// threads running the handlers of other contexts, so those only skip the files without usages.
+pub(crate) type UsageCache = Arc<Mutex<FxHashMap<Definition, Vec<FileId>>>>;
+
 impl<'a> AssistContext<'a> {
+    /// A context of its own, the contexts of a request are made by [`Self::with_usage_cache`].
+    #[cfg(test)]
     pub(crate) fn new(
         sema: Semantics<'a, RootDatabase>,
         config: &'a AssistConfig,
         frange: FileRange,
+    ) -> AssistContext<'a> {
+        Self::with_usage_cache(sema, config, frange, UsageCache::default())
+    }
+
+    /// Like [`Self::new`], but reuses the usage searches of the other contexts sharing
+    /// `shared_usages`.
+    pub(crate) fn with_usage_cache(
+        sema: Semantics<'a, RootDatabase>,
+        config: &'a AssistConfig,
+        frange: FileRange,
+        shared_usages: UsageCache,
     ) -> AssistContext<'a> {
         let source_file = sema.parse(frange.file_id);
 
@@ -78,7 +109,15 @@ pub(crate) fn new(
             _ => frange.range,
         };
 
-        AssistContext { config, sema, frange, source_file, trimmed_range }
+        AssistContext {
+            config,
+            sema,
+            frange,
+            source_file,
+            trimmed_range,
+            usages: RefCell::new(FxHashMap::default()),
+            shared_usages,
+        }
     }
 
     pub(crate) fn db(&self) -> &RootDatabase {
@@ -126,6 +165,91 @@ pub(crate) fn find_node_at_offset_with_descend<N: AstNode>(&self) -> Option<N> {
     pub(crate) fn covering_element(&self) -> SyntaxElement {
         self.source_file.syntax().covering_element(self.selection_trimmed())
     }
+
+    /// Finds all usages of `def`, reusing the result of an earlier search for it.
+    ///
+    /// Assists that look up the same definition more than once while computing their edit should
+    /// go through this instead of searching repeatedly.
+    pub(crate) fn usages(&self, def: Definition) -> UsageSearchResult {
+        if let Some(found) = self.usages.borrow().get(&(def, None)) {
+            return found.clone();
+        }
+        let files = self.shared_usages.lock().unwrap().get(&def).cloned();
+        let found = match files {
+            Some(files) if files.is_empty() => UsageSearchResult::default(),
+            Some(files) => def.usages(&self.sema).in_scope(&SearchScope::files(&files)).all(),
+            None => {
+                let found = def.usages(&self.sema).all();
+                self.share_usages(def, &found);
+                found
+            }
+        };
+        self.usages.borrow_mut().insert((def, None), found.clone());
+        found
+    }
+
+    /// Finds the usages of all `defs` not searched for yet in a single search, so that the
+    /// subsequent calls to [`Self::usages`] for them are answered right away.
+    pub(crate) fn prefetch_usages(&self, defs: &[Definition]) {
+        let (known, unknown): (Vec<_>, Vec<_>) = {
+            let usages = self.usages.borrow();
+            let shared_usages = self.shared_usages.lock().unwrap();
+            defs.iter()
+                .copied()
+                .filter(|&def| !usages.contains_key(&(def, None)))
+                .partition(|def| shared_usages.contains_key(def))
+        };
+        // The searches limited to the files known to have usages are cheap on their own.
+        for def in known {
+            self.usages(def);
+        }
+        if unknown.is_empty() {
+            return;
+        }
+        let found = usages_of_all(&self.sema, &unknown);
+        for (def, result) in &found {
+            self.share_usages(*def, result);
+        }
+        self.usages
+            .borrow_mut()
+            .extend(found.into_iter().map(|(def, result)| ((def, None), result)));
+    }
+
+    /// Like [`Self::usages`], but only finds usages in `file_id`.
+    pub(crate) fn usages_in_file(&self, def: Definition, file_id: FileId) -> UsageSearchResult {
+        let mut usages = self.usages.borrow_mut();
+        if let Some(all) = usages.get(&(def, None)) {
+            let mut res = UsageSearchResult::default();
+            if let Some(refs) = all.references.get(&file_id) {
+                res.references.insert(file_id, refs.clone());
+            }
+            return res;
+        }
+        let has_usages = self
+            .shared_usages
+            .lock()
+            .unwrap()
+            .get(&def)
+            .map_or(true, |files| files.contains(&file_id));
+        usages
+            .entry((def, Some(file_id)))
+            .or_insert_with(|| match has_usages {
+                true => def.usages(&self.sema).in_scope(&SearchScope::single_file(file_id)).all(),
+                false => UsageSearchResult::default(),
+            })
+            .clone()
+    }
+
+    /// Whether any usages were searched for through this context.
+    #[cfg(test)]
+    pub(crate) fn has_searched_usages(&self) -> bool {
+        !self.usages.borrow().is_empty()
+    }
+
+    fn share_usages(&self, def: Definition, found: &UsageSearchResult) {
+        let files = found.references.keys().copied().collect();
+        self.shared_usages.lock().unwrap().insert(def, files);
+    }
 }
 
 pub(crate) struct Assists {
@@ -158,7 +282,10 @@ pub(crate) fn add(
         f: impl FnOnce(&mut SourceChangeBuilder),
     ) -> Option<()> {
         let mut f = Some(f);
-        self.add_impl(None, id, label.into(), target, &mut |it| f.take().unwrap()(it))
+        self.add_impl(None, id, label.into(), None, target, &mut |it| {
+            f.take().unwrap()(it);
+            Some(())
+        })
     }
 
     pub(crate) fn add_group(
@@ -170,7 +297,34 @@ pub(crate) fn add_group(
         f: impl FnOnce(&mut SourceChangeBuilder),
     ) -> Option<()> {
         let mut f = Some(f);
-        self.add_impl(Some(group), id, label.into(), target, &mut |it| f.take().unwrap()(it))
+        self.add_impl(Some(group), id, label.into(), None, target, &mut |it| {
+            f.take().unwrap()(it);
+            Some(())
+        })
+    }
+
+    /// Adds an assist that can only be resolved with a parameter supplied by the client, e.g. a
+    /// destination the user picks. `prompt` describes the expected parameter to the user. The
+    /// assist is left out if it has to be resolved without one, or if `f` fails with the one given.
+    pub(crate) fn add_with_parameter(
+        &mut self,
+        id: AssistId,
+        label: impl Into<String>,
+        prompt: impl Into<String>,
+        target: TextRange,
+        f: impl FnOnce(&mut SourceChangeBuilder, &str) -> Option<()>,
+    ) -> Option<()> {
+        let parameter = match &self.resolve {
+            AssistResolveStrategy::Single(single) => single.parameter.clone(),
+            AssistResolveStrategy::None | AssistResolveStrategy::All => None,
+        };
+        if self.resolve.should_resolve(&id) && parameter.is_none() {
+            return None;
+        }
+        let mut f = Some(f);
+        self.add_impl(None, id, label.into(), Some(prompt.into()), target, &mut |it| {
+            f.take().unwrap()(it, parameter.as_deref().unwrap_or_default())
+        })
     }
 
     fn add_impl(
@@ -178,8 +332,9 @@ fn add_impl(
         group: Option<&GroupLabel>,
         id: AssistId,
         label: String,
+        parameter_prompt: Option<String>,
         target: TextRange,
-        f: &mut dyn FnMut(&mut SourceChangeBuilder),
+        f: &mut dyn FnMut(&mut SourceChangeBuilder) -> Option<()>,
     ) -> Option<()> {
         if !self.is_allowed(&id) {
             return None;
@@ -188,7 +343,7 @@ fn add_impl(
         let mut trigger_signature_help = false;
         let source_change = if self.resolve.should_resolve(&id) {
             let mut builder = SourceChangeBuilder::new(self.file);
-            f(&mut builder);
+            f(&mut builder)?;
             trigger_signature_help = builder.trigger_signature_help;
             Some(builder.finish())
         } else {
@@ -197,7 +352,15 @@ fn add_impl(
 
         let label = Label::new(label);
         let group = group.cloned();
-        self.buf.push(Assist { id, label, group, target, source_change, trigger_signature_help });
+        self.buf.push(Assist {
+            id,
+            label,
+            group,
+            target,
+            source_change,
+            trigger_signature_help,
+            parameter_prompt,
+        });
         Some(())
     }
 
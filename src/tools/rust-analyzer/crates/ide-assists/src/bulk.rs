@@ -0,0 +1,170 @@
+//! Applying an assist to all the places of a file it is applicable to at once, offered as a
+//! "fix all" source action.
+//!
+//! This only works for assists which are not applicable at the same place anymore once they have
+//! been applied, so that a place doesn't need to be visited twice.
+
+use hir::Semantics;
+use ide_db::{
+    base_db::{FileId, FileRange},
+    label::Label,
+    source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, AstNode, AstToken},
+    match_ast, TextRange,
+};
+use text_edit::TextEdit;
+
+use crate::{
+    handlers::{self, Handler},
+    Assist, AssistConfig, AssistContext, AssistId, AssistKind, AssistResolveStrategy, Assists,
+    UsageCache,
+};
+
+pub(crate) struct BulkAssist {
+    /// The id of the assist at a single place. The bulk assist has the same name, with
+    /// [`AssistKind::SourceFixAll`].
+    pub(crate) id: &'static str,
+    pub(crate) label: &'static str,
+    pub(crate) handler: Handler,
+    /// Lists the ranges to invoke the assist with, in the order of the file.
+    pub(crate) sites: fn(&ast::SourceFile) -> Vec<TextRange>,
+}
+
+/// Returns the assists which can be applied to every place in the file they are applicable to
+/// with one source change.
+///
+/// These are only computed if [`AssistKind::SourceFixAll`], or [`AssistKind::Source`] for the
+/// "Source Action..." menu, is explicitly asked for, running assists on a whole file is too
+/// costly to do for every lightbulb.
+pub fn assists_in_file(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    resolve: &AssistResolveStrategy,
+    file_id: FileId,
+) -> Vec<Assist> {
+    let requested =
+        |kind: &AssistKind| matches!(kind, AssistKind::Source | AssistKind::SourceFixAll);
+    if !config.allowed.as_ref().is_some_and(|it| it.iter().any(requested)) {
+        return Vec::new();
+    }
+    let file = Semantics::new(db).parse(file_id);
+    // The edits of the single places are merged, so they can't have snippets.
+    let site_config = AssistConfig { allowed: None, snippet_cap: None, ..config.clone() };
+
+    handlers::bulk()
+        .iter()
+        .filter_map(|bulk| {
+            let id = AssistId(bulk.id, AssistKind::SourceFixAll);
+            let should_resolve = resolve.should_resolve(&id);
+            let site_resolve = match should_resolve {
+                true => AssistResolveStrategy::All,
+                false => AssistResolveStrategy::None,
+            };
+
+            let mut applicable = false;
+            let mut edit = TextEdit::default();
+            let usages = UsageCache::default();
+            for range in (bulk.sites)(&file) {
+                let frange = FileRange { file_id, range };
+                let ctx = AssistContext::with_usage_cache(
+                    Semantics::new(db),
+                    &site_config,
+                    frange,
+                    usages.clone(),
+                );
+                let mut acc = Assists::new(&ctx, site_resolve.clone());
+                (bulk.handler)(&mut acc, &ctx);
+                let Some(assist) = acc.finish().into_iter().find(|it| it.id.0 == bulk.id) else {
+                    continue;
+                };
+                applicable = true;
+                if !should_resolve {
+                    break;
+                }
+                let Some(site_edit) =
+                    assist.source_change.and_then(|it| site_edit(it, file_id, &edit))
+                else {
+                    continue;
+                };
+                // Places whose edits overlap the ones of an earlier place are skipped, they are
+                // left for another run of the assist.
+                if edit.union(site_edit).is_err() {
+                    cov_mark::hit!(bulk_assist_skips_overlapping_place);
+                }
+            }
+
+            applicable.then(|| Assist {
+                id,
+                label: Label::new(bulk.label.to_owned()),
+                group: None,
+                target: file.syntax().text_range(),
+                source_change: should_resolve.then(|| SourceChange::from_text_edit(file_id, edit)),
+                trigger_signature_help: false,
+                parameter_prompt: None,
+            })
+        })
+        .collect()
+}
+
+/// The edit `change` makes to `file_id`, without the parts `merged` already contains, e.g. the
+/// same import added for several places. Changes which touch other files aren't merged.
+fn site_edit(change: SourceChange, file_id: FileId, merged: &TextEdit) -> Option<TextEdit> {
+    if !change.file_system_edits.is_empty() || change.source_file_edits.len() != 1 {
+        return None;
+    }
+    let (edit, _) = change.source_file_edits.get(&file_id)?;
+    let mut builder = TextEdit::builder();
+    for indel in edit.iter().filter(|&indel| !merged.iter().any(|it| it == indel)) {
+        builder.replace(indel.delete, indel.insert.clone());
+    }
+    Some(builder.finish())
+}
+
+/// The `if` expressions and `let` statements of the file.
+pub(crate) fn guarded_return_sites(file: &ast::SourceFile) -> Vec<TextRange> {
+    file.syntax()
+        .descendants()
+        .filter_map(|node| {
+            let keyword = match_ast! {
+                match node {
+                    ast::LetStmt(it) => it.let_token(),
+                    ast::IfExpr(it) => it.if_token(),
+                    _ => None,
+                }
+            };
+            Some(keyword?.text_range())
+        })
+        .collect()
+}
+
+/// The integer literals without digit separators, the assist would remove them otherwise.
+pub(crate) fn number_literal_sites(file: &ast::SourceFile) -> Vec<TextRange> {
+    file.syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| ast::IntNumber::cast(it.into_token()?))
+        .filter(|it| !it.text().contains('_'))
+        .map(|it| it.syntax().text_range())
+        .collect()
+}
+
+/// The whole file, `remove_dbg` removes all the `dbg!` calls in the selection.
+pub(crate) fn dbg_sites(file: &ast::SourceFile) -> Vec<TextRange> {
+    vec![file.syntax().text_range()]
+}
+
+/// The last segments of the qualified paths outside of `use` items.
+pub(crate) fn qualified_path_sites(file: &ast::SourceFile) -> Vec<TextRange> {
+    file.syntax()
+        .descendants()
+        .filter_map(ast::Path::cast)
+        .filter(|path| {
+            path.qualifier().is_some()
+                && !path.syntax().parent().is_some_and(|it| ast::Path::can_cast(it.kind()))
+                && !path.syntax().ancestors().any(|it| ast::Use::can_cast(it.kind()))
+        })
+        .filter_map(|path| Some(path.segment()?.syntax().text_range()))
+        .collect()
+}
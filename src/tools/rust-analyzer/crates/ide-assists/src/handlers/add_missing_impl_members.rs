@@ -147,6 +147,7 @@ fn add_missing_impl_members_inner(
         let new_impl_def = edit.make_mut(impl_def.clone());
         let first_new_item = add_trait_assoc_items_to_impl(
             &ctx.sema,
+            ctx.config,
             &missing_items,
             trait_,
             &new_impl_def,
@@ -192,7 +193,10 @@ fn try_gen_trait_body(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable};
+    use crate::tests::{
+        check_assist, check_assist_by_label_with_config, check_assist_not_applicable, TEST_CONFIG,
+    };
+    use crate::AssistConfig;
 
     use super::*;
 
@@ -326,6 +330,66 @@ fn foo(&self) {
         );
     }
 
+    #[test]
+    fn where_clause_with_defaulted_type_param() {
+        check_assist(
+            add_missing_impl_members,
+            r#"
+trait Foo<T = u32> {
+    fn foo<U>(&self, u: U) -> T
+    where
+        U: Into<T>;
+}
+struct S;
+impl Foo for S {$0}"#,
+            r#"
+trait Foo<T = u32> {
+    fn foo<U>(&self, u: U) -> T
+    where
+        U: Into<T>;
+}
+struct S;
+impl Foo for S {
+    fn foo<U>(&self, u: U) -> u32
+    where
+        U: Into<u32>,
+    {
+        ${0:todo!()}
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn copy_docs() {
+        check_assist_by_label_with_config(
+            add_missing_impl_members,
+            AssistConfig { impl_members_copy_docs: true, ..TEST_CONFIG },
+            r#"
+trait Foo {
+    /// Does the thing.
+    #[must_use]
+    fn foo(&self) -> u32;
+}
+struct S;
+impl Foo for S {$0}"#,
+            r#"
+trait Foo {
+    /// Does the thing.
+    #[must_use]
+    fn foo(&self) -> u32;
+}
+struct S;
+impl Foo for S {
+    /// Does the thing.
+    fn foo(&self) -> u32 {
+        ${0:todo!()}
+    }
+}"#,
+            "Implement missing members",
+        );
+    }
+
     #[test]
     fn fill_in_type_params_1() {
         check_assist(
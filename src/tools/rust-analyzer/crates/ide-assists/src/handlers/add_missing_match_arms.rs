@@ -8,7 +8,17 @@
 use syntax::ast::edit_in_place::Removable;
 use syntax::ast::{self, make, AstNode, HasName, MatchArmList, MatchExpr, Pat};
 
-use crate::{utils, AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    utils::{self, suggest_name},
+    AssistContext, AssistId, AssistKind, Assists, MatchArmFields,
+};
+
+/// The number of arms the assist generates at most.
+const MAX_ARMS: usize = 256;
+
+/// How many levels of newtype variants like `Some(_)` get an arm for each variant of their field,
+/// e.g. `Some(Ok(E::A))`.
+const NESTING_DEPTH: usize = 2;
 
 // Assist: add_missing_match_arms
 //
@@ -76,49 +86,41 @@ pub(crate) fn add_missing_match_arms(acc: &mut Assists, ctx: &AssistContext<'_>)
         Peekable<Box<dyn Iterator<Item = (ast::Pat, bool)>>>,
         bool,
         bool,
-    ) = if let Some(enum_def) = resolve_enum_def(&ctx.sema, &expr) {
+    ) = if let Some((enum_def, ty)) = resolve_enum_def(&ctx.sema, &expr) {
         let is_non_exhaustive = enum_def.is_non_exhaustive(ctx.db(), module.krate());
 
-        let variants = enum_def.variants(ctx.db());
+        let mut pats = enum_pats(ctx, module, enum_def, &ty, NESTING_DEPTH);
+        if pats.len() > MAX_ARMS {
+            pats = enum_pats(ctx, module, enum_def, &ty, 0);
+        }
 
-        let has_hidden_variants =
-            variants.iter().any(|variant| variant.should_be_hidden(ctx.db(), module.krate()));
+        let has_hidden_variants = pats.iter().any(|(_, is_hidden)| *is_hidden);
 
-        let missing_pats = variants
+        let missing_pats = pats
             .into_iter()
-            .filter_map(|variant| {
-                Some((
-                    build_pat(
-                        ctx.db(),
-                        module,
-                        variant,
-                        ctx.config.prefer_no_std,
-                        ctx.config.prefer_prelude,
-                    )?,
-                    variant.should_be_hidden(ctx.db(), module.krate()),
-                ))
-            })
-            .filter(|(variant_pat, _)| is_variant_missing(&top_lvl_pats, variant_pat));
-
-        let option_enum = FamousDefs(&ctx.sema, module.krate()).core_option_Option().map(lift_enum);
-        let missing_pats: Box<dyn Iterator<Item = _>> = if Some(enum_def) == option_enum {
-            // Match `Some` variant first.
-            cov_mark::hit!(option_order);
-            Box::new(missing_pats.rev())
-        } else {
-            Box::new(missing_pats)
-        };
-        (missing_pats.peekable(), is_non_exhaustive, has_hidden_variants)
+            .filter(|(variant_pat, _)| is_variant_missing(ctx, &top_lvl_pats, variant_pat));
+        (
+            (Box::new(missing_pats) as Box<dyn Iterator<Item = _>>).peekable(),
+            is_non_exhaustive,
+            has_hidden_variants,
+        )
     } else if let Some(enum_defs) = resolve_tuple_of_enum_def(&ctx.sema, &expr) {
-        let is_non_exhaustive =
-            enum_defs.iter().any(|enum_def| enum_def.is_non_exhaustive(ctx.db(), module.krate()));
-
-        let mut n_arms = 1;
-        let variants_of_enums: Vec<Vec<ExtendedVariant>> = enum_defs
-            .into_iter()
-            .map(|enum_def| enum_def.variants(ctx.db()))
-            .inspect(|variants| n_arms *= variants.len())
-            .collect();
+        let is_non_exhaustive = enum_defs
+            .iter()
+            .flatten()
+            .any(|(enum_def, _)| enum_def.is_non_exhaustive(ctx.db(), module.krate()));
+
+        // Elements which aren't enums are matched with `_`.
+        let element_pats = |depth| -> Vec<Vec<(ast::Pat, bool)>> {
+            enum_defs
+                .iter()
+                .map(|enum_def| match enum_def {
+                    Some((enum_def, ty)) => enum_pats(ctx, module, *enum_def, ty, depth),
+                    None => vec![(make::wildcard_pat().into(), false)],
+                })
+                .collect()
+        };
+        let n_arms = |pats: &[Vec<_>]| pats.iter().map(Vec::len).product::<usize>();
 
         // When calculating the match arms for a tuple of enums, we want
         // to create a match arm for each possible combination of enum
@@ -127,37 +129,30 @@ pub(crate) fn add_missing_match_arms(acc: &mut Assists, ctx: &AssistContext<'_>)
         // where each tuple represents a proposed match arm.
 
         // A number of arms grows very fast on even a small tuple of large enums.
-        // We skip the assist beyond an arbitrary threshold.
-        if n_arms > 256 {
+        // We don't expand nested patterns beyond an arbitrary threshold, and skip the assist if
+        // there are too many arms even then.
+        let mut pats_of_elements = element_pats(NESTING_DEPTH);
+        if n_arms(&pats_of_elements) > MAX_ARMS {
+            pats_of_elements = element_pats(0);
+        }
+        if n_arms(&pats_of_elements) > MAX_ARMS {
             return None;
         }
 
-        let has_hidden_variants = variants_of_enums
-            .iter()
-            .flatten()
-            .any(|variant| variant.should_be_hidden(ctx.db(), module.krate()));
+        let has_hidden_variants =
+            pats_of_elements.iter().flatten().any(|(_, is_hidden)| *is_hidden);
 
-        let missing_pats = variants_of_enums
+        let missing_pats = pats_of_elements
             .into_iter()
             .multi_cartesian_product()
             .inspect(|_| cov_mark::hit!(add_missing_match_arms_lazy_computation))
-            .map(|variants| {
-                let is_hidden = variants
-                    .iter()
-                    .any(|variant| variant.should_be_hidden(ctx.db(), module.krate()));
-                let patterns = variants.into_iter().filter_map(|variant| {
-                    build_pat(
-                        ctx.db(),
-                        module,
-                        variant,
-                        ctx.config.prefer_no_std,
-                        ctx.config.prefer_prelude,
-                    )
-                });
+            .map(|pats| {
+                let is_hidden = pats.iter().any(|(_, is_hidden)| *is_hidden);
+                let patterns = pats.into_iter().map(|(pat, _)| pat);
 
                 (ast::Pat::from(make::tuple_pat(patterns)), is_hidden)
             })
-            .filter(|(variant_pat, _)| is_variant_missing(&top_lvl_pats, variant_pat));
+            .filter(|(variant_pat, _)| is_variant_missing(ctx, &top_lvl_pats, variant_pat));
         (
             (Box::new(missing_pats) as Box<dyn Iterator<Item = _>>).peekable(),
             is_non_exhaustive,
@@ -167,7 +162,7 @@ pub(crate) fn add_missing_match_arms(acc: &mut Assists, ctx: &AssistContext<'_>)
         let is_non_exhaustive = enum_def.is_non_exhaustive(ctx.db(), module.krate());
         let variants = enum_def.variants(ctx.db());
 
-        if len.pow(variants.len() as u32) > 256 {
+        if len.pow(variants.len() as u32) > MAX_ARMS {
             return None;
         }
 
@@ -191,11 +186,12 @@ pub(crate) fn add_missing_match_arms(acc: &mut Assists, ctx: &AssistContext<'_>)
                         variant,
                         ctx.config.prefer_no_std,
                         ctx.config.prefer_prelude,
+                        ctx.config.match_arm_fields,
                     )
                 });
                 (ast::Pat::from(make::slice_pat(patterns)), is_hidden)
             })
-            .filter(|(variant_pat, _)| is_variant_missing(&top_lvl_pats, variant_pat));
+            .filter(|(variant_pat, _)| is_variant_missing(ctx, &top_lvl_pats, variant_pat));
         (
             (Box::new(missing_pats) as Box<dyn Iterator<Item = _>>).peekable(),
             is_non_exhaustive,
@@ -342,21 +338,46 @@ fn cursor_at_trivial_match_arm_list(
     None
 }
 
-fn is_variant_missing(existing_pats: &[Pat], var: &Pat) -> bool {
-    !existing_pats.iter().any(|pat| does_pat_match_variant(pat, var))
+fn is_variant_missing(ctx: &AssistContext<'_>, existing_pats: &[Pat], var: &Pat) -> bool {
+    !existing_pats.iter().any(|pat| does_pat_match_variant(ctx, pat, var))
 }
 
 // Fixme: this is still somewhat limited, use hir_ty::diagnostics::match_check?
-fn does_pat_match_variant(pat: &Pat, var: &Pat) -> bool {
+fn does_pat_match_variant(ctx: &AssistContext<'_>, pat: &Pat, var: &Pat) -> bool {
     match (pat, var) {
         (Pat::WildcardPat(_), _) => true,
         (Pat::SlicePat(spat), Pat::SlicePat(svar)) => {
-            spat.pats().zip(svar.pats()).all(|(p, v)| does_pat_match_variant(&p, &v))
+            spat.pats().zip(svar.pats()).all(|(p, v)| does_pat_match_variant(ctx, &p, &v))
         }
         (Pat::TuplePat(tpat), Pat::TuplePat(tvar)) => {
-            tpat.fields().zip(tvar.fields()).all(|(p, v)| does_pat_match_variant(&p, &v))
+            tpat.fields().zip(tvar.fields()).all(|(p, v)| does_pat_match_variant(ctx, &p, &v))
+        }
+        (Pat::TupleStructPat(tpat), Pat::TupleStructPat(tvar)) => {
+            let is_binding = |pat: &Pat| match pat {
+                Pat::IdentPat(it) => {
+                    it.pat().is_none() && ctx.sema.resolve_bind_pat_to_const(it).is_none()
+                }
+                Pat::RestPat(_) => true,
+                _ => false,
+            };
+            // Only the fields of nested arms are compared, the ones generated with `_`, `..` or a
+            // binding are matched by any pattern. Generated bindings are snake case, unlike unit
+            // variants.
+            let is_generated_catch_all = |pat: &Pat| match pat {
+                Pat::WildcardPat(_) | Pat::RestPat(_) => true,
+                Pat::IdentPat(it) => it
+                    .name()
+                    .is_some_and(|name| name.text().starts_with(|c: char| c.is_lowercase())),
+                _ => false,
+            };
+            utils::does_pat_match_variant(pat, var)
+                && tpat.fields().zip(tvar.fields()).all(|(p, v)| {
+                    is_generated_catch_all(&v)
+                        || is_binding(&p)
+                        || does_pat_match_variant(ctx, &p, &v)
+                })
         }
-        (Pat::OrPat(opat), _) => opat.pats().any(|p| does_pat_match_variant(&p, var)),
+        (Pat::OrPat(opat), _) => opat.pats().any(|p| does_pat_match_variant(ctx, &p, var)),
         _ => utils::does_pat_match_variant(pat, var),
     }
 }
@@ -411,34 +432,33 @@ fn variants(self, db: &RootDatabase) -> Vec<ExtendedVariant> {
     }
 }
 
-fn resolve_enum_def(sema: &Semantics<'_, RootDatabase>, expr: &ast::Expr) -> Option<ExtendedEnum> {
-    sema.type_of_expr(expr)?.adjusted().autoderef(sema.db).find_map(|ty| match ty.as_adt() {
-        Some(Adt::Enum(e)) => Some(ExtendedEnum::Enum(e)),
-        _ => ty.is_bool().then_some(ExtendedEnum::Bool),
-    })
+fn resolve_enum_def(
+    sema: &Semantics<'_, RootDatabase>,
+    expr: &ast::Expr,
+) -> Option<(ExtendedEnum, hir::Type)> {
+    enum_of_type(sema.db, &sema.type_of_expr(expr)?.adjusted())
 }
 
+/// The enums of the elements of a tuple, if at least one of them is an enum.
 fn resolve_tuple_of_enum_def(
     sema: &Semantics<'_, RootDatabase>,
     expr: &ast::Expr,
-) -> Option<Vec<ExtendedEnum>> {
-    sema.type_of_expr(expr)?
+) -> Option<Vec<Option<(ExtendedEnum, hir::Type)>>> {
+    let enum_defs: Vec<_> = sema
+        .type_of_expr(expr)?
         .adjusted()
         .tuple_fields(sema.db)
         .iter()
-        .map(|ty| {
-            ty.autoderef(sema.db).find_map(|ty| {
-                match ty.as_adt() {
-                    Some(Adt::Enum(e)) => Some(lift_enum(e)),
-                    // For now we only handle expansion for a tuple of enums. Here
-                    // we map non-enum items to None and rely on `collect` to
-                    // convert Vec<Option<hir::Enum>> into Option<Vec<hir::Enum>>.
-                    _ => ty.is_bool().then_some(ExtendedEnum::Bool),
-                }
-            })
-        })
-        .collect::<Option<Vec<ExtendedEnum>>>()
-        .and_then(|list| if list.is_empty() { None } else { Some(list) })
+        .map(|ty| enum_of_type(sema.db, ty))
+        .collect();
+    enum_defs.iter().any(Option::is_some).then_some(enum_defs)
+}
+
+fn enum_of_type(db: &RootDatabase, ty: &hir::Type) -> Option<(ExtendedEnum, hir::Type)> {
+    ty.autoderef(db).find_map(|ty| match ty.as_adt() {
+        Some(Adt::Enum(e)) => Some((lift_enum(e), ty)),
+        _ => ty.is_bool().then_some((ExtendedEnum::Bool, ty)),
+    })
 }
 
 fn resolve_array_of_enum_def(
@@ -453,12 +473,90 @@ fn resolve_array_of_enum_def(
     })
 }
 
+/// The patterns for the variants of `enum_def`, the enum type `ty`. Newtype variants whose field is
+/// an enum get a pattern for each variant of the field, up to `depth` levels deep.
+fn enum_pats(
+    ctx: &AssistContext<'_>,
+    module: hir::Module,
+    enum_def: ExtendedEnum,
+    ty: &hir::Type,
+    depth: usize,
+) -> Vec<(ast::Pat, bool)> {
+    let db = ctx.db();
+    let depth = match ctx.config.match_arm_fields {
+        MatchArmFields::Ignore => 0,
+        _ => depth,
+    };
+    let mut variants = enum_def.variants(db);
+    let option_enum = FamousDefs(&ctx.sema, module.krate()).core_option_Option().map(lift_enum);
+    if Some(enum_def) == option_enum {
+        // Match `Some` variant first.
+        cov_mark::hit!(option_order);
+        variants.reverse();
+    }
+
+    variants
+        .into_iter()
+        .filter_map(|variant| {
+            let pat = build_pat(
+                db,
+                module,
+                variant,
+                ctx.config.prefer_no_std,
+                ctx.config.prefer_prelude,
+                ctx.config.match_arm_fields,
+            )?;
+            Some((variant, pat))
+        })
+        .flat_map(|(variant, pat)| {
+            let is_hidden = variant.should_be_hidden(db, module.krate());
+            let nested = match (variant, &pat) {
+                (ExtendedVariant::Variant(variant), ast::Pat::TupleStructPat(pat)) if depth > 0 => {
+                    newtype_field_pats(ctx, module, variant, ty, depth - 1).zip(pat.path())
+                }
+                _ => None,
+            };
+            match nested {
+                Some((field_pats, path)) => field_pats
+                    .into_iter()
+                    .map(|(field_pat, is_field_hidden)| {
+                        let pat = make::tuple_struct_pat(path.clone(), iter::once(field_pat));
+                        (pat.into(), is_hidden || is_field_hidden)
+                    })
+                    .collect(),
+                None => vec![(pat, is_hidden)],
+            }
+        })
+        .collect()
+}
+
+/// The patterns for the variants of the field of `variant` of the enum type `ty`, if it's a
+/// newtype variant like `Ok(_)` whose field is an exhaustive enum.
+fn newtype_field_pats(
+    ctx: &AssistContext<'_>,
+    module: hir::Module,
+    variant: hir::Variant,
+    ty: &hir::Type,
+    depth: usize,
+) -> Option<Vec<(ast::Pat, bool)>> {
+    let db = ctx.db();
+    let [field] = &variant.fields(db)[..] else {
+        return None;
+    };
+    let (enum_def, field_ty) = enum_of_type(db, &field.ty_with_args(db, ty.type_arguments()))?;
+    if enum_def.is_non_exhaustive(db, module.krate()) {
+        return None;
+    }
+    Some(enum_pats(ctx, module, enum_def, &field_ty, depth))
+}
+
 fn build_pat(
     db: &RootDatabase,
     module: hir::Module,
     var: ExtendedVariant,
     prefer_no_std: bool,
     prefer_prelude: bool,
+    fields: MatchArmFields,
 ) -> Option<ast::Pat> {
     match var {
         ExtendedVariant::Variant(var) => {
@@ -471,6 +569,20 @@ fn build_pat(
 
             // FIXME: use HIR for this; it doesn't currently expose struct vs. tuple vs. unit variants though
             Some(match var.source(db)?.value.kind() {
+                ast::StructKind::Tuple(_) if fields == MatchArmFields::Ignore => {
+                    make::tuple_struct_pat(path, iter::once(make::rest_pat().into())).into()
+                }
+                ast::StructKind::Record(_) if fields == MatchArmFields::Ignore => {
+                    let field_list =
+                        make::record_pat_field_list(iter::empty(), Some(make::rest_pat()));
+                    make::record_pat_with_fields(path, field_list).into()
+                }
+                ast::StructKind::Tuple(_) if fields == MatchArmFields::BindAll => {
+                    let pats = tuple_field_names(db, var)
+                        .into_iter()
+                        .map(|name| make::ext::simple_ident_pat(make::name(&name)).into());
+                    make::tuple_struct_pat(path, pats).into()
+                }
                 ast::StructKind::Tuple(field_list) => {
                     let pats =
                         iter::repeat(make::wildcard_pat().into()).take(field_list.fields().count());
@@ -493,10 +605,25 @@ fn build_pat(
     }
 }
 
+/// Names for the fields of the tuple variant `var`, after their types where possible.
+fn tuple_field_names(db: &RootDatabase, var: hir::Variant) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (idx, field) in var.fields(db).into_iter().enumerate() {
+        let name = suggest_name::for_type(&field.ty(db), db).unwrap_or_else(|| "field".to_owned());
+        let name = if names.contains(&name) { format!("{name}{idx}") } else { name };
+        names.push(name);
+    }
+    names
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::tests::{
-        check_assist, check_assist_not_applicable, check_assist_target, check_assist_unresolved,
+    use crate::{
+        tests::{
+            check_assist, check_assist_by_label_with_config, check_assist_not_applicable,
+            check_assist_target, check_assist_unresolved, TEST_CONFIG,
+        },
+        AssistConfig, MatchArmFields,
     };
 
     use super::add_missing_match_arms;
@@ -572,15 +699,34 @@ fn foo(a: bool) {
 
     #[test]
     fn tuple_of_non_enum() {
-        // for now this case is not handled, although it potentially could be
-        // in the future
         check_assist_not_applicable(
             add_missing_match_arms,
             r#"
+fn main() {
+    match (0, "")$0 {
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn tuple_with_non_enum() {
+        check_assist(
+            add_missing_match_arms,
+            r#"
 fn main() {
     match (0, false)$0 {
     }
 }
+"#,
+            r#"
+fn main() {
+    match (0, false) {
+        (${0:_}, true) => todo!(),
+        (_, false) => todo!(),
+    }
+}
 "#,
         );
     }
@@ -911,6 +1057,7 @@ fn main() {
         A::Es(B::Xs) => (),
         $0A::As => todo!(),
         A::Cs => todo!(),
+        A::Es(B::Ys) => todo!(),
     }
 }
 "#,
@@ -1164,8 +1311,7 @@ fn main() {
 
     #[test]
     fn add_missing_match_arms_partial_with_deep_pattern() {
-        // Fixme: cannot handle deep patterns
-        check_assist_not_applicable(
+        check_assist(
             add_missing_match_arms,
             r#"
 //- minicore: option
@@ -1175,6 +1321,71 @@ fn main() {
         None => {}
     }
 }
+"#,
+            r#"
+fn main() {
+    match Some(true) {
+        Some(true) => {}
+        None => {}
+        $0Some(false) => todo!(),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_missing_match_arms_nested_enums() {
+        check_assist(
+            add_missing_match_arms,
+            r#"
+//- minicore: result
+enum A { X, Y }
+enum B { Z }
+fn foo(r: Result<A, B>) {
+    match r$0 {
+        Ok(A::X) => {}
+    }
+}
+"#,
+            r#"
+enum A { X, Y }
+enum B { Z }
+fn foo(r: Result<A, B>) {
+    match r {
+        Ok(A::X) => {}
+        $0Ok(A::Y) => todo!(),
+        Err(B::Z) => todo!(),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_missing_match_arms_tuple_with_nested_enum() {
+        check_assist(
+            add_missing_match_arms,
+            r#"
+//- minicore: option
+enum A { X, Y }
+fn foo(t: (Option<A>, bool)) {
+    match t$0 {
+        (Some(a), true) => {}
+    }
+}
+"#,
+            r#"
+enum A { X, Y }
+fn foo(t: (Option<A>, bool)) {
+    match t {
+        (Some(a), true) => {}
+        $0(Some(A::X), false) => todo!(),
+        (Some(A::Y), false) => todo!(),
+        (None, true) => todo!(),
+        (None, false) => todo!(),
+    }
+}
 "#,
         );
     }
@@ -1998,4 +2209,60 @@ fn a() {
 }"#,
         )
     }
+
+    #[test]
+    fn bind_all_fields() {
+        check_assist_by_label_with_config(
+            add_missing_match_arms,
+            AssistConfig { match_arm_fields: MatchArmFields::BindAll, ..TEST_CONFIG },
+            r#"
+struct Seed;
+enum A { Bs { x: i32 }, Cs(Seed, i32, i32), Ds }
+fn foo(a: A) {
+    match a$0 {}
+}
+"#,
+            r#"
+struct Seed;
+enum A { Bs { x: i32 }, Cs(Seed, i32, i32), Ds }
+fn foo(a: A) {
+    match a {
+        $0A::Bs { x } => todo!(),
+        A::Cs(seed, field, field2) => todo!(),
+        A::Ds => todo!(),
+    }
+}
+"#,
+            "Fill match arms",
+        );
+    }
+
+    #[test]
+    fn ignore_fields() {
+        check_assist_by_label_with_config(
+            add_missing_match_arms,
+            AssistConfig { match_arm_fields: MatchArmFields::Ignore, ..TEST_CONFIG },
+            r#"
+enum B { X, Y }
+enum A { Bs { x: i32 }, Cs(B), Ds }
+fn foo(a: A) {
+    match a$0 {
+        A::Cs(B::X) => {}
+    }
+}
+"#,
+            r#"
+enum B { X, Y }
+enum A { Bs { x: i32 }, Cs(B), Ds }
+fn foo(a: A) {
+    match a {
+        A::Cs(B::X) => {}
+        $0A::Bs { .. } => todo!(),
+        A::Ds => todo!(),
+    }
+}
+"#,
+            "Fill match arms",
+        );
+    }
 }
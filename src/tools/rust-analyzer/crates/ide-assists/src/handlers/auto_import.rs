@@ -38,6 +38,18 @@
 // use super::AssistContext;
 // ```
 //
+// .Import Groups
+//
+// Which imports share a group is configurable through the `imports.group.style` setting.
+// It has the following configurations:
+//
+// - `default`: The groups listed above.
+// - `std_external_crate`: `std`, `core` and `alloc` imports, imports of other crates, and imports
+//  of the current crate (prefixed by `crate`, `self` or `super`), matching rustfmt's
+//  `group_imports = "StdExternalCrate"`.
+//
+// In `VS Code` the configuration for this is `rust-analyzer.imports.group.style`.
+//
 // .Import Granularity
 //
 // It is possible to configure how use-trees are merged with the `imports.granularity.group` setting.
@@ -244,6 +256,14 @@ fn relevance_score(
         // (prefer items that are more local)
         Some((item_module, current_module)) => {
             score -= module_distance_heuristic(db, current_module, &item_module) as i32;
+
+            // items of other crates re-exported closer to their crate root are usually the
+            // intended way to use them (`tokio::spawn` rather than `tokio::task::spawn`)
+            if ctx.config.prefer_reexports && item_module.krate() != current_module.krate() {
+                let defining_depth = item_module.path_to_root(db).len();
+                let import_depth = import.import_path.len().saturating_sub(1);
+                score += defining_depth.saturating_sub(import_depth) as i32;
+            }
         }
 
         // could not find relevant modules, so just use the length of the path as an estimate
@@ -293,13 +313,17 @@ mod tests {
         check_assist, check_assist_by_label, check_assist_not_applicable, check_assist_target,
         TEST_CONFIG,
     };
+    use crate::AssistConfig;
 
     fn check_auto_import_order(before: &str, order: &[&str]) {
+        check_auto_import_order_with_config(TEST_CONFIG, before, order)
+    }
+
+    fn check_auto_import_order_with_config(config: AssistConfig, before: &str, order: &[&str]) {
         let (db, file_id, range_or_offset) = RootDatabase::with_range_or_offset(before);
         let frange = FileRange { file_id, range: range_or_offset.into() };
 
         let sema = Semantics::new(&db);
-        let config = TEST_CONFIG;
         let ctx = AssistContext::new(sema, &config, frange);
         let mut acc = Assists::new(&ctx, AssistResolveStrategy::All);
         auto_import(&mut acc, &ctx);
@@ -365,6 +389,28 @@ pub mod hash_map {
         )
     }
 
+    #[test]
+    fn prefer_shorter_reexports() {
+        let before = r"
+//- /main.rs crate:main deps:dep,other
+spawn$0();
+
+//- /lib.rs crate:dep
+pub mod task { pub mod inner { pub fn spawn() {} } }
+pub use task::inner::spawn;
+
+//- /lib.rs crate:other
+pub mod a { pub fn spawn() {} }
+        ";
+
+        check_auto_import_order(before, &["Import `dep::spawn`", "Import `other::a::spawn`"]);
+        check_auto_import_order_with_config(
+            AssistConfig { prefer_reexports: false, ..TEST_CONFIG },
+            before,
+            &["Import `other::a::spawn`", "Import `dep::spawn`"],
+        );
+    }
+
     #[test]
     fn not_applicable_if_scope_inside_macro() {
         check_assist_not_applicable(
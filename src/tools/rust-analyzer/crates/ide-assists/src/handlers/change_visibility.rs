@@ -1,14 +1,21 @@
+use hir::{db::HirDatabase, HasVisibility, Module, ModuleDef, PathResolution, Visibility};
+use ide_db::{base_db::salsa::Database, defs::Definition, search::FileReference, RootDatabase};
+use itertools::Itertools;
 use syntax::{
-    ast::{self, HasName, HasVisibility},
-    AstNode,
+    ast::{self, make, HasName, HasVisibility as _, VisibilityKind},
+    syntax_editor::{Position, SyntaxEditor},
+    AstNode, NodeOrToken,
     SyntaxKind::{
-        self, ASSOC_ITEM_LIST, CONST, ENUM, FN, MACRO_DEF, MODULE, SOURCE_FILE, STATIC, STRUCT,
-        TRAIT, TYPE_ALIAS, USE, VISIBILITY,
+        self, ASSOC_ITEM_LIST, CONST, ENUM, FN, ITEM_LIST, MACRO_DEF, MODULE, SOURCE_FILE, STATIC,
+        STRUCT, TRAIT, TYPE_ALIAS, USE, VISIBILITY, WHITESPACE,
     },
-    SyntaxNode, T,
+    SyntaxNode, TextRange, T,
 };
 
-use crate::{utils::vis_offset, AssistContext, AssistId, AssistKind, Assists};
+use crate::{
+    utils::{vis_anchor, vis_owner_def},
+    AssistContext, AssistId, AssistKind, Assists,
+};
 
 // Assist: change_visibility
 //
@@ -28,7 +35,204 @@ pub(crate) fn change_visibility(acc: &mut Assists, ctx: &AssistContext<'_>) -> O
     add_vis(acc, ctx)
 }
 
+// Assist: change_visibility_to_minimal
+//
+// Changes the visibility of an item or field to the narrowest one its usages in the crate need.
+//
+// ```
+// mod foo {
+//     pub mod bar {
+//         $0pub fn frobnicate() {}
+//     }
+//     fn baz() { bar::frobnicate() }
+// }
+// ```
+// ->
+// ```
+// mod foo {
+//     pub mod bar {
+//         pub(super) fn frobnicate() {}
+//     }
+//     fn baz() { bar::frobnicate() }
+// }
+// ```
+pub(crate) fn change_visibility_to_minimal(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let (owner, target) = match ctx.find_node_at_offset::<ast::Visibility>() {
+        Some(vis) => (vis.syntax().parent()?, vis.syntax().text_range()),
+        None => vis_owner(ctx)?,
+    };
+    // Unlike `change_visibility`, this one is offered for the items of inline modules as well.
+    let in_inline_module = owner.parent().is_some_and(|it| it.kind() == ITEM_LIST);
+    if !can_add(&owner) && !in_inline_module && !is_adt_field(&owner) {
+        return None;
+    }
+    // Usages the current visibility doesn't allow aren't found, so it can only be narrowed.
+    let current = owner.children().find_map(ast::Visibility::cast)?;
+    let db = ctx.db();
+    let def = vis_owner_def(&ctx.sema, &owner)?;
+    let module = def.module(db)?;
+    // Items of modules inside of function bodies aren't handled.
+    if module.path_to_root(db).last() != Some(&module.crate_root(db)) {
+        return None;
+    }
+    // Other crates may use the public API of a library.
+    if is_exported(db, def, module) && !module.krate().is_executable(db) {
+        cov_mark::hit!(minimal_visibility_exported_from_library);
+        return None;
+    }
+    let minimal = minimal_visibility(ctx, def, module)?;
+    if minimal.as_ref().map(|it| it.syntax().to_string()) == Some(current.syntax().to_string()) {
+        cov_mark::hit!(minimal_visibility_already_minimal);
+        return None;
+    }
+
+    acc.add(
+        AssistId("change_visibility_to_minimal", AssistKind::RefactorRewrite),
+        "Change visibility to minimal required",
+        target,
+        |edit| {
+            let mut editor = SyntaxEditor::new(owner.clone());
+            match minimal {
+                Some(minimal) => editor.replace(current.syntax(), minimal.syntax()),
+                None => {
+                    if let Some(ws) = current
+                        .syntax()
+                        .next_sibling_or_token()
+                        .filter(|it| it.kind() == WHITESPACE)
+                    {
+                        editor.delete(ws);
+                    }
+                    editor.delete(current.syntax());
+                }
+            }
+            edit.add_syntax_editor(editor);
+        },
+    )
+}
+
+/// Computes the narrowest visibility the item or field `def` of `module` needs for all of its
+/// usages to keep compiling, `None` meaning that it can be private.
+fn minimal_visibility(
+    ctx: &AssistContext<'_>,
+    def: Definition,
+    module: Module,
+) -> Option<Option<ast::Visibility>> {
+    let db = ctx.db();
+    // A private item is visible in the module it's defined in and the descendants of that one.
+    let crate_root = module.crate_root(db);
+
+    // The narrowest module all the usages are in.
+    let mut scope = module;
+    for (_, refs) in ctx.usages(def) {
+        db.unwind_if_cancelled();
+        for FileReference { name, .. } in refs {
+            let node = match name.syntax() {
+                NodeOrToken::Node(node) => node,
+                NodeOrToken::Token(token) => token.parent()?,
+            };
+            let usage_module = ctx.sema.scope(&node)?.module().nearest_non_block_module(db);
+            if usage_module.krate() != module.krate() {
+                return Some(Some(make::visibility_pub()));
+            }
+            scope = common_ancestor(db, scope, usage_module)?;
+
+            // An item can't be re-exported with a wider visibility than its own.
+            let Some(vis) =
+                node.ancestors().find_map(ast::Use::cast).and_then(|it| it.visibility())
+            else {
+                continue;
+            };
+            let reexported_in = match vis.kind() {
+                VisibilityKind::Pub => return Some(Some(make::visibility_pub())),
+                VisibilityKind::PubSelf => usage_module,
+                VisibilityKind::PubSuper => usage_module.parent(db).unwrap_or(crate_root),
+                VisibilityKind::PubCrate => crate_root,
+                VisibilityKind::In(path) => match ctx.sema.resolve_path(&path) {
+                    Some(PathResolution::Def(ModuleDef::Module(it))) => it,
+                    _ => crate_root,
+                },
+            };
+            scope = common_ancestor(db, scope, reexported_in)?;
+        }
+    }
+
+    let vis = if scope == module {
+        None
+    } else if scope == crate_root {
+        Some(make::visibility_pub_crate())
+    } else if module.parent(db) == Some(scope) {
+        Some(make::visibility_pub_super())
+    } else {
+        let mut path = scope.path_to_root(db);
+        path.pop();
+        let path = path
+            .into_iter()
+            .rev()
+            .map(|it| it.name(db).map(|name| name.display(db).to_string()))
+            .collect::<Option<Vec<_>>>()?;
+        let path = make::path_from_text(&format!("crate::{}", path.iter().format("::")));
+        Some(make::visibility_pub_in(path))
+    };
+    Some(vis)
+}
+
+/// Whether the item or field `def` of `module` can be named from other crates.
+fn is_exported(db: &RootDatabase, def: Definition, module: Module) -> bool {
+    let is_public = |def: Definition| def.visibility(db) == Some(Visibility::Public);
+    let parent_is_public = match def {
+        Definition::Field(field) => is_public(Definition::from(field.parent_def(db))),
+        _ => true,
+    };
+    is_public(def)
+        && parent_is_public
+        && module.path_to_root(db).into_iter().all(|module| {
+            module.is_crate_root()
+                || module.name(db).is_some() && module.visibility(db) == Visibility::Public
+        })
+}
+
+fn common_ancestor(db: &dyn HirDatabase, a: Module, b: Module) -> Option<Module> {
+    let b_path = b.path_to_root(db);
+    a.path_to_root(db).into_iter().find(|it| b_path.contains(it))
+}
+
 fn add_vis(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let (owner, target) = vis_owner(ctx)?;
+    let is_field =
+        ast::RecordField::can_cast(owner.kind()) || ast::TupleField::can_cast(owner.kind());
+    if !is_field && !can_add(&owner) {
+        return None;
+    }
+    // Already has visibility, do nothing
+    if owner.children().any(|child| child.kind() == VISIBILITY) {
+        return None;
+    }
+    let anchor = vis_anchor(&owner)?;
+
+    acc.add(
+        AssistId("change_visibility", AssistKind::RefactorRewrite),
+        "Change visibility to pub(crate)",
+        target,
+        |edit| {
+            let mut editor = SyntaxEditor::new(owner);
+            editor.insert_all(
+                Position::before(anchor),
+                vec![
+                    make::visibility_pub_crate().syntax().clone().into(),
+                    make::tokens::single_space().into(),
+                ],
+            );
+            edit.add_syntax_editor(editor);
+        },
+    )
+}
+
+/// Finds the item or field at the cursor which can have a visibility, along with the range the
+/// assists target.
+fn vis_owner(ctx: &AssistContext<'_>) -> Option<(SyntaxNode, TextRange)> {
     let item_keyword = ctx.token_at_offset().find(|leaf| {
         matches!(
             leaf.kind(),
@@ -45,44 +249,29 @@ fn add_vis(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
         )
     });
 
-    let (offset, target) = if let Some(keyword) = item_keyword {
-        let parent = keyword.parent()?;
-
-        if !can_add(&parent) {
-            return None;
-        }
-        // Already has visibility, do nothing
-        if parent.children().any(|child| child.kind() == VISIBILITY) {
-            return None;
-        }
-        (vis_offset(&parent), keyword.text_range())
+    if let Some(keyword) = item_keyword {
+        Some((keyword.parent()?, keyword.text_range()))
     } else if let Some(field_name) = ctx.find_node_at_offset::<ast::Name>() {
         let field = field_name.syntax().ancestors().find_map(ast::RecordField::cast)?;
         if field.name()? != field_name {
             cov_mark::hit!(change_visibility_field_false_positive);
             return None;
         }
-        if field.visibility().is_some() {
-            return None;
-        }
-        (vis_offset(field.syntax()), field_name.syntax().text_range())
-    } else if let Some(field) = ctx.find_node_at_offset::<ast::TupleField>() {
-        if field.visibility().is_some() {
-            return None;
-        }
-        (vis_offset(field.syntax()), field.syntax().text_range())
+        Some((field.syntax().clone(), field_name.syntax().text_range()))
     } else {
-        return None;
-    };
+        let field = ctx.find_node_at_offset::<ast::TupleField>()?;
+        Some((field.syntax().clone(), field.syntax().text_range()))
+    }
+}
 
-    acc.add(
-        AssistId("change_visibility", AssistKind::RefactorRewrite),
-        "Change visibility to pub(crate)",
-        target,
-        |edit| {
-            edit.insert(offset, "pub(crate) ");
-        },
-    )
+/// Whether `node` is a field of a struct or union, the fields of enum variants can't have a
+/// visibility.
+fn is_adt_field(node: &SyntaxNode) -> bool {
+    (ast::RecordField::can_cast(node.kind()) || ast::TupleField::can_cast(node.kind()))
+        && node
+            .parent()
+            .and_then(|field_list| field_list.parent())
+            .is_some_and(|it| !ast::Variant::can_cast(it.kind()))
 }
 
 fn can_add(node: &SyntaxNode) -> bool {
@@ -109,6 +298,7 @@ fn can_add(node: &SyntaxNode) -> bool {
 }
 
 fn change_vis(acc: &mut Assists, vis: ast::Visibility) -> Option<()> {
+    let owner = vis.syntax().parent()?;
     if vis.syntax().text() == "pub" {
         let target = vis.syntax().text_range();
         return acc.add(
@@ -116,7 +306,9 @@ fn change_vis(acc: &mut Assists, vis: ast::Visibility) -> Option<()> {
             "Change Visibility to pub(crate)",
             target,
             |edit| {
-                edit.replace(vis.syntax().text_range(), "pub(crate)");
+                let mut editor = SyntaxEditor::new(owner.clone());
+                editor.replace(vis.syntax(), make::visibility_pub_crate().syntax());
+                edit.add_syntax_editor(editor);
             },
         );
     }
@@ -127,7 +319,9 @@ fn change_vis(acc: &mut Assists, vis: ast::Visibility) -> Option<()> {
             "Change visibility to pub",
             target,
             |edit| {
-                edit.replace(vis.syntax().text_range(), "pub");
+                let mut editor = SyntaxEditor::new(owner.clone());
+                editor.replace(vis.syntax(), make::visibility_pub().syntax());
+                edit.add_syntax_editor(editor);
             },
         );
     }
@@ -274,4 +468,217 @@ fn not_applicable_for_items_within_fns() {
             "fn foo() { impl Trait for Bar { con$0st FOO: u8 = 69; } }",
         );
     }
+
+    #[test]
+    fn minimal_visibility_removes_unneeded_visibility() {
+        check_assist(
+            change_visibility_to_minimal,
+            r"
+mod foo {
+    $0pub(crate) fn bar() {}
+    mod baz {
+        fn qux() { super::bar() }
+    }
+}
+",
+            r"
+mod foo {
+    fn bar() {}
+    mod baz {
+        fn qux() { super::bar() }
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn minimal_visibility_pub_super() {
+        check_assist(
+            change_visibility_to_minimal,
+            r"
+mod foo {
+    mod bar {
+        $0pub(crate) fn baz() {}
+    }
+    fn qux() { bar::baz() }
+}
+",
+            r"
+mod foo {
+    mod bar {
+        pub(super) fn baz() {}
+    }
+    fn qux() { bar::baz() }
+}
+",
+        );
+    }
+
+    #[test]
+    fn minimal_visibility_pub_in_path() {
+        check_assist(
+            change_visibility_to_minimal,
+            r"
+mod a {
+    mod b {
+        pub mod c {
+            $0pub struct S;
+        }
+    }
+    mod d {
+        fn f(_: super::b::c::S) {}
+    }
+}
+",
+            r"
+mod a {
+    mod b {
+        pub mod c {
+            pub(in crate::a) struct S;
+        }
+    }
+    mod d {
+        fn f(_: super::b::c::S) {}
+    }
+}
+",
+        );
+    }
+
+    #[test]
+    fn minimal_visibility_pub_crate() {
+        check_assist(
+            change_visibility_to_minimal,
+            r"
+mod a {
+    pub mod b {
+        pub struct S { pub $0field: u32 }
+    }
+}
+fn f(s: a::b::S) -> u32 { s.field }
+",
+            r"
+mod a {
+    pub mod b {
+        pub struct S { pub(crate) field: u32 }
+    }
+}
+fn f(s: a::b::S) -> u32 { s.field }
+",
+        );
+    }
+
+    #[test]
+    fn minimal_visibility_respects_reexports() {
+        check_assist(
+            change_visibility_to_minimal,
+            r"
+mod a {
+    mod b {
+        $0pub fn f() {}
+    }
+    pub(crate) use self::b::f;
+}
+",
+            r"
+mod a {
+    mod b {
+        pub(crate) fn f() {}
+    }
+    pub(crate) use self::b::f;
+}
+",
+        );
+    }
+
+    #[test]
+    fn minimal_visibility_usage_in_other_crate() {
+        cov_mark::check!(minimal_visibility_exported_from_library);
+        check_assist_not_applicable(
+            change_visibility_to_minimal,
+            r"
+//- /lib.rs crate:lib
+pub mod a {
+    $0pub fn f() {}
+}
+//- /main.rs crate:main deps:lib
+fn main() { lib::a::f() }
+",
+        );
+    }
+
+    #[test]
+    fn minimal_visibility_not_applicable_to_public_api_of_library() {
+        cov_mark::check!(minimal_visibility_exported_from_library);
+        check_assist_not_applicable(
+            change_visibility_to_minimal,
+            r"
+pub mod a {
+    $0pub fn f() {}
+}
+fn g() { a::f() }
+",
+        );
+    }
+
+    #[test]
+    fn minimal_visibility_public_items_of_binary() {
+        check_assist(
+            change_visibility_to_minimal,
+            r"
+//- /main.rs crate:main env:CARGO_BIN_NAME=main
+pub mod a {
+    $0pub fn f() {}
+}
+fn main() { a::f() }
+",
+            r"
+pub mod a {
+    pub(crate) fn f() {}
+}
+fn main() { a::f() }
+",
+        );
+    }
+
+    #[test]
+    fn minimal_visibility_not_applicable_to_public_api_of_library_with_main() {
+        cov_mark::check!(minimal_visibility_exported_from_library);
+        check_assist_not_applicable(
+            change_visibility_to_minimal,
+            r"
+pub mod a {
+    $0pub fn f() {}
+}
+pub fn main() { a::f() }
+",
+        );
+    }
+
+    #[test]
+    fn minimal_visibility_not_applicable_when_already_minimal() {
+        cov_mark::check!(minimal_visibility_already_minimal);
+        check_assist_not_applicable(
+            change_visibility_to_minimal,
+            r"
+mod a {
+    mod b {
+        $0pub(super) fn f() {}
+    }
+    fn g() { b::f() }
+}
+",
+        );
+    }
+
+    #[test]
+    fn minimal_visibility_not_applicable_to_variant_fields() {
+        check_assist_not_applicable(
+            change_visibility_to_minimal,
+            r"
+enum E { V { $0field: u32 } }
+",
+        );
+    }
 }
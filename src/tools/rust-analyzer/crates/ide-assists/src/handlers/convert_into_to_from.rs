@@ -1,4 +1,7 @@
-use ide_db::{famous_defs::FamousDefs, helpers::mod_path_to_ast, traits::resolve_target_trait};
+use ide_db::{
+    defs::Definition, famous_defs::FamousDefs, helpers::mod_path_to_ast,
+    traits::resolve_target_trait,
+};
 use syntax::ast::{self, AstNode, HasName};
 
 use crate::{AssistContext, AssistId, AssistKind, Assists};
@@ -7,7 +10,8 @@
 
 // Assist: convert_into_to_from
 //
-// Converts an Into impl to an equivalent From impl.
+// Converts an Into impl to an equivalent From impl, or a TryInto impl to a TryFrom impl. Calls of
+// the method through a path are changed to call the new one.
 //
 // ```
 // # //- minicore: from
@@ -39,9 +43,15 @@ pub(crate) fn convert_into_to_from(acc: &mut Assists, ctx: &AssistContext<'_>) -
     let module = ctx.sema.scope(impl_.syntax())?.module();
 
     let trait_ = resolve_target_trait(&ctx.sema, &impl_)?;
-    if trait_ != FamousDefs(&ctx.sema, module.krate()).core_convert_Into()? {
-        return None;
-    }
+    let famous_defs = FamousDefs(&ctx.sema, module.krate());
+    let (into, from, into_trait, from_trait) = match Some(trait_) {
+        it if it == famous_defs.core_convert_Into() => ("into", "from", "Into", "From"),
+        it if it == famous_defs.core_convert_TryInto() => {
+            ("try_into", "try_from", "TryInto", "TryFrom")
+        }
+        _ => return None,
+    };
+    let fallible = into_trait == "TryInto";
 
     let src_type_path = {
         let src_type_path = src_type.syntax().descendants().find_map(ast::Path::cast)?;
@@ -67,7 +77,7 @@ pub(crate) fn convert_into_to_from(acc: &mut Assists, ctx: &AssistContext<'_>) -
 
     let into_fn = impl_.assoc_item_list()?.assoc_items().find_map(|item| {
         if let ast::AssocItem::Fn(f) = item {
-            if f.name()?.text() == "into" {
+            if f.name()?.text() == into {
                 return Some(f);
             }
         };
@@ -78,23 +88,55 @@ pub(crate) fn convert_into_to_from(acc: &mut Assists, ctx: &AssistContext<'_>) -
     let into_fn_params = into_fn.param_list()?;
     let into_fn_return = into_fn.ret_type()?;
 
-    let selfs = into_fn
-        .body()?
-        .syntax()
-        .descendants()
-        .filter_map(ast::NameRef::cast)
-        .filter(|name| name.text() == "self" || name.text() == "Self");
+    // `Self::Error` is the same type in both impls.
+    let selfs =
+        into_fn.body()?.syntax().descendants().filter_map(ast::NameRef::cast).filter(|name| {
+            name.text() == "self" || (name.text() == "Self" && !is_self_error(name))
+        });
+
+    // In `Result<Dest, Self::Error>`, only `Dest` becomes `Self`.
+    let ok_type = fallible
+        .then(|| match into_fn_return.ty()? {
+            ast::Type::PathType(path) => {
+                path.path()?.segment()?.generic_arg_list()?.generic_args().next()
+            }
+            _ => None,
+        })
+        .flatten();
+
+    let into_fn_def = ctx.sema.to_def(&into_fn)?;
+    // Types other than plain paths need to be wrapped in `<>` to be qualified.
+    let is_plain_path = match &dest_type {
+        ast::GenericArg::TypeArg(arg) => {
+            matches!(arg.ty(), Some(ast::Type::PathType(_)))
+                && !dest_type
+                    .syntax()
+                    .descendants()
+                    .any(|it| ast::GenericArgList::can_cast(it.kind()))
+        }
+        _ => false,
+    };
+    let from_fn_path = if is_plain_path {
+        format!("{dest_type}::{from}")
+    } else {
+        format!("<{dest_type}>::{from}")
+    };
 
     acc.add(
         AssistId("convert_into_to_from", AssistKind::RefactorRewrite),
-        "Convert Into to From",
+        format!("Convert {into_trait} to {from_trait}"),
         impl_.syntax().text_range(),
         |builder| {
             builder.replace(src_type.syntax().text_range(), dest_type.to_string());
-            builder.replace(ast_trait.syntax().text_range(), format!("From<{src_type}>"));
-            builder.replace(into_fn_return.syntax().text_range(), "-> Self");
+            builder.replace(ast_trait.syntax().text_range(), format!("{from_trait}<{src_type}>"));
+            match ok_type {
+                Some(ok_type) => builder.replace(ok_type.syntax().text_range(), "Self"),
+                None if fallible => builder
+                    .replace(into_fn_return.syntax().text_range(), "-> Result<Self, Self::Error>"),
+                None => builder.replace(into_fn_return.syntax().text_range(), "-> Self"),
+            }
             builder.replace(into_fn_params.syntax().text_range(), format!("(val: {src_type})"));
-            builder.replace(into_fn_name.syntax().text_range(), "from");
+            builder.replace(into_fn_name.syntax().text_range(), from);
 
             for s in selfs {
                 match s.text().as_ref() {
@@ -103,10 +145,36 @@ pub(crate) fn convert_into_to_from(acc: &mut Assists, ctx: &AssistContext<'_>) -
                     _ => {}
                 }
             }
+
+            // Method calls keep working through the blanket impl, calls like
+            // `<Src as Into<Dest>>::into(x)` are turned around.
+            let usages = Definition::Function(into_fn_def).usages(&ctx.sema).all();
+            for (file_id, references) in usages {
+                builder.edit_file(file_id);
+                for reference in references {
+                    let Some(name_ref) = reference.name.as_name_ref() else { continue };
+                    // The name is the last segment of the callee's path.
+                    let Some(path_expr) =
+                        name_ref.syntax().ancestors().nth(3).and_then(ast::PathExpr::cast).filter(
+                            |it| it.syntax().parent().and_then(ast::CallExpr::cast).is_some(),
+                        )
+                    else {
+                        continue;
+                    };
+                    builder.replace(path_expr.syntax().text_range(), from_fn_path.clone());
+                }
+            }
         },
     )
 }
 
+/// Whether `name_ref` is the `Self` of `Self::Error`.
+fn is_self_error(name_ref: &ast::NameRef) -> bool {
+    let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast);
+    let parent_path = segment.and_then(|it| it.parent_path().parent_path());
+    parent_path.and_then(|it| it.segment()?.name_ref()).is_some_and(|it| it.text() == "Error")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -353,4 +421,106 @@ fn into(self) -> Thing {
 "#,
         );
     }
+
+    #[test]
+    fn convert_try_into_to_try_from() {
+        check_assist(
+            convert_into_to_from,
+            r#"
+//- minicore: try_from
+struct Even(u32);
+struct NotEven;
+
+impl $0core::convert::TryInto<Even> for u32 {
+    type Error = NotEven;
+
+    fn try_into(self) -> Result<Even, Self::Error> {
+        if self % 2 == 0 {
+            Ok(Even(self))
+        } else {
+            Err(NotEven)
+        }
+    }
+}
+
+fn f() {
+    let a: Result<Even, _> = 2.try_into();
+    let b = <u32 as TryInto<Even>>::try_into(4);
+}
+"#,
+            r#"
+struct Even(u32);
+struct NotEven;
+
+impl TryFrom<u32> for Even {
+    type Error = NotEven;
+
+    fn try_from(val: u32) -> Result<Self, Self::Error> {
+        if val % 2 == 0 {
+            Ok(Even(val))
+        } else {
+            Err(NotEven)
+        }
+    }
+}
+
+fn f() {
+    let a: Result<Even, _> = 2.try_into();
+    let b = Even::try_from(4);
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn convert_try_into_keeps_self_error() {
+        check_assist(
+            convert_into_to_from,
+            r#"
+//- minicore: try_from
+struct Wrap<T>(T);
+enum Thing {
+    A(Wrap<u8>),
+    B,
+}
+
+impl $0TryInto<Wrap<u8>> for Thing {
+    type Error = Thing;
+
+    fn try_into(self) -> Result<Wrap<u8>, Self::Error> {
+        match self {
+            Self::A(v) => Ok(v),
+            _ => Err::<_, Self::Error>(self),
+        }
+    }
+}
+
+fn f(t: Thing) {
+    TryInto::<Wrap<u8>>::try_into(t);
+}
+"#,
+            r#"
+struct Wrap<T>(T);
+enum Thing {
+    A(Wrap<u8>),
+    B,
+}
+
+impl TryFrom<Thing> for Wrap<u8> {
+    type Error = Thing;
+
+    fn try_from(val: Thing) -> Result<Self, Self::Error> {
+        match val {
+            Thing::A(v) => Ok(v),
+            _ => Err::<_, Self::Error>(val),
+        }
+    }
+}
+
+fn f(t: Thing) {
+    <Wrap<u8>>::try_from(t);
+}
+"#,
+        )
+    }
 }
@@ -0,0 +1,195 @@
+use ide_db::{
+    assists::{AssistId, AssistKind},
+    base_db::AnchoredPathBuf,
+};
+use syntax::{
+    ast::{self, HasAttrs, HasName},
+    AstNode,
+};
+
+use crate::{
+    assist_context::{AssistContext, Assists},
+    utils::{make_path_attr, relative_path_attrs},
+};
+
+// Assist: convert_module_layout
+//
+// Moves the file of a module declared with `mod foo;` from `foo.rs` to `foo/mod.rs`, or the other
+// way around. The files of its child modules are in `foo/` with both layouts, and stay there.
+//
+// ```
+// //- /main.rs
+// mod $0foo;
+// //- /foo.rs
+// fn t() {}
+// ```
+// ->
+// ```
+// mod foo;
+// ```
+pub(crate) fn convert_module_layout(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let module_ast = ctx.find_node_at_offset::<ast::Module>()?;
+    if module_ast.item_list().is_some() {
+        return None;
+    }
+    if module_ast.attrs().any(|attr| attr.simple_name().as_deref() == Some("path")) {
+        cov_mark::hit!(module_with_path_attr);
+        return None;
+    }
+    // The file of `r#mod` has to be `mod/mod.rs`, `mod.rs` would be the one of its parent.
+    let module_name = module_ast.name()?;
+    if module_name.text() == "r#mod" {
+        return None;
+    }
+    let module = ctx.sema.to_def(&module_ast)?;
+    let module_file = module.as_source_file_id(ctx.db())?;
+
+    let name = module_name.text().trim_start_matches("r#").to_owned();
+    let is_mod_rs = module.is_mod_rs(ctx.db());
+    let (label, path) = if is_mod_rs {
+        (format!("Convert {name}/mod.rs to {name}.rs"), format!("../{name}.rs"))
+    } else {
+        (format!("Convert {name}.rs to {name}/mod.rs"), format!("./{name}/mod.rs"))
+    };
+
+    acc.add(
+        AssistId("convert_module_layout", AssistKind::Refactor),
+        label,
+        module_ast.syntax().text_range(),
+        |builder| {
+            let source_file = ctx.sema.parse(module_file);
+            builder.edit_file(module_file);
+            for (attr, attr_path) in relative_path_attrs(&source_file) {
+                let new_attr = make_path_attr(&rebase_path(is_mod_rs, &name, &attr_path));
+                builder.replace(attr.syntax().text_range(), new_attr.to_string());
+            }
+            builder.move_file(module_file, AnchoredPathBuf { anchor: module_file, path });
+        },
+    )
+}
+
+/// The `#[path]` attributes of nested `mod` declarations are relative to the directory of the file,
+/// which is `name/` for a `mod.rs` file and its parent otherwise.
+fn rebase_path(is_mod_rs: bool, name: &str, path: &str) -> String {
+    if !is_mod_rs {
+        return format!("../{path}");
+    }
+    match path.strip_prefix("../") {
+        Some(path) => path.to_owned(),
+        None => format!("{name}/{path}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn file_to_mod_rs() {
+        check_assist(
+            convert_module_layout,
+            r#"
+//- /main.rs
+mod $0a;
+//- /a.rs
+mod b;
+fn t() {}
+//- /a/b.rs
+"#,
+            r#"
+//- /a/mod.rs
+mod b;
+fn t() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn mod_rs_to_file() {
+        check_assist(
+            convert_module_layout,
+            r#"
+//- /main.rs
+mod $0a;
+//- /a/mod.rs
+mod b;
+//- /a/b.rs
+"#,
+            r#"
+//- /a.rs
+mod b;
+"#,
+        );
+    }
+
+    #[test]
+    fn rebase_path_attrs() {
+        // The file is edited before it's moved, the moved file is shown with its old contents.
+        check_assist(
+            convert_module_layout,
+            r#"
+//- /main.rs
+mod $0a;
+//- /a.rs
+#[path = "other/b.rs"]
+mod b;
+//- /other/b.rs
+"#,
+            r#"
+//- /a.rs
+#[path = "../other/b.rs"]
+mod b;
+//- /a/mod.rs
+#[path = "other/b.rs"]
+mod b;
+"#,
+        );
+        check_assist(
+            convert_module_layout,
+            r#"
+//- /main.rs
+mod $0a;
+//- /a/mod.rs
+#[path = "../other/b.rs"]
+mod b;
+#[path = "c_impl.rs"]
+mod c;
+//- /other/b.rs
+//- /a/c_impl.rs
+"#,
+            r#"
+//- /a/mod.rs
+#[path = "other/b.rs"]
+mod b;
+#[path = "a/c_impl.rs"]
+mod c;
+//- /a.rs
+#[path = "../other/b.rs"]
+mod b;
+#[path = "c_impl.rs"]
+mod c;
+"#,
+        );
+    }
+
+    #[test]
+    fn module_with_path_attr() {
+        cov_mark::check!(module_with_path_attr);
+        check_assist_not_applicable(
+            convert_module_layout,
+            r#"
+//- /main.rs
+#[path = "b.rs"]
+mod $0a;
+//- /b.rs
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_inline_module() {
+        check_assist_not_applicable(convert_module_layout, r#"mod $0a {}"#);
+    }
+}
@@ -0,0 +1,464 @@
+use hir::{HirDisplay, Local};
+use ide_db::{
+    defs::{Definition, NameRefClass},
+    FxHashSet,
+};
+use itertools::Itertools;
+use stdx::{format_to, to_lower_snake_case};
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        HasLoopBody, HasName,
+    },
+    match_ast, AstNode, SyntaxKind, SyntaxNode, T,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: convert_state_machine_to_methods
+//
+// Moves the arms of a `loop` matching on the state of a state machine into methods of their own,
+// leaving the loop to dispatch on the state.
+//
+// ```
+// enum State { Idle, Running(u32), Done }
+// struct Machine { state: State }
+// impl Machine {
+//     fn run(&mut self) {
+//         $0loop {
+//             self.state = match self.state {
+//                 State::Idle => State::Running(0),
+//                 State::Running(steps) => {
+//                     let steps = steps + 1;
+//                     if steps == 10 { State::Done } else { State::Running(steps) }
+//                 }
+//                 State::Done => break,
+//             };
+//         }
+//     }
+// }
+// ```
+// ->
+// ```
+// enum State { Idle, Running(u32), Done }
+// struct Machine { state: State }
+// impl Machine {
+//     fn run(&mut self) {
+//         loop {
+//             self.state = match self.state {
+//                 State::Idle => self.on_idle(),
+//                 State::Running(steps) => self.on_running(steps),
+//                 State::Done => break,
+//             };
+//         }
+//     }
+//
+//     fn on_idle(&mut self) -> State {
+//         State::Running(0)
+//     }
+//
+//     fn on_running(&mut self, steps: u32) -> State {
+//         let steps = steps + 1;
+//         if steps == 10 { State::Done } else { State::Running(steps) }
+//     }
+// }
+// ```
+pub(crate) fn convert_state_machine_to_methods(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let loop_kw = ctx.find_token_syntax_at_offset(T![loop])?;
+    let loop_expr = ast::LoopExpr::cast(loop_kw.parent()?)?;
+    let match_expr = state_match(&loop_expr)?;
+
+    let func = loop_expr.syntax().ancestors().find_map(ast::Fn::cast)?;
+    let self_param = func.param_list()?.self_param()?;
+    if self_param.amp_token().is_none() || self_param.mut_token().is_none() {
+        return None;
+    }
+    let impl_ = ast::Impl::cast(func.syntax().parent()?.parent()?)?;
+    let module = ctx.sema.scope(func.syntax())?.module();
+
+    let mut taken_names: FxHashSet<String> = impl_
+        .assoc_item_list()?
+        .assoc_items()
+        .filter_map(|item| match item {
+            ast::AssocItem::Fn(it) => Some(it.name()?.to_string()),
+            _ => None,
+        })
+        .collect();
+    let mut methods = Vec::new();
+    for arm in match_expr.match_arm_list()?.arms() {
+        let Some(method) = StateMethod::new(ctx, module, &arm) else { continue };
+        if taken_names.insert(method.name.clone()) {
+            methods.push(method);
+        }
+    }
+    if methods.is_empty() {
+        return None;
+    }
+
+    acc.add(
+        AssistId("convert_state_machine_to_methods", AssistKind::RefactorExtract),
+        "Convert state machine arms into methods",
+        loop_expr.syntax().text_range(),
+        |builder| {
+            let indent = IndentLevel::from_node(func.syntax());
+            let mut buf = String::new();
+            for method in &methods {
+                let args = method.params.iter().map(|(name, _)| name).join(", ");
+                let call = format!("self.{}({args})", method.name);
+                let range = method.expr.syntax().text_range();
+                match method.arm.comma_token() {
+                    Some(_) => builder.replace(range, call),
+                    None => builder.replace(range, format!("{call},")),
+                }
+                format_to!(buf, "\n\n{indent}{}", method.fn_text(indent));
+            }
+            builder.insert(func.syntax().text_range().end(), buf);
+        },
+    )
+}
+
+/// The match on the state in the body of `loop_expr`, which is either all of the body or assigned
+/// to the state, as in `loop { state = match state { .. } }`.
+fn state_match(loop_expr: &ast::LoopExpr) -> Option<ast::MatchExpr> {
+    let stmt_list = loop_expr.loop_body()?.stmt_list()?;
+    let expr = match (stmt_list.statements().exactly_one().ok(), stmt_list.tail_expr()) {
+        (Some(ast::Stmt::ExprStmt(stmt)), None) => stmt.expr()?,
+        (None, Some(expr)) => expr,
+        _ => return None,
+    };
+    match expr {
+        ast::Expr::MatchExpr(it) => Some(it),
+        ast::Expr::BinExpr(it) if it.op_kind() == Some(ast::BinaryOp::Assignment { op: None }) => {
+            match it.rhs()? {
+                ast::Expr::MatchExpr(it) => Some(it),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+struct StateMethod {
+    arm: ast::MatchArm,
+    expr: ast::Expr,
+    name: String,
+    /// The bindings of the pattern of the arm, passed to the method.
+    params: Vec<(String, String)>,
+    ret_ty: Option<String>,
+}
+
+impl StateMethod {
+    fn new(ctx: &AssistContext<'_>, module: hir::Module, arm: &ast::MatchArm) -> Option<Self> {
+        let db = ctx.db();
+        let pat = arm.pat()?;
+        let expr = arm.expr()?;
+        if arm.guard().is_some() || leaves_arm(&expr) {
+            return None;
+        }
+        let variant = match &pat {
+            ast::Pat::PathPat(it) => it.path()?,
+            ast::Pat::TupleStructPat(it) => it.path()?,
+            ast::Pat::RecordPat(it) => it.path()?,
+            _ => return None,
+        };
+        let variant = variant.segment()?.name_ref()?;
+        let name = format!("on_{}", to_lower_snake_case(variant.text().as_str()));
+
+        let mut params = Vec::new();
+        for ident_pat in pat.syntax().descendants().filter_map(ast::IdentPat::cast) {
+            if ident_pat.ref_token().is_some() || ident_pat.pat().is_some() {
+                return None;
+            }
+            let local = ctx.sema.to_def(&ident_pat)?;
+            let ty = local.ty(db).display_source_code(db, module.into(), true).ok()?;
+            let name = ident_pat.name()?.to_string();
+            let param = match ident_pat.mut_token() {
+                Some(_) => format!("mut {name}: {ty}"),
+                None => format!("{name}: {ty}"),
+            };
+            params.push((name, param));
+        }
+
+        // Other locals than the ones of the arm can't be used by the method.
+        let arm_locals: FxHashSet<Local> = arm
+            .syntax()
+            .descendants()
+            .filter_map(ast::IdentPat::cast)
+            .filter_map(|it| ctx.sema.to_def(&it))
+            .collect();
+        let uses_outer_local =
+            expr.syntax().descendants().filter_map(ast::NameRef::cast).any(|it| {
+                matches!(
+                    NameRefClass::classify(&ctx.sema, &it),
+                    Some(NameRefClass::Definition(Definition::Local(local)))
+                        if !local.is_self(db) && !arm_locals.contains(&local)
+                )
+            });
+        if uses_outer_local {
+            return None;
+        }
+
+        let ty = ctx.sema.type_of_expr(&expr)?.original;
+        let ret_ty = if ty.is_unit() {
+            None
+        } else {
+            Some(ty.display_source_code(db, module.into(), true).ok()?)
+        };
+        Some(StateMethod { arm: arm.clone(), expr, name, params, ret_ty })
+    }
+
+    fn fn_text(&self, indent: IndentLevel) -> String {
+        let params = std::iter::once("&mut self".to_owned())
+            .chain(self.params.iter().map(|(_, it)| it.clone()));
+        let mut buf = format!("fn {}({})", self.name, params.format(", "));
+        if let Some(ret_ty) = &self.ret_ty {
+            format_to!(buf, " -> {ret_ty}");
+        }
+        let arm_indent = IndentLevel::from_node(self.arm.syntax());
+        match &self.expr {
+            ast::Expr::BlockExpr(block) if block.modifier().is_none() => {
+                format_to!(buf, " {}", block.dedent(arm_indent).indent(indent));
+            }
+            expr => {
+                let expr = expr.dedent(arm_indent).indent(indent + 1);
+                format_to!(buf, " {{\n{}{expr}\n{indent}}}", indent + 1);
+            }
+        }
+        buf
+    }
+}
+
+/// Whether `expr` breaks out of or continues the state machine loop, or returns from the function,
+/// which a method can't do.
+fn leaves_arm(expr: &ast::Expr) -> bool {
+    let in_own_body = |node: &SyntaxNode, loops_count: bool| {
+        node.ancestors().take_while(|it| it != expr.syntax()).all(|it| {
+            match_ast! {
+                match it {
+                    ast::ClosureExpr(_) => false,
+                    ast::BlockExpr(it) => !matches!(
+                        it.modifier(),
+                        Some(ast::BlockModifier::Async(_) | ast::BlockModifier::Const(_))
+                    ),
+                    ast::LoopExpr(_) => !loops_count,
+                    ast::WhileExpr(_) => !loops_count,
+                    ast::ForExpr(_) => !loops_count,
+                    _ => true,
+                }
+            }
+        })
+    };
+    expr.syntax().descendants().any(|it| match it.kind() {
+        // Labels may refer to any loop, the arm is left as it is then.
+        SyntaxKind::BREAK_EXPR | SyntaxKind::CONTINUE_EXPR => {
+            it.children().any(|it| it.kind() == SyntaxKind::LIFETIME) || in_own_body(&it, true)
+        }
+        SyntaxKind::RETURN_EXPR | SyntaxKind::TRY_EXPR | SyntaxKind::AWAIT_EXPR => {
+            in_own_body(&it, false)
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn match_as_loop_body() {
+        check_assist(
+            convert_state_machine_to_methods,
+            r#"
+enum State { Start, Stop }
+struct Machine { state: State, count: u32 }
+impl Machine {
+    fn run(&mut self) {
+        lo$0op {
+            match self.state {
+                State::Start => {
+                    self.count += 1;
+                    if self.count > 3 {
+                        self.state = State::Stop;
+                    }
+                }
+                State::Stop => return,
+            }
+        }
+    }
+}
+"#,
+            r#"
+enum State { Start, Stop }
+struct Machine { state: State, count: u32 }
+impl Machine {
+    fn run(&mut self) {
+        loop {
+            match self.state {
+                State::Start => self.on_start(),
+                State::Stop => return,
+            }
+        }
+    }
+
+    fn on_start(&mut self) {
+        self.count += 1;
+        if self.count > 3 {
+            self.state = State::Stop;
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn record_pattern_bindings_become_params() {
+        check_assist(
+            convert_state_machine_to_methods,
+            r#"
+enum State { Reading { pos: usize }, Done }
+struct Parser { state: State }
+impl Parser {
+    fn run(&mut self) {
+        $0loop {
+            self.state = match self.state {
+                State::Reading { mut pos } => {
+                    pos += 1;
+                    State::Reading { pos }
+                }
+                State::Done => break,
+            };
+        }
+    }
+}
+"#,
+            r#"
+enum State { Reading { pos: usize }, Done }
+struct Parser { state: State }
+impl Parser {
+    fn run(&mut self) {
+        loop {
+            self.state = match self.state {
+                State::Reading { mut pos } => self.on_reading(pos),
+                State::Done => break,
+            };
+        }
+    }
+
+    fn on_reading(&mut self, mut pos: usize) -> State {
+        pos += 1;
+        State::Reading { pos }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_arms_using_outer_locals_or_leaving_the_loop() {
+        check_assist(
+            convert_state_machine_to_methods,
+            r#"
+enum State { A, B, C, D }
+struct Machine { state: State }
+impl Machine {
+    fn on_d(&self) {}
+    fn run(&mut self, limit: u32) {
+        $0loop {
+            self.state = match self.state {
+                State::A => State::B,
+                State::B => {
+                    for _ in 0..limit {}
+                    State::C
+                }
+                State::C => {
+                    if true {
+                        continue;
+                    }
+                    State::D
+                }
+                State::D => State::A,
+            };
+        }
+    }
+}
+"#,
+            r#"
+enum State { A, B, C, D }
+struct Machine { state: State }
+impl Machine {
+    fn on_d(&self) {}
+    fn run(&mut self, limit: u32) {
+        loop {
+            self.state = match self.state {
+                State::A => self.on_a(),
+                State::B => {
+                    for _ in 0..limit {}
+                    State::C
+                }
+                State::C => {
+                    if true {
+                        continue;
+                    }
+                    State::D
+                }
+                State::D => State::A,
+            };
+        }
+    }
+
+    fn on_a(&mut self) -> State {
+        State::B
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_of_methods() {
+        check_assist_not_applicable(
+            convert_state_machine_to_methods,
+            r#"
+enum State { A, B }
+fn run(mut state: State) {
+    $0loop {
+        state = match state {
+            State::A => State::B,
+            State::B => break,
+        };
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_other_loops() {
+        check_assist_not_applicable(
+            convert_state_machine_to_methods,
+            r#"
+enum State { A, B }
+struct Machine { state: State }
+impl Machine {
+    fn run(&mut self) {
+        $0loop {
+            self.step();
+            match self.state {
+                State::A => self.state = State::B,
+                State::B => break,
+            }
+        }
+    }
+    fn step(&mut self) {}
+}
+"#,
+        );
+    }
+}
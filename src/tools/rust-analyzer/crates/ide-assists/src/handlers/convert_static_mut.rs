@@ -0,0 +1,931 @@
+use hir::{Access, Adt, HasSource, ModuleDef, PathResolution};
+use ide_db::{
+    assists::{AssistId, AssistKind, GroupLabel},
+    base_db::FileId,
+    defs::Definition,
+    imports::insert_use::{insert_use, ImportScope},
+    source_change::SourceChangeBuilder,
+    FxHashSet,
+};
+use itertools::Itertools;
+use syntax::{
+    ast::{self, edit::IndentLevel, make, HasAttrs},
+    match_ast, AstNode, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextSize,
+};
+
+use crate::{AssistContext, Assists};
+
+/// The primitive types which have an atomic counterpart, along with it.
+const ATOMIC_TYPES: &[(&str, &str)] = &[
+    ("bool", "AtomicBool"),
+    ("i8", "AtomicI8"),
+    ("i16", "AtomicI16"),
+    ("i32", "AtomicI32"),
+    ("i64", "AtomicI64"),
+    ("isize", "AtomicIsize"),
+    ("u8", "AtomicU8"),
+    ("u16", "AtomicU16"),
+    ("u32", "AtomicU32"),
+    ("u64", "AtomicU64"),
+    ("usize", "AtomicUsize"),
+];
+
+// Assist: convert_static_mut_to_atomic
+//
+// Converts a `static mut` into a static that can be accessed safely: an atomic for integers and
+// booleans, a `Mutex` or `RwLock` in a `LazyLock`, or a `thread_local!` with a `Cell` for `Copy`
+// types, which gives each thread a value of its own. All accesses are rewritten accordingly, and
+// `unsafe` blocks which were only needed for them are removed.
+//
+// ```
+// static mut COUNTER$0: u32 = 0;
+//
+// fn increment() -> u32 {
+//     unsafe {
+//         COUNTER += 1;
+//     }
+//     unsafe { COUNTER }
+// }
+// ```
+// ->
+// ```
+// use std::sync::atomic::{AtomicU32, Ordering};
+//
+// static COUNTER: AtomicU32 = AtomicU32::new(0);
+//
+// fn increment() -> u32 {
+//     COUNTER.fetch_add(1, Ordering::SeqCst);
+//     COUNTER.load(Ordering::SeqCst)
+// }
+// ```
+pub(crate) fn convert_static_mut(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let static_ = ctx.find_node_at_offset::<ast::Static>()?;
+    let mut_token = static_.mut_token()?;
+    let ty = static_.ty()?;
+    let body = static_.body()?;
+    // Only offered on the signature, not to get in the way inside of the initializer.
+    if ctx.offset() > ty.syntax().text_range().end() {
+        return None;
+    }
+    let def = ctx.sema.to_def(&static_)?;
+    let static_ty = def.ty(ctx.db());
+    let target = static_.syntax().text_range();
+
+    let mut usages = Vec::new();
+    for (file_id, references) in Definition::Static(def).usages(&ctx.sema).all() {
+        for reference in references {
+            // Implicit captures of format strings can't be rewritten.
+            let name_ref = reference.name.as_name_ref()?;
+            if name_ref.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind())) {
+                continue;
+            }
+            let path = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+            let usage = Usage::new(ctx, file_id, path)?;
+            if file_id == ctx.file_id() && target.contains_range(usage.range) {
+                return None;
+            }
+            usages.push(usage);
+        }
+    }
+
+    let atomic = static_ty.as_builtin().and_then(|builtin| {
+        let name = builtin.name();
+        ATOMIC_TYPES.iter().find(|(ty, _)| name.as_str() == Some(*ty)).map(|&(_, atomic)| atomic)
+    });
+    // Locking twice while the first guard is still alive deadlocks.
+    let locks_overlap = usages.iter().tuple_combinations().any(|(a, b)| {
+        a.file_id == b.file_id
+            && (a.statement.contains_range(b.range) || b.statement.contains_range(a.range))
+    });
+    let wrappers = atomic
+        .map(Wrapper::Atomic)
+        .into_iter()
+        .chain([Wrapper::Mutex, Wrapper::RwLock])
+        .chain(static_ty.is_copy(ctx.db()).then_some(Wrapper::ThreadLocal));
+
+    let group = GroupLabel("Convert `static mut` to a safe wrapper".to_owned());
+    for wrapper in wrappers {
+        if !usages.iter().all(|usage| wrapper.supports(&usage.access)) {
+            continue;
+        }
+        if locks_overlap && matches!(wrapper, Wrapper::Mutex | Wrapper::RwLock) {
+            cov_mark::hit!(convert_static_mut_overlapping_locks);
+            continue;
+        }
+        acc.add_group(
+            &group,
+            AssistId(wrapper.id(), AssistKind::RefactorRewrite),
+            wrapper.label(),
+            target,
+            |builder| {
+                let usages_by_file = usages.iter().into_group_map_by(|usage| usage.file_id);
+                let mut files = usages_by_file.keys().copied().collect_vec();
+                if !files.contains(&ctx.file_id()) {
+                    files.push(ctx.file_id());
+                }
+
+                for file_id in files {
+                    builder.edit_file(file_id);
+                    let mut imports = Vec::new();
+                    if file_id == ctx.file_id() {
+                        convert_declaration(builder, wrapper, &static_, &mut_token, &ty, &body);
+                        if let Some(scope) =
+                            ImportScope::find_insert_use_container(static_.syntax(), &ctx.sema)
+                        {
+                            imports.extend(
+                                wrapper.imports().into_iter().map(|it| (scope.clone(), it)),
+                            );
+                        }
+                    }
+
+                    let usages = usages_by_file.get(&file_id).map_or(&[][..], |it| it.as_slice());
+                    let mut terminated = FxHashSet::default();
+                    for usage in usages {
+                        rewrite_usage(builder, wrapper, usage, &mut terminated);
+                        if let Wrapper::Atomic(_) = wrapper {
+                            if let Some(scope) = ImportScope::find_insert_use_container(
+                                usage.path.syntax(),
+                                &ctx.sema,
+                            ) {
+                                imports.push((scope, "std::sync::atomic::Ordering".to_owned()));
+                            }
+                        }
+                    }
+                    unwrap_unsafe_blocks(builder, ctx, def, usages, &terminated);
+
+                    // All the scopes are made mutable before inserting into any of them, their
+                    // ranges can't be found in the mutable tree anymore afterwards.
+                    let mut seen = FxHashSet::default();
+                    let imports = imports
+                        .into_iter()
+                        .filter(|(scope, path)| {
+                            seen.insert((scope.as_syntax_node().text_range(), path.clone()))
+                        })
+                        .map(|(scope, path)| {
+                            let scope = match scope {
+                                ImportScope::File(it) => ImportScope::File(builder.make_mut(it)),
+                                ImportScope::Module(it) => {
+                                    ImportScope::Module(builder.make_mut(it))
+                                }
+                                ImportScope::Block(it) => ImportScope::Block(builder.make_mut(it)),
+                            };
+                            (scope, path)
+                        })
+                        .collect_vec();
+                    for (scope, path) in imports {
+                        insert_use(&scope, make::path_from_text(&path), &ctx.config.insert_use);
+                    }
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+/// What the value of the static is wrapped into to make it safe to access.
+#[derive(Clone, Copy)]
+enum Wrapper {
+    Atomic(&'static str),
+    Mutex,
+    RwLock,
+    ThreadLocal,
+}
+
+impl Wrapper {
+    fn id(self) -> &'static str {
+        match self {
+            Wrapper::Atomic(_) => "convert_static_mut_to_atomic",
+            Wrapper::Mutex => "convert_static_mut_to_mutex",
+            Wrapper::RwLock => "convert_static_mut_to_rwlock",
+            Wrapper::ThreadLocal => "convert_static_mut_to_thread_local",
+        }
+    }
+
+    fn label(self) -> String {
+        match self {
+            Wrapper::Atomic(atomic) => format!("Convert `static mut` to `{atomic}`"),
+            Wrapper::Mutex => "Convert `static mut` to `LazyLock<Mutex<_>>`".to_owned(),
+            Wrapper::RwLock => "Convert `static mut` to `LazyLock<RwLock<_>>`".to_owned(),
+            // Unlike the others, each thread gets its own value.
+            Wrapper::ThreadLocal => {
+                "Convert `static mut` to a per-thread `thread_local!` `Cell`".to_owned()
+            }
+        }
+    }
+
+    /// Whether the static can still be accessed the way it is once wrapped. Locks hand out
+    /// references to the value which can't outlive the guard, the others only allow to get and
+    /// set it.
+    fn supports(self, access: &StaticAccess) -> bool {
+        match (self, access) {
+            (Wrapper::Mutex | Wrapper::RwLock, StaticAccess::Borrow { escapes, .. }) => !escapes,
+            (Wrapper::Mutex | Wrapper::RwLock, _) => true,
+            (_, StaticAccess::Read | StaticAccess::Assign { op: None, .. }) => true,
+            (Wrapper::Atomic(_), StaticAccess::Assign { op: Some(op), .. }) => {
+                fetch_method(*op).is_some()
+            }
+            (Wrapper::ThreadLocal, StaticAccess::Assign { .. }) => true,
+            _ => false,
+        }
+    }
+
+    /// The imports needed next to the static.
+    fn imports(self) -> Vec<String> {
+        match self {
+            Wrapper::Atomic(atomic) => vec![format!("std::sync::atomic::{atomic}")],
+            Wrapper::Mutex => vec!["std::sync::LazyLock".to_owned(), "std::sync::Mutex".to_owned()],
+            Wrapper::RwLock => {
+                vec!["std::sync::LazyLock".to_owned(), "std::sync::RwLock".to_owned()]
+            }
+            Wrapper::ThreadLocal => vec!["std::cell::Cell".to_owned()],
+        }
+    }
+}
+
+/// A use of the static in an expression.
+struct Usage {
+    file_id: FileId,
+    path: ast::PathExpr,
+    /// The range of the path in the original file.
+    range: TextRange,
+    /// The range of the statement in the original file, until the end of which the guard of a
+    /// lock would be kept alive.
+    statement: TextRange,
+    access: StaticAccess,
+    /// Whether the static is the receiver of a method call or the base of a field or index
+    /// expression, where it is dereferenced automatically.
+    is_receiver: bool,
+}
+
+enum StaticAccess {
+    /// The value is read, or a place based on it is read from.
+    Read,
+    /// The static is assigned to, either plainly or with a compound assignment operator.
+    Assign {
+        op: Option<ast::ArithOp>,
+        rhs: ast::Expr,
+        /// The range of the assignment up to the right hand side in the original file.
+        lhs_range: TextRange,
+        /// The end of the assignment in the original file.
+        end: TextSize,
+        parent_kind: Option<SyntaxKind>,
+    },
+    /// A place based on the static is assigned to.
+    Write,
+    /// A place based on the static is borrowed, possibly implicitly by a method call. The borrow
+    /// escapes if the expression it is used in results in a reference.
+    Borrow { mutable: bool, escapes: bool },
+}
+
+impl Usage {
+    fn new(ctx: &AssistContext<'_>, file_id: FileId, path: ast::PathExpr) -> Option<Usage> {
+        let original_range = |node: &SyntaxNode| {
+            let range = ctx.sema.original_range_opt(node)?;
+            (range.file_id == file_id).then_some(range.range)
+        };
+        let range = original_range(path.syntax())?;
+        let statement = ctx.sema.ancestors_with_macros(path.syntax().clone()).find(|node| {
+            ast::Stmt::can_cast(node.kind())
+                || ast::ClosureExpr::can_cast(node.kind())
+                || is_body_tail(node)
+        })?;
+        let statement = original_range(&statement)?;
+        let is_receiver = path.syntax().parent().is_some_and(|parent| {
+            let base = match_ast! {
+                match parent {
+                    ast::MethodCallExpr(it) => it.receiver(),
+                    ast::FieldExpr(it) => it.expr(),
+                    ast::IndexExpr(it) => it.base(),
+                    _ => None,
+                }
+            };
+            base.is_some_and(|it| it.syntax() == path.syntax())
+        });
+
+        // Walk up the place expressions based on the static to find out what is done with it.
+        let mut access = StaticAccess::Read;
+        let mut place = path.syntax().clone();
+        while let Some(parent) = place.parent() {
+            if let Some(bin_expr) = ast::BinExpr::cast(parent.clone()) {
+                let Some(ast::BinaryOp::Assignment { op }) = bin_expr.op_kind() else { break };
+                if bin_expr.lhs()?.syntax() != &place {
+                    break;
+                }
+                if place != *path.syntax() {
+                    access = StaticAccess::Write;
+                    break;
+                }
+                let rhs = bin_expr.rhs()?;
+                let rhs_range = original_range(rhs.syntax())?;
+                let start = original_range(bin_expr.syntax())?.start();
+                access = StaticAccess::Assign {
+                    op,
+                    lhs_range: TextRange::new(start, rhs_range.start()),
+                    end: rhs_range.end(),
+                    parent_kind: bin_expr.syntax().parent().map(|it| it.kind()),
+                    rhs,
+                };
+                break;
+            }
+            if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+                // Passing the reference on is fine as long as nothing borrowed is returned.
+                let escapes = ref_expr
+                    .syntax()
+                    .parent()
+                    .filter(|it| ast::ArgList::can_cast(it.kind()))
+                    .and_then(|it| it.parent())
+                    .and_then(ast::Expr::cast)
+                    .map_or(true, |call| returns_reference(ctx, &call));
+                access = StaticAccess::Borrow { mutable: ref_expr.mut_token().is_some(), escapes };
+                break;
+            }
+            if let Some(method_call) = ast::MethodCallExpr::cast(parent.clone()) {
+                if method_call.receiver()?.syntax() != &place {
+                    break;
+                }
+                let self_param = ctx
+                    .sema
+                    .resolve_method_call(&method_call)
+                    .and_then(|func| func.self_param(ctx.db()));
+                let escapes = returns_reference(ctx, &method_call.clone().into());
+                access = match self_param.map(|it| it.access(ctx.db())) {
+                    Some(Access::Owned) => StaticAccess::Read,
+                    Some(Access::Shared) => StaticAccess::Borrow { mutable: false, escapes },
+                    _ => StaticAccess::Borrow { mutable: true, escapes },
+                };
+                break;
+            }
+            let base = match_ast! {
+                match (parent.clone()) {
+                    ast::FieldExpr(it) => it.expr(),
+                    ast::IndexExpr(it) => it.base(),
+                    _ => None,
+                }
+            };
+            if base.map_or(true, |it| it.syntax() != &place) {
+                break;
+            }
+            place = parent;
+        }
+        Some(Usage { file_id, path, range, statement, access, is_receiver })
+    }
+}
+
+/// Whether the node is the tail expression of a function body.
+fn is_body_tail(node: &SyntaxNode) -> bool {
+    node.parent()
+        .filter(|it| ast::StmtList::can_cast(it.kind()))
+        .and_then(|it| it.parent())
+        .and_then(|it| it.parent())
+        .is_some_and(|it| ast::Fn::can_cast(it.kind()))
+        && ast::Expr::can_cast(node.kind())
+}
+
+/// Whether the value of the expression may hold on to a borrow.
+fn returns_reference(ctx: &AssistContext<'_>, expr: &ast::Expr) -> bool {
+    ctx.sema.type_of_expr(expr).map_or(true, |ty| ty.original.contains_reference(ctx.db()))
+}
+
+fn fetch_method(op: ast::ArithOp) -> Option<&'static str> {
+    let method = match op {
+        ast::ArithOp::Add => "fetch_add",
+        ast::ArithOp::Sub => "fetch_sub",
+        ast::ArithOp::BitAnd => "fetch_and",
+        ast::ArithOp::BitOr => "fetch_or",
+        ast::ArithOp::BitXor => "fetch_xor",
+        _ => return None,
+    };
+    Some(method)
+}
+
+fn convert_declaration(
+    builder: &mut SourceChangeBuilder,
+    wrapper: Wrapper,
+    static_: &ast::Static,
+    mut_token: &SyntaxToken,
+    ty: &ast::Type,
+    body: &ast::Expr,
+) {
+    let (new_ty, new_body) = match wrapper {
+        Wrapper::Atomic(atomic) => (atomic.to_owned(), format!("{atomic}::new({body})")),
+        Wrapper::Mutex => {
+            (format!("LazyLock<Mutex<{ty}>>"), format!("LazyLock::new(|| Mutex::new({body}))"))
+        }
+        Wrapper::RwLock => {
+            (format!("LazyLock<RwLock<{ty}>>"), format!("LazyLock::new(|| RwLock::new({body}))"))
+        }
+        Wrapper::ThreadLocal => (format!("Cell<{ty}>"), format!("const {{ Cell::new({body}) }}")),
+    };
+    let mut_end = mut_token
+        .next_token()
+        .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+        .map_or(mut_token.text_range().end(), |it| it.text_range().end());
+    let edits = [
+        (TextRange::new(mut_token.text_range().start(), mut_end), String::new()),
+        (ty.syntax().text_range(), new_ty),
+        (body.syntax().text_range(), new_body),
+    ];
+
+    let Wrapper::ThreadLocal = wrapper else {
+        for (range, text) in edits {
+            builder.replace(range, text);
+        }
+        return;
+    };
+    let start = static_.syntax().text_range().start();
+    let mut text = static_.syntax().to_string();
+    for (range, new_text) in edits.iter().rev() {
+        text.replace_range(std::ops::Range::<usize>::from(*range - start), new_text);
+    }
+    let indent = IndentLevel::from_node(static_.syntax());
+    let text = text.replace('\n', &format!("\n{}", IndentLevel(1)));
+    builder.replace(
+        static_.syntax().text_range(),
+        format!("thread_local! {{\n{}{text}\n{indent}}}", indent + 1),
+    );
+}
+
+/// Rewrites a use of the static to go through the wrapper. Assignments which turn into an
+/// expression of another type than `()` get a semicolon, their ends are added to `terminated`.
+fn rewrite_usage(
+    builder: &mut SourceChangeBuilder,
+    wrapper: Wrapper,
+    usage: &Usage,
+    terminated: &mut FxHashSet<TextSize>,
+) {
+    let path = usage.path.syntax().text();
+    let (op, rhs, lhs_range, end, parent_kind) = match (wrapper, &usage.access) {
+        (Wrapper::Mutex | Wrapper::RwLock, access) => {
+            let lock = match (wrapper, access) {
+                (Wrapper::Mutex, _) => "lock",
+                (_, StaticAccess::Read | StaticAccess::Borrow { mutable: false, .. }) => "read",
+                _ => "write",
+            };
+            let deref = if usage.is_receiver { "" } else { "*" };
+            builder.replace(usage.range, format!("{deref}{path}.{lock}().unwrap()"));
+            return;
+        }
+        (Wrapper::Atomic(_), StaticAccess::Read) => {
+            builder.replace(usage.range, format!("{path}.load(Ordering::SeqCst)"));
+            return;
+        }
+        (_, StaticAccess::Read) => {
+            builder.replace(usage.range, format!("{path}.get()"));
+            return;
+        }
+        (_, StaticAccess::Assign { op, rhs, lhs_range, end, parent_kind }) => {
+            (*op, rhs, *lhs_range, *end, *parent_kind)
+        }
+        // Not supported by the wrapper, see `Wrapper::supports`.
+        (_, StaticAccess::Write | StaticAccess::Borrow { .. }) => return,
+    };
+
+    let (mut prefix, mut suffix) = match (wrapper, op) {
+        (Wrapper::Atomic(_), None) => (format!("{path}.store("), ", Ordering::SeqCst)".to_owned()),
+        (Wrapper::Atomic(_), Some(op)) => {
+            let Some(method) = fetch_method(op) else { return };
+            (format!("{path}.{method}("), ", Ordering::SeqCst)".to_owned())
+        }
+        (_, None) => (format!("{path}.set("), ")".to_owned()),
+        (_, Some(op)) => {
+            if matches!(rhs, ast::Expr::BinExpr(_) | ast::Expr::RangeExpr(_)) {
+                (format!("{path}.set({path}.get() {op} ("), "))".to_owned())
+            } else {
+                (format!("{path}.set({path}.get() {op} "), ")".to_owned())
+            }
+        }
+    };
+    // `fetch_*` returns the previous value, the assignment was of type `()`.
+    if let (Wrapper::Atomic(_), Some(_)) = (wrapper, op) {
+        match parent_kind {
+            Some(SyntaxKind::EXPR_STMT) => (),
+            Some(SyntaxKind::STMT_LIST) => {
+                suffix.push(';');
+                terminated.insert(end);
+            }
+            _ => {
+                prefix.insert_str(0, "{ ");
+                suffix.push_str("; }");
+            }
+        }
+    }
+    builder.replace(lhs_range, prefix);
+    builder.insert(end, suffix);
+}
+
+/// Removes the `unsafe` blocks around single expressions which don't do anything unsafe other
+/// than accessing the static.
+fn unwrap_unsafe_blocks(
+    builder: &mut SourceChangeBuilder,
+    ctx: &AssistContext<'_>,
+    def: hir::Static,
+    usages: &[&Usage],
+    terminated: &FxHashSet<TextSize>,
+) {
+    let blocks = usages
+        .iter()
+        .filter(|usage| !ctx.sema.hir_file_for(usage.path.syntax()).is_macro())
+        .filter_map(|usage| {
+            usage
+                .path
+                .syntax()
+                .ancestors()
+                .take_while(|it| !ast::Fn::can_cast(it.kind()))
+                .filter_map(ast::BlockExpr::cast)
+                .find(|it| it.unsafe_token().is_some())
+        })
+        .unique_by(|block| block.syntax().text_range());
+
+    for block in blocks {
+        let Some(stmt_list) = block.stmt_list() else { continue };
+        if block.attrs().next().is_some()
+            || stmt_list.syntax().children_with_tokens().any(|it| it.kind() == SyntaxKind::COMMENT)
+            || has_other_unsafe_ops(ctx, &block, def)
+        {
+            continue;
+        }
+        let inner = match (stmt_list.statements().collect_vec().as_slice(), stmt_list.tail_expr()) {
+            ([], Some(tail)) => tail.syntax().clone(),
+            ([stmt @ ast::Stmt::ExprStmt(_)], None) => stmt.syntax().clone(),
+            _ => continue,
+        };
+        let block_range = block.syntax().text_range();
+        let inner_range = inner.text_range();
+        // A block in statement position doesn't need a semicolon, the expression does.
+        let needs_semicolon = ast::Expr::can_cast(inner.kind())
+            && !terminated.contains(&inner_range.end())
+            && block
+                .syntax()
+                .parent()
+                .and_then(ast::ExprStmt::cast)
+                .is_some_and(|stmt| stmt.semicolon_token().is_none());
+        builder.delete(TextRange::new(block_range.start(), inner_range.start()));
+        builder.replace(
+            TextRange::new(inner_range.end(), block_range.end()),
+            if needs_semicolon { ";" } else { "" },
+        );
+    }
+}
+
+/// Whether the block does anything unsafe other than accessing the static.
+fn has_other_unsafe_ops(ctx: &AssistContext<'_>, block: &ast::BlockExpr, def: hir::Static) -> bool {
+    let db = ctx.db();
+    let resolve = |path: Option<ast::Path>| path.and_then(|path| ctx.sema.resolve_path(&path));
+    let type_of = |expr: Option<ast::Expr>| expr.and_then(|expr| ctx.sema.type_of_expr(&expr));
+    block.syntax().descendants().any(|node| {
+        match_ast! {
+            match node {
+                // The expansion isn't looked at.
+                ast::MacroCall(_) => true,
+                ast::CallExpr(it) => {
+                    let callee = match it.expr() {
+                        Some(ast::Expr::PathExpr(callee)) => resolve(callee.path()),
+                        _ => return true,
+                    };
+                    match callee {
+                        Some(PathResolution::Def(ModuleDef::Function(func))) => {
+                            func.is_unsafe_to_call(db)
+                        }
+                        Some(PathResolution::Local(_))
+                        | Some(PathResolution::Def(ModuleDef::Adt(_) | ModuleDef::Variant(_))) => {
+                            false
+                        }
+                        _ => true,
+                    }
+                },
+                ast::MethodCallExpr(it) => ctx
+                    .sema
+                    .resolve_method_call(&it)
+                    .map_or(true, |func| func.is_unsafe_to_call(db)),
+                ast::PrefixExpr(it) => {
+                    matches!(it.op_kind(), Some(ast::UnaryOp::Deref))
+                        && type_of(it.expr()).map_or(true, |ty| ty.original.is_raw_ptr())
+                },
+                ast::FieldExpr(it) => type_of(it.expr()).map_or(true, |ty| {
+                    matches!(ty.original.strip_references().as_adt(), Some(Adt::Union(_)))
+                }),
+                ast::RefExpr(it) => ctx.sema.is_unsafe_ref_expr(&it),
+                ast::PathExpr(it) => match resolve(it.path()) {
+                    Some(PathResolution::Def(ModuleDef::Static(static_))) if static_ != def => {
+                        static_.is_mut(db)
+                            || static_.source(db).map_or(true, |src| {
+                                src.value.syntax().parent().is_some_and(|it| {
+                                    ast::ExternItemList::can_cast(it.kind())
+                                })
+                            })
+                    }
+                    _ => false,
+                },
+                _ => false,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        check_assist_by_label, check_assist_not_applicable, check_assist_not_applicable_by_label,
+    };
+
+    use super::*;
+
+    #[test]
+    fn convert_to_atomic() {
+        check_assist_by_label(
+            convert_static_mut,
+            r#"
+static mut COUNTER$0: usize = 0;
+static mut OTHER: usize = 0;
+
+fn count(n: usize) -> usize {
+    unsafe {
+        COUNTER = 0;
+    }
+    for _ in 0..n {
+        unsafe { COUNTER += 1 }
+    }
+    unsafe {
+        OTHER = COUNTER;
+    }
+    unsafe { COUNTER * 2 }
+}
+"#,
+            r#"
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+static mut OTHER: usize = 0;
+
+fn count(n: usize) -> usize {
+    COUNTER.store(0, Ordering::SeqCst);
+    for _ in 0..n {
+        COUNTER.fetch_add(1, Ordering::SeqCst);
+    }
+    unsafe {
+        OTHER = COUNTER.load(Ordering::SeqCst);
+    }
+    COUNTER.load(Ordering::SeqCst) * 2
+}
+"#,
+            "Convert `static mut` to `AtomicUsize`",
+        );
+    }
+
+    #[test]
+    fn convert_to_mutex() {
+        check_assist_by_label(
+            convert_static_mut,
+            r#"
+struct Registry {
+    names: [&'static str; 4],
+    len: usize,
+}
+
+impl Registry {
+    const fn new() -> Registry {
+        Registry { names: [""; 4], len: 0 }
+    }
+
+    fn add(&mut self, name: &'static str) {
+        self.names[self.len] = name;
+        self.len += 1;
+    }
+}
+
+static mut REGISTRY$0: Registry = Registry::new();
+
+fn add(name: &'static str) {
+    unsafe { REGISTRY.add(name) }
+}
+
+fn first() -> &'static str {
+    unsafe { REGISTRY.names[0] }
+}
+"#,
+            r#"
+use std::sync::{LazyLock, Mutex};
+
+struct Registry {
+    names: [&'static str; 4],
+    len: usize,
+}
+
+impl Registry {
+    const fn new() -> Registry {
+        Registry { names: [""; 4], len: 0 }
+    }
+
+    fn add(&mut self, name: &'static str) {
+        self.names[self.len] = name;
+        self.len += 1;
+    }
+}
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(|| Mutex::new(Registry::new()));
+
+fn add(name: &'static str) {
+    REGISTRY.lock().unwrap().add(name)
+}
+
+fn first() -> &'static str {
+    REGISTRY.lock().unwrap().names[0]
+}
+"#,
+            "Convert `static mut` to `LazyLock<Mutex<_>>`",
+        );
+    }
+
+    #[test]
+    fn convert_to_rw_lock() {
+        check_assist_by_label(
+            convert_static_mut,
+            r#"
+//- minicore: copy
+static mut LIMIT$0: u32 = 10;
+
+fn limit() -> u32 {
+    unsafe { LIMIT }
+}
+
+fn raise(by: u32) {
+    unsafe {
+        LIMIT += by;
+    }
+}
+"#,
+            r#"
+use std::sync::{LazyLock, RwLock};
+
+static LIMIT: LazyLock<RwLock<u32>> = LazyLock::new(|| RwLock::new(10));
+
+fn limit() -> u32 {
+    *LIMIT.read().unwrap()
+}
+
+fn raise(by: u32) {
+    *LIMIT.write().unwrap() += by;
+}
+"#,
+            "Convert `static mut` to `LazyLock<RwLock<_>>`",
+        );
+    }
+
+    #[test]
+    fn convert_to_thread_local() {
+        check_assist_by_label(
+            convert_static_mut,
+            r#"
+//- minicore: copy
+mod state {
+    static mut DEPTH$0: u32 = 0;
+
+    pub fn enter(levels: u32) -> u32 {
+        unsafe {
+            DEPTH += levels - 1;
+        }
+        unsafe { DEPTH }
+    }
+}
+"#,
+            r#"
+mod state {
+    use std::cell::Cell;
+
+    thread_local! {
+        static DEPTH: Cell<u32> = const { Cell::new(0) };
+    }
+
+    pub fn enter(levels: u32) -> u32 {
+        DEPTH.set(DEPTH.get() + (levels - 1));
+        DEPTH.get()
+    }
+}
+"#,
+            "Convert `static mut` to a per-thread `thread_local!` `Cell`",
+        );
+    }
+
+    #[test]
+    fn keeps_unsafe_blocks_needed_otherwise() {
+        check_assist_by_label(
+            convert_static_mut,
+            r#"
+static mut FLAG$0: bool = false;
+
+unsafe fn danger() {}
+
+fn set() {
+    unsafe {
+        danger();
+        FLAG = true;
+    }
+}
+"#,
+            r#"
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static FLAG: AtomicBool = AtomicBool::new(false);
+
+unsafe fn danger() {}
+
+fn set() {
+    unsafe {
+        danger();
+        FLAG.store(true, Ordering::SeqCst);
+    }
+}
+"#,
+            "Convert `static mut` to `AtomicBool`",
+        );
+    }
+
+    #[test]
+    fn atomic_not_applicable_to_borrows() {
+        check_assist_not_applicable_by_label(
+            convert_static_mut,
+            r#"
+static mut COUNTER$0: u64 = 0;
+
+fn get() -> &'static u64 {
+    unsafe { &COUNTER }
+}
+"#,
+            "Convert `static mut` to `AtomicU64`",
+        );
+    }
+
+    #[test]
+    fn locks_not_applicable_to_usages_in_one_statement() {
+        cov_mark::check!(convert_static_mut_overlapping_locks);
+        check_assist_not_applicable_by_label(
+            convert_static_mut,
+            r#"
+static mut COUNTER$0: u32 = 0;
+
+fn increment() {
+    unsafe {
+        COUNTER = COUNTER + 1;
+    }
+}
+"#,
+            "Convert `static mut` to `LazyLock<Mutex<_>>`",
+        );
+    }
+
+    #[test]
+    fn locks_applicable_to_usages_in_separate_statements() {
+        check_assist_by_label(
+            convert_static_mut,
+            r#"
+static mut COUNTER$0: u32 = 0;
+
+fn increment() -> u32 {
+    unsafe {
+        COUNTER += 1;
+    }
+    unsafe { COUNTER }
+}
+"#,
+            r#"
+use std::sync::{LazyLock, Mutex};
+
+static COUNTER: LazyLock<Mutex<u32>> = LazyLock::new(|| Mutex::new(0));
+
+fn increment() -> u32 {
+    *COUNTER.lock().unwrap() += 1;
+    *COUNTER.lock().unwrap()
+}
+"#,
+            "Convert `static mut` to `LazyLock<Mutex<_>>`",
+        );
+    }
+
+    #[test]
+    fn locks_not_applicable_to_escaping_borrows() {
+        check_assist_not_applicable(
+            convert_static_mut,
+            r#"
+struct Registry {
+    len: usize,
+}
+
+static mut REGISTRY$0: Registry = Registry { len: 0 };
+
+fn registry() -> &'static Registry {
+    unsafe { &REGISTRY }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_immutable_statics() {
+        check_assist_not_applicable(
+            convert_static_mut,
+            r#"
+static COUNTER$0: u64 = 0;
+"#,
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::iter::once;
 
+use either::Either;
 use ide_db::{
     syntax_helpers::node_ext::{is_pattern_cond, single_let},
     ty_filter::TryEnum,
@@ -8,10 +9,11 @@
     ast::{
         self,
         edit::{AstNodeEdit, IndentLevel},
-        make,
+        make, HasLoopBody,
     },
-    ted, AstNode,
-    SyntaxKind::{FN, FOR_EXPR, LOOP_EXPR, WHILE_EXPR, WHITESPACE},
+    ted::{self, Position},
+    AstNode,
+    SyntaxKind::{FN, FOR_EXPR, LOOP_EXPR, MATCH_ARM, WHILE_EXPR, WHITESPACE},
     T,
 };
 
@@ -23,7 +25,8 @@
 
 // Assist: convert_to_guarded_return
 //
-// Replace a large conditional with a guarded return.
+// Replace a large conditional with a guarded return. The body of a `while` loop is turned into a
+// `loop` starting with a guarded break instead.
 //
 // ```
 // fn main() {
@@ -46,8 +49,11 @@
 pub(crate) fn convert_to_guarded_return(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
     if let Some(let_stmt) = ctx.find_node_at_offset() {
         let_stmt_to_guarded_return(let_stmt, acc, ctx)
-    } else if let Some(if_expr) = ctx.find_node_at_offset() {
-        if_expr_to_guarded_return(if_expr, acc, ctx)
+    } else if let Some(expr) = ctx.find_node_at_offset::<Either<ast::IfExpr, ast::WhileExpr>>() {
+        match expr {
+            Either::Left(if_expr) => if_expr_to_guarded_return(if_expr, acc, ctx),
+            Either::Right(while_expr) => while_expr_to_guarded_break(while_expr, acc, ctx),
+        }
     } else {
         None
     }
@@ -104,13 +110,7 @@ fn if_expr_to_guarded_return(
         return None;
     }
 
-    let parent_container = parent_block.syntax().parent()?;
-
-    let early_expression: ast::Expr = match parent_container.kind() {
-        WHILE_EXPR | LOOP_EXPR | FOR_EXPR => make::expr_continue(None),
-        FN => make::expr_return(None),
-        _ => return None,
-    };
+    let early_expression = early_expression(&parent_block)?;
 
     then_block.syntax().first_child_or_token().map(|t| t.kind() == T!['{'])?;
 
@@ -196,16 +196,10 @@ fn let_stmt_to_guarded_return(
     let happy_pattern = try_enum.happy_pattern(pat);
     let target = let_stmt.syntax().text_range();
 
-    let early_expression: ast::Expr = {
+    let early_expression = {
         let parent_block =
             let_stmt.syntax().parent()?.ancestors().find_map(ast::BlockExpr::cast)?;
-        let parent_container = parent_block.syntax().parent()?;
-
-        match parent_container.kind() {
-            WHILE_EXPR | LOOP_EXPR | FOR_EXPR => make::expr_continue(None),
-            FN => make::expr_return(None),
-            _ => return None,
-        }
+        early_expression(&parent_block)?
     };
 
     acc.add(
@@ -232,6 +226,101 @@ fn let_stmt_to_guarded_return(
     )
 }
 
+fn while_expr_to_guarded_break(
+    while_expr: ast::WhileExpr,
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let cond = while_expr.condition()?;
+
+    let while_token_range = while_expr.while_token()?.text_range();
+    let while_cond_range = cond.syntax().text_range();
+    let cursor_in_range =
+        while_token_range.cover(while_cond_range).contains_range(ctx.selection_trimmed());
+    if !cursor_in_range {
+        return None;
+    }
+
+    let (while_let_pat, cond_expr) = if is_pattern_cond(cond.clone()) {
+        let let_ = single_let(cond.clone())?;
+        (Some(let_.pat()?), let_.expr()?)
+    } else {
+        (None, cond.clone())
+    };
+    let body = while_expr.loop_body()?.stmt_list()?;
+
+    let target = while_expr.syntax().text_range();
+    acc.add(
+        AssistId("convert_to_guarded_return", AssistKind::RefactorRewrite),
+        "Convert to guarded break",
+        target,
+        |edit| {
+            let while_expr = edit.make_mut(while_expr);
+            let cond = edit.make_mut(cond);
+            let body = edit.make_mut(body);
+            let body_indent_level = IndentLevel::from_node(while_expr.syntax()) + 1;
+
+            let early_expression = make::expr_break(None, None);
+            let guard = match while_let_pat {
+                None => {
+                    let then_branch =
+                        make::block_expr(once(make::expr_stmt(early_expression).into()), None);
+                    let cond = invert_boolean_expression(cond_expr);
+                    let if_expr = make::expr_if(cond, then_branch, None).indent(body_indent_level);
+                    if_expr.syntax().clone_for_update()
+                }
+                Some(pat) => {
+                    let let_else_stmt = make::let_else_stmt(
+                        pat,
+                        None,
+                        cond_expr,
+                        make::tail_only_block_expr(early_expression),
+                    );
+                    let_else_stmt.indent(body_indent_level).syntax().clone_for_update()
+                }
+            };
+            if let Some(l_curly) = body.l_curly_token() {
+                let ws = make::tokens::whitespace(&format!("\n{body_indent_level}"));
+                ted::insert_all_raw(Position::after(l_curly), vec![ws.into(), guard.into()]);
+            }
+
+            if let Some(ws) =
+                cond.syntax().next_sibling_or_token().filter(|it| it.kind() == WHITESPACE)
+            {
+                ted::remove(ws);
+            }
+            ted::remove(cond.syntax());
+            let loop_expr = make::expr_loop(make::block_expr(None, None)).clone_for_update();
+            if let (Some(while_token), Some(loop_token)) =
+                (while_expr.while_token(), loop_expr.syntax().first_token())
+            {
+                ted::replace(while_token, loop_token);
+            }
+        },
+    )
+}
+
+/// Returns the expression leaving `block` early: `continue` for the body of a loop and `return`
+/// for the body of a function. The block of a match arm is left like the block the match is the
+/// tail of.
+fn early_expression(block: &ast::BlockExpr) -> Option<ast::Expr> {
+    let parent_container = block.syntax().parent()?;
+    match parent_container.kind() {
+        WHILE_EXPR | LOOP_EXPR | FOR_EXPR => Some(make::expr_continue(None)),
+        FN => Some(make::expr_return(None)),
+        MATCH_ARM => {
+            let match_expr = parent_container.ancestors().find_map(ast::MatchExpr::cast)?;
+            let outer_block =
+                match_expr.syntax().parent()?.ancestors().find_map(ast::BlockExpr::cast)?;
+            if outer_block.tail_expr()? != match_expr.into() {
+                return None;
+            }
+            early_expression(&outer_block)
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_not_applicable};
@@ -762,6 +851,108 @@ fn foo() -> Option<i32> {
 fn main() {
     let x = foo()$0;
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_while_let_to_guarded_break() {
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+fn main() {
+    let mut it = [1, 2].into_iter();
+    wh$0ile let Some(x) = it.next() {
+        foo(x);
+        bar();
+    }
+}
+"#,
+            r#"
+fn main() {
+    let mut it = [1, 2].into_iter();
+    loop {
+        let Some(x) = it.next() else { break };
+        foo(x);
+        bar();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_while_to_guarded_break() {
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+fn main() {
+    'outer: while$0 cond() {
+        foo();
+    }
+}
+"#,
+            r#"
+fn main() {
+    'outer: loop {
+        if !cond() {
+            break;
+        }
+        foo();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_inside_match_arm() {
+        check_assist(
+            convert_to_guarded_return,
+            r#"
+fn main(x: Option<i32>) {
+    match x {
+        Some(n) => {
+            i$0f n > 0 {
+                foo(n);
+            }
+        }
+        None => (),
+    }
+}
+"#,
+            r#"
+fn main(x: Option<i32>) {
+    match x {
+        Some(n) => {
+            if n <= 0 {
+                return;
+            }
+            foo(n);
+        }
+        None => (),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignore_inside_match_arm_of_non_tail_match() {
+        check_assist_not_applicable(
+            convert_to_guarded_return,
+            r#"
+fn main(x: Option<i32>) {
+    match x {
+        Some(n) => {
+            i$0f n > 0 {
+                foo(n);
+            }
+        }
+        None => (),
+    }
+    bar();
+}
 "#,
         );
     }
@@ -1,8 +1,11 @@
 use either::Either;
-use ide_db::defs::{Definition, NameRefClass};
+use ide_db::{
+    defs::{Definition, NameRefClass},
+    source_change::ChangeAnnotation,
+};
 use syntax::{
     ast::{self, AstNode, HasGenericParams, HasVisibility},
-    match_ast, SyntaxNode,
+    match_ast, SyntaxNode, TextRange,
 };
 
 use crate::{assist_context::SourceChangeBuilder, AssistContext, AssistId, AssistKind, Assists};
@@ -10,6 +13,8 @@
 // Assist: convert_tuple_struct_to_named_struct
 //
 // Converts tuple struct to struct with named fields, and analogously for tuple enum variants.
+// Usages inside macro calls are converted too, with their edits annotated for confirmation;
+// usages produced by the macro definition itself are left alone.
 //
 // ```
 // struct Point$0(f32, f32);
@@ -134,8 +139,9 @@ fn edit_struct_references(
         match_ast! {
             match node {
                 ast::TupleStructPat(tuple_struct_pat) => {
+                    let Some(range) = edit_range(ctx, edit, &node) else { return Some(()) };
                     edit.replace(
-                        tuple_struct_pat.syntax().text_range(),
+                        range,
                         ast::make::record_pat_with_fields(
                             tuple_struct_pat.path()?,
                             ast::make::record_pat_field_list(tuple_struct_pat.fields().zip(names).map(
@@ -164,9 +170,10 @@ fn edit_struct_references(
                     }
 
                     let arg_list = call_expr.syntax().descendants().find_map(ast::ArgList::cast)?;
+                    let Some(range) = edit_range(ctx, edit, &node) else { return Some(()) };
 
                     edit.replace(
-                        ctx.sema.original_range(&node).range,
+                        range,
                         ast::make::record_expr(
                             path,
                             ast::make::record_expr_field_list(arg_list.args().zip(names).map(
@@ -216,13 +223,39 @@ fn edit_field_references(
             edit.edit_file(file_id);
             for r in refs {
                 if let Some(name_ref) = r.name.as_name_ref() {
-                    edit.replace(ctx.sema.original_range(name_ref.syntax()).range, name.text());
+                    if let Some(range) = edit_range(ctx, edit, name_ref.syntax()) {
+                        edit.replace(range, name.text());
+                    }
                 }
             }
         }
     }
 }
 
+/// The range to replace for a usage, which is only known if the whole node comes from the input of
+/// any macro calls it's in. Edits inside macro calls are annotated, as they may change the
+/// expansion in ways the macro doesn't expect.
+fn edit_range(
+    ctx: &AssistContext<'_>,
+    edit: &mut SourceChangeBuilder,
+    node: &SyntaxNode,
+) -> Option<TextRange> {
+    let Some(range) = ctx.sema.original_range_opt(node) else {
+        cov_mark::hit!(skip_usage_generated_by_macro);
+        return None;
+    };
+    if ctx.sema.hir_file_for(node).is_macro() {
+        edit.annotate(
+            range.range,
+            ChangeAnnotation {
+                label: "Edit inside a macro call".to_owned(),
+                needs_confirmation: true,
+            },
+        );
+    }
+    Some(range.range)
+}
+
 fn generate_names(fields: impl Iterator<Item = ast::TupleField>) -> Vec<ast::Name> {
     fields
         .enumerate()
@@ -262,6 +295,51 @@ macro_rules! foo {($i:expr) => {$i} }
 struct T { field1: u8 }
 fn test() {
     foo!(T { field1: 1 });
+}
+// Edit inside a macro call: "T(1)"
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_field_access_in_macro_args() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+macro_rules! id {($i:expr) => {$i} }
+struct T$0(u8);
+fn test(t: T) -> u8 {
+    id!(t.0)
+}"#,
+            r#"
+macro_rules! id {($i:expr) => {$i} }
+struct T { field1: u8 }
+fn test(t: T) -> u8 {
+    id!(t.field1)
+}
+// Edit inside a macro call: "0"
+"#,
+        );
+    }
+
+    #[test]
+    fn skip_usage_generated_by_macro() {
+        cov_mark::check!(skip_usage_generated_by_macro);
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+macro_rules! call {($f:path, $e:expr) => { $f($e) } }
+struct T$0(u8);
+fn test() {
+    call!(T, 1);
+    T(2);
+}"#,
+            r#"
+macro_rules! call {($f:path, $e:expr) => { $f($e) } }
+struct T { field1: u8 }
+fn test() {
+    call!(T, 1);
+    T { field1: 2 };
 }"#,
         );
     }
@@ -589,7 +667,9 @@ enum T {
 }
 fn test() {
     foo!(T::V { field1: 1 });
-}"#,
+}
+// Edit inside a macro call: "T::V(1)"
+"#,
         );
     }
 
@@ -3,7 +3,7 @@
 use ast::make;
 use either::Either;
 use hir::{
-    DescendPreference, HasSource, HirDisplay, InFile, Local, LocalSource, ModuleDef,
+    DescendPreference, HasSource, HirDisplay, InFile, Local, LocalSource, ModuleDef, ModuleSource,
     PathResolution, Semantics, TypeInfo, TypeParam,
 };
 use ide_db::{
@@ -11,7 +11,8 @@
     famous_defs::FamousDefs,
     helpers::mod_path_to_ast,
     imports::insert_use::{insert_use, ImportScope},
-    search::{FileReference, ReferenceCategory, SearchScope},
+    path_transform::PathTransform,
+    search::{FileReference, ReferenceCategory},
     source_change::SourceChangeBuilder,
     syntax_helpers::node_ext::{
         for_each_tail_expr, preorder_expr, walk_expr, walk_pat, walk_patterns_in_expr,
@@ -19,9 +20,10 @@
     FxIndexSet, RootDatabase,
 };
 use syntax::{
+    algo::find_node_at_range,
     ast::{
         self, edit::IndentLevel, edit_in_place::Indent, AstNode, AstToken, HasGenericParams,
-        HasName,
+        HasModuleItem, HasName,
     },
     match_ast, ted, SyntaxElement,
     SyntaxKind::{self, COMMENT},
@@ -34,6 +36,8 @@
     AssistId,
 };
 
+use super::extract_module::{configured_visibility, resolve_module_path};
+
 // Assist: extract_function
 //
 // Extracts selected statements and comments into new function.
@@ -62,6 +66,18 @@
 // }
 // ```
 pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    extract(acc, ctx, false)
+}
+
+/// Extracts the selected statements into a free function of a new module, for `extract_module`.
+pub(crate) fn extract_function_into_module(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    extract(acc, ctx, true)
+}
+
+fn extract(acc: &mut Assists, ctx: &AssistContext<'_>, into_module: bool) -> Option<()> {
     let range = ctx.selection_trimmed();
     if range.is_empty() {
         return None;
@@ -84,9 +100,16 @@ pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
     };
 
     let body = extraction_target(&node, range)?;
+    if into_module && !matches!(body, FunctionBody::Span { .. }) {
+        return None;
+    }
     let (container_info, contains_tail_expr) = body.analyze_container(&ctx.sema)?;
 
     let (locals_used, self_param) = body.analyze(&ctx.sema);
+    // Methods can't be moved out of their impl.
+    if into_module && self_param.is_some() {
+        return None;
+    }
 
     let anchor = if self_param.is_some() { Anchor::Method } else { Anchor::Freestanding };
     let insert_after = node_to_insert_after(&body, anchor)?;
@@ -101,9 +124,14 @@ pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
 
     let scope = ImportScope::find_insert_use_container(&node, &ctx.sema)?;
 
+    let (assist_id, label) = match into_module {
+        true => ("extract_module", "Extract into function in new module"),
+        false => ("extract_function", "Extract into function"),
+    };
+    let into_existing_module = !into_module && self_param.is_none();
     acc.add(
-        AssistId("extract_function", crate::AssistKind::RefactorExtract),
-        "Extract into function",
+        AssistId(assist_id, crate::AssistKind::RefactorExtract),
+        label,
         target_range,
         move |builder| {
             let outliving_locals: Vec<_> = ret_values.collect();
@@ -115,7 +143,8 @@ pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
             let params =
                 body.extracted_function_params(ctx, &container_info, locals_used.iter().copied());
 
-            let name = make_function_name(&semantics_scope);
+            let name = make::name_ref(&unique_name(&semantics_scope, "fun_name"));
+            let module_name = into_module.then(|| unique_name(&semantics_scope, "modname"));
 
             let fun = Function {
                 name,
@@ -127,6 +156,10 @@ pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
                 outliving_locals,
                 contains_tail_expr,
                 mods: container_info,
+                visibility: module_name
+                    .as_ref()
+                    .map(|_| configured_visibility(ctx, make::visibility_pub_super())),
+                module_name,
             };
 
             let new_indent = IndentLevel::from_node(&insert_after);
@@ -136,34 +169,7 @@ pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
 
             let call_expr = make_call(ctx, &fun, old_indent);
 
-            // Map the element range to replace into the mutable version
-            let elements = match &fun.body {
-                FunctionBody::Expr(expr) => {
-                    // expr itself becomes the replacement target
-                    let expr = &builder.make_mut(expr.clone());
-                    let node = SyntaxElement::Node(expr.syntax().clone());
-
-                    node.clone()..=node
-                }
-                FunctionBody::Span { parent, elements, .. } => {
-                    // Map the element range into the mutable versions
-                    let parent = builder.make_mut(parent.clone());
-
-                    let start = parent
-                        .syntax()
-                        .children_with_tokens()
-                        .nth(elements.start().index())
-                        .expect("should be able to find mutable start element");
-
-                    let end = parent
-                        .syntax()
-                        .children_with_tokens()
-                        .nth(elements.end().index())
-                        .expect("should be able to find mutable end element");
-
-                    start..=end
-                }
-            };
+            let elements = mutable_elements(builder, &fun.body);
 
             let has_impl_wrapper =
                 insert_after.ancestors().any(|a| a.kind() == SyntaxKind::IMPL && a != insert_after);
@@ -176,8 +182,27 @@ pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
                 }
             }
 
-            let fn_def = match fun.self_param_adt(ctx) {
-                Some(adt) if anchor == Anchor::Method && !has_impl_wrapper => {
+            let fn_def = match (&fun.module_name, fun.self_param_adt(ctx)) {
+                (Some(module_name), _) => {
+                    fn_def.indent(new_indent + 1);
+                    let module_def = make_module(module_name, fn_def, new_indent);
+                    if let Some(cap) = ctx.config.snippet_cap {
+                        // Renaming the module renames the qualifier of the call along with it.
+                        let qualifier = call_expr
+                            .descendants()
+                            .filter_map(ast::Path::cast)
+                            .filter_map(|path| path.qualifier()?.segment()?.name_ref())
+                            .find(|name_ref| name_ref.text() == module_name.as_str());
+                        if let (Some(name), Some(qualifier)) = (module_def.name(), qualifier) {
+                            builder.add_placeholder_snippet_group(
+                                cap,
+                                vec![name.syntax().clone(), qualifier.syntax().clone()],
+                            );
+                        }
+                    }
+                    module_def.syntax().clone()
+                }
+                (None, Some(adt)) if anchor == Anchor::Method && !has_impl_wrapper => {
                     fn_def.indent(1.into());
 
                     let impl_ = generate_impl(&adt);
@@ -233,24 +258,242 @@ pub(crate) fn extract_function(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
                 vec![make::tokens::whitespace(&format!("\n\n{new_indent}")).into(), fn_def.into()],
             );
         },
-    )
+    );
+    if into_existing_module {
+        acc.add_with_parameter(
+            AssistId("extract_function", crate::AssistKind::RefactorExtract),
+            "Extract into function in existing module",
+            "Path of the module to put the function into, e.g. `crate::util`",
+            target_range,
+            |builder, path| extract_into_existing_module(ctx, builder, &node, range, path),
+        );
+    }
+    Some(())
+}
+
+/// Extracts the selection into a new function of the existing module at `target_path`, which is
+/// called through the path to that module.
+fn extract_into_existing_module(
+    ctx: &AssistContext<'_>,
+    builder: &mut SourceChangeBuilder,
+    node: &SyntaxNode,
+    range: TextRange,
+    target_path: &str,
+) -> Option<()> {
+    let db = ctx.db();
+    let body = extraction_target(node, range)?;
+    let (container_info, contains_tail_expr) = body.analyze_container(&ctx.sema)?;
+    let (locals_used, self_param) = body.analyze(&ctx.sema);
+    if self_param.is_some() {
+        return None;
+    }
+    let source_scope = ctx.sema.scope(body.node())?;
+    let current = source_scope.module();
+    let destination = resolve_module_path(db, current.krate().root_module(), target_path)?;
+    if destination == current {
+        return None;
+    }
+
+    let control_flow = body.external_control_flow(ctx, &container_info)?;
+    // The `ControlFlow` returned for these would need to be imported in both modules.
+    if control_flow
+        .kind
+        .as_ref()
+        .is_some_and(|kind| matches!(kind, FlowKind::Break(_, _) | FlowKind::Continue(_)))
+    {
+        return None;
+    }
+    let ret_ty = body.return_ty(ctx)?;
+    let outliving_locals: Vec<_> =
+        body.ret_values(ctx, node.parent().as_ref().unwrap_or(node)).collect();
+    if !outliving_locals.is_empty() && !ret_ty.is_unit() {
+        return None;
+    }
+
+    let source = destination.definition_source(db);
+    let target_file_id = source.file_id.file_id()?;
+    let target_file = ctx.sema.parse(target_file_id);
+    let target = match source.value {
+        ModuleSource::SourceFile(_) => Either::Right(target_file),
+        ModuleSource::Module(it) => Either::Left(
+            find_node_at_range::<ast::Module>(target_file.syntax(), it.syntax().text_range())?
+                .item_list()?,
+        ),
+        ModuleSource::BlockExpr(_) => return None,
+    };
+    let target_scope = ctx.sema.scope(target.as_ref().either(AstNode::syntax, AstNode::syntax))?;
+
+    let module_path = current.find_use_path(
+        ctx.sema.db,
+        ModuleDef::Module(destination),
+        ctx.config.prefer_no_std,
+        ctx.config.prefer_prelude,
+    )?;
+    // Items of an ancestor are visible to all of its descendants, the function needs to be
+    // visible in the closest common ancestor otherwise.
+    let ancestors = current.path_to_root(db);
+    let visibility = match ancestors.contains(&destination) {
+        true => None,
+        false => {
+            let required = match destination.parent(db).is_some_and(|it| ancestors.contains(&it)) {
+                true => make::visibility_pub_super(),
+                false => make::visibility_pub_crate(),
+            };
+            Some(configured_visibility(ctx, required))
+        }
+    };
+    let params = body.extracted_function_params(ctx, &container_info, locals_used.iter().copied());
+    let fun = Function {
+        name: make::name_ref(&unique_name(&target_scope, "fun_name")),
+        self_param: None,
+        params,
+        control_flow,
+        ret_ty,
+        body,
+        outliving_locals,
+        contains_tail_expr,
+        mods: container_info,
+        visibility,
+        module_name: Some(mod_path_to_ast(&module_path).to_string()),
+    };
+
+    let old_indent = fun.body.indent_level();
+    let call_expr = make_call(ctx, &fun, old_indent);
+    let fn_def = format_function(ctx, destination, &fun, old_indent).clone_for_update();
+    if let Some(body) = fn_def.body() {
+        PathTransform::generic_transformation(&target_scope, &source_scope).apply(body.syntax());
+    }
+
+    let elements = mutable_elements(builder, &fun.body);
+    fixup_call_site(builder, &fun.body);
+    ted::replace_all(elements, vec![call_expr.into()]);
+
+    if target_file_id != ctx.file_id() {
+        builder.edit_file(target_file_id);
+    }
+    match target {
+        Either::Left(item_list) => {
+            let item_list = builder.make_mut(item_list);
+            let outer_indent = IndentLevel::from_node(&item_list.syntax().parent()?);
+            let indent = outer_indent + 1;
+            fn_def.indent(indent);
+            match item_list.items().last() {
+                Some(last_item) => ted::insert_all_raw(
+                    ted::Position::after(last_item.syntax()),
+                    vec![
+                        make::tokens::whitespace(&format!("\n\n{indent}")).into(),
+                        fn_def.syntax().clone().into(),
+                    ],
+                ),
+                None => {
+                    // Put the function on its own line between the braces.
+                    let whitespace: Vec<_> = item_list
+                        .syntax()
+                        .children_with_tokens()
+                        .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+                        .collect();
+                    whitespace.into_iter().for_each(ted::remove);
+                    ted::insert_all_raw(
+                        ted::Position::after(item_list.l_curly_token()?),
+                        vec![
+                            make::tokens::whitespace(&format!("\n{indent}")).into(),
+                            fn_def.syntax().clone().into(),
+                            make::tokens::whitespace(&format!("\n{outer_indent}")).into(),
+                        ],
+                    );
+                }
+            }
+        }
+        Either::Right(file) => {
+            let file = builder.make_mut(file);
+            match file.items().last() {
+                Some(last_item) => ted::insert_all_raw(
+                    ted::Position::after(last_item.syntax()),
+                    vec![make::tokens::whitespace("\n\n").into(), fn_def.syntax().clone().into()],
+                ),
+                None => ted::insert(
+                    ted::Position::first_child_of(file.syntax()),
+                    fn_def.syntax().clone(),
+                ),
+            }
+        }
+    }
+    if let Some(cap) = ctx.config.snippet_cap {
+        if let Some(name) = fn_def.name() {
+            builder.add_tabstop_before(cap, name);
+        }
+    }
+    Some(())
+}
+
+/// Maps the elements of `body` that get replaced by the call into their mutable versions.
+fn mutable_elements(
+    builder: &mut SourceChangeBuilder,
+    body: &FunctionBody,
+) -> RangeInclusive<SyntaxElement> {
+    match body {
+        FunctionBody::Expr(expr) => {
+            // expr itself becomes the replacement target
+            let expr = &builder.make_mut(expr.clone());
+            let node = SyntaxElement::Node(expr.syntax().clone());
+
+            node.clone()..=node
+        }
+        FunctionBody::Span { parent, elements, .. } => {
+            // Map the element range into the mutable versions
+            let parent = builder.make_mut(parent.clone());
+
+            let start = parent
+                .syntax()
+                .children_with_tokens()
+                .nth(elements.start().index())
+                .expect("should be able to find mutable start element");
+
+            let end = parent
+                .syntax()
+                .children_with_tokens()
+                .nth(elements.end().index())
+                .expect("should be able to find mutable end element");
+
+            start..=end
+        }
+    }
 }
 
-fn make_function_name(semantics_scope: &hir::SemanticsScope<'_>) -> ast::NameRef {
+pub(crate) fn unique_name(semantics_scope: &hir::SemanticsScope<'_>, default_name: &str) -> String {
     let mut names_in_scope = vec![];
     semantics_scope.process_all_names(&mut |name, _| {
         names_in_scope.push(name.display(semantics_scope.db.upcast()).to_string())
     });
 
-    let default_name = "fun_name";
-
     let mut name = default_name.to_owned();
     let mut counter = 0;
     while names_in_scope.contains(&name) {
         counter += 1;
         name = format!("{default_name}{counter}")
     }
-    make::name_ref(&name)
+    name
+}
+
+/// Wraps the extracted function into a module, which sees everything the function did through a
+/// glob import of its parent.
+fn make_module(name: &str, fn_def: ast::Fn, indent: IndentLevel) -> ast::Module {
+    let module = make::mod_(make::name(name), Some(make::item_list())).clone_for_update();
+    let glob = make::use_(None, make::use_tree(make::ext::ident_path("super"), None, None, true))
+        .clone_for_update();
+    if let Some(l_curly) = module.item_list().and_then(|it| it.l_curly_token()) {
+        ted::insert_all(
+            ted::Position::after(l_curly),
+            vec![
+                make::tokens::whitespace(&format!("\n{}", indent + 1)).into(),
+                glob.syntax().clone().into(),
+                make::tokens::whitespace(&format!("\n\n{}", indent + 1)).into(),
+                fn_def.syntax().clone().into(),
+                make::tokens::whitespace(&format!("\n{indent}")).into(),
+            ],
+        );
+    }
+    module
 }
 
 /// Try to guess what user wants to extract
@@ -314,6 +557,10 @@ struct Function {
     /// Whether at least one of the container's tail expr is contained in the range we're extracting.
     contains_tail_expr: bool,
     mods: ContainerInfo,
+    /// The visibility of the new function, needed if it's put into another module.
+    visibility: Option<ast::Visibility>,
+    /// The module the function is put into, if any. It's called through the module then.
+    module_name: Option<String>,
 }
 
 #[derive(Debug)]
@@ -436,12 +683,7 @@ struct OutlivedLocal {
 
 impl LocalUsages {
     fn find_local_usages(ctx: &AssistContext<'_>, var: Local) -> Self {
-        Self(
-            Definition::Local(var)
-                .usages(&ctx.sema)
-                .in_scope(&SearchScope::single_file(ctx.file_id()))
-                .all(),
-        )
+        Self(ctx.usages_in_file(Definition::Local(var), ctx.file_id()))
     }
 
     fn iter(&self) -> impl Iterator<Item = &FileReference> + '_ {
@@ -465,6 +707,15 @@ fn return_type(&self, ctx: &AssistContext<'_>) -> FunType {
         }
     }
 
+    /// The self type of the impl the body is extracted from, if the function ends up outside of
+    /// that impl.
+    fn self_ty_outside_of_impl(&self) -> Option<ast::Type> {
+        if self.self_param.is_some() && self.module_name.is_none() {
+            return None;
+        }
+        self.body.node().ancestors().find_map(ast::Impl::cast)?.self_ty()
+    }
+
     fn self_param_adt(&self, ctx: &AssistContext<'_>) -> Option<ast::Adt> {
         let self_param = self.self_param.as_ref()?;
         let def = ctx.sema.to_def(self_param)?;
@@ -965,6 +1216,14 @@ fn external_control_flow(
         let mut continue_expr = None;
         let mut is_async = false;
         let mut _is_unsafe = false;
+        // The awaits of a selected `async` block or closure belong to the future it creates.
+        let creates_future = match self {
+            FunctionBody::Expr(ast::Expr::BlockExpr(it)) => {
+                matches!(it.modifier(), Some(ast::BlockModifier::Async(_)))
+            }
+            FunctionBody::Expr(ast::Expr::ClosureExpr(_)) => true,
+            _ => false,
+        };
 
         let mut unsafe_depth = 0;
         let mut loop_depth = 0;
@@ -1004,7 +1263,13 @@ fn external_control_flow(
                 ast::Expr::ContinueExpr(it) if loop_depth == 0 => {
                     continue_expr = Some(it);
                 }
-                ast::Expr::AwaitExpr(_) => is_async = true,
+                ast::Expr::AwaitExpr(_) if !creates_future => is_async = true,
+                ast::Expr::MacroExpr(it)
+                    if !creates_future
+                        && it.macro_call().is_some_and(|it| awaits_in_macro(&it)) =>
+                {
+                    is_async = true
+                }
                 // FIXME: Do unsafe analysis on expression, sem highlighting knows this so we should be able
                 // to just lift that out of there
                 // expr if unsafe_depth ==0 && expr.is_unsafe => is_unsafe = true,
@@ -1131,6 +1396,46 @@ fn generic_parents(parent: &SyntaxNode) -> Vec<GenericParent> {
     list
 }
 
+/// checks if the input of `macro_call` awaits a future, like `assert_eq!(fut.await, 1)` does
+///
+/// Macro inputs are plain token trees, so their awaits are not seen when walking the expressions
+/// of the body. Awaits inside `async` blocks of the input are not awaited by the caller.
+fn awaits_in_macro(macro_call: &ast::MacroCall) -> bool {
+    let Some(input) = macro_call.token_tree() else { return false };
+    input
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.text() == "await")
+        .filter(|it| prev_non_trivia_token(it).is_some_and(|prev| prev.kind() == T![.]))
+        .any(|await_| {
+            !await_
+                .parent_ancestors()
+                .take_while(|it| it != input.syntax())
+                .filter_map(ast::TokenTree::cast)
+                .any(|tt| is_async_block_input(&tt))
+        })
+}
+
+/// checks if the token tree `tt` is the block of an `async` or `async move` block
+fn is_async_block_input(tt: &ast::TokenTree) -> bool {
+    if tt.l_curly_token().is_none() {
+        return false;
+    }
+    let mut prev =
+        iter::successors(tt.syntax().prev_sibling_or_token(), |it| it.prev_sibling_or_token())
+            .filter(|it| !it.kind().is_trivia());
+    match prev.next() {
+        Some(it) if it.kind() == T![move] => prev.next().is_some_and(|it| it.kind() == T![async]),
+        Some(it) => it.kind() == T![async],
+        None => false,
+    }
+}
+
+fn prev_non_trivia_token(token: &SyntaxToken) -> Option<SyntaxToken> {
+    iter::successors(token.prev_token(), |it| it.prev_token()).find(|it| !it.kind().is_trivia())
+}
+
 /// checks if relevant var is used with `&mut` access inside body
 fn has_exclusive_usages(
     ctx: &AssistContext<'_>,
@@ -1170,7 +1475,10 @@ fn reference_is_exclusive(
 }
 
 /// checks if this expr requires `&mut` access, recurses on field access
-fn expr_require_exclusive_access(ctx: &AssistContext<'_>, expr: &ast::Expr) -> Option<bool> {
+pub(super) fn expr_require_exclusive_access(
+    ctx: &AssistContext<'_>,
+    expr: &ast::Expr,
+) -> Option<bool> {
     if let ast::Expr::MacroExpr(_) = expr {
         // FIXME: expand macro and check output for mutable usages of the variable?
         return None;
@@ -1404,8 +1712,11 @@ fn make_call(ctx: &AssistContext<'_>, fun: &Function, indent: IndentLevel) -> Sy
         let self_arg = make::expr_path(make::ext::ident_path("self"));
         make::expr_method_call(self_arg, name, args)
     } else {
-        let func = make::expr_path(make::path_unqualified(make::path_segment(name)));
-        make::expr_call(func, args)
+        let path = match &fun.module_name {
+            Some(module_name) => make::path_from_text(&format!("{module_name}::{name}")),
+            None => make::path_unqualified(make::path_segment(name)),
+        };
+        make::expr_call(make::expr_path(path), args)
     };
 
     let handler = FlowHandler::from_ret_ty(fun, &ret_ty);
@@ -1588,7 +1899,7 @@ fn format_function(
     let (generic_params, where_clause) = make_generic_params_and_where_clause(ctx, fun);
 
     make::fn_(
-        None,
+        fun.visibility.clone(),
         fun_name,
         generic_params,
         where_clause,
@@ -1709,11 +2020,31 @@ fn type_params(&self, ctx: &AssistContext<'_>) -> Vec<TypeParam> {
     }
 
     fn make_param_list(&self, ctx: &AssistContext<'_>, module: hir::Module) -> ast::ParamList {
-        let self_param = self.self_param.clone();
+        let self_param = self.self_param.as_ref().map(|it| self.make_self_param(ctx, it));
         let params = self.params.iter().map(|param| param.to_param(ctx, module));
         make::param_list(self_param, params)
     }
 
+    /// Takes `self` by shared reference when the body only reads it, even if the method it is
+    /// extracted from takes `&mut self`.
+    fn make_self_param(
+        &self,
+        ctx: &AssistContext<'_>,
+        self_param: &ast::SelfParam,
+    ) -> ast::SelfParam {
+        let narrow = self_param.kind() == ast::SelfParamKind::MutRef
+            && self_param.lifetime().is_none()
+            && ctx.sema.to_def(self_param).is_some_and(|local| {
+                let usages = LocalUsages::find_local_usages(ctx, local);
+                !has_exclusive_usages(ctx, &usages, &self.body)
+            });
+        if narrow {
+            make::self_param()
+        } else {
+            self_param.clone()
+        }
+    }
+
     fn make_ret_ty(&self, ctx: &AssistContext<'_>, module: hir::Module) -> Option<ast::RetType> {
         let fun_ty = self.return_type(ctx);
         let handler = FlowHandler::from_ret_ty(self, &fun_ty);
@@ -1785,10 +2116,13 @@ fn make_body(ctx: &AssistContext<'_>, old_indent: IndentLevel, fun: &Function) -
 
     let block = match &fun.body {
         FunctionBody::Expr(expr) => {
-            let expr = rewrite_body_segment(ctx, &fun.params, &handler, expr.syntax());
+            let expr = rewrite_body_segment(ctx, fun, &handler, expr.syntax());
             let expr = ast::Expr::cast(expr).expect("Body segment should be an expr");
             match expr {
-                ast::Expr::BlockExpr(block) => {
+                // An `async` block is the future the function returns, it can't be unwrapped.
+                ast::Expr::BlockExpr(block)
+                    if !matches!(block.modifier(), Some(ast::BlockModifier::Async(_))) =>
+                {
                     // If the extracted expression is itself a block, there is no need to wrap it inside another block.
                     block.dedent(old_indent);
                     let elements = block.stmt_list().map_or_else(
@@ -1822,9 +2156,9 @@ fn make_body(ctx: &AssistContext<'_>, old_indent: IndentLevel, fun: &Function) -
                 .children_with_tokens()
                 .filter(|it| text_range.contains_range(it.text_range()))
                 .map(|it| match &it {
-                    syntax::NodeOrToken::Node(n) => syntax::NodeOrToken::Node(
-                        rewrite_body_segment(ctx, &fun.params, &handler, n),
-                    ),
+                    syntax::NodeOrToken::Node(n) => {
+                        syntax::NodeOrToken::Node(rewrite_body_segment(ctx, fun, &handler, n))
+                    }
                     _ => it,
                 })
                 .collect();
@@ -1965,15 +2299,48 @@ fn make_ty(ty: &hir::Type, ctx: &AssistContext<'_>, module: hir::Module) -> ast:
 
 fn rewrite_body_segment(
     ctx: &AssistContext<'_>,
-    params: &[Param],
+    fun: &Function,
     handler: &FlowHandler,
     syntax: &SyntaxNode,
 ) -> SyntaxNode {
-    let syntax = fix_param_usages(ctx, params, syntax);
+    let syntax = fix_param_usages(ctx, &fun.params, syntax);
     update_external_control_flow(handler, &syntax);
+    if let Some(self_ty) = fun.self_ty_outside_of_impl() {
+        replace_self_ty(&syntax, &self_ty);
+    }
     syntax
 }
 
+/// Spells out the `Self` paths in `syntax` with `self_ty`. Paths to values and associated items
+/// name the type without its generic arguments, which are inferred there.
+fn replace_self_ty(syntax: &SyntaxNode, self_ty: &ast::Type) {
+    let self_paths: Vec<_> = syntax
+        .descendants()
+        .filter_map(ast::Path::cast)
+        .filter(|path| {
+            path.qualifier().is_none()
+                && path.segment().and_then(|it| it.kind()) == Some(ast::PathSegmentKind::SelfTypeKw)
+        })
+        .collect();
+    for path in self_paths {
+        let is_type = path.syntax().parent().and_then(ast::PathType::cast).is_some();
+        let new_path = match self_ty {
+            _ if is_type => self_ty.to_string(),
+            ast::Type::PathType(it) => match it.path() {
+                Some(path) => path
+                    .segments()
+                    .filter_map(|it| it.name_ref())
+                    .map(|it| it.to_string())
+                    .collect::<Vec<_>>()
+                    .join("::"),
+                None => continue,
+            },
+            _ => format!("<{self_ty}>"),
+        };
+        ted::replace(path.syntax(), make::path_from_text(&new_path).clone_for_update().syntax());
+    }
+}
+
 /// change all usages to account for added `&`/`&mut` for some params
 fn fix_param_usages(ctx: &AssistContext<'_>, params: &[Param], syntax: &SyntaxNode) -> SyntaxNode {
     let mut usages_for_param: Vec<(&Param, Vec<ast::Expr>)> = Vec::new();
@@ -2115,10 +2482,27 @@ fn make_rewritten_flow(handler: &FlowHandler, arg_expr: Option<ast::Expr>) -> Op
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable};
+    use crate::tests::{
+        check_assist, check_assist_not_applicable, check_assist_not_applicable_with_parameter,
+        check_assist_unresolved, check_assist_with_parameter,
+    };
 
     use super::*;
 
+    #[test]
+    fn params_are_only_analysed_on_resolve() {
+        check_assist_unresolved(
+            extract_function,
+            r#"
+fn foo() {
+    let mut n = 1;
+    $0n += 1;$0
+    let m = n;
+}
+"#,
+        );
+    }
+
     #[test]
     fn no_args_from_binary_expr() {
         check_assist(
@@ -2899,6 +3283,39 @@ fn $0fun_name(&mut self) {
         );
     }
 
+    #[test]
+    fn method_reading_self_of_mut_method() {
+        check_assist(
+            extract_function,
+            r#"
+struct S { f: i32, g: i32 };
+
+impl S {
+    fn foo(&mut self) -> i32 {
+        let n = $0self.f * self.g$0;
+        self.f = n;
+        n
+    }
+}
+"#,
+            r#"
+struct S { f: i32, g: i32 };
+
+impl S {
+    fn foo(&mut self) -> i32 {
+        let n = self.fun_name();
+        self.f = n;
+        n
+    }
+
+    fn $0fun_name(&self) -> i32 {
+        self.f * self.g
+    }
+}
+"#,
+        );
+    }
+
     #[test]
     fn variable_defined_inside_and_used_after_no_ret() {
         check_assist(
@@ -4632,6 +5049,135 @@ async fn $0fun_name() {
 
 async fn some_function() {
 
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_await_in_macro_call() {
+        check_assist(
+            extract_function,
+            r#"
+//- minicore: future
+macro_rules! identity {
+    ($e:expr) => { $e };
+}
+
+async fn foo() {
+    $0identity!(some_function().await);$0
+}
+
+async fn some_function() {}
+"#,
+            r#"
+macro_rules! identity {
+    ($e:expr) => { $e };
+}
+
+async fn foo() {
+    fun_name().await;
+}
+
+async fn $0fun_name() {
+    identity!(some_function().await);
+}
+
+async fn some_function() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_await_in_async_block_of_macro_call() {
+        check_assist(
+            extract_function,
+            r#"
+//- minicore: future
+macro_rules! spawn {
+    ($e:expr) => { $e };
+}
+
+async fn foo() {
+    $0spawn!(async move { some_function().await });$0
+}
+
+async fn some_function() {}
+"#,
+            r#"
+macro_rules! spawn {
+    ($e:expr) => { $e };
+}
+
+async fn foo() {
+    fun_name();
+}
+
+fn $0fun_name() {
+    spawn!(async move { some_function().await });
+}
+
+async fn some_function() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_async_block_returns_future() {
+        check_assist(
+            extract_function,
+            r#"
+//- minicore: future
+fn foo() {
+    let fut = $0async { some_function().await }$0;
+}
+
+async fn some_function() {}
+"#,
+            r#"
+fn foo() {
+    let fut = fun_name();
+}
+
+fn $0fun_name() -> impl Future<Output = ()> {
+    async { some_function().await }
+}
+
+async fn some_function() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_with_await_and_try() {
+        check_assist(
+            extract_function,
+            r#"
+//- minicore: future, result, try
+async fn foo() -> Result<i32, ()> {
+    $0let k = some_function().await?;
+    let n = k + 1;$0
+    Ok(n)
+}
+
+async fn some_function() -> Result<i32, ()> {
+    Ok(1)
+}
+"#,
+            r#"
+async fn foo() -> Result<i32, ()> {
+    let n = fun_name().await?;
+    Ok(n)
+}
+
+async fn $0fun_name() -> Result<i32, ()> {
+    let k = some_function().await?;
+    let n = k + 1;
+    Ok(n)
+}
+
+async fn some_function() -> Result<i32, ()> {
+    Ok(1)
 }
 "#,
         );
@@ -6114,4 +6660,92 @@ fn in_right_brack_is_not_applicable() {
         cov_mark::check!(extract_function_in_braces_is_not_applicable);
         check_assist_not_applicable(extract_function, r"fn foo(arr: &mut $0[$0i32]) {}");
     }
+
+    #[test]
+    fn extract_into_inline_module() {
+        check_assist_with_parameter(
+            extract_function,
+            "crate::util",
+            r#"
+//- minicore: copy
+mod util {}
+
+struct Helper(i32);
+
+fn main() {
+    let n = 1;
+    $0let h = Helper(n + 1);$0
+    let _ = n;
+}
+"#,
+            r#"
+mod util {
+    pub(super) fn $0fun_name(n: i32) {
+        let h = crate::Helper(n + 1);
+    }
+}
+
+struct Helper(i32);
+
+fn main() {
+    let n = 1;
+    util::fun_name(n);
+    let _ = n;
+}
+"#,
+            "Extract into function in existing module",
+        );
+    }
+
+    #[test]
+    fn extract_into_module_in_other_file() {
+        check_assist_with_parameter(
+            extract_function,
+            "util",
+            r#"
+//- /main.rs
+mod util;
+
+fn main() {
+    let n = 1;
+    let m = $0n * 2$0;
+}
+//- /util.rs
+pub fn helper() {}
+"#,
+            r#"
+//- /main.rs
+mod util;
+
+fn main() {
+    let n = 1;
+    let m = util::fun_name(n);
+}
+//- /util.rs
+pub fn helper() {}
+
+pub(super) fn $0fun_name(n: i32) -> i32 {
+    n * 2
+}
+"#,
+            "Extract into function in existing module",
+        );
+    }
+
+    #[test]
+    fn extract_into_unresolved_module() {
+        check_assist_not_applicable_with_parameter(
+            extract_function,
+            "crate::missing",
+            r#"
+mod util {}
+
+fn main() {
+    let n = 1;
+    let m = $0n * 2$0;
+}
+"#,
+            "Extract into function in existing module",
+        );
+    }
 }
@@ -1,36 +1,48 @@
 use std::iter;
 
 use either::Either;
-use hir::{HasSource, HirFileIdExt, ModuleSource};
+use hir::{ModuleSource, PathResolution, ScopeDef};
 use ide_db::{
     assists::{AssistId, AssistKind},
-    base_db::FileId,
-    defs::{Definition, NameClass, NameRefClass},
-    search::{FileReference, SearchScope},
-    FxHashMap, FxHashSet,
+    defs::Definition,
+    syntax_helpers::insert_item::{add_item_to_module, ItemPlacement},
+    FxHashSet,
 };
 use itertools::Itertools;
-use smallvec::SmallVec;
 use syntax::{
-    algo::find_node_at_range,
+    algo::{self, find_node_at_range},
     ast::{
         self,
         edit::{AstNodeEdit, IndentLevel},
-        make, HasVisibility,
+        edit_in_place::AttrsOwnerEdit,
+        make, HasAttrs, HasGenericParams, HasModuleItem, HasName,
     },
-    match_ast, ted, AstNode,
-    SyntaxKind::{self, WHITESPACE},
-    SyntaxNode, TextRange, TextSize,
+    ted, AstNode, AstToken, Direction, NodeOrToken, SyntaxElement,
+    SyntaxKind::{IDENT, WHITESPACE},
+    SyntaxNode, SyntaxToken, TextRange, TextSize,
 };
 
-use crate::{AssistContext, Assists};
+use text_edit::TextEdit;
 
-use super::remove_unused_param::range_to_remove;
+use crate::{assist_context::SourceChangeBuilder, AssistContext, Assists, ExtractModuleVisibility};
+
+use self::{
+    imports::check_intersection_and_push, statements::extract_default_methods,
+    trait_imports::import_into, visibility::is_cfg,
+};
+use super::extract_function::{extract_function_into_module, unique_name};
+
+mod imports;
+mod statements;
+mod trait_imports;
+mod usages;
+mod visibility;
 
 // Assist: extract_module
 //
 // Extracts a selected region as separate module. All the references, visibility and imports are
-// resolved.
+// resolved. The items can also be moved into an existing module, or re-exported from the
+// current one so that their usages don't change.
 //
 // ```
 // $0fn foo(name: i32) -> i32 {
@@ -38,19 +50,19 @@
 // }$0
 //
 // fn bar(name: i32) -> i32 {
-//     name + 2
+//     foo(name) + 2
 // }
 // ```
 // ->
 // ```
-// mod modname {
-//     pub(crate) fn foo(name: i32) -> i32 {
+// mod ${0:modname} {
+//     pub(super) fn foo(name: i32) -> i32 {
 //         name + 1
 //     }
 // }
 //
 // fn bar(name: i32) -> i32 {
-//     name + 2
+//     ${0:modname}::foo(name) + 2
 // }
 // ```
 pub(crate) fn extract_module(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
@@ -64,18 +76,22 @@ pub(crate) fn extract_module(acc: &mut Assists, ctx: &AssistContext<'_>) -> Opti
         syntax::NodeOrToken::Token(t) => t.parent()?,
     };
 
-    //If the selection is inside impl block, we need to place new module outside impl block,
-    //as impl blocks cannot contain modules
+    // Default methods can't be moved out of a trait, their bodies are moved into free functions
+    // instead.
+    let trait_parent = ast::AssocItemList::cast(node.clone())
+        .or_else(|| node.parent().and_then(ast::AssocItemList::cast))
+        .and_then(|list| ast::Trait::cast(list.syntax().parent()?));
+    if let Some(trait_) = trait_parent {
+        return extract_default_methods(acc, ctx, trait_);
+    }
 
-    let mut impl_parent: Option<ast::Impl> = None;
-    let mut impl_child_count: usize = 0;
-    if let Some(parent_assoc_list) = node.parent() {
-        if let Some(parent_impl) = parent_assoc_list.parent() {
-            if let Some(impl_) = ast::Impl::cast(parent_impl) {
-                impl_child_count = parent_assoc_list.children().count();
-                impl_parent = Some(impl_);
-            }
-        }
+    //If the selection is inside impl blocks, we need to place new module outside impl blocks,
+    //as impl blocks cannot contain modules
+    let impl_parents = selected_impl_items(&node, ctx.selection_trimmed());
+    let mut item_impls = Vec::new();
+    for (impl_, items) in &impl_parents {
+        impl_.self_ty()?;
+        item_impls.extend(items.iter().map(|_| impl_.clone()));
     }
 
     let mut curr_parent_module: Option<ast::Module> = None;
@@ -83,721 +99,965 @@ pub(crate) fn extract_module(acc: &mut Assists, ctx: &AssistContext<'_>) -> Opti
         curr_parent_module = ast::Module::cast(mod_syn_opt);
     }
 
-    let mut module = extract_target(&node, ctx.selection_trimmed())?;
+    // The name of a new module is only picked when the assist is resolved, see `apply`.
+    let mut module = extract_target(&node, ctx.selection_trimmed(), String::new())?;
+    if !impl_parents.is_empty() {
+        module.body_items = impl_parents
+            .iter()
+            .flat_map(|(_, items)| items)
+            .filter_map(|item| ast::Item::cast(item.syntax().clone()))
+            .collect();
+        module.comments.clear();
+    }
     if module.body_items.is_empty() {
-        return None;
+        // Statements are extracted into a function of the new module instead.
+        return extract_function_into_module(acc, ctx);
     }
 
     let old_item_indent = module.body_items[0].indent_level();
 
-    acc.add(
-        AssistId("extract_module", AssistKind::RefactorExtract),
-        "Extract Module",
-        module.text_range,
-        |builder| {
-            //This takes place in three steps:
-            //
-            //- Firstly, we will update the references(usages) e.g. converting a
-            //  function call bar() to modname::bar(), and similarly for other items
-            //
-            //- Secondly, changing the visibility of each item inside the newly selected module
-            //  i.e. making a fn a() {} to pub(crate) fn a() {}
-            //
-            //- Thirdly, resolving all the imports this includes removing paths from imports
-            //  outside the module, shifting/cloning them inside new module, or shifting the imports, or making
-            //  new import statements
-
-            //We are getting item usages and record_fields together, record_fields
-            //for change_visibility and usages for first point mentioned above in the process
-
-            let (usages_to_be_processed, record_fields, use_stmts_to_be_inserted) =
-                module.get_usages_and_record_fields(ctx);
-
-            builder.edit_file(ctx.file_id());
-            use_stmts_to_be_inserted.into_iter().for_each(|(_, use_stmt)| {
-                builder.insert(ctx.selection_trimmed().end(), format!("\n{use_stmt}"));
-            });
-
-            let import_paths_to_be_removed = module.resolve_imports(curr_parent_module, ctx);
-            module.change_visibility(record_fields);
-
-            let module_def = generate_module_def(&impl_parent, &mut module, old_item_indent);
-
-            let mut usages_to_be_processed_for_cur_file = vec![];
-            for (file_id, usages) in usages_to_be_processed {
-                if file_id == ctx.file_id() {
-                    usages_to_be_processed_for_cur_file = usages;
-                    continue;
-                }
-                builder.edit_file(file_id);
-                for (text_range, usage) in usages {
-                    builder.replace(text_range, usage)
-                }
+    // Inline modules next to the selection, which the items can be moved into instead.
+    let existing_modules: Vec<ast::Module> = if impl_parents.is_empty() {
+        let selection = ctx.selection_trimmed();
+        let container =
+            if ast::Item::can_cast(node.kind()) && selection.contains_range(node.text_range()) {
+                node.parent()?
+            } else {
+                node.clone()
+            };
+        container
+            .children()
+            .filter_map(ast::Module::cast)
+            .filter(|it| it.item_list().is_some() && it.name().is_some())
+            .filter(|it| {
+                selection.intersect(it.syntax().text_range()).filter(|it| !it.is_empty()).is_none()
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let apply = |builder: &mut SourceChangeBuilder,
+                 mut module: Module,
+                 existing_module: Option<ast::Module>| {
+        //This takes place in three steps:
+        //
+        //- Firstly, we will update the references(usages) e.g. converting a
+        //  function call bar() to modname::bar(), and similarly for other items
+        //
+        //- Secondly, changing the visibility of each item inside the newly selected module
+        //  i.e. making a fn a() {} to pub(crate) fn a() {}
+        //
+        //- Thirdly, resolving all the imports this includes removing paths from imports
+        //  outside the module, shifting/cloning them inside new module, or shifting the imports, or making
+        //  new import statements
+
+        //We are getting item usages and record_fields together, record_fields
+        //for change_visibility and usages for first point mentioned above in the process
+
+        // The new module mustn't shadow, or be shadowed by, an item next to it.
+        if existing_module.is_none() {
+            let Some(scope) = ctx.sema.scope(&node) else { return };
+            module.name = unique_name(&scope, "modname");
+        }
+        let (mut usages_to_be_processed, record_fields, use_stmts_to_be_inserted) =
+            module.get_usages_and_record_fields(ctx);
+        let reexported_names =
+            if module.reexport { module.reexported_names(ctx) } else { Vec::new() };
+
+        // Edits to the current file, each with the offsets of the module name in its new
+        // text, so that all occurrences of the name can be linked into a single placeholder.
+        // The new items are built as nodes, but spliced in as text next to the rewritten usages.
+        let mut edits: Vec<(TextRange, String, Vec<TextSize>)> = Vec::new();
+        let use_stmts_offset = ctx.selection_trimmed().end();
+        // The imports of the new module are only valid where the module is compiled.
+        let cfgs = match existing_module {
+            Some(_) => Vec::new(),
+            None => module.common_cfgs(),
+        };
+        for (_, use_stmt) in use_stmts_to_be_inserted {
+            let use_stmt = with_attrs(use_stmt, &cfgs);
+            let name_offsets = use_stmt
+                .syntax()
+                .descendants()
+                .filter_map(ast::NameRef::cast)
+                .filter(|name_ref| name_ref.text() == module.name)
+                .map(|name_ref| TextSize::of("\n") + name_ref.syntax().text_range().start())
+                .collect();
+            edits.push((TextRange::empty(use_stmts_offset), format!("\n{use_stmt}"), name_offsets));
+        }
+        let parent_module = ctx.sema.scope(&node).map(|scope| scope.module());
+        for (use_module, path) in module.trait_imports_for_item_uses(ctx, parent_module) {
+            let use_ = make::use_(None, make::use_tree(path, None, None, false));
+            let import = if Some(use_module) == parent_module {
+                let use_ = with_attrs(use_, &cfgs);
+                Some((ctx.file_id(), use_stmts_offset, format!("\n{use_}")))
+            } else {
+                import_into(ctx, use_module, &use_)
+            };
+            if let Some((file_id, offset, text)) = import {
+                usages_to_be_processed
+                    .entry(file_id)
+                    .or_default()
+                    .push((TextRange::empty(offset), text));
+            }
+        }
+
+        let import_paths_to_be_removed = module.resolve_imports(curr_parent_module.clone(), ctx);
+        // The items end up one level deeper either way.
+        let mut paths_to_fix =
+            module.super_chains_to_fix(ctx, &module.defs_in_selection(ctx), None);
+        if let Some(existing_module) = &existing_module {
+            module.retain_imports_outside_of(existing_module);
+            paths_to_fix.extend(module.qualifiers_referring_to(ctx, existing_module));
+        }
+        module.change_visibility(ctx, record_fields, &paths_to_fix);
+
+        let mut other_files_changed = false;
+        for (file_id, usages) in usages_to_be_processed {
+            if file_id == ctx.file_id() {
+                // Usages are rewritten to `modname::usage`, possibly behind the path to the parent
+                // module. Others, like `crate::` in front of exported macros, don't name it.
+                edits.extend(usages.into_iter().map(|(range, usage)| {
+                    let name_offset = usage
+                        .rfind("::")
+                        .and_then(|end| end.checked_sub(module.name.len()))
+                        .filter(|&start| usage[start..].starts_with(&*module.name))
+                        .map(|start| TextSize::of(&usage[..start]));
+                    (range, usage, name_offset.into_iter().collect())
+                }));
+                continue;
+            }
+            other_files_changed |= !usages.is_empty();
+            builder.edit_file(file_id);
+            for (text_range, usage) in usages {
+                builder.replace(text_range, usage)
             }
+        }
 
-            builder.edit_file(ctx.file_id());
-            for (text_range, usage) in usages_to_be_processed_for_cur_file {
-                builder.replace(text_range, usage);
+        if let Some((last_impl, _)) = impl_parents.last() {
+            // The module goes next to the impls, one level out of their items.
+            let indent = IndentLevel(old_item_indent.0.saturating_sub(1));
+            let module_def = generate_module_def(ctx, &item_impls, &mut module, indent);
+
+            for (impl_, items) in &impl_parents {
+                let item_count = impl_.assoc_item_list().map_or(0, |it| it.assoc_items().count());
+                if items.len() == item_count {
+                    // Remove complete impl block if all of its items are moved (as such it will
+                    // be empty after deleting them)
+                    edits.push((impl_.syntax().text_range(), String::new(), Vec::new()));
+                    // Remove preceding indentation from node
+                    if let Some(range) = indent_range_before_given_node(impl_.syntax()) {
+                        edits.push((range, String::new(), Vec::new()));
+                    }
+                } else {
+                    edits.push((range_of_assoc_items(items), String::new(), Vec::new()));
+                }
             }
 
-            if let Some(impl_) = impl_parent {
-                // Remove complete impl block if it has only one child (as such it will be empty
-                // after deleting that child)
-                let node_to_be_removed = if impl_child_count == 1 {
-                    impl_.syntax()
+            let prefix = format!("\n\n{indent}");
+            edits.push((
+                TextRange::empty(last_impl.syntax().text_range().end()),
+                format!("{prefix}{module_def}"),
+                vec![TextSize::of(&prefix) + name_offset(&module_def)],
+            ));
+        } else {
+            for import_path_text_range in import_paths_to_be_removed {
+                if module.text_range.intersect(import_path_text_range).is_some() {
+                    module.text_range = module.text_range.cover(import_path_text_range);
                 } else {
-                    //Remove selected node
-                    &node
-                };
-
-                builder.delete(node_to_be_removed.text_range());
-                // Remove preceding indentation from node
-                if let Some(range) = indent_range_before_given_node(node_to_be_removed) {
-                    builder.delete(range);
+                    edits.push((import_path_text_range, String::new(), Vec::new()));
                 }
+            }
 
-                builder.insert(impl_.syntax().text_range().end(), format!("\n\n{module_def}"));
-            } else {
-                for import_path_text_range in import_paths_to_be_removed {
-                    if module.text_range.intersect(import_path_text_range).is_some() {
-                        module.text_range = module.text_range.cover(import_path_text_range);
-                    } else {
-                        builder.delete(import_path_text_range);
+            match &existing_module {
+                Some(existing_module) => {
+                    let mut removed_range =
+                        range_with_trailing_whitespace(&node, module.text_range);
+                    // Imports are inserted right after the selection, keep them intact.
+                    if edits.iter().any(|(range, ..)| {
+                        range.start() >= module.text_range.end()
+                            && range.start() < removed_range.end()
+                    }) {
+                        removed_range = module.text_range;
+                    }
+                    edits.push((removed_range, String::new(), Vec::new()));
+                    module.merge_use_items(ctx);
+                    let target = existing_module.item_list().map(Either::Left);
+                    edits.extend(
+                        target
+                            .iter()
+                            .flat_map(|target| {
+                                items_for_existing_module(&module, target, old_item_indent)
+                            })
+                            .map(|(range, text)| (range, text, Vec::new())),
+                    );
+                }
+                None => {
+                    let module_def = generate_module_def(ctx, &[], &mut module, old_item_indent);
+                    edits.push((
+                        module.text_range,
+                        module_def.to_string(),
+                        vec![name_offset(&module_def)],
+                    ));
+                    let cfgs = module_def.attrs().collect_vec();
+                    // Only the items of the public API are re-exported to other crates. Names
+                    // with the same attributes share a re-export.
+                    let mut groups: Vec<(bool, Vec<ast::Attr>, Vec<String>)> = Vec::new();
+                    for (name, public, attrs) in reexported_names {
+                        let same_attrs = |it: &[ast::Attr]| {
+                            it.iter()
+                                .map(ToString::to_string)
+                                .eq(attrs.iter().map(ToString::to_string))
+                        };
+                        match groups.iter_mut().find(|(p, a, _)| *p == public && same_attrs(a)) {
+                            Some((_, _, names)) => names.push(name),
+                            None => groups.push((public, attrs, vec![name])),
+                        }
+                    }
+                    groups.sort_by_key(|(public, ..)| !public);
+                    for (public, attrs, names) in groups {
+                        let vis = if public {
+                            make::visibility_pub()
+                        } else {
+                            make::visibility_pub_crate()
+                        };
+                        let module_path = make::ext::ident_path(&module.name);
+                        let use_tree = match &*names {
+                            [name] => make::use_tree(
+                                make::path_concat(module_path, make::ext::ident_path(name)),
+                                None,
+                                None,
+                                false,
+                            ),
+                            names => {
+                                let names = names.iter().map(|name| {
+                                    make::use_tree(make::ext::ident_path(name), None, None, false)
+                                });
+                                make::use_tree(
+                                    module_path,
+                                    Some(make::use_tree_list(names)),
+                                    None,
+                                    false,
+                                )
+                            }
+                        };
+                        let reexport = with_attrs(
+                            make::use_(Some(vis), use_tree),
+                            &cfgs.iter().chain(&attrs).cloned().collect_vec(),
+                        )
+                        .indent(old_item_indent);
+                        let prefix = format!("\n\n{old_item_indent}");
+                        let name_offset = reexport
+                            .use_tree()
+                            .and_then(|it| it.path()?.first_segment())
+                            .map(|it| it.syntax().text_range().start());
+                        edits.push((
+                            TextRange::empty(module.text_range.end()),
+                            format!("{prefix}{reexport}"),
+                            name_offset.map(|it| TextSize::of(&prefix) + it).into_iter().collect(),
+                        ));
                     }
                 }
+            }
+        }
+
+        builder.edit_file(ctx.file_id());
+        let name_ranges = replace_tracking_name(builder, edits, &module.name);
 
-                builder.replace(module.text_range, module_def)
+        // Snippets can only be placed in a single file, so the name is left as is if other
+        // files refer to the new module as well. An existing module keeps its name.
+        if let Some(cap) = ctx.config.snippet_cap {
+            if !other_files_changed && existing_module.is_none() {
+                builder.add_placeholder_snippet_group_at(cap, name_ranges);
             }
-        },
-    )
+        }
+    };
+
+    // Items of impls can't be re-exported.
+    let reexport_by_default = impl_parents.is_empty()
+        && ctx.config.extract_module_visibility == ExtractModuleVisibility::Reexport;
+    module.reexport = reexport_by_default;
+
+    // Everything outside of `apply` is purely syntactic. Name lookups, usage searches and import
+    // resolution must only run when an assist is resolved. Each variant has its own id,
+    // so that resolving one of them doesn't compute the others.
+    acc.add(
+        AssistId("extract_module", AssistKind::RefactorExtract),
+        "Extract Module",
+        module.text_range,
+        |builder| apply(builder, module, None),
+    );
+    if impl_parents.is_empty() && !reexport_by_default {
+        if let Some(mut module) = extract_target(&node, ctx.selection_trimmed(), String::new()) {
+            module.reexport = true;
+            acc.add(
+                AssistId("extract_module_with_reexports", AssistKind::RefactorExtract),
+                "Extract Module with re-exports",
+                module.text_range,
+                |builder| apply(builder, module, None),
+            );
+        }
+    }
+    for existing_module in existing_modules {
+        let Some(name) = existing_module.name() else { continue };
+        let Some(module) = extract_target(&node, ctx.selection_trimmed(), name.to_string()) else {
+            continue;
+        };
+        acc.add(
+            AssistId("move_into_existing_module", AssistKind::RefactorExtract),
+            format!("Move into existing module `{name}`"),
+            module.text_range,
+            |builder| apply(builder, module, Some(existing_module)),
+        );
+    }
+    if impl_parents.is_empty() {
+        if let Some(module) = extract_target(&node, ctx.selection_trimmed(), String::new()) {
+            acc.add_with_parameter(
+                AssistId("move_to_module", AssistKind::RefactorExtract),
+                "Move to another module",
+                "Path of the module to move the items into, e.g. `crate::util`",
+                module.text_range,
+                |builder, path| move_to_module(ctx, builder, module, &node, path),
+            );
+        }
+    }
+    Some(())
 }
 
+/// Applies `edits` to the current file, each given with the offsets of the module `name` in its new
+/// text. Returns the ranges of all these occurrences of the name in the edited file.
+fn replace_tracking_name(
+    builder: &mut SourceChangeBuilder,
+    mut edits: Vec<(TextRange, String, Vec<TextSize>)>,
+    name: &str,
+) -> Vec<TextRange> {
+    // Same order as the one the edits are applied in, so that we can track where the new text
+    // ends up.
+    edits.sort_by_key(|(range, ..)| (range.start(), range.end()));
+    let mut name_ranges = Vec::new();
+    let (mut inserted, mut deleted) = (TextSize::from(0), TextSize::from(0));
+    for (range, text, name_offsets) in edits {
+        let new_start = range.start() + inserted - deleted;
+        name_ranges.extend(
+            name_offsets
+                .into_iter()
+                .map(|offset| TextRange::at(new_start + offset, TextSize::of(name))),
+        );
+        inserted += TextSize::of(&text);
+        deleted += range.len();
+        builder.replace(range, text);
+    }
+    name_ranges
+}
+
+/// Moves the selected items into the module at `target_path`, which may live in another file.
+/// Usages of the items are requalified, and so are the paths in the items that wouldn't resolve
+/// the same way from their new module.
+fn move_to_module(
+    ctx: &AssistContext<'_>,
+    builder: &mut SourceChangeBuilder,
+    mut module: Module,
+    node: &SyntaxNode,
+    target_path: &str,
+) -> Option<()> {
+    let db = ctx.db();
+    let current = ctx.sema.scope(node)?.module();
+    let destination = resolve_module_path(db, current.krate().root_module(), target_path)?;
+    if destination == current {
+        return None;
+    }
+    let moved_defs = module.defs_in_selection(ctx);
+    // A module can't be moved into itself.
+    if destination
+        .path_to_root(db)
+        .into_iter()
+        .any(|it| moved_defs.contains(&Definition::Module(it)))
+    {
+        return None;
+    }
+
+    let source = destination.definition_source(db);
+    let target_file_id = source.file_id.file_id()?;
+    let target_file = ctx.sema.parse(target_file_id);
+    let target = match source.value {
+        ModuleSource::SourceFile(_) => Either::Right(target_file),
+        ModuleSource::Module(it) => Either::Left(
+            find_node_at_range::<ast::Module>(target_file.syntax(), it.syntax().text_range())?
+                .item_list()?,
+        ),
+        ModuleSource::BlockExpr(_) => return None,
+    };
+    let target_scope = ctx.sema.scope(target.as_ref().either(AstNode::syntax, AstNode::syntax))?;
+
+    module.destination = Some(destination);
+    let old_indent = module.body_items.first()?.indent_level();
+    let visibility_edits =
+        module.visibility_edits_for_destination(ctx, &moved_defs, destination)?;
+    let (usages, record_fields, _) = module.get_usages_and_record_fields(ctx);
+    let (mut replacements, alias_imports) =
+        module.paths_to_fix_for_destination(ctx, &moved_defs, &target_scope);
+    replacements.extend(module.super_chains_to_fix(ctx, &moved_defs, Some(destination)));
+    module.change_visibility(ctx, record_fields, &replacements);
+    let use_items = make_items_mut(&module.use_items);
+    let use_replacements = replacements
+        .iter()
+        .filter(|(_, replacement)| replacement.is_some())
+        .filter_map(|(element, replacement)| {
+            let copy = use_items.iter().find_map(|it| counterpart(it.syntax(), element))?;
+            Some((copy, replacement.clone()))
+        })
+        .collect();
+    replace_elements(use_replacements);
+    module.use_items = use_items;
+    module.use_items.extend(alias_imports);
+
+    module.merge_use_items(ctx);
+    let mut edits = usages;
+    for (file_id, range, text) in visibility_edits {
+        edits.entry(file_id).or_default().push((range, text));
+    }
+    edits
+        .entry(ctx.file_id())
+        .or_default()
+        .push((range_with_trailing_whitespace(node, module.text_range), String::new()));
+    edits
+        .entry(target_file_id)
+        .or_default()
+        .extend(items_for_existing_module(&module, &target, old_indent));
+    for (file_id, edits) in edits {
+        // Removals of nested use trees may overlap on the comma between them.
+        let (removals, edits): (Vec<_>, Vec<_>) =
+            edits.into_iter().partition(|(range, text)| text.is_empty() && !range.is_empty());
+        let mut merged_removals = Vec::new();
+        for (range, _) in removals {
+            check_intersection_and_push(&mut merged_removals, range);
+        }
+        if edits.is_empty() && merged_removals.is_empty() {
+            continue;
+        }
+        builder.edit_file(file_id);
+        for range in merged_removals {
+            builder.delete(range);
+        }
+        for (range, text) in edits {
+            builder.replace(range, text);
+        }
+    }
+    Some(())
+}
+
+/// Resolves a path like `crate::foo::bar` or `foo::bar` to a module of the crate with the root
+/// module `root`.
+pub(super) fn resolve_module_path(
+    db: &dyn hir::db::HirDatabase,
+    root: hir::Module,
+    path: &str,
+) -> Option<hir::Module> {
+    let mut segments = path.trim().split("::").map(str::trim).peekable();
+    segments.next_if_eq(&"crate");
+    segments.try_fold(root, |module, segment| {
+        module
+            .children(db)
+            .find(|child| child.name(db).is_some_and(|it| it.as_str() == Some(segment)))
+    })
+}
+
+/// Generates the new module at `old_indent`. If the body items come out of impl blocks,
+/// `item_impls` holds the impl of each of them, and the items are put into one impl for each
+/// distinct impl header. The `#[cfg]`s all the items share are moved onto the module.
 fn generate_module_def(
-    parent_impl: &Option<ast::Impl>,
+    ctx: &AssistContext<'_>,
+    item_impls: &[ast::Impl],
     module: &mut Module,
     old_indent: IndentLevel,
-) -> String {
-    let (items_to_be_processed, new_item_indent) = if parent_impl.is_some() {
-        (Either::Left(module.body_items.iter()), old_indent + 2)
+) -> ast::Module {
+    let mut impls: Vec<(ast::Impl, Vec<ast::Item>)> = Vec::new();
+    for (impl_, item) in item_impls.iter().zip(&module.body_items) {
+        match impls.iter_mut().find(|(it, _)| impl_header(it) == impl_header(impl_)) {
+            Some((_, items)) => items.push(item.clone()),
+            None => impls.push((impl_.clone(), vec![item.clone()])),
+        }
+    }
+    // Add the imports for the self types, bounds and generic arguments of the impl blocks
+    for (impl_, _) in impls.iter().rev() {
+        for name_ref in names_to_import_for_impl_header(ctx, impl_).iter().rev() {
+            module.make_use_stmt_of_node_with_super(name_ref.syntax());
+        }
+    }
+    module.merge_use_items(ctx);
+
+    let cfgs = module.common_cfgs();
+    // Only whitespace tokens are reindented, multi-line string literals keep their contents.
+    let detached = |item: &ast::Item| {
+        let item = item.indent(IndentLevel(1)).clone_for_update();
+        let hoisted =
+            item.attrs().filter(|attr| cfgs.iter().any(|cfg| cfg.to_string() == attr.to_string()));
+        for attr in hoisted.collect_vec() {
+            if let Some(ws) =
+                attr.syntax().next_sibling_or_token().filter(|it| it.kind() == WHITESPACE)
+            {
+                ted::remove(ws);
+            }
+            ted::remove(attr.syntax());
+        }
+        item.syntax().clone()
+    };
+    let use_groups = module
+        .use_item_groups()
+        .iter()
+        .map(|group| group.iter().map(|item| SyntaxElement::from(detached(item))).collect_vec())
+        .collect_vec();
+    let detached = |item: &ast::Item| vec![SyntaxElement::from(detached(item))];
+    let items = if impls.is_empty() {
+        let mut comments = module.comments.iter().peekable();
+        let mut items = use_groups;
+        for (idx, item) in module.body_items.iter().enumerate() {
+            while let Some((_, comments)) = comments.next_if(|(next_item, _)| *next_item <= idx) {
+                items.push(
+                    comments.iter().map(|it| make::tokens::comment(it.text()).into()).collect(),
+                );
+            }
+            items.push(detached(item));
+        }
+        items.extend(comments.map(|(_, comments)| {
+            comments.iter().map(|it| make::tokens::comment(it.text()).into()).collect()
+        }));
+        items
     } else {
-        (Either::Right(module.use_items.iter().chain(module.body_items.iter())), old_indent + 1)
+        let impls = impls.into_iter().filter_map(|(impl_, assoc_items)| {
+            let (generic_params, self_ty) = (impl_.generic_param_list(), impl_.self_ty()?);
+            let where_clause = impl_.where_clause().map(|it| it.dedent(impl_.indent_level()));
+            // An empty body keeps a newline out of the braces that follow a where clause.
+            let impl_ = make::impl_(generic_params, None, self_ty, where_clause, Some(Vec::new()))
+                .indent(old_indent + 1)
+                .clone_for_update();
+            if let Some(l_curly) = impl_.get_or_create_assoc_item_list().l_curly_token() {
+                let assoc_items = assoc_items.iter().map(detached).collect();
+                fill_braces(&l_curly, assoc_items, old_indent + 2, old_indent + 1);
+            }
+            Some(vec![impl_.syntax().clone().into()])
+        });
+        use_groups.into_iter().chain(impls).collect()
     };
 
-    let mut body = items_to_be_processed
-        .map(|item| item.indent(IndentLevel(1)))
-        .map(|item| format!("{new_item_indent}{item}"))
-        .join("\n\n");
+    let module_def =
+        make::mod_(make::name(&module.name), Some(make::item_list())).clone_for_update();
+    if let Some(l_curly) = module_def.item_list().and_then(|it| it.l_curly_token()) {
+        fill_braces(&l_curly, items, old_indent + 1, old_indent);
+    }
+    let attrs = cfgs
+        .iter()
+        .flat_map(|cfg| {
+            let ws = make::tokens::whitespace(&format!("\n{old_indent}"));
+            [SyntaxElement::from(cfg.syntax().clone_subtree().clone_for_update()), ws.into()]
+        })
+        .collect();
+    ted::insert_all_raw(ted::Position::first_child_of(module_def.syntax()), attrs);
+    module_def
+}
 
-    if let Some(self_ty) = parent_impl.as_ref().and_then(|imp| imp.self_ty()) {
-        let impl_indent = old_indent + 1;
-        body = format!("{impl_indent}impl {self_ty} {{\n{body}\n{impl_indent}}}");
+/// The part of `impl_` that the moved items of one impl in the new module share.
+fn impl_header(impl_: &ast::Impl) -> String {
+    let generic_params = impl_.generic_param_list().map_or_else(String::new, |it| it.to_string());
+    let self_ty = impl_.self_ty().map_or_else(String::new, |it| it.to_string());
+    let where_clause = impl_.where_clause().map_or_else(String::new, |it| it.to_string());
+    format!("{generic_params} {self_ty} {where_clause}")
+}
 
-        // Add the import for enum/struct corresponding to given impl block
-        module.make_use_stmt_of_node_with_super(self_ty.syntax());
-        for item in module.use_items.iter() {
-            body = format!("{impl_indent}{item}\n\n{body}");
+/// The names in the header of `impl_`, like its self type, the traits in the bounds of its
+/// generic parameters and the types in its generic arguments, that the new module has to import
+/// from its parent. Names the parent doesn't define or import, like the ones of the prelude or
+/// of the generic parameters themselves, resolve the same way from the new module.
+fn names_to_import_for_impl_header(
+    ctx: &AssistContext<'_>,
+    impl_: &ast::Impl,
+) -> Vec<ast::NameRef> {
+    let Some(scope) = ctx.sema.scope(impl_.syntax()) else { return Vec::new() };
+    let parent_defs = scope.module().scope(ctx.db(), None);
+    let header = [
+        impl_.generic_param_list().map(|it| it.syntax().clone()),
+        impl_.self_ty().map(|it| it.syntax().clone()),
+        impl_.where_clause().map(|it| it.syntax().clone()),
+    ];
+    let mut names: Vec<ast::NameRef> = Vec::new();
+    for path in header.iter().flatten().flat_map(|it| it.descendants()).filter_map(ast::Path::cast)
+    {
+        // Only the first segment of a path has to be in scope.
+        if path.qualifier().is_some() {
+            continue;
+        }
+        let Some(name_ref) = path.segment().and_then(|it| it.name_ref()) else { continue };
+        let Some(PathResolution::Def(def)) = ctx.sema.resolve_path(&path) else { continue };
+        let in_parent = parent_defs.iter().any(|(name, it)| {
+            *it == ScopeDef::ModuleDef(def) && name.to_smol_str() == name_ref.text().as_str()
+        });
+        if in_parent && !names.iter().any(|it| it.text() == name_ref.text()) {
+            names.push(name_ref);
         }
     }
+    names
+}
+
+/// A mutable copy of `item` with copies of `attrs` in front of it, each on a line of its own.
+fn with_attrs<N: HasAttrs>(item: N, attrs: &[ast::Attr]) -> N {
+    let item = item.clone_subtree().clone_for_update();
+    for attr in attrs {
+        item.add_attr(attr.clone_subtree().clone_for_update());
+    }
+    item
+}
+
+/// The offset of the name of a generated module in its text.
+fn name_offset(module_def: &ast::Module) -> TextSize {
+    module_def.name().map_or(TextSize::of("mod "), |name| name.syntax().text_range().start())
+}
 
-    let module_name = module.name;
-    format!("mod {module_name} {{\n{body}\n{old_indent}}}")
+/// Fills the empty braces opened by `l_curly` with `items`, each starting on a new line at
+/// `indent` and separated by blank lines, and puts the closing brace at `closing_indent`. The
+/// elements of a single item, like the lines of a comment, go on consecutive lines.
+fn fill_braces(
+    l_curly: &SyntaxToken,
+    items: Vec<Vec<SyntaxElement>>,
+    indent: IndentLevel,
+    closing_indent: IndentLevel,
+) {
+    let mut elements: Vec<SyntaxElement> = Vec::new();
+    for (idx, item) in items.into_iter().enumerate() {
+        let newlines = if idx == 0 { "\n" } else { "\n\n" };
+        elements.push(make::tokens::whitespace(&format!("{newlines}{indent}")).into());
+        for (idx, element) in item.into_iter().enumerate() {
+            if idx != 0 {
+                elements.push(make::tokens::whitespace(&format!("\n{indent}")).into());
+            }
+            elements.push(element);
+        }
+    }
+    elements.push(make::tokens::whitespace(&format!("\n{closing_indent}")).into());
+    ted::insert_all(ted::Position::after(l_curly), elements);
 }
 
 #[derive(Debug)]
 struct Module {
     text_range: TextRange,
-    name: &'static str,
+    name: String,
     /// All items except use items.
     body_items: Vec<ast::Item>,
     /// Use items are kept separately as they help when the selection is inside an impl block,
     /// we can directly take these items and keep them outside generated impl block inside
     /// generated module.
     use_items: Vec<ast::Item>,
+    /// Comments between the selected items which aren't attached to any of them, grouped by
+    /// consecutive lines, along with the index of the body item they precede.
+    comments: Vec<(usize, Vec<ast::Comment>)>,
+    /// The module the items are moved into if it's anywhere else in the crate, see
+    /// [`move_to_module`]. Usages of the items go through `name` otherwise.
+    destination: Option<hir::Module>,
+    /// Whether the items used outside of the selection are re-exported from the parent module
+    /// instead of having their usages rewritten.
+    reexport: bool,
 }
 
-fn extract_target(node: &SyntaxNode, selection_range: TextRange) -> Option<Module> {
+fn extract_target(node: &SyntaxNode, selection_range: TextRange, name: String) -> Option<Module> {
     let selected_nodes = node
         .children()
         .filter(|node| selection_range.contains_range(node.text_range()))
         .chain(iter::once(node.clone()));
-    let (use_items, body_items) = selected_nodes
+    let (use_items, body_items): (Vec<_>, Vec<_>) = selected_nodes
         .filter_map(ast::Item::cast)
         .partition(|item| matches!(item, ast::Item::Use(..)));
 
-    Some(Module { text_range: selection_range, name: "modname", body_items, use_items })
-}
-
-impl Module {
-    fn get_usages_and_record_fields(
-        &self,
-        ctx: &AssistContext<'_>,
-    ) -> (FxHashMap<FileId, Vec<(TextRange, String)>>, Vec<SyntaxNode>, FxHashMap<TextSize, ast::Use>)
-    {
-        let mut adt_fields = Vec::new();
-        let mut refs: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
-        // use `TextSize` as key to avoid repeated use stmts
-        let mut use_stmts_to_be_inserted = FxHashMap::default();
-
-        //Here impl is not included as each item inside impl will be tied to the parent of
-        //implementing block(a struct, enum, etc), if the parent is in selected module, it will
-        //get updated by ADT section given below or if it is not, then we dont need to do any operation
-
-        for item in &self.body_items {
-            match_ast! {
-                match (item.syntax()) {
-                    ast::Adt(it) => {
-                        if let Some( nod ) = ctx.sema.to_def(&it) {
-                            let node_def = Definition::Adt(nod);
-                            self.expand_and_group_usages_file_wise(ctx, node_def, &mut refs, &mut use_stmts_to_be_inserted);
-
-                            //Enum Fields are not allowed to explicitly specify pub, it is implied
-                            match it {
-                                ast::Adt::Struct(x) => {
-                                    if let Some(field_list) = x.field_list() {
-                                        match field_list {
-                                            ast::FieldList::RecordFieldList(record_field_list) => {
-                                                record_field_list.fields().for_each(|record_field| {
-                                                    adt_fields.push(record_field.syntax().clone());
-                                                });
-                                            },
-                                            ast::FieldList::TupleFieldList(tuple_field_list) => {
-                                                tuple_field_list.fields().for_each(|tuple_field| {
-                                                    adt_fields.push(tuple_field.syntax().clone());
-                                                });
-                                            },
-                                        }
-                                    }
-                                },
-                                ast::Adt::Union(x) => {
-                                        if let Some(record_field_list) = x.record_field_list() {
-                                            record_field_list.fields().for_each(|record_field| {
-                                                    adt_fields.push(record_field.syntax().clone());
-                                            });
-                                        }
-                                },
-                                ast::Adt::Enum(_) => {},
-                            }
-                        }
-                    },
-                    ast::TypeAlias(it) => {
-                        if let Some( nod ) = ctx.sema.to_def(&it) {
-                            let node_def = Definition::TypeAlias(nod);
-                            self.expand_and_group_usages_file_wise(ctx, node_def, &mut refs, &mut use_stmts_to_be_inserted);
-                        }
-                    },
-                    ast::Const(it) => {
-                        if let Some( nod ) = ctx.sema.to_def(&it) {
-                            let node_def = Definition::Const(nod);
-                            self.expand_and_group_usages_file_wise(ctx, node_def, &mut refs, &mut use_stmts_to_be_inserted);
-                        }
-                    },
-                    ast::Static(it) => {
-                        if let Some( nod ) = ctx.sema.to_def(&it) {
-                            let node_def = Definition::Static(nod);
-                            self.expand_and_group_usages_file_wise(ctx, node_def, &mut refs, &mut use_stmts_to_be_inserted);
-                        }
-                    },
-                    ast::Fn(it) => {
-                        if let Some( nod ) = ctx.sema.to_def(&it) {
-                            let node_def = Definition::Function(nod);
-                            self.expand_and_group_usages_file_wise(ctx, node_def, &mut refs, &mut use_stmts_to_be_inserted);
-                        }
-                    },
-                    ast::Macro(it) => {
-                        if let Some(nod) = ctx.sema.to_def(&it) {
-                            self.expand_and_group_usages_file_wise(ctx, Definition::Macro(nod), &mut refs, &mut use_stmts_to_be_inserted);
-                        }
-                    },
-                    _ => (),
-                }
-            }
+    // Comments right in front of an item are part of it already.
+    let mut comments: Vec<(usize, Vec<ast::Comment>)> = Vec::new();
+    let mut on_next_line = false;
+    let elements =
+        if ast::Item::can_cast(node.kind()) { None } else { Some(node.children_with_tokens()) };
+    for element in elements.into_iter().flatten() {
+        if !selection_range.contains_range(element.text_range()) {
+            continue;
         }
-
-        (refs, adt_fields, use_stmts_to_be_inserted)
-    }
-
-    fn expand_and_group_usages_file_wise(
-        &self,
-        ctx: &AssistContext<'_>,
-        node_def: Definition,
-        refs_in_files: &mut FxHashMap<FileId, Vec<(TextRange, String)>>,
-        use_stmts_to_be_inserted: &mut FxHashMap<TextSize, ast::Use>,
-    ) {
-        let mod_name = self.name;
-        let covering_node = match ctx.covering_element() {
-            syntax::NodeOrToken::Node(node) => node,
-            syntax::NodeOrToken::Token(tok) => tok.parent().unwrap(), // won't panic
+        let comment = match element.into_token() {
+            Some(token) if token.kind() == WHITESPACE => {
+                on_next_line &= token.text().matches('\n').count() == 1;
+                continue;
+            }
+            Some(token) => ast::Comment::cast(token)
+                .filter(|it| it.kind().doc != Some(ast::CommentPlacement::Inner)),
+            None => None,
         };
-        let out_of_sel = |node: &SyntaxNode| !self.text_range.contains_range(node.text_range());
-        let mut use_stmts_set = FxHashSet::default();
-
-        for (file_id, refs) in node_def.usages(&ctx.sema).all() {
-            let source_file = ctx.sema.parse(file_id);
-            let usages = refs.into_iter().filter_map(|FileReference { range, .. }| {
-                // handle normal usages
-                let name_ref = find_node_at_range::<ast::NameRef>(source_file.syntax(), range)?;
-
-                if out_of_sel(name_ref.syntax()) {
-                    let new_ref = format!("{mod_name}::{name_ref}");
-                    return Some((range, new_ref));
-                } else if let Some(use_) = name_ref.syntax().ancestors().find_map(ast::Use::cast) {
-                    // handle usages in use_stmts which is in_sel
-                    // check if `use` is top stmt in selection
-                    if use_.syntax().parent().is_some_and(|parent| parent == covering_node)
-                        && use_stmts_set.insert(use_.syntax().text_range().start())
-                    {
-                        let use_ = use_stmts_to_be_inserted
-                            .entry(use_.syntax().text_range().start())
-                            .or_insert_with(|| use_.clone_subtree().clone_for_update());
-                        for seg in use_
-                            .syntax()
-                            .descendants()
-                            .filter_map(ast::NameRef::cast)
-                            .filter(|seg| seg.syntax().to_string() == name_ref.to_string())
-                        {
-                            let new_ref = make::path_from_text(&format!("{mod_name}::{seg}"))
-                                .clone_for_update();
-                            ted::replace(seg.syntax().parent()?, new_ref.syntax());
-                        }
-                    }
-                }
-
-                None
-            });
-            refs_in_files.entry(file_id).or_default().extend(usages);
+        let Some(comment) = comment else {
+            on_next_line = false;
+            continue;
+        };
+        match comments.last_mut() {
+            Some((_, lines)) if on_next_line => lines.push(comment),
+            _ => {
+                let start = comment.syntax().text_range().start();
+                let next_item = body_items
+                    .iter()
+                    .take_while(|item| item.syntax().text_range().start() < start)
+                    .count();
+                comments.push((next_item, vec![comment]));
+            }
         }
+        on_next_line = true;
     }
 
-    fn change_visibility(&mut self, record_fields: Vec<SyntaxNode>) {
-        let (mut replacements, record_field_parents, impls) =
-            get_replacements_for_visibility_change(&mut self.body_items, false);
+    Some(Module {
+        text_range: selection_range,
+        name,
+        body_items,
+        use_items,
+        comments,
+        destination: None,
+        reexport: false,
+    })
+}
 
-        let mut impl_items = impls
+impl Module {
+    /// The `#[cfg]` attributes shared by all of the body items, which the new module gets
+    /// instead of them.
+    fn common_cfgs(&self) -> Vec<ast::Attr> {
+        let cfgs = |item: &ast::Item| item.attrs().filter(is_cfg).collect_vec();
+        let Some((first, rest)) = self.body_items.split_first() else { return Vec::new() };
+        cfgs(first)
             .into_iter()
-            .flat_map(|impl_| impl_.syntax().descendants())
-            .filter_map(ast::Item::cast)
-            .collect_vec();
-
-        let (mut impl_item_replacements, _, _) =
-            get_replacements_for_visibility_change(&mut impl_items, true);
-
-        replacements.append(&mut impl_item_replacements);
+            .filter(|cfg| {
+                rest.iter()
+                    .all(|item| cfgs(item).iter().any(|it| it.to_string() == cfg.to_string()))
+            })
+            .collect()
+    }
+}
 
-        for (_, field_owner) in record_field_parents {
-            for desc in field_owner.descendants().filter_map(ast::RecordField::cast) {
-                let is_record_field_present =
-                    record_fields.clone().into_iter().any(|x| x.to_string() == desc.to_string());
-                if is_record_field_present {
-                    replacements.push((desc.visibility(), desc.syntax().clone()));
-                }
-            }
-        }
+/// The visibility to give to a moved item according to the configured style, `required` being the
+/// narrowest one its usages need.
+pub(super) fn configured_visibility(
+    ctx: &AssistContext<'_>,
+    required: ast::Visibility,
+) -> ast::Visibility {
+    match ctx.config.extract_module_visibility {
+        ExtractModuleVisibility::PubCrate => make::visibility_pub_crate(),
+        ExtractModuleVisibility::Pub => make::visibility_pub(),
+        ExtractModuleVisibility::PubSuper | ExtractModuleVisibility::Reexport => required,
+    }
+}
 
-        for (vis, syntax) in replacements {
-            let item = syntax.children_with_tokens().find(|node_or_token| {
-                match node_or_token.kind() {
-                    // We're skipping comments, doc comments, and attribute macros that may precede the keyword
-                    // that the visibility should be placed before.
-                    SyntaxKind::COMMENT | SyntaxKind::ATTR | SyntaxKind::WHITESPACE => false,
-                    _ => true,
+/// Mutable copies of `items` to edit in place. The tree they're part of is cloned only once,
+/// rather than each item on its own, and the copies keep the text ranges of the originals until
+/// they're edited.
+fn make_items_mut(items: &[ast::Item]) -> Vec<ast::Item> {
+    let mut roots: Vec<(SyntaxNode, SyntaxNode)> = Vec::new();
+    items
+        .iter()
+        .map(|item| {
+            let root = item.syntax().ancestors().last().unwrap_or_else(|| item.syntax().clone());
+            let root_mut = match roots.iter().find(|(it, _)| *it == root) {
+                Some((_, root_mut)) => root_mut.clone(),
+                None => {
+                    let root_mut = root.clone_for_update();
+                    roots.push((root, root_mut.clone()));
+                    root_mut
                 }
-            });
+            };
+            counterpart(&root_mut, &item.syntax().clone().into())
+                .and_then(|it| ast::Item::cast(it.into_node()?))
+                .unwrap_or_else(|| item.clone_for_update())
+        })
+        .collect()
+}
 
-            add_change_vis(vis, item);
-        }
+/// The element of `copy` corresponding to `element`, where `copy` is part of an unedited copy of
+/// the tree `element` is in.
+fn counterpart(copy: &SyntaxNode, element: &SyntaxElement) -> Option<SyntaxElement> {
+    let range = element.text_range();
+    if !copy.text_range().contains_range(range) {
+        return None;
     }
+    let node = match copy.covering_element(range) {
+        NodeOrToken::Token(token) if token.kind() == element.kind() => return Some(token.into()),
+        NodeOrToken::Token(token) => token.parent()?,
+        NodeOrToken::Node(node) => node,
+    };
+    node.ancestors()
+        .take_while(|it| it.text_range() == range)
+        .find(|it| it.kind() == element.kind())
+        .map(Into::into)
+}
 
-    fn resolve_imports(
-        &mut self,
-        module: Option<ast::Module>,
-        ctx: &AssistContext<'_>,
-    ) -> Vec<TextRange> {
-        let mut imports_to_remove = vec![];
-        let mut node_set = FxHashSet::default();
-
-        for item in self.body_items.clone() {
-            item.syntax()
-                .descendants()
-                .filter_map(|x| {
-                    if let Some(name) = ast::Name::cast(x.clone()) {
-                        NameClass::classify(&ctx.sema, &name).and_then(|nc| match nc {
-                            NameClass::Definition(def) => Some((name.syntax().clone(), def)),
-                            _ => None,
-                        })
-                    } else if let Some(name_ref) = ast::NameRef::cast(x) {
-                        NameRefClass::classify(&ctx.sema, &name_ref).and_then(|nc| match nc {
-                            NameRefClass::Definition(def) => Some((name_ref.syntax().clone(), def)),
-                            _ => None,
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .for_each(|(node, def)| {
-                    if node_set.insert(node.to_string()) {
-                        if let Some(import) = self.process_def_in_sel(def, &node, &module, ctx) {
-                            check_intersection_and_push(&mut imports_to_remove, import);
-                        }
-                    }
-                })
-        }
-
-        imports_to_remove
-    }
-
-    fn process_def_in_sel(
-        &mut self,
-        def: Definition,
-        use_node: &SyntaxNode,
-        curr_parent_module: &Option<ast::Module>,
-        ctx: &AssistContext<'_>,
-    ) -> Option<TextRange> {
-        //We only need to find in the current file
-        let selection_range = ctx.selection_trimmed();
-        let file_id = ctx.file_id();
-        let usage_res = def.usages(&ctx.sema).in_scope(&SearchScope::single_file(file_id)).all();
-        let file = ctx.sema.parse(file_id);
-
-        // track uses which does not exists in `Use`
-        let mut uses_exist_in_sel = false;
-        let mut uses_exist_out_sel = false;
-        'outside: for (_, refs) in usage_res.iter() {
-            for x in refs
-                .iter()
-                .filter(|x| find_node_at_range::<ast::Use>(file.syntax(), x.range).is_none())
-                .filter_map(|x| find_node_at_range::<ast::Path>(file.syntax(), x.range))
-            {
-                let in_selection = selection_range.contains_range(x.syntax().text_range());
-                uses_exist_in_sel |= in_selection;
-                uses_exist_out_sel |= !in_selection;
-
-                if uses_exist_in_sel && uses_exist_out_sel {
-                    break 'outside;
-                }
+/// Replaces the elements of the `replacements` in place, which are part of the mutable items,
+/// deleting the ones without a replacement.
+fn replace_elements(replacements: Vec<(SyntaxElement, Option<SyntaxNode>)>) {
+    for (element, replacement) in replacements {
+        match replacement {
+            Some(replacement) => {
+                ted::replace(element, replacement.clone_subtree().clone_for_update())
             }
+            None => ted::remove(element),
         }
+    }
+}
 
-        let (def_in_mod, def_out_sel) =
-            check_def_in_mod_and_out_sel(def, ctx, curr_parent_module, selection_range, file_id);
-
-        // Find use stmt that use def in current file
-        let use_stmt: Option<ast::Use> = usage_res
-            .into_iter()
-            .filter(|(use_file_id, _)| *use_file_id == file_id)
-            .flat_map(|(_, refs)| refs.into_iter().rev())
-            .find_map(|fref| find_node_at_range(file.syntax(), fref.range));
-        let use_stmt_not_in_sel = use_stmt.as_ref().is_some_and(|use_stmt| {
-            !selection_range.contains_range(use_stmt.syntax().text_range())
-        });
-
-        let mut use_tree_paths: Option<Vec<ast::Path>> = None;
-        //Exists inside and outside selection
-        // - Use stmt for item is present -> get the use_tree_str and reconstruct the path in new
-        // module
-        // - Use stmt for item is not present ->
-        //If it is not found, the definition is either ported inside new module or it stays
-        //outside:
-        //- Def is inside: Nothing to import
-        //- Def is outside: Import it inside with super
-
-        //Exists inside selection but not outside -> Check for the import of it in original module,
-        //get the use_tree_str, reconstruct the use stmt in new module
-
-        let mut import_path_to_be_removed: Option<TextRange> = None;
-        if uses_exist_in_sel && uses_exist_out_sel {
-            //Changes to be made only inside new module
-
-            //If use_stmt exists, find the use_tree_str, reconstruct it inside new module
-            //If not, insert a use stmt with super and the given nameref
-            match self.process_use_stmt_for_import_resolve(use_stmt, use_node) {
-                Some((use_tree_str, _)) => use_tree_paths = Some(use_tree_str),
-                None if def_in_mod && def_out_sel => {
-                    //Considered only after use_stmt is not present
-                    //def_in_mod && def_out_sel | exists_outside_sel(exists_inside_sel =
-                    //true for all cases)
-                    // false | false -> Do nothing
-                    // false | true -> If source is in selection -> nothing to do, If source is outside
-                    // mod -> ust_stmt transversal
-                    // true  | false -> super import insertion
-                    // true  | true -> super import insertion
-                    self.make_use_stmt_of_node_with_super(use_node);
-                }
-                None => {}
-            }
-        } else if uses_exist_in_sel && !uses_exist_out_sel {
-            //Changes to be made inside new module, and remove import from outside
-
-            if let Some((mut use_tree_str, text_range_opt)) =
-                self.process_use_stmt_for_import_resolve(use_stmt, use_node)
-            {
-                if let Some(text_range) = text_range_opt {
-                    import_path_to_be_removed = Some(text_range);
-                }
-
-                if def_in_mod && def_out_sel {
-                    if let Some(first_path_in_use_tree) = use_tree_str.last() {
-                        let first_path_in_use_tree_str = first_path_in_use_tree.to_string();
-                        if !first_path_in_use_tree_str.contains("super")
-                            && !first_path_in_use_tree_str.contains("crate")
-                        {
-                            let super_path = make::ext::ident_path("super");
-                            use_tree_str.push(super_path);
-                        }
-                    }
-                }
+fn has_ident(node: &SyntaxNode, ident: &str) -> bool {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .any(|token| token.kind() == IDENT && token.text() == ident)
+}
 
-                use_tree_paths = Some(use_tree_str);
-            } else if def_in_mod && def_out_sel {
-                self.make_use_stmt_of_node_with_super(use_node);
-            }
+/// Edits appending the selected items to `target`, the item list of an inline module or a whole
+/// file. New imports go after the ones already in there, unless they are already present.
+fn items_for_existing_module(
+    module: &Module,
+    target: &Either<ast::ItemList, ast::SourceFile>,
+    old_indent: IndentLevel,
+) -> Vec<(TextRange, String)> {
+    // The items are added to a copy of the target, which is then diffed against the original one,
+    // as the other edits to the file are textual.
+    let (target, copy) = match target {
+        Either::Left(item_list) => {
+            let copy = item_list.clone_subtree().clone_for_update();
+            (item_list.syntax(), Either::Left(copy))
         }
-
-        if let Some(mut use_tree_paths) = use_tree_paths {
-            use_tree_paths.reverse();
-
-            if uses_exist_out_sel || !uses_exist_in_sel || !def_in_mod || !def_out_sel {
-                if let Some(first_path_in_use_tree) = use_tree_paths.first() {
-                    if first_path_in_use_tree.to_string().contains("super") {
-                        use_tree_paths.insert(0, make::ext::ident_path("super"));
-                    }
-                }
-            }
-
-            let is_item = matches!(
-                def,
-                Definition::Macro(_)
-                    | Definition::Module(_)
-                    | Definition::Function(_)
-                    | Definition::Adt(_)
-                    | Definition::Const(_)
-                    | Definition::Static(_)
-                    | Definition::Trait(_)
-                    | Definition::TraitAlias(_)
-                    | Definition::TypeAlias(_)
-            );
-
-            if (def_out_sel || !is_item) && use_stmt_not_in_sel {
-                let use_ = make::use_(
-                    None,
-                    make::use_tree(make::join_paths(use_tree_paths), None, None, false),
-                );
-                self.use_items.insert(0, ast::Item::from(use_));
-            }
+        Either::Right(file) => {
+            (file.syntax(), Either::Right(file.clone_subtree().clone_for_update()))
         }
-
-        import_path_to_be_removed
-    }
-
-    fn make_use_stmt_of_node_with_super(&mut self, node_syntax: &SyntaxNode) -> ast::Item {
-        let super_path = make::ext::ident_path("super");
-        let node_path = make::ext::ident_path(&node_syntax.to_string());
-        let use_ = make::use_(
-            None,
-            make::use_tree(make::join_paths(vec![super_path, node_path]), None, None, false),
-        );
-
-        let item = ast::Item::from(use_);
-        self.use_items.insert(0, item.clone());
-        item
-    }
-
-    fn process_use_stmt_for_import_resolve(
-        &self,
-        use_stmt: Option<ast::Use>,
-        node_syntax: &SyntaxNode,
-    ) -> Option<(Vec<ast::Path>, Option<TextRange>)> {
-        let use_stmt = use_stmt?;
-        for path_seg in use_stmt.syntax().descendants().filter_map(ast::PathSegment::cast) {
-            if path_seg.syntax().to_string() == node_syntax.to_string() {
-                let mut use_tree_str = vec![path_seg.parent_path()];
-                get_use_tree_paths_from_path(path_seg.parent_path(), &mut use_tree_str);
-
-                //Here we are looking for use_tree with same string value as node
-                //passed above as the range_to_remove function looks for a comma and
-                //then includes it in the text range to remove it. But the comma only
-                //appears at the use_tree level
-                for use_tree in path_seg.syntax().ancestors().filter_map(ast::UseTree::cast) {
-                    if use_tree.syntax().to_string() == node_syntax.to_string() {
-                        return Some((use_tree_str, Some(range_to_remove(use_tree.syntax()))));
-                    }
-                }
-
-                return Some((use_tree_str, None));
-            }
+    };
+    let add = |item: &ast::Item, placement| {
+        let item = item.dedent(old_indent).clone_subtree().clone_for_update();
+        match &copy {
+            Either::Left(item_list) => add_item_to_module(item_list, item, placement),
+            Either::Right(file) => add_item_to_module(file, item, placement),
         }
+    };
 
-        None
+    let existing_use_texts: FxHashSet<String> = match &copy {
+        Either::Left(item_list) => item_list.items().collect_vec(),
+        Either::Right(file) => file.items().collect_vec(),
     }
+    .into_iter()
+    .filter(|item| matches!(item, ast::Item::Use(_)))
+    .map(|item| item.to_string())
+    .collect();
+    module
+        .use_item_groups()
+        .iter()
+        .flatten()
+        .filter(|item| !existing_use_texts.contains(&item.to_string()))
+        .for_each(|item| add(item, ItemPlacement::AfterImports));
+    module.body_items.iter().for_each(|item| add(item, ItemPlacement::End));
+
+    let copy = copy.either(|it| it.syntax().clone(), |it| it.syntax().clone());
+    let mut edit = TextEdit::builder();
+    algo::diff(target, &copy).into_text_edit(&mut edit);
+    edit.finish().into_iter().map(|indel| (indel.delete, indel.insert)).collect()
 }
 
-fn check_intersection_and_push(
-    import_paths_to_be_removed: &mut Vec<TextRange>,
-    mut import_path: TextRange,
-) {
-    // Text ranges received here for imports are extended to the
-    // next/previous comma which can cause intersections among them
-    // and later deletion of these can cause panics similar
-    // to reported in #11766. So to mitigate it, we
-    // check for intersection between all current members
-    // and combine all such ranges into one.
-    let s: SmallVec<[_; 2]> = import_paths_to_be_removed
-        .iter_mut()
-        .positions(|it| it.intersect(import_path).is_some())
-        .collect();
-    for pos in s.into_iter().rev() {
-        let intersecting_path = import_paths_to_be_removed.swap_remove(pos);
-        import_path = import_path.cover(intersecting_path);
+/// Extends `range` over the whitespace following it, so that removing it doesn't leave a gap.
+fn range_with_trailing_whitespace(node: &SyntaxNode, range: TextRange) -> TextRange {
+    let root = node.ancestors().last().unwrap_or_else(|| node.clone());
+    match root.token_at_offset(range.end()).right_biased() {
+        Some(ws) if ws.kind() == WHITESPACE && ws.text_range().start() == range.end() => {
+            range.cover(ws.text_range())
+        }
+        _ => range,
     }
-    import_paths_to_be_removed.push(import_path);
 }
 
-fn check_def_in_mod_and_out_sel(
-    def: Definition,
-    ctx: &AssistContext<'_>,
-    curr_parent_module: &Option<ast::Module>,
-    selection_range: TextRange,
-    curr_file_id: FileId,
-) -> (bool, bool) {
-    macro_rules! check_item {
-        ($x:ident) => {
-            if let Some(source) = $x.source(ctx.db()) {
-                let have_same_parent = if let Some(ast_module) = &curr_parent_module {
-                    ctx.sema.to_module_def(ast_module).is_some_and(|it| it == $x.module(ctx.db()))
-                } else {
-                    source.file_id.original_file(ctx.db()) == curr_file_id
-                };
+/// The impl blocks the selection extracts items out of, along with the selected items of each.
+/// Empty unless the selection is inside of an impl, or spans several impls without covering all
+/// of them completely, as whole impls are moved like any other item.
+fn selected_impl_items(
+    node: &SyntaxNode,
+    selection: TextRange,
+) -> Vec<(ast::Impl, Vec<ast::AssocItem>)> {
+    let impl_of = |list: ast::AssocItemList| ast::Impl::cast(list.syntax().parent()?);
+    let items_in_selection = |impl_: &ast::Impl| -> Vec<ast::AssocItem> {
+        impl_
+            .assoc_item_list()
+            .into_iter()
+            .flat_map(|list| list.assoc_items())
+            .filter(|item| selection.contains_range(item.syntax().text_range()))
+            .collect()
+    };
 
-                let in_sel = !selection_range.contains_range(source.value.syntax().text_range());
-                return (have_same_parent, in_sel);
-            }
-        };
+    if let Some(item) = ast::AssocItem::cast(node.clone()) {
+        let impl_ = node.parent().and_then(ast::AssocItemList::cast).and_then(impl_of);
+        return impl_.map(|impl_| vec![(impl_, vec![item])]).unwrap_or_default();
+    }
+    if let Some(impl_) = ast::AssocItemList::cast(node.clone()).and_then(impl_of) {
+        let items = items_in_selection(&impl_);
+        return vec![(impl_, items)];
     }
 
-    match def {
-        Definition::Module(x) => {
-            let source = x.definition_source(ctx.db());
-            let have_same_parent = match (&curr_parent_module, x.parent(ctx.db())) {
-                (Some(ast_module), Some(hir_module)) => {
-                    ctx.sema.to_module_def(ast_module).is_some_and(|it| it == hir_module)
-                }
-                _ => source.file_id.original_file(ctx.db()) == curr_file_id,
-            };
-
-            if have_same_parent {
-                if let ModuleSource::Module(module_) = source.value {
-                    let in_sel = !selection_range.contains_range(module_.syntax().text_range());
-                    return (have_same_parent, in_sel);
-                }
-            }
-
-            return (have_same_parent, false);
-        }
-        Definition::Function(x) => check_item!(x),
-        Definition::Adt(x) => check_item!(x),
-        Definition::Variant(x) => check_item!(x),
-        Definition::Const(x) => check_item!(x),
-        Definition::Static(x) => check_item!(x),
-        Definition::Trait(x) => check_item!(x),
-        Definition::TypeAlias(x) => check_item!(x),
-        _ => {}
-    }
-
-    (false, false)
-}
-
-fn get_replacements_for_visibility_change(
-    items: &mut [ast::Item],
-    is_clone_for_updated: bool,
-) -> (
-    Vec<(Option<ast::Visibility>, SyntaxNode)>,
-    Vec<(Option<ast::Visibility>, SyntaxNode)>,
-    Vec<ast::Impl>,
-) {
-    let mut replacements = Vec::new();
-    let mut record_field_parents = Vec::new();
     let mut impls = Vec::new();
-
-    for item in items {
-        if !is_clone_for_updated {
-            *item = item.clone_for_update();
-        }
-        //Use stmts are ignored
-        macro_rules! push_to_replacement {
-            ($it:ident) => {
-                replacements.push(($it.visibility(), $it.syntax().clone()))
-            };
-        }
-
-        match item {
-            ast::Item::Const(it) => push_to_replacement!(it),
-            ast::Item::Enum(it) => push_to_replacement!(it),
-            ast::Item::ExternCrate(it) => push_to_replacement!(it),
-            ast::Item::Fn(it) => push_to_replacement!(it),
-            //Associated item's visibility should not be changed
-            ast::Item::Impl(it) if it.for_token().is_none() => impls.push(it.clone()),
-            ast::Item::MacroDef(it) => push_to_replacement!(it),
-            ast::Item::Module(it) => push_to_replacement!(it),
-            ast::Item::Static(it) => push_to_replacement!(it),
-            ast::Item::Struct(it) => {
-                push_to_replacement!(it);
-                record_field_parents.push((it.visibility(), it.syntax().clone()));
-            }
-            ast::Item::Trait(it) => push_to_replacement!(it),
-            ast::Item::TypeAlias(it) => push_to_replacement!(it),
-            ast::Item::Union(it) => {
-                push_to_replacement!(it);
-                record_field_parents.push((it.visibility(), it.syntax().clone()));
-            }
-            _ => (),
+    let mut partially_selected = false;
+    let intersecting = node
+        .children()
+        .filter(|child| selection.intersect(child.text_range()).is_some_and(|it| !it.is_empty()));
+    for child in intersecting {
+        let Some(impl_) = ast::Impl::cast(child) else { return Vec::new() };
+        partially_selected |= !selection.contains_range(impl_.syntax().text_range());
+        let items = items_in_selection(&impl_);
+        if !items.is_empty() {
+            impls.push((impl_, items));
         }
     }
-
-    (replacements, record_field_parents, impls)
-}
-
-fn get_use_tree_paths_from_path(
-    path: ast::Path,
-    use_tree_str: &mut Vec<ast::Path>,
-) -> Option<&mut Vec<ast::Path>> {
-    path.syntax()
-        .ancestors()
-        .filter(|x| x.to_string() != path.to_string())
-        .filter_map(ast::UseTree::cast)
-        .find_map(|use_tree| {
-            if let Some(upper_tree_path) = use_tree.path() {
-                if upper_tree_path.to_string() != path.to_string() {
-                    use_tree_str.push(upper_tree_path.clone());
-                    get_use_tree_paths_from_path(upper_tree_path, use_tree_str);
-                    return Some(use_tree);
-                }
-            }
-            None
-        })?;
-
-    Some(use_tree_str)
+    if !partially_selected {
+        return Vec::new();
+    }
+    impls
 }
 
-fn add_change_vis(vis: Option<ast::Visibility>, node_or_token_opt: Option<syntax::SyntaxElement>) {
-    if vis.is_none() {
-        if let Some(node_or_token) = node_or_token_opt {
-            let pub_crate_vis = make::visibility_pub_crate().clone_for_update();
-            ted::insert(ted::Position::before(node_or_token), pub_crate_vis.syntax());
-        }
+/// The range of a run of adjacent assoc items to be removed from an impl, along with the
+/// whitespace before them.
+fn range_of_assoc_items(items: &[ast::AssocItem]) -> TextRange {
+    let first = items[0].syntax();
+    let mut range = first.text_range().cover(items[items.len() - 1].syntax().text_range());
+    if let Some(indent) = indent_range_before_given_node(first) {
+        range = range.cover(indent);
     }
+    // Don't leave a blank line at the start of the impl.
+    let is_first_item = first.prev_sibling().is_none();
+    let next_whitespace = items[items.len() - 1]
+        .syntax()
+        .next_sibling_or_token()
+        .and_then(|it| it.into_token())
+        .filter(|it| it.kind() == WHITESPACE && it.text().starts_with("\n\n"));
+    if let Some(whitespace) = next_whitespace.filter(|_| is_first_item) {
+        range = range.cover_offset(whitespace.text_range().start() + TextSize::of('\n'));
+    }
+    range
 }
 
 fn indent_range_before_given_node(node: &SyntaxNode) -> Option<TextRange> {
-    node.siblings_with_tokens(syntax::Direction::Prev)
+    node.siblings_with_tokens(Direction::Prev)
         .find(|x| x.kind() == WHITESPACE)
         .map(|x| x.text_range())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable};
+    use hir::Semantics;
+    use ide_db::{base_db::FileRange, RootDatabase};
+    use test_fixture::WithFixture;
+
+    use crate::{
+        tests::{
+            check_assist_by_label, check_assist_by_label_with_config, check_assist_not_applicable,
+            check_assist_not_applicable_with_parameter, check_assist_unresolved,
+            check_assist_with_parameter, TEST_CONFIG,
+        },
+        AssistConfig, AssistResolveStrategy,
+    };
 
     use super::*;
 
+    /// Checks the plain extraction, the other variants are checked by their labels.
+    #[track_caller]
+    fn check_assist(assist: crate::handlers::Handler, before: &str, after: &str) {
+        check_assist_by_label(assist, before, after, "Extract Module");
+    }
+
     #[test]
     fn test_not_applicable_without_selection() {
         check_assist_not_applicable(
@@ -811,15 +1071,58 @@ fn test_not_applicable_without_selection() {
     }
 
     #[test]
-    fn test_extract_module() {
-        check_assist(
+    fn test_edit_is_computed_lazily() {
+        check_assist_unresolved(
             extract_module,
             r"
-            mod thirdpartycrate {
-                pub mod nest {
-                    pub struct SomeType;
-                    pub struct SomeType2;
-                }
+$0struct PrivateStruct;
+
+fn foo() -> PrivateStruct {
+    PrivateStruct
+}$0
+
+fn bar() -> PrivateStruct {
+    foo()
+}
+",
+        );
+    }
+
+    #[test]
+    fn test_usages_are_searched_on_resolve() {
+        // The counterpart of the test above, `check_assist_unresolved` makes sure that nothing was
+        // searched for.
+        let (db, file_id, range) = RootDatabase::with_range_or_offset(
+            r"
+$0struct PrivateStruct;
+
+fn foo() -> PrivateStruct {
+    PrivateStruct
+}$0
+
+fn bar() -> PrivateStruct {
+    foo()
+}
+",
+        );
+        let frange = FileRange { file_id, range: range.into() };
+        let ctx = AssistContext::new(Semantics::new(&db), &TEST_CONFIG, frange);
+        let mut acc = Assists::new(&ctx, AssistResolveStrategy::All);
+        extract_module(&mut acc, &ctx);
+        assert!(!acc.finish().is_empty());
+        assert!(ctx.has_searched_usages());
+    }
+
+    #[test]
+    fn test_extract_module() {
+        check_assist(
+            extract_module,
+            r"
+            mod thirdpartycrate {
+                pub mod nest {
+                    pub struct SomeType;
+                    pub struct SomeType2;
+                }
                 pub struct SomeType1;
             }
 
@@ -874,26 +1177,26 @@ mod bar {
                 use crate::thirdpartycrate::{nest::{SomeType2}, SomeType1};
 
                 pub struct PublicStruct {
-                    field: modname::PrivateStruct,
+                    field: ${0:modname}::PrivateStruct,
                     field1: SomeType1,
                 }
 
                 impl PublicStruct {
                     pub fn new() -> Self {
-                        Self { field: modname::PrivateStruct::new(), field1: SomeType1 }
+                        Self { field: ${0:modname}::PrivateStruct::new(), field1: SomeType1 }
                     }
                 }
 
                 fn foo() {
-                    let _s = modname::PrivateStruct::new();
-                    let _a = modname::bar();
+                    let _s = ${0:modname}::PrivateStruct::new();
+                    let _a = ${0:modname}::bar();
                 }
 
-mod modname {
+mod ${0:modname} {
     use crate::thirdpartycrate::nest::SomeType;
 
-    pub(crate) struct PrivateStruct {
-        pub(crate) inner: SomeType,
+    pub(super) struct PrivateStruct {
+        inner: SomeType,
     }
 
     pub struct PrivateStruct1 {
@@ -901,12 +1204,12 @@ pub struct PrivateStruct1 {
     }
 
     impl PrivateStruct {
-        pub(crate) fn new() -> Self {
+        pub(super) fn new() -> Self {
              PrivateStruct { inner: SomeType }
         }
     }
 
-    pub(crate) fn bar() -> i32 {
+    pub(super) fn bar() -> i32 {
         2
     }
 }
@@ -915,6 +1218,416 @@ pub(crate) fn bar() -> i32 {
         );
     }
 
+    #[test]
+    fn test_extract_module_infers_minimal_visibility() {
+        check_assist_by_label(
+            extract_module,
+            r"
+mod outer {
+$0pub struct Kept;
+
+struct Used {
+    used: i32,
+    unused: i32,
+}
+
+fn used_in_child() {}
+
+fn unused() {}$0
+
+    fn parent(it: Used) -> i32 {
+        it.used
+    }
+
+    mod child {
+        fn f() {
+            super::used_in_child();
+        }
+    }
+}
+",
+            r"
+mod outer {
+mod ${0:modname} {
+    pub struct Kept;
+
+    pub(super) struct Used {
+        pub(super) used: i32,
+        unused: i32,
+    }
+
+    pub(super) fn used_in_child() {}
+
+    fn unused() {}
+}
+
+    fn parent(it: ${0:modname}::Used) -> i32 {
+        it.used
+    }
+
+    mod child {
+        fn f() {
+            super::${0:modname}::used_in_child();
+        }
+    }
+}
+",
+            "Extract Module",
+        );
+    }
+
+    #[test]
+    fn test_extract_module_extends_super_chains() {
+        check_assist_by_label(
+            extract_module,
+            r"
+fn top() {}
+
+mod outer {
+$0fn a() {
+    super::top();
+}
+
+mod inner {
+    fn b() {
+        super::a();
+        super::super::top();
+    }
+}$0
+}
+",
+            r"
+fn top() {}
+
+mod outer {
+mod ${0:modname} {
+    fn a() {
+        super::super::top();
+    }
+
+    mod inner {
+        fn b() {
+            super::a();
+            super::super::super::top();
+        }
+    }
+}
+}
+",
+            "Extract Module",
+        );
+    }
+
+    #[test]
+    fn test_move_into_existing_module_extends_super_chains() {
+        check_assist_by_label(
+            extract_module,
+            r"
+fn top() {}
+
+mod outer {
+mod util {}
+
+$0fn foo() {
+    super::top();
+}$0
+
+fn bar() {}
+}
+",
+            r"
+fn top() {}
+
+mod outer {
+mod util {
+    fn foo() {
+        super::super::top();
+    }
+}
+
+fn bar() {}
+}
+",
+            "Move into existing module `util`",
+        );
+    }
+
+    #[test]
+    fn test_extract_module_keeps_multi_line_string_literals() {
+        check_assist(
+            extract_module,
+            r##"
+mod outer {
+    $0fn text() -> &'static str {
+        "first
+  second"
+    }
+
+    fn raw() -> &'static str {
+        r#"
+    indented
+"#
+    }$0
+}
+"##,
+            r##"
+mod outer {
+    mod ${0:modname} {
+        fn text() -> &'static str {
+            "first
+  second"
+        }
+
+        fn raw() -> &'static str {
+            r#"
+    indented
+"#
+        }
+    }
+}
+"##,
+        )
+    }
+
+    #[test]
+    fn test_move_into_existing_module() {
+        check_assist_by_label(
+            extract_module,
+            r"
+mod util {
+    pub(super) fn helper() -> i32 {
+        1
+    }
+}
+
+$0fn foo() -> i32 {
+    util::helper() + 1
+}$0
+
+fn bar() -> i32 {
+    foo()
+}
+",
+            r"
+mod util {
+    pub(super) fn helper() -> i32 {
+        1
+    }
+
+    pub(super) fn foo() -> i32 {
+        helper() + 1
+    }
+}
+
+fn bar() -> i32 {
+    util::foo()
+}
+",
+            "Move into existing module `util`",
+        );
+    }
+
+    #[test]
+    fn test_move_into_existing_empty_module() {
+        check_assist_by_label(
+            extract_module,
+            r"
+mod util {}
+
+$0struct S;$0
+
+fn f(_: S) {}
+",
+            r"
+mod util {
+    pub(super) struct S;
+}
+
+fn f(_: util::S) {}
+",
+            "Move into existing module `util`",
+        );
+    }
+
+    #[test]
+    fn test_move_to_module_in_other_file() {
+        check_assist_with_parameter(
+            extract_module,
+            "crate::foo",
+            r"
+//- /main.rs
+mod foo;
+
+$0fn bar() -> i32 {
+    2
+}$0
+
+fn baz() -> i32 {
+    bar()
+}
+//- /foo.rs
+pub fn qux() {}
+",
+            r"
+//- /main.rs
+mod foo;
+
+fn baz() -> i32 {
+    foo::bar()
+}
+//- /foo.rs
+pub fn qux() {}
+
+pub(super) fn bar() -> i32 {
+    2
+}
+",
+            "Move to another module",
+        );
+    }
+
+    #[test]
+    fn test_move_to_unresolved_module() {
+        check_assist_not_applicable_with_parameter(
+            extract_module,
+            "crate::missing",
+            r"
+mod foo {}
+
+$0fn bar() -> i32 {
+    2
+}$0
+",
+            "Move to another module",
+        );
+    }
+
+    #[test]
+    fn test_move_to_module_requalifies_paths() {
+        check_assist_with_parameter(
+            extract_module,
+            "util",
+            r"
+mod util {}
+
+struct Helper;
+
+$0fn make() -> Helper {
+    Helper
+}$0
+
+fn main() {
+    let _ = make();
+}
+",
+            r"
+mod util {
+    pub(super) fn make() -> crate::Helper {
+        crate::Helper
+    }
+}
+
+struct Helper;
+
+fn main() {
+    let _ = util::make();
+}
+",
+            "Move to another module",
+        );
+    }
+
+    #[test]
+    fn test_move_to_module_widens_visibility_of_private_siblings() {
+        check_assist_with_parameter(
+            extract_module,
+            "crate::util",
+            r"
+//- /main.rs
+mod util;
+
+mod app {
+    struct Config;
+
+    mod inner {
+        pub(self) struct Helper;
+
+        enum Kind {
+            Plain,
+        }
+
+        $0fn make(_: super::Config) -> Helper {
+            let _ = Kind::Plain;
+            Helper
+        }$0
+    }
+}
+//- /util.rs
+",
+            r"
+//- /main.rs
+mod util;
+
+mod app {
+    pub(super) struct Config;
+
+    pub(super) mod inner {
+        pub(crate) struct Helper;
+
+        pub(crate) enum Kind {
+            Plain,
+        }
+
+        }
+}
+//- /util.rs
+fn make(_: crate::app::Config) -> crate::app::inner::Helper {
+    let _ = crate::app::inner::Kind::Plain;
+    crate::app::inner::Helper
+}
+",
+            "Move to another module",
+        );
+    }
+
+    #[test]
+    fn test_move_to_module_keeps_aliases() {
+        check_assist_with_parameter(
+            extract_module,
+            "util",
+            r"
+mod util {}
+
+mod shapes {
+    pub struct Circle;
+}
+
+mod app {
+    use crate::shapes::Circle as Round;
+
+    $0fn make(_: Round) {}$0
+}
+",
+            r"
+mod util {
+    use crate::shapes::Circle as Round;
+
+    fn make(_: Round) {}
+}
+
+mod shapes {
+    pub struct Circle;
+}
+
+mod app {
+    use crate::shapes::Circle as Round;
+
+    }
+",
+            "Move to another module",
+        );
+    }
+
     #[test]
     fn test_extract_module_for_function_only() {
         check_assist(
@@ -929,8 +1642,8 @@ fn bar(name: i32) -> i32 {
                 }
             ",
             r"
-mod modname {
-    pub(crate) fn foo(name: i32) -> i32 {
+mod ${0:modname} {
+    fn foo(name: i32) -> i32 {
         name + 1
     }
 }
@@ -963,8 +1676,8 @@ fn a() {
             ",
             r"
             mod impl_play {
-mod modname {
-    pub(crate) struct A {}
+mod ${0:modname} {
+    pub(super) struct A {}
 
     impl A {
         pub fn new_a() -> i32 {
@@ -974,7 +1687,7 @@ pub fn new_a() -> i32 {
 }
 
                 fn a() {
-                    let _a = modname::A::new_a();
+                    let _a = ${0:modname}::A::new_a();
                 }
             }
             ",
@@ -1012,11 +1725,11 @@ mod foo {
             mod bar {
                 use super::foo::{PrivateStruct1};
 
-mod modname {
+mod ${0:modname} {
     use super::super::foo::PrivateStruct;
 
-    pub(crate) struct Strukt {
-        pub(crate) field: PrivateStruct,
+    struct Strukt {
+        field: PrivateStruct,
     }
 }
 
@@ -1057,11 +1770,11 @@ mod foo {
             mod bar {
                 use super::foo::PrivateStruct;
 
-mod modname {
+mod ${0:modname} {
     use super::super::foo::PrivateStruct;
 
-    pub(crate) struct Strukt {
-        pub(crate) field: PrivateStruct,
+    struct Strukt {
+        field: PrivateStruct,
     }
 }
 
@@ -1094,11 +1807,11 @@ struct Strukt1 {
             mod bar {
                 pub struct PrivateStruct;
 
-mod modname {
+mod ${0:modname} {
     use super::PrivateStruct;
 
-    pub(crate) struct Strukt {
-       pub(crate) field: PrivateStruct,
+    struct Strukt {
+       field: PrivateStruct,
     }
 }
 
@@ -1111,37 +1824,215 @@ struct Strukt1 {
     }
 
     #[test]
-    fn test_extract_module_for_corresponding_adt_of_impl_present_in_same_mod_but_not_in_selection()
-    {
+    fn test_import_resolve_keeps_alias_used_inside_and_outside_selection() {
         check_assist(
             extract_module,
             r"
-            mod impl_play {
-                struct A {}
+mod foo {
+    pub struct Bar;
+}
 
-$0impl A {
-    pub fn new_a() -> i32 {
-        2
-    }
-}$0
+use crate::foo::Bar as Baz;
 
-                fn a() {
-                    let _a = A::new_a();
-                }
-            }
-            ",
+$0fn f(_: Baz) {}$0
+
+fn g(_: Baz) {}
+",
             r"
-            mod impl_play {
-                struct A {}
+mod foo {
+    pub struct Bar;
+}
 
-mod modname {
-    use super::A;
+use crate::foo::Bar as Baz;
 
-    impl A {
-        pub fn new_a() -> i32 {
-            2
-        }
-    }
+mod ${0:modname} {
+    use crate::foo::Bar as Baz;
+
+    fn f(_: Baz) {}
+}
+
+fn g(_: Baz) {}
+",
+        )
+    }
+
+    #[test]
+    fn test_import_resolve_moves_alias_only_used_inside_selection() {
+        check_assist(
+            extract_module,
+            r"
+mod foo {
+    pub struct Bar;
+    pub struct Qux;
+}
+
+use crate::foo::{Bar as Baz, Qux};
+
+$0fn f(_: Baz) {}$0
+
+fn g(_: Qux) {}
+",
+            r"
+mod foo {
+    pub struct Bar;
+    pub struct Qux;
+}
+
+use crate::foo::{Qux};
+
+mod ${0:modname} {
+    use crate::foo::Bar as Baz;
+
+    fn f(_: Baz) {}
+}
+
+fn g(_: Qux) {}
+",
+        )
+    }
+
+    #[test]
+    fn test_import_resolve_in_attribute_macro_input() {
+        check_assist(
+            extract_module,
+            r"
+//- proc_macros: identity
+mod foo {
+    pub struct Bar;
+    pub struct Qux;
+}
+
+use crate::foo::{Bar, Qux};
+
+$0#[proc_macros::identity]
+fn f() {
+    let _ = Bar;
+}$0
+
+fn g(_: Qux) {}
+",
+            r"
+mod foo {
+    pub struct Bar;
+    pub struct Qux;
+}
+
+use crate::foo::{Qux};
+
+mod ${0:modname} {
+    use crate::foo::Bar;
+
+    #[proc_macros::identity]
+    fn f() {
+        let _ = Bar;
+    }
+}
+
+fn g(_: Qux) {}
+",
+        )
+    }
+
+    #[test]
+    fn test_import_resolve_for_glob_imported_item() {
+        check_assist(
+            extract_module,
+            r"
+mod foo {
+    pub struct Bar;
+}
+
+use foo::*;
+
+$0fn f(_: Bar) {}$0
+
+fn g(_: Bar) {}
+",
+            r"
+mod foo {
+    pub struct Bar;
+}
+
+use foo::*;
+
+mod ${0:modname} {
+    use super::foo::Bar;
+
+    fn f(_: Bar) {}
+}
+
+fn g(_: Bar) {}
+",
+        )
+    }
+
+    #[test]
+    fn test_import_resolve_for_glob_imported_variant() {
+        check_assist(
+            extract_module,
+            r"
+enum Direction {
+    Up,
+    Down,
+}
+
+use Direction::*;
+
+$0fn f() {
+    let _ = Up;
+}$0
+",
+            r"
+enum Direction {
+    Up,
+    Down,
+}
+
+use Direction::*;
+
+mod ${0:modname} {
+    use super::Direction::Up;
+
+    fn f() {
+        let _ = Up;
+    }
+}
+",
+        )
+    }
+
+    #[test]
+    fn test_extract_module_for_corresponding_adt_of_impl_present_in_same_mod_but_not_in_selection()
+    {
+        check_assist(
+            extract_module,
+            r"
+            mod impl_play {
+                struct A {}
+
+$0impl A {
+    pub fn new_a() -> i32 {
+        2
+    }
+}$0
+
+                fn a() {
+                    let _a = A::new_a();
+                }
+            }
+            ",
+            r"
+            mod impl_play {
+                struct A {}
+
+mod ${0:modname} {
+    use super::A;
+
+    impl A {
+        pub fn new_a() -> i32 {
+            2
+        }
+    }
 }
 
                 fn a() {
@@ -1182,7 +2073,7 @@ pub struct A {}
             mod impl_play {
                 use super::foo::A;
 
-mod modname {
+mod ${0:modname} {
     use super::super::foo::A;
 
     impl A {
@@ -1226,18 +2117,18 @@ fn bar() {
             mod impl_play2 {
                 trait JustATrait {}
 
-mod modname {
+mod ${0:modname} {
     use super::JustATrait;
 
-    pub(crate) struct A {}
+    struct A {}
 
-    pub(crate) fn foo<T: JustATrait>(arg: T) -> T {
+    fn foo<T: JustATrait>(arg: T) -> T {
         arg
     }
 
     impl JustATrait for A {}
 
-    pub(crate) fn bar() {
+    fn bar() {
         let a = A {};
         foo(a);
     }
@@ -1260,8 +2151,8 @@ pub struct A {}
             ",
             r"
             mod impl_play2 {
-mod modname {
-    pub(crate) mod impl_play {
+mod ${0:modname} {
+    mod impl_play {
         pub struct A {}
     }
 }
@@ -1271,465 +2162,1849 @@ pub struct A {}
     }
 
     #[test]
-    fn test_extract_module_with_multiple_files() {
+    fn test_extract_module_requalifies_usages_in_other_files() {
         check_assist(
             extract_module,
             r"
-            //- /main.rs
-            mod foo;
-
-            use foo::PrivateStruct;
+//- /main.rs
+mod foo;
 
-            pub struct Strukt {
-                field: PrivateStruct,
-            }
+$0fn bar() {}$0
+//- /foo.rs
+use crate::bar;
 
-            fn main() {
-                $0struct Strukt1 {
-                    field: Strukt,
-                }$0
-            }
-            //- /foo.rs
-            pub struct PrivateStruct;
-            ",
+fn f() {
+    bar()
+}
+",
             r"
-            mod foo;
-
-            use foo::PrivateStruct;
-
-            pub struct Strukt {
-                field: PrivateStruct,
-            }
+//- /main.rs
+mod foo;
 
-            fn main() {
-                mod modname {
-                    use super::Strukt;
+mod modname {
+    pub(super) fn bar() {}
+}
+//- /foo.rs
+use crate::modname::bar;
 
-                    pub(crate) struct Strukt1 {
-                        pub(crate) field: Strukt,
-                    }
-                }
-            }
-            ",
+fn f() {
+    crate::modname::bar()
+}
+",
         )
     }
 
     #[test]
-    fn test_extract_module_macro_rules() {
+    fn test_extract_module_imports_traits_for_method_calls() {
         check_assist(
             extract_module,
             r"
-$0macro_rules! m {
-    () => {};
+struct S;
+
+$0trait Greet {
+    fn greet(&self) -> u32;
+}
+
+impl Greet for S {
+    fn greet(&self) -> u32 {
+        0
+    }
 }$0
-m! {}
-            ",
+
+fn hello() -> u32 {
+    S.greet()
+}
+
+mod tests {
+    use super::*;
+
+    fn test() {
+        S.greet();
+    }
+}
+",
             r"
-mod modname {
-    macro_rules! m {
-        () => {};
+struct S;
+
+mod ${0:modname} {
+    use super::S;
+
+    pub(super) trait Greet {
+        fn greet(&self) -> u32;
+    }
+
+    impl Greet for S {
+        fn greet(&self) -> u32 {
+            0
+        }
     }
 }
-modname::m! {}
-            ",
-        );
+use ${0:modname}::Greet;
+
+fn hello() -> u32 {
+    S.greet()
+}
+
+mod tests {
+    use super::*;
+
+    fn test() {
+        S.greet();
+    }
+}
+",
+        )
     }
 
     #[test]
-    fn test_do_not_apply_visibility_modifier_to_trait_impl_items() {
+    fn test_extract_module_imports_traits_for_items_named_through_types() {
         check_assist(
             extract_module,
             r"
-            trait ATrait {
-                fn function();
-            }
+struct S;
 
-            struct A {}
+$0trait Make {
+    const ID: u32;
+    fn make() -> Self;
+}
 
-$0impl ATrait for A {
-    fn function() {}
+impl Make for S {
+    const ID: u32 = 1;
+    fn make() -> Self {
+        S
+    }
+}
+
+trait Describe {
+    fn describe(&self) -> u32;
+}
+
+impl<T: Make> Describe for T {
+    fn describe(&self) -> u32 {
+        T::ID
+    }
 }$0
-            ",
+
+fn f() -> u32 {
+    S::make().describe() + S::ID
+}
+",
             r"
-            trait ATrait {
-                fn function();
-            }
+struct S;
 
-            struct A {}
+mod ${0:modname} {
+    use super::S;
 
-mod modname {
-    use super::A;
+    pub(super) trait Make {
+        const ID: u32;
+        fn make() -> Self;
+    }
+
+    impl Make for S {
+        const ID: u32 = 1;
+        fn make() -> Self {
+            S
+        }
+    }
 
-    use super::ATrait;
+    pub(super) trait Describe {
+        fn describe(&self) -> u32;
+    }
 
-    impl ATrait for A {
-        fn function() {}
+    impl<T: Make> Describe for T {
+        fn describe(&self) -> u32 {
+            T::ID
+        }
     }
 }
-            ",
+use ${0:modname}::Make;
+use ${0:modname}::Describe;
+
+fn f() -> u32 {
+    S::make().describe() + S::ID
+}
+",
         )
     }
 
     #[test]
-    fn test_if_inside_impl_block_generate_module_outside() {
+    fn test_extract_module_imports_traits_for_method_calls_in_other_files() {
         check_assist(
             extract_module,
             r"
-            struct A {}
+//- /main.rs
+mod foo;
+mod bar;
 
-            impl A {
-$0fn foo() {}$0
-                fn bar() {}
-            }
-        ",
+struct S;
+
+$0trait Greet {
+    fn greet(&self) -> u32;
+}
+
+impl Greet for S {
+    fn greet(&self) -> u32 {
+        0
+    }
+}$0
+//- /foo.rs
+use crate::Greet;
+
+fn f() -> u32 {
+    crate::S.greet()
+}
+//- /bar.rs
+use crate::*;
+
+fn f() -> u32 {
+    S.greet()
+}
+",
             r"
-            struct A {}
+//- /main.rs
+mod foo;
+mod bar;
 
-            impl A {
-                fn bar() {}
-            }
+struct S;
 
 mod modname {
-    use super::A;
+    use super::S;
 
-    impl A {
-        pub(crate) fn foo() {}
+    pub(super) trait Greet {
+        fn greet(&self) -> u32;
+    }
+
+    impl Greet for S {
+        fn greet(&self) -> u32 {
+            0
+        }
     }
 }
-        ",
+//- /foo.rs
+use crate::modname::Greet;
+
+fn f() -> u32 {
+    crate::S.greet()
+}
+//- /bar.rs
+use crate::*;
+use crate::modname::Greet;
+
+fn f() -> u32 {
+    S.greet()
+}
+",
         )
     }
 
     #[test]
-    fn test_if_inside_impl_block_generate_module_outside_but_impl_block_having_one_child() {
-        check_assist(
+    fn test_extract_module_picks_unique_name() {
+        check_assist_by_label(
             extract_module,
             r"
-            struct A {}
-            struct B {}
+mod modname {}
+struct modname1;
 
-            impl A {
-$0fn foo(x: B) {}$0
-            }
-        ",
+$0fn foo() {}$0
+",
             r"
-            struct A {}
-            struct B {}
+mod modname {}
+struct modname1;
 
-mod modname {
-    use super::B;
+mod ${0:modname2} {
+    fn foo() {}
+}
+",
+            "Extract Module",
+        )
+    }
 
-    use super::A;
+    #[test]
+    fn test_extract_module_keeps_free_standing_comments() {
+        check_assist_by_label(
+            extract_module,
+            r"
+$0fn foo() {}
 
-    impl A {
-        pub(crate) fn foo(x: B) {}
-    }
+// first line
+// second line
+
+/* block */
+
+fn bar() {}
+
+// trailing$0
+",
+            r"
+mod ${0:modname} {
+    fn foo() {}
+
+    // first line
+    // second line
+
+    /* block */
+
+    fn bar() {}
+
+    // trailing
 }
-        ",
+",
+            "Extract Module",
         )
     }
 
     #[test]
-    fn test_issue_11766() {
-        //https://github.com/rust-lang/rust-analyzer/issues/11766
+    fn test_extract_module_with_multiple_files() {
         check_assist(
             extract_module,
             r"
-            mod x {
-                pub struct Foo;
-                pub struct Bar;
-            }
+            //- /main.rs
+            mod foo;
 
-            use x::{Bar, Foo};
+            use foo::PrivateStruct;
 
-            $0type A = (Foo, Bar);$0
-        ",
-            r"
-            mod x {
-                pub struct Foo;
-                pub struct Bar;
+            pub struct Strukt {
+                field: PrivateStruct,
             }
 
-            use x::{};
+            fn main() {
+                $0struct Strukt1 {
+                    field: Strukt,
+                }$0
+            }
+            //- /foo.rs
+            pub struct PrivateStruct;
+            ",
+            r"
+            mod foo;
+
+            use foo::PrivateStruct;
 
-            mod modname {
-                use super::x::Bar;
+            pub struct Strukt {
+                field: PrivateStruct,
+            }
 
-                use super::x::Foo;
+            fn main() {
+                mod ${0:modname} {
+                    use super::Strukt;
 
-                pub(crate) type A = (Foo, Bar);
+                    struct Strukt1 {
+                        field: Strukt,
+                    }
+                }
             }
-        ",
+            ",
         )
     }
 
     #[test]
-    fn test_issue_12790() {
+    fn test_extract_module_macro_rules() {
         check_assist(
             extract_module,
             r"
-            $0/// A documented function
-            fn documented_fn() {}
+$0macro_rules! m {
+    () => {};
+}$0
+m! {}
+            ",
+            r"
+mod ${0:modname} {
+    macro_rules! m {
+        () => {};
+    }
+}
+${0:modname}::m! {}
+            ",
+        );
+    }
 
-            // A commented function with a #[] attribute macro
-            #[cfg(test)]
-            fn attribute_fn() {}
+    #[test]
+    fn test_extract_module_exported_macro_rules() {
+        check_assist(
+            extract_module,
+            r"
+$0#[macro_export]
+macro_rules! m {
+    () => {};
+}$0
+m! {}
+mod inner {
+    fn f() {
+        m!();
+        crate::m!();
+    }
+}
+            ",
+            r"
+mod ${0:modname} {
+    #[macro_export]
+    macro_rules! m {
+        () => {};
+    }
+}
+m! {}
+mod inner {
+    fn f() {
+        crate::m!();
+        crate::m!();
+    }
+}
+            ",
+        );
+    }
 
-            // A normally commented function
-            fn normal_fn() {}
+    #[test]
+    fn test_extract_module_exported_macro_rules_in_crate_root() {
+        check_assist(
+            extract_module,
+            r"
+$0#[macro_export]
+macro_rules! m {
+    () => {};
+}
 
-            /// A documented Struct
-            struct DocumentedStruct {
-                // Normal field
-                x: i32,
+fn f() {
+    m!();
+}$0
 
-                /// Documented field
-                y: i32,
+fn g() {
+    m!();
+    f();
+}
+            ",
+            r"
+mod ${0:modname} {
+    #[macro_export]
+    macro_rules! m {
+        () => {};
+    }
 
-                // Macroed field
-                #[cfg(test)]
-                z: i32,
+    pub(super) fn f() {
+        m!();
+    }
+}
+
+fn g() {
+    m!();
+    ${0:modname}::f();
+}
+            ",
+        );
+    }
+
+    #[test]
+    fn test_do_not_apply_visibility_modifier_to_trait_impl_items() {
+        check_assist(
+            extract_module,
+            r"
+            trait ATrait {
+                fn function();
             }
 
-            // A macroed Struct
-            #[cfg(test)]
-            struct MacroedStruct {
-                // Normal field
-                x: i32,
+            struct A {}
 
-                /// Documented field
-                y: i32,
+$0impl ATrait for A {
+    fn function() {}
+}$0
+            ",
+            r"
+            trait ATrait {
+                fn function();
+            }
 
-                // Macroed field
-                #[cfg(test)]
-                z: i32,
+            struct A {}
+
+mod ${0:modname} {
+    use super::{ATrait, A};
+
+    impl ATrait for A {
+        fn function() {}
+    }
+}
+            ",
+        )
+    }
+
+    #[test]
+    fn test_if_inside_impl_block_generate_module_outside() {
+        check_assist(
+            extract_module,
+            r"
+            struct A {}
+
+            impl A {
+$0fn foo() {}$0
+                fn bar() {}
             }
+        ",
+            r"
+            struct A {}
 
-            // A normal Struct
-            struct NormalStruct {
-                // Normal field
-                x: i32,
+            impl A {
+                fn bar() {}
+            }
 
-                /// Documented field
-                y: i32,
+mod ${0:modname} {
+    use super::A;
 
-                // Macroed field
-                #[cfg(test)]
-                z: i32,
+    impl A {
+        fn foo() {}
+    }
+}
+        ",
+        )
+    }
+
+    #[test]
+    fn test_if_inside_impl_block_generate_module_outside_but_impl_block_having_one_child() {
+        check_assist(
+            extract_module,
+            r"
+            struct A {}
+            struct B {}
+
+            impl A {
+$0fn foo(x: B) {}$0
             }
+        ",
+            r"
+            struct A {}
+            struct B {}
 
-            /// A documented type
-            type DocumentedType = i32;
+mod ${0:modname} {
+    use super::{A, B};
 
-            // A macroed type
-            #[cfg(test)]
-            type MacroedType = i32;
+    impl A {
+        fn foo(x: B) {}
+    }
+}
+        ",
+        )
+    }
 
-            /// A module to move
-            mod module {}
+    #[test]
+    fn test_if_inside_impl_block_generated_imports_are_deduplicated() {
+        check_assist(
+            extract_module,
+            r"
+            struct A {}
+
+            impl A {
+$0fn foo(x: A) {}$0
+                fn bar() {}
+            }
+        ",
+            r"
+            struct A {}
+
+            impl A {
+                fn bar() {}
+            }
+
+mod ${0:modname} {
+    use super::A;
+
+    impl A {
+        fn foo(x: A) {}
+    }
+}
+        ",
+        )
+    }
+
+    #[test]
+    fn test_items_of_impl_stay_reachable_through_self() {
+        check_assist(
+            extract_module,
+            r"
+            struct A {}
+
+            impl A {
+$0fn foo() {}$0
+                fn bar() {
+                    Self::foo();
+                    A::foo();
+                }
+            }
+        ",
+            r"
+            struct A {}
+
+            impl A {
+                fn bar() {
+                    Self::foo();
+                    A::foo();
+                }
+            }
+
+mod ${0:modname} {
+    use super::A;
+
+    impl A {
+        pub(super) fn foo() {}
+    }
+}
+        ",
+        )
+    }
+
+    #[test]
+    fn test_items_of_impl_keep_their_attributes_and_receivers() {
+        check_assist(
+            extract_module,
+            r"
+struct A;
+
+impl A {
+    $0/// The answer.
+    #[inline]
+    const fn answer(&self) -> u8 {
+        self.half() * 2
+    }
+
+    const HALF: u8 = 21;$0
+
+    fn half(&self) -> u8 {
+        Self::HALF
+    }
+}
+
+fn main() {
+    A.answer();
+}
+",
+            r"
+struct A;
+
+impl A {
+    fn half(&self) -> u8 {
+        Self::HALF
+    }
+}
+
+mod ${0:modname} {
+    use super::A;
+
+    impl A {
+        /// The answer.
+        #[inline]
+        pub(super) const fn answer(&self) -> u8 {
+            self.half() * 2
+        }
+
+        pub(super) const HALF: u8 = 21;
+    }
+}
+
+fn main() {
+    A.answer();
+}
+",
+        )
+    }
+
+    #[test]
+    fn test_items_of_impls_of_different_types() {
+        check_assist(
+            extract_module,
+            r#"
+struct A;
+struct B;
+
+impl A {
+    fn a() {}
+
+    $0fn b() {}
+}
+
+impl B {
+    fn c() {}$0
+
+    fn d() {}
+}
+"#,
+            r#"
+struct A;
+struct B;
+
+impl A {
+    fn a() {}
+}
+
+impl B {
+    fn d() {}
+}
+
+mod ${0:modname} {
+    use super::{A, B};
+
+    impl A {
+        fn b() {}
+    }
+
+    impl B {
+        fn c() {}
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn test_items_of_generic_impl_import_bounds_and_arguments() {
+        check_assist(
+            extract_module,
+            r#"
+trait Shape {}
+trait Scale<U> {}
+struct Meters;
+struct Wrapper<T>(T);
+
+impl<T: Shape> Wrapper<T>
+where
+    T: Scale<Meters>,
+{
+    $0fn area(&self) {}$0
+
+    fn other(&self) {}
+}
+"#,
+            r#"
+trait Shape {}
+trait Scale<U> {}
+struct Meters;
+struct Wrapper<T>(T);
+
+impl<T: Shape> Wrapper<T>
+where
+    T: Scale<Meters>,
+{
+    fn other(&self) {}
+}
+
+mod ${0:modname} {
+    use super::{Meters, Scale, Shape, Wrapper};
+
+    impl<T: Shape> Wrapper<T>
+    where
+        T: Scale<Meters>,
+    {
+        fn area(&self) {}
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn test_items_of_impls_are_grouped_by_header() {
+        check_assist(
+            extract_module,
+            r#"
+trait Shape {}
+struct Wrapper<T>(T);
+
+impl<T> Wrapper<T> {
+    fn x() {}
+
+    $0fn a() {}
+}
+
+impl<T: Shape> Wrapper<T> {
+    fn b() {}$0
+
+    fn y() {}
+}
+"#,
+            r#"
+trait Shape {}
+struct Wrapper<T>(T);
+
+impl<T> Wrapper<T> {
+    fn x() {}
+}
+
+impl<T: Shape> Wrapper<T> {
+    fn y() {}
+}
+
+mod ${0:modname} {
+    use super::{Shape, Wrapper};
+
+    impl<T> Wrapper<T> {
+        fn a() {}
+    }
+
+    impl<T: Shape> Wrapper<T> {
+        fn b() {}
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn test_items_of_impls_are_grouped_by_type() {
+        check_assist(
+            extract_module,
+            r#"
+struct A;
+struct B;
+
+impl A {
+    $0fn a() {}
+}
+
+impl B {
+    fn b() {}
+}
+
+impl A {
+    fn c() {}$0
+
+    fn d() {}
+}
+"#,
+            r#"
+struct A;
+struct B;
+
+impl A {
+    fn d() {}
+}
+
+mod ${0:modname} {
+    use super::{A, B};
+
+    impl A {
+        fn a() {}
+
+        fn c() {}
+    }
+
+    impl B {
+        fn b() {}
+    }
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn test_issue_11766() {
+        //https://github.com/rust-lang/rust-analyzer/issues/11766
+        check_assist_by_label(
+            extract_module,
+            r"
+            mod x {
+                pub struct Foo;
+                pub struct Bar;
+            }
+
+            use x::{Bar, Foo};
+
+            $0type A = (Foo, Bar);$0
+        ",
+            r"
+            mod x {
+                pub struct Foo;
+                pub struct Bar;
+            }
+
+            use x::{};
+
+            mod ${0:modname} {
+                use super::x::{Bar, Foo};
+
+                type A = (Foo, Bar);
+            }
+        ",
+            "Extract Module",
+        )
+    }
+
+    #[test]
+    fn test_issue_12790() {
+        check_assist(
+            extract_module,
+            r"
+            $0/// A documented function
+            fn documented_fn() {}
+
+            // A commented function with a #[] attribute macro
+            #[cfg(test)]
+            fn attribute_fn() {}
+
+            // A normally commented function
+            fn normal_fn() {}
+
+            /// A documented Struct
+            struct DocumentedStruct {
+                // Normal field
+                x: i32,
+
+                /// Documented field
+                y: i32,
+
+                // Macroed field
+                #[cfg(test)]
+                z: i32,
+            }
+
+            // A macroed Struct
+            #[cfg(test)]
+            struct MacroedStruct {
+                // Normal field
+                x: i32,
+
+                /// Documented field
+                y: i32,
+
+                // Macroed field
+                #[cfg(test)]
+                z: i32,
+            }
+
+            // A normal Struct
+            struct NormalStruct {
+                // Normal field
+                x: i32,
+
+                /// Documented field
+                y: i32,
+
+                // Macroed field
+                #[cfg(test)]
+                z: i32,
+            }
+
+            /// A documented type
+            type DocumentedType = i32;
+
+            // A macroed type
+            #[cfg(test)]
+            type MacroedType = i32;
+
+            /// A module to move
+            mod module {}
+
+            /// An impl to move
+            impl NormalStruct {
+                /// A method
+                fn new() {}
+            }
+
+            /// A documented trait
+            trait DocTrait {
+                /// Inner function
+                fn doc() {}
+            }
+
+            /// An enum
+            enum DocumentedEnum {
+                /// A variant
+                A,
+                /// Another variant
+                B { x: i32, y: i32 }
+            }
+
+            /// Documented const
+            const MY_CONST: i32 = 0;$0
+        ",
+            r"
+            mod ${0:modname} {
+                /// A documented function
+                fn documented_fn() {}
+
+                // A commented function with a #[] attribute macro
+                #[cfg(test)]
+                pub(crate) fn attribute_fn() {}
+
+                // A normally commented function
+                fn normal_fn() {}
+
+                /// A documented Struct
+                struct DocumentedStruct {
+                    // Normal field
+                    x: i32,
+
+                    /// Documented field
+                    y: i32,
+
+                    // Macroed field
+                    #[cfg(test)]
+                    pub(crate) z: i32,
+                }
+
+                // A macroed Struct
+                #[cfg(test)]
+                pub(crate) struct MacroedStruct {
+                    // Normal field
+                    pub(crate) x: i32,
+
+                    /// Documented field
+                    pub(crate) y: i32,
+
+                    // Macroed field
+                    #[cfg(test)]
+                    pub(crate) z: i32,
+                }
+
+                // A normal Struct
+                struct NormalStruct {
+                    // Normal field
+                    x: i32,
+
+                    /// Documented field
+                    y: i32,
+
+                    // Macroed field
+                    #[cfg(test)]
+                    pub(crate) z: i32,
+                }
+
+                /// A documented type
+                type DocumentedType = i32;
+
+                // A macroed type
+                #[cfg(test)]
+                pub(crate) type MacroedType = i32;
+
+                /// A module to move
+                mod module {}
+
+                /// An impl to move
+                impl NormalStruct {
+                    /// A method
+                    fn new() {}
+                }
+
+                /// A documented trait
+                trait DocTrait {
+                    /// Inner function
+                    fn doc() {}
+                }
+
+                /// An enum
+                enum DocumentedEnum {
+                    /// A variant
+                    A,
+                    /// Another variant
+                    B { x: i32, y: i32 }
+                }
+
+                /// Documented const
+                const MY_CONST: i32 = 0;
+            }
+        ",
+        )
+    }
+
+    #[test]
+    fn test_merge_multiple_intersections() {
+        check_assist_by_label(
+            extract_module,
+            r#"
+mod dep {
+    pub struct A;
+    pub struct B;
+    pub struct C;
+}
+
+use dep::{A, B, C};
+
+$0struct S {
+    inner: A,
+    state: C,
+    condvar: B,
+}$0
+"#,
+            r#"
+mod dep {
+    pub struct A;
+    pub struct B;
+    pub struct C;
+}
+
+use dep::{};
+
+mod ${0:modname} {
+    use super::dep::{A, B, C};
+
+    struct S {
+        inner: A,
+        state: C,
+        condvar: B,
+    }
+}
+"#,
+            "Extract Module",
+        );
+    }
+
+    #[test]
+    fn test_extract_module_with_reexports() {
+        check_assist_by_label(
+            extract_module,
+            r"
+$0fn helper() -> i32 {
+    1
+}
+
+fn other_helper() -> i32 {
+    2
+}
+
+fn unused() {}$0
+
+fn caller() -> i32 {
+    helper() + other_helper()
+}
+
+mod inner {
+    fn f() -> i32 {
+        super::helper()
+    }
+}
+",
+            r"
+mod ${0:modname} {
+    pub(crate) fn helper() -> i32 {
+        1
+    }
+
+    pub(crate) fn other_helper() -> i32 {
+        2
+    }
+
+    fn unused() {}
+}
+
+pub(crate) use ${0:modname}::{helper, other_helper};
+
+fn caller() -> i32 {
+    helper() + other_helper()
+}
+
+mod inner {
+    fn f() -> i32 {
+        super::helper()
+    }
+}
+",
+            "Extract Module with re-exports",
+        );
+    }
+
+    #[test]
+    fn test_extract_module_reexports_public_api() {
+        check_assist_by_label(
+            extract_module,
+            r"
+//- /lib.rs
+pub mod api {
+    $0pub struct Client;
+
+    pub fn connect() -> Client {
+        Client
+    }
+
+    fn helper() {}$0
+
+    fn caller() {
+        helper();
+    }
+}
+",
+            r"
+pub mod api {
+    mod ${0:modname} {
+        pub struct Client;
+
+        pub fn connect() -> Client {
+            Client
+        }
+
+        pub(crate) fn helper() {}
+    }
+
+    pub use ${0:modname}::{Client, connect};
+
+    pub(crate) use ${0:modname}::helper;
+
+    fn caller() {
+        helper();
+    }
+}
+",
+            "Extract Module with re-exports",
+        );
+    }
+
+    #[test]
+    fn test_reexports_keep_doc_hidden_and_cfgs() {
+        check_assist_by_label(
+            extract_module,
+            r#"
+//- /lib.rs cfg:feature=tls
+pub mod api {
+    $0pub struct Client;
+
+    #[doc(hidden)]
+    pub fn internal() {}
+
+    #[cfg(feature = "tls")]
+    pub fn connect_tls() {}$0
+}
+"#,
+            r#"
+pub mod api {
+    mod ${0:modname} {
+        pub struct Client;
+
+        #[doc(hidden)]
+        pub fn internal() {}
+
+        #[cfg(feature = "tls")]
+        pub fn connect_tls() {}
+    }
+
+    pub use ${0:modname}::Client;
+
+    #[doc(hidden)]
+    pub use ${0:modname}::internal;
+
+    #[cfg(feature = "tls")]
+    pub use ${0:modname}::connect_tls;
+}
+"#,
+            "Extract Module with re-exports",
+        );
+    }
+
+    #[test]
+    fn test_extract_module_public_items_of_private_module() {
+        check_assist_by_label(
+            extract_module,
+            r"
+mod private {
+    $0pub fn used() {}
+
+    pub fn unused() {}$0
+
+    fn caller() {
+        used();
+    }
+}
+",
+            r"
+mod private {
+    mod ${0:modname} {
+        pub fn used() {}
+
+        pub fn unused() {}
+    }
+
+    pub(crate) use ${0:modname}::used;
+
+    fn caller() {
+        used();
+    }
+}
+",
+            "Extract Module with re-exports",
+        );
+    }
+
+    #[test]
+    fn test_common_cfg_is_moved_onto_module() {
+        check_assist(
+            extract_module,
+            r#"
+//- /main.rs cfg:feature=fast
+$0#[cfg(feature = "fast")]
+fn helper() -> u32 {
+    1
+}
+
+/// Runs it.
+#[cfg(feature = "fast")]
+#[inline]
+fn run() -> u32 {
+    helper()
+}$0
+
+#[cfg(feature = "fast")]
+fn main() {
+    run();
+}
+"#,
+            r#"
+#[cfg(feature = "fast")]
+mod ${0:modname} {
+    fn helper() -> u32 {
+        1
+    }
+
+    /// Runs it.
+    #[inline]
+    pub(super) fn run() -> u32 {
+        helper()
+    }
+}
+
+#[cfg(feature = "fast")]
+fn main() {
+    ${0:modname}::run();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_common_cfg_is_kept_on_reexports() {
+        check_assist_by_label(
+            extract_module,
+            r#"
+//- /main.rs cfg:feature=fast
+$0#[cfg(feature = "fast")]
+fn run() -> u32 {
+    1
+}$0
+
+#[cfg(feature = "fast")]
+fn main() {
+    run();
+}
+"#,
+            r#"
+#[cfg(feature = "fast")]
+mod ${0:modname} {
+    pub(crate) fn run() -> u32 {
+        1
+    }
+}
+
+#[cfg(feature = "fast")]
+pub(crate) use ${0:modname}::run;
+
+#[cfg(feature = "fast")]
+fn main() {
+    run();
+}
+"#,
+            "Extract Module with re-exports",
+        );
+    }
+
+    #[test]
+    fn test_differing_cfgs_stay_on_items() {
+        check_assist(
+            extract_module,
+            r#"
+//- /main.rs cfg:feature=fast
+$0#[cfg(feature = "fast")]
+fn fast() {}
+
+fn slow() {}$0
+"#,
+            r#"
+mod ${0:modname} {
+    #[cfg(feature = "fast")]
+    fn fast() {}
+
+    fn slow() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_extract_module_sorts_and_groups_imports() {
+        check_assist(
+            extract_module,
+            r"
+//- /main.rs crate:main deps:dep,std
+mod foo {
+    pub struct Foo;
+}
+
+$0use crate::foo::Foo;
+use dep::Handle;
+use std::fmt::Error;
+
+fn f(_: Foo, _: Handle, _: Error) {}$0
+//- /dep.rs crate:dep
+pub struct Handle;
+//- /std.rs crate:std
+pub mod fmt {
+    pub struct Error;
+}
+",
+            r"
+mod foo {
+    pub struct Foo;
+}
+
+mod ${0:modname} {
+    use std::fmt::Error;
+
+    use dep::Handle;
+
+    use crate::foo::Foo;
+
+    fn f(_: Foo, _: Handle, _: Error) {}
+}
+",
+        )
+    }
+
+    #[test]
+    fn test_remove_import_path_inside_selection() {
+        check_assist(
+            extract_module,
+            r#"
+$0struct Point;
+impl Point {
+    pub const fn direction(self, other: Self) -> Option<Direction> {
+        Some(Vertical)
+    }
+}
+
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+use Direction::{Horizontal, Vertical};$0
+
+fn main() {
+    let x = Vertical;
+}
+"#,
+            r#"
+mod ${0:modname} {
+    use Direction::{Horizontal, Vertical};
+
+    struct Point;
+
+    impl Point {
+        pub const fn direction(self, other: Self) -> Option<Direction> {
+            Some(Vertical)
+        }
+    }
+
+    pub enum Direction {
+        Horizontal,
+        Vertical,
+    }
+}
+use ${0:modname}::Direction::{Horizontal, Vertical};
+
+fn main() {
+    let x = Vertical;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_extract_default_methods_of_trait() {
+        check_assist(
+            extract_module,
+            r#"
+trait Shape {
+    fn area(&self) -> f64;
+
+    $0fn describe(&self) -> String {
+        format!("area {}", self.area())
+    }$0
+}
+"#,
+            r#"
+trait Shape {
+    fn area(&self) -> f64;
+
+    fn describe(&self) -> String {
+        ${0:modname}::describe(self)
+    }
+}
+
+mod ${0:modname} {
+    use super::Shape;
+
+    pub(super) fn describe<T: Shape + ?Sized>(this: &T) -> String {
+        format!("area {}", this.area())
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_extract_default_methods_of_generic_trait() {
+        check_assist(
+            extract_module,
+            r#"
+trait Store<K> {
+    fn get(&self, key: &K) -> Option<u32>;
 
-            /// An impl to move
-            impl NormalStruct {
-                /// A method
-                fn new() {}
-            }
+    $0fn get_or<D: Into<u32>>(&self, key: &K, default: D) -> u32 {
+        self.get(key).unwrap_or(default.into())
+    }
 
-            /// A documented trait
-            trait DocTrait {
-                /// Inner function
-                fn doc() {}
-            }
+    fn empty() -> Vec<Self> where Self: Sized {
+        Vec::new()
+    }$0
+}
+"#,
+            r#"
+trait Store<K> {
+    fn get(&self, key: &K) -> Option<u32>;
 
-            /// An enum
-            enum DocumentedEnum {
-                /// A variant
-                A,
-                /// Another variant
-                B { x: i32, y: i32 }
-            }
+    fn get_or<D: Into<u32>>(&self, key: &K, default: D) -> u32 {
+        ${0:modname}::get_or::<Self, K, D>(self, key, default)
+    }
 
-            /// Documented const
-            const MY_CONST: i32 = 0;$0
-        ",
-            r"
-            mod modname {
-                /// A documented function
-                pub(crate) fn documented_fn() {}
+    fn empty() -> Vec<Self> where Self: Sized {
+        ${0:modname}::empty::<Self, K>()
+    }
+}
 
-                // A commented function with a #[] attribute macro
-                #[cfg(test)]
-                pub(crate) fn attribute_fn() {}
+mod ${0:modname} {
+    use super::Store;
 
-                // A normally commented function
-                pub(crate) fn normal_fn() {}
+    pub(super) fn get_or<T: Store<K> + ?Sized, K, D: Into<u32>>(this: &T, key: &K, default: D) -> u32 {
+        this.get(key).unwrap_or(default.into())
+    }
 
-                /// A documented Struct
-                pub(crate) struct DocumentedStruct {
-                    // Normal field
-                    pub(crate) x: i32,
+    pub(super) fn empty<T: Store<K> + ?Sized, K>() -> Vec<T> where T: Sized {
+        Vec::new()
+    }
+}
+"#,
+        );
+    }
 
-                    /// Documented field
-                    pub(crate) y: i32,
+    #[test]
+    fn test_extract_default_methods_calls_keep_unsafe_and_async() {
+        check_assist(
+            extract_module,
+            r#"
+trait Device {
+    $0unsafe fn read(&self) -> u8 {
+        0
+    }
 
-                    // Macroed field
-                    #[cfg(test)]
-                    pub(crate) z: i32,
-                }
+    async fn wait(&self) {}$0
+}
+"#,
+            r#"
+trait Device {
+    unsafe fn read(&self) -> u8 {
+        unsafe { ${0:modname}::read(self) }
+    }
 
-                // A macroed Struct
-                #[cfg(test)]
-                pub(crate) struct MacroedStruct {
-                    // Normal field
-                    pub(crate) x: i32,
+    async fn wait(&self) {
+        ${0:modname}::wait(self).await
+    }
+}
 
-                    /// Documented field
-                    pub(crate) y: i32,
+mod ${0:modname} {
+    use super::Device;
 
-                    // Macroed field
-                    #[cfg(test)]
-                    pub(crate) z: i32,
-                }
+    pub(super) unsafe fn read<T: Device + ?Sized>(this: &T) -> u8 {
+        0
+    }
 
-                // A normal Struct
-                pub(crate) struct NormalStruct {
-                    // Normal field
-                    pub(crate) x: i32,
+    pub(super) async fn wait<T: Device + ?Sized>(this: &T) {}
+}
+"#,
+        );
+    }
 
-                    /// Documented field
-                    pub(crate) y: i32,
+    #[test]
+    fn test_extract_default_methods_not_applicable() {
+        check_assist_not_applicable(
+            extract_module,
+            r#"
+trait Shape {
+    $0fn area(&self) -> f64;
 
-                    // Macroed field
-                    #[cfg(test)]
-                    pub(crate) z: i32,
-                }
+    fn describe(&self) -> String {
+        String::new()
+    }$0
+}
+"#,
+        );
+        check_assist_not_applicable(
+            extract_module,
+            r#"
+trait Shape {
+    $0fn scale(&mut self, (x, y): (f64, f64)) {}$0
+}
+"#,
+        );
+    }
 
-                /// A documented type
-                pub(crate) type DocumentedType = i32;
+    #[test]
+    fn test_extract_statements_into_module() {
+        check_assist_by_label(
+            extract_module,
+            r#"
+fn main() {
+    let n = 1;
+    $0let m = n + 2;
+    let k = m + n;$0
+    let g = 3;
+}
+"#,
+            r#"
+fn main() {
+    let n = 1;
+    ${0:modname}::fun_name(n);
+    let g = 3;
+}
 
-                // A macroed type
-                #[cfg(test)]
-                pub(crate) type MacroedType = i32;
+mod ${0:modname} {
+    use super::*;
 
-                /// A module to move
-                pub(crate) mod module {}
+    pub(super) fn $1fun_name(n: i32) {
+        let m = n + 2;
+        let k = m + n;
+    }
+}
+"#,
+            "Extract into function in new module",
+        );
+    }
 
-                /// An impl to move
-                impl NormalStruct {
-                    /// A method
-                    pub(crate) fn new() {}
-                }
+    #[test]
+    fn test_extract_statements_with_outliving_local_into_module() {
+        check_assist_by_label(
+            extract_module,
+            r#"
+struct Config {
+    verbose: bool,
+}
 
-                /// A documented trait
-                pub(crate) trait DocTrait {
-                    /// Inner function
-                    fn doc() {}
-                }
+fn run(config: &Config) -> u32 {
+    $0let level = if config.verbose { 2 } else { 1 };$0
+    level * 10
+}
+"#,
+            r#"
+struct Config {
+    verbose: bool,
+}
 
-                /// An enum
-                pub(crate) enum DocumentedEnum {
-                    /// A variant
-                    A,
-                    /// Another variant
-                    B { x: i32, y: i32 }
-                }
+fn run(config: &Config) -> u32 {
+    let level = ${0:modname}::fun_name(config);
+    level * 10
+}
 
-                /// Documented const
-                pub(crate) const MY_CONST: i32 = 0;
-            }
-        ",
-        )
+mod ${0:modname} {
+    use super::*;
+
+    pub(super) fn $1fun_name(config: &Config) -> u32 {
+        let level = if config.verbose { 2 } else { 1 };
+        level
+    }
+}
+"#,
+            "Extract into function in new module",
+        );
     }
 
     #[test]
-    fn test_merge_multiple_intersections() {
-        check_assist(
+    fn test_extract_statements_of_impl_into_module_names_self_type() {
+        check_assist_by_label(
             extract_module,
             r#"
-mod dep {
-    pub struct A;
-    pub struct B;
-    pub struct C;
+struct Counter {
+    count: u32,
 }
 
-use dep::{A, B, C};
+impl Counter {
+    const START: u32 = 1;
 
-$0struct S {
-    inner: A,
-    state: C,
-    condvar: B,
-}$0
+    fn new() -> Self {
+        $0let counter: Self = Self { count: Self::START };$0
+        counter
+    }
+}
 "#,
             r#"
-mod dep {
-    pub struct A;
-    pub struct B;
-    pub struct C;
+struct Counter {
+    count: u32,
 }
 
-use dep::{};
-
-mod modname {
-    use super::dep::B;
+impl Counter {
+    const START: u32 = 1;
 
-    use super::dep::C;
+    fn new() -> Self {
+        let counter = ${0:modname}::fun_name();
+        counter
+    }
+}
 
-    use super::dep::A;
+mod ${0:modname} {
+    use super::*;
 
-    pub(crate) struct S {
-        pub(crate) inner: A,
-        pub(crate) state: C,
-        pub(crate) condvar: B,
+    pub(super) fn $1fun_name() -> Counter {
+        let counter: Counter = Counter { count: Counter::START };
+        counter
     }
 }
 "#,
+            "Extract into function in new module",
         );
     }
 
     #[test]
-    fn test_remove_import_path_inside_selection() {
-        check_assist(
+    fn test_extract_statements_into_module_not_applicable() {
+        check_assist_not_applicable(
             extract_module,
             r#"
-$0struct Point;
-impl Point {
-    pub const fn direction(self, other: Self) -> Option<Direction> {
-        Some(Vertical)
+struct S(u32);
+
+impl S {
+    fn get(&self) -> u32 {
+        $0let x = self.0;$0
+        x
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            extract_module,
+            r#"
+fn main() {
+    let x = $01 + 2$0;
+}
+"#,
+        );
     }
+
+    #[test]
+    fn test_extract_module_with_configured_visibility() {
+        check_assist_by_label_with_config(
+            extract_module,
+            AssistConfig { extract_module_visibility: ExtractModuleVisibility::Pub, ..TEST_CONFIG },
+            r"
+$0fn helper() -> i32 {
+    1
 }
 
-pub enum Direction {
-    Horizontal,
-    Vertical,
+fn unused() {}$0
+
+fn caller() -> i32 {
+    helper()
 }
-use Direction::{Horizontal, Vertical};$0
+",
+            r"
+mod ${0:modname} {
+    pub fn helper() -> i32 {
+        1
+    }
 
-fn main() {
-    let x = Vertical;
+    fn unused() {}
 }
-"#,
+
+fn caller() -> i32 {
+    ${0:modname}::helper()
+}
+",
+            "Extract Module",
+        );
+    }
+
+    #[test]
+    fn test_hidden_and_feature_gated_items_keep_narrowest_visibility() {
+        check_assist_by_label_with_config(
+            extract_module,
+            AssistConfig { extract_module_visibility: ExtractModuleVisibility::Pub, ..TEST_CONFIG },
             r#"
-mod modname {
-    use Direction::{Horizontal, Vertical};
+//- /main.rs cfg:feature=extra
+$0#[doc(hidden)]
+fn hidden_helper() -> i32 {
+    1
+}
 
-    pub(crate) struct Point;
+#[cfg(feature = "extra")]
+fn extra() {}
 
-    impl Point {
-        pub const fn direction(self, other: Self) -> Option<Direction> {
-            Some(Vertical)
-        }
+fn helper() -> i32 {
+    2
+}$0
+
+fn caller() -> i32 {
+    hidden_helper() + helper()
+}
+
+#[cfg(feature = "extra")]
+fn caller_with_extra() {
+    extra();
+}
+"#,
+            r#"
+mod ${0:modname} {
+    #[doc(hidden)]
+    pub(super) fn hidden_helper() -> i32 {
+        1
     }
 
-    pub enum Direction {
-        Horizontal,
-        Vertical,
+    #[cfg(feature = "extra")]
+    pub(super) fn extra() {}
+
+    pub fn helper() -> i32 {
+        2
     }
 }
-use modname::Direction::{Horizontal, Vertical};
 
-fn main() {
-    let x = Vertical;
+fn caller() -> i32 {
+    ${0:modname}::hidden_helper() + ${0:modname}::helper()
+}
+
+#[cfg(feature = "extra")]
+fn caller_with_extra() {
+    ${0:modname}::extra();
 }
 "#,
+            "Extract Module",
+        );
+    }
+
+    #[test]
+    fn test_extract_module_reexports_by_default() {
+        check_assist_by_label_with_config(
+            extract_module,
+            AssistConfig {
+                extract_module_visibility: ExtractModuleVisibility::Reexport,
+                ..TEST_CONFIG
+            },
+            r"
+$0fn helper() -> i32 {
+    1
+}$0
+
+fn caller() -> i32 {
+    helper()
+}
+",
+            r"
+mod ${0:modname} {
+    pub(crate) fn helper() -> i32 {
+        1
+    }
+}
+
+pub(crate) use ${0:modname}::helper;
+
+fn caller() -> i32 {
+    helper()
+}
+",
+            "Extract Module",
         );
     }
 }
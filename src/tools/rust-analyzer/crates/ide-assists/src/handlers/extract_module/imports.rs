@@ -0,0 +1,632 @@
+//! Resolution of the imports the moved items need in their new module, and removal of the ones
+//! only they used from the current module.
+
+use std::cmp::Ordering;
+
+use hir::{
+    AsAssocItem, DescendPreference, HasSource, HirFileIdExt, ModuleDef, ModuleSource,
+    PathResolution,
+};
+use ide_db::{
+    base_db::{salsa::Database, FileId},
+    defs::{Definition, NameClass, NameRefClass},
+    imports::{
+        insert_use::{ImportGranularity, ImportGroup},
+        merge_imports::{try_merge_imports, use_tree_cmp, MergeBehavior},
+    },
+    FxHashSet,
+};
+use itertools::Itertools;
+use smallvec::SmallVec;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, make, HasModuleItem, HasName},
+    AstNode, SyntaxElement,
+    SyntaxKind::IDENT,
+    SyntaxNode, TextRange,
+};
+
+use crate::{handlers::remove_unused_param::range_to_remove, AssistContext};
+
+use super::{range_with_trailing_whitespace, Module};
+
+impl Module {
+    /// Drops the imports of `module` itself and of its items, they are in scope anyway once the
+    /// selected items are moved into it.
+    pub(super) fn retain_imports_outside_of(&mut self, module: &ast::Module) {
+        let names: FxHashSet<String> = module
+            .name()
+            .into_iter()
+            .chain(
+                module
+                    .item_list()
+                    .into_iter()
+                    .flat_map(|item_list| item_list.items())
+                    .filter_map(|item| ast::AnyHasName::cast(item.syntax().clone())?.name()),
+            )
+            .map(|name| name.to_string())
+            .collect();
+        self.use_items.retain(|item| {
+            let ast::Item::Use(use_) = item else { return true };
+            let imported = use_
+                .use_tree()
+                .and_then(|tree| tree.path())
+                .and_then(|path| path.segment())
+                .and_then(|segment| segment.name_ref());
+            !imported.is_some_and(|name_ref| names.contains(&name_ref.to_string()))
+        });
+    }
+
+    pub(super) fn resolve_imports(
+        &mut self,
+        module: Option<ast::Module>,
+        ctx: &AssistContext<'_>,
+    ) -> Vec<TextRange> {
+        let mut imports_to_remove = vec![];
+        let mut node_set = FxHashSet::default();
+
+        for item in self.body_items.clone() {
+            ctx.db().unwind_if_cancelled();
+            for (node, def) in names_and_defs(ctx, &item) {
+                if node_set.insert(node.to_string()) {
+                    if let Some(import) = self.process_def_in_sel(def, &node, &module, ctx) {
+                        check_intersection_and_push(&mut imports_to_remove, import);
+                    }
+                }
+            }
+        }
+
+        imports_to_remove
+    }
+
+    fn process_def_in_sel(
+        &mut self,
+        def: Definition,
+        use_node: &SyntaxNode,
+        curr_parent_module: &Option<ast::Module>,
+        ctx: &AssistContext<'_>,
+    ) -> Option<TextRange> {
+        // Associated items are reached through their type or trait, they can't be imported.
+        let is_assoc_item = match def {
+            Definition::Function(it) => it.as_assoc_item(ctx.db()).is_some(),
+            Definition::Const(it) => it.as_assoc_item(ctx.db()).is_some(),
+            Definition::TypeAlias(it) => it.as_assoc_item(ctx.db()).is_some(),
+            _ => false,
+        };
+        if is_assoc_item {
+            return None;
+        }
+
+        //We only need to find in the current file
+        let selection_range = ctx.selection_trimmed();
+        let file_id = ctx.file_id();
+        let usage_res = ctx.usages_in_file(def, file_id);
+        let file = ctx.sema.parse(file_id);
+        // `use_node` may refer to `def` through a `use ... as` alias, whose usages can't be found
+        // by searching for the name of `def`.
+        let alias_tree = aliasing_use_tree(ctx, def, use_node, file.syntax());
+        // Usages inside of macro inputs, e.g. in attributes, aren't part of a path of the file.
+        let usage_ranges: Vec<TextRange> = match &alias_tree {
+            Some(_) => paths_through_alias(ctx, def, use_node, file.syntax())
+                .iter()
+                .map(|path| path.syntax().text_range())
+                .collect(),
+            None => usage_res
+                .iter()
+                .flat_map(|(_, refs)| refs.iter())
+                .filter(|x| find_node_at_range::<ast::Use>(file.syntax(), x.range).is_none())
+                .map(|x| x.range)
+                .collect(),
+        };
+
+        // track uses which does not exists in `Use`
+        let mut uses_exist_in_sel = false;
+        let mut uses_exist_out_sel = false;
+        for range in usage_ranges {
+            let in_selection = selection_range.contains_range(range);
+            uses_exist_in_sel |= in_selection;
+            uses_exist_out_sel |= !in_selection;
+
+            if uses_exist_in_sel && uses_exist_out_sel {
+                break;
+            }
+        }
+
+        let (def_in_mod, def_out_sel) =
+            check_def_in_mod_and_out_sel(def, ctx, curr_parent_module, selection_range, file_id);
+
+        // Find use stmt that use def in current file
+        let use_stmt: Option<ast::Use> = match &alias_tree {
+            Some(tree) => tree.syntax().ancestors().find_map(ast::Use::cast),
+            None => usage_res
+                .into_iter()
+                .filter(|(use_file_id, _)| *use_file_id == file_id)
+                .flat_map(|(_, refs)| refs.into_iter().rev())
+                .find_map(|fref| find_node_at_range(file.syntax(), fref.range)),
+        };
+        let use_stmt_not_in_sel = use_stmt.as_ref().is_some_and(|use_stmt| {
+            !selection_range.contains_range(use_stmt.syntax().text_range())
+        });
+
+        // Names brought in by a glob import get an explicit import of their own.
+        if use_stmt.is_none() && alias_tree.is_none() && uses_exist_in_sel {
+            if let Some(use_) = import_through_glob(ctx, def, use_node, file.syntax()) {
+                self.use_items.insert(0, ast::Item::from(use_));
+                return None;
+            }
+        }
+
+        let mut use_tree_paths: Option<Vec<ast::Path>> = None;
+        //Exists inside and outside selection
+        // - Use stmt for item is present -> get the use_tree_str and reconstruct the path in new
+        // module
+        // - Use stmt for item is not present ->
+        //If it is not found, the definition is either ported inside new module or it stays
+        //outside:
+        //- Def is inside: Nothing to import
+        //- Def is outside: Import it inside with super
+
+        //Exists inside selection but not outside -> Check for the import of it in original module,
+        //get the use_tree_str, reconstruct the use stmt in new module
+
+        let mut import_path_to_be_removed: Option<TextRange> = None;
+        if uses_exist_in_sel && uses_exist_out_sel {
+            //Changes to be made only inside new module
+
+            //If use_stmt exists, find the use_tree_str, reconstruct it inside new module
+            //If not, insert a use stmt with super and the given nameref
+            match self.process_use_stmt_for_import_resolve(use_stmt, use_node, alias_tree.as_ref())
+            {
+                Some((use_tree_str, _)) => use_tree_paths = Some(use_tree_str),
+                None if def_in_mod && def_out_sel => {
+                    //Considered only after use_stmt is not present
+                    //def_in_mod && def_out_sel | exists_outside_sel(exists_inside_sel =
+                    //true for all cases)
+                    // false | false -> Do nothing
+                    // false | true -> If source is in selection -> nothing to do, If source is outside
+                    // mod -> ust_stmt transversal
+                    // true  | false -> super import insertion
+                    // true  | true -> super import insertion
+                    self.make_use_stmt_of_node_with_super(use_node);
+                }
+                None => {}
+            }
+        } else if uses_exist_in_sel && !uses_exist_out_sel {
+            //Changes to be made inside new module, and remove import from outside
+
+            if let Some((mut use_tree_str, text_range_opt)) =
+                self.process_use_stmt_for_import_resolve(use_stmt, use_node, alias_tree.as_ref())
+            {
+                if let Some(text_range) = text_range_opt {
+                    import_path_to_be_removed = Some(text_range);
+                }
+
+                if def_in_mod && def_out_sel {
+                    if let Some(first_path_in_use_tree) = use_tree_str.last() {
+                        let first_path_in_use_tree_str = first_path_in_use_tree.to_string();
+                        if !first_path_in_use_tree_str.contains("super")
+                            && !first_path_in_use_tree_str.contains("crate")
+                        {
+                            let super_path = make::ext::ident_path("super");
+                            use_tree_str.push(super_path);
+                        }
+                    }
+                }
+
+                use_tree_paths = Some(use_tree_str);
+            } else if def_in_mod && def_out_sel {
+                self.make_use_stmt_of_node_with_super(use_node);
+            }
+        }
+
+        if let Some(mut use_tree_paths) = use_tree_paths {
+            use_tree_paths.reverse();
+
+            if uses_exist_out_sel || !uses_exist_in_sel || !def_in_mod || !def_out_sel {
+                if let Some(first_path_in_use_tree) = use_tree_paths.first() {
+                    if first_path_in_use_tree.to_string().contains("super") {
+                        use_tree_paths.insert(0, make::ext::ident_path("super"));
+                    }
+                }
+            }
+
+            let is_item = matches!(
+                def,
+                Definition::Macro(_)
+                    | Definition::Module(_)
+                    | Definition::Function(_)
+                    | Definition::Adt(_)
+                    | Definition::Const(_)
+                    | Definition::Static(_)
+                    | Definition::Trait(_)
+                    | Definition::TraitAlias(_)
+                    | Definition::TypeAlias(_)
+            );
+
+            if (def_out_sel || !is_item) && use_stmt_not_in_sel {
+                let alias = alias_tree.and_then(|tree| tree.rename());
+                let use_ = make::use_(
+                    None,
+                    make::use_tree(make::join_paths(use_tree_paths), None, alias, false),
+                );
+                self.use_items.insert(0, ast::Item::from(use_));
+            }
+        }
+
+        import_path_to_be_removed
+    }
+
+    /// Merges the imports of the items into nested use trees as far as the configured import
+    /// granularity allows, dropping duplicates, and sorts them like `rustfmt` does.
+    pub(super) fn merge_use_items(&mut self, ctx: &AssistContext<'_>) {
+        let merge_behavior = match ctx.config.insert_use.granularity {
+            ImportGranularity::One => MergeBehavior::One,
+            _ => MergeBehavior::Crate,
+        };
+        let mut merged: Vec<ast::Item> = Vec::new();
+        for item in std::mem::take(&mut self.use_items) {
+            if merged.iter().any(|it| it.to_string() == item.to_string()) {
+                continue;
+            }
+            let ast::Item::Use(use_) = &item else {
+                merged.push(item);
+                continue;
+            };
+            let merged_use = merged.iter().enumerate().find_map(|(idx, it)| match it {
+                ast::Item::Use(existing) => {
+                    Some((idx, try_merge_imports(existing, use_, merge_behavior)?))
+                }
+                _ => None,
+            });
+            match merged_use {
+                Some((idx, new_use)) => merged[idx] = ast::Item::Use(new_use),
+                None => merged.push(item),
+            }
+        }
+        merged.sort_by(|a, b| match (use_tree_of(a), use_tree_of(b)) {
+            (Some(a), Some(b)) => {
+                ImportGroup::new(&a).cmp(&ImportGroup::new(&b)).then_with(|| use_tree_cmp(&a, &b))
+            }
+            _ => Ordering::Equal,
+        });
+        self.use_items = merged;
+    }
+
+    /// The use items split into the groups that are separated by blank lines, e.g. the imports
+    /// from `std` and the ones from other crates.
+    pub(super) fn use_item_groups(&self) -> Vec<Vec<ast::Item>> {
+        let group = |item: &ast::Item| use_tree_of(item).map(|tree| ImportGroup::new(&tree));
+        let mut groups: Vec<Vec<ast::Item>> = Vec::new();
+        for item in &self.use_items {
+            match groups.last_mut() {
+                Some(last) if group(&last[0]) == group(item) => last.push(item.clone()),
+                _ => groups.push(vec![item.clone()]),
+            }
+        }
+        groups
+    }
+
+    pub(super) fn make_use_stmt_of_node_with_super(
+        &mut self,
+        node_syntax: &SyntaxNode,
+    ) -> ast::Item {
+        let super_path = make::ext::ident_path("super");
+        let node_path = make::ext::ident_path(&node_syntax.to_string());
+        let use_ = make::use_(
+            None,
+            make::use_tree(make::join_paths(vec![super_path, node_path]), None, None, false),
+        );
+
+        let item = ast::Item::from(use_);
+        self.use_items.insert(0, item.clone());
+        item
+    }
+
+    fn process_use_stmt_for_import_resolve(
+        &self,
+        use_stmt: Option<ast::Use>,
+        node_syntax: &SyntaxNode,
+        alias_tree: Option<&ast::UseTree>,
+    ) -> Option<(Vec<ast::Path>, Option<TextRange>)> {
+        let use_stmt = use_stmt?;
+        if let Some(alias_tree) = alias_tree {
+            let path = alias_tree.path()?;
+            let mut use_tree_str = vec![path.clone()];
+            get_use_tree_paths_from_path(path, &mut use_tree_str);
+            let range_to_remove = match alias_tree.parent_use_tree_list() {
+                Some(_) => range_to_remove(alias_tree.syntax()),
+                None => range_with_trailing_whitespace(
+                    use_stmt.syntax(),
+                    use_stmt.syntax().text_range(),
+                ),
+            };
+            return Some((use_tree_str, Some(range_to_remove)));
+        }
+        for path_seg in use_stmt.syntax().descendants().filter_map(ast::PathSegment::cast) {
+            if path_seg.syntax().to_string() == node_syntax.to_string() {
+                let mut use_tree_str = vec![path_seg.parent_path()];
+                get_use_tree_paths_from_path(path_seg.parent_path(), &mut use_tree_str);
+
+                //Here we are looking for use_tree with same string value as node
+                //passed above as the range_to_remove function looks for a comma and
+                //then includes it in the text range to remove it. But the comma only
+                //appears at the use_tree level
+                for use_tree in path_seg.syntax().ancestors().filter_map(ast::UseTree::cast) {
+                    if use_tree.syntax().to_string() == node_syntax.to_string() {
+                        return Some((use_tree_str, Some(range_to_remove(use_tree.syntax()))));
+                    }
+                }
+
+                return Some((use_tree_str, None));
+            }
+        }
+
+        None
+    }
+}
+
+pub(super) fn check_intersection_and_push(
+    import_paths_to_be_removed: &mut Vec<TextRange>,
+    mut import_path: TextRange,
+) {
+    // Text ranges received here for imports are extended to the
+    // next/previous comma which can cause intersections among them
+    // and later deletion of these can cause panics similar
+    // to reported in #11766. So to mitigate it, we
+    // check for intersection between all current members
+    // and combine all such ranges into one.
+    let s: SmallVec<[_; 2]> = import_paths_to_be_removed
+        .iter_mut()
+        .positions(|it| it.intersect(import_path).is_some())
+        .collect();
+    for pos in s.into_iter().rev() {
+        let intersecting_path = import_paths_to_be_removed.swap_remove(pos);
+        import_path = import_path.cover(intersecting_path);
+    }
+    import_paths_to_be_removed.push(import_path);
+}
+
+/// The names and name references in `item` together with the definitions they resolve to.
+///
+/// Items under attribute macros or derives are only resolved in their expansions, as are the
+/// arguments of macro calls, so the tokens of `item` are descended into those first. Names and
+/// name references of `item` itself are returned as they are, for the others the node in the
+/// expansion is returned.
+fn names_and_defs(ctx: &AssistContext<'_>, item: &ast::Item) -> Vec<(SyntaxNode, Definition)> {
+    let classify = |node: &SyntaxNode| {
+        if let Some(name) = ast::Name::cast(node.clone()) {
+            match NameClass::classify(&ctx.sema, &name)? {
+                NameClass::Definition(def) => Some(def),
+                _ => None,
+            }
+        } else if let Some(name_ref) = ast::NameRef::cast(node.clone()) {
+            match NameRefClass::classify(&ctx.sema, &name_ref)? {
+                NameRefClass::Definition(def) => Some(def),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    };
+
+    item.syntax()
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter_map(|token| {
+            let parent = token.parent()?;
+            let in_name =
+                ast::Name::can_cast(parent.kind()) || ast::NameRef::can_cast(parent.kind());
+            if !in_name && token.kind() != IDENT {
+                return None;
+            }
+            ctx.sema.descend_into_macros(DescendPreference::SameText, token).into_iter().find_map(
+                |token| {
+                    let node = token.parent()?;
+                    let def = classify(&node)?;
+                    Some((if in_name { parent.clone() } else { node }, def))
+                },
+            )
+        })
+        .collect()
+}
+
+/// Whether `name_ref` refers to `def` by another name, i.e. through a `use ... as` alias.
+pub(super) fn refers_through_alias(
+    ctx: &AssistContext<'_>,
+    def: Definition,
+    name_ref: &ast::NameRef,
+) -> bool {
+    name_ref.ident_token().is_some()
+        && def.name(ctx.db()).is_some_and(|name| name.as_str() != Some(name_ref.text().as_str()))
+}
+
+/// The use tree in `file` which renames `def` to the alias `node` refers to it by, e.g.
+/// `Bar as Baz` for `Baz`.
+fn aliasing_use_tree(
+    ctx: &AssistContext<'_>,
+    def: Definition,
+    node: &SyntaxNode,
+    file: &SyntaxNode,
+) -> Option<ast::UseTree> {
+    let name_ref = ast::NameRef::cast(node.clone())?;
+    if !refers_through_alias(ctx, def, &name_ref) {
+        return None;
+    }
+    let module = ctx.sema.scope(node)?.module();
+    file.descendants()
+        .filter_map(ast::Rename::cast)
+        .filter(|rename| rename.name().is_some_and(|name| name.text() == name_ref.text()))
+        .filter_map(|rename| ast::UseTree::cast(rename.syntax().parent()?))
+        .filter(|tree| ctx.sema.scope(tree.syntax()).is_some_and(|it| it.module() == module))
+        .find(|tree| {
+            let Some(name_ref) = tree.path().and_then(|it| it.segment()?.name_ref()) else {
+                return false;
+            };
+            matches!(
+                NameRefClass::classify(&ctx.sema, &name_ref),
+                Some(NameRefClass::Definition(it)) if it == def
+            )
+        })
+}
+
+/// The paths in `file` outside of use items that refer to `def` through the alias `alias`.
+fn paths_through_alias(
+    ctx: &AssistContext<'_>,
+    def: Definition,
+    alias: &SyntaxNode,
+    file: &SyntaxNode,
+) -> Vec<ast::Path> {
+    let alias = alias.to_string();
+    file.descendants()
+        .filter_map(ast::NameRef::cast)
+        .filter(|name_ref| name_ref.text() == alias.as_str())
+        .filter(|name_ref| !name_ref.syntax().ancestors().any(|it| ast::Use::can_cast(it.kind())))
+        .filter(|name_ref| {
+            matches!(
+                NameRefClass::classify(&ctx.sema, name_ref),
+                Some(NameRefClass::Definition(it)) if it == def
+            )
+        })
+        .filter_map(|name_ref| name_ref.syntax().ancestors().find_map(ast::Path::cast))
+        .collect()
+}
+
+/// An import of `def` for the new module, if `node` refers to it through a glob import outside of
+/// the selection, e.g. `use super::foo::Name;` for `use foo::*;`.
+fn import_through_glob(
+    ctx: &AssistContext<'_>,
+    def: Definition,
+    node: &SyntaxNode,
+    file: &SyntaxNode,
+) -> Option<ast::Use> {
+    let db = ctx.db();
+    let module = ctx.sema.scope(node)?.module();
+    // Enum variants aren't in the scope of the module of their enum.
+    if !matches!(def, Definition::Variant(_)) && def.module(db) == Some(module) {
+        return None;
+    }
+    let selection = ctx.selection_trimmed();
+    let glob = file
+        .descendants()
+        .filter_map(ast::UseTree::cast)
+        .filter(|tree| tree.star_token().is_some())
+        .filter(|tree| !selection.contains_range(tree.syntax().text_range()))
+        .filter(|tree| ctx.sema.scope(tree.syntax()).is_some_and(|it| it.module() == module))
+        .find(|tree| {
+            let Some(path) = tree.path() else { return false };
+            match ctx.sema.resolve_path(&path) {
+                Some(PathResolution::Def(ModuleDef::Module(it))) => def.module(db) == Some(it),
+                Some(PathResolution::Def(ModuleDef::Adt(hir::Adt::Enum(it)))) => {
+                    matches!(def, Definition::Variant(variant) if variant.parent_enum(db) == it)
+                }
+                _ => false,
+            }
+        })?;
+
+    let prefix = glob
+        .syntax()
+        .ancestors()
+        .filter_map(ast::UseTree::cast)
+        .filter_map(|tree| tree.path())
+        .collect_vec();
+    let prefix = make::join_paths(prefix.into_iter().rev()).to_string();
+    // The new module is a child of the current one.
+    let outermost = glob.syntax().ancestors().filter_map(ast::UseTree::cast).last()?.path()?;
+    let prefix = match outermost.first_segment()?.kind()? {
+        ast::PathSegmentKind::SelfKw => prefix.replacen("self", "super", 1),
+        ast::PathSegmentKind::SuperKw => format!("super::{prefix}"),
+        ast::PathSegmentKind::Name(_) => {
+            match ctx.sema.resolve_path(&outermost.first_qualifier_or_self()) {
+                // Other crates are in scope everywhere.
+                Some(PathResolution::Def(ModuleDef::Module(it))) if it.is_crate_root() => prefix,
+                _ => format!("super::{prefix}"),
+            }
+        }
+        _ => prefix,
+    };
+    let path = make::path_from_text(&format!("{prefix}::{node}"));
+    Some(make::use_(None, make::use_tree(path, None, None, false)))
+}
+
+fn check_def_in_mod_and_out_sel(
+    def: Definition,
+    ctx: &AssistContext<'_>,
+    curr_parent_module: &Option<ast::Module>,
+    selection_range: TextRange,
+    curr_file_id: FileId,
+) -> (bool, bool) {
+    macro_rules! check_item {
+        ($x:ident) => {
+            if let Some(source) = $x.source(ctx.db()) {
+                let have_same_parent = if let Some(ast_module) = &curr_parent_module {
+                    ctx.sema.to_module_def(ast_module).is_some_and(|it| it == $x.module(ctx.db()))
+                } else {
+                    source.file_id.original_file(ctx.db()) == curr_file_id
+                };
+
+                let in_sel = !selection_range.contains_range(source.value.syntax().text_range());
+                return (have_same_parent, in_sel);
+            }
+        };
+    }
+
+    match def {
+        Definition::Module(x) => {
+            let source = x.definition_source(ctx.db());
+            let have_same_parent = match (&curr_parent_module, x.parent(ctx.db())) {
+                (Some(ast_module), Some(hir_module)) => {
+                    ctx.sema.to_module_def(ast_module).is_some_and(|it| it == hir_module)
+                }
+                _ => source.file_id.original_file(ctx.db()) == curr_file_id,
+            };
+
+            if have_same_parent {
+                if let ModuleSource::Module(module_) = source.value {
+                    let in_sel = !selection_range.contains_range(module_.syntax().text_range());
+                    return (have_same_parent, in_sel);
+                }
+            }
+
+            return (have_same_parent, false);
+        }
+        Definition::Function(x) => check_item!(x),
+        Definition::Adt(x) => check_item!(x),
+        Definition::Variant(x) => check_item!(x),
+        Definition::Const(x) => check_item!(x),
+        Definition::Static(x) => check_item!(x),
+        Definition::Trait(x) => check_item!(x),
+        Definition::TypeAlias(x) => check_item!(x),
+        _ => {}
+    }
+
+    (false, false)
+}
+
+fn get_use_tree_paths_from_path(
+    path: ast::Path,
+    use_tree_str: &mut Vec<ast::Path>,
+) -> Option<&mut Vec<ast::Path>> {
+    path.syntax()
+        .ancestors()
+        .filter(|x| x.to_string() != path.to_string())
+        .filter_map(ast::UseTree::cast)
+        .find_map(|use_tree| {
+            if let Some(upper_tree_path) = use_tree.path() {
+                if upper_tree_path.to_string() != path.to_string() {
+                    use_tree_str.push(upper_tree_path.clone());
+                    get_use_tree_paths_from_path(upper_tree_path, use_tree_str);
+                    return Some(use_tree);
+                }
+            }
+            None
+        })?;
+
+    Some(use_tree_str)
+}
+
+fn use_tree_of(item: &ast::Item) -> Option<ast::UseTree> {
+    match item {
+        ast::Item::Use(use_) => use_.use_tree(),
+        _ => None,
+    }
+}
@@ -0,0 +1,315 @@
+//! Extraction of statements into free functions of the new module. A run of statements in a
+//! function body is handled by `extract_function`, the bodies of the default methods of a trait
+//! are moved here.
+
+use std::iter;
+
+use ide_db::assists::{AssistId, AssistKind};
+use itertools::Itertools;
+use syntax::{
+    algo::skip_trivia_token,
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        make, HasGenericParams, HasName, HasTypeBounds,
+    },
+    ted, AstNode, Direction,
+    SyntaxKind::{SELF_KW, SELF_TYPE_KW},
+    SyntaxToken, T,
+};
+
+use crate::{handlers::extract_function::unique_name, utils::vis_anchor, AssistContext, Assists};
+
+use super::{configured_visibility, generate_module_def, has_ident, make_items_mut, Module};
+
+/// Moves the bodies of the selected default methods of `trait_` into free functions of a new
+/// module next to it, generic over the implementing type. The default bodies call them instead.
+pub(super) fn extract_default_methods(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+    trait_: ast::Trait,
+) -> Option<()> {
+    let selection = ctx.selection_trimmed();
+    let trait_name = trait_.name()?;
+    let methods = trait_
+        .assoc_item_list()?
+        .assoc_items()
+        .filter(|it| selection.contains_range(it.syntax().text_range()))
+        .map(movable_default_method)
+        .collect::<Option<Vec<_>>>()?;
+    if methods.is_empty() {
+        return None;
+    }
+    let module_name = unique_name(&ctx.sema.scope(trait_.syntax())?, "modname");
+
+    acc.add(
+        AssistId("extract_module", AssistKind::RefactorExtract),
+        "Extract Module",
+        selection,
+        |builder| {
+            let mut module = Module {
+                text_range: selection,
+                name: module_name,
+                body_items: methods.iter().cloned().map(ast::Item::Fn).collect(),
+                use_items: Vec::new(),
+                comments: Vec::new(),
+                destination: None,
+                reexport: false,
+            };
+            // The signatures stay in the trait, so its imports are copied rather than moved.
+            let curr_parent_module = trait_.syntax().ancestors().find_map(ast::Module::cast);
+            module.resolve_imports(curr_parent_module, ctx);
+            module.make_use_stmt_of_node_with_super(trait_name.syntax());
+
+            let generic_name = ('T'..='Z')
+                .map(String::from)
+                .chain(iter::once("Implementor".to_owned()))
+                .find(|name| !has_ident(trait_.syntax(), name))
+                .unwrap_or_default();
+            let mut calls = Vec::new();
+            let copies = make_items_mut(&module.body_items);
+            module.body_items = methods
+                .iter()
+                .zip(copies)
+                .filter_map(|(method, copy)| {
+                    calls.push((
+                        method.body()?,
+                        call_of_free_function(method, &trait_, &module.name)?,
+                    ));
+                    let ast::Item::Fn(copy) = copy else { return None };
+                    free_function(ctx, copy, &trait_, &trait_name, &generic_name)
+                })
+                .map(|item| item.dedent(IndentLevel(1)))
+                .collect();
+
+            let indent = IndentLevel::from_node(trait_.syntax());
+            let module_def = generate_module_def(ctx, &[], &mut module, indent);
+            let mut names =
+                module_def.name().and_then(|it| it.ident_token()).into_iter().collect_vec();
+
+            // The nodes are all looked up in the mutable tree before it's edited.
+            let bodies = calls.iter().map(|(body, _)| builder.make_mut(body.clone())).collect_vec();
+            let trait_ = builder.make_mut(trait_.clone());
+            for (body, (_, (call, module_name))) in bodies.into_iter().zip(calls) {
+                ted::replace(body.syntax(), call.syntax());
+                names.extend(module_name.ident_token());
+            }
+            ted::insert_all_raw(
+                ted::Position::after(trait_.syntax()),
+                vec![
+                    make::tokens::whitespace(&format!("\n\n{indent}")).into(),
+                    module_def.syntax().clone().into(),
+                ],
+            );
+
+            if let Some(cap) = ctx.config.snippet_cap {
+                builder.add_placeholder_snippet_group_tokens(cap, names);
+            }
+        },
+    )
+}
+
+/// Default methods whose parameters can all be passed on by name.
+fn movable_default_method(item: ast::AssocItem) -> Option<ast::Fn> {
+    let ast::AssocItem::Fn(method) = item else { return None };
+    method.body()?;
+    let simple_params = method
+        .param_list()?
+        .params()
+        .all(|param| matches!(param.pat(), Some(ast::Pat::IdentPat(pat)) if pat.pat().is_none()));
+    simple_params.then_some(method)
+}
+
+/// The free function the body of `method` is moved into, made by editing `method`, a mutable copy
+/// of the method, in place. `self` becomes a parameter named `this` of the type `generic_name`,
+/// which replaces `Self` as well.
+fn free_function(
+    ctx: &AssistContext<'_>,
+    method: ast::Fn,
+    trait_: &ast::Trait,
+    trait_name: &ast::Name,
+    generic_name: &str,
+) -> Option<ast::Item> {
+    let this = if has_ident(method.syntax(), "this") { "this_" } else { "this" };
+
+    let self_param = method.param_list()?.self_param();
+    let self_param_range = self_param.as_ref().map(|it| it.syntax().text_range());
+    let renamed = method
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|token| {
+            !self_param_range.is_some_and(|range| range.contains_range(token.text_range()))
+        })
+        .filter_map(|token| match token.kind() {
+            SELF_TYPE_KW => Some((token, generic_name)),
+            SELF_KW if is_self_value(&token) => Some((token, this)),
+            _ => None,
+        })
+        .collect_vec();
+    if let Some(self_param) = &self_param {
+        let ty = match (self_param.ty(), self_param.amp_token()) {
+            (Some(ty), _) => {
+                let ty = ty.clone_subtree().clone_for_update();
+                let self_types = ty
+                    .syntax()
+                    .descendants_with_tokens()
+                    .filter_map(|it| it.into_token())
+                    .filter(|token| token.kind() == SELF_TYPE_KW)
+                    .collect_vec();
+                for token in self_types {
+                    ted::replace(token, mutable_ident(generic_name));
+                }
+                ty
+            }
+            (None, Some(_)) => {
+                let ty = make::ty_ref(make::ty(generic_name), self_param.mut_token().is_some())
+                    .clone_for_update();
+                let amp = ty.syntax().first_token()?;
+                if let Some(lifetime) = self_param.lifetime() {
+                    ted::insert_all(
+                        ted::Position::after(amp),
+                        vec![
+                            lifetime.clone_subtree().clone_for_update().syntax().clone().into(),
+                            make::tokens::single_space().into(),
+                        ],
+                    );
+                }
+                ty
+            }
+            (None, None) => make::ty(generic_name),
+        };
+        let mut_ = self_param.amp_token().is_none() && self_param.mut_token().is_some();
+        let param = make::param(make::ident_pat(false, mut_, make::name(this)).into(), ty);
+        ted::replace(self_param.syntax(), param.clone_for_update().syntax());
+    }
+    for (token, name) in renamed {
+        ted::replace(token, mutable_ident(name));
+    }
+
+    let trait_params = trait_.generic_param_list().into_iter().flat_map(|it| it.generic_params());
+    let trait_args = trait_params.clone().filter_map(|param| generic_arg(&param)).join(", ");
+    let bound = match trait_args.as_str() {
+        "" => make::type_bound_text(&trait_name.to_string()),
+        args => make::type_bound_text(&format!("{trait_name}<{args}>")),
+    };
+    let bound = make::type_param(
+        make::name(generic_name),
+        make::type_bound_list([bound, make::type_bound_text("?Sized")]),
+    );
+    let method_params = method.generic_param_list().into_iter().flat_map(|it| it.generic_params());
+    let (lifetimes, others): (Vec<_>, Vec<_>) = trait_params
+        .chain(method_params)
+        .filter_map(|param| generic_param_without_default(&param))
+        .partition(|param| matches!(param, ast::GenericParam::LifetimeParam(_)));
+    let params = make::generic_param_list(
+        lifetimes.into_iter().chain(iter::once(bound.into())).chain(others),
+    )
+    .clone_for_update();
+    match method.generic_param_list() {
+        Some(list) => ted::replace(list.syntax(), params.syntax()),
+        None => ted::insert_raw(ted::Position::after(method.name()?.syntax()), params.syntax()),
+    }
+
+    let vis = configured_visibility(ctx, make::visibility_pub_super()).clone_for_update();
+    ted::insert_all(
+        ted::Position::before(vis_anchor(method.syntax())?),
+        vec![vis.syntax().clone().into(), make::tokens::single_space().into()],
+    );
+    Some(ast::Item::Fn(method))
+}
+
+/// An identifier token to put into a mutable tree.
+fn mutable_ident(text: &str) -> SyntaxToken {
+    let token =
+        make::tokens::ident(text).parent().unwrap().clone_for_update().first_token().unwrap();
+    token.detach();
+    token
+}
+
+/// The new body of `method`, calling the free function in `module_name`, along with the name of
+/// the module in the call.
+fn call_of_free_function(
+    method: &ast::Fn,
+    trait_: &ast::Trait,
+    module_name: &str,
+) -> Option<(ast::BlockExpr, ast::NameRef)> {
+    let param_list = method.param_list()?;
+    let args = param_list.self_param().map(|_| make::ext::expr_self()).into_iter().chain(
+        param_list.params().filter_map(|param| match param.pat()? {
+            ast::Pat::IdentPat(pat) => {
+                Some(make::expr_path(make::ext::ident_path(&pat.name()?.text())))
+            }
+            _ => None,
+        }),
+    );
+
+    // `Self` can only be inferred from the `self` argument, and the arguments of the trait not at
+    // all.
+    let type_args = |list: Option<ast::GenericParamList>| {
+        list.into_iter()
+            .flat_map(|it| it.generic_params())
+            .filter(|param| !matches!(param, ast::GenericParam::LifetimeParam(_)))
+            .filter_map(|param| generic_arg(&param))
+            .collect_vec()
+    };
+    let trait_args = type_args(trait_.generic_param_list());
+    let mut segment = make::path_segment(make::name_ref(&method.name()?.text()));
+    if param_list.self_param().is_none() || !trait_args.is_empty() {
+        let args = iter::once("Self".to_owned())
+            .chain(trait_args)
+            .chain(type_args(method.generic_param_list()))
+            .map(|arg| make::type_arg(make::ty(&arg)).into());
+        segment = segment.clone_for_update();
+        ted::append_child(
+            segment.syntax(),
+            make::turbofish_generic_arg_list(args).clone_for_update().syntax(),
+        );
+    }
+    let path =
+        make::path_concat(make::ext::ident_path(module_name), make::path_unqualified(segment));
+    let mut call = make::expr_call(make::expr_path(path), make::arg_list(args));
+    if method.async_token().is_some() {
+        call = make::expr_await(call);
+    }
+    if method.unsafe_token().is_some() {
+        call = make::expr_unsafe(make::tail_only_block_expr(call));
+    }
+    let body = make::block_expr(None, Some(call))
+        .indent(IndentLevel::from_node(method.syntax()))
+        .clone_for_update();
+    let module_name = body
+        .syntax()
+        .descendants()
+        .filter_map(ast::Path::cast)
+        .find_map(|path| path.qualifier()?.segment()?.name_ref())?;
+    Some((body, module_name))
+}
+
+/// The argument referring to the generic parameter `param` from where it's declared.
+fn generic_arg(param: &ast::GenericParam) -> Option<String> {
+    Some(match param {
+        ast::GenericParam::LifetimeParam(it) => it.lifetime()?.to_string(),
+        ast::GenericParam::TypeParam(it) => it.name()?.to_string(),
+        ast::GenericParam::ConstParam(it) => it.name()?.to_string(),
+    })
+}
+
+/// Defaults of generic parameters are only allowed on types and traits, not on functions.
+fn generic_param_without_default(param: &ast::GenericParam) -> Option<ast::GenericParam> {
+    Some(match param {
+        ast::GenericParam::LifetimeParam(it) => it.clone().into(),
+        ast::GenericParam::TypeParam(it) => {
+            make::type_param(it.name()?, it.type_bound_list()).into()
+        }
+        ast::GenericParam::ConstParam(it) => make::const_param(it.name()?, it.ty()?).into(),
+    })
+}
+
+/// Whether the `self` keyword refers to the receiver rather than starting a path to a module,
+/// both in code and in the arguments of a macro call.
+fn is_self_value(token: &SyntaxToken) -> bool {
+    let is_path_sep = |it: Option<SyntaxToken>| it.is_some_and(|it| it.kind() == T![::]);
+    !is_path_sep(token.next_token().and_then(|it| skip_trivia_token(it, Direction::Next)))
+        && !is_path_sep(token.prev_token().and_then(|it| skip_trivia_token(it, Direction::Prev)))
+}
@@ -0,0 +1,216 @@
+//! Imports of moved traits for the modules that use their items without naming them, e.g. through
+//! method calls.
+
+use std::iter;
+
+use hir::{ModuleDef, ModuleSource, PathResolution};
+use ide_db::{
+    base_db::{salsa::Database, FileId},
+    defs::Definition,
+    helpers::mod_path_to_ast,
+    search::FileReference,
+    FxHashSet,
+};
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, edit::IndentLevel, make, HasModuleItem, HasName},
+    AstNode,
+    SyntaxKind::{ITEM_LIST, SOURCE_FILE, STMT_LIST},
+    SyntaxNode, TextRange, TextSize,
+};
+
+use crate::AssistContext;
+
+use super::Module;
+
+impl Module {
+    /// Imports of the moved traits for the modules outside of the selection which use their items
+    /// without naming the trait, but only see them as items of the parent module, directly or
+    /// through a glob import. Method calls and associated items named through a type need their
+    /// trait in scope, while the paths of modules importing a trait by name are requalified along
+    /// with its other usages already. Each import is given with the module to insert it into.
+    pub(super) fn trait_imports_for_item_uses(
+        &self,
+        ctx: &AssistContext<'_>,
+        parent_module: Option<hir::Module>,
+    ) -> Vec<(hir::Module, ast::Path)> {
+        // The re-exports keep the traits in scope.
+        let Some(parent_module) = parent_module.filter(|_| !self.reexport) else {
+            return Vec::new();
+        };
+        let mut imports: Vec<(hir::Module, ast::Path)> = Vec::new();
+        for item in &self.body_items {
+            let ast::Item::Trait(trait_) = item else { continue };
+            let (Some(def), Some(name)) = (ctx.sema.to_def(trait_), trait_.name()) else {
+                continue;
+            };
+            let importing_modules: FxHashSet<hir::Module> = ctx
+                .usages(Definition::Trait(def))
+                .into_iter()
+                .flat_map(|(_, refs)| refs)
+                .filter_map(|FileReference { name, .. }| {
+                    let node = match name.syntax() {
+                        syntax::NodeOrToken::Node(node) => node,
+                        syntax::NodeOrToken::Token(token) => token.parent()?,
+                    };
+                    node.ancestors().find_map(ast::Use::cast)?;
+                    ctx.sema.scope(&node).map(|scope| scope.module())
+                })
+                .collect();
+            let uses: Vec<(hir::Module, SyntaxNode)> = trait_item_uses(ctx, def, self.text_range)
+                .into_iter()
+                .filter_map(|(_, node)| {
+                    let module = ctx.sema.scope(&node)?.module();
+                    (!importing_modules.contains(&module)).then_some((module, node))
+                })
+                .collect();
+            // The import of the parent module is seen by the glob imports of its descendants.
+            let parent_imports = uses.iter().any(|(module, _)| *module == parent_module);
+            for (module, node) in uses {
+                if parent_imports
+                    && module != parent_module
+                    && module.path_to_root(ctx.db()).contains(&parent_module)
+                    && glob_imports(ctx, &node, parent_module)
+                {
+                    continue;
+                }
+                let path = make::path_concat(
+                    make::ext::ident_path(&self.name),
+                    make::ext::ident_path(&name.text()),
+                );
+                let path = if module == parent_module {
+                    path
+                } else {
+                    let Some(parent_path) = module.find_use_path(
+                        ctx.db(),
+                        ModuleDef::Module(parent_module),
+                        ctx.config.prefer_no_std,
+                        ctx.config.prefer_prelude,
+                    ) else {
+                        continue;
+                    };
+                    make::path_concat(mod_path_to_ast(&parent_path), path)
+                };
+                let path_text = path.to_string();
+                if !imports.iter().any(|(it, path)| *it == module && path.to_string() == path_text)
+                {
+                    imports.push((module, path));
+                }
+            }
+        }
+        imports
+    }
+}
+
+/// The usages outside of `selection` of the items of `trait_`, or of one of its impls, that need
+/// the trait in scope: method calls, and paths naming an associated function or constant through
+/// a type, like `Type::new()`. Impls for all types, e.g. `impl<T: Display> Trait for T`, are
+/// included, so the items they add to foreign types are found as well.
+pub(super) fn trait_item_uses(
+    ctx: &AssistContext<'_>,
+    trait_: hir::Trait,
+    selection: TextRange,
+) -> Vec<(FileId, SyntaxNode)> {
+    let db = ctx.db();
+    let items: Vec<Definition> = trait_
+        .items(db)
+        .into_iter()
+        .chain(hir::Impl::all_for_trait(db, trait_).into_iter().flat_map(|impl_| impl_.items(db)))
+        .filter(|item| matches!(item, hir::AssocItem::Function(_) | hir::AssocItem::Const(_)))
+        .map(Definition::from)
+        .collect();
+    ctx.prefetch_usages(&items);
+
+    let mut seen = FxHashSet::default();
+    let mut uses = Vec::new();
+    for item in items {
+        for (file_id, refs) in ctx.usages(item) {
+            ctx.db().unwind_if_cancelled();
+            let source_file = ctx.sema.parse(file_id);
+            for FileReference { range, .. } in refs {
+                if file_id == ctx.file_id() && selection.contains_range(range)
+                    || !seen.insert((file_id, range))
+                {
+                    continue;
+                }
+                let Some(name_ref) =
+                    find_node_at_range::<ast::NameRef>(source_file.syntax(), range)
+                else {
+                    continue;
+                };
+                let Some(parent) = name_ref.syntax().parent() else { continue };
+                if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+                    uses.push((file_id, call.syntax().clone()));
+                } else if let Some(path) = ast::PathSegment::cast(parent).map(|it| it.parent_path())
+                {
+                    if is_qualified_by_type(ctx, trait_, &path) {
+                        uses.push((file_id, path.syntax().clone()));
+                    }
+                }
+            }
+        }
+    }
+    uses
+}
+
+/// Whether the qualifier of `path` is a type rather than `trait_`, as in `Type::new` and unlike
+/// `Trait::new`, `<Type as Trait>::new`, or `Self::new` in an impl of `trait_`.
+fn is_qualified_by_type(ctx: &AssistContext<'_>, trait_: hir::Trait, path: &ast::Path) -> bool {
+    let Some(qualifier) = path.qualifier() else { return false };
+    if qualifier.segment().is_some_and(|segment| segment.path_type().is_some()) {
+        return false;
+    }
+    match ctx.sema.resolve_path(&qualifier) {
+        Some(PathResolution::Def(ModuleDef::Trait(_))) => false,
+        Some(PathResolution::SelfType(impl_)) => impl_.trait_(ctx.db()) != Some(trait_),
+        _ => true,
+    }
+}
+
+/// Whether the scope of `node` glob imports the items of `module`.
+fn glob_imports(ctx: &AssistContext<'_>, node: &SyntaxNode, module: hir::Module) -> bool {
+    let Some(module_items) =
+        node.ancestors().find(|it| matches!(it.kind(), ITEM_LIST | SOURCE_FILE))
+    else {
+        return false;
+    };
+    // The `use` items of the enclosing blocks are in scope as well.
+    node.ancestors()
+        .take_while(|it| *it != module_items)
+        .filter(|it| it.kind() == STMT_LIST)
+        .chain(iter::once(module_items.clone()))
+        .flat_map(|container| container.children().filter_map(ast::Use::cast))
+        .flat_map(|use_| use_.syntax().descendants().filter_map(ast::UseTree::cast))
+        .filter(|tree| tree.star_token().is_some())
+        .filter_map(|tree| ctx.sema.resolve_path(&tree.path()?))
+        .any(|resolution| resolution == PathResolution::Def(ModuleDef::Module(module)))
+}
+
+/// Where to insert the `import` into `module`, after its last `use` or before its first item,
+/// and the text to insert there.
+pub(super) fn import_into(
+    ctx: &AssistContext<'_>,
+    module: hir::Module,
+    import: &ast::Use,
+) -> Option<(FileId, TextSize, String)> {
+    let source = module.definition_source(ctx.db());
+    let file_id = source.file_id.file_id()?;
+    let items: Vec<SyntaxNode> = match source.value {
+        ModuleSource::SourceFile(it) => it.items().map(|it| it.syntax().clone()).collect(),
+        ModuleSource::Module(it) => it.item_list()?.items().map(|it| it.syntax().clone()).collect(),
+        ModuleSource::BlockExpr(it) => {
+            it.stmt_list()?.statements().map(|it| it.syntax().clone()).collect()
+        }
+    };
+    match items.iter().rev().find(|it| ast::Use::can_cast(it.kind())) {
+        Some(last_use) => {
+            let indent = IndentLevel::from_node(last_use);
+            Some((file_id, last_use.text_range().end(), format!("\n{indent}{import}")))
+        }
+        None => {
+            let first_item = items.first()?;
+            let indent = IndentLevel::from_node(first_item);
+            Some((file_id, first_item.text_range().start(), format!("{import}\n\n{indent}")))
+        }
+    }
+}
@@ -0,0 +1,576 @@
+//! Rewriting of the usages of the moved items, and of the paths in them that would resolve
+//! differently from their new module.
+
+use hir::{AsAssocItem, ModuleDef, PathResolution, SemanticsScope};
+use ide_db::{
+    base_db::{salsa::Database, FileId},
+    defs::{Definition, NameClass},
+    helpers::mod_path_to_ast,
+    search::FileReference,
+    FxHashMap, FxHashSet,
+};
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, edit::IndentLevel, make},
+    match_ast, ted, AstNode, SyntaxElement, SyntaxNode, TextRange, TextSize,
+};
+
+use crate::{handlers::remove_unused_param::range_to_remove, AssistContext};
+
+use super::{imports::refers_through_alias, Module};
+
+impl Module {
+    pub(super) fn get_usages_and_record_fields(
+        &self,
+        ctx: &AssistContext<'_>,
+    ) -> (FxHashMap<FileId, Vec<(TextRange, String)>>, Vec<SyntaxNode>, FxHashMap<TextSize, ast::Use>)
+    {
+        let mut adt_fields = Vec::new();
+        let mut defs = Vec::new();
+        let mut refs: FxHashMap<FileId, Vec<(TextRange, String)>> = FxHashMap::default();
+        // use `TextSize` as key to avoid repeated use stmts
+        let mut use_stmts_to_be_inserted = FxHashMap::default();
+
+        //Here impl is not included as each item inside impl will be tied to the parent of
+        //implementing block(a struct, enum, etc), if the parent is in selected module, it will
+        //get updated by ADT section given below or if it is not, then we dont need to do any operation
+
+        for item in &self.body_items {
+            ctx.db().unwind_if_cancelled();
+            match_ast! {
+                match (item.syntax()) {
+                    ast::Adt(it) => {
+                        if let Some( nod ) = ctx.sema.to_def(&it) {
+                            let node_def = Definition::Adt(nod);
+                            defs.push(node_def);
+
+                            //Enum Fields are not allowed to explicitly specify pub, it is implied
+                            match it {
+                                ast::Adt::Struct(x) => {
+                                    if let Some(field_list) = x.field_list() {
+                                        match field_list {
+                                            ast::FieldList::RecordFieldList(record_field_list) => {
+                                                record_field_list.fields().for_each(|record_field| {
+                                                    adt_fields.push(record_field.syntax().clone());
+                                                });
+                                            },
+                                            ast::FieldList::TupleFieldList(tuple_field_list) => {
+                                                tuple_field_list.fields().for_each(|tuple_field| {
+                                                    adt_fields.push(tuple_field.syntax().clone());
+                                                });
+                                            },
+                                        }
+                                    }
+                                },
+                                ast::Adt::Union(x) => {
+                                        if let Some(record_field_list) = x.record_field_list() {
+                                            record_field_list.fields().for_each(|record_field| {
+                                                    adt_fields.push(record_field.syntax().clone());
+                                            });
+                                        }
+                                },
+                                ast::Adt::Enum(_) => {},
+                            }
+                        }
+                    },
+                    ast::TypeAlias(it) => {
+                        if let Some( nod ) = ctx.sema.to_def(&it) {
+                            let node_def = Definition::TypeAlias(nod);
+                            defs.push(node_def);
+                        }
+                    },
+                    ast::Const(it) => {
+                        if let Some( nod ) = ctx.sema.to_def(&it) {
+                            let node_def = Definition::Const(nod);
+                            defs.push(node_def);
+                        }
+                    },
+                    ast::Static(it) => {
+                        if let Some( nod ) = ctx.sema.to_def(&it) {
+                            let node_def = Definition::Static(nod);
+                            defs.push(node_def);
+                        }
+                    },
+                    ast::Fn(it) => {
+                        if let Some( nod ) = ctx.sema.to_def(&it) {
+                            let node_def = Definition::Function(nod);
+                            defs.push(node_def);
+                        }
+                    },
+                    ast::Macro(it) => {
+                        if let Some(nod) = ctx.sema.to_def(&it) {
+                            defs.push(Definition::Macro(nod));
+                        }
+                    },
+                    ast::Trait(it) => {
+                        if let Some(nod) = ctx.sema.to_def(&it) {
+                            defs.push(Definition::Trait(nod));
+                        }
+                    },
+                    _ => (),
+                }
+            }
+        }
+
+        // Items moved out of an impl are still reached through their type, e.g. as `Self::foo`,
+        // their usages don't go through the new module.
+        defs.retain(|def| def.as_assoc_item(ctx.db()).is_none());
+
+        // A single search for all the items, each file with usages is only parsed once as well.
+        ctx.prefetch_usages(&defs);
+        let mut files = FxHashMap::default();
+        for def in defs {
+            self.expand_and_group_usages_file_wise(
+                ctx,
+                def,
+                &mut files,
+                &mut refs,
+                &mut use_stmts_to_be_inserted,
+            );
+        }
+
+        (refs, adt_fields, use_stmts_to_be_inserted)
+    }
+
+    fn expand_and_group_usages_file_wise(
+        &self,
+        ctx: &AssistContext<'_>,
+        node_def: Definition,
+        files: &mut FxHashMap<FileId, ast::SourceFile>,
+        refs_in_files: &mut FxHashMap<FileId, Vec<(TextRange, String)>>,
+        use_stmts_to_be_inserted: &mut FxHashMap<TextSize, ast::Use>,
+    ) {
+        if let Definition::Macro(mac) = node_def {
+            if mac.is_macro_export(ctx.db()) {
+                self.qualify_exported_macro_usages(ctx, node_def, files, refs_in_files);
+                return;
+            }
+        }
+        if let Some(destination) = self.destination {
+            self.requalify_usages(ctx, node_def, destination, files, refs_in_files);
+            return;
+        }
+        let mod_name = &self.name;
+        let covering_node = match ctx.covering_element() {
+            syntax::NodeOrToken::Node(node) => node,
+            syntax::NodeOrToken::Token(tok) => tok.parent().unwrap(), // won't panic
+        };
+        let parent_module = ctx
+            .sema
+            .scope(&covering_node)
+            .map(|scope| scope.module().nearest_non_block_module(ctx.db()));
+        let out_of_sel = |file_id: FileId, node: &SyntaxNode| {
+            file_id != ctx.file_id() || !self.text_range.contains_range(node.text_range())
+        };
+        let mut use_stmts_set = FxHashSet::default();
+
+        for (file_id, refs) in ctx.usages(node_def) {
+            ctx.db().unwind_if_cancelled();
+            let source_file =
+                files.entry(file_id).or_insert_with(|| ctx.sema.parse(file_id)).clone();
+            let usages = refs.into_iter().filter_map(|FileReference { range, .. }| {
+                // handle normal usages
+                let name_ref = find_node_at_range::<ast::NameRef>(source_file.syntax(), range)?;
+
+                if out_of_sel(file_id, name_ref.syntax()) {
+                    if self.reexport {
+                        return None;
+                    }
+                    let module_path = self.path_to_new_module(ctx, parent_module, &name_ref);
+                    return Some((range, format!("{module_path}::{name_ref}")));
+                } else if let Some(use_) = name_ref.syntax().ancestors().find_map(ast::Use::cast) {
+                    // handle usages in use_stmts which is in_sel
+                    // check if `use` is top stmt in selection
+                    if use_.syntax().parent().is_some_and(|parent| parent == covering_node)
+                        && use_stmts_set.insert(use_.syntax().text_range().start())
+                    {
+                        let use_ = use_stmts_to_be_inserted
+                            .entry(use_.syntax().text_range().start())
+                            .or_insert_with(|| use_.clone_subtree().clone_for_update());
+                        for seg in use_
+                            .syntax()
+                            .descendants()
+                            .filter_map(ast::NameRef::cast)
+                            .filter(|seg| seg.syntax().to_string() == name_ref.to_string())
+                        {
+                            let new_ref = make::path_from_text(&format!("{mod_name}::{seg}"))
+                                .clone_for_update();
+                            ted::replace(seg.syntax().parent()?, new_ref.syntax());
+                        }
+                    }
+                }
+
+                None
+            });
+            refs_in_files.entry(file_id).or_default().extend(usages);
+        }
+    }
+
+    /// The path the new module is referred to with in place of `name_ref`. Qualified paths go
+    /// through the parent module of the selection already, any other usage outside of the parent
+    /// module needs the path to it, e.g. `crate::parent::modname`.
+    fn path_to_new_module(
+        &self,
+        ctx: &AssistContext<'_>,
+        parent_module: Option<hir::Module>,
+        name_ref: &ast::NameRef,
+    ) -> String {
+        let mod_name = &self.name;
+        let Some(path) =
+            name_ref.syntax().parent().and_then(ast::PathSegment::cast).map(|it| it.parent_path())
+        else {
+            return mod_name.clone();
+        };
+        // Nested use trees are relative to their prefix.
+        let in_use_tree_list = path
+            .syntax()
+            .ancestors()
+            .find_map(ast::UseTree::cast)
+            .is_some_and(|tree| tree.parent_use_tree_list().is_some());
+        if path.qualifier().is_some() || in_use_tree_list {
+            return mod_name.clone();
+        }
+        // Blocks with items are modules of their own, compare the modules containing them.
+        let usage_module = ctx
+            .sema
+            .scope(path.syntax())
+            .map(|scope| scope.module().nearest_non_block_module(ctx.db()));
+        let (Some(parent_module), Some(usage_module)) = (parent_module, usage_module) else {
+            return mod_name.clone();
+        };
+        if usage_module == parent_module {
+            return mod_name.clone();
+        }
+        match usage_module.find_use_path(
+            ctx.db(),
+            ModuleDef::Module(parent_module),
+            ctx.config.prefer_no_std,
+            ctx.config.prefer_prelude,
+        ) {
+            Some(parent_path) => format!("{}::{mod_name}", mod_path_to_ast(&parent_path)),
+            None => mod_name.clone(),
+        }
+    }
+
+    /// A `#[macro_export]` macro keeps being exported from the crate root wherever it's defined,
+    /// so its paths stay valid. Its textual scope ends with the new module though, which breaks
+    /// bare invocations outside of the crate root, they are qualified with `crate::` instead.
+    fn qualify_exported_macro_usages(
+        &self,
+        ctx: &AssistContext<'_>,
+        node_def: Definition,
+        files: &mut FxHashMap<FileId, ast::SourceFile>,
+        refs_in_files: &mut FxHashMap<FileId, Vec<(TextRange, String)>>,
+    ) {
+        for (file_id, refs) in ctx.usages(node_def) {
+            ctx.db().unwind_if_cancelled();
+            let source_file =
+                files.entry(file_id).or_insert_with(|| ctx.sema.parse(file_id)).clone();
+            let edits = refs_in_files.entry(file_id).or_default();
+            for FileReference { range, .. } in refs {
+                if file_id == ctx.file_id() && self.text_range.contains_range(range) {
+                    continue;
+                }
+                let Some(name_ref) =
+                    find_node_at_range::<ast::NameRef>(source_file.syntax(), range)
+                else {
+                    continue;
+                };
+                let Some(path) = name_ref
+                    .syntax()
+                    .parent()
+                    .and_then(ast::PathSegment::cast)
+                    .map(|segment| segment.parent_path())
+                else {
+                    continue;
+                };
+                if path.qualifier().is_some()
+                    || path.syntax().ancestors().any(|it| ast::Use::can_cast(it.kind()))
+                {
+                    continue;
+                }
+                // The crate root sees the macro through its path-based scope.
+                let in_crate_root = ctx.sema.scope(path.syntax()).is_some_and(|scope| {
+                    scope.module().nearest_non_block_module(ctx.db()).is_crate_root()
+                });
+                if !in_crate_root {
+                    edits.push((range, format!("crate::{name_ref}")));
+                }
+            }
+        }
+    }
+
+    /// Rewrites the usages of `node_def` outside of the selection to go through `destination`.
+    /// Use trees nested in a `{}` list are split off into a `use` of their own.
+    fn requalify_usages(
+        &self,
+        ctx: &AssistContext<'_>,
+        node_def: Definition,
+        destination: hir::Module,
+        files: &mut FxHashMap<FileId, ast::SourceFile>,
+        refs_in_files: &mut FxHashMap<FileId, Vec<(TextRange, String)>>,
+    ) {
+        for (file_id, refs) in ctx.usages(node_def) {
+            ctx.db().unwind_if_cancelled();
+            let source_file =
+                files.entry(file_id).or_insert_with(|| ctx.sema.parse(file_id)).clone();
+            let edits = refs_in_files.entry(file_id).or_default();
+            for FileReference { range, .. } in refs {
+                if file_id == ctx.file_id() && self.text_range.contains_range(range) {
+                    continue;
+                }
+                let Some(name_ref) =
+                    find_node_at_range::<ast::NameRef>(source_file.syntax(), range)
+                else {
+                    continue;
+                };
+                let Some(path) = name_ref
+                    .syntax()
+                    .parent()
+                    .and_then(ast::PathSegment::cast)
+                    .map(|segment| segment.parent_path())
+                else {
+                    continue;
+                };
+                let Some(usage_module) = ctx.sema.scope(path.syntax()).map(|it| it.module()) else {
+                    continue;
+                };
+                let Some(destination_path) = usage_module.find_use_path(
+                    ctx.db(),
+                    ModuleDef::Module(destination),
+                    ctx.config.prefer_no_std,
+                    ctx.config.prefer_prelude,
+                ) else {
+                    continue;
+                };
+                let destination_path = mod_path_to_ast(&destination_path);
+                let path_range = TextRange::new(
+                    path.syntax().text_range().start(),
+                    name_ref.syntax().text_range().end(),
+                );
+                let new_path = format!("{destination_path}::{name_ref}");
+
+                let nested_tree = path
+                    .syntax()
+                    .ancestors()
+                    .find_map(ast::UseTree::cast)
+                    .filter(|tree| tree.parent_use_tree_list().is_some());
+                let (Some(tree), Some(use_)) =
+                    (nested_tree, path.syntax().ancestors().find_map(ast::Use::cast))
+                else {
+                    edits.push((path_range, new_path));
+                    continue;
+                };
+                let tree_range = tree.syntax().text_range();
+                let rest = &tree.syntax().text().to_string()
+                    [usize::from(path_range.end() - tree_range.start())..];
+                let indent = IndentLevel::from_node(use_.syntax());
+                edits.push((range_to_remove(tree.syntax()), String::new()));
+                edits.push((
+                    TextRange::empty(use_.syntax().text_range().end()),
+                    format!("\n{indent}use {new_path}{rest};"),
+                ));
+            }
+        }
+    }
+
+    /// Definitions of the selected items and everything in them.
+    pub(super) fn defs_in_selection(&self, ctx: &AssistContext<'_>) -> FxHashSet<Definition> {
+        self.body_items
+            .iter()
+            .flat_map(|item| item.syntax().descendants())
+            .filter_map(ast::Name::cast)
+            .filter_map(|name| match NameClass::classify(&ctx.sema, &name)? {
+                NameClass::Definition(def) => Some(def),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Replacements for the first segments of the paths in the selected items that would resolve
+    /// differently from `target_scope`, with a path to what they refer to right now. Paths going
+    /// through a `use ... as` alias are kept, and imports re-creating the aliases are returned
+    /// instead. Items `target_scope` can't see yet are named through their module, they're made
+    /// visible by `visibility_edits_for_destination`.
+    pub(super) fn paths_to_fix_for_destination(
+        &self,
+        ctx: &AssistContext<'_>,
+        moved_defs: &FxHashSet<Definition>,
+        target_scope: &SemanticsScope<'_>,
+    ) -> (Vec<(SyntaxElement, Option<SyntaxNode>)>, Vec<ast::Item>) {
+        let destination = target_scope.module();
+        let mut alias_imports = Vec::new();
+        let replacements = self
+            .use_items
+            .iter()
+            .chain(&self.body_items)
+            .flat_map(|item| item.syntax().descendants())
+            .filter_map(ast::Path::cast)
+            .filter(|path| path.qualifier().is_none())
+            // `super` chains are taken care of by `super_chains_to_fix`.
+            .filter(|path| {
+                path.segment().and_then(|it| it.kind()) != Some(ast::PathSegmentKind::SuperKw)
+            })
+            // Nested use trees are relative to their prefix.
+            .filter(|path| {
+                !path
+                    .syntax()
+                    .ancestors()
+                    .find_map(ast::UseTree::cast)
+                    .is_some_and(|tree| tree.parent_use_tree_list().is_some())
+            })
+            .filter_map(|path| {
+                let Some(PathResolution::Def(def)) = ctx.sema.resolve_path(&path) else {
+                    return None;
+                };
+                if moved_defs.contains(&Definition::from(def)) {
+                    return None;
+                }
+                // Paths to moved items through a module, e.g. `self::Moved`, keep working.
+                let full_path = path.syntax().ancestors().map_while(ast::Path::cast).last()?;
+                if let Some(PathResolution::Def(full_def)) = ctx.sema.resolve_path(&full_path) {
+                    if moved_defs.contains(&Definition::from(full_def)) {
+                        return None;
+                    }
+                }
+                if target_scope.speculative_resolve(&path) == Some(PathResolution::Def(def)) {
+                    return None;
+                }
+                let name_ref = path.segment()?.name_ref()?;
+                let new_path = match destination.find_use_path(
+                    ctx.db(),
+                    def,
+                    ctx.config.prefer_no_std,
+                    ctx.config.prefer_prelude,
+                ) {
+                    Some(new_path) => mod_path_to_ast(&new_path),
+                    None => path_from_crate_root(ctx, def, &name_ref)?,
+                };
+                if refers_through_alias(ctx, def.into(), &name_ref) {
+                    let alias = make::rename(make::name(&name_ref.text()));
+                    let use_ = make::use_(None, make::use_tree(new_path, None, Some(alias), false));
+                    alias_imports.push(ast::Item::from(use_));
+                    return None;
+                }
+                Some((name_ref.syntax().clone().into(), Some(new_path.syntax().clone_for_update())))
+            })
+            .collect();
+        (replacements, alias_imports)
+    }
+
+    /// Replacements for the `super` chains in the selected items that lead out of them, e.g.
+    /// `super` in `super::foo`, as they would miss a level once the items are moved. Without a
+    /// `destination` the items go one module deeper and another `super` is prepended, otherwise
+    /// the chain is replaced with a path to the same module from `destination`.
+    pub(super) fn super_chains_to_fix(
+        &self,
+        ctx: &AssistContext<'_>,
+        moved_defs: &FxHashSet<Definition>,
+        destination: Option<hir::Module>,
+    ) -> Vec<(SyntaxElement, Option<SyntaxNode>)> {
+        let db = ctx.db();
+        let Some(current) = self.body_items.first().and_then(|it| ctx.sema.scope(it.syntax()))
+        else {
+            return Vec::new();
+        };
+        let outer_modules = current.module().path_to_root(db);
+        let is_super = |path: &ast::Path| {
+            path.segment().and_then(|it| it.kind()) == Some(ast::PathSegmentKind::SuperKw)
+        };
+        self.body_items
+            .iter()
+            .flat_map(|item| item.syntax().descendants())
+            .filter_map(ast::Path::cast)
+            .filter(|path| path.qualifier().is_none() && is_super(path))
+            // `pub(super)` and the like are taken care of by `change_visibility`.
+            .filter(|path| {
+                !path.syntax().ancestors().any(|it| ast::Visibility::can_cast(it.kind()))
+            })
+            .filter_map(|first| {
+                let chain = first
+                    .syntax()
+                    .ancestors()
+                    .map_while(ast::Path::cast)
+                    .take_while(is_super)
+                    .last()?;
+                let Some(PathResolution::Def(ModuleDef::Module(module))) =
+                    ctx.sema.resolve_path(&chain)
+                else {
+                    return None;
+                };
+                if !outer_modules.contains(&module) {
+                    return None;
+                }
+                // Modules moved along with the items still reach the moved items this way.
+                let rest = chain.syntax().parent().and_then(ast::Path::cast);
+                if let Some(PathResolution::Def(def)) =
+                    rest.and_then(|it| ctx.sema.resolve_path(&it))
+                {
+                    if moved_defs.contains(&Definition::from(def)) {
+                        return None;
+                    }
+                }
+                let (element, new_path) = match destination {
+                    None => (first.syntax().clone(), make::path_from_text("super::super")),
+                    Some(destination) => {
+                        let new_path = destination.find_use_path(
+                            db,
+                            ModuleDef::Module(module),
+                            ctx.config.prefer_no_std,
+                            ctx.config.prefer_prelude,
+                        )?;
+                        (chain.syntax().clone(), mod_path_to_ast(&new_path))
+                    }
+                };
+                Some((element.into(), Some(new_path.syntax().clone_for_update())))
+            })
+            .collect()
+    }
+
+    /// Qualifiers in the selected items that refer to `module`, e.g. `util::` in `util::foo`,
+    /// as elements to delete. They have to be dropped once the items are moved into `module`.
+    pub(super) fn qualifiers_referring_to(
+        &self,
+        ctx: &AssistContext<'_>,
+        module: &ast::Module,
+    ) -> Vec<(SyntaxElement, Option<SyntaxNode>)> {
+        let Some(module) = ctx.sema.to_def(module) else { return Vec::new() };
+        self.body_items
+            .iter()
+            .flat_map(|item| item.syntax().descendants())
+            .filter_map(ast::Path::cast)
+            .filter_map(|path| Some((path.qualifier()?, path.coloncolon_token()?)))
+            .filter(|(qualifier, _)| qualifier.qualifier().is_none())
+            .filter(|(qualifier, _)| {
+                matches!(
+                    ctx.sema.resolve_path(qualifier),
+                    Some(PathResolution::Def(ModuleDef::Module(it))) if it == module
+                )
+            })
+            .flat_map(|(qualifier, coloncolon)| {
+                [(qualifier.syntax().clone().into(), None), (coloncolon.into(), None)]
+            })
+            .collect()
+    }
+}
+
+/// The path to `def` from the crate root, through the modules containing it, whatever their
+/// visibility.
+fn path_from_crate_root(
+    ctx: &AssistContext<'_>,
+    def: ModuleDef,
+    name_ref: &ast::NameRef,
+) -> Option<ast::Path> {
+    let db = ctx.db();
+    let mut segments = vec![make::path_segment_crate()];
+    for module in def.module(db)?.path_to_root(db).into_iter().rev().skip(1) {
+        let name = module.name(db)?.display(db).to_string();
+        segments.push(make::path_segment(make::name_ref(&name)));
+    }
+    if let ModuleDef::Variant(variant) = def {
+        let name = variant.parent_enum(db).name(db).display(db).to_string();
+        segments.push(make::path_segment(make::name_ref(&name)));
+    }
+    segments.push(make::path_segment(make::name_ref(&name_ref.text())));
+    Some(make::path_from_segments(segments, false))
+}
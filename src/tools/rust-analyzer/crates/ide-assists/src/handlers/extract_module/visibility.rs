@@ -0,0 +1,413 @@
+//! The visibility of the moved items, the narrowest one their usages outside of the new module
+//! need unless configured otherwise.
+
+use hir::{HasVisibility as _, ModuleDef, PathResolution};
+use ide_db::{
+    base_db::{salsa::Database, FileId, FileRange},
+    defs::Definition,
+    search::FileReference,
+    FxHashSet, RootDatabase,
+};
+use itertools::Itertools;
+use syntax::{
+    algo::find_node_at_range,
+    ast::{self, make, HasAttrs, HasName, HasVisibility},
+    ted, AstNode, SyntaxElement, SyntaxNode, TextRange,
+};
+
+use crate::{
+    utils::{vis_anchor, vis_owner_def},
+    AssistContext,
+};
+
+use super::{
+    configured_visibility, counterpart, has_ident, make_items_mut, replace_elements,
+    trait_imports::trait_item_uses, Module,
+};
+
+impl Module {
+    /// Names of the selected items which are used outside of the selection or are part of the
+    /// public API of the crate, they are the ones to re-export from the parent module. The latter
+    /// are marked with `true`. Each name comes with the attributes its re-export needs to expose
+    /// no more than the item: the `#[cfg]`s not moved onto the module, and `#[doc(hidden)]`.
+    pub(super) fn reexported_names(
+        &self,
+        ctx: &AssistContext<'_>,
+    ) -> Vec<(String, bool, Vec<ast::Attr>)> {
+        let db = ctx.db();
+        let common_cfgs = self.common_cfgs().iter().map(ToString::to_string).collect_vec();
+        // Only the `pub` items of modules that other crates can name are part of the public API.
+        let parent_is_exported =
+            self.body_items.first().and_then(|item| ctx.sema.scope(item.syntax())).is_some_and(
+                |scope| {
+                    scope.module().path_to_root(db).into_iter().all(|module| {
+                        module.is_crate_root()
+                            || module.name(db).is_some()
+                                && hir::HasVisibility::visibility(&module, db)
+                                    == hir::Visibility::Public
+                    })
+                },
+            );
+        self.body_items
+            .iter()
+            .filter(|item| {
+                matches!(
+                    item,
+                    ast::Item::Const(_)
+                        | ast::Item::Enum(_)
+                        | ast::Item::Fn(_)
+                        | ast::Item::MacroDef(_)
+                        | ast::Item::Module(_)
+                        | ast::Item::Static(_)
+                        | ast::Item::Struct(_)
+                        | ast::Item::Trait(_)
+                        | ast::Item::TypeAlias(_)
+                        | ast::Item::Union(_)
+                )
+            })
+            .filter_map(|item| {
+                let public = parent_is_exported
+                    && ast::AnyHasVisibility::cast(item.syntax().clone())
+                        .and_then(|it| it.visibility())
+                        .is_some_and(|vis| matches!(vis.kind(), ast::VisibilityKind::Pub));
+                if !public
+                    && required_visibility(ctx, self.text_range, None, item.syntax()).is_none()
+                {
+                    return None;
+                }
+                let name = ast::AnyHasName::cast(item.syntax().clone())?.name()?;
+                let attrs = item
+                    .attrs()
+                    .filter(|attr| {
+                        is_cfg(attr) && !common_cfgs.contains(&attr.to_string())
+                            || public && is_doc_hidden(attr)
+                    })
+                    .collect();
+                Some((name.to_string(), public, attrs))
+            })
+            .collect()
+    }
+
+    /// Also applies the `element_replacements` in the items, deleting the ones without a
+    /// replacement. The items are edited in place, in a mutable copy of the tree.
+    pub(super) fn change_visibility(
+        &mut self,
+        ctx: &AssistContext<'_>,
+        record_fields: Vec<SyntaxNode>,
+        element_replacements: &[(SyntaxElement, Option<SyntaxNode>)],
+    ) {
+        let (text_range, destination, reexport) =
+            (self.text_range, self.destination, self.reexport);
+        let items = make_items_mut(&self.body_items);
+        // The edits are looked up on the original items, which are known to the semantic model,
+        // and only made once all of them are found in the copies.
+        let mut insertions = Vec::new();
+        let mut replacements = Vec::new();
+        for (item, copy) in self.body_items.iter().zip(&items) {
+            ctx.db().unwind_if_cancelled();
+            let (mut vis_replacements, record_field_parents, impls) =
+                get_replacements_for_visibility_change(std::slice::from_ref(item));
+
+            let impl_items = impls
+                .into_iter()
+                .flat_map(|impl_| impl_.syntax().descendants())
+                .filter_map(ast::Item::cast)
+                .collect_vec();
+
+            let (mut impl_item_replacements, _, _) =
+                get_replacements_for_visibility_change(&impl_items);
+
+            vis_replacements.append(&mut impl_item_replacements);
+
+            for (_, field_owner) in record_field_parents {
+                for desc in field_owner.descendants().filter_map(ast::RecordField::cast) {
+                    let is_record_field_present = record_fields
+                        .clone()
+                        .into_iter()
+                        .any(|x| x.to_string() == desc.to_string());
+                    if is_record_field_present {
+                        vis_replacements.push((desc.visibility(), desc.syntax().clone()));
+                    }
+                }
+            }
+
+            for (vis, syntax) in vis_replacements {
+                if vis.is_some() {
+                    continue;
+                }
+                let Some(vis) = required_visibility(ctx, text_range, destination, &syntax) else {
+                    continue;
+                };
+                // A re-export can't be more visible than the item itself.
+                let vis = if reexport && syntax == *item.syntax() {
+                    make::visibility_pub_crate()
+                } else if [&syntax, item.syntax()].into_iter().any(is_hidden_or_feature_gated) {
+                    vis
+                } else {
+                    configured_visibility(ctx, vis)
+                };
+                // We're skipping comments, doc comments, and attribute macros that may precede
+                // the keyword that the visibility should be placed before.
+                if let Some(anchor) =
+                    vis_anchor(&syntax).and_then(|it| counterpart(copy.syntax(), &it))
+                {
+                    insertions.push((anchor, vis));
+                }
+            }
+
+            let item_range = item.syntax().text_range();
+            replacements.extend(
+                element_replacements
+                    .iter()
+                    .filter(|(it, _)| item_range.contains_range(it.text_range()))
+                    .filter_map(|(element, replacement)| {
+                        Some((counterpart(copy.syntax(), element)?, replacement.clone()))
+                    }),
+            );
+        }
+
+        for (anchor, vis) in insertions {
+            ted::insert_all(
+                ted::Position::before(anchor),
+                vec![
+                    vis.clone_for_update().syntax().clone().into(),
+                    make::tokens::single_space().into(),
+                ],
+            );
+        }
+        replace_elements(replacements);
+        self.body_items = items;
+    }
+    /// Edits widening the visibility of the items the moved items refer to, which `destination`
+    /// can't see, e.g. private items next to the selection. Returns `None` if one of them can't be
+    /// changed, as it's defined by a macro or in another crate.
+    pub(super) fn visibility_edits_for_destination(
+        &self,
+        ctx: &AssistContext<'_>,
+        moved_defs: &FxHashSet<Definition>,
+        destination: hir::Module,
+    ) -> Option<Vec<(FileId, TextRange, String)>> {
+        let db = ctx.db();
+        let mut hidden: FxHashSet<ModuleDef> = self
+            .body_items
+            .iter()
+            .flat_map(|item| item.syntax().descendants())
+            .filter_map(ast::Path::cast)
+            .filter_map(|path| match ctx.sema.resolve_path(&path)? {
+                PathResolution::Def(ModuleDef::Variant(variant)) => {
+                    Some(ModuleDef::Adt(variant.parent_enum(db).into()))
+                }
+                PathResolution::Def(def) => Some(def),
+                _ => None,
+            })
+            .filter(|&def| {
+                !moved_defs.contains(&Definition::from(def))
+                    && !def.is_visible_from(db, destination)
+            })
+            .collect();
+        // They're named through the modules containing them, which need to be visible as well.
+        let modules = hidden
+            .iter()
+            .filter_map(|def| def.module(db))
+            .flat_map(|module| module.path_to_root(db))
+            .filter(|module| !module.is_crate_root() && !module.is_visible_from(db, destination))
+            .map(ModuleDef::Module)
+            .collect_vec();
+        hidden.extend(modules);
+
+        let mut edits = Vec::new();
+        for def in hidden {
+            let module = def.module(db)?;
+            // The items of blocks can't be named from the outside at all.
+            if module.krate() != destination.krate()
+                || module.nearest_non_block_module(db) != module
+            {
+                return None;
+            }
+            let vis = visibility_from(db, module, destination)?;
+            let FileRange { file_id, range } = Definition::from(def).range_for_rename(&ctx.sema)?;
+            let file = ctx.sema.parse(file_id);
+            let owner = find_node_at_range::<ast::Name>(file.syntax(), range)?.syntax().parent()?;
+            match ast::AnyHasVisibility::cast(owner.clone())?.visibility() {
+                Some(old) => edits.push((file_id, old.syntax().text_range(), vis.to_string())),
+                None => {
+                    let anchor = vis_anchor(&owner)?.text_range().start();
+                    edits.push((file_id, TextRange::empty(anchor), format!("{vis} ")));
+                }
+            }
+        }
+        Some(edits)
+    }
+}
+
+/// The narrowest visibility that lets `user` name the items of `module`, `None` if private items
+/// are visible to it already.
+fn visibility_from(
+    db: &RootDatabase,
+    module: hir::Module,
+    user: hir::Module,
+) -> Option<ast::Visibility> {
+    let path_to_root = user.path_to_root(db);
+    if path_to_root.contains(&module) {
+        return None;
+    }
+    match module.parent(db) {
+        Some(parent) if path_to_root.contains(&parent) => Some(make::visibility_pub_super()),
+        _ => Some(make::visibility_pub_crate()),
+    }
+}
+
+/// Computes the narrowest visibility the item or record field `node` needs once it's moved into
+/// the new module, or into `destination` if set, based on where it's used outside of the
+/// `selection`. Returns `None` if it can stay private.
+fn required_visibility(
+    ctx: &AssistContext<'_>,
+    selection: TextRange,
+    destination: Option<hir::Module>,
+    node: &SyntaxNode,
+) -> Option<ast::Visibility> {
+    let def = vis_owner_def(&ctx.sema, node).or_else(|| {
+        // Items under attribute macros are only defined by their expansion.
+        let expansion = ctx.sema.expand_attr_macro(&ast::Item::cast(node.clone())?)?;
+        expansion
+            .descendants()
+            .filter(|it| it.kind() == node.kind())
+            .find_map(|it| vis_owner_def(&ctx.sema, &it))
+    });
+    // Without a definition to look up usages for, stay on the safe side.
+    let Some((def, parent)) = def.and_then(|def| Some((def, def.module(ctx.db())?))) else {
+        return Some(make::visibility_pub_crate());
+    };
+
+    // The new module is a child of the current one.
+    let parent = match destination {
+        Some(destination) => destination.parent(ctx.db()),
+        None => Some(parent),
+    };
+
+    let covering_node = match ctx.covering_element() {
+        syntax::NodeOrToken::Node(node) => node,
+        syntax::NodeOrToken::Token(token) => token.parent().unwrap(), // won't panic
+    };
+    let mut usages = Vec::new();
+    for (file_id, refs) in ctx.usages(def) {
+        ctx.db().unwind_if_cancelled();
+        for FileReference { range, name, .. } in refs {
+            let node = match name.syntax() {
+                syntax::NodeOrToken::Node(node) => node,
+                syntax::NodeOrToken::Token(token) => match token.parent() {
+                    Some(node) => node,
+                    None => continue,
+                },
+            };
+            usages.push((file_id, range, node));
+        }
+    }
+    // The trait is imported wherever its items are used without naming it.
+    if let Definition::Trait(trait_) = def {
+        usages.extend(
+            trait_item_uses(ctx, trait_, selection)
+                .into_iter()
+                .map(|(file_id, node)| (file_id, node.text_range(), node)),
+        );
+    }
+
+    let mut used_in_parent = false;
+    for (file_id, range, node) in usages {
+        if file_id == ctx.file_id() && selection.contains_range(range) {
+            // Top level `use` items of the selection are copied out of the new module, but
+            // moved along into an existing one.
+            let in_top_level_use = node
+                .ancestors()
+                .find_map(ast::Use::cast)
+                .is_some_and(|use_| use_.syntax().parent().as_ref() == Some(&covering_node));
+            if destination.is_some() || !in_top_level_use {
+                continue;
+            }
+        }
+        // The path to the root of a block with items stops at the block.
+        let Some(module) =
+            ctx.sema.scope(&node).map(|scope| scope.module().nearest_non_block_module(ctx.db()))
+        else {
+            return Some(make::visibility_pub_crate());
+        };
+        let path_to_root = module.path_to_root(ctx.db());
+        if destination.is_some_and(|it| path_to_root.contains(&it)) {
+            continue;
+        }
+        if !parent.is_some_and(|it| path_to_root.contains(&it)) {
+            return Some(make::visibility_pub_crate());
+        }
+        used_in_parent = true;
+    }
+
+    used_in_parent.then(make::visibility_pub_super)
+}
+
+pub(super) fn is_cfg(attr: &ast::Attr) -> bool {
+    attr.as_simple_call().is_some_and(|(name, _)| name == "cfg")
+}
+
+fn is_doc_hidden(attr: &ast::Attr) -> bool {
+    attr.as_simple_call()
+        .is_some_and(|(name, tt)| name == "doc" && has_ident(tt.syntax(), "hidden"))
+}
+
+/// Whether `node` is `#[doc(hidden)]` or only compiled with some feature. Such items aren't meant
+/// to be part of the API in general, so they don't get a wider visibility than they need.
+fn is_hidden_or_feature_gated(node: &SyntaxNode) -> bool {
+    ast::AnyHasAttrs::cast(node.clone()).is_some_and(|it| {
+        it.attrs().any(|attr| {
+            is_doc_hidden(&attr)
+                || attr
+                    .as_simple_call()
+                    .is_some_and(|(name, tt)| name == "cfg" && has_ident(tt.syntax(), "feature"))
+        })
+    })
+}
+
+fn get_replacements_for_visibility_change(
+    items: &[ast::Item],
+) -> (
+    Vec<(Option<ast::Visibility>, SyntaxNode)>,
+    Vec<(Option<ast::Visibility>, SyntaxNode)>,
+    Vec<ast::Impl>,
+) {
+    let mut replacements = Vec::new();
+    let mut record_field_parents = Vec::new();
+    let mut impls = Vec::new();
+
+    for item in items {
+        //Use stmts are ignored
+        macro_rules! push_to_replacement {
+            ($it:ident) => {
+                replacements.push(($it.visibility(), $it.syntax().clone()))
+            };
+        }
+
+        match item {
+            ast::Item::Const(it) => push_to_replacement!(it),
+            ast::Item::Enum(it) => push_to_replacement!(it),
+            ast::Item::ExternCrate(it) => push_to_replacement!(it),
+            ast::Item::Fn(it) => push_to_replacement!(it),
+            //Associated item's visibility should not be changed
+            ast::Item::Impl(it) if it.for_token().is_none() => impls.push(it.clone()),
+            ast::Item::MacroDef(it) => push_to_replacement!(it),
+            ast::Item::Module(it) => push_to_replacement!(it),
+            ast::Item::Static(it) => push_to_replacement!(it),
+            ast::Item::Struct(it) => {
+                push_to_replacement!(it);
+                record_field_parents.push((it.visibility(), it.syntax().clone()));
+            }
+            ast::Item::Trait(it) => push_to_replacement!(it),
+            ast::Item::TypeAlias(it) => push_to_replacement!(it),
+            ast::Item::Union(it) => {
+                push_to_replacement!(it);
+                record_field_parents.push((it.visibility(), it.syntax().clone()));
+            }
+            _ => (),
+        }
+    }
+
+    (replacements, record_field_parents, impls)
+}
@@ -1,12 +1,19 @@
-use hir::TypeInfo;
+use either::Either;
+use hir::{Local, ModuleDef, PathResolution, TypeInfo};
+use ide_db::source_change::SourceChangeBuilder;
 use syntax::{
     ast::{self, edit::IndentLevel, edit_in_place::Indent, make, AstNode, HasName},
-    ted, NodeOrToken,
-    SyntaxKind::{BLOCK_EXPR, BREAK_EXPR, COMMENT, LOOP_EXPR, MATCH_GUARD, PATH_EXPR, RETURN_EXPR},
-    SyntaxNode,
+    match_ast, ted, NodeOrToken,
+    SyntaxKind::{
+        BLOCK_EXPR, BREAK_EXPR, COMMENT, CONTINUE_EXPR, FOR_EXPR, IDENT, LOOP_EXPR, MACRO_CALL,
+        MATCH_GUARD, PATH_EXPR, RETURN_EXPR, TRY_EXPR, WHILE_EXPR, YIELD_EXPR,
+    },
+    SyntaxNode, TextRange, TextSize,
 };
 
-use crate::{utils::suggest_name, AssistContext, AssistId, AssistKind, Assists};
+use crate::{utils::suggest_name, AssistContext, AssistId, AssistKind, Assists, GroupLabel};
+
+use super::extract_function::expr_require_exclusive_access;
 
 // Assist: extract_variable
 //
@@ -64,141 +71,531 @@ pub(crate) fn extract_variable(acc: &mut Assists, ctx: &AssistContext<'_>) -> Op
 
     let anchor = Anchor::from(&to_extract)?;
     let target = to_extract.syntax().text_range();
-    acc.add(
-        AssistId("extract_variable", AssistKind::RefactorExtract),
-        "Extract into variable",
-        target,
-        move |edit| {
-            let field_shorthand = to_extract
-                .syntax()
-                .parent()
-                .and_then(ast::RecordExprField::cast)
-                .filter(|field| field.name_ref().is_some());
-
-            let (var_name, expr_replace) = match field_shorthand {
-                Some(field) => (field.to_string(), field.syntax().clone()),
-                None => (
-                    suggest_name::for_variable(&to_extract, &ctx.sema),
-                    to_extract.syntax().clone(),
-                ),
-            };
-
-            let ident_pat = match parent {
-                Some(ast::Expr::RefExpr(expr)) if expr.mut_token().is_some() => {
-                    make::ident_pat(false, true, make::name(&var_name))
+    if let Some((first_stmt, occurrences)) = identical_occurrences(ctx, &to_extract) {
+        let to_extract = to_extract.clone();
+        acc.add(
+            AssistId("extract_variable", AssistKind::RefactorExtract),
+            format!("Extract all {} occurrences into variable", occurrences.len()),
+            target,
+            move |edit| {
+                let var_name = suggest_name::for_variable(&to_extract, &ctx.sema);
+                let prev_ws = first_stmt.prev_sibling_or_token().and_then(|it| it.into_token());
+                let indent_to = IndentLevel::from_node(&first_stmt);
+                let trailing_ws = if prev_ws.is_some_and(|it| it.text().starts_with('\n')) {
+                    format!("\n{indent_to}")
+                } else {
+                    " ".to_owned()
+                };
+
+                let occurrences: Vec<_> =
+                    occurrences.into_iter().map(|it| edit.make_mut(it)).collect();
+                let first_stmt = edit.make_syntax_mut(first_stmt);
+                let ident_pat = make::ident_pat(false, false, make::name(&var_name));
+                let let_stmt =
+                    make::let_stmt(ident_pat.into(), None, Some(to_extract)).clone_for_update();
+                ted::insert_all_raw(
+                    ted::Position::before(first_stmt),
+                    vec![
+                        let_stmt.syntax().clone().into(),
+                        make::tokens::whitespace(&trailing_ws).into(),
+                    ],
+                );
+                for occurrence in occurrences {
+                    let name_expr =
+                        make::expr_path(make::ext::ident_path(&var_name)).clone_for_update();
+                    // The parentheses around an occurrence aren't needed around the name.
+                    let occurrence = match occurrence.syntax().parent() {
+                        Some(parent) if ast::ParenExpr::can_cast(parent.kind()) => parent,
+                        _ => occurrence.syntax().clone(),
+                    };
+                    ted::replace(occurrence, name_expr.syntax());
                 }
-                _ => make::ident_pat(false, false, make::name(&var_name)),
-            };
 
-            let to_extract = match ty.as_ref().filter(|_| needs_adjust) {
-                Some(receiver_type) if receiver_type.is_mutable_reference() => {
-                    make::expr_ref(to_extract, true)
-                }
-                Some(receiver_type) if receiver_type.is_reference() => {
-                    make::expr_ref(to_extract, false)
+                if let Some(cap) = ctx.config.snippet_cap {
+                    if let Some(ast::Pat::IdentPat(ident_pat)) = let_stmt.pat() {
+                        if let Some(name) = ident_pat.name() {
+                            edit.add_tabstop_before(cap, name);
+                        }
+                    }
                 }
-                _ => to_extract,
+            },
+        );
+    }
+    // Placing the variable elsewhere is offered as a group with the usual placement.
+    let placements = other_placements(ctx, &to_extract, &anchor);
+    let group = GroupLabel("Extract into variable".to_owned());
+    let grouped = !placements.is_empty();
+    for (label, place) in placements {
+        let (to_extract, ty, parent) = (to_extract.clone(), ty.clone(), parent.clone());
+        acc.add_group(
+            &group,
+            AssistId("extract_variable", AssistKind::RefactorExtract),
+            label,
+            target,
+            move |edit| {
+                extract_with_anchor(
+                    edit,
+                    ctx,
+                    to_extract,
+                    ty,
+                    parent,
+                    needs_adjust,
+                    Anchor::Before(place),
+                )
+            },
+        );
+    }
+    let extract = move |edit: &mut SourceChangeBuilder| {
+        extract_with_anchor(edit, ctx, to_extract, ty, parent, needs_adjust, anchor)
+    };
+    let id = AssistId("extract_variable", AssistKind::RefactorExtract);
+    match grouped {
+        true => acc.add_group(&group, id, "Extract into variable", target, extract),
+        false => acc.add(id, "Extract into variable", target, extract),
+    }
+}
+
+fn extract_with_anchor(
+    edit: &mut SourceChangeBuilder,
+    ctx: &AssistContext<'_>,
+    to_extract: ast::Expr,
+    ty: Option<hir::Type>,
+    parent: Option<ast::Expr>,
+    needs_adjust: bool,
+    anchor: Anchor,
+) {
+    let field_shorthand = to_extract
+        .syntax()
+        .parent()
+        .and_then(ast::RecordExprField::cast)
+        .filter(|field| field.name_ref().is_some());
+
+    let (var_name, expr_replace) = match field_shorthand {
+        Some(field) => (field.to_string(), field.syntax().clone()),
+        None => (suggest_name::for_variable(&to_extract, &ctx.sema), to_extract.syntax().clone()),
+    };
+
+    let ident_pat = match parent {
+        Some(ast::Expr::RefExpr(expr)) if expr.mut_token().is_some() => {
+            make::ident_pat(false, true, make::name(&var_name))
+        }
+        _ => make::ident_pat(false, false, make::name(&var_name)),
+    };
+
+    let to_extract = match ty.as_ref().filter(|_| needs_adjust) {
+        Some(receiver_type) if receiver_type.is_mutable_reference() => {
+            make::expr_ref(to_extract, true)
+        }
+        Some(receiver_type) if receiver_type.is_reference() => make::expr_ref(to_extract, false),
+        _ => to_extract,
+    };
+
+    let expr_replace = edit.make_syntax_mut(expr_replace);
+    let let_stmt = make::let_stmt(ident_pat.into(), None, Some(to_extract)).clone_for_update();
+    let name_expr = make::expr_path(make::ext::ident_path(&var_name)).clone_for_update();
+
+    match anchor {
+        Anchor::Before(place) => {
+            let prev_ws = place.prev_sibling_or_token().and_then(|it| it.into_token());
+            let indent_to = IndentLevel::from_node(&place);
+            let insert_place = edit.make_syntax_mut(place);
+
+            // Adjust ws to insert depending on if this is all inline or on separate lines
+            let trailing_ws = if prev_ws.is_some_and(|it| it.text().starts_with('\n')) {
+                format!("\n{indent_to}")
+            } else {
+                " ".to_owned()
             };
 
-            let expr_replace = edit.make_syntax_mut(expr_replace);
-            let let_stmt =
-                make::let_stmt(ident_pat.into(), None, Some(to_extract)).clone_for_update();
-            let name_expr = make::expr_path(make::ext::ident_path(&var_name)).clone_for_update();
-
-            match anchor {
-                Anchor::Before(place) => {
-                    let prev_ws = place.prev_sibling_or_token().and_then(|it| it.into_token());
-                    let indent_to = IndentLevel::from_node(&place);
-                    let insert_place = edit.make_syntax_mut(place);
-
-                    // Adjust ws to insert depending on if this is all inline or on separate lines
-                    let trailing_ws = if prev_ws.is_some_and(|it| it.text().starts_with('\n')) {
-                        format!("\n{indent_to}")
-                    } else {
-                        " ".to_owned()
-                    };
+            ted::insert_all_raw(
+                ted::Position::before(insert_place),
+                vec![
+                    let_stmt.syntax().clone().into(),
+                    make::tokens::whitespace(&trailing_ws).into(),
+                ],
+            );
 
-                    ted::insert_all_raw(
-                        ted::Position::before(insert_place),
-                        vec![
-                            let_stmt.syntax().clone().into(),
-                            make::tokens::whitespace(&trailing_ws).into(),
-                        ],
-                    );
-
-                    ted::replace(expr_replace, name_expr.syntax());
-
-                    if let Some(cap) = ctx.config.snippet_cap {
-                        if let Some(ast::Pat::IdentPat(ident_pat)) = let_stmt.pat() {
-                            if let Some(name) = ident_pat.name() {
-                                edit.add_tabstop_before(cap, name);
-                            }
-                        }
+            ted::replace(expr_replace, name_expr.syntax());
+
+            if let Some(cap) = ctx.config.snippet_cap {
+                if let Some(ast::Pat::IdentPat(ident_pat)) = let_stmt.pat() {
+                    if let Some(name) = ident_pat.name() {
+                        edit.add_tabstop_before(cap, name);
                     }
                 }
-                Anchor::Replace(stmt) => {
-                    cov_mark::hit!(test_extract_var_expr_stmt);
+            }
+        }
+        Anchor::Replace(stmt) => {
+            cov_mark::hit!(test_extract_var_expr_stmt);
 
-                    let stmt_replace = edit.make_mut(stmt);
-                    ted::replace(stmt_replace.syntax(), let_stmt.syntax());
+            let stmt_replace = edit.make_mut(stmt);
+            ted::replace(stmt_replace.syntax(), let_stmt.syntax());
 
-                    if let Some(cap) = ctx.config.snippet_cap {
-                        if let Some(ast::Pat::IdentPat(ident_pat)) = let_stmt.pat() {
-                            if let Some(name) = ident_pat.name() {
-                                edit.add_tabstop_before(cap, name);
-                            }
-                        }
+            if let Some(cap) = ctx.config.snippet_cap {
+                if let Some(ast::Pat::IdentPat(ident_pat)) = let_stmt.pat() {
+                    if let Some(name) = ident_pat.name() {
+                        edit.add_tabstop_before(cap, name);
                     }
                 }
-                Anchor::WrapInBlock(to_wrap) => {
-                    let indent_to = to_wrap.indent_level();
-
-                    let block = if to_wrap.syntax() == &expr_replace {
-                        // Since `expr_replace` is the same that needs to be wrapped in a block,
-                        // we can just directly replace it with a block
-                        let block =
-                            make::block_expr([let_stmt.into()], Some(name_expr)).clone_for_update();
-                        ted::replace(expr_replace, block.syntax());
-
-                        block
-                    } else {
-                        // `expr_replace` is a descendant of `to_wrap`, so both steps need to be
-                        // handled separately, otherwise we wrap the wrong expression
-                        let to_wrap = edit.make_mut(to_wrap);
-
-                        // Replace the target expr first so that we don't need to find where
-                        // `expr_replace` is in the wrapped `to_wrap`
-                        ted::replace(expr_replace, name_expr.syntax());
-
-                        // Wrap `to_wrap` in a block
-                        let block = make::block_expr([let_stmt.into()], Some(to_wrap.clone()))
-                            .clone_for_update();
-                        ted::replace(to_wrap.syntax(), block.syntax());
-
-                        block
-                    };
+            }
+        }
+        Anchor::WrapInBlock(to_wrap) => {
+            let indent_to = to_wrap.indent_level();
+
+            let block = if to_wrap.syntax() == &expr_replace {
+                // Since `expr_replace` is the same that needs to be wrapped in a block,
+                // we can just directly replace it with a block
+                let block = make::block_expr([let_stmt.into()], Some(name_expr)).clone_for_update();
+                ted::replace(expr_replace, block.syntax());
+
+                block
+            } else {
+                // `expr_replace` is a descendant of `to_wrap`, so both steps need to be
+                // handled separately, otherwise we wrap the wrong expression
+                let to_wrap = edit.make_mut(to_wrap);
+
+                // Replace the target expr first so that we don't need to find where
+                // `expr_replace` is in the wrapped `to_wrap`
+                ted::replace(expr_replace, name_expr.syntax());
+
+                // Wrap `to_wrap` in a block
+                let block =
+                    make::block_expr([let_stmt.into()], Some(to_wrap.clone())).clone_for_update();
+                ted::replace(to_wrap.syntax(), block.syntax());
+
+                block
+            };
 
-                    if let Some(cap) = ctx.config.snippet_cap {
-                        // Adding a tabstop to `name` requires finding the let stmt again, since
-                        // the existing `let_stmt` is not actually added to the tree
-                        let pat = block.statements().find_map(|stmt| {
-                            let ast::Stmt::LetStmt(let_stmt) = stmt else { return None };
-                            let_stmt.pat()
-                        });
-
-                        if let Some(ast::Pat::IdentPat(ident_pat)) = pat {
-                            if let Some(name) = ident_pat.name() {
-                                edit.add_tabstop_before(cap, name);
-                            }
-                        }
+            if let Some(cap) = ctx.config.snippet_cap {
+                // Adding a tabstop to `name` requires finding the let stmt again, since
+                // the existing `let_stmt` is not actually added to the tree
+                let pat = block.statements().find_map(|stmt| {
+                    let ast::Stmt::LetStmt(let_stmt) = stmt else { return None };
+                    let_stmt.pat()
+                });
+
+                if let Some(ast::Pat::IdentPat(ident_pat)) = pat {
+                    if let Some(name) = ident_pat.name() {
+                        edit.add_tabstop_before(cap, name);
                     }
-
-                    // fixup indentation of block
-                    block.indent(indent_to);
                 }
             }
-        },
-    )
+
+            // fixup indentation of block
+            block.indent(indent_to);
+        }
+    }
+}
+
+/// The statements other than the one `anchor` places the variable in front of which the variable
+/// can be put in front of, along with the labels of the assists doing so: the start of the block,
+/// and in front of the enclosing `loop` if `to_extract` has the same value in each iteration. Both
+/// are only offered if `to_extract` is always evaluated once the new variable is.
+fn other_placements(
+    ctx: &AssistContext<'_>,
+    to_extract: &ast::Expr,
+    anchor: &Anchor,
+) -> Vec<(&'static str, SyntaxNode)> {
+    let mut placements = Vec::new();
+    let Anchor::Before(place) = anchor else { return placements };
+
+    if let Some(stmt_list) = place.parent().and_then(ast::StmtList::cast) {
+        let first = match stmt_list.statements().next() {
+            Some(stmt) => Some(stmt.syntax().clone()),
+            None => stmt_list.tail_expr().map(|it| it.syntax().clone()),
+        };
+        if let Some(first) = first.filter(|it| it != place) {
+            if always_reached(to_extract, &first)
+                && can_evaluate_before(
+                    ctx,
+                    to_extract,
+                    &first,
+                    to_extract.syntax().text_range().end(),
+                )
+            {
+                placements.push(("Extract into variable at start of block", first));
+            }
+        }
+    }
+
+    let loop_ = to_extract
+        .syntax()
+        .ancestors()
+        .take_while(|it| !ast::Item::can_cast(it.kind()) && !ast::ClosureExpr::can_cast(it.kind()))
+        .find(|it| matches!(it.kind(), LOOP_EXPR | WHILE_EXPR | FOR_EXPR));
+    if let Some(loop_) = loop_ {
+        let stmt = loop_
+            .ancestors()
+            .find(|it| it.parent().is_some_and(|parent| ast::StmtList::can_cast(parent.kind())))
+            .filter(|it| it != place && placements.iter().all(|(_, other)| other != it));
+        if let Some(stmt) = stmt {
+            // Changes anywhere in the loop are seen by the next iteration.
+            if always_reached(to_extract, &stmt)
+                && can_evaluate_before(ctx, to_extract, &stmt, loop_.text_range().end())
+            {
+                placements.push(("Extract into variable before loop", stmt));
+            }
+        }
+    }
+    placements
+}
+
+/// Finds the expressions of the enclosing function which are the same as `to_extract`, along with
+/// the statement to put the variable in front of. They are only returned if each of them is
+/// evaluated once the first one is, and the value is the same at each of them: `to_extract` can't
+/// panic or run user code, and the locals it reads aren't changed between the new variable and the
+/// last of them.
+fn identical_occurrences(
+    ctx: &AssistContext<'_>,
+    to_extract: &ast::Expr,
+) -> Option<(SyntaxNode, Vec<ast::Expr>)> {
+    if !ctx.sema.type_of_expr(to_extract)?.original.is_copy(ctx.db()) {
+        return None;
+    }
+    let locals = locals_in(ctx, to_extract);
+
+    let body = to_extract.syntax().ancestors().find_map(ast::Fn::cast)?.body()?;
+    let body_stmts = body.stmt_list()?;
+    if !is_evaluated_in(to_extract.syntax(), body_stmts.syntax()) {
+        return None;
+    }
+    let occurrences: Vec<_> = body
+        .syntax()
+        .descendants()
+        .filter(|it| it.kind() == to_extract.syntax().kind())
+        .filter(|it| {
+            !it.ancestors()
+                .take_while(|it| it != body.syntax())
+                .any(|it| ast::Item::can_cast(it.kind()))
+        })
+        .filter(|it| same_tokens(it, to_extract.syntax()))
+        .filter(|it| is_evaluated_in(it, body_stmts.syntax()))
+        .filter_map(ast::Expr::cast)
+        .filter(|it| locals_in(ctx, it) == locals)
+        .collect();
+    if occurrences.len() < 2
+        || occurrences.iter().any(|it| expr_require_exclusive_access(ctx, it) != Some(false))
+    {
+        return None;
+    }
+
+    let first = occurrences.first()?;
+    let covering =
+        occurrences.iter().map(|it| it.syntax().text_range()).reduce(TextRange::cover)?;
+    let stmt_list = first
+        .syntax()
+        .ancestors()
+        .filter_map(ast::StmtList::cast)
+        .find(|it| it.syntax().text_range().contains_range(covering))?;
+    let first_stmt =
+        first.syntax().ancestors().find(|it| it.parent().as_ref() == Some(stmt_list.syntax()))?;
+    let last = occurrences.last()?.syntax().text_range().start();
+    if exits_early(stmt_list.syntax(), TextRange::new(first_stmt.text_range().start(), last)) {
+        return None;
+    }
+
+    // An occurrence in a loop sees the changes made anywhere in the loop in its next iteration.
+    let end = occurrences
+        .iter()
+        .map(|occurrence| {
+            occurrence
+                .syntax()
+                .ancestors()
+                .take_while(|it| it != stmt_list.syntax())
+                .filter(|it| matches!(it.kind(), LOOP_EXPR | WHILE_EXPR | FOR_EXPR))
+                .last()
+                .map_or(occurrence.syntax().text_range().end(), |it| it.text_range().end())
+        })
+        .max()?;
+    can_evaluate_before(ctx, to_extract, &first_stmt, end).then_some((first_stmt, occurrences))
+}
+
+/// Whether `expr` is evaluated whenever the statement `stmt` in front of it is: it isn't in a
+/// branch, a loop body that may not run or a closure, and nothing in between may exit early.
+fn always_reached(expr: &ast::Expr, stmt: &SyntaxNode) -> bool {
+    let Some(stmt_list) = stmt.parent() else { return false };
+    is_evaluated_in(expr.syntax(), &stmt_list)
+        && !exits_early(
+            &stmt_list,
+            TextRange::new(stmt.text_range().start(), expr.syntax().text_range().start()),
+        )
+}
+
+/// Whether `node` is evaluated once its ancestor `stmt_list` runs up to it. A `loop` body always
+/// runs at least once, but the bodies of the other loops don't.
+fn is_evaluated_in(node: &SyntaxNode, stmt_list: &SyntaxNode) -> bool {
+    let mut child = node.clone();
+    for parent in node.ancestors().skip(1) {
+        if &parent == stmt_list {
+            return true;
+        }
+        let is_child = |it: Option<ast::Expr>| it.is_some_and(|it| it.syntax() == &child);
+        let conditional = match_ast! {
+            match (parent.clone()) {
+                ast::IfExpr(it) => !is_child(it.condition()),
+                ast::WhileExpr(it) => !is_child(it.condition()),
+                ast::ForExpr(it) => !is_child(it.iterable()),
+                ast::BinExpr(it) => {
+                    matches!(it.op_kind(), Some(ast::BinaryOp::LogicOp(_))) && is_child(it.rhs())
+                },
+                ast::BlockExpr(it) => {
+                    matches!(it.modifier(), Some(ast::BlockModifier::Async(_)))
+                },
+                ast::MatchArm(_) => true,
+                ast::LetElse(_) => true,
+                ast::ClosureExpr(_) => true,
+                _ => ast::Item::can_cast(parent.kind()),
+            }
+        };
+        if conditional {
+            return false;
+        }
+        child = parent;
+    }
+    false
+}
+
+/// Whether something in `range` of `stmt_list` may leave it or panic. Macro calls are assumed to.
+fn exits_early(stmt_list: &SyntaxNode, range: TextRange) -> bool {
+    stmt_list.descendants().filter(|it| range.contains_range(it.text_range())).any(|it| {
+        matches!(
+            it.kind(),
+            RETURN_EXPR | BREAK_EXPR | CONTINUE_EXPR | TRY_EXPR | YIELD_EXPR | MACRO_CALL
+        )
+    })
+}
+
+/// Whether `expr` has the same value up to `end` when evaluated in front of the statement `stmt`
+/// instead: it can't panic or run user code, the locals it reads are in scope in front of `stmt` and
+/// they aren't changed until `end`.
+fn can_evaluate_before(
+    ctx: &AssistContext<'_>,
+    expr: &ast::Expr,
+    stmt: &SyntaxNode,
+    end: TextSize,
+) -> bool {
+    let db = ctx.db();
+    let Some(stmt_list) = stmt.parent().and_then(ast::StmtList::cast) else { return false };
+    if !expr.syntax().descendants().filter_map(ast::Expr::cast).all(|it| is_pure(ctx, &it)) {
+        return false;
+    }
+    let locals = locals_in(ctx, expr);
+    // Calls can change the value behind a `&mut` without mentioning the local.
+    if locals.iter().any(|local| local.ty(db).is_mutable_reference()) {
+        return false;
+    }
+    // The locals need to be bound before `stmt`, not by it or after it.
+    if locals.iter().any(|local| {
+        let source = local.primary_source(db);
+        source.file() == ctx.file_id().into()
+            && source.syntax().text_range().start() >= stmt.text_range().start()
+    }) {
+        return false;
+    }
+    let range = TextRange::new(stmt.text_range().start(), end);
+    !changes_locals(ctx, &stmt_list, range, &locals)
+}
+
+/// Whether evaluating `expr` can't panic or run user code, and gives the same value each time if its
+/// operands haven't changed: it's a literal, a local, a const or a field of one of them. Operators
+/// and indexing may overflow, go out of bounds or call trait impls, so they aren't.
+fn is_pure(ctx: &AssistContext<'_>, expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::PathExpr(it) => matches!(
+            it.path().and_then(|path| ctx.sema.resolve_path(&path)),
+            Some(
+                PathResolution::Local(_)
+                    | PathResolution::ConstParam(_)
+                    | PathResolution::Def(ModuleDef::Const(_))
+            )
+        ),
+        // Fields reached by dereferencing something other than a reference run its `Deref` impl.
+        ast::Expr::FieldExpr(it) => {
+            let Some(receiver) = it.expr().and_then(|it| ctx.sema.type_of_expr(&it)) else {
+                return false;
+            };
+            let receiver = receiver.original.strip_references();
+            match ctx.sema.resolve_field(it) {
+                Some(Either::Left(field)) => match field.parent_def(ctx.db()) {
+                    hir::VariantDef::Struct(it) => receiver.as_adt() == Some(it.into()),
+                    hir::VariantDef::Union(it) => receiver.as_adt() == Some(it.into()),
+                    hir::VariantDef::Variant(_) => false,
+                },
+                Some(Either::Right(_)) => receiver.is_tuple(),
+                None => false,
+            }
+        }
+        ast::Expr::Literal(_) | ast::Expr::ParenExpr(_) => true,
+        _ => false,
+    }
+}
+
+/// The locals `expr` reads, in order.
+fn locals_in(ctx: &AssistContext<'_>, expr: &ast::Expr) -> Vec<Local> {
+    expr.syntax()
+        .descendants()
+        .filter_map(ast::PathExpr::cast)
+        .filter_map(|it| match ctx.sema.resolve_path(&it.path()?)? {
+            PathResolution::Local(local) => Some(local),
+            _ => None,
+        })
+        .collect()
+}
+
+fn same_tokens(a: &SyntaxNode, b: &SyntaxNode) -> bool {
+    let tokens = |node: &SyntaxNode| {
+        node.descendants_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .filter(|it| !it.kind().is_trivia())
+            .map(|it| it.text().to_owned())
+            .collect::<Vec<_>>()
+    };
+    tokens(a) == tokens(b)
+}
+
+/// Whether one of `locals` may be changed in `range` of `stmt_list`. Macro calls mentioning them
+/// are assumed to change them.
+fn changes_locals(
+    ctx: &AssistContext<'_>,
+    stmt_list: &ast::StmtList,
+    range: TextRange,
+    locals: &[Local],
+) -> bool {
+    let db = ctx.db();
+    stmt_list.syntax().descendants().filter(|it| range.contains_range(it.text_range())).any(|node| {
+        match_ast! {
+            match node {
+                ast::PathExpr(it) => {
+                    let resolution = it.path().and_then(|path| ctx.sema.resolve_path(&path));
+                    matches!(resolution, Some(PathResolution::Local(local)) if locals.contains(&local))
+                        && place_is_changed(ctx, it.into())
+                },
+                ast::MacroCall(it) => it.token_tree().is_some_and(|tt| {
+                    tt.syntax()
+                        .descendants_with_tokens()
+                        .filter_map(NodeOrToken::into_token)
+                        .filter(|token| token.kind() == IDENT)
+                        .any(|token| {
+                            locals.iter().any(|local| local.name(db).as_str() == Some(token.text()))
+                        })
+                }),
+                _ => false,
+            }
+        }
+    })
+}
+
+/// Whether the place `expr` is the base of is changed, e.g. by `expr[0] = 1`.
+fn place_is_changed(ctx: &AssistContext<'_>, expr: ast::Expr) -> bool {
+    let mut place = expr;
+    while let Some(parent) = place.syntax().parent().and_then(ast::Expr::cast) {
+        match &parent {
+            ast::Expr::IndexExpr(it) if it.base().as_ref() == Some(&place) => place = parent,
+            ast::Expr::ParenExpr(_) => place = parent,
+            _ => break,
+        }
+    }
+    expr_require_exclusive_access(ctx, &place) != Some(false)
 }
 
 /// Check whether the node is a valid expression which can be extracted to a variable.
@@ -271,7 +668,10 @@ fn from(to_extract: &ast::Expr) -> Option<Anchor> {
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
+    use crate::tests::{
+        check_assist, check_assist_by_label, check_assist_not_applicable,
+        check_assist_not_applicable_by_label, check_assist_target,
+    };
 
     use super::*;
 
@@ -1460,4 +1860,314 @@ fn foo() {
 }"#,
         );
     }
+
+    #[test]
+    fn extract_all_occurrences() {
+        check_assist_by_label(
+            extract_variable,
+            r#"
+//- minicore: copy
+struct Size { width: i32 }
+struct S { size: Size }
+fn f(s: &S) -> i32 {
+    let x = $0s.size.width$0 + 1;
+    let y = 2 * (s.size.width);
+    x + y + s.size.width
+}
+"#,
+            r#"
+struct Size { width: i32 }
+struct S { size: Size }
+fn f(s: &S) -> i32 {
+    let $0width = s.size.width;
+    let x = width + 1;
+    let y = 2 * width;
+    x + y + width
+}
+"#,
+            "Extract all 3 occurrences into variable",
+        );
+    }
+
+    #[test]
+    fn extract_all_occurrences_needs_same_value() {
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+//- minicore: copy
+struct S { n: i32 }
+fn f(mut s: S) -> i32 {
+    let x = $0s.n$0;
+    s.n += 1;
+    x + s.n
+}
+"#,
+            "Extract all 2 occurrences into variable",
+        );
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+//- minicore: copy
+struct S { n: i32 }
+fn f(mut s: S) {
+    let n = $0s.n$0;
+    while n < s.n {
+        s.n += 1;
+    }
+}
+"#,
+            "Extract all 2 occurrences into variable",
+        );
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+fn g() -> i32 { 0 }
+fn f() -> i32 {
+    $0g()$0 + g()
+}
+"#,
+            "Extract all 2 occurrences into variable",
+        );
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+//- minicore: copy
+fn f() -> i32 {
+    let a = (1,);
+    let x = $0a.0$0;
+    let a = (2,);
+    x + a.0
+}
+"#,
+            "Extract all 2 occurrences into variable",
+        );
+    }
+
+    #[test]
+    fn extract_all_occurrences_that_may_panic() {
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+//- minicore: copy, index, slice
+fn f(v: &[i32], i: usize) -> i32 {
+    $0v[i]$0 + v[i]
+}
+"#,
+            "Extract all 2 occurrences into variable",
+        );
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+//- minicore: copy
+fn f(a: i32, b: i32) -> i32 {
+    $0a / b$0 + a / b
+}
+"#,
+            "Extract all 2 occurrences into variable",
+        );
+    }
+
+    #[test]
+    fn extract_all_occurrences_in_other_branches() {
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+//- minicore: copy
+struct S { n: i32 }
+fn f(s: &S, c: bool) -> i32 {
+    if c {
+        $0s.n$0
+    } else {
+        s.n + 1
+    }
+}
+"#,
+            "Extract all 2 occurrences into variable",
+        );
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+//- minicore: copy
+struct S { n: i32 }
+fn f(s: &S) -> i32 {
+    let x = $0s.n$0;
+    if x == 0 {
+        return 0;
+    }
+    x + s.n
+}
+"#,
+            "Extract all 2 occurrences into variable",
+        );
+    }
+
+    #[test]
+    fn extract_at_start_of_block() {
+        check_assist_by_label(
+            extract_variable,
+            r#"
+struct S { n: i32 }
+fn f(s: &S) -> i32 {
+    let x = 1;
+    let y = x + $0s.n$0;
+    y
+}
+"#,
+            r#"
+struct S { n: i32 }
+fn f(s: &S) -> i32 {
+    let $0n = s.n;
+    let x = 1;
+    let y = x + n;
+    y
+}
+"#,
+            "Extract into variable at start of block",
+        );
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+fn f() -> i32 {
+    let x = (1, 2);
+    let y = $0x.0$0 * 2;
+    y
+}
+"#,
+            "Extract into variable at start of block",
+        );
+    }
+
+    #[test]
+    fn extract_at_start_of_block_after_early_exit() {
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+struct S { n: i32 }
+fn f(s: Option<&S>) -> i32 {
+    let x = 1;
+    let Some(s) = s else { return 0 };
+    x + $0s.n$0
+}
+"#,
+            "Extract into variable at start of block",
+        );
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+struct S { n: i32 }
+fn f(s: &S, len: i32) -> i32 {
+    if len == 0 {
+        return 0;
+    }
+    let y = $0s.n$0 * 2;
+    y
+}
+"#,
+            "Extract into variable at start of block",
+        );
+    }
+
+    #[test]
+    fn extract_at_start_of_block_from_branch() {
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+struct S { n: i32 }
+fn f(s: &S, c: bool) -> i32 {
+    let x = 1;
+    let y = if c { $0s.n$0 * 2 } else { x };
+    y
+}
+"#,
+            "Extract into variable at start of block",
+        );
+    }
+
+    #[test]
+    fn extract_before_loop() {
+        check_assist_by_label(
+            extract_variable,
+            r#"
+struct S { n: usize }
+fn f(s: &S) -> usize {
+    let mut sum = 0;
+    loop {
+        sum += $0s.n$0;
+        if sum > 100 {
+            break;
+        }
+    }
+    sum
+}
+"#,
+            r#"
+struct S { n: usize }
+fn f(s: &S) -> usize {
+    let mut sum = 0;
+    let $0n = s.n;
+    loop {
+        sum += n;
+        if sum > 100 {
+            break;
+        }
+    }
+    sum
+}
+"#,
+            "Extract into variable before loop",
+        );
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+struct S { n: usize }
+fn f(mut s: S) -> usize {
+    let mut sum = 0;
+    loop {
+        sum += $0s.n$0;
+        s.n -= 1;
+        if s.n == 0 {
+            break;
+        }
+    }
+    sum
+}
+"#,
+            "Extract into variable before loop",
+        );
+    }
+
+    #[test]
+    fn extract_before_loop_that_may_not_run() {
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+struct S { n: usize }
+fn f(v: &[usize], s: &S) -> usize {
+    let mut sum = 0;
+    for x in v {
+        sum += $0s.n$0;
+    }
+    sum
+}
+"#,
+            "Extract into variable before loop",
+        );
+        check_assist_not_applicable_by_label(
+            extract_variable,
+            r#"
+struct S { n: usize }
+fn f(s: &S) -> usize {
+    let mut sum = 0;
+    loop {
+        if sum > 100 {
+            break;
+        }
+        sum += $0s.n$0;
+    }
+    sum
+}
+"#,
+            "Extract into variable before loop",
+        );
+    }
 }
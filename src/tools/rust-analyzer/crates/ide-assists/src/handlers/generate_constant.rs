@@ -1,13 +1,15 @@
 use crate::assist_context::{AssistContext, Assists};
-use hir::{HasVisibility, HirDisplay, HirFileIdExt, Module};
+use hir::{HasVisibility, HirDisplay, HirFileIdExt, Module, ModuleSource};
 use ide_db::{
     assists::{AssistId, AssistKind},
     base_db::{FileId, Upcast},
     defs::{Definition, NameRefClass},
+    syntax_helpers::insert_item::{add_item_to_module, ItemPlacement},
 };
 use syntax::{
-    ast::{self, edit::IndentLevel, NameRef},
-    AstNode, Direction, SyntaxKind, TextSize,
+    ast::{self, edit::IndentLevel, edit_in_place::Indent, make, HasModuleItem, NameRef},
+    ted::{self, Position},
+    AstNode, Edition, SourceFile, T,
 };
 
 // Assist: generate_constant
@@ -78,71 +80,90 @@ pub(crate) fn generate_constant(acc: &mut Assists, ctx: &AssistContext<'_>) -> O
             }
         }
     }
-    let (offset, indent, file_id, post_string) =
-        target_data_for_generate_constant(ctx, current_module, constant_module).unwrap_or_else(
-            || {
-                let indent = IndentLevel::from_node(statement.syntax());
-                (statement.syntax().text_range().start(), indent, None, format!("\n{indent}"))
-            },
-        );
-
-    let text = get_text_for_generate_constant(not_exist_name_ref, indent, outer_exists, type_name)?;
+    let item = make_constant_item(not_exist_name_ref, outer_exists, type_name)?;
+    let target_module = target_module_for_generate_constant(ctx, current_module, constant_module);
     acc.add(
         AssistId("generate_constant", AssistKind::QuickFix),
         "Generate constant",
         target,
         |builder| {
-            if let Some(file_id) = file_id {
-                builder.edit_file(file_id);
+            match target_module {
+                Some((file_id, ModuleSource::SourceFile(it))) => {
+                    builder.edit_file(file_id);
+                    add_item_to_module(
+                        &builder.make_mut(it),
+                        item.clone(),
+                        ItemPlacement::Conventional,
+                    );
+                }
+                Some((file_id, ModuleSource::Module(it))) => {
+                    builder.edit_file(file_id);
+                    let Some(item_list) = it.item_list() else { return };
+                    add_item_to_module(
+                        &builder.make_mut(item_list),
+                        item.clone(),
+                        ItemPlacement::Conventional,
+                    );
+                }
+                Some((_, ModuleSource::BlockExpr(_))) => return,
+                None => {
+                    let statement = builder.make_mut(statement);
+                    let indent = IndentLevel::from_node(statement.syntax());
+                    item.reindent_to(indent);
+                    ted::insert_all_raw(
+                        Position::before(statement.syntax()),
+                        vec![
+                            item.syntax().clone().into(),
+                            make::tokens::whitespace(&format!("\n{indent}")).into(),
+                        ],
+                    );
+                }
+            }
+            let semicolon = item
+                .syntax()
+                .descendants()
+                .find_map(ast::Const::cast)
+                .and_then(|it| it.syntax().last_token())
+                .filter(|it| it.kind() == T![;]);
+            if let Some((cap, semicolon)) = ctx.config.snippet_cap.zip(semicolon) {
+                builder.add_tabstop_before_token(cap, semicolon);
             }
-            builder.insert(offset, format!("{text}{post_string}"));
         },
     )
 }
 
-fn get_text_for_generate_constant(
+/// Makes the constant, wrapped into the modules of `not_exist_name_ref` which don't exist yet. Its
+/// value is left empty for the user to fill in.
+fn make_constant_item(
     mut not_exist_name_ref: Vec<NameRef>,
-    indent: IndentLevel,
     outer_exists: bool,
     type_name: String,
-) -> Option<String> {
+) -> Option<ast::Item> {
     let constant_token = not_exist_name_ref.pop()?;
-    let vis = if not_exist_name_ref.is_empty() && !outer_exists { "" } else { "\npub " };
-    let mut text = format!("{vis}const {constant_token}: {type_name} = $0;");
+    let vis = if not_exist_name_ref.is_empty() && !outer_exists { "" } else { "pub " };
+    let mut text = format!("{vis}const {constant_token}: {type_name} = ;");
     while let Some(name_ref) = not_exist_name_ref.pop() {
-        let vis = if not_exist_name_ref.is_empty() && !outer_exists { "" } else { "\npub " };
+        let vis = if not_exist_name_ref.is_empty() && !outer_exists { "" } else { "pub " };
         text = text.replace('\n', "\n    ");
-        text = format!("{vis}mod {name_ref} {{{text}\n}}");
+        text = format!("{vis}mod {name_ref} {{\n    {text}\n}}");
     }
-    Some(text.replace('\n', &format!("\n{indent}")))
+    let file = SourceFile::parse(&text, Edition::CURRENT).tree();
+    Some(file.items().next()?.clone_subtree().clone_for_update())
 }
 
-fn target_data_for_generate_constant(
+/// The module to add the constant to, if it isn't the one of the expression.
+fn target_module_for_generate_constant(
     ctx: &AssistContext<'_>,
     current_module: Module,
     constant_module: Module,
-) -> Option<(TextSize, IndentLevel, Option<FileId>, String)> {
+) -> Option<(FileId, ModuleSource)> {
     if current_module == constant_module {
         // insert in current file
         return None;
     }
     let in_file_source = current_module.definition_source(ctx.sema.db);
     let file_id = in_file_source.file_id.original_file(ctx.sema.db.upcast());
-    match in_file_source.value {
-        hir::ModuleSource::Module(module_node) => {
-            let indent = IndentLevel::from_node(module_node.syntax());
-            let l_curly_token = module_node.item_list()?.l_curly_token()?;
-            let offset = l_curly_token.text_range().end();
-
-            let siblings_has_newline = l_curly_token
-                .siblings_with_tokens(Direction::Next)
-                .any(|it| it.kind() == SyntaxKind::WHITESPACE && it.to_string().contains('\n'));
-            let post_string =
-                if siblings_has_newline { format!("{indent}") } else { format!("\n{indent}") };
-            Some((offset, indent + 1, Some(file_id), post_string))
-        }
-        _ => Some((TextSize::from(0), 0.into(), Some(file_id), "\n".into())),
-    }
+    Some((file_id, in_file_source.value))
 }
 
 #[cfg(test)]
@@ -257,6 +278,35 @@ fn bar() -> i32 {
         );
     }
 
+    #[test]
+    fn test_constant_grouped_with_other_constants() {
+        check_assist(
+            generate_constant,
+            r#"mod foo {
+    use core::ops::Add;
+
+    pub const OTHER: i32 = 1;
+
+    pub fn f() {}
+}
+fn bar() -> i32 {
+    foo::A_CON$0STANT
+}"#,
+            r#"mod foo {
+    use core::ops::Add;
+
+    pub const OTHER: i32 = 1;
+
+    pub const A_CONSTANT: i32 = $0;
+
+    pub fn f() {}
+}
+fn bar() -> i32 {
+    foo::A_CONSTANT
+}"#,
+        );
+    }
+
     #[test]
     fn test_wont_apply_when_not_last_path_segment() {
         cov_mark::check!(not_last_path_segment);
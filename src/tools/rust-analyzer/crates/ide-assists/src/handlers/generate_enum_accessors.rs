@@ -0,0 +1,277 @@
+use hir::AssocItem;
+use ide_db::FxHashSet;
+use syntax::ast::{self, AstNode, HasVisibility};
+
+use crate::{
+    utils::{add_method_to_adt, find_struct_impl},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+use super::{
+    generate_enum_is_method::is_method,
+    generate_enum_projection_method::{projection_method, AS, INTO, TRY_INTO},
+};
+
+// Assist: generate_enum_accessors
+//
+// Generates the `is_`, `as_`, `try_into_` and `into_` methods for all variants of this enum,
+// skipping the ones that already exist. Variants without exactly one field only get an `is_`
+// method.
+//
+// ```
+// enum Value$0 {
+//     Null,
+//     Number(i32),
+// }
+//
+// impl Value {
+//     fn is_null(&self) -> bool {
+//         matches!(self, Self::Null)
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Value {
+//     Null,
+//     Number(i32),
+// }
+//
+// impl Value {
+//     fn is_null(&self) -> bool {
+//         matches!(self, Self::Null)
+//     }
+//
+//     /// Returns `true` if the value is [`Number`].
+//     ///
+//     /// [`Number`]: Value::Number
+//     #[must_use]
+//     fn is_number(&self) -> bool {
+//         matches!(self, Self::Number(..))
+//     }
+//
+//     fn as_number(&self) -> Option<&i32> {
+//         if let Self::Number(v) = self {
+//             Some(v)
+//         } else {
+//             None
+//         }
+//     }
+//
+//     fn try_into_number(self) -> Result<i32, Self> {
+//         if let Self::Number(v) = self {
+//             Ok(v)
+//         } else {
+//             Err(self)
+//         }
+//     }
+//
+//     fn into_number(self) -> Option<i32> {
+//         if let Self::Number(v) = self {
+//             Some(v)
+//         } else {
+//             None
+//         }
+//     }
+// }
+// ```
+pub(crate) fn generate_enum_accessors(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let enum_ = ast::Enum::cast(name.syntax().parent()?)?;
+    let enum_def = ctx.sema.to_def(&enum_)?;
+    let db = ctx.db();
+
+    let mut existing: FxHashSet<String> = hir::Impl::all_for_type(db, enum_def.ty(db))
+        .into_iter()
+        .filter(|imp| imp.trait_(db).is_none())
+        .flat_map(|imp| imp.items(db))
+        .filter_map(|item| match item {
+            AssocItem::Function(f) => Some(f.name(db).display(db).to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let parent_enum = ast::Adt::Enum(enum_.clone());
+    let vis = parent_enum.visibility().map_or(String::new(), |v| format!("{v} "));
+    let mut methods = Vec::new();
+    for variant in enum_.variant_list()?.variants() {
+        let projections = [&AS, &TRY_INTO, &INTO]
+            .into_iter()
+            .map(|props| projection_method(ctx, props, &variant, &vis));
+        let accessors = std::iter::once(is_method(&parent_enum, &variant, &vis)).chain(projections);
+        for (fn_name, method) in accessors.flatten() {
+            if existing.insert(fn_name) {
+                methods.push(method);
+            }
+        }
+    }
+    if methods.is_empty() {
+        cov_mark::hit!(all_enum_accessors_exist);
+        return None;
+    }
+
+    let impl_def = find_struct_impl(ctx, &parent_enum, &[])?;
+
+    acc.add(
+        AssistId("generate_enum_accessors", AssistKind::Generate),
+        "Generate accessors for all variants",
+        name.syntax().text_range(),
+        |builder| add_method_to_adt(builder, &parent_enum, impl_def, &methods.join("\n\n")),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generate_for_all_variants() {
+        check_assist(
+            generate_enum_accessors,
+            r#"
+pub enum Shape$0 {
+    Circle { radius: f32 },
+    Rect(f32, f32),
+}
+"#,
+            r#"
+pub enum Shape {
+    Circle { radius: f32 },
+    Rect(f32, f32),
+}
+
+impl Shape {
+    /// Returns `true` if the shape is [`Circle`].
+    ///
+    /// [`Circle`]: Shape::Circle
+    #[must_use]
+    pub fn is_circle(&self) -> bool {
+        matches!(self, Self::Circle { .. })
+    }
+
+    pub fn as_circle(&self) -> Option<&f32> {
+        if let Self::Circle { radius } = self {
+            Some(radius)
+        } else {
+            None
+        }
+    }
+
+    pub fn try_into_circle(self) -> Result<f32, Self> {
+        if let Self::Circle { radius } = self {
+            Ok(radius)
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn into_circle(self) -> Option<f32> {
+        if let Self::Circle { radius } = self {
+            Some(radius)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the shape is [`Rect`].
+    ///
+    /// [`Rect`]: Shape::Rect
+    #[must_use]
+    pub fn is_rect(&self) -> bool {
+        matches!(self, Self::Rect(..))
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn skip_methods_of_other_impls() {
+        check_assist(
+            generate_enum_accessors,
+            r#"
+enum E$0 {
+    A(u8),
+}
+
+impl E {
+    fn as_a(&self) -> Option<&u8> {
+        todo!()
+    }
+}
+
+impl E {
+    fn try_into_a(self) -> Result<u8, Self> {
+        todo!()
+    }
+}
+"#,
+            r#"
+enum E {
+    A(u8),
+}
+
+impl E {
+    fn as_a(&self) -> Option<&u8> {
+        todo!()
+    }
+
+    /// Returns `true` if the e is [`A`].
+    ///
+    /// [`A`]: E::A
+    #[must_use]
+    fn is_a(&self) -> bool {
+        matches!(self, Self::A(..))
+    }
+
+    fn into_a(self) -> Option<u8> {
+        if let Self::A(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+
+impl E {
+    fn try_into_a(self) -> Result<u8, Self> {
+        todo!()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn all_enum_accessors_exist() {
+        cov_mark::check!(all_enum_accessors_exist);
+        check_assist_not_applicable(
+            generate_enum_accessors,
+            r#"
+enum E$0 {
+    A,
+}
+
+impl E {
+    fn is_a(&self) -> bool {
+        matches!(self, Self::A)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_enum_name() {
+        check_assist_not_applicable(
+            generate_enum_accessors,
+            r#"
+enum E {
+    A$0(u8),
+}
+"#,
+        );
+    }
+}
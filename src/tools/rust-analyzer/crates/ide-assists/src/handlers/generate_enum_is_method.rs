@@ -39,8 +39,30 @@
 // ```
 pub(crate) fn generate_enum_is_method(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
     let variant = ctx.find_node_at_offset::<ast::Variant>()?;
-    let variant_name = variant.name()?;
     let parent_enum = ast::Adt::Enum(variant.parent_enum());
+    let vis = parent_enum.visibility().map_or(String::new(), |v| format!("{v} "));
+    let (fn_name, method) = is_method(&parent_enum, &variant, &vis)?;
+
+    // Return early if we've found an existing new fn
+    let impl_def = find_struct_impl(ctx, &parent_enum, &[fn_name])?;
+
+    let target = variant.syntax().text_range();
+    acc.add_group(
+        &GroupLabel("Generate an `is_`,`as_`, or `try_into_` for this enum variant".to_owned()),
+        AssistId("generate_enum_is_method", AssistKind::Generate),
+        "Generate an `is_` method for this enum variant",
+        target,
+        |builder| add_method_to_adt(builder, &parent_enum, impl_def, &method),
+    )
+}
+
+/// The name and text of the `is_` method for `variant`.
+pub(super) fn is_method(
+    parent_enum: &ast::Adt,
+    variant: &ast::Variant,
+    vis: &str,
+) -> Option<(String, String)> {
+    let variant_name = variant.name()?;
     let pattern_suffix = match variant.kind() {
         ast::StructKind::Record(_) => " { .. }",
         ast::StructKind::Tuple(_) => "(..)",
@@ -51,30 +73,16 @@ pub(crate) fn generate_enum_is_method(acc: &mut Assists, ctx: &AssistContext<'_>
     let enum_lowercase_name = to_lower_snake_case(&enum_name.to_string()).replace('_', " ");
     let fn_name = format!("is_{}", &to_lower_snake_case(&variant_name.text()));
 
-    // Return early if we've found an existing new fn
-    let impl_def = find_struct_impl(ctx, &parent_enum, &[fn_name.clone()])?;
-
-    let target = variant.syntax().text_range();
-    acc.add_group(
-        &GroupLabel("Generate an `is_`,`as_`, or `try_into_` for this enum variant".to_owned()),
-        AssistId("generate_enum_is_method", AssistKind::Generate),
-        "Generate an `is_` method for this enum variant",
-        target,
-        |builder| {
-            let vis = parent_enum.visibility().map_or(String::new(), |v| format!("{v} "));
-            let method = format!(
-                "    /// Returns `true` if the {enum_lowercase_name} is [`{variant_name}`].
+    let method = format!(
+        "    /// Returns `true` if the {enum_lowercase_name} is [`{variant_name}`].
     ///
     /// [`{variant_name}`]: {enum_name}::{variant_name}
     #[must_use]
     {vis}fn {fn_name}(&self) -> bool {{
         matches!(self, Self::{variant_name}{pattern_suffix})
     }}",
-            );
-
-            add_method_to_adt(builder, &parent_enum, impl_def, &method);
-        },
-    )
+    );
+    Some((fn_name, method))
 }
 
 #[cfg(test)]
@@ -45,14 +45,7 @@ pub(crate) fn generate_enum_try_into_method(
         ctx,
         "generate_enum_try_into_method",
         "Generate a `try_into_` method for this enum variant",
-        ProjectionProps {
-            fn_name_prefix: "try_into",
-            self_param: "self",
-            return_prefix: "Result<",
-            return_suffix: ", Self>",
-            happy_case: "Ok",
-            sad_case: "Err(self)",
-        },
+        &TRY_INTO,
     )
 }
 
@@ -89,18 +82,38 @@ pub(crate) fn generate_enum_as_method(acc: &mut Assists, ctx: &AssistContext<'_>
         ctx,
         "generate_enum_as_method",
         "Generate an `as_` method for this enum variant",
-        ProjectionProps {
-            fn_name_prefix: "as",
-            self_param: "&self",
-            return_prefix: "Option<&",
-            return_suffix: ">",
-            happy_case: "Some",
-            sad_case: "None",
-        },
+        &AS,
     )
 }
 
-struct ProjectionProps {
+pub(super) const TRY_INTO: ProjectionProps = ProjectionProps {
+    fn_name_prefix: "try_into",
+    self_param: "self",
+    return_prefix: "Result<",
+    return_suffix: ", Self>",
+    happy_case: "Ok",
+    sad_case: "Err(self)",
+};
+
+pub(super) const AS: ProjectionProps = ProjectionProps {
+    fn_name_prefix: "as",
+    self_param: "&self",
+    return_prefix: "Option<&",
+    return_suffix: ">",
+    happy_case: "Some",
+    sad_case: "None",
+};
+
+pub(super) const INTO: ProjectionProps = ProjectionProps {
+    fn_name_prefix: "into",
+    self_param: "self",
+    return_prefix: "Option<",
+    return_suffix: ">",
+    happy_case: "Some",
+    sad_case: "None",
+};
+
+pub(super) struct ProjectionProps {
     fn_name_prefix: &'static str,
     self_param: &'static str,
     return_prefix: &'static str,
@@ -114,8 +127,33 @@ fn generate_enum_projection_method(
     ctx: &AssistContext<'_>,
     assist_id: &'static str,
     assist_description: &str,
-    props: ProjectionProps,
+    props: &ProjectionProps,
 ) -> Option<()> {
+    let variant = ctx.find_node_at_offset::<ast::Variant>()?;
+    let parent_enum = ast::Adt::Enum(variant.parent_enum());
+    let vis = parent_enum.visibility().map_or(String::new(), |v| format!("{v} "));
+    let (fn_name, method) = projection_method(ctx, props, &variant, &vis)?;
+
+    // Return early if we've found an existing new fn
+    let impl_def = find_struct_impl(ctx, &parent_enum, &[fn_name])?;
+
+    let target = variant.syntax().text_range();
+    acc.add_group(
+        &GroupLabel("Generate an `is_`,`as_`, or `try_into_` for this enum variant".to_owned()),
+        AssistId(assist_id, AssistKind::Generate),
+        assist_description,
+        target,
+        |builder| add_method_to_adt(builder, &parent_enum, impl_def, &method),
+    )
+}
+
+/// The name and text of the projection method for `variant`, which needs to have a single field.
+pub(super) fn projection_method(
+    ctx: &AssistContext<'_>,
+    props: &ProjectionProps,
+    variant: &ast::Variant,
+    vis: &str,
+) -> Option<(String, String)> {
     let ProjectionProps {
         fn_name_prefix,
         self_param,
@@ -125,9 +163,7 @@ fn generate_enum_projection_method(
         sad_case,
     } = props;
 
-    let variant = ctx.find_node_at_offset::<ast::Variant>()?;
     let variant_name = variant.name()?;
-    let parent_enum = ast::Adt::Enum(variant.parent_enum());
 
     let (pattern_suffix, field_type, bound_name) = match variant.kind() {
         ast::StructKind::Record(record) => {
@@ -146,40 +182,20 @@ fn generate_enum_projection_method(
     };
 
     let fn_name = format!("{fn_name_prefix}_{}", &to_lower_snake_case(&variant_name.text()));
+    let field_type_syntax = field_type.syntax();
 
-    // Return early if we've found an existing new fn
-    let impl_def = find_struct_impl(ctx, &parent_enum, &[fn_name.clone()])?;
+    let must_use = if ctx.config.assist_emit_must_use { "#[must_use]\n    " } else { "" };
 
-    let target = variant.syntax().text_range();
-    acc.add_group(
-        &GroupLabel("Generate an `is_`,`as_`, or `try_into_` for this enum variant".to_owned()),
-        AssistId(assist_id, AssistKind::Generate),
-        assist_description,
-        target,
-        |builder| {
-            let vis = parent_enum.visibility().map_or(String::new(), |v| format!("{v} "));
-
-            let field_type_syntax = field_type.syntax();
-
-            let must_use = if ctx.config.assist_emit_must_use {
-                "#[must_use]\n    "
-            } else {
-                ""
-            };
-
-            let method = format!(
-                "    {must_use}{vis}fn {fn_name}({self_param}) -> {return_prefix}{field_type_syntax}{return_suffix} {{
+    let method = format!(
+        "    {must_use}{vis}fn {fn_name}({self_param}) -> {return_prefix}{field_type_syntax}{return_suffix} {{
         if let Self::{variant_name}{pattern_suffix} = self {{
             {happy_case}({bound_name})
         }} else {{
             {sad_case}
         }}
     }}"
-            );
-
-            add_method_to_adt(builder, &parent_enum, impl_def, &method);
-        },
-    )
+    );
+    Some((fn_name, method))
 }
 
 #[cfg(test)]
@@ -0,0 +1,609 @@
+use hir::{HasAttrs, Semantics};
+use ide_db::RootDatabase;
+use stdx::{format_to, to_camel_case};
+use syntax::{
+    ast::{self, edit::IndentLevel, HasArgList, HasName, HasVisibility},
+    AstNode, WalkEvent,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: generate_error_enum
+//
+// Replaces the string literals a function returns as errors with the variants of a new error
+// enum. The enum displays the original messages, and implements `std::error::Error` unless the
+// crate is `no_std`.
+//
+// ```
+// fn parse_port$0(input: &str) -> Result<u16, &'static str> {
+//     if input.is_empty() {
+//         return Err("empty input");
+//     }
+//     input.parse().ok().ok_or("not a number")
+// }
+// ```
+// ->
+// ```
+// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// enum ParsePortError {
+//     EmptyInput,
+//     NotANumber,
+// }
+//
+// impl core::fmt::Display for ParsePortError {
+//     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+//         let message = match self {
+//             Self::EmptyInput => "empty input",
+//             Self::NotANumber => "not a number",
+//         };
+//         f.write_str(message)
+//     }
+// }
+//
+// impl std::error::Error for ParsePortError {}
+//
+// fn parse_port(input: &str) -> Result<u16, ParsePortError> {
+//     if input.is_empty() {
+//         return Err(ParsePortError::EmptyInput);
+//     }
+//     input.parse().ok().ok_or(ParsePortError::NotANumber)
+// }
+// ```
+pub(crate) fn generate_error_enum(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let fn_ = ctx.find_node_at_offset::<ast::Fn>()?;
+    let body = fn_.body()?;
+    // Only offered on the signature, not to get in the way inside of the body.
+    if ctx.offset() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let error_ty = result_error_type(&fn_)?;
+    if !is_string_type(&error_ty) {
+        return None;
+    }
+    let sites = error_sites(&ctx.sema, &body)?;
+    let krate = ctx.sema.scope(fn_.syntax())?.krate();
+    let no_std = krate.root_module().attrs(ctx.db()).by_key("no_std").exists();
+    if sites.is_empty() {
+        return None;
+    }
+
+    let enum_name = format!("{}Error", to_camel_case(fn_.name()?.text().trim_start_matches("r#")));
+    // Methods can't be preceded by an enum, it goes in front of their impl or trait.
+    let anchor = match fn_.syntax().parent().and_then(ast::AssocItemList::cast) {
+        Some(list) => list.syntax().parent()?,
+        None => fn_.syntax().clone(),
+    };
+
+    let target = fn_.syntax().text_range();
+    acc.add(
+        AssistId("generate_error_enum", AssistKind::Generate),
+        "Generate error enum from string literals",
+        target,
+        |builder| {
+            let mut variants: Vec<ErrorVariant> = Vec::new();
+            for site in &sites {
+                if variants.iter().any(|it| it.message == site.message) {
+                    continue;
+                }
+                let name = unique_variant_name(&variants, &site.message);
+                variants.push(ErrorVariant {
+                    name,
+                    message: site.message.clone(),
+                    literal: site.literal.to_string(),
+                });
+            }
+
+            let indent = IndentLevel::from_node(&anchor);
+            let vis = fn_.visibility().map(|it| format!("{it} ")).unwrap_or_default();
+            let mut buf = format!("#[derive(Debug, Clone, Copy, PartialEq, Eq)]\n{indent}");
+            format_to!(buf, "{vis}enum {enum_name} {{\n");
+            for variant in &variants {
+                format_to!(buf, "{}{},\n", indent + 1, variant.name);
+            }
+            format_to!(buf, "{indent}}}\n\n");
+            format_to!(buf, "{indent}impl core::fmt::Display for {enum_name} {{\n");
+            format_to!(
+                buf,
+                "{}fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {{\n",
+                indent + 1
+            );
+            format_to!(buf, "{}let message = match self {{\n", indent + 2);
+            for variant in &variants {
+                format_to!(buf, "{}Self::{} => {},\n", indent + 3, variant.name, variant.literal);
+            }
+            format_to!(buf, "{}}};\n", indent + 2);
+            format_to!(buf, "{}f.write_str(message)\n", indent + 2);
+            format_to!(buf, "{}}}\n", indent + 1);
+            format_to!(buf, "{indent}}}\n\n");
+            if !no_std {
+                format_to!(buf, "{indent}impl std::error::Error for {enum_name} {{}}\n\n");
+            }
+            format_to!(buf, "{indent}");
+            builder.insert(anchor.text_range().start(), buf);
+
+            builder.replace(error_ty.syntax().text_range(), enum_name.clone());
+            for site in sites {
+                let Some(variant) = variants.iter().find(|it| it.message == site.message) else {
+                    continue;
+                };
+                builder.replace(
+                    site.expr.syntax().text_range(),
+                    format!("{enum_name}::{}", variant.name),
+                );
+            }
+        },
+    )
+}
+
+/// An expression giving the message of an error.
+struct ErrorSite {
+    expr: ast::Expr,
+    literal: ast::Literal,
+    message: String,
+}
+
+struct ErrorVariant {
+    name: String,
+    message: String,
+    /// The literal the message was first given by, kept as written.
+    literal: String,
+}
+
+/// The `E` of a function returning `Result<T, E>`.
+fn result_error_type(fn_: &ast::Fn) -> Option<ast::Type> {
+    let ast::Type::PathType(ty) = fn_.ret_type()?.ty()? else { return None };
+    let segment = ty.path()?.segment()?;
+    if segment.name_ref()?.text() != "Result" {
+        return None;
+    }
+    let args: Vec<_> = segment.generic_arg_list()?.generic_args().collect();
+    match args.as_slice() {
+        [ast::GenericArg::TypeArg(_), ast::GenericArg::TypeArg(error)] => error.ty(),
+        _ => None,
+    }
+}
+
+fn is_string_type(ty: &ast::Type) -> bool {
+    match ty {
+        ast::Type::RefType(ty) => ty.ty().is_some_and(|it| it.syntax().text() == "str"),
+        ast::Type::PathType(ty) => ty
+            .path()
+            .and_then(|path| path.segment()?.name_ref())
+            .is_some_and(|name| name.text() == "String"),
+        _ => false,
+    }
+}
+
+/// Finds the messages of the errors the function returns: those given to the `Err(..)`s and
+/// `.ok_or(..)`s it returns or applies `?` to. Returns `None` if it may return any other error, as
+/// its type is about to change.
+fn error_sites(
+    sema: &Semantics<'_, RootDatabase>,
+    body: &ast::BlockExpr,
+) -> Option<Vec<ErrorSite>> {
+    let mut sites = Vec::new();
+    let mut returned: Vec<ast::Expr> = body.tail_expr().into_iter().collect();
+    let mut preorder = body.syntax().preorder();
+    while let Some(event) = preorder.next() {
+        let WalkEvent::Enter(node) = event else { continue };
+        // Errors of closures, async blocks and nested items are none of our business.
+        let is_async_block =
+            ast::BlockExpr::cast(node.clone()).is_some_and(|it| it.async_token().is_some());
+        if is_async_block
+            || ast::ClosureExpr::can_cast(node.kind())
+            || ast::Item::can_cast(node.kind())
+        {
+            preorder.skip_subtree();
+            continue;
+        }
+
+        if let Some(return_) = ast::ReturnExpr::cast(node.clone()) {
+            returned.extend(return_.expr());
+        } else if let Some(try_) = ast::TryExpr::cast(node) {
+            // Other errors can't be converted into the new enum.
+            let ast::Expr::MethodCallExpr(call) = try_.expr()? else { return None };
+            sites.push(ok_or_site(&call)?);
+        }
+    }
+
+    while let Some(expr) = returned.pop() {
+        match expr {
+            ast::Expr::BlockExpr(block) if block.modifier().is_none() => {
+                returned.extend(block.tail_expr());
+            }
+            ast::Expr::IfExpr(if_) => {
+                returned.extend(if_.then_branch().map(ast::Expr::from));
+                returned.extend(if_.else_branch().map(|it| match it {
+                    ast::ElseBranch::Block(it) => it.into(),
+                    ast::ElseBranch::IfExpr(it) => it.into(),
+                }));
+            }
+            ast::Expr::MatchExpr(match_) => {
+                returned.extend(match_.match_arm_list()?.arms().filter_map(|arm| arm.expr()));
+            }
+            ast::Expr::ParenExpr(paren) => returned.extend(paren.expr()),
+            ast::Expr::CallExpr(call) if is_call_to(&call, "Err") => {
+                sites.push(message_site(single_arg(call.arg_list()?)?)?);
+            }
+            ast::Expr::CallExpr(call) if is_call_to(&call, "Ok") => (),
+            ast::Expr::MethodCallExpr(call) if is_ok_or(&call) => sites.push(ok_or_site(&call)?),
+            // Diverging expressions like `return` and `panic!()` don't give a value to return,
+            // everything else may give an error of the old type.
+            _ if sema.type_of_expr(&expr)?.original.is_never() => (),
+            _ => return None,
+        }
+    }
+    sites.sort_by_key(|site| site.expr.syntax().text_range().start());
+    Some(sites)
+}
+
+fn is_call_to(call: &ast::CallExpr, name: &str) -> bool {
+    call.expr().is_some_and(|callee| callee.syntax().text() == name)
+}
+
+fn is_ok_or(call: &ast::MethodCallExpr) -> bool {
+    call.name_ref().is_some_and(|name| name.text() == "ok_or")
+}
+
+/// The message given to `.ok_or(..)`.
+fn ok_or_site(call: &ast::MethodCallExpr) -> Option<ErrorSite> {
+    if !is_ok_or(call) {
+        return None;
+    }
+    message_site(single_arg(call.arg_list()?)?)
+}
+
+fn message_site(expr: ast::Expr) -> Option<ErrorSite> {
+    let literal = message_literal(&expr)?;
+    let ast::LiteralKind::String(string) = literal.kind() else { return None };
+    let message = string.value()?.into_owned();
+    Some(ErrorSite { expr, literal, message })
+}
+
+fn single_arg(arg_list: ast::ArgList) -> Option<ast::Expr> {
+    let mut args = arg_list.args();
+    let arg = args.next()?;
+    args.next().is_none().then_some(arg)
+}
+
+/// The string literal `expr` is, possibly converted to an owned string.
+fn message_literal(expr: &ast::Expr) -> Option<ast::Literal> {
+    let literal = match expr {
+        ast::Expr::Literal(it) => return Some(it.clone()),
+        // `"..".into()`, `"..".to_string()` and `"..".to_owned()`
+        ast::Expr::MethodCallExpr(call) => {
+            let method = call.name_ref()?;
+            if !matches!(method.text().as_str(), "into" | "to_string" | "to_owned")
+                || call.arg_list()?.args().next().is_some()
+            {
+                return None;
+            }
+            call.receiver()?
+        }
+        // `String::from("..")`
+        ast::Expr::CallExpr(call) => {
+            if call.expr()?.syntax().text() != "String::from" {
+                return None;
+            }
+            single_arg(call.arg_list()?)?
+        }
+        _ => return None,
+    };
+    match literal {
+        ast::Expr::Literal(it) => Some(it),
+        _ => None,
+    }
+}
+
+/// The message in camel case, e.g. `NotANumber` for "not a number!".
+fn unique_variant_name(variants: &[ErrorVariant], message: &str) -> String {
+    let words = message
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    let mut name = to_camel_case(&words.join("_"));
+    if !name.starts_with(|c: char| c.is_alphabetic()) {
+        name.insert_str(0, "Error");
+    }
+    let mut unique_name = name.clone();
+    let mut counter = 1;
+    while variants.iter().any(|it| it.name == unique_name) {
+        counter += 1;
+        unique_name = format!("{name}{counter}");
+    }
+    unique_name
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generate_from_owned_messages() {
+        check_assist(
+            generate_error_enum,
+            r#"
+pub fn parse_port$0(input: &str) -> Result<u16, String> {
+    if input.is_empty() {
+        return Err("empty input".to_string());
+    }
+    let port = input.parse::<u16>().ok().ok_or("not a number")?;
+    if port == 0 {
+        return Err(String::from("port must not be zero"));
+    }
+    if port < 1024 {
+        Err("empty input".into())
+    } else {
+        Ok(port)
+    }
+}
+"#,
+            r#"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParsePortError {
+    EmptyInput,
+    NotANumber,
+    PortMustNotBeZero,
+}
+
+impl core::fmt::Display for ParsePortError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let message = match self {
+            Self::EmptyInput => "empty input",
+            Self::NotANumber => "not a number",
+            Self::PortMustNotBeZero => "port must not be zero",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ParsePortError {}
+
+pub fn parse_port(input: &str) -> Result<u16, ParsePortError> {
+    if input.is_empty() {
+        return Err(ParsePortError::EmptyInput);
+    }
+    let port = input.parse::<u16>().ok().ok_or(ParsePortError::NotANumber)?;
+    if port == 0 {
+        return Err(ParsePortError::PortMustNotBeZero);
+    }
+    if port < 1024 {
+        Err(ParsePortError::EmptyInput)
+    } else {
+        Ok(port)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_for_method() {
+        check_assist(
+            generate_error_enum,
+            r#"
+struct Config;
+
+impl Config {
+    fn load$0(&self, path: &str) -> Result<(), &str> {
+        let closure = |x: u32| -> Result<u32, &str> { Err("ignored") };
+        if path.is_empty() {
+            return Err("404: not found");
+        }
+        Err("Not found!")
+    }
+}
+"#,
+            r#"
+struct Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoadError {
+    Error404NotFound,
+    NotFound,
+}
+
+impl core::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let message = match self {
+            Self::Error404NotFound => "404: not found",
+            Self::NotFound => "Not found!",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl Config {
+    fn load(&self, path: &str) -> Result<(), LoadError> {
+        let closure = |x: u32| -> Result<u32, &str> { Err("ignored") };
+        if path.is_empty() {
+            return Err(LoadError::Error404NotFound);
+        }
+        Err(LoadError::NotFound)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn only_replaces_returned_errors() {
+        check_assist(
+            generate_error_enum,
+            r#"
+//- minicore: panic
+fn parse$0(input: &str) -> Result<u32, &'static str> {
+    let checked: Result<(), &str> = Err("unchecked");
+    if checked.is_err() && input.is_empty() {
+        return Err("empty");
+    }
+    match input.len() {
+        0 => panic!("no input"),
+        1 => Err("too short"),
+        _ => Ok(1),
+    }
+}
+"#,
+            r#"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParseError {
+    Empty,
+    TooShort,
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let message = match self {
+            Self::Empty => "empty",
+            Self::TooShort => "too short",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn parse(input: &str) -> Result<u32, ParseError> {
+    let checked: Result<(), &str> = Err("unchecked");
+    if checked.is_err() && input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    match input.len() {
+        0 => panic!("no input"),
+        1 => Err(ParseError::TooShort),
+        _ => Ok(1),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_in_no_std_crate() {
+        check_assist(
+            generate_error_enum,
+            r#"
+#![no_std]
+fn parse$0(input: &str) -> Result<u32, &'static str> {
+    Err("empty")
+}
+"#,
+            r#"
+#![no_std]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParseError {
+    Empty,
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let message = match self {
+            Self::Empty => "empty",
+        };
+        f.write_str(message)
+    }
+}
+
+fn parse(input: &str) -> Result<u32, ParseError> {
+    Err(ParseError::Empty)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_errors_returned_otherwise() {
+        check_assist_not_applicable(
+            generate_error_enum,
+            r#"
+//- minicore: result
+fn check(input: &str) -> Result<u32, &'static str> {
+    Err("invalid")
+}
+fn parse$0(input: &str) -> Result<u32, &'static str> {
+    if input.is_empty() {
+        return Err("empty");
+    }
+    return check(input);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_error_enum,
+            r#"
+//- minicore: result
+fn check(input: &str) -> Result<u32, &'static str> {
+    Err("invalid")
+}
+fn parse$0(input: &str) -> Result<u32, &'static str> {
+    if input.is_empty() {
+        Err("empty")
+    } else {
+        check(input)
+    }
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_error_enum,
+            r#"
+//- minicore: result, try
+fn check(input: &str) -> Result<u32, &'static str> {
+    Err("invalid")
+}
+fn parse$0(input: &str) -> Result<u32, &'static str> {
+    let n = check(input)?;
+    if n == 0 {
+        return Err("zero");
+    }
+    Ok(n)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_other_errors() {
+        check_assist_not_applicable(
+            generate_error_enum,
+            r#"
+fn parse$0(input: &str) -> Result<u32, String> {
+    if input.is_empty() {
+        return Err("empty".to_owned());
+    }
+    Err(format!("invalid: {input}"))
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_error_enum,
+            r#"
+fn parse$0(input: &str) -> Result<u32, &'static str> {
+    input.parse().map_err(|_| "invalid")?;
+    Err("empty")
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_error_enum,
+            r#"
+fn parse$0(input: &str) -> Result<u32, std::io::Error> {
+    Err("empty")
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_error_enum,
+            r#"
+fn parse(input: &str) -> Result<u32, &'static str> {
+    Err("empty"$0)
+}
+"#,
+        );
+    }
+}
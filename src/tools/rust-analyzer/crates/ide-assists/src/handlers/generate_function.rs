@@ -9,6 +9,7 @@
     helpers::is_editable_crate,
     path_transform::PathTransform,
     source_change::SourceChangeBuilder,
+    syntax_helpers::insert_item::{add_item_to_module, ItemPlacement},
     FxHashMap, FxHashSet, RootDatabase, SnippetCap,
 };
 use itertools::Itertools;
@@ -16,13 +17,13 @@
 use syntax::{
     ast::{
         self, edit::IndentLevel, edit_in_place::Indent, make, AstNode, CallExpr, HasArgList,
-        HasGenericParams, HasModuleItem, HasTypeBounds,
+        HasGenericParams, HasName, HasTypeBounds,
     },
     ted, SyntaxKind, SyntaxNode, TextRange, T,
 };
 
 use crate::{
-    utils::{convert_reference_type, find_struct_impl},
+    utils::{convert_reference_type, find_struct_impl, generate_impl},
     AssistContext, AssistId, AssistKind, Assists,
 };
 
@@ -66,7 +67,7 @@ fn gen_fn(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
     }
 
     let fn_name = &*name_ref.text();
-    let TargetInfo { target_module, adt_name, target, file } =
+    let TargetInfo { target_module, new_impl_for, target, file } =
         fn_target_info(ctx, path, &call, fn_name)?;
 
     if let Some(m) = target_module {
@@ -78,12 +79,14 @@ fn gen_fn(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
     let function_builder = FunctionBuilder::from_call(ctx, &call, fn_name, target_module, target)?;
     let text_range = call.syntax().text_range();
     let label = format!("Generate {} function", function_builder.fn_name);
-    add_func_to_accumulator(acc, ctx, text_range, function_builder, file, adt_name, label)
+    add_func_to_accumulator(acc, ctx, text_range, function_builder, file, new_impl_for, label)
 }
 
 struct TargetInfo {
     target_module: Option<Module>,
-    adt_name: Option<hir::Name>,
+    /// The type to generate a new impl block for, if the function is an associated function of
+    /// a type without an inherent impl.
+    new_impl_for: Option<ast::Adt>,
     target: GeneratedFunctionTarget,
     file: FileId,
 }
@@ -91,11 +94,11 @@ struct TargetInfo {
 impl TargetInfo {
     fn new(
         target_module: Option<Module>,
-        adt_name: Option<hir::Name>,
+        new_impl_for: Option<ast::Adt>,
         target: GeneratedFunctionTarget,
         file: FileId,
     ) -> Self {
-        Self { target_module, adt_name, target, file }
+        Self { target_module, new_impl_for, target, file }
     }
 }
 
@@ -120,6 +123,14 @@ fn fn_target_info(
 
                 assoc_fn_target_info(ctx, call, adt, fn_name)
             }
+            Some(hir::PathResolution::Def(hir::ModuleDef::TypeAlias(alias))) => {
+                let adt = alias.ty(ctx.db()).as_adt()?;
+                if matches!(adt, hir::Adt::Enum(_)) && fn_name.starts_with(char::is_uppercase) {
+                    return None;
+                }
+
+                assoc_fn_target_info(ctx, call, adt, fn_name)
+            }
             Some(hir::PathResolution::SelfType(impl_)) => {
                 let adt = impl_.self_ty(ctx.db()).as_adt()?;
                 assoc_fn_target_info(ctx, call, adt, fn_name)
@@ -145,7 +156,7 @@ fn gen_method(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
         return None;
     }
 
-    let (impl_, file) = get_adt_source(ctx, &adt, fn_name.text().as_str())?;
+    let (impl_, adt_source, file) = get_adt_source(ctx, &adt, fn_name.text().as_str())?;
     let target = get_method_target(ctx, &impl_, &adt)?;
 
     let function_builder = FunctionBuilder::from_method_call(
@@ -157,9 +168,9 @@ fn gen_method(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
         target,
     )?;
     let text_range = call.syntax().text_range();
-    let adt_name = if impl_.is_none() { Some(adt.name(ctx.sema.db)) } else { None };
+    let new_impl_for = if impl_.is_none() { Some(adt_source) } else { None };
     let label = format!("Generate {} method", function_builder.fn_name);
-    add_func_to_accumulator(acc, ctx, text_range, function_builder, file, adt_name, label)
+    add_func_to_accumulator(acc, ctx, text_range, function_builder, file, new_impl_for, label)
 }
 
 fn add_func_to_accumulator(
@@ -168,7 +179,7 @@ fn add_func_to_accumulator(
     text_range: TextRange,
     function_builder: FunctionBuilder,
     file: FileId,
-    adt_name: Option<hir::Name>,
+    new_impl_for: Option<ast::Adt>,
     label: String,
 ) -> Option<()> {
     acc.add(AssistId("generate_function", AssistKind::Generate), label, text_range, |edit| {
@@ -177,11 +188,8 @@ fn add_func_to_accumulator(
         let target = function_builder.target.clone();
         let func = function_builder.render(ctx.config.snippet_cap, edit);
 
-        if let Some(name) = adt_name {
-            let name = make::ty_path(make::ext::ident_path(&format!("{}", name.display(ctx.db()))));
-
-            // FIXME: adt may have generic params.
-            let impl_ = make::impl_(None, None, name, None, None).clone_for_update();
+        if let Some(adt) = new_impl_for {
+            let impl_ = generate_impl(&adt);
 
             func.indent(IndentLevel(1));
             impl_.get_or_create_assoc_item_list().add_item(func.into());
@@ -196,12 +204,13 @@ fn get_adt_source(
     ctx: &AssistContext<'_>,
     adt: &hir::Adt,
     fn_name: &str,
-) -> Option<(Option<ast::Impl>, FileId)> {
+) -> Option<(Option<ast::Impl>, ast::Adt, FileId)> {
     let range = adt.source(ctx.sema.db)?.syntax().original_file_range_rooted(ctx.sema.db);
     let file = ctx.sema.parse(range.file_id);
-    let adt_source =
+    let adt_source: ast::Adt =
         ctx.sema.find_node_at_offset_with_macros(file.syntax(), range.range.start())?;
-    find_struct_impl(ctx, &adt_source, &[fn_name.to_owned()]).map(|impl_| (impl_, range.file_id))
+    let impl_ = find_struct_impl(ctx, &adt_source, &[fn_name.to_owned()])?;
+    Some((impl_, adt_source, range.file_id))
 }
 
 struct FunctionBuilder {
@@ -233,22 +242,30 @@ fn from_call(
         let visibility = calculate_necessary_visibility(current_module, target_module, ctx);
         let fn_name = make::name(fn_name);
         let mut necessary_generic_params = FxHashSet::default();
+        let mut impl_trait_params = ImplTraitParams::new(ctx, call.syntax());
         let params = fn_args(
             ctx,
             target_module,
             ast::CallableExpr::Call(call.clone()),
             &mut necessary_generic_params,
+            &mut impl_trait_params,
         )?;
 
         let await_expr = call.syntax().parent().and_then(ast::AwaitExpr::cast);
         let is_async = await_expr.is_some();
 
         let expr_for_ret_ty = await_expr.map_or_else(|| call.clone().into(), |it| it.into());
-        let (ret_type, should_focus_return_type) =
-            make_return_type(ctx, &expr_for_ret_ty, target_module, &mut necessary_generic_params);
+        let (ret_type, should_focus_return_type) = make_return_type(
+            ctx,
+            &expr_for_ret_ty,
+            target_module,
+            &mut necessary_generic_params,
+            &mut impl_trait_params,
+        );
 
         let (generic_param_list, where_clause) =
             fn_generic_params(ctx, necessary_generic_params, &target)?;
+        let generic_param_list = impl_trait_params.add_to(generic_param_list);
 
         Some(Self {
             target,
@@ -276,23 +293,34 @@ fn from_method_call(
 
         let fn_name = make::name(&name.text());
         let mut necessary_generic_params = FxHashSet::default();
-        necessary_generic_params.extend(receiver_ty.generic_params(ctx.db()));
+        let mut impl_trait_params = ImplTraitParams::new(ctx, call.syntax());
         let params = fn_args(
             ctx,
             target_module,
             ast::CallableExpr::MethodCall(call.clone()),
             &mut necessary_generic_params,
+            &mut impl_trait_params,
         )?;
 
         let await_expr = call.syntax().parent().and_then(ast::AwaitExpr::cast);
         let is_async = await_expr.is_some();
 
         let expr_for_ret_ty = await_expr.map_or_else(|| call.clone().into(), |it| it.into());
-        let (ret_type, should_focus_return_type) =
-            make_return_type(ctx, &expr_for_ret_ty, target_module, &mut necessary_generic_params);
+        let (ret_type, should_focus_return_type) = make_return_type(
+            ctx,
+            &expr_for_ret_ty,
+            target_module,
+            &mut necessary_generic_params,
+            &mut impl_trait_params,
+        );
+
+        // The generic arguments of the receiver are in scope through the impl's parameters.
+        let receiver_params = receiver_ty.generic_params(ctx.db());
+        necessary_generic_params.retain(|it| !receiver_params.contains(it));
 
         let (generic_param_list, where_clause) =
             fn_generic_params(ctx, necessary_generic_params, &target)?;
+        let generic_param_list = impl_trait_params.add_to(generic_param_list);
 
         Some(Self {
             target,
@@ -371,20 +399,24 @@ fn make_return_type(
     expr: &ast::Expr,
     target_module: Module,
     necessary_generic_params: &mut FxHashSet<hir::GenericParam>,
+    impl_trait_params: &mut ImplTraitParams,
 ) -> (Option<ast::RetType>, bool) {
     let (ret_ty, should_focus_return_type) = {
         match ctx.sema.type_of_expr(expr).map(TypeInfo::original) {
             Some(ty) if ty.is_unknown() => (Some(make::ty_placeholder()), true),
             None => (Some(make::ty_placeholder()), true),
             Some(ty) if ty.is_unit() => (None, false),
-            Some(ty) => {
-                necessary_generic_params.extend(ty.generic_params(ctx.db()));
-                let rendered = ty.display_source_code(ctx.db(), target_module.into(), true);
-                match rendered {
-                    Ok(rendered) => (Some(make::ty(&rendered)), false),
-                    Err(_) => (Some(make::ty_placeholder()), true),
+            Some(ty) => match impl_trait_params.param_for(ctx, target_module, &ty) {
+                Some(param) => (Some(make::ty(&param)), false),
+                None => {
+                    necessary_generic_params.extend(ty.generic_params(ctx.db()));
+                    let rendered = ty.display_source_code(ctx.db(), target_module.into(), true);
+                    match rendered {
+                        Ok(rendered) => (Some(make::ty(&rendered)), false),
+                        Err(_) => (Some(make::ty_placeholder()), true),
+                    }
                 }
-            }
+            },
         }
     };
     let ret_type = ret_ty.map(make::ret_type);
@@ -441,10 +473,10 @@ fn assoc_fn_target_info(
     if current_module.krate() != module.krate() {
         return None;
     }
-    let (impl_, file) = get_adt_source(ctx, &adt, fn_name)?;
+    let (impl_, adt_source, file) = get_adt_source(ctx, &adt, fn_name)?;
     let target = get_method_target(ctx, &impl_, &adt)?;
-    let adt_name = if impl_.is_none() { Some(adt.name(ctx.sema.db)) } else { None };
-    Some(TargetInfo::new(target_module, adt_name, target, file))
+    let new_impl_for = if impl_.is_none() { Some(adt_source) } else { None };
+    Some(TargetInfo::new(target_module, new_impl_for, target, file))
 }
 
 #[derive(Clone)]
@@ -452,6 +484,8 @@ enum GeneratedFunctionTarget {
     AfterItem(SyntaxNode),
     InEmptyItemList(SyntaxNode),
     InImpl(ast::Impl),
+    /// Among the items of a module, which is either an `ast::SourceFile` or an `ast::ItemList`.
+    InModule(SyntaxNode),
 }
 
 impl GeneratedFunctionTarget {
@@ -460,6 +494,7 @@ fn syntax(&self) -> &SyntaxNode {
             GeneratedFunctionTarget::AfterItem(it) => it,
             GeneratedFunctionTarget::InEmptyItemList(it) => it,
             GeneratedFunctionTarget::InImpl(it) => it.syntax(),
+            GeneratedFunctionTarget::InModule(it) => it,
         }
     }
 
@@ -468,6 +503,7 @@ fn parent(&self) -> SyntaxNode {
             GeneratedFunctionTarget::AfterItem(it) => it.parent().expect("item without parent"),
             GeneratedFunctionTarget::InEmptyItemList(it) => it.clone(),
             GeneratedFunctionTarget::InImpl(it) => it.syntax().clone(),
+            GeneratedFunctionTarget::InModule(it) => it.clone(),
         }
     }
 
@@ -506,6 +542,9 @@ fn insert_impl_at(&self, edit: &mut SourceChangeBuilder, impl_: ast::Impl) {
             GeneratedFunctionTarget::InImpl(_) => {
                 unreachable!("can't insert an impl inside an impl")
             }
+            GeneratedFunctionTarget::InModule(module) => {
+                add_to_module(&edit.make_syntax_mut(module.clone()), impl_.into())
+            }
         }
     }
 
@@ -556,22 +595,40 @@ fn insert_fn_at(&self, edit: &mut SourceChangeBuilder, func: ast::Fn) {
 
                 impl_.get_or_create_assoc_item_list().add_item(func.into());
             }
+            GeneratedFunctionTarget::InModule(module) => {
+                add_to_module(&edit.make_syntax_mut(module.clone()), func.into())
+            }
         }
     }
 }
 
+fn add_to_module(module: &SyntaxNode, item: ast::Item) {
+    if let Some(file) = ast::SourceFile::cast(module.clone()) {
+        add_item_to_module(&file, item, ItemPlacement::Conventional);
+    } else if let Some(item_list) = ast::ItemList::cast(module.clone()) {
+        add_item_to_module(&item_list, item, ItemPlacement::Conventional);
+    }
+}
+
 /// Computes parameter list for the generated function.
 fn fn_args(
     ctx: &AssistContext<'_>,
     target_module: Module,
     call: ast::CallableExpr,
     necessary_generic_params: &mut FxHashSet<hir::GenericParam>,
+    impl_trait_params: &mut ImplTraitParams,
 ) -> Option<ast::ParamList> {
     let mut arg_names = Vec::new();
     let mut arg_types = Vec::new();
     for arg in call.arg_list()?.args() {
         arg_names.push(fn_arg_name(&ctx.sema, &arg));
-        arg_types.push(fn_arg_type(ctx, target_module, &arg, necessary_generic_params));
+        arg_types.push(fn_arg_type(
+            ctx,
+            target_module,
+            &arg,
+            necessary_generic_params,
+            impl_trait_params,
+        ));
     }
     deduplicate_arg_names(&mut arg_names);
     let params = arg_names.into_iter().zip(arg_types).map(|(name, ty)| {
@@ -998,18 +1055,24 @@ fn fn_arg_type(
     target_module: Module,
     fn_arg: &ast::Expr,
     generic_params: &mut FxHashSet<hir::GenericParam>,
+    impl_trait_params: &mut ImplTraitParams,
 ) -> String {
     fn maybe_displayed_type(
         ctx: &AssistContext<'_>,
         target_module: Module,
         fn_arg: &ast::Expr,
         generic_params: &mut FxHashSet<hir::GenericParam>,
+        impl_trait_params: &mut ImplTraitParams,
     ) -> Option<String> {
         let ty = ctx.sema.type_of_expr(fn_arg)?.adjusted();
         if ty.is_unknown() {
             return None;
         }
 
+        if let Some(param) = impl_trait_params.param_for(ctx, target_module, &ty) {
+            return Some(param);
+        }
+
         generic_params.extend(ty.generic_params(ctx.db()));
 
         if ty.is_reference() || ty.is_mutable_reference() {
@@ -1022,10 +1085,81 @@ fn maybe_displayed_type(
         }
     }
 
-    maybe_displayed_type(ctx, target_module, fn_arg, generic_params)
+    maybe_displayed_type(ctx, target_module, fn_arg, generic_params, impl_trait_params)
         .unwrap_or_else(|| String::from("_"))
 }
 
+/// Named type parameters replacing the `impl Trait` parameters of the caller, so that the same
+/// type used by several arguments or the return type stays the same type.
+struct ImplTraitParams {
+    taken_names: FxHashSet<String>,
+    params: Vec<(hir::TypeParam, ast::TypeParam)>,
+}
+
+impl ImplTraitParams {
+    fn new(ctx: &AssistContext<'_>, call: &SyntaxNode) -> Self {
+        let mut taken_names = FxHashSet::default();
+        if let Some(scope) = ctx.sema.scope(call) {
+            scope.process_all_names(&mut |name, _| {
+                taken_names.insert(name.display(ctx.db()).to_string());
+            });
+        }
+        Self { taken_names, params: Vec::new() }
+    }
+
+    /// Renders `ty` with the named type parameter replacing it, if it's an `impl Trait`
+    /// parameter of the caller or a reference to one. The type parameter is introduced with the
+    /// trait's bounds on the first use of the `impl Trait` parameter.
+    fn param_for(
+        &mut self,
+        ctx: &AssistContext<'_>,
+        target_module: Module,
+        ty: &Type,
+    ) -> Option<String> {
+        let db = ctx.db();
+        let (prefix, ty) = match ty.as_reference() {
+            Some((inner, mutability)) => (format!("&{}", mutability.as_keyword_for_ref()), inner),
+            None => (String::new(), ty.clone()),
+        };
+        // `Self` of traits is implicit as well, but can be named already
+        let param = ty.as_type_param(db).filter(|it| {
+            it.is_implicit(db)
+                && !matches!(hir::GenericParam::TypeParam(*it).parent(), hir::GenericDef::Trait(_))
+        })?;
+        if let Some((_, existing)) = self.params.iter().find(|(it, _)| *it == param) {
+            return Some(format!("{prefix}{}", existing.name()?));
+        }
+
+        let ast::Type::ImplTraitType(impl_trait) =
+            make::ty(&ty.display_source_code(db, target_module.into(), true).ok()?)
+        else {
+            return None;
+        };
+        let name = ["T", "U", "V", "W"]
+            .into_iter()
+            .map(String::from)
+            .chain((1..).map(|idx| format!("T{idx}")))
+            .find(|it| !self.taken_names.contains(it))?;
+        self.taken_names.insert(name.clone());
+        let type_param = make::type_param(make::name(&name), impl_trait.type_bound_list());
+        self.params.push((param, type_param));
+        Some(format!("{prefix}{name}"))
+    }
+
+    /// Appends the introduced type parameters to `generic_param_list`.
+    fn add_to(
+        &self,
+        generic_param_list: Option<ast::GenericParamList>,
+    ) -> Option<ast::GenericParamList> {
+        if self.params.is_empty() {
+            return generic_param_list;
+        }
+        let introduced = self.params.iter().map(|(_, it)| ast::GenericParam::TypeParam(it.clone()));
+        let params = generic_param_list.iter().flat_map(|it| it.generic_params()).chain(introduced);
+        Some(make::generic_param_list(params))
+    }
+}
+
 /// Returns the position inside the current mod or file
 /// directly after the current block
 /// We want to write the generated function directly after
@@ -1057,18 +1191,12 @@ fn next_space_for_fn_in_module(
     let module_source = target_module.definition_source(db);
     let file = module_source.file_id.original_file(db.upcast());
     let assist_item = match &module_source.value {
-        hir::ModuleSource::SourceFile(it) => match it.items().last() {
-            Some(last_item) => GeneratedFunctionTarget::AfterItem(last_item.syntax().clone()),
-            None => GeneratedFunctionTarget::AfterItem(it.syntax().clone()),
-        },
-        hir::ModuleSource::Module(it) => match it.item_list().and_then(|it| it.items().last()) {
-            Some(last_item) => GeneratedFunctionTarget::AfterItem(last_item.syntax().clone()),
-            None => {
-                let item_list =
-                    it.item_list().expect("module definition source should have an item list");
-                GeneratedFunctionTarget::InEmptyItemList(item_list.syntax().clone())
-            }
-        },
+        hir::ModuleSource::SourceFile(it) => GeneratedFunctionTarget::InModule(it.syntax().clone()),
+        hir::ModuleSource::Module(it) => {
+            let item_list =
+                it.item_list().expect("module definition source should have an item list");
+            GeneratedFunctionTarget::InModule(item_list.syntax().clone())
+        }
         hir::ModuleSource::BlockExpr(it) => {
             if let Some(last_item) =
                 it.statements().take_while(|stmt| matches!(stmt, ast::Stmt::Item(_))).last()
@@ -1644,7 +1772,6 @@ fn bar<T, U>(t: T, u: U) {
 
     #[test]
     fn generic_param_in_receiver_type() {
-        // FIXME: Generic parameter `T` should be part of impl, not method.
         check_assist(
             generate_function,
             r"
@@ -1653,8 +1780,8 @@ fn foo<T, U>(s: S<T>, u: U) { s.$0foo(u) }
 ",
             r"
 struct S<T>(T);
-impl S {
-    fn foo<T, U>(&self, u: U) {
+impl<T> S<T> {
+    fn foo<U>(&self, u: U) {
         ${0:todo!()}
     }
 }
@@ -1663,6 +1790,46 @@ fn foo<T, U>(s: S<T>, u: U) { s.foo(u) }
         )
     }
 
+    #[test]
+    fn impl_trait_param_becomes_generic_param() {
+        check_assist(
+            generate_function,
+            r"
+//- minicore: sized
+trait Foo {}
+fn foo(x: impl Foo) { $0bar(x) }
+",
+            r"
+trait Foo {}
+fn foo(x: impl Foo) { bar(x) }
+
+fn bar<T: Foo>(x: T) {
+    ${0:todo!()}
+}
+",
+        )
+    }
+
+    #[test]
+    fn impl_trait_params_keep_their_identity() {
+        check_assist(
+            generate_function,
+            r"
+//- minicore: sized
+trait Foo {}
+fn foo<T: Foo>(t: T, x: impl Foo, y: impl Foo) { $0bar(t, &x, y, &x) }
+",
+            r"
+trait Foo {}
+fn foo<T: Foo>(t: T, x: impl Foo, y: impl Foo) { bar(t, &x, y, &x) }
+
+fn bar<T: Foo, U: Foo, V: Foo>(t: T, x_1: &U, y: V, x_2: &U) {
+    ${0:todo!()}
+}
+",
+        )
+    }
+
     #[test]
     fn generic_param_in_return_type() {
         check_assist(
@@ -2151,6 +2318,36 @@ fn foo() {
         )
     }
 
+    #[test]
+    fn add_function_in_module_before_tests_module() {
+        check_assist(
+            generate_function,
+            r"
+//- /main.rs
+mod foo;
+
+fn main() {
+    foo::bar$0()
+}
+//- /foo.rs
+fn something_else() {}
+
+#[cfg(test)]
+mod tests {}
+",
+            r"
+fn something_else() {}
+
+pub(crate) fn bar() {
+    ${0:todo!()}
+}
+
+#[cfg(test)]
+mod tests {}
+",
+        )
+    }
+
     #[test]
     fn add_function_in_nested_module() {
         check_assist(
@@ -2194,11 +2391,10 @@ fn main() {
 //- /foo.rs
 ",
             r"
-
-
 pub(crate) fn bar() {
     ${0:todo!()}
-}",
+}
+",
         )
     }
 
@@ -2578,6 +2774,60 @@ pub(crate) fn bar() ${0:-> _} {
         )
     }
 
+    #[test]
+    fn create_static_method_through_type_alias() {
+        check_assist(
+            generate_function,
+            r"
+mod s {
+    pub struct S;
+    pub type Alias = S;
+}
+fn foo() {s::Alias::bar$0();}
+",
+            r"
+mod s {
+    pub struct S;
+    impl S {
+        pub(crate) fn bar() ${0:-> _} {
+            todo!()
+        }
+    }
+    pub type Alias = S;
+}
+fn foo() {s::Alias::bar();}
+",
+        )
+    }
+
+    #[test]
+    fn create_method_for_generic_type_in_another_file() {
+        check_assist(
+            generate_function,
+            r"
+//- /main.rs
+mod s;
+fn foo(s: s::S<u32>) {
+    s.bar$0();
+}
+//- /s.rs
+pub struct S<T: Clone> {
+    pub t: T,
+}
+",
+            r"
+pub struct S<T: Clone> {
+    pub t: T,
+}
+impl<T: Clone> S<T> {
+    pub(crate) fn bar(&self) ${0:-> _} {
+        todo!()
+    }
+}
+",
+        )
+    }
+
     #[test]
     fn create_static_method_with_cursor_anywhere_on_call_expression() {
         check_assist(
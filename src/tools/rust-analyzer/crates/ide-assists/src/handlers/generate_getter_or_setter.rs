@@ -149,6 +149,101 @@ pub(crate) fn generate_getter_mut(acc: &mut Assists, ctx: &AssistContext<'_>) ->
     generate_getter_impl(acc, ctx, true)
 }
 
+// Assist: generate_accessors
+//
+// Generate getters for all private fields of a struct, optionally along with setters or mut
+// getters.
+//
+// ```
+// struct Per$0son {
+//     name: u32,
+//     last_seen: u64,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: u32,
+//     last_seen: u64,
+// }
+//
+// impl Person {
+//     /// Returns the name.
+//     fn name(&self) -> &u32 {
+//         &self.name
+//     }
+//
+//     /// Returns the last seen.
+//     fn $0last_seen(&self) -> &u64 {
+//         &self.last_seen
+//     }
+// }
+// ```
+pub(crate) fn generate_accessors(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    if !ctx.has_empty_selection() {
+        return None;
+    }
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let strukt = name.syntax().parent().and_then(ast::Struct::cast)?;
+    let ast::FieldList::RecordFieldList(field_list) = strukt.field_list()? else {
+        return None;
+    };
+    let private_fields: Vec<_> =
+        field_list.fields().filter(|field| field.visibility().is_none()).collect();
+
+    let impl_def = find_struct_impl(ctx, &ast::Adt::Struct(strukt.clone()), &[])?;
+    let existing_fns: Vec<String> = impl_def
+        .iter()
+        .flat_map(|impl_def| impl_def.assoc_item_list())
+        .flat_map(|it| it.assoc_items())
+        .filter_map(|item| match item {
+            ast::AssocItem::Fn(it) => Some(it.name()?.text().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let target = name.syntax().text_range();
+    let group = GroupLabel("Generate accessors for all private fields".to_owned());
+    for (label, assist_types) in [
+        ("Generate getters for all private fields", &[AssistType::Get][..]),
+        (
+            "Generate getters and setters for all private fields",
+            &[AssistType::Get, AssistType::Set],
+        ),
+        (
+            "Generate getters and mut getters for all private fields",
+            &[AssistType::Get, AssistType::MutGet],
+        ),
+    ] {
+        let accessors: Vec<_> = private_fields
+            .iter()
+            .flat_map(|field| {
+                assist_types.iter().filter_map(move |&assist_type| {
+                    let info = parse_record_field(field.clone(), &assist_type)?;
+                    Some((assist_type, info))
+                })
+            })
+            .filter(|(assist_type, info)| {
+                !existing_fns.contains(&accessor_fn_name(*assist_type, info))
+            })
+            .collect();
+        if accessors.is_empty() {
+            continue;
+        }
+
+        acc.add_group(
+            &group,
+            AssistId("generate_accessors", AssistKind::Generate),
+            label,
+            target,
+            |builder| {
+                build_accessors(builder, ctx, &strukt, impl_def.clone(), accessors, true);
+            },
+        );
+    }
+    Some(())
+}
+
 #[derive(Clone, Debug)]
 struct RecordFieldInfo {
     field_name: syntax::ast::Name,
@@ -163,6 +258,7 @@ struct AssistInfo {
     assist_type: AssistType,
 }
 
+#[derive(Clone, Copy)]
 enum AssistType {
     Get,
     MutGet,
@@ -370,23 +466,56 @@ fn parse_record_field(
     Some(RecordFieldInfo { field_name, field_ty, fn_name, target })
 }
 
+/// The name of the method generating `assist_type` generates for the field.
+fn accessor_fn_name(assist_type: AssistType, record_field_info: &RecordFieldInfo) -> String {
+    match assist_type {
+        AssistType::Set => format!("set_{}", record_field_info.fn_name),
+        AssistType::Get | AssistType::MutGet => record_field_info.fn_name.clone(),
+    }
+}
+
+/// A doc comment for the accessor, made up from the field's name.
+fn accessor_doc_comment(assist_type: AssistType, record_field_info: &RecordFieldInfo) -> String {
+    let field = record_field_info.field_name.text().trim_start_matches("r#").replace('_', " ");
+    match assist_type {
+        AssistType::Get => format!("/// Returns the {field}."),
+        AssistType::MutGet => format!("/// Returns a mutable reference to the {field}."),
+        AssistType::Set => format!("/// Sets the {field}."),
+    }
+}
+
 fn build_source_change(
     builder: &mut SourceChangeBuilder,
     ctx: &AssistContext<'_>,
     info_of_record_fields: Vec<RecordFieldInfo>,
     assist_info: AssistInfo,
 ) {
-    let record_fields_count = info_of_record_fields.len();
+    let accessors = info_of_record_fields
+        .into_iter()
+        .map(|record_field_info| (assist_info.assist_type, record_field_info))
+        .collect();
+    build_accessors(builder, ctx, &assist_info.strukt, assist_info.impl_def, accessors, false);
+}
+
+fn build_accessors(
+    builder: &mut SourceChangeBuilder,
+    ctx: &AssistContext<'_>,
+    strukt: &ast::Struct,
+    impl_def: Option<ast::Impl>,
+    accessors: Vec<(AssistType, RecordFieldInfo)>,
+    with_docs: bool,
+) {
+    let accessors_count = accessors.len();
 
-    let impl_def = if let Some(impl_def) = &assist_info.impl_def {
+    let impl_def = if let Some(impl_def) = impl_def {
         // We have an existing impl to add to
-        builder.make_mut(impl_def.clone())
+        builder.make_mut(impl_def)
     } else {
         // Generate a new impl to add the methods to
-        let impl_def = generate_impl(&ast::Adt::Struct(assist_info.strukt.clone()));
+        let impl_def = generate_impl(&ast::Adt::Struct(strukt.clone()));
 
         // Insert it after the adt
-        let strukt = builder.make_mut(assist_info.strukt.clone());
+        let strukt = builder.make_mut(strukt.clone());
 
         ted::insert_all_raw(
             ted::Position::after(strukt.syntax()),
@@ -398,17 +527,26 @@ fn build_source_change(
 
     let assoc_item_list = impl_def.get_or_create_assoc_item_list();
 
-    for (i, record_field_info) in info_of_record_fields.iter().enumerate() {
+    for (i, (assist_type, record_field_info)) in accessors.iter().enumerate() {
+        let assist_info =
+            AssistInfo { impl_def: None, strukt: strukt.clone(), assist_type: *assist_type };
         // Make the new getter or setter fn
-        let new_fn = match assist_info.assist_type {
+        let new_fn = match assist_type {
             AssistType::Set => generate_setter_from_info(&assist_info, record_field_info),
             _ => generate_getter_from_info(ctx, &assist_info, record_field_info),
         }
         .clone_for_update();
+        if with_docs {
+            let doc = make::tokens::comment(&accessor_doc_comment(*assist_type, record_field_info));
+            ted::insert_all(
+                ted::Position::first_child_of(new_fn.syntax()),
+                vec![doc.into(), make::tokens::single_newline().into()],
+            );
+        }
         new_fn.indent(1.into());
 
         // Insert a tabstop only for last method we generate
-        if i == record_fields_count - 1 {
+        if i == accessors_count - 1 {
             if let Some(cap) = ctx.config.snippet_cap {
                 if let Some(name) = new_fn.name() {
                     builder.add_tabstop_before(cap, name);
@@ -903,6 +1041,136 @@ fn data(&self) -> &Data {
     }
 }
 
+#[cfg(test)]
+mod tests_accessors {
+    use crate::tests::{check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn generate_getters_for_private_fields() {
+        check_assist_by_label(
+            generate_accessors,
+            r#"
+pub struct Cont$0ext {
+    data: Data,
+    pub public: u32,
+    item_count: usize,
+}
+"#,
+            r#"
+pub struct Context {
+    data: Data,
+    pub public: u32,
+    item_count: usize,
+}
+
+impl Context {
+    /// Returns the data.
+    pub fn data(&self) -> &Data {
+        &self.data
+    }
+
+    /// Returns the item count.
+    pub fn $0item_count(&self) -> &usize {
+        &self.item_count
+    }
+}
+"#,
+            "Generate getters for all private fields",
+        );
+    }
+
+    #[test]
+    fn generate_getters_and_setters_skipping_existing() {
+        check_assist_by_label(
+            generate_accessors,
+            r#"
+struct Cont$0ext {
+    data: Data,
+    count: usize,
+}
+
+impl Context {
+    fn data(&self) -> &Data {
+        &self.data
+    }
+}
+"#,
+            r#"
+struct Context {
+    data: Data,
+    count: usize,
+}
+
+impl Context {
+    fn data(&self) -> &Data {
+        &self.data
+    }
+
+    /// Sets the data.
+    fn set_data(&mut self, data: Data) {
+        self.data = data;
+    }
+
+    /// Returns the count.
+    fn count(&self) -> &usize {
+        &self.count
+    }
+
+    /// Sets the count.
+    fn $0set_count(&mut self, count: usize) {
+        self.count = count;
+    }
+}
+"#,
+            "Generate getters and setters for all private fields",
+        );
+    }
+
+    #[test]
+    fn generate_getters_and_mut_getters() {
+        check_assist_by_label(
+            generate_accessors,
+            r#"
+struct Cont$0ext {
+    data: Data,
+}
+"#,
+            r#"
+struct Context {
+    data: Data,
+}
+
+impl Context {
+    /// Returns the data.
+    fn data(&self) -> &Data {
+        &self.data
+    }
+
+    /// Returns a mutable reference to the data.
+    fn $0data_mut(&mut self) -> &mut Data {
+        &mut self.data
+    }
+}
+"#,
+            "Generate getters and mut getters for all private fields",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_private_fields() {
+        check_assist_not_applicable(
+            generate_accessors,
+            r#"
+struct Cont$0ext {
+    pub data: Data,
+}
+"#,
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests_setter {
     use crate::tests::{check_assist, check_assist_not_applicable};
@@ -0,0 +1,378 @@
+use hir::{known, ModuleDef, Mutability, Trait};
+use ide_db::{famous_defs::FamousDefs, RootDatabase};
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    ast::{self, HasName},
+    AstNode,
+};
+
+use crate::{
+    utils::{generate_trait_impl_text_for_ref, generate_trait_impl_text_intransitive},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: generate_into_iterator_impls
+//
+// Generate the `IntoIterator` impls for a wrapper around a collection and for references to it,
+// along with a `FromIterator` impl, delegating to the wrapped collection.
+//
+// ```
+// # //- minicore: iterator, from_iterator
+// # struct Vec<T>(T);
+// # struct IntoIter<T>(T);
+// # impl<T> Iterator for IntoIter<T> {
+// #     type Item = T;
+// #     fn next(&mut self) -> Option<T> { loop {} }
+// # }
+// # impl<T> IntoIterator for Vec<T> {
+// #     type Item = T;
+// #     type IntoIter = IntoIter<T>;
+// #     fn into_iter(self) -> IntoIter<T> { loop {} }
+// # }
+// # impl<T> core::iter::FromIterator<T> for Vec<T> {
+// #     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self { loop {} }
+// # }
+// struct Stack<T>($0Vec<T>);
+// ```
+// ->
+// ```
+// # struct Vec<T>(T);
+// # struct IntoIter<T>(T);
+// # impl<T> Iterator for IntoIter<T> {
+// #     type Item = T;
+// #     fn next(&mut self) -> Option<T> { loop {} }
+// # }
+// # impl<T> IntoIterator for Vec<T> {
+// #     type Item = T;
+// #     type IntoIter = IntoIter<T>;
+// #     fn into_iter(self) -> IntoIter<T> { loop {} }
+// # }
+// # impl<T> core::iter::FromIterator<T> for Vec<T> {
+// #     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self { loop {} }
+// # }
+// struct Stack<T>(Vec<T>);
+//
+// impl<T> IntoIterator for Stack<T> {
+//     type Item = <Vec<T> as IntoIterator>::Item;
+//     type IntoIter = <Vec<T> as IntoIterator>::IntoIter;
+//
+//     fn into_iter(self) -> Self::IntoIter {
+//         self.0.into_iter()
+//     }
+// }
+//
+// impl<T> core::iter::FromIterator<<Vec<T> as IntoIterator>::Item> for Stack<T> {
+//     fn from_iter<I: IntoIterator<Item = <Vec<T> as IntoIterator>::Item>>(iter: I) -> Self {
+//         Self(iter.into_iter().collect())
+//     }
+// }
+// ```
+pub(crate) fn generate_into_iterator_impls(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let (field_name, field_ty) = match strukt.field_list()? {
+        ast::FieldList::RecordFieldList(fields) => {
+            let field = fields.fields().exactly_one().ok()?;
+            (field.name()?.to_string(), field.ty()?)
+        }
+        ast::FieldList::TupleFieldList(fields) => {
+            ("0".to_owned(), fields.fields().exactly_one().ok()?.ty()?)
+        }
+    };
+
+    let db = ctx.db();
+    let strukt_def = ctx.sema.to_def(&strukt)?;
+    let module = strukt_def.module(db);
+    let famous_defs = FamousDefs(&ctx.sema, module.krate());
+    let into_iter_trait = famous_defs.core_iter_IntoIterator()?;
+    let from_iter_trait = famous_defs.core_iter_FromIterator();
+
+    let inner_ty = strukt_def.fields(db).pop()?.ty(db);
+    let strukt_ty = strukt_def.ty(db);
+    // Impls that exist already aren't generated again.
+    let missing_into_iter = |mutability: Option<Mutability>| {
+        let (inner_ty, strukt_ty) = match mutability {
+            Some(m) => (hir::Type::reference(&inner_ty, m), hir::Type::reference(&strukt_ty, m)),
+            None => (inner_ty.clone(), strukt_ty.clone()),
+        };
+        inner_ty.impls_trait(db, into_iter_trait, &[])
+            && !strukt_ty.impls_trait(db, into_iter_trait, &[])
+    };
+    let owned = missing_into_iter(None);
+    let by_ref = missing_into_iter(Some(Mutability::Shared));
+    let by_mut_ref = missing_into_iter(Some(Mutability::Mut));
+    let from_iter = from_iter_trait.filter(|&from_iter_trait| {
+        let Some(item_ty) = iterator_item(db, into_iter_trait, &inner_ty) else {
+            return false;
+        };
+        inner_ty.impls_trait(db, from_iter_trait, std::slice::from_ref(&item_ty))
+            && !strukt_ty.impls_trait(db, from_iter_trait, &[item_ty])
+    });
+    if !(owned || by_ref || by_mut_ref || from_iter.is_some()) {
+        return None;
+    }
+
+    let trait_path = |trait_: Trait| {
+        module
+            .find_use_path(
+                db,
+                ModuleDef::Trait(trait_),
+                ctx.config.prefer_no_std,
+                ctx.config.prefer_prelude,
+            )
+            .map(|path| path.display(db).to_string())
+    };
+    let into_iter_path = trait_path(into_iter_trait)?;
+    let from_iter_path = match from_iter {
+        Some(trait_) => Some(trait_path(trait_)?),
+        None => None,
+    };
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("generate_into_iterator_impls", AssistKind::Generate),
+        "Generate `IntoIterator` and `FromIterator` impls",
+        target,
+        |builder| {
+            let adt = ast::Adt::Struct(strukt.clone());
+            let mut buf = String::new();
+            if owned {
+                let code = into_iter_code(&into_iter_path, &field_ty, "", "", &field_name);
+                buf.push_str(&generate_trait_impl_text_intransitive(&adt, &into_iter_path, &code));
+            }
+            for (mutable, ref_kind, self_ref) in [(false, "&'a ", "&"), (true, "&'a mut ", "&mut ")]
+            {
+                if !(if mutable { by_mut_ref } else { by_ref }) {
+                    continue;
+                }
+                let code =
+                    into_iter_code(&into_iter_path, &field_ty, ref_kind, self_ref, &field_name);
+                buf.push_str(&generate_trait_impl_text_for_ref(
+                    &adt,
+                    "'a",
+                    mutable,
+                    &into_iter_path,
+                    &code,
+                ));
+            }
+            if let Some(from_iter_path) = from_iter_path {
+                let item = format!("<{field_ty} as {into_iter_path}>::Item");
+                let ctor = match strukt.field_list() {
+                    Some(ast::FieldList::RecordFieldList(_)) => {
+                        format!("Self {{ {field_name}: iter.into_iter().collect() }}")
+                    }
+                    _ => "Self(iter.into_iter().collect())".to_owned(),
+                };
+                let mut code = String::new();
+                format_to!(
+                    code,
+                    "    fn from_iter<I: {into_iter_path}<Item = {item}>>(iter: I) -> Self {{\n"
+                );
+                format_to!(code, "        {ctor}\n    }}");
+                buf.push_str(&generate_trait_impl_text_intransitive(
+                    &adt,
+                    &format!("{from_iter_path}<{item}>"),
+                    &code,
+                ));
+            }
+            builder.insert(target.end(), buf);
+        },
+    )
+}
+
+/// The body of an `IntoIterator` impl delegating to the field `field_name` of type `field_ty`,
+/// `ref_kind` is the kind of reference to the field type and `self_ref` the one taken of the field.
+fn into_iter_code(
+    into_iter_path: &str,
+    field_ty: &ast::Type,
+    ref_kind: &str,
+    self_ref: &str,
+    field_name: &str,
+) -> String {
+    let inner = format!("<{ref_kind}{field_ty} as {into_iter_path}>");
+    let receiver = match self_ref {
+        "" => format!("self.{field_name}"),
+        _ => format!("({self_ref}self.{field_name})"),
+    };
+    format!(
+        "    type Item = {inner}::Item;
+    type IntoIter = {inner}::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {{
+        {receiver}.into_iter()
+    }}"
+    )
+}
+
+/// The `Item` type of `ty` as an `IntoIterator`.
+fn iterator_item(db: &RootDatabase, into_iter_trait: Trait, ty: &hir::Type) -> Option<hir::Type> {
+    let item = into_iter_trait.items(db).into_iter().find_map(|item| match item {
+        hir::AssocItem::TypeAlias(alias) if alias.name(db) == known::Item => Some(alias),
+        _ => None,
+    })?;
+    ty.normalize_trait_assoc_type(db, &[], item)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    const LIST: &str = r#"struct List<T>(T);
+struct Iter<T>(T);
+impl<T> Iterator for Iter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> { loop {} }
+}
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = Iter<T>;
+    fn into_iter(self) -> Iter<T> { loop {} }
+}
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<&'a T>;
+    fn into_iter(self) -> Iter<&'a T> { loop {} }
+}
+impl<T> core::iter::FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self { loop {} }
+}
+"#;
+
+    #[test]
+    fn generate_for_record_struct() {
+        check_assist(
+            generate_into_iterator_impls,
+            &format!("//- minicore: iterator, from_iterator\n{LIST}struct Names {{ $0names: List<u32> }}"),
+            &format!(
+                "{LIST}struct Names {{ names: List<u32> }}
+
+impl IntoIterator for Names {{
+    type Item = <List<u32> as IntoIterator>::Item;
+    type IntoIter = <List<u32> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {{
+        self.names.into_iter()
+    }}
+}}
+
+impl<'a> IntoIterator for &'a Names {{
+    type Item = <&'a List<u32> as IntoIterator>::Item;
+    type IntoIter = <&'a List<u32> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {{
+        (&self.names).into_iter()
+    }}
+}}
+
+impl core::iter::FromIterator<<List<u32> as IntoIterator>::Item> for Names {{
+    fn from_iter<I: IntoIterator<Item = <List<u32> as IntoIterator>::Item>>(iter: I) -> Self {{
+        Self {{ names: iter.into_iter().collect() }}
+    }}
+}}"
+            ),
+        );
+    }
+
+    #[test]
+    fn generate_for_generic_tuple_struct() {
+        check_assist(
+            generate_into_iterator_impls,
+            &format!(
+                "//- minicore: iterator, from_iterator\n{LIST}struct Stack<T: Copy>$0(List<T>);"
+            ),
+            &format!(
+                "{LIST}struct Stack<T: Copy>(List<T>);
+
+impl<T: Copy> IntoIterator for Stack<T> {{
+    type Item = <List<T> as IntoIterator>::Item;
+    type IntoIter = <List<T> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {{
+        self.0.into_iter()
+    }}
+}}
+
+impl<'a, T: Copy> IntoIterator for &'a Stack<T> {{
+    type Item = <&'a List<T> as IntoIterator>::Item;
+    type IntoIter = <&'a List<T> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {{
+        (&self.0).into_iter()
+    }}
+}}
+
+impl<T: Copy> core::iter::FromIterator<<List<T> as IntoIterator>::Item> for Stack<T> {{
+    fn from_iter<I: IntoIterator<Item = <List<T> as IntoIterator>::Item>>(iter: I) -> Self {{
+        Self(iter.into_iter().collect())
+    }}
+}}"
+            ),
+        );
+    }
+
+    #[test]
+    fn skips_existing_impls() {
+        check_assist(
+            generate_into_iterator_impls,
+            &format!(
+                "//- minicore: iterator, from_iterator
+{LIST}struct Stack(List<u32>$0);
+impl IntoIterator for Stack {{
+    type Item = u32;
+    type IntoIter = Iter<u32>;
+    fn into_iter(self) -> Iter<u32> {{ loop {{}} }}
+}}
+impl<'a> IntoIterator for &'a Stack {{
+    type Item = &'a u32;
+    type IntoIter = Iter<&'a u32>;
+    fn into_iter(self) -> Iter<&'a u32> {{ loop {{}} }}
+}}"
+            ),
+            &format!(
+                "{LIST}struct Stack(List<u32>);
+
+impl core::iter::FromIterator<<List<u32> as IntoIterator>::Item> for Stack {{
+    fn from_iter<I: IntoIterator<Item = <List<u32> as IntoIterator>::Item>>(iter: I) -> Self {{
+        Self(iter.into_iter().collect())
+    }}
+}}
+impl IntoIterator for Stack {{
+    type Item = u32;
+    type IntoIter = Iter<u32>;
+    fn into_iter(self) -> Iter<u32> {{ loop {{}} }}
+}}
+impl<'a> IntoIterator for &'a Stack {{
+    type Item = &'a u32;
+    type IntoIter = Iter<&'a u32>;
+    fn into_iter(self) -> Iter<&'a u32> {{ loop {{}} }}
+}}"
+            ),
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_collection() {
+        check_assist_not_applicable(
+            generate_into_iterator_impls,
+            r#"
+//- minicore: iterator, from_iterator
+struct Id($0u32);
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_several_fields() {
+        check_assist_not_applicable(
+            generate_into_iterator_impls,
+            &format!(
+                "//- minicore: iterator, from_iterator\n{LIST}struct Names {{ $0names: List<u32>, len: usize }}"
+            ),
+        );
+    }
+}
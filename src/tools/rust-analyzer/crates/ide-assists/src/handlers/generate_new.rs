@@ -1,16 +1,27 @@
+use hir::Adt;
 use ide_db::{
-    imports::import_assets::item_for_path_search, use_trivial_constructor::use_trivial_constructor,
+    famous_defs::FamousDefs, imports::import_assets::item_for_path_search,
+    use_trivial_constructor::use_trivial_constructor,
 };
+use stdx::format_to;
 use syntax::{
-    ast::{self, edit_in_place::Indent, make, AstNode, HasName, HasVisibility, StructKind},
+    ast::{
+        self, edit_in_place::Indent, make, AstNode, HasGenericParams, HasName, HasVisibility,
+        StructKind,
+    },
     ted,
 };
 
 use crate::{
-    utils::{find_struct_impl, generate_impl},
+    utils::{add_method_to_adt, find_struct_impl, generate_impl},
     AssistContext, AssistId, AssistKind, Assists,
 };
 
+/// A builder is offered for structs with at least this many fields, ...
+const BUILDER_MIN_FIELDS: usize = 5;
+/// ... or with at least this many `Option` fields.
+const BUILDER_MIN_OPTIONAL_FIELDS: usize = 2;
+
 // Assist: generate_new
 //
 // Adds a `fn new` for a type.
@@ -147,6 +158,178 @@ pub(crate) fn generate_new(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option
     })
 }
 
+// Assist: generate_builder
+//
+// Adds a builder for a struct with many fields, or several optional ones: a `FooBuilder` with a
+// setter per field and a `build` method, and a `Foo::builder()` to start from.
+//
+// ```
+// //- minicore: option
+// struct Person {$0
+//     name: String,
+//     nickname: Option<String>,
+//     email: Option<String>,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+//     nickname: Option<String>,
+//     email: Option<String>,
+// }
+//
+// impl Person {
+//     fn builder() -> PersonBuilder {
+//         PersonBuilder::default()
+//     }
+// }
+//
+// #[derive(Default)]
+// struct PersonBuilder {
+//     name: Option<String>,
+//     nickname: Option<String>,
+//     email: Option<String>,
+// }
+//
+// impl PersonBuilder {
+//     fn name(mut self, name: String) -> Self {
+//         self.name = Some(name);
+//         self
+//     }
+//
+//     fn nickname(mut self, nickname: String) -> Self {
+//         self.nickname = Some(nickname);
+//         self
+//     }
+//
+//     fn email(mut self, email: String) -> Self {
+//         self.email = Some(email);
+//         self
+//     }
+//
+//     /// Returns `None` if a required field isn't set.
+//     fn build(self) -> Option<Person> {
+//         Some(Person {
+//             name: self.name?,
+//             nickname: self.nickname,
+//             email: self.email,
+//         })
+//     }
+// }
+// ```
+pub(crate) fn generate_builder(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let field_list = match strukt.kind() {
+        StructKind::Record(named) => named,
+        _ => return None,
+    };
+    // `#[derive(Default)]` on the builder would require all type parameters to be `Default`.
+    if strukt.generic_param_list().is_some() {
+        cov_mark::hit!(builder_for_generic_struct);
+        return None;
+    }
+
+    let adt = ast::Adt::Struct(strukt.clone());
+    let impl_def = find_struct_impl(ctx, &adt, &[String::from("builder")])?;
+
+    let krate = ctx.sema.scope(strukt.syntax())?.krate();
+    let option = FamousDefs(&ctx.sema, krate).core_option_Option();
+    // Name, the type set by the setter, and whether the field is optional.
+    let fields = field_list
+        .fields()
+        .map(|field| {
+            let name = field.name()?;
+            let ty = field.ty()?;
+            let is_option = option.is_some()
+                && ctx.sema.resolve_type(&ty).and_then(|it| it.as_adt()) == option.map(Adt::Enum);
+            match option_inner_ty(&ty).filter(|_| is_option) {
+                Some(inner) => Some((name, inner, true)),
+                None => Some((name, ty, false)),
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let optional_fields = fields.iter().filter(|(_, _, is_optional)| *is_optional).count();
+    if fields.len() < BUILDER_MIN_FIELDS && optional_fields < BUILDER_MIN_OPTIONAL_FIELDS {
+        return None;
+    }
+
+    let strukt_name = strukt.name()?;
+    let builder_name = format!("{strukt_name}Builder");
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId("generate_builder", AssistKind::Generate),
+        "Generate builder",
+        target,
+        |builder| {
+            let vis = strukt.visibility().map_or(String::new(), |v| format!("{v} "));
+
+            let method = format!(
+                "    {vis}fn builder() -> {builder_name} {{
+        {builder_name}::default()
+    }}"
+            );
+
+            let mut buf = format!("\n\n#[derive(Default)]\n{vis}struct {builder_name} {{\n");
+            for (name, ty, _) in &fields {
+                format_to!(buf, "    {name}: Option<{ty}>,\n");
+            }
+            format_to!(buf, "}}\n\nimpl {builder_name} {{\n");
+            for (name, ty, _) in &fields {
+                format_to!(
+                    buf,
+                    "    {vis}fn {name}(mut self, {name}: {ty}) -> Self {{
+        self.{name} = Some({name});
+        self
+    }}\n\n"
+                );
+            }
+
+            let has_required_fields = optional_fields < fields.len();
+            if has_required_fields {
+                format_to!(
+                    buf,
+                    "    /// Returns `None` if a required field isn't set.
+    {vis}fn build(self) -> Option<{strukt_name}> {{
+        Some({strukt_name} {{\n"
+                );
+            } else {
+                format_to!(
+                    buf,
+                    "    {vis}fn build(self) -> {strukt_name} {{
+        {strukt_name} {{\n"
+                );
+            }
+            for (name, _, is_optional) in &fields {
+                let unwrap = if *is_optional { "" } else { "?" };
+                format_to!(buf, "            {name}: self.{name}{unwrap},\n");
+            }
+            if has_required_fields {
+                buf.push_str("        })\n    }\n}");
+            } else {
+                buf.push_str("        }\n    }\n}");
+            }
+
+            let offset = match &impl_def {
+                Some(impl_def) => impl_def.syntax().text_range().end(),
+                None => strukt.syntax().text_range().end(),
+            };
+            add_method_to_adt(builder, &adt, impl_def, &method);
+            builder.insert(offset, buf);
+        },
+    )
+}
+
+/// The `T` of a field type written as `Option<T>`.
+fn option_inner_ty(ty: &ast::Type) -> Option<ast::Type> {
+    let ast::Type::PathType(path_ty) = ty else { return None };
+    let generic_args = path_ty.path()?.segment()?.generic_arg_list()?;
+    match generic_args.generic_args().next()? {
+        ast::GenericArg::TypeArg(arg) => arg.ty(),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_not_applicable, check_assist_target};
@@ -553,6 +736,185 @@ pub fn map<F: FnOnce(T) -> U, U>(self, f: F) -> Source<U> {
         Source { file_id: self.file_id, ast: f(self.ast) }
     }
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_builder_into_existing_impl() {
+        check_assist(
+            generate_builder,
+            r#"
+//- minicore: option
+pub struct Opts {$0
+    verbose: Option<bool>,
+    jobs: Option<u32>,
+}
+
+impl Opts {
+    fn f() {}
+}
+"#,
+            r#"
+pub struct Opts {
+    verbose: Option<bool>,
+    jobs: Option<u32>,
+}
+
+impl Opts {
+    fn f() {}
+
+    pub fn builder() -> OptsBuilder {
+        OptsBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct OptsBuilder {
+    verbose: Option<bool>,
+    jobs: Option<u32>,
+}
+
+impl OptsBuilder {
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = Some(verbose);
+        self
+    }
+
+    pub fn jobs(mut self, jobs: u32) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
+    pub fn build(self) -> Opts {
+        Opts {
+            verbose: self.verbose,
+            jobs: self.jobs,
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_builder_for_many_fields() {
+        check_assist(
+            generate_builder,
+            r#"
+struct Rect {$0
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    label: String,
+}
+"#,
+            r#"
+struct Rect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    label: String,
+}
+
+impl Rect {
+    fn builder() -> RectBuilder {
+        RectBuilder::default()
+    }
+}
+
+#[derive(Default)]
+struct RectBuilder {
+    x: Option<i32>,
+    y: Option<i32>,
+    width: Option<u32>,
+    height: Option<u32>,
+    label: Option<String>,
+}
+
+impl RectBuilder {
+    fn x(mut self, x: i32) -> Self {
+        self.x = Some(x);
+        self
+    }
+
+    fn y(mut self, y: i32) -> Self {
+        self.y = Some(y);
+        self
+    }
+
+    fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    fn label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Returns `None` if a required field isn't set.
+    fn build(self) -> Option<Rect> {
+        Some(Rect {
+            x: self.x?,
+            y: self.y?,
+            width: self.width?,
+            height: self.height?,
+            label: self.label?,
+        })
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_builder_not_applicable() {
+        // Too few fields, and only one of them optional.
+        check_assist_not_applicable(
+            generate_builder,
+            r#"
+//- minicore: option
+struct Foo {$0
+    a: u8,
+    b: Option<u8>,
+}
+"#,
+        );
+        check_assist_not_applicable(
+            generate_builder,
+            r#"
+//- minicore: option
+struct Foo {$0
+    a: Option<u8>,
+    b: Option<u8>,
+}
+
+impl Foo {
+    fn builder() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generate_builder_for_generic_struct() {
+        cov_mark::check!(builder_for_generic_struct);
+        check_assist_not_applicable(
+            generate_builder,
+            r#"
+//- minicore: option
+struct Foo<T> {$0
+    a: Option<T>,
+    b: Option<T>,
+}
 "#,
         );
     }
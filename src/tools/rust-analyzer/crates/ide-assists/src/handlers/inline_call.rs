@@ -2,7 +2,7 @@
 
 use ast::make;
 use either::Either;
-use hir::{db::HirDatabase, PathResolution, Semantics, TypeInfo};
+use hir::{db::HirDatabase, AsAssocItem, HasVisibility, PathResolution, Semantics, TypeInfo};
 use ide_db::{
     base_db::{FileId, FileRange},
     defs::Definition,
@@ -201,6 +201,12 @@ pub(crate) fn inline_call(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<
             (ctx.sema.resolve_method_call(call)?, format!("Inline `{name_ref}`"))
         }
     };
+    // Path calls like `Trait::method(&value)` resolve to the trait's method, inline the
+    // implementation instead if the receiver has the concrete type of its only impl.
+    let (function, label) = match sole_impl_method(ctx, function, &call_info) {
+        Some(method) => (method, format!("{label} from its only impl")),
+        None => (function, label),
+    };
 
     let fn_source = ctx.sema.source(function)?;
     let fn_body = fn_source.value.body()?;
@@ -221,6 +227,55 @@ pub(crate) fn inline_call(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<
     }
 
     let syntax = call_info.node.syntax().clone();
+    if file_id != ctx.file_id() && !fn_source.file_id.is_macro() && is_removable(ctx, function) {
+        acc.add(
+            AssistId("inline_call", AssistKind::RefactorInline),
+            format!("{label} and remove its definition if unused"),
+            syntax.text_range(),
+            |builder| {
+                let replacement =
+                    inline(&ctx.sema, file_id, function, &fn_body, &params, &call_info);
+                let mut usages = Definition::Function(function).usages(&ctx.sema).all();
+                let calls = usages
+                    .iter()
+                    .flat_map(|(_, refs)| refs)
+                    .filter(|it| match &it.name {
+                        FileReferenceNode::NameRef(name_ref) => !name_ref
+                            .syntax()
+                            .ancestors()
+                            .any(|it| ast::UseTree::can_cast(it.kind())),
+                        _ => true,
+                    })
+                    .count();
+                let call = builder.make_syntax_mut(syntax.clone());
+                // Unless this is the last call, the definition in the other file is still needed.
+                if calls != 1 {
+                    ted::replace(call, replacement.syntax());
+                    return;
+                }
+
+                let current_file_refs =
+                    usages.references.remove(&ctx.file_id()).unwrap_or_default();
+                let (_, uses) =
+                    split_refs_and_uses(builder, current_file_refs, |_| None::<ast::NameRef>);
+                ted::replace(call, replacement.syntax());
+                uses.iter().for_each(remove_path_if_in_use_stmt);
+
+                for (ref_file_id, refs) in usages {
+                    builder.edit_file(ref_file_id);
+                    let (_, uses) = split_refs_and_uses(builder, refs, |_| None::<ast::NameRef>);
+                    uses.iter().for_each(remove_path_if_in_use_stmt);
+                }
+                builder.edit_file(file_id);
+                let whitespace = fn_source
+                    .value
+                    .syntax()
+                    .next_sibling_or_token()
+                    .filter(|it| it.kind() == SyntaxKind::WHITESPACE);
+                builder.delete(whitespace.map_or(range, |it| range.cover(it.text_range())));
+            },
+        );
+    }
     acc.add(
         AssistId("inline_call", AssistKind::RefactorInline),
         label,
@@ -238,6 +293,55 @@ pub(crate) fn inline_call(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<
     )
 }
 
+/// Whether the definition of `function` can be deleted once it isn't called anymore: it's only
+/// visible within the crate and isn't needed to implement a trait.
+fn is_removable(ctx: &AssistContext<'_>, function: hir::Function) -> bool {
+    let db = ctx.db();
+    if function.visibility(db) == hir::Visibility::Public {
+        return false;
+    }
+    match function.as_assoc_item(db) {
+        Some(item) => {
+            matches!(item.container(db), hir::AssocItemContainer::Impl(imp) if imp.trait_(db).is_none())
+        }
+        None => true,
+    }
+}
+
+/// The method implementing the trait method `function` in the only impl of the trait, if there's
+/// exactly one, it doesn't have type parameters of its own and the receiver of the call is its
+/// self type. Receivers of other types, like type parameters and trait objects, can be dispatched
+/// to impls this crate doesn't see, so they keep the trait's method.
+fn sole_impl_method(
+    ctx: &AssistContext<'_>,
+    function: hir::Function,
+    call_info: &CallInfo,
+) -> Option<hir::Function> {
+    let db = ctx.db();
+    let hir::AssocItemContainer::Trait(trait_) = function.as_assoc_item(db)?.container(db) else {
+        return None;
+    };
+    if !function.has_self_param(db) {
+        return None;
+    }
+    let [impl_] = hir::Impl::all_for_trait(db, trait_)[..] else {
+        return None;
+    };
+    if !hir::GenericDef::from(impl_).type_or_const_params(db).is_empty() {
+        return None;
+    }
+    let receiver_ty =
+        ctx.sema.type_of_expr(call_info.arguments.first()?)?.original.strip_references();
+    if receiver_ty.contains_unknown() || !impl_.self_ty(db).could_unify_with(db, &receiver_ty) {
+        return None;
+    }
+    let name = function.name(db);
+    impl_.items(db).into_iter().find_map(|item| match item {
+        hir::AssocItem::Function(method) if method.name(db) == name => Some(method),
+        _ => None,
+    })
+}
+
 struct CallInfo {
     node: ast::CallableExpr,
     arguments: Vec<ast::Expr>,
@@ -574,7 +678,10 @@ fn path_expr_as_record_field(usage: &PathExpr) -> Option<ast::RecordExprField> {
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable};
+    use crate::tests::{
+        check_assist, check_assist_by_label, check_assist_not_applicable,
+        check_assist_not_applicable_by_label,
+    };
 
     use super::*;
 
@@ -1811,4 +1918,195 @@ fn f() {
 "#,
         );
     }
+
+    #[test]
+    fn trait_method_path_call_with_sole_impl() {
+        check_assist(
+            inline_call,
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+struct Square(u32);
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+fn area(square: &Square) -> u32 {
+    Shape::area$0(square)
+}
+"#,
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+struct Square(u32);
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+fn area(square: &Square) -> u32 {
+    {
+        let this = &square;
+        this.0 * this.0
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_method_on_type_param_with_sole_impl() {
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+struct Square(u32);
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+fn area<T: Shape>(shape: &T) -> u32 {
+    shape.area$0()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_object_method_with_sole_impl() {
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+trait Shape {
+    fn scaled(&self, factor: u32) -> u32;
+}
+struct Square(u32);
+impl Shape for Square {
+    fn scaled(&self, factor: u32) -> u32 {
+        self.0 * factor
+    }
+}
+fn area(shape: &dyn Shape) -> u32 {
+    shape.scaled$0(2)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_method_with_several_impls() {
+        check_assist_not_applicable(
+            inline_call,
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+struct Square(u32);
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+struct Line;
+impl Shape for Line {
+    fn area(&self) -> u32 {
+        0
+    }
+}
+fn area<T: Shape>(shape: &T) -> u32 {
+    shape.area$0()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_last_call_removes_definition_in_other_file() {
+        check_assist_by_label(
+            inline_call,
+            r#"
+//- /lib.rs
+mod foo;
+fn do_the_math(b: u32) -> u32 {
+    let foo = 10;
+    foo * b + foo
+}
+fn bar() {}
+//- /foo.rs
+use super::do_the_math;
+fn foo() {
+    do_the_math$0(0);
+}
+"#,
+            r#"
+//- /lib.rs
+mod foo;
+fn bar() {}
+//- /foo.rs
+fn foo() {
+    {
+        let foo = 10;
+        foo * 0 + foo
+    };
+}
+"#,
+            "Inline `do_the_math` and remove its definition if unused",
+        );
+    }
+
+    #[test]
+    fn inline_keeps_definition_with_other_calls() {
+        check_assist_by_label(
+            inline_call,
+            r#"
+//- /lib.rs
+mod foo;
+fn do_the_math(b: u32) -> u32 {
+    let foo = 10;
+    foo * b + foo
+}
+fn bar() {
+    do_the_math(1);
+}
+//- /foo.rs
+use super::do_the_math;
+fn foo() {
+    do_the_math$0(0);
+}
+"#,
+            r#"
+use super::do_the_math;
+fn foo() {
+    {
+        let foo = 10;
+        foo * 0 + foo
+    };
+}
+"#,
+            "Inline `do_the_math` and remove its definition if unused",
+        );
+        check_assist_not_applicable_by_label(
+            inline_call,
+            r#"
+//- /lib.rs
+mod foo;
+pub fn do_the_math(b: u32) -> u32 {
+    let foo = 10;
+    foo * b + foo
+}
+//- /foo.rs
+use super::do_the_math;
+fn foo() {
+    do_the_math$0(0);
+}
+"#,
+            "Inline `do_the_math` and remove its definition if unused",
+        );
+    }
 }
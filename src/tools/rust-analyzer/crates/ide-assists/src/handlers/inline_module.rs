@@ -0,0 +1,255 @@
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        HasAttrs, HasName,
+    },
+    ted, AstNode,
+};
+
+use crate::{
+    utils::{make_path_attr, path_attr_value, relative_path_attrs},
+    AssistContext, AssistId, AssistKind, Assists,
+};
+
+// Assist: inline_module
+//
+// Moves the contents of a module's file into an inline module and deletes the file.
+//
+// ```
+// //- /main.rs
+// mod $0foo;
+// //- /foo.rs
+// fn t() {}
+// ```
+// ->
+// ```
+// mod foo {
+//     fn t() {}
+// }
+// ```
+pub(crate) fn inline_module(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let module_ast = ctx.find_node_at_offset::<ast::Module>()?;
+    if module_ast.item_list().is_some() {
+        return None;
+    }
+    let semicolon = module_ast.semicolon_token()?;
+    let module_name = module_ast.name()?;
+    let module = ctx.sema.to_def(&module_ast)?;
+    let module_file = module.as_source_file_id(ctx.db())?;
+    let path_attr = module_ast.attrs().find(|attr| attr.simple_name().as_deref() == Some("path"));
+
+    // The contents of the file stay relative to the same directory: a `#[path]` attribute of the
+    // declaration now names the directory of the file, which nested `mod` declarations resolve
+    // from. Without one, they already resolve from the directory named after the module, except
+    // for the `#[path]` attributes at the top level of a `foo.rs` file.
+    let new_path_attr = match &path_attr {
+        Some(attr) => {
+            let path = path_attr_value(attr)?;
+            let dir = match path.rsplit_once('/') {
+                Some((dir, _)) if !dir.is_empty() => dir,
+                _ => ".",
+            };
+            Some(make_path_attr(dir))
+        }
+        None => None,
+    };
+    let nested_prefix = (path_attr.is_none() && !module.is_mod_rs(ctx.db())).then_some("../");
+
+    let target = module_ast.syntax().text_range();
+    acc.add(
+        AssistId("inline_module", AssistKind::RefactorInline),
+        format!("Inline module `{module_name}`"),
+        target,
+        |builder| {
+            let source_file = ctx.sema.parse(module_file).clone_for_update();
+            if let Some(prefix) = nested_prefix {
+                for (attr, path) in relative_path_attrs(&source_file) {
+                    let new_attr = make_path_attr(&format!("{prefix}{path}"));
+                    ted::replace(attr.syntax(), new_attr.syntax());
+                }
+            }
+
+            let indent = IndentLevel::from_node(module_ast.syntax());
+            let contents = source_file.indent(indent + 1).to_string();
+            let contents = contents.trim();
+            let body = if contents.is_empty() {
+                " {}".to_owned()
+            } else {
+                format!(" {{\n{}{contents}\n{indent}}}", indent + 1)
+            };
+
+            if let Some(path_attr) = path_attr {
+                match new_path_attr {
+                    Some(new_path_attr) => {
+                        builder.replace(path_attr.syntax().text_range(), new_path_attr.to_string())
+                    }
+                    None => builder.delete(path_attr.syntax().text_range()),
+                }
+            }
+            builder.replace(semicolon.text_range(), body);
+            builder.delete_file(module_file);
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn inline_from_root() {
+        check_assist(
+            inline_module,
+            r#"
+//- /main.rs cfg:test
+#[cfg(test)]
+mod $0tests;
+//- /tests.rs
+use super::*;
+
+#[test]
+fn t() {
+    let s = "
+multi-line";
+}
+"#,
+            r#"
+//- /main.rs
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t() {
+        let s = "
+multi-line";
+    }
+}
+//- /tests.rs (deleted)
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_into_nested_module() {
+        check_assist(
+            inline_module,
+            r#"
+//- /main.rs
+mod a {
+    pub mod $0b;
+}
+//- /a/b.rs
+//! Docs.
+fn f() {}
+"#,
+            r#"
+//- /main.rs
+mod a {
+    pub mod b {
+        //! Docs.
+        fn f() {}
+    }
+}
+//- /a/b.rs (deleted)
+"#,
+        );
+    }
+
+    #[test]
+    fn inline_empty_file() {
+        check_assist(
+            inline_module,
+            r#"
+//- /main.rs
+mod $0foo;
+//- /foo/mod.rs
+"#,
+            r#"
+//- /main.rs
+mod foo {}
+//- /foo/mod.rs (deleted)
+"#,
+        );
+    }
+
+    #[test]
+    fn nested_path_attrs_of_non_mod_rs_file() {
+        check_assist(
+            inline_module,
+            r#"
+//- /main.rs
+mod $0foo;
+//- /foo.rs
+mod bar;
+#[path = "other/baz.rs"]
+mod baz;
+//- /foo/bar.rs
+//- /other/baz.rs
+"#,
+            r#"
+//- /main.rs
+mod foo {
+    mod bar;
+    #[path = "../other/baz.rs"]
+    mod baz;
+}
+//- /foo.rs (deleted)
+"#,
+        );
+    }
+
+    #[test]
+    fn declaration_with_path_attr() {
+        check_assist(
+            inline_module,
+            r#"
+//- /main.rs
+#[path = "dir/imp.rs"]
+mod $0foo;
+//- /dir/imp.rs
+mod bar;
+//- /dir/bar.rs
+"#,
+            r#"
+//- /main.rs
+#[path = "dir"]
+mod foo {
+    mod bar;
+}
+//- /dir/imp.rs (deleted)
+"#,
+        );
+        check_assist(
+            inline_module,
+            r#"
+//- /main.rs
+#[path = "imp.rs"]
+mod $0foo;
+//- /imp.rs
+fn f() {}
+"#,
+            r#"
+//- /main.rs
+#[path = "."]
+mod foo {
+    fn f() {}
+}
+//- /imp.rs (deleted)
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_inline_module() {
+        check_assist_not_applicable(inline_module, r#"mod $0foo { fn f() {} }"#);
+    }
+
+    #[test]
+    fn not_applicable_to_unresolved_module() {
+        check_assist_not_applicable(inline_module, r#"mod $0foo;"#);
+    }
+}
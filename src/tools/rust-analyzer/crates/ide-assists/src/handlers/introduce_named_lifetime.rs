@@ -1,8 +1,11 @@
-use ide_db::FxHashSet;
+use hir::{AsAssocItem, AssocItem};
+use ide_db::{base_db::FileId, FxHashSet};
+use itertools::Itertools;
 use syntax::{
     ast::{self, edit_in_place::GenericParamsOwnerEdit, make, HasGenericParams},
+    match_ast,
     ted::{self, Position},
-    AstNode, TextRange,
+    AstNode, SyntaxNode, TextRange,
 };
 
 use crate::{assist_context::SourceChangeBuilder, AssistContext, AssistId, AssistKind, Assists};
@@ -41,7 +44,7 @@ pub(crate) fn introduce_named_lifetime(acc: &mut Assists, ctx: &AssistContext<'_
     let lifetime_loc = lifetime.lifetime_ident_token()?.text_range();
 
     if let Some(fn_def) = lifetime.syntax().ancestors().find_map(ast::Fn::cast) {
-        generate_fn_def_assist(acc, fn_def, lifetime_loc, lifetime)
+        generate_fn_def_assist(acc, ctx, fn_def, lifetime_loc, lifetime)
     } else if let Some(impl_def) = lifetime.syntax().ancestors().find_map(ast::Impl::cast) {
         generate_impl_def_assist(acc, impl_def, lifetime_loc, lifetime)
     } else {
@@ -52,6 +55,7 @@ pub(crate) fn introduce_named_lifetime(acc: &mut Assists, ctx: &AssistContext<'_
 /// Generate the assist for the fn def case
 fn generate_fn_def_assist(
     acc: &mut Assists,
+    ctx: &AssistContext<'_>,
     fn_def: ast::Fn,
     lifetime_loc: TextRange,
     lifetime: ast::Lifetime,
@@ -83,22 +87,116 @@ fn generate_fn_def_assist(
             _ => return None,
         }
     };
+    let sites = std::iter::once(ElidedLifetime::Anonymous(lifetime))
+        .chain(loc_needing_lifetime.map(ElidedLifetime::Implicit))
+        .collect();
+    let fn_edit = FnEdit { fn_def, lifetime_param: new_lifetime_param, sites };
+    let related_edits = related_fn_edits(ctx, &fn_edit);
+
     acc.add(AssistId(ASSIST_NAME, AssistKind::Refactor), ASSIST_LABEL, lifetime_loc, |builder| {
-        let fn_def = builder.make_mut(fn_def);
-        let lifetime = builder.make_mut(lifetime);
-        let loc_needing_lifetime =
-            loc_needing_lifetime.and_then(|it| it.make_mut(builder).to_position());
+        let (same_file, mut other_files): (Vec<_>, Vec<_>) =
+            related_edits.into_iter().partition(|(file_id, _)| *file_id == ctx.file_id());
+        let edits_mut: Vec<_> = std::iter::once(fn_edit)
+            .chain(same_file.into_iter().map(|(_, it)| it))
+            .map(|it| it.make_mut(builder))
+            .collect();
+        edits_mut.into_iter().for_each(FnEdit::apply);
 
-        fn_def.get_or_create_generic_param_list().add_generic_param(
-            make::lifetime_param(new_lifetime_param.clone()).clone_for_update().into(),
-        );
-        ted::replace(lifetime.syntax(), new_lifetime_param.clone_for_update().syntax());
-        if let Some(position) = loc_needing_lifetime {
-            ted::insert(position, new_lifetime_param.clone_for_update().syntax());
+        other_files.sort_by_key(|(file_id, _)| *file_id);
+        for (file_id, edits) in &other_files.into_iter().group_by(|(file_id, _)| *file_id) {
+            builder.edit_file(file_id);
+            let edits_mut: Vec<_> = edits.map(|(_, it)| it.make_mut(builder)).collect();
+            edits_mut.into_iter().for_each(FnEdit::apply);
         }
     })
 }
 
+/// For a trait method, returns the edits naming the same lifetime in the trait declaration and in
+/// the other implementations, so that their signatures keep matching. Functions whose signature
+/// elides lifetimes in different places are skipped.
+fn related_fn_edits(ctx: &AssistContext<'_>, fn_edit: &FnEdit) -> Vec<(FileId, FnEdit)> {
+    let db = ctx.db();
+    let Some(func) = ctx.sema.to_def(&fn_edit.fn_def) else { return Vec::new() };
+    let Some(trait_) = func.as_assoc_item(db).and_then(|it| it.container_or_implemented_trait(db))
+    else {
+        return Vec::new();
+    };
+    if !trait_.module(db).krate().origin(db).is_local() {
+        return Vec::new();
+    }
+
+    let elided = elided_lifetimes(&fn_edit.fn_def);
+    let indices: Vec<usize> = fn_edit
+        .sites
+        .iter()
+        .filter_map(|site| elided.iter().position(|it| it.syntax() == site.syntax()))
+        .collect();
+    if indices.len() != fn_edit.sites.len() {
+        return Vec::new();
+    }
+
+    let name = func.name(db);
+    let find_fn = |items: Vec<AssocItem>| {
+        items.into_iter().find_map(|item| match item {
+            AssocItem::Function(it) if it.name(db) == name => Some(it),
+            _ => None,
+        })
+    };
+    let related_fns = find_fn(trait_.items(db)).into_iter().chain(
+        hir::Impl::all_for_trait(db, trait_).into_iter().filter_map(|it| find_fn(it.items(db))),
+    );
+
+    let mut edits = Vec::new();
+    for related_fn in related_fns.filter(|&it| it != func) {
+        let Some(source) = ctx.sema.source(related_fn) else { continue };
+        let Some(file_id) = source.file_id.file_id() else { continue };
+        let fn_def = source.value;
+        let related_elided = elided_lifetimes(&fn_def);
+        if related_elided.len() != elided.len() {
+            cov_mark::hit!(skip_related_fn_with_other_elided_lifetimes);
+            continue;
+        }
+        let Some(lifetime_param) = generate_unique_lifetime_param_name(fn_def.generic_param_list())
+        else {
+            continue;
+        };
+        let sites = related_elided
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| indices.contains(idx))
+            .map(|(_, it)| it)
+            .collect();
+        edits.push((file_id, FnEdit { fn_def, lifetime_param, sites }));
+    }
+    edits
+}
+
+/// Returns the places of the signature of `fn_def` whose lifetime is elided, in source order.
+fn elided_lifetimes(fn_def: &ast::Fn) -> Vec<ElidedLifetime> {
+    let signature = fn_def
+        .param_list()
+        .map(|it| it.syntax().clone())
+        .into_iter()
+        .chain(fn_def.ret_type().map(|it| it.syntax().clone()));
+    signature
+        .flat_map(|node| node.descendants())
+        .filter_map(|node| {
+            match_ast! {
+                match node {
+                    ast::Lifetime(it) => (it.text() == "'_").then_some(ElidedLifetime::Anonymous(it)),
+                    ast::SelfParam(it) => (it.amp_token().is_some() && it.lifetime().is_none())
+                        .then_some(ElidedLifetime::Implicit(NeedsLifetime::SelfParam(it))),
+                    ast::RefType(it) => it
+                        .lifetime()
+                        .is_none()
+                        .then_some(ElidedLifetime::Implicit(NeedsLifetime::RefType(it))),
+                    _ => None,
+                }
+            }
+        })
+        .collect()
+}
+
 /// Generate the assist for the impl def case
 fn generate_impl_def_assist(
     acc: &mut Assists,
@@ -134,6 +232,63 @@ fn generate_unique_lifetime_param_name(
     .map(|it| make::lifetime(&it))
 }
 
+/// Introduces `lifetime_param` in `fn_def` and uses it at all `sites`.
+struct FnEdit {
+    fn_def: ast::Fn,
+    lifetime_param: ast::Lifetime,
+    sites: Vec<ElidedLifetime>,
+}
+
+impl FnEdit {
+    fn make_mut(self, builder: &mut SourceChangeBuilder) -> Self {
+        FnEdit {
+            fn_def: builder.make_mut(self.fn_def),
+            lifetime_param: self.lifetime_param,
+            sites: self.sites.into_iter().map(|it| it.make_mut(builder)).collect(),
+        }
+    }
+
+    fn apply(self) {
+        self.fn_def.get_or_create_generic_param_list().add_generic_param(
+            make::lifetime_param(self.lifetime_param.clone()).clone_for_update().into(),
+        );
+        for site in self.sites {
+            let lifetime = self.lifetime_param.clone_for_update();
+            match site {
+                ElidedLifetime::Anonymous(it) => ted::replace(it.syntax(), lifetime.syntax()),
+                ElidedLifetime::Implicit(it) => {
+                    if let Some(position) = it.to_position() {
+                        ted::insert(position, lifetime.syntax());
+                    }
+                }
+            }
+        }
+    }
+}
+
+enum ElidedLifetime {
+    /// An explicit `'_`.
+    Anonymous(ast::Lifetime),
+    Implicit(NeedsLifetime),
+}
+
+impl ElidedLifetime {
+    fn make_mut(self, builder: &mut SourceChangeBuilder) -> Self {
+        match self {
+            Self::Anonymous(it) => Self::Anonymous(builder.make_mut(it)),
+            Self::Implicit(it) => Self::Implicit(it.make_mut(builder)),
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Self::Anonymous(it) => it.syntax(),
+            Self::Implicit(NeedsLifetime::SelfParam(it)) => it.syntax(),
+            Self::Implicit(NeedsLifetime::RefType(it)) => it.syntax(),
+        }
+    }
+}
+
 enum NeedsLifetime {
     SelfParam(ast::SelfParam),
     RefType(ast::RefType),
@@ -335,4 +490,98 @@ fn test_function_add_lifetime_to_self_ref_mut() {
             r#"fn foo<'a>(&'a mut self) -> &'a ()"#,
         );
     }
+
+    #[test]
+    fn test_function_propagates_to_trait_impls() {
+        check_assist(
+            introduce_named_lifetime,
+            r#"
+trait Get {
+    fn get(&self) -> &'_$0 str;
+}
+struct S;
+impl Get for S {
+    fn get(&self) -> &str {
+        ""
+    }
+}
+"#,
+            r#"
+trait Get {
+    fn get<'a>(&'a self) -> &'a str;
+}
+struct S;
+impl Get for S {
+    fn get<'a>(&'a self) -> &'a str {
+        ""
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_function_propagates_to_trait_in_other_file() {
+        check_assist(
+            introduce_named_lifetime,
+            r#"
+//- /main.rs
+mod tr;
+struct S;
+impl tr::Tr for S {
+    fn f(&self, x: &u8) -> &'_$0 u8 {
+        x
+    }
+}
+//- /tr.rs
+pub trait Tr {
+    fn f(&self, x: &u8) -> &u8;
+}
+"#,
+            r#"
+//- /main.rs
+mod tr;
+struct S;
+impl tr::Tr for S {
+    fn f<'a>(&'a self, x: &u8) -> &'a u8 {
+        x
+    }
+}
+//- /tr.rs
+pub trait Tr {
+    fn f<'a>(&'a self, x: &u8) -> &'a u8;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_function_skips_impl_with_other_elided_lifetimes() {
+        cov_mark::check!(skip_related_fn_with_other_elided_lifetimes);
+        check_assist(
+            introduce_named_lifetime,
+            r#"
+struct Wrap<'a>(&'a str);
+trait Get {
+    fn get(&self) -> Wrap<'_$0>;
+}
+impl Get for () {
+    fn get(&self) -> Wrap {
+        Wrap("")
+    }
+}
+"#,
+            r#"
+struct Wrap<'a>(&'a str);
+trait Get {
+    fn get<'a>(&'a self) -> Wrap<'a>;
+}
+impl Get for () {
+    fn get(&self) -> Wrap {
+        Wrap("")
+    }
+}
+"#,
+        );
+    }
 }
@@ -1,13 +1,20 @@
 use either::Either;
-use ide_db::imports::{
-    insert_use::{ImportGranularity, InsertUseConfig},
-    merge_imports::{try_merge_imports, try_merge_trees, try_normalize_use_tree, MergeBehavior},
+use ide_db::{
+    imports::{
+        insert_use::{ImportGranularity, ImportGroup, InsertUseConfig},
+        merge_imports::{
+            try_merge_imports, try_merge_trees, try_normalize_import, try_normalize_use_tree,
+            use_tree_cmp, MergeBehavior,
+        },
+    },
+    FxHashSet,
 };
 use itertools::Itertools;
+use stdx::format_to;
 use syntax::{
     algo::neighbor,
-    ast::{self, edit_in_place::Removable},
-    match_ast, ted, AstNode, SyntaxElement, SyntaxNode,
+    ast::{self, edit::IndentLevel, edit_in_place::Removable, make, HasAttrs, HasVisibility},
+    match_ast, ted, AstNode, SyntaxElement, SyntaxKind, SyntaxNode, TextRange,
 };
 
 use crate::{
@@ -119,6 +126,166 @@ pub(crate) fn merge_imports(acc: &mut Assists, ctx: &AssistContext<'_>) -> Optio
     )
 }
 
+// Assist: merge_all_imports
+//
+// Merges all imports of the file as far as the configured granularity allows, splitting up the
+// ones that are nested deeper than it. The imports are then sorted and duplicates are removed.
+//
+// ```
+// use std::fmt::Display;
+// use std::$0io;
+// use std::fmt::Display;
+// use std::fmt::Debug;
+// ```
+// ->
+// ```
+// use std::{fmt::{Debug, Display}, io};
+// ```
+pub(crate) fn merge_all_imports(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    if !ctx.has_empty_selection() {
+        return None;
+    }
+    let use_item = ctx.find_node_at_offset::<ast::Use>()?;
+
+    let edits: Vec<_> = ctx
+        .sema
+        .parse(ctx.file_id())
+        .syntax()
+        .descendants()
+        .filter(|it| matches!(it.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST))
+        .filter_map(|container| normalize_imports(&container, &ctx.config.insert_use))
+        .collect();
+    if edits.is_empty() {
+        cov_mark::hit!(imports_already_normalized);
+        return None;
+    }
+
+    acc.add(
+        AssistId("merge_all_imports", AssistKind::RefactorRewrite),
+        "Merge all imports in the file",
+        use_item.syntax().text_range(),
+        |builder| {
+            for (uses, text) in edits {
+                builder.replace(uses[0].syntax().text_range(), text);
+                for use_item in &uses[1..] {
+                    builder.delete(range_with_leading_ws(use_item));
+                }
+            }
+        },
+    )
+}
+
+/// Computes the normalized imports of the `use` items directly inside `container`, returning the
+/// items and the text replacing them if it differs from the current one. Items with attributes
+/// are left as they are.
+fn normalize_imports(
+    container: &SyntaxNode,
+    cfg: &InsertUseConfig,
+) -> Option<(Vec<ast::Use>, String)> {
+    let uses: Vec<ast::Use> = container
+        .children()
+        .filter_map(ast::Use::cast)
+        .filter(|it| it.attrs().next().is_none())
+        .collect();
+    let first = uses.first()?;
+
+    let mut leaves = Vec::new();
+    for use_item in &uses {
+        let mut trees = Vec::new();
+        flatten_use_tree(None, &use_item.use_tree()?, &mut trees);
+        leaves.extend(trees.into_iter().map(|tree| make::use_(use_item.visibility(), tree)));
+    }
+    let mut seen = FxHashSet::default();
+    leaves.retain(|it| seen.insert(it.to_string()));
+    leaves.sort_by(cmp_uses);
+
+    let merge_behavior = match cfg.granularity {
+        ImportGranularity::Item => None,
+        ImportGranularity::Module => Some(MergeBehavior::Module),
+        ImportGranularity::One => Some(MergeBehavior::One),
+        ImportGranularity::Crate | ImportGranularity::Preserve => Some(MergeBehavior::Crate),
+    };
+    let mut merged: Vec<ast::Use> = Vec::new();
+    for leaf in leaves {
+        let target = merge_behavior.and_then(|mb| {
+            merged.iter_mut().find_map(|it| try_merge_imports(it, &leaf, mb).map(|m| (it, m)))
+        });
+        match target {
+            Some((it, merged_use)) => *it = merged_use,
+            None => merged.push(leaf),
+        }
+    }
+    let mut merged: Vec<ast::Use> = merged
+        .into_iter()
+        .map(|it| try_normalize_import(&it, cfg.granularity.into()).unwrap_or(it))
+        .collect();
+
+    let group = |it: &ast::Use| {
+        it.use_tree()
+            .filter(|_| cfg.group)
+            .map(|tree| ImportGroup::with_style(&tree, cfg.group_style))
+    };
+    merged.sort_by(|a, b| group(a).cmp(&group(b)).then_with(|| cmp_uses(a, b)));
+
+    let old = uses.iter().map(ToString::to_string);
+    if old.eq(merged.iter().map(ToString::to_string)) {
+        return None;
+    }
+
+    let indent = IndentLevel::from_node(first.syntax());
+    let mut text = String::new();
+    for (idx, it) in merged.iter().enumerate() {
+        if idx > 0 {
+            text.push('\n');
+            if group(&merged[idx - 1]) != group(it) {
+                text.push('\n');
+            }
+            format_to!(text, "{indent}");
+        }
+        format_to!(text, "{it}");
+    }
+    Some((uses, text))
+}
+
+/// Splits `tree` into trees that import a single path each, prepending `prefix` to them.
+fn flatten_use_tree(prefix: Option<ast::Path>, tree: &ast::UseTree, acc: &mut Vec<ast::UseTree>) {
+    let is_self = tree.path().is_some_and(|path| {
+        path.qualifier().is_none() && path.segment().is_some_and(|it| it.self_token().is_some())
+    });
+    let path = match (prefix, tree.path()) {
+        (Some(prefix), _) if is_self => Some(prefix),
+        (Some(prefix), Some(path)) => Some(make::path_concat(prefix, path)),
+        (prefix, path) => prefix.or(path),
+    };
+    if let Some(use_tree_list) = tree.use_tree_list() {
+        for tree in use_tree_list.use_trees() {
+            flatten_use_tree(path.clone(), &tree, acc);
+        }
+        return;
+    }
+    match path {
+        Some(path) => {
+            acc.push(make::use_tree(path, None, tree.rename(), tree.star_token().is_some()))
+        }
+        None => acc.push(tree.clone()),
+    }
+}
+
+fn cmp_uses(a: &ast::Use, b: &ast::Use) -> std::cmp::Ordering {
+    match (a.use_tree(), b.use_tree()) {
+        (Some(a), Some(b)) => use_tree_cmp(&a, &b),
+        _ => std::cmp::Ordering::Equal,
+    }
+}
+
+fn range_with_leading_ws(use_item: &ast::Use) -> TextRange {
+    let range = use_item.syntax().text_range();
+    match use_item.syntax().prev_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => ws.text_range().cover(range),
+        _ => range,
+    }
+}
+
 trait Merge: AstNode + Clone {
     fn try_merge_from(
         self,
@@ -177,9 +344,12 @@ fn replace(old: impl AstNode, new: impl AstNode) -> Self {
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{
-        check_assist, check_assist_import_one, check_assist_not_applicable,
-        check_assist_not_applicable_for_import_one,
+    use crate::{
+        tests::{
+            check_assist, check_assist_by_label_with_config, check_assist_import_one,
+            check_assist_not_applicable, check_assist_not_applicable_for_import_one, TEST_CONFIG,
+        },
+        AssistConfig,
     };
 
     use super::*;
@@ -714,4 +884,70 @@ fn merge_selection_use_trees() {
             r"use std::fmt::{Debug, Display};",
         );
     }
+
+    #[test]
+    fn merge_all_imports_of_each_module() {
+        check_assist(
+            merge_all_imports,
+            r"
+use crate::a::{X, Y};
+use std::fmt$0;
+use crate::a::X;
+pub use crate::b::Z;
+
+mod m {
+    use std::io::Write;
+    use std::io::{self, Read};
+}
+",
+            r"
+use std::fmt;
+
+use crate::a::{X, Y};
+pub use crate::b::Z;
+
+mod m {
+    use std::io::{self, Read, Write};
+}
+",
+        );
+    }
+
+    #[test]
+    fn merge_all_imports_splits_for_item_granularity() {
+        let config = AssistConfig {
+            insert_use: InsertUseConfig {
+                granularity: ImportGranularity::Item,
+                ..TEST_CONFIG.insert_use
+            },
+            ..TEST_CONFIG
+        };
+        check_assist_by_label_with_config(
+            merge_all_imports,
+            config,
+            r"
+use std::{fmt, io::{$0Read, Write as _}};
+use std::fmt;
+",
+            r"
+use std::fmt;
+use std::io::Read;
+use std::io::Write as _;
+",
+            "Merge all imports in the file",
+        );
+    }
+
+    #[test]
+    fn merge_all_imports_not_applicable_to_normalized_imports() {
+        cov_mark::check!(imports_already_normalized);
+        check_assist_not_applicable(
+            merge_all_imports,
+            r"
+use std::{fmt, io};
+
+use crate::$0a::{X, Y};
+",
+        );
+    }
 }
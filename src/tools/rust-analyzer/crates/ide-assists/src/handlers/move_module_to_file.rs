@@ -9,11 +9,12 @@
     AstNode, SmolStr, TextRange,
 };
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{assist_config::ModuleFileLayout, AssistContext, AssistId, AssistKind, Assists};
 
 // Assist: move_module_to_file
 //
-// Moves inline module's contents to a separate file.
+// Moves inline module's contents to a separate file, `foo.rs` or `foo/mod.rs` depending on the
+// configured layout.
 //
 // ```
 // mod $0foo {
@@ -65,7 +66,9 @@ pub(crate) fn move_module_to_file(acc: &mut Assists, ctx: &AssistContext<'_>) ->
 
                 // We need to special case mod named `r#mod` and place the file in a
                 // subdirectory as "mod.rs" would be of its parent module otherwise.
-                if module_name.text() == "r#mod" {
+                if module_name.text() == "r#mod"
+                    || ctx.config.module_file_layout == ModuleFileLayout::ModRs
+                {
                     format_to!(buf, "/mod.rs");
                 } else {
                     format_to!(buf, ".rs");
@@ -103,7 +106,13 @@ pub(crate) fn move_module_to_file(acc: &mut Assists, ctx: &AssistContext<'_>) ->
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable};
+    use crate::{
+        tests::{
+            check_assist, check_assist_by_label_with_config, check_assist_not_applicable,
+            TEST_CONFIG,
+        },
+        AssistConfig,
+    };
 
     use super::*;
 
@@ -148,6 +157,32 @@ fn f() {}
         );
     }
 
+    #[test]
+    fn extract_to_mod_rs() {
+        check_assist_by_label_with_config(
+            move_module_to_file,
+            AssistConfig { module_file_layout: ModuleFileLayout::ModRs, ..TEST_CONFIG },
+            r#"
+//- /main.rs
+mod submod;
+//- /submod.rs
+$0mod inner {
+    mod nested;
+}
+fn g() {}
+//- /submod/inner/nested.rs
+"#,
+            r#"
+//- /submod.rs
+mod inner;
+fn g() {}
+//- /submod/inner/mod.rs
+mod nested;
+"#,
+            "Extract module to file",
+        );
+    }
+
     #[test]
     fn extract_from_mod_rs() {
         check_assist(
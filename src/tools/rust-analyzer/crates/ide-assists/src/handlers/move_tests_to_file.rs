@@ -0,0 +1,465 @@
+use std::collections::BTreeSet;
+
+use hir::{AsAssocItem, DescendPreference, ScopeDef};
+use ide_db::{
+    base_db::AnchoredPathBuf,
+    defs::{Definition, NameRefClass},
+    FxHashSet,
+};
+use itertools::Itertools;
+use syntax::{
+    ast::{
+        self,
+        edit::{AstNodeEdit, IndentLevel},
+        edit_in_place::Removable,
+        make, HasAttrs, HasModuleItem, HasName,
+    },
+    ted, AstNode, SyntaxElement, SyntaxKind, TextRange,
+};
+
+use crate::{utils::vis_anchor, AssistContext, AssistId, AssistKind, Assists};
+
+// Assist: move_tests_to_file
+//
+// Moves an inline `#[cfg(test)]` module into a file of its own. The file is put next to the
+// current one, as `tests.rs` for crate roots and `mod.rs` files, and as `<parent>_tests.rs`
+// otherwise. A `use super::*` in the tests is replaced by imports of the items it provides.
+//
+// ```
+// //- /main.rs cfg:test
+// fn add(a: i32, b: i32) -> i32 {
+//     a + b
+// }
+//
+// #[cfg(test)]
+// mod $0tests {
+//     use super::*;
+//
+//     #[test]
+//     fn test_add() {
+//         assert_eq!(add(1, 2), 3);
+//     }
+// }
+// ```
+// ->
+// ```
+// fn add(a: i32, b: i32) -> i32 {
+//     a + b
+// }
+//
+// #[cfg(test)]
+// mod tests;
+// ```
+pub(crate) fn move_tests_to_file(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let module_ast = ctx.find_node_at_offset::<ast::Module>()?;
+    let module_items = module_ast.item_list()?;
+
+    let l_curly_offset = module_items.syntax().text_range().start();
+    if l_curly_offset <= ctx.offset() {
+        return None;
+    }
+    if !module_ast.attrs().any(|attr| is_cfg_test(&attr)) {
+        return None;
+    }
+    // Nested test modules are left to `move_module_to_file`.
+    if !ast::SourceFile::can_cast(module_ast.syntax().parent()?.kind()) {
+        return None;
+    }
+    let target = TextRange::new(module_ast.syntax().text_range().start(), l_curly_offset);
+
+    let module_name = module_ast.name()?;
+    let tests_module = ctx.sema.to_def(&module_ast)?;
+    let parent_module = tests_module.parent(ctx.db())?;
+
+    acc.add(
+        AssistId("move_tests_to_file", AssistKind::RefactorExtract),
+        "Move tests to a separate file",
+        target,
+        |builder| {
+            let name = module_name.text();
+            let name = name.trim_start_matches("r#");
+            // Modules declared in `foo.rs` live in `foo/`, so the tests need a `#[path]` to be
+            // found next to it.
+            let (file_name, path_attr) = match parent_module.name(ctx.db()) {
+                Some(parent) if !parent_module.is_mod_rs(ctx.db()) => {
+                    let parent = parent.display(ctx.db()).to_string();
+                    let file_name = format!("{}_{name}.rs", parent.trim_start_matches("r#"));
+                    let path_attr = format!("#[path = \"{file_name}\"]");
+                    (file_name, Some(path_attr))
+                }
+                _ => (format!("{name}.rs"), None),
+            };
+
+            // The copy for update keeps the ranges of the original tree.
+            let items = module_items.clone_for_update();
+            if let Some((glob, names)) = names_from_super_glob(ctx, &module_items, tests_module) {
+                let glob = items
+                    .syntax()
+                    .descendants()
+                    .filter_map(ast::Use::cast)
+                    .find(|it| it.syntax().text_range() == glob.syntax().text_range());
+                if let Some(glob) = glob {
+                    match names.len() {
+                        0 => glob.remove(),
+                        _ => ted::replace(glob.syntax(), imports_from_super(names).syntax()),
+                    }
+                }
+            }
+            let contents = {
+                let items = items.dedent(IndentLevel(1)).to_string();
+                let mut items =
+                    items.trim_start_matches('{').trim_end_matches('}').trim().to_owned();
+                if !items.is_empty() {
+                    items.push('\n');
+                }
+                items
+            };
+
+            let replacement_start = match module_ast.mod_token() {
+                Some(mod_token) => mod_token.text_range(),
+                None => module_ast.syntax().text_range(),
+            }
+            .start();
+            builder.replace(
+                TextRange::new(replacement_start, module_ast.syntax().text_range().end()),
+                format!("mod {module_name};"),
+            );
+            if let Some(path_attr) = path_attr {
+                let indent = IndentLevel::from_node(module_ast.syntax());
+                let anchor = vis_anchor(module_ast.syntax())
+                    .map_or(replacement_start, |it| it.text_range().start());
+                builder.insert(anchor, format!("{path_attr}\n{indent}"));
+            }
+
+            let dst = AnchoredPathBuf { anchor: ctx.file_id(), path: format!("./{file_name}") };
+            builder.create_file(dst, contents);
+        },
+    )
+}
+
+fn is_cfg_test(attr: &ast::Attr) -> bool {
+    attr.as_simple_call().is_some_and(|(name, tt)| name == "cfg" && tt.to_string() == "(test)")
+}
+
+/// Finds a `use super::*;` among the items of the tests, along with the names it brings in that
+/// are used by them, sorted.
+fn names_from_super_glob(
+    ctx: &AssistContext<'_>,
+    items: &ast::ItemList,
+    tests_module: hir::Module,
+) -> Option<(ast::Use, BTreeSet<String>)> {
+    let glob = items.items().find_map(|item| match item {
+        ast::Item::Use(use_) if is_super_glob(&use_) => Some(use_),
+        _ => None,
+    })?;
+    let parent_module = tests_module.parent(ctx.db())?;
+    let parent_scope = parent_module.scope(ctx.db(), Some(tests_module));
+    let declared = declared_names(items);
+
+    // Paths in macro calls are only resolved in their expansions.
+    let name_refs = items.syntax().descendants_with_tokens().flat_map(|element| match element {
+        SyntaxElement::Node(node) => ast::NameRef::cast(node).into_iter().collect_vec(),
+        SyntaxElement::Token(token)
+            if token.kind() == SyntaxKind::IDENT
+                && token.parent().is_some_and(|it| ast::TokenTree::can_cast(it.kind())) =>
+        {
+            ctx.sema
+                .descend_into_macros(DescendPreference::None, token)
+                .into_iter()
+                .filter_map(|it| it.parent().and_then(ast::NameRef::cast))
+                .collect()
+        }
+        SyntaxElement::Token(_) => Vec::new(),
+    });
+
+    let mut names = BTreeSet::new();
+    for name_ref in name_refs {
+        let (def, expected_name) =
+            match name_ref.syntax().parent().and_then(ast::MethodCallExpr::cast) {
+                // Trait methods can only be called with the trait in scope.
+                Some(call) => {
+                    let Some(trait_) = ctx
+                        .sema
+                        .resolve_method_call(&call)
+                        .and_then(|it| it.as_assoc_item(ctx.db()))
+                        .and_then(|it| it.container_or_implemented_trait(ctx.db()))
+                    else {
+                        continue;
+                    };
+                    (Definition::Trait(trait_), None)
+                }
+                None => {
+                    let Some(path) = name_ref.syntax().parent().and_then(ast::PathSegment::cast)
+                    else {
+                        continue;
+                    };
+                    let path = path.parent_path();
+                    if path.qualifier().is_some()
+                        || path.syntax().ancestors().any(|it| ast::Use::can_cast(it.kind()))
+                    {
+                        continue;
+                    }
+                    let Some(NameRefClass::Definition(def)) =
+                        NameRefClass::classify(&ctx.sema, &name_ref)
+                    else {
+                        continue;
+                    };
+                    (def, Some(name_ref.text().to_string()))
+                }
+            };
+        let name = parent_scope.iter().find_map(|(name, scope_def)| {
+            let name = name.display(ctx.db()).to_string();
+            let same_def =
+                matches!(scope_def, ScopeDef::ModuleDef(it) if Definition::from(*it) == def);
+            (same_def && expected_name.as_ref().map_or(true, |it| *it == name)).then_some(name)
+        });
+        if let Some(name) = name.filter(|it| !declared.contains(it)) {
+            names.insert(name);
+        }
+    }
+    Some((glob, names))
+}
+
+fn is_super_glob(use_: &ast::Use) -> bool {
+    use_.use_tree().is_some_and(|tree| {
+        tree.star_token().is_some() && tree.path().is_some_and(|path| path.to_string() == "super")
+    })
+}
+
+/// Names of the items of the tests and of their other imports, which shadow the ones from the
+/// glob import.
+fn declared_names(items: &ast::ItemList) -> FxHashSet<String> {
+    items
+        .items()
+        .flat_map(|item| match item {
+            ast::Item::Use(use_) => use_
+                .syntax()
+                .descendants()
+                .filter_map(ast::UseTree::cast)
+                .filter(|tree| tree.use_tree_list().is_none() && tree.star_token().is_none())
+                .filter_map(|tree| match tree.rename() {
+                    Some(rename) => Some(rename.name()?.to_string()),
+                    None => Some(tree.path()?.segment()?.name_ref()?.to_string()),
+                })
+                .collect(),
+            item => ast::AnyHasName::cast(item.syntax().clone())
+                .and_then(|it| it.name())
+                .map(|it| it.to_string())
+                .into_iter()
+                .collect_vec(),
+        })
+        .collect()
+}
+
+fn imports_from_super(names: BTreeSet<String>) -> ast::Use {
+    let tree = match names.into_iter().collect_vec().as_slice() {
+        [name] => {
+            make::use_tree(make::path_from_text(&format!("super::{name}")), None, None, false)
+        }
+        names => make::use_tree(
+            make::ext::ident_path("super"),
+            Some(make::use_tree_list(
+                names
+                    .iter()
+                    .map(|name| make::use_tree(make::ext::ident_path(name), None, None, false)),
+            )),
+            None,
+            false,
+        ),
+    };
+    make::use_(None, tree).clone_for_update()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn move_tests_of_crate_root() {
+        check_assist(
+            move_tests_to_file,
+            r#"
+//- /main.rs cfg:test
+struct Counter(u32);
+
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[cfg(test)]
+mod $0tests {
+    use super::*;
+
+    fn helper() -> Counter {
+        Counter(add(1, 2))
+    }
+
+    #[test]
+    fn counts() {
+        assert_eq!(helper().0, add(2, 1));
+    }
+}
+"#,
+            r#"
+//- /main.rs
+struct Counter(u32);
+
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests;
+//- /tests.rs
+use super::{Counter, add};
+
+fn helper() -> Counter {
+    Counter(add(1, 2))
+}
+
+#[test]
+fn counts() {
+    assert_eq!(helper().0, add(2, 1));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_tests_next_to_file() {
+        check_assist(
+            move_tests_to_file,
+            r#"
+//- /main.rs cfg:test
+mod parser;
+//- /parser.rs
+pub fn parse() {}
+
+/// Tests.
+#[cfg(test)]
+pub(crate) mod $0tests {
+    use super::*;
+
+    #[test]
+    fn parses() {
+        parse();
+    }
+}
+"#,
+            r#"
+//- /parser.rs
+pub fn parse() {}
+
+/// Tests.
+#[cfg(test)]
+#[path = "parser_tests.rs"]
+pub(crate) mod tests;
+//- /parser_tests.rs
+use super::parse;
+
+#[test]
+fn parses() {
+    parse();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn imports_traits_of_method_calls() {
+        check_assist(
+            move_tests_to_file,
+            r#"
+//- minicore: assert
+//- /main.rs cfg:test
+mod shapes;
+//- /shapes/mod.rs
+pub trait Area {
+    fn area(&self) -> u32;
+}
+
+pub struct Square(pub u32);
+
+impl Area for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+fn unused() {}
+
+#[cfg(test)]
+mod $0tests {
+    use super::*;
+    use super::Square;
+
+    #[test]
+    fn area() {
+        assert!(Square(2).area() == 4);
+    }
+}
+"#,
+            r#"
+//- /shapes/mod.rs
+pub trait Area {
+    fn area(&self) -> u32;
+}
+
+pub struct Square(pub u32);
+
+impl Area for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+fn unused() {}
+
+#[cfg(test)]
+mod tests;
+//- /shapes/tests.rs
+use super::Area;
+use super::Square;
+
+#[test]
+fn area() {
+    assert!(Square(2).area() == 4);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(
+            move_tests_to_file,
+            r#"
+mod $0tests {
+    #[test]
+    fn t() {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            move_tests_to_file,
+            r#"
+#[cfg(test)]
+mod tests {
+    $0#[test]
+    fn t() {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            move_tests_to_file,
+            r#"
+mod outer {
+    #[cfg(test)]
+    mod $0tests {}
+}
+"#,
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use hir::HirDisplay;
+use itertools::Itertools;
 use syntax::{
     ast::{self, make},
     ted, AstNode,
@@ -10,7 +12,8 @@
 
 // Assist: pull_assignment_up
 //
-// Extracts variable assignment to outside an if or match statement.
+// Extracts variable assignment to outside an if or match statement. If the assigned values have
+// different types that coerce to the type of the variable, they're cast to it.
 //
 // ```
 // fn main() {
@@ -66,6 +69,8 @@ pub(crate) fn pull_assignment_up(acc: &mut Assists, ctx: &AssistContext<'_>) ->
         }
     }
 
+    let casts = coercion_casts(ctx, &collector.common_lhs, &collector.assignments);
+
     acc.add(
         AssistId("pull_assignment_up", AssistKind::RefactorExtract),
         "Pull assignment up",
@@ -74,7 +79,14 @@ pub(crate) fn pull_assignment_up(acc: &mut Assists, ctx: &AssistContext<'_>) ->
             let assignments: Vec<_> = collector
                 .assignments
                 .into_iter()
-                .map(|(stmt, rhs)| (edit.make_mut(stmt), rhs.clone_for_update()))
+                .zip(casts)
+                .map(|((stmt, rhs), cast)| {
+                    let rhs = match cast {
+                        Some(ty) => cast_expr(rhs, ty),
+                        None => rhs,
+                    };
+                    (edit.make_mut(stmt), rhs.clone_for_update())
+                })
                 .collect();
 
             let tgt = edit.make_mut(tgt);
@@ -96,6 +108,59 @@ pub(crate) fn pull_assignment_up(acc: &mut Assists, ctx: &AssistContext<'_>) ->
     )
 }
 
+/// Returns the type each assigned value has to be cast to. As the branches of the pulled up `if` or
+/// `match` have to have the same type, values are cast to the type of `lhs` if they differ.
+fn coercion_casts(
+    ctx: &AssistContext<'_>,
+    lhs: &ast::Expr,
+    assignments: &[(ast::BinExpr, ast::Expr)],
+) -> Vec<Option<ast::Type>> {
+    let db = ctx.db();
+    let rhs_tys: Vec<_> = assignments
+        .iter()
+        .map(|(_, rhs)| ctx.sema.type_of_expr(rhs).map(|it| it.original))
+        .collect();
+    let all_unify =
+        rhs_tys.iter().flatten().tuple_windows().all(|(a, b)| a.could_unify_with(db, b));
+    let lhs_ty = ctx.sema.type_of_expr(lhs).map(|it| it.original);
+    let cast_ty = match (all_unify, &lhs_ty, ctx.sema.scope(lhs.syntax())) {
+        (false, Some(lhs_ty), Some(scope)) => lhs_ty
+            .display_source_code(db, scope.module().into(), false)
+            .ok()
+            .map(|it| make::ty(&it)),
+        _ => None,
+    };
+
+    rhs_tys
+        .into_iter()
+        .map(|ty| {
+            let (ty, lhs_ty) = (ty?, lhs_ty.as_ref()?);
+            if ty.could_unify_with(db, lhs_ty) || !ty.could_coerce_to(db, lhs_ty) {
+                return None;
+            }
+            let cast_ty = cast_ty.clone()?;
+            cov_mark::hit!(test_pull_assignment_up_coerced_values);
+            Some(cast_ty)
+        })
+        .collect()
+}
+
+fn cast_expr(expr: ast::Expr, ty: ast::Type) -> ast::Expr {
+    // Make `(expr) as ty`, to check whether the parentheses are needed
+    let dummy_cast = make::expr_cast(make::expr_paren(expr.clone()), ty.clone());
+
+    // (unreachable!s are fine since we've just constructed the expression)
+    let ast::Expr::CastExpr(cast) = &dummy_cast else { unreachable!() };
+    let Some(ast::Expr::ParenExpr(parens)) = cast.expr() else { unreachable!() };
+    let Some(inner) = parens.expr() else { unreachable!() };
+
+    if inner.needs_parens_in(dummy_cast.syntax().clone()) {
+        dummy_cast
+    } else {
+        make::expr_cast(expr, ty)
+    }
+}
+
 struct AssignmentsCollector<'a> {
     sema: &'a hir::Semantics<'a, ide_db::RootDatabase>,
     common_lhs: ast::Expr,
@@ -256,6 +321,115 @@ fn foo() {
         );
     }
 
+    #[test]
+    fn test_pull_assignment_up_coerced_values() {
+        cov_mark::check!(test_pull_assignment_up_coerced_values);
+        check_assist(
+            pull_assignment_up,
+            r#"
+fn foo() {
+    let mut a = 1;
+    let b = 2;
+    let mut r: &i32 = &0;
+
+    match 1 {
+        1 => {
+            $0r = &mut a;
+        }
+        _ => {
+            r = &b;
+        }
+    }
+}"#,
+            r#"
+fn foo() {
+    let mut a = 1;
+    let b = 2;
+    let mut r: &i32 = &0;
+
+    r = match 1 {
+        1 => {
+            &mut a as &i32
+        }
+        _ => {
+            &b
+        }
+    };
+}"#,
+        );
+    }
+
+    #[test]
+    fn test_pull_assignment_up_trait_objects() {
+        check_assist(
+            pull_assignment_up,
+            r#"
+//- minicore: coerce_unsized
+trait Shape {}
+struct Circle;
+struct Square;
+impl Shape for Circle {}
+impl Shape for Square {}
+
+fn foo(c: bool) {
+    let mut shape: &dyn Shape = &Circle;
+    if c {
+        $0shape = &Circle;
+    } else {
+        shape = &Square;
+    }
+}"#,
+            r#"
+trait Shape {}
+struct Circle;
+struct Square;
+impl Shape for Circle {}
+impl Shape for Square {}
+
+fn foo(c: bool) {
+    let mut shape: &dyn Shape = &Circle;
+    shape = if c {
+        &Circle as &dyn Shape
+    } else {
+        &Square as &dyn Shape
+    };
+}"#,
+        );
+    }
+
+    #[test]
+    fn test_pull_assignment_up_cast_needs_parens() {
+        check_assist(
+            pull_assignment_up,
+            r#"
+fn bar() -> i32 {
+    2
+}
+
+fn foo(c: bool) {
+    let mut f: fn() -> i32 = bar;
+    if c {
+        $0f = || 1;
+    } else {
+        f = bar;
+    }
+}"#,
+            r#"
+fn bar() -> i32 {
+    2
+}
+
+fn foo(c: bool) {
+    let mut f: fn() -> i32 = bar;
+    f = if c {
+        (|| 1) as fn() -> i32
+    } else {
+        bar as fn() -> i32
+    };
+}"#,
+        );
+    }
+
     #[test]
     fn test_pull_assignment_up_assignment_expressions() {
         check_assist(
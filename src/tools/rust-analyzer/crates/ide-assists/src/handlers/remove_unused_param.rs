@@ -1,4 +1,5 @@
-use ide_db::{base_db::FileId, defs::Definition, search::FileReference};
+use hir::{AsAssocItem, AssocItem};
+use ide_db::{base_db::FileId, defs::Definition, search::FileReference, FxHashMap};
 use syntax::{
     algo::find_node_at_range,
     ast::{self, HasArgList},
@@ -16,6 +17,9 @@
 //
 // Removes unused function parameter.
 //
+// For trait methods, the parameter is removed from the declaration in the trait and from all of
+// its implementations at once, as long as none of them uses it.
+//
 // ```
 // fn frobnicate(x: i32$0) {}
 //
@@ -41,19 +45,8 @@ pub(crate) fn remove_unused_param(acc: &mut Assists, ctx: &AssistContext<'_>) ->
     let is_self_present =
         param.syntax().parent()?.children().find_map(ast::SelfParam::cast).is_some();
 
-    // check if fn is in impl Trait for ..
-    if func
-        .syntax()
-        .parent() // AssocItemList
-        .and_then(|x| x.parent())
-        .and_then(ast::Impl::cast)
-        .map_or(false, |imp| imp.trait_().is_some())
-    {
-        cov_mark::hit!(trait_impl);
-        return None;
-    }
-
-    let mut param_position = func.param_list()?.params().position(|it| it == param)?;
+    let param_index = func.param_list()?.params().position(|it| it == param)?;
+    let mut param_position = param_index;
     // param_list() does not take the self param into consideration, hence this additional check
     // is required. For associated functions, param_position is incremented here. For inherent
     // calls we revet the increment below, in process_usage, as those calls will not have an
@@ -61,10 +54,7 @@ pub(crate) fn remove_unused_param(acc: &mut Assists, ctx: &AssistContext<'_>) ->
     if is_self_present {
         param_position += 1;
     }
-    let fn_def = {
-        let func = ctx.sema.to_def(&func)?;
-        Definition::Function(func)
-    };
+    let fn_def = ctx.sema.to_def(&func)?;
 
     let param_def = {
         let local = ctx.sema.to_def(&ident_pat)?;
@@ -74,19 +64,85 @@ pub(crate) fn remove_unused_param(acc: &mut Assists, ctx: &AssistContext<'_>) ->
         cov_mark::hit!(keep_used);
         return None;
     }
+
+    // The same parameter of the trait declaration and of the other implementations.
+    let mut other_params = Vec::new();
+    let related_fns = related_fns(ctx, fn_def)?;
+    for &related_fn in related_fns.iter().filter(|&&it| it != fn_def) {
+        let source = ctx.sema.source(related_fn)?;
+        let file_id = source.file_id.file_id()?;
+        let related_param = source.value.param_list()?.params().nth(param_index)?;
+        match related_param.pat() {
+            // Parameters of declarations without a body can't be used.
+            _ if source.value.body().is_none() => (),
+            Some(ast::Pat::IdentPat(ident_pat)) => {
+                let local = ctx.sema.to_def(&ident_pat)?;
+                if Definition::Local(local).usages(&ctx.sema).at_least_one() {
+                    cov_mark::hit!(keep_used_in_related_fn);
+                    return None;
+                }
+            }
+            Some(ast::Pat::WildcardPat(_)) | None => (),
+            Some(_) => return None,
+        }
+        other_params.push((file_id, range_to_remove(related_param.syntax())));
+    }
+
     acc.add(
         AssistId("remove_unused_param", AssistKind::Refactor),
         "Remove unused parameter",
         param.syntax().text_range(),
         |builder| {
             builder.delete(range_to_remove(param.syntax()));
-            for (file_id, references) in fn_def.usages(&ctx.sema).all() {
+            for (file_id, range) in other_params {
+                builder.edit_file(file_id);
+                builder.delete(range);
+            }
+
+            let mut usages = FxHashMap::<FileId, Vec<FileReference>>::default();
+            for related_fn in related_fns {
+                for (file_id, references) in
+                    Definition::Function(related_fn).usages(&ctx.sema).all()
+                {
+                    usages.entry(file_id).or_default().extend(references);
+                }
+            }
+            for (file_id, references) in usages {
                 process_usages(ctx, builder, file_id, references, param_position, is_self_present);
             }
         },
     )
 }
 
+/// Returns the functions whose parameters have to stay the same as the ones of `func`: for a trait
+/// method, its declaration in the trait and all of its implementations, otherwise `func` alone.
+///
+/// Returns `None` if the trait can't be changed.
+fn related_fns(ctx: &AssistContext<'_>, func: hir::Function) -> Option<Vec<hir::Function>> {
+    let db = ctx.db();
+    let Some(trait_) = func.as_assoc_item(db).and_then(|it| it.container_or_implemented_trait(db))
+    else {
+        return Some(vec![func]);
+    };
+    if !trait_.module(db).krate().origin(db).is_local() {
+        cov_mark::hit!(trait_impl);
+        return None;
+    }
+
+    let name = func.name(db);
+    let find_fn = |items: Vec<AssocItem>| {
+        items.into_iter().find_map(|item| match item {
+            AssocItem::Function(it) if it.name(db) == name => Some(it),
+            _ => None,
+        })
+    };
+    let mut fns = vec![find_fn(trait_.items(db))?];
+    fns.extend(
+        hir::Impl::all_for_trait(db, trait_).into_iter().filter_map(|it| find_fn(it.items(db))),
+    );
+    Some(fns)
+}
+
 fn process_usages(
     ctx: &AssistContext<'_>,
     builder: &mut SourceChangeBuilder,
@@ -313,12 +369,115 @@ fn trait_impl() {
         check_assist_not_applicable(
             remove_unused_param,
             r#"
+//- /lib.rs crate:lib library
+pub trait Trait {
+    fn foo(x: i32);
+}
+//- /main.rs crate:main deps:lib
+impl lib::Trait for () {
+    fn foo($0x: i32) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_from_trait_and_impls() {
+        check_assist(
+            remove_unused_param,
+            r#"
+trait Trait {
+    fn foo(&self, x: i32, y: i32);
+}
+struct A;
+impl Trait for A {
+    fn foo(&self, x: i32, $0y: i32) { x; }
+}
+struct B;
+impl Trait for B {
+    fn foo(&self, x: i32, _: i32) { x; }
+}
+fn main() {
+    A.foo(1, 2);
+    B.foo(3, 4);
+    Trait::foo(&A, 5, 6);
+    <B as Trait>::foo(&B, 7, 8);
+}
+"#,
+            r#"
+trait Trait {
+    fn foo(&self, x: i32);
+}
+struct A;
+impl Trait for A {
+    fn foo(&self, x: i32) { x; }
+}
+struct B;
+impl Trait for B {
+    fn foo(&self, x: i32) { x; }
+}
+fn main() {
+    A.foo(1);
+    B.foo(3);
+    Trait::foo(&A, 5);
+    <B as Trait>::foo(&B, 7);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_from_trait_declaration() {
+        check_assist(
+            remove_unused_param,
+            r#"
+//- /main.rs
+mod imp;
+trait Trait {
+    fn foo($0x: i32) {}
+}
+fn main() {
+    <() as Trait>::foo(1);
+}
+//- /imp.rs
+impl crate::Trait for () {
+    fn foo(_x: i32) {}
+}
+"#,
+            r#"
+//- /main.rs
+mod imp;
+trait Trait {
+    fn foo() {}
+}
+fn main() {
+    <() as Trait>::foo();
+}
+//- /imp.rs
+impl crate::Trait for () {
+    fn foo() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keep_used_in_related_fn() {
+        cov_mark::check!(keep_used_in_related_fn);
+        check_assist_not_applicable(
+            remove_unused_param,
+            r#"
 trait Trait {
     fn foo(x: i32);
 }
-impl Trait for () {
+struct A;
+impl Trait for A {
     fn foo($0x: i32) {}
 }
+struct B;
+impl Trait for B {
+    fn foo(x: i32) { x; }
+}
 "#,
         );
     }
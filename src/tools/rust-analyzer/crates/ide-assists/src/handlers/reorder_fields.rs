@@ -1,14 +1,17 @@
 use either::Either;
-use ide_db::FxHashMap;
+use ide_db::{base_db::FileId, defs::Definition, FxHashMap};
 use itertools::Itertools;
-use syntax::{ast, ted, AstNode};
+use syntax::{ast, ted, AstNode, SyntaxNode, TextRange};
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{assist_context::SourceChangeBuilder, AssistContext, AssistId, AssistKind, Assists};
+
+type FieldList = Either<ast::RecordExprFieldList, ast::RecordPatFieldList>;
 
 // Assist: reorder_fields
 //
 // Reorder the fields of record literals and record patterns in the same order as in
-// the definition.
+// the definition. Invoked on the name of a struct definition, or on a literal when others
+// are unordered too, the fields of all its record literals and patterns are reordered.
 //
 // ```
 // struct Foo {foo: i32, bar: i32};
@@ -20,92 +23,176 @@
 // const test: Foo = Foo {foo: 1, bar: 0}
 // ```
 pub(crate) fn reorder_fields(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let struct_name = ctx
+        .find_node_at_offset::<ast::Name>()
+        .filter(|it| it.syntax().parent().is_some_and(|it| ast::Struct::can_cast(it.kind())));
+    if let Some(name) = struct_name {
+        let strukt = ctx.sema.to_def(&ast::Struct::cast(name.syntax().parent()?)?)?;
+        return reorder_fields_at_usages(acc, ctx, strukt, name.syntax().text_range(), None);
+    }
+
     let path = ctx.find_node_at_offset::<ast::Path>()?;
     let record =
         path.syntax().parent().and_then(<Either<ast::RecordExpr, ast::RecordPat>>::cast)?;
+    let strukt = match ctx.sema.resolve_path(&path) {
+        Some(hir::PathResolution::Def(hir::ModuleDef::Adt(hir::Adt::Struct(it)))) => it,
+        _ => return None,
+    };
 
-    let ranks = compute_fields_ranks(&path, ctx)?;
-    let get_rank_of_field =
-        |of: Option<_>| *ranks.get(&of.unwrap_or_default()).unwrap_or(&usize::MAX);
+    let ranks = compute_fields_ranks(strukt, ctx);
+    let field_list = field_list(&record)?;
+    let target = record.as_ref().either(AstNode::syntax, AstNode::syntax).text_range();
+    if is_sorted(&field_list, &ranks) {
+        cov_mark::hit!(reorder_sorted_fields);
+    } else {
+        let field_list = field_list.clone();
+        acc.add(
+            AssistId("reorder_fields", AssistKind::RefactorRewrite),
+            "Reorder record fields",
+            target,
+            |builder| reorder(&make_mut(builder, field_list), &ranks),
+        );
+    }
+    reorder_fields_at_usages(acc, ctx, strukt, target, Some(field_list.syntax()))
+}
 
-    let field_list = match &record {
-        Either::Left(it) => Either::Left(it.record_expr_field_list()?),
-        Either::Right(it) => Either::Right(it.record_pat_field_list()?),
-    };
-    let fields = match field_list {
-        Either::Left(it) => Either::Left((
-            it.fields()
-                .sorted_unstable_by_key(|field| {
-                    get_rank_of_field(field.field_name().map(|it| it.to_string()))
-                })
-                .collect::<Vec<_>>(),
-            it,
-        )),
-        Either::Right(it) => Either::Right((
-            it.fields()
-                .sorted_unstable_by_key(|field| {
-                    get_rank_of_field(field.field_name().map(|it| it.to_string()))
+/// Offers reordering the fields of all record literals and patterns of `strukt`, unless the only
+/// unordered one is `current`.
+fn reorder_fields_at_usages(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+    strukt: hir::Struct,
+    target: TextRange,
+    current: Option<&SyntaxNode>,
+) -> Option<()> {
+    let ranks = compute_fields_ranks(strukt, ctx);
+    let mut unsorted: Vec<(FileId, Vec<FieldList>)> = Definition::Adt(strukt.into())
+        .usages(&ctx.sema)
+        .all()
+        .into_iter()
+        .map(|(file_id, references)| {
+            let field_lists = references
+                .iter()
+                .filter_map(|reference| {
+                    let path = reference
+                        .name
+                        .as_name_ref()?
+                        .syntax()
+                        .ancestors()
+                        .find_map(ast::Path::cast)?;
+                    let record =
+                        <Either<ast::RecordExpr, ast::RecordPat>>::cast(path.syntax().parent()?)?;
+                    field_list(&record)
                 })
-                .collect::<Vec<_>>(),
-            it,
-        )),
+                .filter(|it| !is_sorted(it, &ranks))
+                .collect::<Vec<_>>();
+            (file_id, field_lists)
+        })
+        .filter(|(_, field_lists)| !field_lists.is_empty())
+        .collect();
+    let only_current = match &unsorted[..] {
+        [(_, field_lists)] => {
+            matches!(&field_lists[..], [it] if Some(it.syntax()) == current)
+        }
+        _ => false,
     };
-
-    let is_sorted = fields.as_ref().either(
-        |(sorted, field_list)| field_list.fields().zip(sorted).all(|(a, b)| a == *b),
-        |(sorted, field_list)| field_list.fields().zip(sorted).all(|(a, b)| a == *b),
-    );
-    if is_sorted {
-        cov_mark::hit!(reorder_sorted_fields);
+    if unsorted.is_empty() || only_current {
         return None;
     }
-    let target = record.as_ref().either(AstNode::syntax, AstNode::syntax).text_range();
+    unsorted.sort_by_key(|(file_id, _)| *file_id);
+
     acc.add(
         AssistId("reorder_fields", AssistKind::RefactorRewrite),
-        "Reorder record fields",
+        format!(
+            "Reorder record fields of all `{}` literals and patterns",
+            strukt.name(ctx.db()).display(ctx.db())
+        ),
         target,
-        |builder| match fields {
-            Either::Left((sorted, field_list)) => {
-                replace(builder.make_mut(field_list).fields(), sorted)
-            }
-            Either::Right((sorted, field_list)) => {
-                replace(builder.make_mut(field_list).fields(), sorted)
+        |builder| {
+            for (file_id, field_lists) in unsorted {
+                builder.edit_file(file_id);
+                let mut field_lists: Vec<_> =
+                    field_lists.into_iter().map(|it| make_mut(builder, it)).collect();
+                // Reorder nested lists first, so that moving the outer fields keeps their changes.
+                field_lists.sort_by_key(|it| std::cmp::Reverse(it.syntax().text_range().start()));
+                field_lists.iter().for_each(|it| reorder(it, &ranks));
             }
         },
     )
 }
 
+fn field_list(record: &Either<ast::RecordExpr, ast::RecordPat>) -> Option<FieldList> {
+    match record {
+        Either::Left(it) => Some(Either::Left(it.record_expr_field_list()?)),
+        Either::Right(it) => Some(Either::Right(it.record_pat_field_list()?)),
+    }
+}
+
+fn make_mut(builder: &mut SourceChangeBuilder, field_list: FieldList) -> FieldList {
+    match field_list {
+        Either::Left(it) => Either::Left(builder.make_mut(it)),
+        Either::Right(it) => Either::Right(builder.make_mut(it)),
+    }
+}
+
+fn is_sorted(field_list: &FieldList, ranks: &FxHashMap<String, usize>) -> bool {
+    match field_list {
+        Either::Left(it) => sorted_fields(it.fields(), ranks, |it| it.field_name()).is_none(),
+        Either::Right(it) => sorted_fields(it.fields(), ranks, |it| it.field_name()).is_none(),
+    }
+}
+
+fn reorder(field_list: &FieldList, ranks: &FxHashMap<String, usize>) {
+    match field_list {
+        Either::Left(it) => {
+            if let Some(sorted) = sorted_fields(it.fields(), ranks, |it| it.field_name()) {
+                replace(it.fields(), sorted)
+            }
+        }
+        Either::Right(it) => {
+            if let Some(sorted) = sorted_fields(it.fields(), ranks, |it| it.field_name()) {
+                replace(it.fields(), sorted)
+            }
+        }
+    }
+}
+
+/// Returns `fields` in the order of their definition, or `None` if they are already ordered.
+fn sorted_fields<T: AstNode + Clone + PartialEq, N: ToString>(
+    fields: impl Iterator<Item = T>,
+    ranks: &FxHashMap<String, usize>,
+    field_name: impl Fn(&T) -> Option<N>,
+) -> Option<Vec<T>> {
+    let fields = fields.collect::<Vec<_>>();
+    let get_rank_of_field = |field: &T| {
+        let name = field_name(field).map(|it| it.to_string()).unwrap_or_default();
+        *ranks.get(&name).unwrap_or(&usize::MAX)
+    };
+    let sorted = fields.iter().cloned().sorted_by_key(get_rank_of_field).collect::<Vec<_>>();
+    (sorted != fields).then_some(sorted)
+}
+
 fn replace<T: AstNode + PartialEq>(
     fields: impl Iterator<Item = T>,
     sorted_fields: impl IntoIterator<Item = T>,
 ) {
     fields.zip(sorted_fields).for_each(|(field, sorted_field)| {
-        ted::replace(field.syntax(), sorted_field.syntax().clone_for_update())
+        ted::replace(field.syntax(), sorted_field.syntax().clone_subtree().clone_for_update())
     });
 }
 
-fn compute_fields_ranks(
-    path: &ast::Path,
-    ctx: &AssistContext<'_>,
-) -> Option<FxHashMap<String, usize>> {
-    let strukt = match ctx.sema.resolve_path(path) {
-        Some(hir::PathResolution::Def(hir::ModuleDef::Adt(hir::Adt::Struct(it)))) => it,
-        _ => return None,
-    };
-
-    let res = strukt
+fn compute_fields_ranks(strukt: hir::Struct, ctx: &AssistContext<'_>) -> FxHashMap<String, usize> {
+    strukt
         .fields(ctx.db())
         .into_iter()
         .enumerate()
         .map(|(idx, field)| (field.name(ctx.db()).display(ctx.db()).to_string(), idx))
-        .collect();
-
-    Some(res)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable};
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
 
     use super::*;
 
@@ -204,6 +291,70 @@ fn new() -> Foo {
         }
     }
 }
+"#,
+        )
+    }
+
+    #[test]
+    fn reorder_all_usages_from_definition() {
+        check_assist(
+            reorder_fields,
+            r#"
+//- /main.rs
+mod other;
+struct $0Foo { foo: i32, bar: i32 }
+const A: Foo = Foo { bar: 0, foo: 1 };
+const B: Foo = Foo { foo: 0, bar: 1 };
+//- /other.rs
+use crate::Foo;
+fn f(Foo { bar, foo }: Foo) -> i32 {
+    bar + foo
+}
+"#,
+            r#"
+//- /main.rs
+mod other;
+struct Foo { foo: i32, bar: i32 }
+const A: Foo = Foo { foo: 1, bar: 0 };
+const B: Foo = Foo { foo: 0, bar: 1 };
+//- /other.rs
+use crate::Foo;
+fn f(Foo { foo, bar }: Foo) -> i32 {
+    bar + foo
+}
+"#,
+        )
+    }
+
+    #[test]
+    fn reorder_all_usages_from_literal() {
+        check_assist_by_label(
+            reorder_fields,
+            r#"
+struct Foo { foo: i32, bar: Option<Box<Foo>> }
+const A: Foo = $0Foo { bar: None, foo: 1 };
+fn f() -> Foo {
+    Foo { bar: Some(Box::new(Foo { bar: None, foo: 0 })), foo: 1 }
+}
+"#,
+            r#"
+struct Foo { foo: i32, bar: Option<Box<Foo>> }
+const A: Foo = Foo { foo: 1, bar: None };
+fn f() -> Foo {
+    Foo { foo: 1, bar: Some(Box::new(Foo { foo: 0, bar: None })) }
+}
+"#,
+            "Reorder record fields of all `Foo` literals and patterns",
+        )
+    }
+
+    #[test]
+    fn not_applicable_on_definition_with_sorted_usages() {
+        check_assist_not_applicable(
+            reorder_fields,
+            r#"
+struct $0Foo { foo: i32, bar: i32 }
+const A: Foo = Foo { foo: 1, bar: 0 };
 "#,
         )
     }
@@ -1,8 +1,11 @@
 use hir::{InFile, MacroFileIdExt, ModuleDef};
-use ide_db::{helpers::mod_path_to_ast, imports::import_assets::NameToImport, items_locator};
+use ide_db::{
+    helpers::mod_path_to_ast, imports::import_assets::NameToImport, items_locator,
+    syntax_helpers::insert_whitespace_into_node::insert_ws_into,
+};
 use itertools::Itertools;
 use syntax::{
-    ast::{self, make, AstNode, HasName},
+    ast::{self, edit::IndentLevel, edit_in_place::Indent, make, AstNode, HasName},
     ted,
     SyntaxKind::WHITESPACE,
     T,
@@ -19,7 +22,8 @@
 
 // Assist: replace_derive_with_manual_impl
 //
-// Converts a `derive` impl into a manual one.
+// Converts a `derive` impl into a manual one. Derives implemented by procedural macros can also be
+// replaced by their expansion.
 //
 // ```
 // # //- minicore: derive
@@ -111,7 +115,67 @@ pub(crate) fn replace_derive_with_manual_impl(
     if no_traits_found {
         add_assist(acc, ctx, &attr, &current_derives, &args, &path, &path, None, &adt)?;
     }
-    Some(())
+
+    let index = current_derives
+        .iter()
+        .position(|derive| derive.syntax().text_range() == path.syntax().text_range())?;
+    add_expansion_assist(acc, ctx, &attr, &current_derives, &args, &path, &adt, index)
+}
+
+fn add_expansion_assist(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+    attr: &ast::Attr,
+    old_derives: &[ast::Path],
+    old_tree: &ast::TokenTree,
+    old_trait_path: &ast::Path,
+    adt: &ast::Adt,
+    index: usize,
+) -> Option<()> {
+    // Built-in derives get a proper skeleton instead.
+    let derive = ctx.sema.resolve_derive_macro(attr)?.get(index).copied().flatten()?;
+    if derive.is_builtin_derive(ctx.db()) {
+        return None;
+    }
+    let expansion = insert_ws_into(ctx.sema.expand_derive_macro_at(attr, index)?);
+    // Derives re-emitting the annotated item would define it twice.
+    let name = adt.name()?;
+    let items = expansion
+        .children()
+        .filter_map(ast::Item::cast)
+        .filter(|item| {
+            ast::Adt::cast(item.syntax().clone())
+                .and_then(|it| it.name())
+                .map_or(true, |it| it.text() != name.text())
+        })
+        .collect::<Vec<_>>();
+    if items.is_empty() {
+        return None;
+    }
+
+    acc.add(
+        AssistId("replace_derive_with_manual_impl", AssistKind::Refactor),
+        format!("Replace `derive({old_trait_path})` with its expansion"),
+        attr.syntax().text_range(),
+        |builder| {
+            let adt = builder.make_mut(adt.clone());
+            update_attribute(builder, old_derives, old_tree, old_trait_path, attr);
+
+            let indent = IndentLevel::from_node(adt.syntax());
+            let mut elements = Vec::new();
+            for item in items {
+                ted::remove(item.syntax());
+                while let Some(ws) = item.syntax().last_token().filter(|it| it.kind() == WHITESPACE)
+                {
+                    ted::remove(ws);
+                }
+                item.indent(indent);
+                elements.push(make::tokens::blank_line().into());
+                elements.push(item.syntax().clone().into());
+            }
+            ted::insert_all(ted::Position::after(adt.syntax()), elements);
+        },
+    )
 }
 
 fn add_assist(
@@ -137,7 +201,7 @@ fn add_assist(
             let insert_after = ted::Position::after(builder.make_mut(adt.clone()).syntax());
 
             let impl_def_with_items =
-                impl_def_from_trait(&ctx.sema, adt, &annotated_name, trait_, replace_trait_path);
+                impl_def_from_trait(ctx, adt, &annotated_name, trait_, replace_trait_path);
             update_attribute(builder, old_derives, old_tree, old_trait_path, attr);
 
             let trait_path = make::ty_path(replace_trait_path.clone());
@@ -194,12 +258,13 @@ fn add_assist(
 }
 
 fn impl_def_from_trait(
-    sema: &hir::Semantics<'_, ide_db::RootDatabase>,
+    ctx: &AssistContext<'_>,
     adt: &ast::Adt,
     annotated_name: &ast::Name,
     trait_: Option<hir::Trait>,
     trait_path: &ast::Path,
 ) -> Option<(ast::Impl, ast::AssocItem)> {
+    let sema = &ctx.sema;
     let trait_ = trait_?;
     let target_scope = sema.scope(annotated_name.syntax())?;
 
@@ -218,8 +283,14 @@ fn impl_def_from_trait(
     }
     let impl_def = generate_trait_impl(adt, make::ty_path(trait_path.clone()));
 
-    let first_assoc_item =
-        add_trait_assoc_items_to_impl(sema, &trait_items, trait_, &impl_def, target_scope);
+    let first_assoc_item = add_trait_assoc_items_to_impl(
+        sema,
+        ctx.config,
+        &trait_items,
+        trait_,
+        &impl_def,
+        target_scope,
+    );
 
     // Generate a default `impl` function body for the derived trait.
     if let ast::AssocItem::Fn(ref func) = first_assoc_item {
@@ -276,7 +347,10 @@ fn update_attribute(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::{check_assist, check_assist_not_applicable};
+    use crate::tests::{
+        check_assist, check_assist_by_label, check_assist_not_applicable,
+        check_assist_not_applicable_by_label,
+    };
 
     use super::*;
 
@@ -1379,4 +1453,57 @@ impl core::fmt::Debug for Foo {
 "#,
         )
     }
+
+    #[test]
+    fn replace_proc_macro_derive_with_expansion() {
+        check_assist_by_label(
+            replace_derive_with_manual_impl,
+            r#"
+//- proc_macros: derive_marker
+//- minicore: derive
+trait Marker {}
+#[derive(proc_macros::$0DeriveMarker)]
+struct S;
+"#,
+            r#"
+trait Marker {}
+struct S;
+
+impl Marker for S{}
+"#,
+            "Replace `derive(proc_macros::DeriveMarker)` with its expansion",
+        );
+        check_assist_by_label(
+            replace_derive_with_manual_impl,
+            r#"
+//- proc_macros: derive_marker
+//- minicore: clone, derive
+trait Marker {}
+#[derive(Clone, proc_macros::$0DeriveMarker)]
+struct S;
+"#,
+            r#"
+trait Marker {}
+#[derive(Clone)]
+struct S;
+
+impl Marker for S{}
+"#,
+            "Replace `derive(proc_macros::DeriveMarker)` with its expansion",
+        );
+    }
+
+    #[test]
+    fn replace_proc_macro_derive_reemitting_item() {
+        check_assist_not_applicable_by_label(
+            replace_derive_with_manual_impl,
+            r#"
+//- proc_macros: derive_identity
+//- minicore: derive
+#[derive(proc_macros::$0DeriveIdentity)]
+struct S;
+"#,
+            "Replace `derive(proc_macros::DeriveIdentity)` with its expansion",
+        );
+    }
 }
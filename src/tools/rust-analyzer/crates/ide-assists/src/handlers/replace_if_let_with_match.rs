@@ -7,13 +7,15 @@
     ty_filter::TryEnum,
     RootDatabase,
 };
+use itertools::Itertools;
 use syntax::{
     ast::{
         self,
         edit::{AstNodeEdit, IndentLevel},
         make, HasName,
     },
-    AstNode, TextRange, T,
+    ted::{self, Position},
+    AstNode, SyntaxElement, SyntaxKind, SyntaxToken, TextRange, T,
 };
 
 use crate::{
@@ -73,6 +75,8 @@ pub(crate) fn replace_if_let_with_match(acc: &mut Assists, ctx: &AssistContext<'
 
     let mut pat_seen = false;
     let mut cond_bodies = Vec::new();
+    // The comments between a branch and the next `else` go before the arm of that `else`.
+    let mut arm_comments = vec![Vec::new()];
     for if_expr in if_exprs {
         let cond = if_expr.condition()?;
         let cond = match single_let(cond.clone()) {
@@ -93,6 +97,15 @@ pub(crate) fn replace_if_let_with_match(acc: &mut Assists, ctx: &AssistContext<'
             None => Either::Right(cond),
         };
         let body = if_expr.then_branch()?;
+        arm_comments.push(
+            if_expr
+                .syntax()
+                .children_with_tokens()
+                .skip_while(|it| it.as_node() != Some(body.syntax()))
+                .filter_map(SyntaxElement::into_token)
+                .filter(|it| it.kind() == SyntaxKind::COMMENT)
+                .collect(),
+        );
         cond_bodies.push((cond, body));
     }
 
@@ -130,7 +143,17 @@ pub(crate) fn replace_if_let_with_match(acc: &mut Assists, ctx: &AssistContext<'
                     }
                 };
                 let arms = cond_bodies.into_iter().map(make_match_arm).chain(iter::once(else_arm));
-                let match_expr = make::expr_match(scrutinee_to_be_expr, make::match_arm_list(arms));
+                let match_arm_list = make::match_arm_list(arms).clone_for_update();
+                for (arm, comments) in match_arm_list.arms().zip(arm_comments) {
+                    let elements = comments.iter().flat_map(|comment| {
+                        [make::tokens::comment(comment.text()), make::tokens::whitespace("\n    ")]
+                    });
+                    ted::insert_all_raw(
+                        Position::before(arm.syntax()),
+                        elements.map(SyntaxElement::Token).collect(),
+                    );
+                }
+                let match_expr = make::expr_match(scrutinee_to_be_expr, match_arm_list);
                 match_expr.indent(IndentLevel::from_node(if_expr.syntax()))
             };
 
@@ -235,6 +258,11 @@ pub(crate) fn replace_match_with_if_let(acc: &mut Assists, ctx: &AssistContext<'
         first_arm.expr()?,
         second_arm.expr()?,
     )?;
+    let (mut then_comments, mut else_comments) =
+        arm_comments(&match_arm_list).into_iter().collect_tuple()?;
+    if first_arm.expr().as_ref() != Some(&then_expr) {
+        std::mem::swap(&mut then_comments, &mut else_comments);
+    }
     let scrutinee = match_expr.expr()?;
 
     let let_ = match &if_let_pat {
@@ -276,20 +304,68 @@ fn make_block_expr(expr: ast::Expr) -> ast::BlockExpr {
                 }
                 _ => make::expr_let(if_let_pat, scrutinee).into(),
             };
-            let then_block = make_block_expr(then_expr.reset_indent());
-            let else_expr = if is_empty_expr(&else_expr) { None } else { Some(else_expr) };
-            let if_let_expr = make::expr_if(
-                condition,
-                then_block,
-                else_expr.map(make_block_expr).map(ast::ElseBranch::Block),
-            )
-            .indent(IndentLevel::from_node(match_expr.syntax()));
+            let then_block =
+                prepend_comments(make_block_expr(then_expr.reset_indent()), &then_comments);
+            let else_block = match (is_empty_expr(&else_expr), else_comments.is_empty()) {
+                (true, true) => None,
+                (true, false) => Some(make::block_expr(iter::empty(), None)),
+                (false, _) => Some(make_block_expr(else_expr)),
+            };
+            let else_block = else_block.map(|it| prepend_comments(it, &else_comments));
+            let if_let_expr =
+                make::expr_if(condition, then_block, else_block.map(ast::ElseBranch::Block))
+                    .indent(IndentLevel::from_node(match_expr.syntax()));
 
             edit.replace_ast::<ast::Expr>(match_expr.into(), if_let_expr);
         },
     )
 }
 
+/// Returns the comments of each arm of `match_arm_list`: the ones on the lines before the arm, and
+/// the ones following it on the same line.
+fn arm_comments(match_arm_list: &ast::MatchArmList) -> Vec<Vec<SyntaxToken>> {
+    let mut res: Vec<Vec<SyntaxToken>> = Vec::new();
+    let mut pending = Vec::new();
+    let mut same_line_as_arm = false;
+    for element in match_arm_list.syntax().children_with_tokens() {
+        match element {
+            SyntaxElement::Node(node) if ast::MatchArm::can_cast(node.kind()) => {
+                res.push(std::mem::take(&mut pending));
+                same_line_as_arm = true;
+            }
+            SyntaxElement::Token(token) => match token.kind() {
+                SyntaxKind::WHITESPACE if token.text().contains('\n') => same_line_as_arm = false,
+                SyntaxKind::COMMENT => match res.last_mut() {
+                    Some(comments) if same_line_as_arm => comments.push(token),
+                    _ => pending.push(token),
+                },
+                _ => (),
+            },
+            SyntaxElement::Node(_) => (),
+        }
+    }
+    // Comments after the last arm belong to it.
+    if let Some(comments) = res.last_mut() {
+        comments.extend(pending);
+    }
+    res
+}
+
+/// Inserts `comments` at the start of `block`, each on its own line.
+fn prepend_comments(block: ast::BlockExpr, comments: &[SyntaxToken]) -> ast::BlockExpr {
+    if comments.is_empty() {
+        return block;
+    }
+    let block = block.clone_subtree().clone_for_update();
+    if let Some(l_curly) = block.stmt_list().and_then(|it| it.l_curly_token()) {
+        let elements = comments.iter().flat_map(|comment| {
+            [make::tokens::whitespace("\n    "), make::tokens::comment(comment.text())]
+        });
+        ted::insert_all_raw(Position::after(l_curly), elements.map(SyntaxElement::Token).collect());
+    }
+    block
+}
+
 /// Pick the pattern for the if let condition and return the expressions for the `then` body and `else` body in that order.
 fn pick_pattern_and_expr_order(
     sema: &hir::Semantics<'_, RootDatabase>,
@@ -1149,6 +1225,86 @@ fn main() {
         code()
     }
 }
+"#,
+        )
+    }
+
+    #[test]
+    fn if_let_with_match_keeps_comments_between_branches() {
+        check_assist(
+            replace_if_let_with_match,
+            r#"
+//- minicore: option
+fn foo(x: Option<i32>) {
+    $0if let Some(x) = x {
+        println!("{}", x)
+    } // the value is missing
+    else {
+        println!("none")
+    }
+}
+"#,
+            r#"
+fn foo(x: Option<i32>) {
+    match x {
+        Some(x) => println!("{}", x),
+        // the value is missing
+        None => println!("none"),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn match_to_if_let_keeps_arm_comments() {
+        check_assist(
+            replace_match_with_if_let,
+            r#"
+//- minicore: option
+fn foo(x: Option<i32>) {
+    $0match x {
+        // print the value
+        Some(x) => println!("{}", x), // with a newline
+        None => println!("none"), // nothing to print
+    }
+}
+"#,
+            r#"
+fn foo(x: Option<i32>) {
+    if let Some(x) = x {
+        // print the value
+        // with a newline
+        println!("{}", x)
+    } else {
+        // nothing to print
+        println!("none")
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn match_to_if_let_keeps_comments_of_empty_arm() {
+        check_assist(
+            replace_match_with_if_let,
+            r#"
+fn main() {
+    $0match path.strip_prefix(root_path) {
+        Ok(rel_path) => println!("{}", rel_path),
+        _ => (), // not under the root
+    }
+}
+"#,
+            r#"
+fn main() {
+    if let Ok(rel_path) = path.strip_prefix(root_path) {
+        println!("{}", rel_path)
+    } else {
+        // not under the root
+    }
+}
 "#,
         )
     }
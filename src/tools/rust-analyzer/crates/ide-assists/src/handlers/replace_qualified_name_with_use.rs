@@ -8,11 +8,12 @@
     match_ast, ted, AstNode, SyntaxNode,
 };
 
-use crate::{AssistContext, AssistId, AssistKind, Assists};
+use crate::{assist_context::SourceChangeBuilder, AssistContext, AssistId, AssistKind, Assists};
 
 // Assist: replace_qualified_name_with_use
 //
-// Adds a use statement for a given fully-qualified name.
+// Adds a use statement for a given fully-qualified name, and shortens the other occurrences of the
+// name in the file. Modules with such occurrences get a use statement of their own.
 //
 // ```
 // # mod std { pub mod collections { pub struct HashMap<T, U>(T, U); } }
@@ -42,11 +43,48 @@ pub(crate) fn replace_qualified_name_with_use(
     }
 
     // only offer replacement for non assoc items
-    match ctx.sema.resolve_path(&path)? {
-        hir::PathResolution::Def(def) if def.as_assoc_item(ctx.sema.db).is_none() => (),
+    let def = match ctx.sema.resolve_path(&path)? {
+        hir::PathResolution::Def(def) if def.as_assoc_item(ctx.sema.db).is_none() => def,
         _ => return None,
-    }
-    // then search for an import for the first path segment of what we want to replace
+    };
+    let import = import_path(ctx, &path)?;
+
+    let scope = ImportScope::find_insert_use_container(path.syntax(), &ctx.sema)?;
+    let (in_scope, other_scopes) = other_occurrences(ctx, &path, def, &scope);
+    let target = path.syntax().text_range();
+    acc.add(
+        AssistId("replace_qualified_name_with_use", AssistKind::RefactorRewrite),
+        "Replace qualified path with use",
+        target,
+        |builder| {
+            // Everything is made mutable before editing, which would invalidate later lookups.
+            let other_scopes = other_scopes
+                .into_iter()
+                .map(|(scope, import, paths)| {
+                    let paths = paths.into_iter().map(|it| builder.make_mut(it)).collect();
+                    (make_scope_mut(builder, scope), import, paths)
+                })
+                .collect::<Vec<(_, _, Vec<_>)>>();
+            let in_scope = in_scope.into_iter().map(|it| builder.make_mut(it)).collect::<Vec<_>>();
+            let scope = make_scope_mut(builder, scope);
+
+            // Now that we've brought the name into scope, re-qualify all paths that could be
+            // affected (that is, all paths inside the node we added the `use` to).
+            shorten_paths(scope.as_syntax_node(), &path);
+            in_scope.iter().for_each(shorten_path);
+            insert_use(&scope, import, &ctx.config.insert_use);
+
+            for (scope, import, paths) in other_scopes {
+                paths.iter().for_each(shorten_path);
+                insert_use(&scope, import, &ctx.config.insert_use);
+            }
+        },
+    )
+}
+
+/// The path to import the item `path` refers to with.
+fn import_path(ctx: &AssistContext<'_>, path: &ast::Path) -> Option<ast::Path> {
+    // search for an import for the first path segment of what we want to replace
     // that way it is less likely that we import the item from a different location due re-exports
     let module = match ctx.sema.resolve_path(&path.first_qualifier_or_self())? {
         hir::PathResolution::Def(module @ hir::ModuleDef::Module(_)) => module,
@@ -73,30 +111,77 @@ pub(crate) fn replace_qualified_name_with_use(
         })
         .flatten();
 
-    let scope = ImportScope::find_insert_use_container(path.syntax(), &ctx.sema)?;
-    let target = path.syntax().text_range();
-    acc.add(
-        AssistId("replace_qualified_name_with_use", AssistKind::RefactorRewrite),
-        "Replace qualified path with use",
-        target,
-        |builder| {
-            // Now that we've brought the name into scope, re-qualify all paths that could be
-            // affected (that is, all paths inside the node we added the `use` to).
-            let scope = match scope {
-                ImportScope::File(it) => ImportScope::File(builder.make_mut(it)),
-                ImportScope::Module(it) => ImportScope::Module(builder.make_mut(it)),
-                ImportScope::Block(it) => ImportScope::Block(builder.make_mut(it)),
-            };
-            shorten_paths(scope.as_syntax_node(), &path);
-            let path = drop_generic_args(&path);
-            // stick the found import in front of the to be replaced path
-            let path = match path_to_qualifier.and_then(|it| mod_path_to_ast(&it).qualifier()) {
-                Some(qualifier) => make::path_concat(qualifier, path),
-                None => path,
-            };
-            insert_use(&scope, path, &ctx.config.insert_use);
-        },
-    )
+    let path = drop_generic_args(path);
+    // stick the found import in front of the to be replaced path
+    Some(match path_to_qualifier.and_then(|it| mod_path_to_ast(&it).qualifier()) {
+        Some(qualifier) => make::path_concat(qualifier, path),
+        None => path,
+    })
+}
+
+/// Finds the other qualified paths to `def` in the file. Returns the ones in `scope` that are
+/// spelled differently from `path`, and the ones outside of it grouped by the scope to insert their
+/// `use` into, along with the path to import.
+fn other_occurrences(
+    ctx: &AssistContext<'_>,
+    path: &ast::Path,
+    def: hir::ModuleDef,
+    scope: &ImportScope,
+) -> (Vec<ast::Path>, Vec<(ImportScope, ast::Path, Vec<ast::Path>)>) {
+    let mut in_scope = Vec::new();
+    let mut res: Vec<(ImportScope, ast::Path, Vec<ast::Path>)> = Vec::new();
+    let Some(name) = path.segment().and_then(|it| it.name_ref()) else { return (in_scope, res) };
+    let source_file = ctx.sema.parse(ctx.file_id());
+    for other in source_file.syntax().descendants().filter_map(ast::Path::cast) {
+        let same_name =
+            other.segment().and_then(|it| it.name_ref()).is_some_and(|it| it.text() == name.text());
+        if !same_name
+            || other.qualifier().is_none()
+            || other.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind()))
+        {
+            continue;
+        }
+        if !matches!(ctx.sema.resolve_path(&other), Some(hir::PathResolution::Def(it)) if it == def)
+        {
+            continue;
+        }
+        let Some(other_scope) = ImportScope::find_insert_use_container(other.syntax(), &ctx.sema)
+        else {
+            continue;
+        };
+        if other_scope.as_syntax_node() == scope.as_syntax_node() {
+            // Paths spelled like the selected one are shortened along with it.
+            if !path_eq_no_generics(other.clone(), path.clone()) {
+                in_scope.push(other);
+            }
+            continue;
+        }
+        // The name mustn't already refer to something else there.
+        let name_resolution = ctx
+            .sema
+            .scope(other.syntax())
+            .and_then(|it| it.speculative_resolve(&make::ext::ident_path(&name.text())));
+        if name_resolution.is_some_and(|it| it != hir::PathResolution::Def(def)) {
+            continue;
+        }
+        match res.iter_mut().find(|(it, ..)| it.as_syntax_node() == other_scope.as_syntax_node()) {
+            Some((.., paths)) => paths.push(other),
+            None => {
+                if let Some(import) = import_path(ctx, &other) {
+                    res.push((other_scope, import, vec![other]));
+                }
+            }
+        }
+    }
+    (in_scope, res)
+}
+
+fn make_scope_mut(builder: &mut SourceChangeBuilder, scope: ImportScope) -> ImportScope {
+    match scope {
+        ImportScope::File(it) => ImportScope::File(builder.make_mut(it)),
+        ImportScope::Module(it) => ImportScope::Module(builder.make_mut(it)),
+        ImportScope::Block(it) => ImportScope::Block(builder.make_mut(it)),
+    }
 }
 
 fn drop_generic_args(path: &ast::Path) -> ast::Path {
@@ -133,16 +218,18 @@ fn maybe_replace_path(path: ast::Path, target: ast::Path) -> Option<()> {
     if !path_eq_no_generics(path.clone(), target) {
         return None;
     }
+    shorten_path(&path);
+    Some(())
+}
 
-    // Shorten `path`, leaving only its last segment.
+/// Shortens `path`, leaving only its last segment.
+fn shorten_path(path: &ast::Path) {
     if let Some(parent) = path.qualifier() {
         ted::remove(parent.syntax());
     }
     if let Some(double_colon) = path.coloncolon_token() {
         ted::remove(&double_colon);
     }
-
-    Some(())
 }
 
 fn path_eq_no_generics(lhs: ast::Path, rhs: ast::Path) -> bool {
@@ -437,4 +524,101 @@ fn main() {
 ",
         );
     }
+
+    #[test]
+    fn replaces_in_other_modules_of_the_file() {
+        check_assist(
+            replace_qualified_name_with_use,
+            r"
+mod std { pub mod fmt { pub trait Debug {} } }
+fn main() {
+    std::fmt::Debug$0;
+}
+
+mod sub {
+    fn f() {
+        super::std::fmt::Debug;
+        let x: super::std::fmt::Debug = super::std::fmt::Debug;
+    }
+}
+    ",
+            r"
+use std::fmt::Debug;
+
+mod std { pub mod fmt { pub trait Debug {} } }
+fn main() {
+    Debug;
+}
+
+mod sub {
+    use super::std::fmt::Debug;
+
+    fn f() {
+        Debug;
+        let x: Debug = Debug;
+    }
+}
+    ",
+        );
+    }
+
+    #[test]
+    fn replaces_other_spellings_in_the_same_scope() {
+        check_assist(
+            replace_qualified_name_with_use,
+            r"
+mod std { pub mod fmt { pub trait Debug {} } }
+fn main() {
+    std::fmt::Debug$0;
+    crate::std::fmt::Debug;
+    self::std::fmt::Debug;
+}
+    ",
+            r"
+use std::fmt::Debug;
+
+mod std { pub mod fmt { pub trait Debug {} } }
+fn main() {
+    Debug;
+    Debug;
+    Debug;
+}
+    ",
+        );
+    }
+
+    #[test]
+    fn skips_other_modules_where_the_name_is_taken() {
+        check_assist(
+            replace_qualified_name_with_use,
+            r"
+mod std { pub mod fmt { pub trait Debug {} } }
+fn main() {
+    std::fmt::Debug$0;
+}
+
+mod sub {
+    struct Debug;
+    fn f() {
+        super::std::fmt::Debug;
+    }
+}
+    ",
+            r"
+use std::fmt::Debug;
+
+mod std { pub mod fmt { pub trait Debug {} } }
+fn main() {
+    Debug;
+}
+
+mod sub {
+    struct Debug;
+    fn f() {
+        super::std::fmt::Debug;
+    }
+}
+    ",
+        );
+    }
 }
@@ -0,0 +1,405 @@
+use std::mem;
+
+use itertools::Itertools;
+use syntax::{
+    ast::{self, edit::IndentLevel, HasAttrs},
+    AstNode, NodeOrToken,
+    SyntaxKind::{ATTR, COMMENT, WHITESPACE},
+    TextRange, TextSize,
+};
+
+use crate::{AssistContext, AssistId, AssistKind, Assists};
+
+const REGION_START: &str = "// region:";
+const REGION_END: &str = "// endregion";
+
+// Assist: split_impl
+//
+// Splits an inherent impl into several ones, either moving the selected items into an impl of
+// their own or starting a new impl at each `// region:` marker. All the new impls keep the
+// attributes of the original one.
+//
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// impl$0 Point {
+//     pub fn new(x: i32, y: i32) -> Self {
+//         Point { x, y }
+//     }
+//
+//     // region: getters
+//     pub fn x(&self) -> i32 {
+//         self.x
+//     }
+//     // endregion
+// }
+// ```
+// ->
+// ```
+// struct Point { x: i32, y: i32 }
+//
+// impl Point {
+//     pub fn new(x: i32, y: i32) -> Self {
+//         Point { x, y }
+//     }
+// }
+//
+// impl Point {
+//     // region: getters
+//     pub fn x(&self) -> i32 {
+//         self.x
+//     }
+//     // endregion
+// }
+// ```
+pub(crate) fn split_impl(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let impl_ = ctx.find_node_at_offset::<ast::Impl>()?;
+    // A trait can only be implemented once.
+    if impl_.trait_().is_some() {
+        return None;
+    }
+    let item_list = impl_.assoc_item_list()?;
+    let list_range = item_list.syntax().text_range();
+    let pieces = pieces(&item_list);
+
+    let (label, parts) = if ctx.has_empty_selection() {
+        // Only offered on the header, not to get in the way inside of the items.
+        if ctx.offset() >= list_range.start() {
+            return None;
+        }
+        ("Split impl at region markers", parts_at_region_markers(pieces))
+    } else {
+        let selection = ctx.selection_trimmed();
+        if !list_range.contains_range(selection) {
+            return None;
+        }
+        let (selected, rest): (Vec<_>, Vec<_>) =
+            pieces.into_iter().partition(|piece| selection.contains_range(piece.range));
+        ("Split selected items into a new impl", vec![rest, selected])
+    };
+    if parts.iter().filter(|part| part.iter().any(|piece| piece.is_item)).count() < 2 {
+        return None;
+    }
+
+    let target = impl_.syntax().text_range();
+    acc.add(AssistId("split_impl", AssistKind::RefactorRewrite), label, target, |builder| {
+        let indent = IndentLevel::from_node(impl_.syntax());
+        let text = impl_.syntax().to_string();
+        let offset_in_impl = |offset: TextSize| usize::from(offset - target.start());
+        let header_start = impl_
+            .syntax()
+            .children_with_tokens()
+            .find(|it| !matches!(it.kind(), ATTR | COMMENT | WHITESPACE))
+            .map_or(target.start(), |it| it.text_range().start());
+        // Doc comments stay with the first impl, attributes are repeated on all of them.
+        let leading = &text[..offset_in_impl(header_start)];
+        let attrs = impl_.attrs().map(|attr| format!("{attr}\n{indent}")).join("");
+        let header = &text[offset_in_impl(header_start)..offset_in_impl(list_range.start())];
+
+        let impls = parts
+            .iter()
+            .filter(|part| !part.is_empty())
+            .sorted_by_key(|part| part[0].range.start())
+            .enumerate()
+            .map(|(idx, part)| {
+                let prefix = if idx == 0 { leading } else { attrs.as_str() };
+                let body = part
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, piece)| match idx {
+                        0 => format!("\n{}{}", indent + 1, piece.text),
+                        _ => format!("{}{}", piece.leading_ws, piece.text),
+                    })
+                    .join("");
+                format!("{prefix}{header}{{{body}\n{indent}}}")
+            })
+            .join(&format!("\n\n{indent}"));
+        builder.replace(target, impls);
+    })
+}
+
+/// An item of the impl, or a comment in between, along with the whitespace preceding it.
+#[derive(Debug)]
+struct Piece {
+    range: TextRange,
+    text: String,
+    leading_ws: String,
+    is_item: bool,
+}
+
+impl Piece {
+    fn is_region_start(&self) -> bool {
+        !self.is_item && self.text.starts_with(REGION_START)
+    }
+
+    fn is_region_end(&self) -> bool {
+        !self.is_item && self.text.starts_with(REGION_END)
+    }
+}
+
+fn pieces(item_list: &ast::AssocItemList) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut leading_ws = String::new();
+    for element in item_list.syntax().children_with_tokens() {
+        let node = match element {
+            NodeOrToken::Token(token) if token.kind() == WHITESPACE => {
+                leading_ws = token.text().to_owned();
+                continue;
+            }
+            NodeOrToken::Token(token) if token.kind() == COMMENT => {
+                pieces.push(Piece {
+                    range: token.text_range(),
+                    text: token.text().to_owned(),
+                    leading_ws: mem::take(&mut leading_ws),
+                    is_item: false,
+                });
+                continue;
+            }
+            NodeOrToken::Token(_) => continue,
+            NodeOrToken::Node(node) => node,
+        };
+
+        // Comments right above an item are part of it, region markers among them are split off.
+        let trivia = node
+            .children_with_tokens()
+            .map_while(NodeOrToken::into_token)
+            .take_while(|it| matches!(it.kind(), COMMENT | WHITESPACE))
+            .collect_vec();
+        let split = trivia
+            .iter()
+            .rposition(|it| {
+                it.text().starts_with(REGION_START) || it.text().starts_with(REGION_END)
+            })
+            .map_or(0, |idx| idx + 1);
+        for token in &trivia[..split] {
+            if token.kind() == WHITESPACE {
+                leading_ws = token.text().to_owned();
+                continue;
+            }
+            pieces.push(Piece {
+                range: token.text_range(),
+                text: token.text().to_owned(),
+                leading_ws: mem::take(&mut leading_ws),
+                is_item: false,
+            });
+        }
+        let mut start = node.text_range().start();
+        if let Some(last_marker) = trivia[..split].last() {
+            start = last_marker.text_range().end();
+            if let Some(ws) = trivia.get(split).filter(|it| it.kind() == WHITESPACE) {
+                leading_ws = ws.text().to_owned();
+                start = ws.text_range().end();
+            }
+        }
+        let node_text = node.to_string();
+        pieces.push(Piece {
+            range: TextRange::new(start, node.text_range().end()),
+            text: node_text[usize::from(start - node.text_range().start())..].to_owned(),
+            leading_ws: mem::take(&mut leading_ws),
+            is_item: true,
+        });
+    }
+    pieces
+}
+
+/// Groups the pieces into the ones outside of any region, followed by one group for each top
+/// level region.
+fn parts_at_region_markers(pieces: Vec<Piece>) -> Vec<Vec<Piece>> {
+    let mut parts = vec![Vec::new()];
+    let mut depth = 0usize;
+    for piece in pieces {
+        if piece.is_region_start() {
+            if depth == 0 {
+                parts.push(Vec::new());
+            }
+            depth += 1;
+        }
+        let part = if depth == 0 { 0 } else { parts.len() - 1 };
+        if piece.is_region_end() {
+            depth = depth.saturating_sub(1);
+        }
+        parts[part].push(piece);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn split_at_region_markers() {
+        check_assist(
+            split_impl,
+            r#"
+struct S;
+
+impl$0 S {
+    // region: constructors
+
+    fn new() -> Self {
+        S
+    }
+    // endregion
+
+    fn a(&self) {}
+
+    // region: getters
+    fn b(&self) {}
+
+    fn c(&self) {}
+    // endregion
+}
+"#,
+            r#"
+struct S;
+
+impl S {
+    // region: constructors
+
+    fn new() -> Self {
+        S
+    }
+    // endregion
+}
+
+impl S {
+    fn a(&self) {}
+}
+
+impl S {
+    // region: getters
+    fn b(&self) {}
+
+    fn c(&self) {}
+    // endregion
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_selected_items_keeps_attributes_and_docs() {
+        check_assist(
+            split_impl,
+            r#"
+struct S<T>(T);
+
+/// Docs of the impl.
+#[cfg(test)]
+impl<T: Clone> S<T> {
+    fn a(&self) {}
+
+    $0/// Docs.
+    #[inline]
+    fn b(&self) {}
+
+    fn c(&self) {}$0
+}
+"#,
+            r#"
+struct S<T>(T);
+
+/// Docs of the impl.
+#[cfg(test)]
+impl<T: Clone> S<T> {
+    fn a(&self) {}
+}
+
+#[cfg(test)]
+impl<T: Clone> S<T> {
+    /// Docs.
+    #[inline]
+    fn b(&self) {}
+
+    fn c(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn split_nested_impl() {
+        check_assist(
+            split_impl,
+            r#"
+struct S;
+
+mod m {
+    impl$0 super::S {
+        fn a(&self) {}
+
+        // region: more
+        fn b(&self) {}
+        // endregion
+    }
+}
+"#,
+            r#"
+struct S;
+
+mod m {
+    impl super::S {
+        fn a(&self) {}
+    }
+
+    impl super::S {
+        // region: more
+        fn b(&self) {}
+        // endregion
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_trait_impls() {
+        check_assist_not_applicable(
+            split_impl,
+            r#"
+struct S;
+trait T {
+    fn a(&self);
+    fn b(&self);
+}
+
+impl T for S {
+    $0fn a(&self) {}$0
+
+    fn b(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_nothing_would_be_split_off() {
+        check_assist_not_applicable(
+            split_impl,
+            r#"
+struct S;
+
+impl$0 S {
+    fn a(&self) {}
+
+    fn b(&self) {}
+}
+"#,
+        );
+        check_assist_not_applicable(
+            split_impl,
+            r#"
+struct S;
+
+impl S {
+    $0fn a(&self) {}
+
+    fn b(&self) {}$0
+}
+"#,
+        );
+    }
+}
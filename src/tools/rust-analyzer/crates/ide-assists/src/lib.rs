@@ -62,17 +62,23 @@
 
 mod assist_config;
 mod assist_context;
+mod bulk;
 #[cfg(test)]
 mod tests;
 pub mod utils;
 
 use hir::Semantics;
-use ide_db::{base_db::FileRange, RootDatabase};
+use ide_db::{
+    base_db::{salsa::ParallelDatabase, FileRange},
+    RootDatabase,
+};
+use rayon::prelude::*;
 use syntax::TextRange;
 
-pub(crate) use crate::assist_context::{AssistContext, Assists};
+pub(crate) use crate::assist_context::{AssistContext, Assists, UsageCache};
 
-pub use assist_config::AssistConfig;
+pub use assist_config::{AssistConfig, ExtractModuleVisibility, MatchArmFields, ModuleFileLayout};
+pub use bulk::assists_in_file;
 pub use ide_db::assists::{
     Assist, AssistId, AssistKind, AssistResolveStrategy, GroupLabel, SingleResolve,
 };
@@ -87,17 +93,32 @@ pub fn assists(
     resolve: AssistResolveStrategy,
     range: FileRange,
 ) -> Vec<Assist> {
-    let sema = Semantics::new(db);
-    let ctx = AssistContext::new(sema, config, range);
-    let mut acc = Assists::new(&ctx, resolve);
-    handlers::all().iter().for_each(|handler| {
-        handler(&mut acc, &ctx);
-    });
-    acc.finish()
+    // Handlers only read the database, so they are split into chunks that run in parallel, each
+    // with its own snapshot and context. The contexts share their usage searches. Chunks are
+    // merged in order, which keeps the result the same as running all handlers one after another.
+    let handlers = handlers::all();
+    let usages = UsageCache::default();
+    let chunk_size = handlers.len().div_ceil(rayon::current_num_threads());
+    let snapshots: Vec<_> = handlers.chunks(chunk_size).map(|_| db.snapshot()).collect();
+    let mut res: Vec<Assist> = handlers
+        .par_chunks(chunk_size)
+        .zip(snapshots)
+        .flat_map_iter(|(handlers, db)| {
+            let sema = Semantics::new(&*db);
+            let ctx = AssistContext::with_usage_cache(sema, config, range, usages.clone());
+            let mut acc = Assists::new(&ctx, resolve.clone());
+            handlers.iter().for_each(|handler| {
+                handler(&mut acc, &ctx);
+            });
+            acc.finish()
+        })
+        .collect();
+    res.sort_by_key(|assist| assist.target.len());
+    res
 }
 
 mod handlers {
-    use crate::{AssistContext, Assists};
+    use crate::{bulk, bulk::BulkAssist, AssistContext, Assists};
 
     pub(crate) type Handler = fn(&mut Assists, &AssistContext<'_>) -> Option<()>;
 
@@ -122,8 +143,11 @@ mod handlers {
     mod convert_iter_for_each_to_for;
     mod convert_let_else_to_match;
     mod convert_match_to_let_else;
+    mod convert_module_layout;
     mod convert_named_struct_to_tuple_struct;
     mod convert_nested_function_to_closure;
+    mod convert_state_machine_to_methods;
+    mod convert_static_mut;
     mod convert_to_guarded_return;
     mod convert_tuple_return_type_to_struct;
     mod convert_tuple_struct_to_named_struct;
@@ -152,13 +176,16 @@ mod handlers {
     mod generate_deref;
     mod generate_derive;
     mod generate_documentation_template;
+    mod generate_enum_accessors;
     mod generate_enum_is_method;
     mod generate_enum_projection_method;
     mod generate_enum_variant;
+    mod generate_error_enum;
     mod generate_from_impl_for_enum;
     mod generate_function;
     mod generate_getter_or_setter;
     mod generate_impl;
+    mod generate_into_iterator_impls;
     mod generate_is_empty_from_len;
     mod generate_mut_trait_impl;
     mod generate_new;
@@ -167,6 +194,7 @@ mod handlers {
     mod inline_const_as_literal;
     mod inline_local_variable;
     mod inline_macro;
+    mod inline_module;
     mod inline_type_alias;
     mod into_to_qualified_from;
     mod introduce_named_generic;
@@ -180,6 +208,7 @@ mod handlers {
     mod move_from_mod_rs;
     mod move_guard;
     mod move_module_to_file;
+    mod move_tests_to_file;
     mod move_to_mod_rs;
     mod normalize_import;
     mod number_representation;
@@ -207,6 +236,7 @@ mod handlers {
     mod replace_try_expr_with_match;
     mod replace_turbofish_with_explicit_type;
     mod sort_items;
+    mod split_impl;
     mod split_import;
     mod term_search;
     mod toggle_ignore;
@@ -236,6 +266,7 @@ pub(crate) fn all() -> &'static [Handler] {
             bind_unused_param::bind_unused_param,
             bool_to_enum::bool_to_enum,
             change_visibility::change_visibility,
+            change_visibility::change_visibility_to_minimal,
             convert_bool_then::convert_bool_then_to_if,
             convert_bool_then::convert_if_to_bool_then,
             convert_comment_block::convert_comment_block,
@@ -246,9 +277,12 @@ pub(crate) fn all() -> &'static [Handler] {
             convert_iter_for_each_to_for::convert_for_loop_with_for_each,
             convert_let_else_to_match::convert_let_else_to_match,
             convert_match_to_let_else::convert_match_to_let_else,
+            convert_module_layout::convert_module_layout,
             convert_tuple_return_type_to_struct::convert_tuple_return_type_to_struct,
             convert_named_struct_to_tuple_struct::convert_named_struct_to_tuple_struct,
             convert_nested_function_to_closure::convert_nested_function_to_closure,
+            convert_state_machine_to_methods::convert_state_machine_to_methods,
+            convert_static_mut::convert_static_mut,
             convert_to_guarded_return::convert_to_guarded_return,
             convert_tuple_struct_to_named_struct::convert_tuple_struct_to_named_struct,
             convert_two_arm_bool_match_to_matches_macro::convert_two_arm_bool_match_to_matches_macro,
@@ -272,28 +306,34 @@ pub(crate) fn all() -> &'static [Handler] {
             generate_derive::generate_derive,
             generate_documentation_template::generate_documentation_template,
             generate_documentation_template::generate_doc_example,
+            generate_enum_accessors::generate_enum_accessors,
             generate_enum_is_method::generate_enum_is_method,
             generate_enum_projection_method::generate_enum_as_method,
             generate_enum_projection_method::generate_enum_try_into_method,
             generate_enum_variant::generate_enum_variant,
+            generate_error_enum::generate_error_enum,
             generate_from_impl_for_enum::generate_from_impl_for_enum,
             generate_function::generate_function,
             generate_impl::generate_impl,
             generate_impl::generate_trait_impl,
+            generate_into_iterator_impls::generate_into_iterator_impls,
             generate_mut_trait_impl::generate_mut_trait_impl,
             generate_is_empty_from_len::generate_is_empty_from_len,
             generate_new::generate_new,
+            generate_new::generate_builder,
             generate_trait_from_impl::generate_trait_from_impl,
             inline_call::inline_call,
             inline_call::inline_into_callers,
             inline_const_as_literal::inline_const_as_literal,
             inline_local_variable::inline_local_variable,
+            inline_module::inline_module,
             inline_type_alias::inline_type_alias,
             inline_type_alias::inline_type_alias_uses,
             into_to_qualified_from::into_to_qualified_from,
             introduce_named_generic::introduce_named_generic,
             introduce_named_lifetime::introduce_named_lifetime,
             invert_if::invert_if,
+            merge_imports::merge_all_imports,
             merge_imports::merge_imports,
             merge_match_arms::merge_match_arms,
             merge_nested_if::merge_nested_if,
@@ -302,6 +342,7 @@ pub(crate) fn all() -> &'static [Handler] {
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
             move_module_to_file::move_module_to_file,
+            move_tests_to_file::move_tests_to_file,
             move_to_mod_rs::move_to_mod_rs,
             move_from_mod_rs::move_from_mod_rs,
             normalize_import::normalize_import,
@@ -334,6 +375,7 @@ pub(crate) fn all() -> &'static [Handler] {
             replace_arith_op::replace_arith_with_checked,
             replace_arith_op::replace_arith_with_saturating,
             sort_items::sort_items,
+            split_impl::split_impl,
             split_import::split_import,
             term_search::term_search,
             toggle_ignore::toggle_ignore,
@@ -362,6 +404,7 @@ pub(crate) fn all() -> &'static [Handler] {
             extract_function::extract_function,
             extract_module::extract_module,
             //
+            generate_getter_or_setter::generate_accessors,
             generate_getter_or_setter::generate_getter,
             generate_getter_or_setter::generate_getter_mut,
             generate_getter_or_setter::generate_setter,
@@ -374,4 +417,34 @@ pub(crate) fn all() -> &'static [Handler] {
             // sorted list above?
         ]
     }
+
+    /// The assists [`crate::assists_in_file`] applies to all the places of a file at once.
+    pub(crate) fn bulk() -> &'static [BulkAssist] {
+        &[
+            BulkAssist {
+                id: "convert_to_guarded_return",
+                label: "Convert to guarded returns everywhere in file",
+                handler: convert_to_guarded_return::convert_to_guarded_return,
+                sites: bulk::guarded_return_sites,
+            },
+            BulkAssist {
+                id: "reformat_number_literal",
+                label: "Add digit separators to all number literals in file",
+                handler: number_representation::reformat_number_literal,
+                sites: bulk::number_literal_sites,
+            },
+            BulkAssist {
+                id: "remove_dbg",
+                label: "Remove all dbg!() in file",
+                handler: remove_dbg::remove_dbg,
+                sites: bulk::dbg_sites,
+            },
+            BulkAssist {
+                id: "replace_qualified_name_with_use",
+                label: "Replace all qualified paths with use in file",
+                handler: replace_qualified_name_with_use::replace_qualified_name_with_use,
+                sites: bulk::qualified_path_sites,
+            },
+        ]
+    }
 }
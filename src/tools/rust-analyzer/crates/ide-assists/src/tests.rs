@@ -4,7 +4,8 @@
 use hir::Semantics;
 use ide_db::{
     base_db::{FileId, FileRange, SourceDatabaseExt},
-    imports::insert_use::{ImportGranularity, InsertUseConfig},
+    defs::Definition,
+    imports::insert_use::{ImportGranularity, ImportGroupStyle, InsertUseConfig},
     source_change::FileSystemEdit,
     RootDatabase, SnippetCap,
 };
@@ -14,8 +15,9 @@
 use test_utils::{assert_eq_text, extract_offset};
 
 use crate::{
-    assists, handlers::Handler, Assist, AssistConfig, AssistContext, AssistKind,
-    AssistResolveStrategy, Assists, SingleResolve,
+    assists, assists_in_file, handlers::Handler, Assist, AssistConfig, AssistContext, AssistKind,
+    AssistResolveStrategy, Assists, ExtractModuleVisibility, MatchArmFields, ModuleFileLayout,
+    SingleResolve, UsageCache,
 };
 
 pub(crate) const TEST_CONFIG: AssistConfig = AssistConfig {
@@ -26,11 +28,17 @@
         prefix_kind: hir::PrefixKind::Plain,
         enforce_granularity: true,
         group: true,
+        group_style: ImportGroupStyle::Default,
         skip_glob_imports: true,
     },
     prefer_no_std: false,
     prefer_prelude: true,
+    prefer_reexports: true,
     assist_emit_must_use: false,
+    extract_module_visibility: ExtractModuleVisibility::PubSuper,
+    match_arm_fields: MatchArmFields::BindRecordFields,
+    impl_members_copy_docs: false,
+    module_file_layout: ModuleFileLayout::File,
 };
 
 pub(crate) const TEST_CONFIG_NO_SNIPPET_CAP: AssistConfig = AssistConfig {
@@ -41,11 +49,17 @@
         prefix_kind: hir::PrefixKind::Plain,
         enforce_granularity: true,
         group: true,
+        group_style: ImportGroupStyle::Default,
         skip_glob_imports: true,
     },
     prefer_no_std: false,
     prefer_prelude: true,
+    prefer_reexports: true,
     assist_emit_must_use: false,
+    extract_module_visibility: ExtractModuleVisibility::PubSuper,
+    match_arm_fields: MatchArmFields::BindRecordFields,
+    impl_members_copy_docs: false,
+    module_file_layout: ModuleFileLayout::File,
 };
 
 pub(crate) const TEST_CONFIG_IMPORT_ONE: AssistConfig = AssistConfig {
@@ -56,11 +70,17 @@
         prefix_kind: hir::PrefixKind::Plain,
         enforce_granularity: true,
         group: true,
+        group_style: ImportGroupStyle::Default,
         skip_glob_imports: true,
     },
     prefer_no_std: false,
     prefer_prelude: true,
+    prefer_reexports: true,
     assist_emit_must_use: false,
+    extract_module_visibility: ExtractModuleVisibility::PubSuper,
+    match_arm_fields: MatchArmFields::BindRecordFields,
+    impl_members_copy_docs: false,
+    module_file_layout: ModuleFileLayout::File,
 };
 
 pub(crate) fn with_single_file(text: &str) -> (RootDatabase, FileId) {
@@ -86,6 +106,7 @@ pub(crate) fn check_assist_no_snippet_cap(
         ra_fixture_before,
         ExpectedResult::After(&ra_fixture_after),
         None,
+        None,
     );
 }
 
@@ -102,6 +123,7 @@ pub(crate) fn check_assist_import_one(
         ra_fixture_before,
         ExpectedResult::After(&ra_fixture_after),
         None,
+        None,
     );
 }
 
@@ -117,6 +139,63 @@ pub(crate) fn check_assist_by_label(
     check(assist, ra_fixture_before, ExpectedResult::After(&ra_fixture_after), Some(label));
 }
 
+#[track_caller]
+pub(crate) fn check_assist_by_label_with_config(
+    assist: Handler,
+    config: AssistConfig,
+    ra_fixture_before: &str,
+    ra_fixture_after: &str,
+    label: &str,
+) {
+    let ra_fixture_after = trim_indent(ra_fixture_after);
+    check_with_config(
+        config,
+        assist,
+        ra_fixture_before,
+        ExpectedResult::After(&ra_fixture_after),
+        Some(label),
+        None,
+    );
+}
+
+/// Checks an assist that needs a `parameter` from the client to be resolved.
+#[track_caller]
+pub(crate) fn check_assist_with_parameter(
+    assist: Handler,
+    parameter: &str,
+    ra_fixture_before: &str,
+    ra_fixture_after: &str,
+    label: &str,
+) {
+    let ra_fixture_after = trim_indent(ra_fixture_after);
+    check_with_config(
+        TEST_CONFIG,
+        assist,
+        ra_fixture_before,
+        ExpectedResult::After(&ra_fixture_after),
+        Some(label),
+        Some(parameter),
+    );
+}
+
+/// Checks that an assist needing a `parameter` from the client isn't resolved with this one.
+#[track_caller]
+pub(crate) fn check_assist_not_applicable_with_parameter(
+    assist: Handler,
+    parameter: &str,
+    ra_fixture: &str,
+    label: &str,
+) {
+    check_with_config(
+        TEST_CONFIG,
+        assist,
+        ra_fixture,
+        ExpectedResult::NotApplicable,
+        Some(label),
+        Some(parameter),
+    );
+}
+
 // FIXME: instead of having a separate function here, maybe use
 // `extract_ranges` and mark the target as `<target> </target>` in the
 // fixture?
@@ -143,6 +222,7 @@ pub(crate) fn check_assist_not_applicable_for_import_one(assist: Handler, ra_fix
         ra_fixture,
         ExpectedResult::NotApplicable,
         None,
+        None,
     );
 }
 
@@ -202,7 +282,7 @@ enum ExpectedResult<'a> {
 
 #[track_caller]
 fn check(handler: Handler, before: &str, expected: ExpectedResult<'_>, assist_label: Option<&str>) {
-    check_with_config(TEST_CONFIG, handler, before, expected, assist_label);
+    check_with_config(TEST_CONFIG, handler, before, expected, assist_label, None);
 }
 
 #[track_caller]
@@ -212,6 +292,7 @@ fn check_with_config(
     before: &str,
     expected: ExpectedResult<'_>,
     assist_label: Option<&str>,
+    parameter: Option<&str>,
 ) {
     let (mut db, file_with_caret_id, range_or_offset) = RootDatabase::with_range_or_offset(before);
     db.enable_proc_attr_macros();
@@ -221,9 +302,28 @@ fn check_with_config(
 
     let sema = Semantics::new(&db);
     let ctx = AssistContext::new(sema, &config, frange);
-    let resolve = match expected {
-        ExpectedResult::Unresolved => AssistResolveStrategy::None,
-        _ => AssistResolveStrategy::All,
+    let resolve = match (&expected, parameter) {
+        (ExpectedResult::Unresolved, _) => AssistResolveStrategy::None,
+        (_, Some(parameter)) => {
+            // The parameter is meant for a single assist, so look up which one that is first.
+            let mut acc = Assists::new(&ctx, AssistResolveStrategy::None);
+            handler(&mut acc, &ctx);
+            let id = acc
+                .finish()
+                .into_iter()
+                .find(|it| match assist_label {
+                    Some(label) => it.label == label,
+                    None => true,
+                })
+                .expect("code action is not applicable")
+                .id;
+            AssistResolveStrategy::Single(SingleResolve {
+                assist_id: id.0.to_owned(),
+                assist_kind: id.1,
+                parameter: Some(parameter.to_owned()),
+            })
+        }
+        (_, None) => AssistResolveStrategy::All,
     };
     let mut acc = Assists::new(&ctx, resolve);
     handler(&mut acc, &ctx);
@@ -269,6 +369,12 @@ fn check_with_config(
                         // temporary placeholder for MoveDir since we are not using MoveDir in ide assists yet.
                         (dst, format!("{src_id:?}\n{src:?}"))
                     }
+                    FileSystemEdit::DeleteFile { src } => {
+                        let sr = db.file_source_root(src);
+                        let sr = db.source_root(sr);
+                        format_to!(buf, "//- {} (deleted)\n", sr.path_for_file(&src).unwrap());
+                        continue;
+                    }
                 };
                 let sr = db.file_source_root(dst.anchor);
                 let sr = db.source_root(sr);
@@ -279,16 +385,27 @@ fn check_with_config(
                 buf.push_str(&contents);
             }
 
+            for (file_id, range, annotation) in source_change.annotations {
+                if !buf.is_empty() && !buf.ends_with('\n') {
+                    buf.push('\n');
+                }
+                let text = db.file_text(file_id);
+                format_to!(buf, "// {}: {:?}\n", annotation.label, &text[range]);
+            }
+
             assert_eq_text!(after, &buf);
         }
         (Some(assist), ExpectedResult::Target(target)) => {
             let range = assist.target;
             assert_eq_text!(&text_without_caret[range], target);
         }
-        (Some(assist), ExpectedResult::Unresolved) => assert!(
-            assist.source_change.is_none(),
-            "unresolved assist should not contain source changes"
-        ),
+        (Some(assist), ExpectedResult::Unresolved) => {
+            assert!(
+                assist.source_change.is_none(),
+                "unresolved assist should not contain source changes"
+            );
+            assert!(!ctx.has_searched_usages(), "unresolved assist should not search for usages");
+        }
         (Some(_), ExpectedResult::NotApplicable) => panic!("assist should not be applicable!"),
         (
             None,
@@ -355,6 +472,7 @@ pub fn test_some_range(a: int) -> bool {
         Convert integer base
         Extract into variable
         Extract into function
+        Extract into function in existing module
         Replace if let with match
     "#]]
     .assert_eq(&expected);
@@ -384,6 +502,7 @@ pub fn test_some_range(a: int) -> bool {
             Convert integer base
             Extract into variable
             Extract into function
+            Extract into function in existing module
             Replace if let with match
         "#]]
         .assert_eq(&expected);
@@ -398,6 +517,7 @@ pub fn test_some_range(a: int) -> bool {
         expect![[r#"
             Extract into variable
             Extract into function
+            Extract into function in existing module
         "#]]
         .assert_eq(&expected);
     }
@@ -412,6 +532,140 @@ pub fn test_some_range(a: int) -> bool {
     }
 }
 
+#[test]
+fn assists_in_file_apply_everywhere() {
+    let (db, file_id) = with_single_file(
+        r#"
+fn f(x: i32) -> i32 {
+    let a = dbg!(x);
+    let b = dbg!(a) + 100000;
+    b + 2000000
+}
+"#,
+    );
+    let mut cfg = TEST_CONFIG;
+    assert!(assists_in_file(&db, &cfg, &AssistResolveStrategy::All, file_id).is_empty());
+
+    cfg.allowed = Some(vec![AssistKind::SourceFixAll]);
+    let assists = assists_in_file(&db, &cfg, &AssistResolveStrategy::None, file_id);
+    expect![[r#"
+        Add digit separators to all number literals in file
+        Remove all dbg!() in file
+    "#]]
+    .assert_eq(&labels(&assists));
+
+    let apply = |assist_id: &str| apply_assist_in_file(&db, file_id, assist_id);
+    expect![[r#"
+        fn f(x: i32) -> i32 {
+            let a = dbg!(x);
+            let b = dbg!(a) + 100_000;
+            b + 2_000_000
+        }
+    "#]]
+    .assert_eq(&apply("reformat_number_literal"));
+    expect![[r#"
+        fn f(x: i32) -> i32 {
+            let a = x;
+            let b = a + 100000;
+            b + 2000000
+        }
+    "#]]
+    .assert_eq(&apply("remove_dbg"));
+}
+
+#[test]
+fn assists_in_file_requested_as_source_action() {
+    let (db, file_id) = with_single_file(
+        r#"
+fn f(x: i32) -> i32 {
+    x + 100000
+}
+"#,
+    );
+    let mut cfg = TEST_CONFIG;
+    cfg.allowed = Some(vec![AssistKind::Source]);
+    let assists = assists_in_file(&db, &cfg, &AssistResolveStrategy::None, file_id);
+    expect![[r#"
+        Add digit separators to all number literals in file
+    "#]]
+    .assert_eq(&labels(&assists));
+}
+
+#[test]
+fn assists_in_file_leave_overlapping_places() {
+    // The inner `if` is rewritten by the conversion of the outer one already.
+    cov_mark::check!(bulk_assist_skips_overlapping_place);
+    let (db, file_id) = with_single_file(
+        r#"
+fn f(a: bool, b: bool) {
+    if a {
+        loop {
+            if b {
+                foo();
+            }
+        }
+    }
+}
+"#,
+    );
+    expect![[r#"
+        fn f(a: bool, b: bool) {
+            if !a {
+                return;
+            }
+            loop {
+                if b {
+                    foo();
+                }
+            }
+        }
+    "#]]
+    .assert_eq(&apply_assist_in_file(&db, file_id, "convert_to_guarded_return"));
+}
+
+#[test]
+fn assists_in_file_merge_duplicate_edits() {
+    // Each of the paths adds the same import and shortens all of them.
+    let (db, file_id) = with_single_file(
+        r#"mod std { pub mod fmt { pub trait Debug {} } }
+fn main() {
+    std::fmt::Debug;
+    let x: std::fmt::Debug = std::fmt::Debug;
+}
+"#,
+    );
+    expect![[r#"
+        use std::fmt::Debug;
+
+        mod std { pub mod fmt { pub trait Debug {} } }
+        fn main() {
+            Debug;
+            let x: Debug = Debug;
+        }
+    "#]]
+    .assert_eq(&apply_assist_in_file(&db, file_id, "replace_qualified_name_with_use"));
+}
+
+/// Resolves the bulk variant of `assist_id` and applies it to the text of `file_id`.
+fn apply_assist_in_file(db: &RootDatabase, file_id: FileId, assist_id: &str) -> String {
+    let mut cfg = TEST_CONFIG;
+    cfg.allowed = Some(vec![AssistKind::SourceFixAll]);
+    let resolve = AssistResolveStrategy::Single(SingleResolve {
+        assist_id: assist_id.to_owned(),
+        assist_kind: AssistKind::SourceFixAll,
+        parameter: None,
+    });
+    let assist = assists_in_file(db, &cfg, &resolve, file_id)
+        .into_iter()
+        .find(|it| it.id.0 == assist_id)
+        .unwrap();
+    let mut text = db.file_text(file_id).to_string();
+    let source_change = assist.source_change.unwrap();
+    let (edit, _) = source_change.get_source_and_snippet_edit(file_id).unwrap();
+    edit.apply(&mut text);
+    text
+}
+
 #[test]
 fn various_resolve_strategies() {
     let (db, frange) = RootDatabase::with_range(
@@ -431,7 +685,7 @@ pub fn test_some_range(a: int) -> bool {
 
     {
         let assists = assists(&db, &cfg, AssistResolveStrategy::None, frange);
-        assert_eq!(2, assists.len());
+        assert_eq!(3, assists.len());
         let mut assists = assists.into_iter();
 
         let extract_into_variable_assist = assists.next().unwrap();
@@ -446,6 +700,7 @@ pub fn test_some_range(a: int) -> bool {
                 target: 59..60,
                 source_change: None,
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_variable_assist);
@@ -462,9 +717,29 @@ pub fn test_some_range(a: int) -> bool {
                 target: 59..60,
                 source_change: None,
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_function_assist);
+
+        let extract_into_function_in_module_assist = assists.next().unwrap();
+        expect![[r#"
+            Assist {
+                id: AssistId(
+                    "extract_function",
+                    RefactorExtract,
+                ),
+                label: "Extract into function in existing module",
+                group: None,
+                target: 59..60,
+                source_change: None,
+                trigger_signature_help: false,
+                parameter_prompt: Some(
+                    "Path of the module to put the function into, e.g. `crate::util`",
+                ),
+            }
+        "#]]
+        .assert_debug_eq(&extract_into_function_in_module_assist);
     }
 
     {
@@ -474,10 +749,11 @@ pub fn test_some_range(a: int) -> bool {
             AssistResolveStrategy::Single(SingleResolve {
                 assist_id: "SOMETHING_MISMATCHING".to_owned(),
                 assist_kind: AssistKind::RefactorExtract,
+                parameter: None,
             }),
             frange,
         );
-        assert_eq!(2, assists.len());
+        assert_eq!(3, assists.len());
         let mut assists = assists.into_iter();
 
         let extract_into_variable_assist = assists.next().unwrap();
@@ -492,6 +768,7 @@ pub fn test_some_range(a: int) -> bool {
                 target: 59..60,
                 source_change: None,
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_variable_assist);
@@ -508,6 +785,7 @@ pub fn test_some_range(a: int) -> bool {
                 target: 59..60,
                 source_change: None,
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_function_assist);
@@ -520,10 +798,11 @@ pub fn test_some_range(a: int) -> bool {
             AssistResolveStrategy::Single(SingleResolve {
                 assist_id: "extract_variable".to_owned(),
                 assist_kind: AssistKind::RefactorExtract,
+                parameter: None,
             }),
             frange,
         );
-        assert_eq!(2, assists.len());
+        assert_eq!(3, assists.len());
         let mut assists = assists.into_iter();
 
         let extract_into_variable_assist = assists.next().unwrap();
@@ -576,9 +855,11 @@ pub fn test_some_range(a: int) -> bool {
                         },
                         file_system_edits: [],
                         is_snippet: true,
+                        annotations: [],
                     },
                 ),
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_variable_assist);
@@ -595,6 +876,7 @@ pub fn test_some_range(a: int) -> bool {
                 target: 59..60,
                 source_change: None,
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_function_assist);
@@ -655,9 +937,11 @@ pub fn test_some_range(a: int) -> bool {
                         },
                         file_system_edits: [],
                         is_snippet: true,
+                        annotations: [],
                     },
                 ),
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_variable_assist);
@@ -704,11 +988,50 @@ pub fn test_some_range(a: int) -> bool {
                         },
                         file_system_edits: [],
                         is_snippet: true,
+                        annotations: [],
                     },
                 ),
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&extract_into_function_assist);
     }
 }
+
+#[test]
+fn usage_searches_are_shared_between_contexts() {
+    let (db, file_ids) = RootDatabase::with_many_files(
+        r#"
+//- /main.rs
+mod m;
+fn foo() {}
+fn bar() { foo(); }
+//- /m.rs
+fn baz() {}
+"#,
+    );
+    let file_id = file_ids[0];
+    let frange = FileRange { file_id, range: TextRange::empty(0.into()) };
+    let usages = UsageCache::default();
+    let ctx =
+        AssistContext::with_usage_cache(Semantics::new(&db), &TEST_CONFIG, frange, usages.clone());
+    let foo = ctx
+        .sema
+        .file_to_module_def(file_id)
+        .unwrap()
+        .declarations(&db)
+        .into_iter()
+        .find(|it| it.name(&db).is_some_and(|name| name.display(&db).to_string() == "foo"))
+        .unwrap();
+    let def = Definition::from(foo);
+    let found = ctx.usages(def);
+    assert_eq!(usages.lock().unwrap()[&def], vec![file_id]);
+
+    // Another context only looks at the files known to have usages, with the same result.
+    let other = AssistContext::with_usage_cache(Semantics::new(&db), &TEST_CONFIG, frange, usages);
+    assert_eq!(
+        other.usages(def).file_ranges().collect::<Vec<_>>(),
+        found.file_ranges().collect::<Vec<_>>()
+    );
+}
@@ -345,6 +345,29 @@ pub(crate) fn frobnicate() {}
     )
 }
 
+#[test]
+fn doctest_change_visibility_to_minimal() {
+    check_doc_test(
+        "change_visibility_to_minimal",
+        r#####"
+mod foo {
+    pub mod bar {
+        $0pub fn frobnicate() {}
+    }
+    fn baz() { bar::frobnicate() }
+}
+"#####,
+        r#####"
+mod foo {
+    pub mod bar {
+        pub(super) fn frobnicate() {}
+    }
+    fn baz() { bar::frobnicate() }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_bool_then_to_if() {
     check_doc_test(
@@ -550,6 +573,22 @@ fn foo(opt: Option<()>) {
     )
 }
 
+#[test]
+fn doctest_convert_module_layout() {
+    check_doc_test(
+        "convert_module_layout",
+        r#####"
+//- /main.rs
+mod $0foo;
+//- /foo.rs
+fn t() {}
+"#####,
+        r#####"
+mod foo;
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_named_struct_to_tuple_struct() {
     check_doc_test(
@@ -616,6 +655,82 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_state_machine_to_methods() {
+    check_doc_test(
+        "convert_state_machine_to_methods",
+        r#####"
+enum State { Idle, Running(u32), Done }
+struct Machine { state: State }
+impl Machine {
+    fn run(&mut self) {
+        $0loop {
+            self.state = match self.state {
+                State::Idle => State::Running(0),
+                State::Running(steps) => {
+                    let steps = steps + 1;
+                    if steps == 10 { State::Done } else { State::Running(steps) }
+                }
+                State::Done => break,
+            };
+        }
+    }
+}
+"#####,
+        r#####"
+enum State { Idle, Running(u32), Done }
+struct Machine { state: State }
+impl Machine {
+    fn run(&mut self) {
+        loop {
+            self.state = match self.state {
+                State::Idle => self.on_idle(),
+                State::Running(steps) => self.on_running(steps),
+                State::Done => break,
+            };
+        }
+    }
+
+    fn on_idle(&mut self) -> State {
+        State::Running(0)
+    }
+
+    fn on_running(&mut self, steps: u32) -> State {
+        let steps = steps + 1;
+        if steps == 10 { State::Done } else { State::Running(steps) }
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_static_mut_to_atomic() {
+    check_doc_test(
+        "convert_static_mut_to_atomic",
+        r#####"
+static mut COUNTER$0: u32 = 0;
+
+fn increment() -> u32 {
+    unsafe {
+        COUNTER += 1;
+    }
+    unsafe { COUNTER }
+}
+"#####,
+        r#####"
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+fn increment() -> u32 {
+    COUNTER.fetch_add(1, Ordering::SeqCst);
+    COUNTER.load(Ordering::SeqCst)
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check_doc_test(
@@ -899,18 +1014,18 @@ fn doctest_extract_module() {
 }$0
 
 fn bar(name: i32) -> i32 {
-    name + 2
+    foo(name) + 2
 }
 "#####,
         r#####"
-mod modname {
-    pub(crate) fn foo(name: i32) -> i32 {
+mod ${0:modname} {
+    pub(super) fn foo(name: i32) -> i32 {
         name + 1
     }
 }
 
 fn bar(name: i32) -> i32 {
-    name + 2
+    ${0:modname}::foo(name) + 2
 }
 "#####,
     )
@@ -1059,6 +1174,98 @@ fn foo<T: Copy + Clone>() { }
     )
 }
 
+#[test]
+fn doctest_generate_accessors() {
+    check_doc_test(
+        "generate_accessors",
+        r#####"
+struct Per$0son {
+    name: u32,
+    last_seen: u64,
+}
+"#####,
+        r#####"
+struct Person {
+    name: u32,
+    last_seen: u64,
+}
+
+impl Person {
+    /// Returns the name.
+    fn name(&self) -> &u32 {
+        &self.name
+    }
+
+    /// Returns the last seen.
+    fn $0last_seen(&self) -> &u64 {
+        &self.last_seen
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_builder() {
+    check_doc_test(
+        "generate_builder",
+        r#####"
+//- minicore: option
+struct Person {$0
+    name: String,
+    nickname: Option<String>,
+    email: Option<String>,
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+    nickname: Option<String>,
+    email: Option<String>,
+}
+
+impl Person {
+    fn builder() -> PersonBuilder {
+        PersonBuilder::default()
+    }
+}
+
+#[derive(Default)]
+struct PersonBuilder {
+    name: Option<String>,
+    nickname: Option<String>,
+    email: Option<String>,
+}
+
+impl PersonBuilder {
+    fn name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    fn nickname(mut self, nickname: String) -> Self {
+        self.nickname = Some(nickname);
+        self
+    }
+
+    fn email(mut self, email: String) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    /// Returns `None` if a required field isn't set.
+    fn build(self) -> Option<Person> {
+        Some(Person {
+            name: self.name?,
+            nickname: self.nickname,
+            email: self.email,
+        })
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_constant() {
     check_doc_test(
@@ -1343,6 +1550,69 @@ pub unsafe fn set_len(&mut self, len: usize) -> Result<(), std::io::Error> {
     )
 }
 
+#[test]
+fn doctest_generate_enum_accessors() {
+    check_doc_test(
+        "generate_enum_accessors",
+        r#####"
+enum Value$0 {
+    Null,
+    Number(i32),
+}
+
+impl Value {
+    fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+}
+"#####,
+        r#####"
+enum Value {
+    Null,
+    Number(i32),
+}
+
+impl Value {
+    fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns `true` if the value is [`Number`].
+    ///
+    /// [`Number`]: Value::Number
+    #[must_use]
+    fn is_number(&self) -> bool {
+        matches!(self, Self::Number(..))
+    }
+
+    fn as_number(&self) -> Option<&i32> {
+        if let Self::Number(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    fn try_into_number(self) -> Result<i32, Self> {
+        if let Self::Number(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+
+    fn into_number(self) -> Option<i32> {
+        if let Self::Number(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_enum_as_method() {
     check_doc_test(
@@ -1458,6 +1728,47 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_generate_error_enum() {
+    check_doc_test(
+        "generate_error_enum",
+        r#####"
+fn parse_port$0(input: &str) -> Result<u16, &'static str> {
+    if input.is_empty() {
+        return Err("empty input");
+    }
+    input.parse().ok().ok_or("not a number")
+}
+"#####,
+        r#####"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParsePortError {
+    EmptyInput,
+    NotANumber,
+}
+
+impl core::fmt::Display for ParsePortError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let message = match self {
+            Self::EmptyInput => "empty input",
+            Self::NotANumber => "not a number",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ParsePortError {}
+
+fn parse_port(input: &str) -> Result<u16, ParsePortError> {
+    if input.is_empty() {
+        return Err(ParsePortError::EmptyInput);
+    }
+    input.parse().ok().ok_or(ParsePortError::NotANumber)
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_from_impl_for_enum() {
     check_doc_test(
@@ -1598,6 +1909,63 @@ impl<T: Clone> Ctx<T> {$0}
     )
 }
 
+#[test]
+fn doctest_generate_into_iterator_impls() {
+    check_doc_test(
+        "generate_into_iterator_impls",
+        r#####"
+//- minicore: iterator, from_iterator
+struct Vec<T>(T);
+struct IntoIter<T>(T);
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> { loop {} }
+}
+impl<T> IntoIterator for Vec<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> { loop {} }
+}
+impl<T> core::iter::FromIterator<T> for Vec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self { loop {} }
+}
+struct Stack<T>($0Vec<T>);
+"#####,
+        r#####"
+struct Vec<T>(T);
+struct IntoIter<T>(T);
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> { loop {} }
+}
+impl<T> IntoIterator for Vec<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> { loop {} }
+}
+impl<T> core::iter::FromIterator<T> for Vec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self { loop {} }
+}
+struct Stack<T>(Vec<T>);
+
+impl<T> IntoIterator for Stack<T> {
+    type Item = <Vec<T> as IntoIterator>::Item;
+    type IntoIter = <Vec<T> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T> core::iter::FromIterator<<Vec<T> as IntoIterator>::Item> for Stack<T> {
+    fn from_iter<I: IntoIterator<Item = <Vec<T> as IntoIterator>::Item>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_is_empty_from_len() {
     check_doc_test(
@@ -1917,6 +2285,24 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_inline_module() {
+    check_doc_test(
+        "inline_module",
+        r#####"
+//- /main.rs
+mod $0foo;
+//- /foo.rs
+fn t() {}
+"#####,
+        r#####"
+mod foo {
+    fn t() {}
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_type_alias() {
     check_doc_test(
@@ -2103,6 +2489,22 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_merge_all_imports() {
+    check_doc_test(
+        "merge_all_imports",
+        r#####"
+use std::fmt::Display;
+use std::$0io;
+use std::fmt::Display;
+use std::fmt::Debug;
+"#####,
+        r#####"
+use std::{fmt::{Debug, Display}, io};
+"#####,
+    )
+}
+
 #[test]
 fn doctest_merge_imports() {
     check_doc_test(
@@ -2293,6 +2695,37 @@ fn t() {}
     )
 }
 
+#[test]
+fn doctest_move_tests_to_file() {
+    check_doc_test(
+        "move_tests_to_file",
+        r#####"
+//- /main.rs cfg:test
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod $0tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        assert_eq!(add(1, 2), 3);
+    }
+}
+"#####,
+        r#####"
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests;
+"#####,
+    )
+}
+
 #[test]
 fn doctest_move_to_mod_rs() {
     check_doc_test(
@@ -3007,6 +3440,45 @@ enum Animal {
     )
 }
 
+#[test]
+fn doctest_split_impl() {
+    check_doc_test(
+        "split_impl",
+        r#####"
+struct Point { x: i32, y: i32 }
+
+impl$0 Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+
+    // region: getters
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+    // endregion
+}
+"#####,
+        r#####"
+struct Point { x: i32, y: i32 }
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+}
+
+impl Point {
+    // region: getters
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+    // endregion
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_split_import() {
     check_doc_test(
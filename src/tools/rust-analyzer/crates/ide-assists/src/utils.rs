@@ -3,7 +3,7 @@
 pub(crate) use gen_trait_fn_body::gen_trait_fn_body;
 use hir::{db::HirDatabase, HasAttrs as HirHasAttrs, HirDisplay, InFile, Semantics};
 use ide_db::{
-    famous_defs::FamousDefs, path_transform::PathTransform,
+    defs::Definition, famous_defs::FamousDefs, path_transform::PathTransform,
     syntax_helpers::insert_whitespace_into_node::insert_ws_into, RootDatabase,
 };
 use stdx::format_to;
@@ -12,14 +12,18 @@
         self,
         edit::{AstNodeEdit, IndentLevel},
         edit_in_place::{AttrsOwnerEdit, Indent, Removable},
-        make, HasArgList, HasAttrs, HasGenericParams, HasName, HasTypeBounds, Whitespace,
+        make, HasArgList, HasAttrs, HasGenericParams, HasModuleItem, HasName, HasTypeBounds,
+        Whitespace,
     },
-    ted, AstNode, AstToken, Direction, SourceFile,
+    match_ast, ted, AstNode, AstToken, Direction, SourceFile, SyntaxElement,
     SyntaxKind::*,
     SyntaxNode, TextRange, TextSize, T,
 };
 
-use crate::assist_context::{AssistContext, SourceChangeBuilder};
+use crate::{
+    assist_context::{AssistContext, SourceChangeBuilder},
+    AssistConfig,
+};
 
 mod gen_trait_fn_body;
 pub(crate) mod ref_field_expr;
@@ -168,9 +172,10 @@ fn has_def_name(item: &InFile<ast::AssocItem>) -> bool {
 /// Given `original_items` retrieved from the trait definition (usually by
 /// [`filter_assoc_items()`]), clones each item for update and applies path transformation to it,
 /// then inserts into `impl_`. Returns the modified `impl_` and the first associated item that got
-/// inserted.
+/// inserted. The doc comments of the items are kept if the config asks for it.
 pub fn add_trait_assoc_items_to_impl(
     sema: &Semantics<'_, RootDatabase>,
+    config: &AssistConfig,
     original_items: &[InFile<ast::AssocItem>],
     trait_: hir::Trait,
     impl_: &ast::Impl,
@@ -198,7 +203,11 @@ pub fn add_trait_assoc_items_to_impl(
                 PathTransform::trait_impl(&target_scope, &source_scope, trait_, impl_.clone());
             transform.apply(cloned_item.syntax());
         }
-        cloned_item.remove_attrs_and_docs();
+        if config.impl_members_copy_docs {
+            cloned_item.remove_attrs_keeping_docs();
+        } else {
+            cloned_item.remove_attrs_and_docs();
+        }
         cloned_item.reindent_to(new_indent_level);
         cloned_item
     });
@@ -213,7 +222,10 @@ pub fn add_trait_assoc_items_to_impl(
                     &make::block_expr(None, Some(make::ext::expr_todo())),
                     new_indent_level,
                 );
-                ted::replace(fn_.get_or_create_body().syntax(), body.clone_for_update().syntax())
+                ted::replace(fn_.get_or_create_body().syntax(), body.clone_for_update().syntax());
+                if let Some(where_clause) = fn_.where_clause() {
+                    format_where_clause_before_body(fn_, &where_clause, new_indent_level);
+                }
             }
             ast::AssocItem::TypeAlias(type_alias) => {
                 if let Some(type_bound_list) = type_alias.type_bound_list() {
@@ -229,11 +241,58 @@ pub fn add_trait_assoc_items_to_impl(
     first_item.unwrap()
 }
 
-pub(crate) fn vis_offset(node: &SyntaxNode) -> TextSize {
-    node.children_with_tokens()
-        .find(|it| !matches!(it.kind(), WHITESPACE | COMMENT | ATTR))
-        .map(|it| it.text_range().start())
-        .unwrap_or_else(|| node.text_range().start())
+/// Puts the body of `fn_` on its own line after a multi-line where clause, the way rustfmt does,
+/// instead of right after the last predicate.
+fn format_where_clause_before_body(
+    fn_: &ast::Fn,
+    where_clause: &ast::WhereClause,
+    indent: IndentLevel,
+) {
+    if !where_clause.syntax().text().contains_char('\n') {
+        return;
+    }
+    if let Some(last_pred) = where_clause.predicates().last() {
+        if where_clause.syntax().last_token().map(|it| it.kind()) != Some(T![,]) {
+            ted::insert(ted::Position::after(last_pred.syntax()), make::token(T![,]));
+        }
+    }
+    let ws_before_body = fn_
+        .body()
+        .and_then(|body| body.syntax().prev_sibling_or_token())
+        .filter(|it| it.kind() == WHITESPACE);
+    if let Some(ws) = ws_before_body {
+        ted::replace(ws, make::tokens::whitespace(&format!("\n{indent}")));
+    }
+}
+
+/// The element a new visibility has to be inserted in front of, skipping over
+/// attributes and comments.
+pub(crate) fn vis_anchor(node: &SyntaxNode) -> Option<SyntaxElement> {
+    node.children_with_tokens().find(|it| !matches!(it.kind(), WHITESPACE | COMMENT | ATTR))
+}
+
+/// The definition of an item or field which can have a visibility.
+pub(crate) fn vis_owner_def(
+    sema: &Semantics<'_, RootDatabase>,
+    node: &SyntaxNode,
+) -> Option<Definition> {
+    match_ast! {
+        match node {
+            ast::Const(it) => sema.to_def(&it).map(Definition::Const),
+            ast::Enum(it) => sema.to_def(&it).map(|it| Definition::Adt(it.into())),
+            ast::Fn(it) => sema.to_def(&it).map(Definition::Function),
+            ast::Macro(it) => sema.to_def(&it).map(Definition::Macro),
+            ast::Module(it) => sema.to_def(&it).map(Definition::Module),
+            ast::RecordField(it) => sema.to_def(&it).map(Definition::Field),
+            ast::Static(it) => sema.to_def(&it).map(Definition::Static),
+            ast::Struct(it) => sema.to_def(&it).map(|it| Definition::Adt(it.into())),
+            ast::Trait(it) => sema.to_def(&it).map(Definition::Trait),
+            ast::TupleField(it) => sema.to_def(&it).map(Definition::Field),
+            ast::TypeAlias(it) => sema.to_def(&it).map(Definition::TypeAlias),
+            ast::Union(it) => sema.to_def(&it).map(|it| Definition::Adt(it.into())),
+            _ => None,
+        }
+    }
 }
 
 pub(crate) fn invert_boolean_expression(expr: ast::Expr) -> ast::Expr {
@@ -438,7 +497,7 @@ pub(crate) fn find_impl_block_end(impl_def: ast::Impl, buf: &mut String) -> Opti
 /// parameters.
 // FIXME: migrate remaining uses to `generate_impl`
 pub(crate) fn generate_impl_text(adt: &ast::Adt, code: &str) -> String {
-    generate_impl_text_inner(adt, None, true, code)
+    generate_impl_text_inner(adt, None, true, None, code)
 }
 
 /// Generates the surrounding `impl <trait> for Type { <code> }` including type
@@ -447,7 +506,7 @@ pub(crate) fn generate_impl_text(adt: &ast::Adt, code: &str) -> String {
 /// This is useful for traits like `PartialEq`, since `impl<T> PartialEq for U<T>` often requires `T: PartialEq`.
 // FIXME: migrate remaining uses to `generate_trait_impl`
 pub(crate) fn generate_trait_impl_text(adt: &ast::Adt, trait_text: &str, code: &str) -> String {
-    generate_impl_text_inner(adt, Some(trait_text), true, code)
+    generate_impl_text_inner(adt, Some(trait_text), true, None, code)
 }
 
 /// Generates the surrounding `impl <trait> for Type { <code> }` including type
@@ -460,13 +519,27 @@ pub(crate) fn generate_trait_impl_text_intransitive(
     trait_text: &str,
     code: &str,
 ) -> String {
-    generate_impl_text_inner(adt, Some(trait_text), false, code)
+    generate_impl_text_inner(adt, Some(trait_text), false, None, code)
+}
+
+/// Generates the surrounding `impl<'a> <trait> for &'a Type { <code> }`, or for `&'a mut Type` if
+/// `mutable` is set, including type and lifetime parameters, with `impl`'s generic parameters'
+/// bounds kept as-is.
+pub(crate) fn generate_trait_impl_text_for_ref(
+    adt: &ast::Adt,
+    lifetime: &str,
+    mutable: bool,
+    trait_text: &str,
+    code: &str,
+) -> String {
+    generate_impl_text_inner(adt, Some(trait_text), false, Some((lifetime, mutable)), code)
 }
 
 fn generate_impl_text_inner(
     adt: &ast::Adt,
     trait_text: Option<&str>,
     trait_is_transitive: bool,
+    self_ref: Option<(&str, bool)>,
     code: &str,
 ) -> String {
     // Ensure lifetime params are before type & const params
@@ -504,6 +577,18 @@ fn generate_impl_text_inner(
 
         make::generic_param_list(itertools::chain(lifetime_params, ty_or_const_params))
     });
+    let generic_args = generic_params.as_ref().map(|params| params.to_generic_args());
+    // The lifetime of the reference comes first, it isn't a parameter of the type.
+    let generic_params = match self_ref {
+        Some((lifetime, _)) => {
+            let lifetime = make::lifetime_param(make::lifetime(lifetime));
+            let params = generic_params.iter().flat_map(|params| params.generic_params());
+            Some(make::generic_param_list(
+                std::iter::once(ast::GenericParam::LifetimeParam(lifetime)).chain(params),
+            ))
+        }
+        None => generic_params,
+    };
 
     // FIXME: use syntax::make & mutable AST apis instead
     // `trait_text` and `code` can't be opaque blobs of text
@@ -526,9 +611,15 @@ fn generate_impl_text_inner(
         buf.push_str(trait_text);
         buf.push_str(" for ");
     }
+    if let Some((lifetime, mutable)) = self_ref {
+        format_to!(buf, "&{lifetime} ");
+        if mutable {
+            buf.push_str("mut ");
+        }
+    }
     buf.push_str(&adt.name().unwrap().text());
-    if let Some(generic_params) = generic_params {
-        format_to!(buf, "{}", generic_params.to_generic_args());
+    if let Some(generic_args) = generic_args {
+        format_to!(buf, "{generic_args}");
     }
 
     match adt.where_clause() {
@@ -862,6 +953,39 @@ pub(crate) fn trimmed_text_range(source_file: &SourceFile, initial_range: TextRa
     trimmed_range
 }
 
+/// The `#[path]` attributes with relative paths of the `mod` declarations at the top level of
+/// `source_file`, along with those paths.
+pub(crate) fn relative_path_attrs(source_file: &SourceFile) -> Vec<(ast::Attr, String)> {
+    source_file
+        .items()
+        .filter_map(|item| match item {
+            ast::Item::Module(it) => Some(it),
+            _ => None,
+        })
+        .flat_map(|module| module.attrs())
+        .filter(|attr| attr.simple_name().as_deref() == Some("path"))
+        .filter_map(|attr| {
+            let path = path_attr_value(&attr)?;
+            (!path.starts_with('/')).then_some((attr, path))
+        })
+        .collect()
+}
+
+pub(crate) fn path_attr_value(attr: &ast::Attr) -> Option<String> {
+    let ast::Expr::Literal(literal) = attr.meta()?.expr()? else { return None };
+    let ast::LiteralKind::String(string) = literal.kind() else { return None };
+    Some(string.value()?.into_owned())
+}
+
+pub(crate) fn make_path_attr(path: &str) -> ast::Attr {
+    let path_literal = make::expr_literal(&format!("{path:?}"));
+    make::attr_outer(make::meta_expr(
+        make::ext::ident_path("path"),
+        ast::Expr::Literal(path_literal),
+    ))
+    .clone_for_update()
+}
+
 /// Convert a list of function params to a list of arguments that can be passed
 /// into a function call.
 pub(crate) fn convert_param_list_to_arg_list(list: ast::ParamList) -> ast::ArgList {
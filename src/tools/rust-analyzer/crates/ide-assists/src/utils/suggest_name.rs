@@ -271,11 +271,14 @@ fn var_name_from_pat(pat: &ast::Pat) -> Option<ast::Name> {
     }
 }
 
-fn from_type(expr: &ast::Expr, sema: &Semantics<'_, RootDatabase>) -> Option<String> {
-    let ty = sema.type_of_expr(expr)?.adjusted();
-    let ty = ty.remove_ref().unwrap_or(ty);
+/// Suggests a name for a value of type `ty`, e.g. `seed` for `Option<&Seed>`.
+pub(crate) fn for_type(ty: &hir::Type, db: &RootDatabase) -> Option<String> {
+    let ty = ty.remove_ref().unwrap_or_else(|| ty.clone());
+    name_of_type(&ty, db)
+}
 
-    name_of_type(&ty, sema.db)
+fn from_type(expr: &ast::Expr, sema: &Semantics<'_, RootDatabase>) -> Option<String> {
+    for_type(&sema.type_of_expr(expr)?.adjusted(), sema.db)
 }
 
 fn name_of_type(ty: &hir::Type, db: &RootDatabase) -> Option<String> {
@@ -6,7 +6,7 @@
 use crate::{
     context::{PathCompletionCtx, Qualified, TypeAscriptionTarget, TypeLocation},
     render::render_type_inference,
-    CompletionContext, Completions,
+    CompletionContext, CompletionItem, CompletionItemKind, Completions,
 };
 
 pub(crate) fn complete_type_path(
@@ -17,6 +17,18 @@ pub(crate) fn complete_type_path(
 ) {
     let _p = tracing::span!(tracing::Level::INFO, "complete_type_path").entered();
 
+    // Only constants of its type can be the argument of a const parameter.
+    let const_param = match location {
+        TypeLocation::GenericArg {
+            corresponding_param: Some(ast::GenericParam::ConstParam(param)),
+            ..
+        } => ctx.sema.to_def(param),
+        _ => None,
+    };
+    let const_param_ty = const_param.map(|param| param.ty(ctx.db));
+    let fits_const_param =
+        |ty: hir::Type| const_param_ty.as_ref().map_or(true, |it| ty.could_unify_with(ctx.db, it));
+
     let scope_def_applicable = |def| {
         use hir::{GenericParam::*, ModuleDef::*};
         match def {
@@ -25,8 +37,11 @@ pub(crate) fn complete_type_path(
             // no values in type places
             ScopeDef::ModuleDef(Function(_) | Variant(_) | Static(_)) | ScopeDef::Local(_) => false,
             // unless its a constant in a generic arg list position
-            ScopeDef::ModuleDef(Const(_)) | ScopeDef::GenericParam(ConstParam(_)) => {
-                location.complete_consts()
+            ScopeDef::ModuleDef(Const(it)) => {
+                location.complete_consts() && fits_const_param(it.ty(ctx.db))
+            }
+            ScopeDef::GenericParam(ConstParam(it)) => {
+                location.complete_consts() && fits_const_param(it.ty(ctx.db))
             }
             ScopeDef::ImplSelfType(_) => location.complete_self_type(),
             // Don't suggest attribute macros and derives.
@@ -47,7 +62,10 @@ pub(crate) fn complete_type_path(
     };
 
     let add_assoc_item = |acc: &mut Completions, item| match item {
-        hir::AssocItem::Const(ct) if matches!(location, TypeLocation::GenericArg { .. }) => {
+        hir::AssocItem::Const(ct)
+            if matches!(location, TypeLocation::GenericArg { .. })
+                && fits_const_param(ct.ty(ctx.db)) =>
+        {
             acc.add_const(ctx, ct)
         }
         hir::AssocItem::Function(_) | hir::AssocItem::Const(_) => (),
@@ -213,10 +231,37 @@ pub(crate) fn complete_type_path(
                     acc.add_path_resolution(ctx, path_ctx, name, def, doc_aliases);
                 }
             });
+            if let Some(param) = const_param {
+                complete_const_arg_literals(acc, ctx, param);
+            }
         }
     }
 }
 
+/// Suggests the literals commonly passed to a const parameter of the type of `param`, detailed
+/// with the parameter they are the argument of.
+fn complete_const_arg_literals(
+    acc: &mut Completions,
+    ctx: &CompletionContext<'_>,
+    param: hir::ConstParam,
+) {
+    let ty = param.ty(ctx.db);
+    let literals: &[&str] = if ty.is_bool() {
+        &["false", "true"]
+    } else if ty.is_int_or_uint() {
+        &["0", "1"]
+    } else {
+        &[]
+    };
+    let detail = format!("const {}: {}", param.name(ctx.db).display(ctx.db), ty.display(ctx.db));
+    for &literal in literals {
+        let mut item =
+            CompletionItem::new(CompletionItemKind::Expression, ctx.source_range(), literal);
+        item.detail(detail.clone());
+        item.add_to(acc, ctx.db);
+    }
+}
+
 pub(crate) fn complete_ascribed_type(
     acc: &mut Completions,
     ctx: &CompletionContext<'_>,
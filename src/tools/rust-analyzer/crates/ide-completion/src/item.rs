@@ -54,6 +54,10 @@ pub struct CompletionItem {
     /// That is, in `foo.bar$0` lookup of `abracadabra` will be accepted (it
     /// contains `bar` sub sequence), and `quux` will rejected.
     pub lookup: SmolStr,
+    /// The identifier as typed, if this item only completes it once its typos are corrected.
+    ///
+    /// Such an item is not accepted by its `lookup`, so clients should filter it by this text.
+    pub typo_match: Option<SmolStr>,
 
     /// Additional info to show in the UI pop up.
     pub detail: Option<String>,
@@ -103,6 +107,9 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.lookup() != self.label {
             s.field("lookup", &self.lookup());
         }
+        if let Some(typo_match) = &self.typo_match {
+            s.field("typo_match", &typo_match);
+        }
         if let Some(detail) = &self.detail {
             s.field("detail", &detail);
         }
@@ -168,6 +175,9 @@ pub struct CompletionRelevance {
     pub is_definite: bool,
     /// This is set for items that are function (associated or method)
     pub function: Option<CompletionRelevanceFn>,
+    /// The number of typos that had to be corrected to match the typed identifier, see
+    /// `CompletionConfig::enable_typo_tolerance`. Zero for items that match as typed.
+    pub typos: u8,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -238,7 +248,7 @@ impl CompletionRelevance {
     /// See is_relevant if you need to make some judgement about score
     /// in an absolute sense.
     pub fn score(self) -> u32 {
-        let mut score = 0;
+        let mut score: u32 = 0;
         let CompletionRelevance {
             exact_name_match,
             type_match,
@@ -252,6 +262,7 @@ pub fn score(self) -> u32 {
             is_definite,
             is_item_from_notable_trait,
             function,
+            typos,
         } = self;
 
         // lower rank private things
@@ -323,7 +334,8 @@ pub fn score(self) -> u32 {
             })
             .unwrap_or_default();
 
-        score
+        // lower rank items matching only with typos corrected, the more typos the lower
+        score.saturating_sub(4 * u32::from(typos))
     }
 
     /// Returns true when the score for this threshold is above
@@ -551,6 +563,7 @@ pub(crate) fn build(self, db: &RootDatabase) -> CompletionItem {
             detail: self.detail,
             documentation: self.documentation,
             lookup,
+            typo_match: None,
             kind: self.kind,
             deprecated: self.deprecated,
             trigger_call_info: self.trigger_call_info,
@@ -700,6 +713,8 @@ fn relevance_score() {
             vec![],
             vec![Cr { is_op_method: true, is_private_editable: true, ..default }],
             vec![Cr { is_op_method: true, ..default }],
+            vec![Cr { typos: 2, ..default }],
+            vec![Cr { typos: 1, ..default }],
             vec![Cr { postfix_match: Some(CompletionRelevancePostfixMatch::NonExact), ..default }],
             vec![Cr { is_private_editable: true, ..default }],
             vec![default],
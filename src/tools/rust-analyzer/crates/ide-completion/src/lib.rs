@@ -6,6 +6,7 @@
 mod config;
 mod context;
 mod item;
+mod matcher;
 mod render;
 
 mod snippet;
@@ -226,7 +227,17 @@ pub fn completions(
         }
     }
 
-    Some(completions.into())
+    let mut completions: Vec<_> = completions.into();
+    if config.enable_typo_tolerance {
+        if let CompletionAnalysis::NameRef(NameRefContext {
+            kind: NameRefKind::Path(_) | NameRefKind::DotAccess(_),
+            ..
+        }) = analysis
+        {
+            matcher::tolerate_typos(ctx, &mut completions);
+        }
+    }
+    Some(completions)
 }
 
 /// Resolves additional completion data at the position given.
@@ -0,0 +1,106 @@
+//! Typo tolerant matching of completion items against the identifier typed so far.
+//!
+//! Completion items are filtered by the client, which only keeps the items whose `lookup`
+//! contains the typed identifier as a subsequence. With typo tolerance enabled, items that only
+//! match once a transposition or a few mistyped characters are corrected get the typed identifier
+//! as their `typo_match` to be filtered by instead, and are ranked below the items matching as
+//! typed.
+
+use std::mem;
+
+use syntax::SyntaxKind;
+
+use crate::{CompletionContext, CompletionItem};
+
+/// Makes the items that are a mistyped prefix away from the identifier at the cursor match it.
+pub(crate) fn tolerate_typos(ctx: &CompletionContext<'_>, items: &mut [CompletionItem]) {
+    if ctx.original_token.kind() != SyntaxKind::IDENT {
+        return;
+    }
+    let typed = ctx.original_token.text();
+    let input: Vec<char> = typed.chars().flat_map(char::to_lowercase).collect();
+    let max_typos = max_typos(input.len());
+    if max_typos == 0 {
+        return;
+    }
+
+    for item in items {
+        let lookup: Vec<char> = item.lookup.chars().flat_map(char::to_lowercase).collect();
+        if is_subsequence(&input, &lookup) {
+            continue;
+        }
+        match typos_in_prefix(&input, &lookup) {
+            Some(typos) if typos <= max_typos => {
+                item.typo_match = Some(typed.into());
+                item.relevance.typos = typos;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// The number of typos tolerated in an identifier of the given length. Short identifiers match
+/// too many items once a character may be wrong.
+fn max_typos(len: usize) -> u8 {
+    match len {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+fn is_subsequence(needle: &[char], haystack: &[char]) -> bool {
+    let mut haystack = haystack.iter();
+    needle.iter().all(|c| haystack.any(|it| it == c))
+}
+
+/// Returns the smallest number of insertions, deletions, substitutions and transpositions of
+/// adjacent characters that turn `input` into a prefix of `candidate`, which is the optimal
+/// string alignment distance to the closest prefix. Both have to start with the same character,
+/// as the first character of an identifier is hardly ever mistyped.
+fn typos_in_prefix(input: &[char], candidate: &[char]) -> Option<u8> {
+    if input.first()? != candidate.first()? {
+        return None;
+    }
+    // `prev[j]` is the distance between the characters of `input` seen so far and the first `j`
+    // characters of `candidate`, `prev2` the same without the last character of `input`.
+    let mut prev2 = Vec::new();
+    let mut prev: Vec<usize> = (0..=candidate.len()).collect();
+    for (i, &c) in input.iter().enumerate() {
+        let mut cur = vec![i + 1; candidate.len() + 1];
+        for (j, &d) in candidate.iter().enumerate() {
+            let substitution = usize::from(c != d);
+            let mut dist = (prev[j + 1] + 1).min(cur[j] + 1).min(prev[j] + substitution);
+            if i > 0 && j > 0 && c == candidate[j - 1] && input[i - 1] == d {
+                dist = dist.min(prev2[j - 1] + 1);
+            }
+            cur[j + 1] = dist;
+        }
+        prev2 = mem::replace(&mut prev, cur);
+    }
+    let typos = prev.iter().min()?;
+    u8::try_from(*typos).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::typos_in_prefix;
+
+    fn check(input: &str, candidate: &str, expected: Option<u8>) {
+        let input: Vec<char> = input.chars().collect();
+        let candidate: Vec<char> = candidate.chars().collect();
+        assert_eq!(typos_in_prefix(&input, &candidate), expected);
+    }
+
+    #[test]
+    fn typos() {
+        check("length", "length", Some(0));
+        check("len", "length", Some(0));
+        check("lnegth", "length", Some(1));
+        check("lenght", "length", Some(1));
+        check("legnht", "length", Some(2));
+        check("lengthx", "length", Some(1));
+        check("lnegth", "len", Some(4));
+        check("elngth", "length", None);
+    }
+}
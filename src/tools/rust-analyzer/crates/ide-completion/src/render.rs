@@ -1266,6 +1266,7 @@ fn main() { let _: m::Spam = S$0 }
                             postfix_match: None,
                             is_definite: false,
                             function: None,
+                            typos: 0,
                         },
                         trigger_call_info: true,
                     },
@@ -1294,6 +1295,7 @@ fn main() { let _: m::Spam = S$0 }
                             postfix_match: None,
                             is_definite: false,
                             function: None,
+                            typos: 0,
                         },
                         trigger_call_info: true,
                     },
@@ -1374,6 +1376,7 @@ fn foo() { A { the$0 } }
                             postfix_match: None,
                             is_definite: false,
                             function: None,
+                            typos: 0,
                         },
                     },
                 ]
@@ -1431,6 +1434,7 @@ fn bar(self) { self.$0 }
                                     return_type: Other,
                                 },
                             ),
+                            typos: 0,
                         },
                     },
                     CompletionItem {
@@ -1558,6 +1562,7 @@ fn foo(s: S) { s.$0 }
                                     return_type: Other,
                                 },
                             ),
+                            typos: 0,
                         },
                     },
                 ]
@@ -2451,6 +2456,7 @@ fn foo(f: Foo) { let _: &u32 = f.b$0 }
                                     return_type: Other,
                                 },
                             ),
+                            typos: 0,
                         },
                         ref_match: "&@107",
                     },
@@ -2527,6 +2533,7 @@ fn foo() {
                             postfix_match: None,
                             is_definite: false,
                             function: None,
+                            typos: 0,
                         },
                     },
                 ]
@@ -2583,6 +2590,7 @@ fn main() {
                                     return_type: Other,
                                 },
                             ),
+                            typos: 0,
                         },
                         ref_match: "&@92",
                     },
@@ -2949,6 +2957,7 @@ fn main() {
                             postfix_match: None,
                             is_definite: false,
                             function: None,
+                            typos: 0,
                         },
                     },
                     CompletionItem {
@@ -2974,6 +2983,7 @@ fn main() {
                             postfix_match: None,
                             is_definite: false,
                             function: None,
+                            typos: 0,
                         },
                     },
                 ]
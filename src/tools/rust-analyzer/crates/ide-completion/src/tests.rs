@@ -27,7 +27,7 @@
 use hir::PrefixKind;
 use ide_db::{
     base_db::{FileLoader, FilePosition},
-    imports::insert_use::{ImportGranularity, InsertUseConfig},
+    imports::insert_use::{ImportGranularity, ImportGroupStyle, InsertUseConfig},
     RootDatabase, SnippetCap,
 };
 use itertools::Itertools;
@@ -66,6 +66,7 @@ union Union { field: i32 }
     enable_self_on_the_fly: true,
     enable_private_editable: false,
     enable_term_search: true,
+    enable_typo_tolerance: false,
     full_function_signatures: false,
     callable: Some(CallableSnippets::FillArguments),
     snippet_cap: SnippetCap::new(true),
@@ -76,6 +77,7 @@ union Union { field: i32 }
         prefix_kind: PrefixKind::Plain,
         enforce_granularity: true,
         group: true,
+        group_style: ImportGroupStyle::Default,
         skip_glob_imports: true,
     },
     snippets: Vec::new(),
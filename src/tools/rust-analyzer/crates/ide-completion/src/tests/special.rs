@@ -1514,3 +1514,47 @@ fn foo<T>() {
         "#]],
     );
 }
+
+#[test]
+fn typo_tolerant_matching() {
+    const TYPO_TOLERANT_CONFIG: crate::CompletionConfig = {
+        let mut x = TEST_CONFIG;
+        x.enable_typo_tolerance = true;
+        x
+    };
+    let fixture = r#"
+struct S;
+impl S {
+    fn length(&self) -> usize { 0 }
+    fn len(&self) -> usize { 0 }
+    fn last(&self) {}
+}
+fn f(s: S) { s.lnegth$0 }
+"#;
+    let render = |config| {
+        do_completion_with_config(
+            config,
+            fixture,
+            CompletionItemKind::SymbolKind(SymbolKind::Method),
+        )
+        .into_iter()
+        .map(|it| {
+            let typo_match = it.typo_match.as_deref().unwrap_or("-");
+            format!("{} {} {typo_match} {}\n", it.label, it.lookup(), it.relevance.typos)
+        })
+        .collect::<String>()
+    };
+
+    expect![[r#"
+        last() last - 0
+        len() len - 0
+        length() length lnegth 1
+    "#]]
+    .assert_eq(&render(TYPO_TOLERANT_CONFIG));
+    expect![[r#"
+        last() last - 0
+        len() len - 0
+        length() length - 0
+    "#]]
+    .assert_eq(&render(TEST_CONFIG));
+}
@@ -766,11 +766,12 @@ fn main() {
     }
             "#,
         expect![[r#"
-            ct CONST     Unit
             ct X         usize
             ma makro!(…) macro_rules! makro
             kw crate::
             kw self::
+            ex 0         const N: usize
+            ex 1         const N: usize
         "#]],
     );
 
@@ -809,11 +810,12 @@ fn main() {
     }
             "#,
         expect![[r#"
-            ct CONST     Unit
             ct X         usize
             ma makro!(…) macro_rules! makro
             kw crate::
             kw self::
+            ex 0         const N: usize
+            ex 1         const N: usize
         "#]],
     );
 
@@ -853,11 +855,12 @@ trait Bar {
     fn foo<T: Bar<Baz<(), $0> = ()>>() {}
             "#,
         expect![[r#"
-            ct CONST     Unit
             ct X         usize
             ma makro!(…) macro_rules! makro
             kw crate::
             kw self::
+            ex 0         const X: usize
+            ex 1         const X: usize
         "#]],
     );
 
@@ -871,11 +874,12 @@ fn main() {
     }
             "#,
         expect![[r#"
-            ct CONST     Unit
             ct X         usize
             ma makro!(…) macro_rules! makro
             kw crate::
             kw self::
+            ex 0         const N: usize
+            ex 1         const N: usize
         "#]],
     );
 
@@ -890,11 +894,12 @@ fn main() {
     }
             "#,
         expect![[r#"
-            ct CONST     Unit
             ct X         usize
             ma makro!(…) macro_rules! makro
             kw crate::
             kw self::
+            ex 0         const X: usize
+            ex 1         const X: usize
         "#]],
     );
 
@@ -908,11 +913,12 @@ fn main() {
     }
             "#,
         expect![[r#"
-            ct CONST     Unit
             ct X         usize
             ma makro!(…) macro_rules! makro
             kw crate::
             kw self::
+            ex 0         const N: usize
+            ex 1         const N: usize
         "#]],
     );
 
@@ -924,11 +930,12 @@ trait Foo<T, const N: usize> {}
     impl Foo<(), $0> for () {}
             "#,
         expect![[r#"
-            ct CONST     Unit
             ct X         usize
             ma makro!(…) macro_rules! makro
             kw crate::
             kw self::
+            ex 0         const N: usize
+            ex 1         const N: usize
         "#]],
     );
 
@@ -942,11 +949,12 @@ trait Foo<T, const N: usize> {}
     fn foo<T: Bar<X$0, ()>>() {}
             "#,
         expect![[r#"
-            ct CONST     Unit
             ct X         usize
             ma makro!(…) macro_rules! makro
             kw crate::
             kw self::
+            ex 0         const M: usize
+            ex 1         const M: usize
         "#]],
     );
 
@@ -957,10 +965,11 @@ fn foo<T: Bar<X$0, ()>>() {}
 fn foo<'a>() { S::<F$0, _>; }
         "#,
         expect![[r#"
-            ct CONST     Unit
             ma makro!(…) macro_rules! makro
             kw crate::
             kw self::
+            ex 0         const C: usize
+            ex 1         const C: usize
         "#]],
     );
     // Explicit lifetime params
@@ -970,10 +979,11 @@ fn foo<T: Bar<X$0, ()>>() {}
 fn foo<'a>() { S::<'static, 'static, F$0, _>; }
         "#,
         expect![[r#"
-            ct CONST     Unit
             ma makro!(…) macro_rules! makro
             kw crate::
             kw self::
+            ex 0         const C: usize
+            ex 1         const C: usize
         "#]],
     );
     check(
@@ -990,6 +1000,26 @@ fn foo<T: Bar<X$0, ()>>() {}
     );
 }
 
+#[test]
+fn completes_const_args_of_the_param_type() {
+    check(
+        r#"
+struct Flags<const ENABLED: bool>;
+const ON: bool = true;
+const SIZE: usize = 0;
+fn f(_: Flags<$0>) {}
+"#,
+        expect![[r#"
+            ct ON        bool
+            ma makro!(…) macro_rules! makro
+            kw crate::
+            kw self::
+            ex false     const ENABLED: bool
+            ex true      const ENABLED: bool
+        "#]],
+    );
+}
+
 #[test]
 fn complete_traits_on_impl_trait_block() {
     check(
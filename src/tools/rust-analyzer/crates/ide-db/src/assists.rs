@@ -30,6 +30,9 @@ pub struct Assist {
     /// structure, such as a diagnostic.
     pub source_change: Option<SourceChange>,
     pub trigger_signature_help: bool,
+    /// If set, the assist can only be resolved with a parameter supplied by the client, e.g. a
+    /// destination picked by the user. This describes the parameter to the user.
+    pub parameter_prompt: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -43,6 +46,10 @@ pub enum AssistKind {
     RefactorExtract,
     RefactorInline,
     RefactorRewrite,
+    /// An action on a whole file, e.g. [`AssistKind::SourceFixAll`].
+    Source,
+    /// An assist applied to all the places of a file it is applicable to at once.
+    SourceFixAll,
 }
 
 impl AssistKind {
@@ -59,6 +66,7 @@ pub fn contains(self, other: AssistKind) -> bool {
                     | AssistKind::RefactorInline
                     | AssistKind::RefactorRewrite
             ),
+            AssistKind::Source => other == AssistKind::SourceFixAll,
             _ => false,
         }
     }
@@ -72,6 +80,8 @@ pub fn name(&self) -> &str {
             AssistKind::RefactorExtract => "RefactorExtract",
             AssistKind::RefactorInline => "RefactorInline",
             AssistKind::RefactorRewrite => "RefactorRewrite",
+            AssistKind::Source => "Source",
+            AssistKind::SourceFixAll => "SourceFixAll",
         }
     }
 }
@@ -88,6 +98,8 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             "RefactorExtract" => Ok(AssistKind::RefactorExtract),
             "RefactorInline" => Ok(AssistKind::RefactorInline),
             "RefactorRewrite" => Ok(AssistKind::RefactorRewrite),
+            "Source" => Ok(AssistKind::Source),
+            "SourceFixAll" => Ok(AssistKind::SourceFixAll),
             unknown => Err(format!("Unknown AssistKind: '{unknown}'")),
         }
     }
@@ -100,7 +112,7 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
 
 /// A way to control how many assist to resolve during the assist resolution.
 /// When an assist is resolved, its edits are calculated that might be costly to always do by default.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AssistResolveStrategy {
     /// No assists should be resolved.
     None,
@@ -113,12 +125,15 @@ pub enum AssistResolveStrategy {
 /// Hold the [`AssistId`] data of a certain assist to resolve.
 /// The original id object cannot be used due to a `'static` lifetime
 /// and the requirement to construct this struct dynamically during the resolve handling.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SingleResolve {
     /// The id of the assist.
     pub assist_id: String,
     // The kind of the assist.
     pub assist_kind: AssistKind,
+    /// The parameter the client supplied for an assist with a
+    /// [`parameter_prompt`](Assist::parameter_prompt).
+    pub parameter: Option<String>,
 }
 
 impl AssistResolveStrategy {
@@ -1,7 +1,7 @@
 //! See [`FamousDefs`].
 
 use base_db::{CrateOrigin, LangCrateOrigin, SourceDatabase};
-use hir::{Crate, Enum, Function, Macro, Module, ScopeDef, Semantics, Trait};
+use hir::{Crate, Enum, Function, Macro, Module, ScopeDef, Semantics, Struct, Trait};
 
 use crate::RootDatabase;
 
@@ -54,6 +54,14 @@ pub fn core_convert_Into(&self) -> Option<Trait> {
         self.find_trait("core:convert:Into")
     }
 
+    pub fn core_convert_TryFrom(&self) -> Option<Trait> {
+        self.find_trait("core:convert:TryFrom")
+    }
+
+    pub fn core_convert_TryInto(&self) -> Option<Trait> {
+        self.find_trait("core:convert:TryInto")
+    }
+
     pub fn core_convert_Index(&self) -> Option<Trait> {
         self.find_trait("core:ops:Index")
     }
@@ -78,6 +86,14 @@ pub fn core_iter_IntoIterator(&self) -> Option<Trait> {
         self.find_trait("core:iter:traits:collect:IntoIterator")
     }
 
+    pub fn core_iter_FromIterator(&self) -> Option<Trait> {
+        self.find_trait("core:iter:traits:collect:FromIterator")
+    }
+
+    pub fn core_iter_FusedIterator(&self) -> Option<Trait> {
+        self.find_trait("core:iter:traits:marker:FusedIterator")
+    }
+
     pub fn core_iter(&self) -> Option<Module> {
         self.find_module("core:iter")
     }
@@ -122,6 +138,26 @@ pub fn core_macros_unimplemented(&self) -> Option<Macro> {
         self.find_macro("core:unimplemented")
     }
 
+    pub fn alloc_vec_Vec(&self) -> Option<Struct> {
+        self.find_struct("alloc:vec:Vec")
+    }
+
+    pub fn std_sync_Mutex(&self) -> Option<Struct> {
+        self.find_struct("std:sync:Mutex")
+    }
+
+    pub fn std_sync_RwLock(&self) -> Option<Struct> {
+        self.find_struct("std:sync:RwLock")
+    }
+
+    pub fn std_thread_JoinHandle(&self) -> Option<Struct> {
+        self.find_struct("std:thread:JoinHandle")
+    }
+
+    pub fn std_thread_ScopedJoinHandle(&self) -> Option<Struct> {
+        self.find_struct("std:thread:ScopedJoinHandle")
+    }
+
     pub fn builtin_crates(&self) -> impl Iterator<Item = Crate> {
         IntoIterator::into_iter([
             self.std(),
@@ -154,6 +190,13 @@ fn find_enum(&self, path: &str) -> Option<Enum> {
         }
     }
 
+    fn find_struct(&self, path: &str) -> Option<Struct> {
+        match self.find_def(path)? {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Adt(hir::Adt::Struct(it))) => Some(it),
+            _ => None,
+        }
+    }
+
     fn find_module(&self, path: &str) -> Option<Module> {
         match self.find_def(path)? {
             hir::ScopeDef::ModuleDef(hir::ModuleDef::Module(it)) => Some(it),
@@ -50,12 +50,23 @@ fn from(granularity: ImportGranularity) -> Self {
     }
 }
 
+/// Which imports are put into the same group when grouping them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImportGroupStyle {
+    /// `std` and `core`, other crates, `crate`, `self` and `super` imports are in separate groups.
+    Default,
+    /// `std`, `core` and `alloc`, other crates, and imports of the current crate are in separate
+    /// groups, like rustfmt's `group_imports = "StdExternalCrate"`.
+    StdExternalCrate,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct InsertUseConfig {
     pub granularity: ImportGranularity,
     pub enforce_granularity: bool,
     pub prefix_kind: PrefixKind,
     pub group: bool,
+    pub group_style: ImportGroupStyle,
     pub skip_glob_imports: bool,
 }
 
@@ -236,7 +247,7 @@ fn insert_use_with_alias_option(
 
     // either we weren't allowed to merge or there is no import that fits the merge conditions
     // so look for the place we have to insert to
-    insert_use_(scope, use_item, cfg.group);
+    insert_use_(scope, use_item, cfg.group.then_some(cfg.group_style));
 }
 
 pub fn ast_to_remove_for_path_in_use_stmt(path: &ast::Path) -> Option<Box<dyn Removable>> {
@@ -260,8 +271,9 @@ pub fn remove_path_if_in_use_stmt(path: &ast::Path) {
     }
 }
 
-#[derive(Eq, PartialEq, PartialOrd, Ord)]
-enum ImportGroup {
+/// The groups imports are sorted into, separated by blank lines.
+#[derive(Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum ImportGroup {
     // the order here defines the order of new group inserts
     Std,
     ExternCrate,
@@ -272,7 +284,7 @@ enum ImportGroup {
 }
 
 impl ImportGroup {
-    fn new(use_tree: &ast::UseTree) -> ImportGroup {
+    pub fn new(use_tree: &ast::UseTree) -> ImportGroup {
         if use_tree.path().is_none() && use_tree.use_tree_list().is_some() {
             return ImportGroup::One;
         }
@@ -297,6 +309,24 @@ fn new(use_tree: &ast::UseTree) -> ImportGroup {
             PathSegmentKind::Type { .. } => ImportGroup::ExternCrate,
         }
     }
+
+    /// The group of `use_tree` when grouping imports with `style`.
+    pub fn with_style(use_tree: &ast::UseTree, style: ImportGroupStyle) -> ImportGroup {
+        let group = ImportGroup::new(use_tree);
+        match style {
+            ImportGroupStyle::Default => group,
+            ImportGroupStyle::StdExternalCrate => match group {
+                ImportGroup::ThisModule | ImportGroup::SuperModule => ImportGroup::ThisCrate,
+                ImportGroup::ExternCrate if is_alloc_path(use_tree) => ImportGroup::Std,
+                group => group,
+            },
+        }
+    }
+}
+
+fn is_alloc_path(use_tree: &ast::UseTree) -> bool {
+    let first_segment = use_tree.path().as_ref().and_then(ast::Path::first_segment);
+    first_segment.and_then(|it| it.name_ref()).is_some_and(|it| it.text() == "alloc")
 }
 
 #[derive(PartialEq, PartialOrd, Debug, Clone, Copy)]
@@ -397,11 +427,10 @@ fn guess_granularity_from_scope(scope: &ImportScope) -> ImportGranularityGuess {
     }
 }
 
-fn insert_use_(scope: &ImportScope, use_item: ast::Use, group_imports: bool) {
+fn insert_use_(scope: &ImportScope, use_item: ast::Use, group_style: Option<ImportGroupStyle>) {
     let scope_syntax = scope.as_syntax_node();
     let insert_use_tree =
         use_item.use_tree().expect("`use_item` should have a use tree for `insert_path`");
-    let group = ImportGroup::new(&insert_use_tree);
     let path_node_iter = scope_syntax
         .children()
         .filter_map(|node| ast::Use::cast(node.clone()).zip(Some(node)))
@@ -410,13 +439,14 @@ fn insert_use_(scope: &ImportScope, use_item: ast::Use, group_imports: bool) {
             Some((tree, node))
         });
 
-    if group_imports {
+    if let Some(style) = group_style {
+        let group = ImportGroup::with_style(&insert_use_tree, style);
         // Iterator that discards anything that's not in the required grouping
         // This implementation allows the user to rearrange their import groups as this only takes the first group that fits
         let group_iter = path_node_iter
             .clone()
-            .skip_while(|(use_tree, ..)| ImportGroup::new(use_tree) != group)
-            .take_while(|(use_tree, ..)| ImportGroup::new(use_tree) == group);
+            .skip_while(|(use_tree, ..)| ImportGroup::with_style(use_tree, style) != group)
+            .take_while(|(use_tree, ..)| ImportGroup::with_style(use_tree, style) == group);
 
         // track the last element we iterated over, if this is still None after the iteration then that means we never iterated in the first place
         let mut last = None;
@@ -442,7 +472,7 @@ fn insert_use_(scope: &ImportScope, use_item: ast::Use, group_imports: bool) {
         // find the group that comes after where we want to insert
         let post_group = path_node_iter
             .inspect(|(.., node)| last = Some(node.clone()))
-            .find(|(use_tree, ..)| ImportGroup::new(use_tree) > group);
+            .find(|(use_tree, ..)| ImportGroup::with_style(use_tree, style) > group);
         if let Some((.., node)) = post_group {
             cov_mark::hit!(insert_group_new_group);
             ted::insert(ted::Position::before(&node), use_item.syntax());
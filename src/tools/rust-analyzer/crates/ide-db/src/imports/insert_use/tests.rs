@@ -96,6 +96,7 @@ fn insert_not_group() {
             enforce_granularity: true,
             prefix_kind: PrefixKind::Plain,
             group: false,
+            group_style: ImportGroupStyle::Default,
             skip_glob_imports: true,
         },
     );
@@ -358,6 +359,42 @@ fn insert_missing_group_self() {
     )
 }
 
+#[test]
+fn insert_std_external_crate_self_into_crate_group() {
+    check_std_external_crate(
+        "self::bar::C",
+        r"
+use std::fmt;
+
+use foo::bar::A;
+
+use crate::a::B;",
+        r"
+use std::fmt;
+
+use foo::bar::A;
+
+use self::bar::C;
+use crate::a::B;",
+    )
+}
+
+#[test]
+fn insert_std_external_crate_alloc_into_std_group() {
+    check_std_external_crate(
+        "alloc::vec::Vec",
+        r"
+use core::fmt;
+
+use foo::bar::A;",
+        r"
+use alloc::vec::Vec;
+use core::fmt;
+
+use foo::bar::A;",
+    )
+}
+
 #[test]
 fn insert_no_imports() {
     check_crate(
@@ -396,6 +433,7 @@ fn insert_empty_file() {
             enforce_granularity: true,
             prefix_kind: PrefixKind::Plain,
             group: false,
+            group_style: ImportGroupStyle::Default,
             skip_glob_imports: true,
         },
     );
@@ -431,6 +469,7 @@ mod x {
             enforce_granularity: true,
             prefix_kind: PrefixKind::Plain,
             group: false,
+            group_style: ImportGroupStyle::Default,
             skip_glob_imports: true,
         },
     );
@@ -461,6 +500,7 @@ fn insert_after_inner_attr() {
             enforce_granularity: true,
             prefix_kind: PrefixKind::Plain,
             group: false,
+            group_style: ImportGroupStyle::Default,
             skip_glob_imports: true,
         },
     );
@@ -869,6 +909,7 @@ fn merge_mod_into_glob() {
             enforce_granularity: true,
             prefix_kind: PrefixKind::Plain,
             group: false,
+            group_style: ImportGroupStyle::Default,
             skip_glob_imports: false,
         },
     )
@@ -885,6 +926,7 @@ fn merge_self_glob() {
             enforce_granularity: true,
             prefix_kind: PrefixKind::Plain,
             group: false,
+            group_style: ImportGroupStyle::Default,
             skip_glob_imports: false,
         },
     )
@@ -1197,6 +1239,7 @@ fn insert_with_renamed_import_simple_use() {
             prefix_kind: hir::PrefixKind::BySelf,
             enforce_granularity: true,
             group: true,
+            group_style: ImportGroupStyle::Default,
             skip_glob_imports: true,
         },
     );
@@ -1217,6 +1260,7 @@ fn insert_with_renamed_import_complex_use() {
             prefix_kind: hir::PrefixKind::BySelf,
             enforce_granularity: true,
             group: true,
+            group_style: ImportGroupStyle::Default,
             skip_glob_imports: true,
         },
     );
@@ -1270,6 +1314,23 @@ fn check(
             enforce_granularity: true,
             prefix_kind: PrefixKind::Plain,
             group: true,
+            group_style: ImportGroupStyle::Default,
+            skip_glob_imports: true,
+        },
+    )
+}
+
+fn check_std_external_crate(path: &str, ra_fixture_before: &str, ra_fixture_after: &str) {
+    check_with_config(
+        path,
+        ra_fixture_before,
+        ra_fixture_after,
+        &InsertUseConfig {
+            granularity: ImportGranularity::Item,
+            enforce_granularity: true,
+            prefix_kind: PrefixKind::Plain,
+            group: true,
+            group_style: ImportGroupStyle::StdExternalCrate,
             skip_glob_imports: true,
         },
     )
@@ -38,6 +38,7 @@ pub mod generated {
 pub mod syntax_helpers {
     pub mod format_string;
     pub mod format_string_exprs;
+    pub mod insert_item;
     pub mod insert_whitespace_into_node;
     pub mod node_ext;
 
@@ -147,6 +148,7 @@ pub fn new(lru_capacity: Option<usize>) -> RootDatabase {
         db.set_local_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_library_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_expand_proc_attr_macros_with_durability(false, Durability::HIGH);
+        db.set_macro_expansion_limits_with_durability(Default::default(), Durability::HIGH);
         db.update_base_query_lru_capacities(lru_capacity);
         db.setup_syntax_context_root();
         db
@@ -165,6 +167,22 @@ pub fn update_base_query_lru_capacities(&mut self, lru_capacity: Option<usize>)
         hir::db::BorrowckQuery.in_db_mut(self).set_lru_capacity(base_db::DEFAULT_BORROWCK_LRU_CAP);
     }
 
+    /// Evicts all but the most recently used values of the queries that are LRU cached by
+    /// default, along with the item trees and macro inputs, which can be opted into LRU caching
+    /// via [`Self::update_lru_capacities`]. Evicted values are recomputed on demand.
+    pub fn trim_lru_caches(&mut self) {
+        use hir::db as hir_db;
+
+        let _p = tracing::span!(tracing::Level::INFO, "RootDatabase::trim_lru_caches").entered();
+        base_db::FileTextQuery.in_db_mut(self).trim_lru();
+        base_db::ParseQuery.in_db_mut(self).trim_lru();
+        hir_db::AstIdMapQuery.in_db_mut(self).trim_lru();
+        hir_db::MacroArgQuery.in_db_mut(self).trim_lru();
+        hir_db::ParseMacroExpansionQuery.in_db_mut(self).trim_lru();
+        hir_db::FileItemTreeQuery.in_db_mut(self).trim_lru();
+        hir_db::BorrowckQuery.in_db_mut(self).trim_lru();
+    }
+
     pub fn update_lru_capacities(&mut self, lru_capacities: &FxHashMap<Box<str>, usize>) {
         use hir::db as hir_db;
 
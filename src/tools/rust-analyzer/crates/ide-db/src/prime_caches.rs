@@ -1,7 +1,9 @@
 //! rust-analyzer is lazy and doesn't compute anything unless asked. This
 //! sometimes is counter productive when, for example, the first goto definition
 //! request takes longer to compute. This modules implemented prepopulation of
-//! various caches, it's not really advanced at the moment.
+//! various caches: the `DefMap`s and `ImportMap`s of the crates, and the symbol
+//! indices of the workspace crates. The work is spread over a number of worker
+//! threads, in dependency order, and gives way to foreground work.
 mod topologic_sort;
 
 use std::time::Duration;
@@ -13,7 +15,8 @@
         salsa::{Database, ParallelDatabase, Snapshot},
         Cancelled, CrateGraph, CrateId, SourceDatabase, SourceDatabaseExt,
     },
-    FxHashSet, FxIndexMap, RootDatabase,
+    symbol_index::SymbolsDatabase,
+    FxHashMap, FxHashSet, FxIndexMap, RootDatabase,
 };
 
 /// We're indexing many crates.
@@ -31,46 +34,37 @@ pub fn parallel_prime_caches(
     db: &RootDatabase,
     num_worker_threads: u8,
     cb: &(dyn Fn(ParallelPrimeCachesProgress) + Sync),
+    should_yield: &dyn Fn() -> bool,
 ) {
     let _p = tracing::span!(tracing::Level::INFO, "prime_caches").entered();
 
     let graph = db.crate_graph();
-    let mut crates_to_prime = {
-        let crate_ids = compute_crates_to_prime(db, &graph);
-
-        let mut builder = topologic_sort::TopologicalSortIter::builder();
-
-        for &crate_id in &crate_ids {
-            let crate_data = &graph[crate_id];
-            let dependencies = crate_data
-                .dependencies
-                .iter()
-                .map(|d| d.crate_id)
-                .filter(|i| crate_ids.contains(i));
-
-            builder.add(crate_id, dependencies);
-        }
-
-        builder.build()
-    };
+    let mut jobs = priming_jobs(db, &graph);
 
     enum ParallelPrimeCacheWorkerProgress {
-        BeginCrate { crate_id: CrateId, crate_name: String },
-        EndCrate { crate_id: CrateId },
+        BeginJob { job: PrimingJob, crate_name: String },
+        EndJob { job: PrimingJob },
     }
 
     let (work_sender, progress_receiver) = {
         let (progress_sender, progress_receiver) = crossbeam_channel::unbounded();
         let (work_sender, work_receiver) = crossbeam_channel::unbounded();
         let prime_caches_worker = move |db: Snapshot<RootDatabase>| {
-            while let Ok((crate_id, crate_name)) = work_receiver.recv() {
+            while let Ok((job, crate_name)) = work_receiver.recv() {
                 progress_sender
-                    .send(ParallelPrimeCacheWorkerProgress::BeginCrate { crate_id, crate_name })?;
+                    .send(ParallelPrimeCacheWorkerProgress::BeginJob { job, crate_name })?;
 
-                // This also computes the DefMap
-                db.import_map(crate_id);
+                match job {
+                    PrimingJob::ImportMap(crate_id) => {
+                        // This also computes the DefMap
+                        db.import_map(crate_id);
+                    }
+                    PrimingJob::CrateSymbols(crate_id) => {
+                        db.crate_symbols(crate_id.into());
+                    }
+                }
 
-                progress_sender.send(ParallelPrimeCacheWorkerProgress::EndCrate { crate_id })?;
+                progress_sender.send(ParallelPrimeCacheWorkerProgress::EndJob { job })?;
             }
 
             Ok::<_, crossbeam_channel::SendError<_>>(())
@@ -90,8 +84,16 @@ enum ParallelPrimeCacheWorkerProgress {
         (work_sender, progress_receiver)
     };
 
-    let crates_total = crates_to_prime.pending();
+    // Workspace crates have two jobs, a crate is done once all of its jobs are.
+    let mut jobs_left_of_crate = FxHashMap::<CrateId, usize>::default();
+    for job in jobs.pending() {
+        *jobs_left_of_crate.entry(job.crate_id()).or_default() += 1;
+    }
+    let crates_total = jobs_left_of_crate.len();
     let mut crates_done = 0;
+    // Jobs are handed out one at a time as workers become idle rather than all at once, so that
+    // the ones most other jobs wait on are picked first.
+    let mut jobs_in_flight = 0;
 
     // an index map is used to preserve ordering so we can sort the progress report in order of
     // "longest crate to index" first
@@ -101,13 +103,13 @@ enum ParallelPrimeCacheWorkerProgress {
     while crates_done < crates_total {
         db.unwind_if_cancelled();
 
-        for crate_id in &mut crates_to_prime {
-            work_sender
-                .send((
-                    crate_id,
-                    graph[crate_id].display_name.as_deref().unwrap_or_default().to_owned(),
-                ))
-                .ok();
+        // No new jobs are started while `should_yield` tells us that there is more urgent work,
+        // like highlighting the file the user is looking at. The jobs in flight still finish.
+        while jobs_in_flight < num_worker_threads && !should_yield() {
+            let Some(job) = jobs.next() else { break };
+            let crate_name = graph[job.crate_id()].display_name.as_deref().unwrap_or_default();
+            work_sender.send((job, crate_name.to_owned())).ok();
+            jobs_in_flight += 1;
         }
 
         // recv_timeout is somewhat a hack, we need a way to from this thread check to see if the current salsa revision
@@ -125,13 +127,18 @@ enum ParallelPrimeCacheWorkerProgress {
             }
         };
         match worker_progress {
-            ParallelPrimeCacheWorkerProgress::BeginCrate { crate_id, crate_name } => {
-                crates_currently_indexing.insert(crate_id, crate_name);
+            ParallelPrimeCacheWorkerProgress::BeginJob { job, crate_name } => {
+                crates_currently_indexing.insert(job, crate_name);
             }
-            ParallelPrimeCacheWorkerProgress::EndCrate { crate_id } => {
-                crates_currently_indexing.swap_remove(&crate_id);
-                crates_to_prime.mark_done(crate_id);
-                crates_done += 1;
+            ParallelPrimeCacheWorkerProgress::EndJob { job } => {
+                crates_currently_indexing.swap_remove(&job);
+                jobs.mark_done(job);
+                jobs_in_flight -= 1;
+                let jobs_left = jobs_left_of_crate.get_mut(&job.crate_id()).unwrap();
+                *jobs_left -= 1;
+                if *jobs_left == 0 {
+                    crates_done += 1;
+                }
             }
         };
 
@@ -145,11 +152,58 @@ enum ParallelPrimeCacheWorkerProgress {
     }
 }
 
-fn compute_crates_to_prime(db: &RootDatabase, graph: &CrateGraph) -> FxHashSet<CrateId> {
-    // We're only interested in the workspace crates and the `ImportMap`s of their direct
-    // dependencies, though in practice the latter also compute the `DefMap`s.
-    // We don't prime transitive dependencies because they're generally not visible in
-    // the current workspace.
+/// The jobs priming the `ImportMap`s of the dependencies of the workspace crates and the symbols
+/// of the workspace crates, in the order they can run in.
+fn priming_jobs(
+    db: &RootDatabase,
+    graph: &CrateGraph,
+) -> topologic_sort::TopologicalSortIter<PrimingJob> {
+    let local_crates = local_crates(db, graph);
+    let crate_ids = compute_crates_to_prime(graph, &local_crates);
+    let import_maps_of = |crate_id: CrateId| {
+        graph[crate_id]
+            .dependencies
+            .iter()
+            .map(|d| d.crate_id)
+            .filter(|i| crate_ids.contains(i))
+            .map(PrimingJob::ImportMap)
+    };
+
+    let mut builder = topologic_sort::TopologicalSortIter::builder();
+
+    for &crate_id in &crate_ids {
+        builder.add(PrimingJob::ImportMap(crate_id), import_maps_of(crate_id));
+    }
+    // The symbols of a crate are collected from its `DefMap`, which needs the ones of its
+    // dependencies.
+    for &crate_id in &local_crates {
+        let own_import_map =
+            crate_ids.contains(&crate_id).then_some(PrimingJob::ImportMap(crate_id));
+        builder.add(
+            PrimingJob::CrateSymbols(crate_id),
+            import_maps_of(crate_id).chain(own_import_map),
+        );
+    }
+
+    builder.build()
+}
+
+/// A unit of work for the workers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PrimingJob {
+    ImportMap(CrateId),
+    CrateSymbols(CrateId),
+}
+
+impl PrimingJob {
+    fn crate_id(self) -> CrateId {
+        match self {
+            PrimingJob::ImportMap(it) | PrimingJob::CrateSymbols(it) => it,
+        }
+    }
+}
+
+fn local_crates(db: &RootDatabase, graph: &CrateGraph) -> Vec<CrateId> {
     graph
         .iter()
         .filter(|&id| {
@@ -157,6 +211,77 @@ fn compute_crates_to_prime(db: &RootDatabase, graph: &CrateGraph) -> FxHashSet<C
             let root_id = db.file_source_root(file_id);
             !db.source_root(root_id).is_library
         })
-        .flat_map(|id| graph[id].dependencies.iter().map(|krate| krate.crate_id))
         .collect()
 }
+
+fn compute_crates_to_prime(graph: &CrateGraph, local_crates: &[CrateId]) -> FxHashSet<CrateId> {
+    // We're only interested in the workspace crates and the `ImportMap`s of their direct
+    // dependencies, though in practice the latter also compute the `DefMap`s.
+    // We don't prime transitive dependencies because they're generally not visible in
+    // the current workspace.
+    local_crates
+        .iter()
+        .flat_map(|&id| graph[id].dependencies.iter().map(|krate| krate.crate_id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use expect_test::expect;
+    use test_fixture::WithFixture;
+
+    use super::*;
+
+    const FIXTURE: &str = r#"
+//- /transitive.rs crate:transitive new_source_root:library
+pub struct Transitive;
+//- /dep.rs crate:dep deps:transitive
+pub struct Dep;
+//- /lib.rs crate:lib deps:dep new_source_root:local
+pub struct Lib;
+//- /main.rs crate:main deps:lib
+fn main() {}
+"#;
+
+    #[test]
+    fn jobs_of_workspace_crates_and_their_dependencies() {
+        let (db, _) = RootDatabase::with_many_files(FIXTURE);
+        let graph = db.crate_graph();
+        let mut jobs = priming_jobs(&db, &graph);
+        let mut order = Vec::new();
+        while let Some(job) = jobs.next() {
+            let name = graph[job.crate_id()].display_name.as_deref().unwrap_or_default();
+            match job {
+                PrimingJob::ImportMap(_) => order.push(format!("import map of {name}")),
+                PrimingJob::CrateSymbols(_) => order.push(format!("symbols of {name}")),
+            }
+            jobs.mark_done(job);
+        }
+        expect![[r#"
+            [
+                "import map of dep",
+                "import map of lib",
+                "symbols of lib",
+                "symbols of main",
+            ]
+        "#]]
+        .assert_debug_eq(&order);
+    }
+
+    #[test]
+    fn progress_counts_crates() {
+        let (db, _) = RootDatabase::with_many_files(FIXTURE);
+        let reports = Mutex::new(Vec::new());
+        parallel_prime_caches(
+            &db,
+            2,
+            &|progress| reports.lock().unwrap().push((progress.crates_done, progress.crates_total)),
+            &|| false,
+        );
+        let reports = reports.into_inner().unwrap();
+        assert!(reports.iter().all(|&(_, total)| total == 3));
+        assert_eq!(reports.last(), Some(&(3, 3)));
+    }
+}
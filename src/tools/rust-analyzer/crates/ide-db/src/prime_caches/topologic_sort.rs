@@ -57,8 +57,9 @@ pub(crate) fn builder() -> TopologicSortIterBuilder<T> {
         TopologicSortIterBuilder::new()
     }
 
-    pub(crate) fn pending(&self) -> usize {
-        self.nodes.len()
+    /// The items that are not done yet.
+    pub(crate) fn pending(&self) -> impl Iterator<Item = T> + '_ {
+        self.nodes.keys().copied()
     }
 
     pub(crate) fn mark_done(&mut self, item: T) {
@@ -78,11 +79,19 @@ pub(crate) fn mark_done(&mut self, item: T) {
     }
 }
 
-impl<T> Iterator for TopologicalSortIter<T> {
+impl<T> Iterator for TopologicalSortIter<T>
+where
+    T: Copy + Eq + PartialEq + Hash,
+{
     type Item = T;
 
+    /// Returns the ready item with the most successors first, as it unblocks the most work.
     fn next(&mut self) -> Option<Self::Item> {
-        self.ready.pop_front()
+        let num_successors =
+            |item: &T| self.nodes.get(item).map_or(0, |entry| entry.successors.len());
+        let (index, _) =
+            self.ready.iter().enumerate().rev().max_by_key(|&(_, item)| num_successors(item))?;
+        self.ready.remove(index)
     }
 }
 
@@ -96,3 +105,35 @@ fn default() -> Self {
         Self { successors: Default::default(), num_predecessors: 0 }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::TopologicalSortIter;
+
+    #[test]
+    fn most_successors_first() {
+        let mut builder = TopologicalSortIter::builder();
+        builder.add(1, []);
+        builder.add(2, []);
+        builder.add(3, [2]);
+        builder.add(4, [2]);
+        builder.add(5, [1, 4]);
+        let mut iter = builder.build();
+
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), None);
+
+        iter.mark_done(2);
+        // Both are ready now, but only 4 unblocks another item.
+        assert_eq!(iter.next(), Some(4));
+        assert_eq!(iter.next(), Some(3));
+        iter.mark_done(1);
+        iter.mark_done(4);
+        assert_eq!(iter.next(), Some(5));
+        iter.mark_done(3);
+        iter.mark_done(5);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.pending().count(), 0);
+    }
+}
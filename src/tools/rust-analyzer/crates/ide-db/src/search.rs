@@ -15,13 +15,15 @@
 use nohash_hasher::IntMap;
 use once_cell::unsync::Lazy;
 use parser::SyntaxKind;
-use syntax::{ast, match_ast, AstNode, AstToken, SyntaxElement, TextRange, TextSize};
+use syntax::{
+    ast, match_ast, AstNode, AstToken, SmolStr, SyntaxElement, SyntaxNode, TextRange, TextSize,
+};
 use triomphe::Arc;
 
 use crate::{
     defs::{Definition, NameClass, NameRefClass},
     traits::{as_trait_assoc_def, convert_to_def_in_trait},
-    RootDatabase,
+    FxHashMap, RootDatabase,
 };
 
 #[derive(Debug, Default, Clone)]
@@ -437,64 +439,16 @@ pub fn search(&self, sink: &mut dyn FnMut(FileId, FileReference) -> bool) {
         let _p = tracing::span!(tracing::Level::INFO, "FindUsages:search").entered();
         let sema = self.sema;
 
-        let search_scope = {
-            // FIXME: Is the trait scope needed for trait impl assoc items?
-            let base =
-                as_trait_assoc_def(sema.db, self.def).unwrap_or(self.def).search_scope(sema.db);
-            match &self.scope {
-                None => base,
-                Some(scope) => base.intersection(scope),
-            }
-        };
-
-        let name = match self.def {
-            // special case crate modules as these do not have a proper name
-            Definition::Module(module) if module.is_crate_root() => {
-                // FIXME: This assumes the crate name is always equal to its display name when it
-                // really isn't
-                // we should instead look at the dependency edge name and recursively search our way
-                // up the ancestors
-                module
-                    .krate()
-                    .display_name(self.sema.db)
-                    .map(|crate_name| crate_name.crate_name().as_smol_str().clone())
-            }
-            _ => {
-                let self_kw_refs = || {
-                    self.include_self_kw_refs.as_ref().and_then(|ty| {
-                        ty.as_adt()
-                            .map(|adt| adt.name(self.sema.db))
-                            .or_else(|| ty.as_builtin().map(|builtin| builtin.name()))
-                    })
-                };
-                // We need to unescape the name in case it is written without "r#" in earlier
-                // editions of Rust where it isn't a keyword.
-                self.def.name(sema.db).or_else(self_kw_refs).map(|it| it.unescaped().to_smol_str())
-            }
-        };
-        let name = match &name {
-            Some(s) => s.as_str(),
+        let search_scope = self.search_scope();
+        let name = match self.search_name() {
+            Some(name) => name,
             None => return,
         };
+        let name = name.as_str();
         let finder = &Finder::new(name);
         let include_self_kw_refs =
             self.include_self_kw_refs.as_ref().map(|ty| (ty, Finder::new("Self")));
 
-        // for<'a> |text: &'a str, name: &'a str, search_range: TextRange| -> impl Iterator<Item = TextSize> + 'a { ... }
-        fn match_indices<'a>(
-            text: &'a str,
-            finder: &'a Finder<'a>,
-            search_range: TextRange,
-        ) -> impl Iterator<Item = TextSize> + 'a {
-            finder.find_iter(text.as_bytes()).filter_map(move |idx| {
-                let offset: TextSize = idx.try_into().unwrap();
-                if !search_range.contains_inclusive(offset) {
-                    return None;
-                }
-                Some(offset)
-            })
-        }
-
         // for<'a> |scope: &'a SearchScope| -> impl Iterator<Item = (Arc<String>, FileId, TextRange)> + 'a { ... }
         fn scope_files<'a>(
             sema: &'a Semantics<'_, RootDatabase>,
@@ -509,32 +463,14 @@ fn scope_files<'a>(
             })
         }
 
-        let find_nodes = move |name: &str, node: &syntax::SyntaxNode, offset: TextSize| {
-            node.token_at_offset(offset)
-                .find(|it| {
-                    // `name` is stripped of raw ident prefix. See the comment on name retrieval above.
-                    it.text().trim_start_matches("r#") == name
-                })
-                .into_iter()
-                .flat_map(move |token| {
-                    // FIXME: There should be optimization potential here
-                    // Currently we try to descend everything we find which
-                    // means we call `Semantics::descend_into_macros` on
-                    // every textual hit. That function is notoriously
-                    // expensive even for things that do not get down mapped
-                    // into macros.
-                    sema.descend_into_macros(DescendPreference::None, token)
-                        .into_iter()
-                        .filter_map(|it| it.parent())
-                })
-        };
-
         for (text, file_id, search_range) in scope_files(sema, &search_scope) {
             self.sema.db.unwind_if_cancelled();
             let tree = Lazy::new(move || sema.parse(file_id).syntax().clone());
 
             // Search for occurrences of the items name
             for offset in match_indices(&text, finder, search_range) {
+                // Common names can have a lot of textual hits, each of which has to be resolved.
+                self.sema.db.unwind_if_cancelled();
                 tree.token_at_offset(offset).for_each(|token| {
                     let Some(str_token) = ast::String::cast(token.clone()) else { return };
                     if let Some((range, nameres)) =
@@ -544,7 +480,7 @@ fn scope_files<'a>(
                     }
                 });
 
-                for name in find_nodes(name, &tree, offset).filter_map(ast::NameLike::cast) {
+                for name in find_nodes(sema, name, &tree, offset).filter_map(ast::NameLike::cast) {
                     if match name {
                         ast::NameLike::NameRef(name_ref) => self.found_name_ref(&name_ref, sink),
                         ast::NameLike::Name(name) => self.found_name(&name, sink),
@@ -557,7 +493,9 @@ fn scope_files<'a>(
             // Search for occurrences of the `Self` referring to our type
             if let Some((self_ty, finder)) = &include_self_kw_refs {
                 for offset in match_indices(&text, finder, search_range) {
-                    for name_ref in find_nodes("Self", &tree, offset).filter_map(ast::NameRef::cast)
+                    self.sema.db.unwind_if_cancelled();
+                    for name_ref in
+                        find_nodes(sema, "Self", &tree, offset).filter_map(ast::NameRef::cast)
                     {
                         if self.found_self_ty_name_ref(self_ty, &name_ref, sink) {
                             return;
@@ -581,7 +519,7 @@ fn scope_files<'a>(
 
                 for offset in match_indices(&text, finder, search_range) {
                     for name_ref in
-                        find_nodes("super", &tree, offset).filter_map(ast::NameRef::cast)
+                        find_nodes(sema, "super", &tree, offset).filter_map(ast::NameRef::cast)
                     {
                         if self.found_name_ref(&name_ref, sink) {
                             return;
@@ -591,7 +529,7 @@ fn scope_files<'a>(
                 if let Some(finder) = &is_crate_root {
                     for offset in match_indices(&text, finder, search_range) {
                         for name_ref in
-                            find_nodes("crate", &tree, offset).filter_map(ast::NameRef::cast)
+                            find_nodes(sema, "crate", &tree, offset).filter_map(ast::NameRef::cast)
                         {
                             if self.found_name_ref(&name_ref, sink) {
                                 return;
@@ -633,7 +571,8 @@ fn scope_files<'a>(
                 let finder = &Finder::new("self");
 
                 for offset in match_indices(&text, finder, search_range) {
-                    for name_ref in find_nodes("self", &tree, offset).filter_map(ast::NameRef::cast)
+                    for name_ref in
+                        find_nodes(sema, "self", &tree, offset).filter_map(ast::NameRef::cast)
                     {
                         if self.found_self_module_name_ref(&name_ref, sink) {
                             return;
@@ -645,6 +584,50 @@ fn scope_files<'a>(
         }
     }
 
+    /// The scope the usages are searched for in, narrowed down to the scope set by the caller.
+    fn search_scope(&self) -> SearchScope {
+        // FIXME: Is the trait scope needed for trait impl assoc items?
+        let base = as_trait_assoc_def(self.sema.db, self.def)
+            .unwrap_or(self.def)
+            .search_scope(self.sema.db);
+        match &self.scope {
+            None => base,
+            Some(scope) => base.intersection(scope),
+        }
+    }
+
+    /// The name the usages are written with, matches of it are the candidates to be resolved.
+    fn search_name(&self) -> Option<SmolStr> {
+        match self.def {
+            // special case crate modules as these do not have a proper name
+            Definition::Module(module) if module.is_crate_root() => {
+                // FIXME: This assumes the crate name is always equal to its display name when it
+                // really isn't
+                // we should instead look at the dependency edge name and recursively search our way
+                // up the ancestors
+                module
+                    .krate()
+                    .display_name(self.sema.db)
+                    .map(|crate_name| crate_name.crate_name().as_smol_str().clone())
+            }
+            _ => {
+                let self_kw_refs = || {
+                    self.include_self_kw_refs.as_ref().and_then(|ty| {
+                        ty.as_adt()
+                            .map(|adt| adt.name(self.sema.db))
+                            .or_else(|| ty.as_builtin().map(|builtin| builtin.name()))
+                    })
+                };
+                // We need to unescape the name in case it is written without "r#" in earlier
+                // editions of Rust where it isn't a keyword.
+                self.def
+                    .name(self.sema.db)
+                    .or_else(self_kw_refs)
+                    .map(|it| it.unescaped().to_smol_str())
+            }
+        }
+    }
+
     fn found_self_ty_name_ref(
         &self,
         self_ty: &hir::Type,
@@ -864,6 +847,162 @@ fn found_name(
     }
 }
 
+/// Finds the usages of all `defs` in a single pass over their combined search scopes.
+///
+/// Every file is read and parsed only once, and each textual hit of a name is descended into
+/// macros once for all the definitions with that name, instead of once per definition. Modules
+/// can also be referred to through `self`, `super` and `crate`, so their usages are still searched
+/// for one by one.
+pub fn usages_of_all(
+    sema: &Semantics<'_, RootDatabase>,
+    defs: &[Definition],
+) -> FxHashMap<Definition, UsageSearchResult> {
+    let _p = tracing::span!(tracing::Level::INFO, "usages_of_all").entered();
+    let mut res: FxHashMap<Definition, UsageSearchResult> = FxHashMap::default();
+    let mut by_name: FxHashMap<SmolStr, Vec<(FindUsages<'_>, SearchScope)>> = FxHashMap::default();
+    for &def in defs {
+        if res.contains_key(&def) {
+            continue;
+        }
+        let usages = def.usages(sema);
+        if let Definition::Module(_) = def {
+            res.insert(def, usages.all());
+            continue;
+        }
+        res.insert(def, UsageSearchResult::default());
+        let Some(name) = usages.search_name() else { continue };
+        let scope = usages.search_scope();
+        by_name.entry(name).or_default().push((usages, scope));
+    }
+
+    let mut files: IntMap<FileId, Option<TextRange>> = IntMap::default();
+    for (_, scope) in by_name.values().flatten() {
+        for (&file_id, &range) in &scope.entries {
+            files
+                .entry(file_id)
+                .and_modify(|known| *known = known.zip(range).map(|(a, b)| a.cover(b)))
+                .or_insert(range);
+        }
+    }
+    let by_name = by_name
+        .iter()
+        .map(|(name, usages)| (name.as_str(), Finder::new(name.as_str()), usages))
+        .collect::<Vec<_>>();
+
+    for (file_id, search_range) in files {
+        sema.db.unwind_if_cancelled();
+        let text = sema.db.file_text(file_id);
+        let search_range = search_range.unwrap_or_else(|| TextRange::up_to(TextSize::of(&*text)));
+        let tree = Lazy::new(move || sema.parse(file_id).syntax().clone());
+
+        for (name, finder, usages) in &by_name {
+            let usages = usages
+                .iter()
+                .filter_map(|(usages, scope)| Some((usages, *scope.entries.get(&file_id)?)))
+                .collect::<Vec<_>>();
+            if usages.is_empty() {
+                continue;
+            }
+            for offset in match_indices(&text, finder, search_range) {
+                sema.db.unwind_if_cancelled();
+                let in_scope = usages
+                    .iter()
+                    .filter(|(_, range)| range.map_or(true, |it| it.contains_inclusive(offset)))
+                    .map(|&(usages, _)| usages)
+                    .collect::<Vec<_>>();
+                if in_scope.is_empty() {
+                    continue;
+                }
+
+                tree.token_at_offset(offset).for_each(|token| {
+                    let Some(str_token) = ast::String::cast(token.clone()) else { return };
+                    if let Some((range, nameres)) =
+                        sema.check_for_format_args_template(token, offset)
+                    {
+                        for usages in &in_scope {
+                            let found = &mut res.entry(usages.def).or_default().references;
+                            usages.found_format_args_ref(
+                                file_id,
+                                range,
+                                str_token.clone(),
+                                nameres,
+                                &mut |file_id, reference| {
+                                    found.entry(file_id).or_default().push(reference);
+                                    false
+                                },
+                            );
+                        }
+                    }
+                });
+
+                for name_like in
+                    find_nodes(sema, name, &tree, offset).filter_map(ast::NameLike::cast)
+                {
+                    for usages in &in_scope {
+                        let found = &mut res.entry(usages.def).or_default().references;
+                        let sink: &mut dyn FnMut(FileId, FileReference) -> bool =
+                            &mut |file_id, reference| {
+                                found.entry(file_id).or_default().push(reference);
+                                false
+                            };
+                        match &name_like {
+                            ast::NameLike::NameRef(name_ref) => {
+                                usages.found_name_ref(name_ref, sink)
+                            }
+                            ast::NameLike::Name(name) => usages.found_name(name, sink),
+                            ast::NameLike::Lifetime(lifetime) => {
+                                usages.found_lifetime(lifetime, sink)
+                            }
+                        };
+                    }
+                }
+            }
+        }
+    }
+    res
+}
+
+fn match_indices<'a>(
+    text: &'a str,
+    finder: &'a Finder<'a>,
+    search_range: TextRange,
+) -> impl Iterator<Item = TextSize> + 'a {
+    finder.find_iter(text.as_bytes()).filter_map(move |idx| {
+        let offset: TextSize = idx.try_into().unwrap();
+        if !search_range.contains_inclusive(offset) {
+            return None;
+        }
+        Some(offset)
+    })
+}
+
+/// The nodes of the tokens named `name` at `offset`, descended into macros.
+fn find_nodes<'a>(
+    sema: &'a Semantics<'_, RootDatabase>,
+    name: &'a str,
+    node: &SyntaxNode,
+    offset: TextSize,
+) -> impl Iterator<Item = SyntaxNode> + 'a {
+    node.token_at_offset(offset)
+        .find(|it| {
+            // `name` is stripped of raw ident prefix. See the comment on name retrieval in
+            // `FindUsages::search_name`.
+            it.text().trim_start_matches("r#") == name
+        })
+        .into_iter()
+        .flat_map(move |token| {
+            // FIXME: There should be optimization potential here
+            // Currently we try to descend everything we find which
+            // means we call `Semantics::descend_into_macros` on
+            // every textual hit. That function is notoriously
+            // expensive even for things that do not get down mapped
+            // into macros.
+            sema.descend_into_macros(DescendPreference::None, token)
+                .into_iter()
+                .filter_map(|it| it.parent())
+        })
+}
+
 fn def_to_ty(sema: &Semantics<'_, RootDatabase>, def: &Definition) -> Option<hir::Type> {
     match def {
         Definition::Adt(adt) => Some(adt.ty(sema.db)),
@@ -11,7 +11,8 @@
 use nohash_hasher::IntMap;
 use stdx::never;
 use syntax::{
-    algo, AstNode, SyntaxElement, SyntaxNode, SyntaxNodePtr, SyntaxToken, TextRange, TextSize,
+    algo, syntax_editor::SyntaxEditor, AstNode, SyntaxElement, SyntaxNode, SyntaxNodePtr,
+    SyntaxToken, TextRange, TextSize,
 };
 use text_edit::{TextEdit, TextEditBuilder};
 
@@ -20,6 +21,17 @@ pub struct SourceChange {
     pub source_file_edits: IntMap<FileId, (TextEdit, Option<SnippetEdit>)>,
     pub file_system_edits: Vec<FileSystemEdit>,
     pub is_snippet: bool,
+    /// Notes for the user about some of the edits, identified by the file and the range they
+    /// replace.
+    pub annotations: Vec<(FileId, TextRange, ChangeAnnotation)>,
+}
+
+/// A note about an edit which the client can show when previewing the change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeAnnotation {
+    pub label: String,
+    /// Whether the user should confirm the edit before it's applied.
+    pub needs_confirmation: bool,
 }
 
 impl SourceChange {
@@ -29,7 +41,7 @@ pub fn from_edits(
         source_file_edits: IntMap<FileId, (TextEdit, Option<SnippetEdit>)>,
         file_system_edits: Vec<FileSystemEdit>,
     ) -> Self {
-        SourceChange { source_file_edits, file_system_edits, is_snippet: false }
+        SourceChange { source_file_edits, file_system_edits, ..Default::default() }
     }
 
     pub fn from_text_edit(file_id: FileId, edit: TextEdit) -> Self {
@@ -86,8 +98,35 @@ pub fn merge(mut self, other: SourceChange) -> SourceChange {
         self.extend(other.source_file_edits);
         self.extend(other.file_system_edits);
         self.is_snippet |= other.is_snippet;
+        self.annotations.extend(other.annotations);
         self
     }
+
+    /// Summarizes which files the change touches, so that clients can preview it.
+    pub fn summary(&self) -> SourceChangeSummary {
+        let mut edited_files: Vec<_> = self
+            .source_file_edits
+            .iter()
+            .map(|(&file_id, (edit, _))| (file_id, edit.len()))
+            .collect();
+        edited_files.sort_unstable();
+        SourceChangeSummary { edited_files, file_system_edits: self.file_system_edits.len() }
+    }
+}
+
+/// The files a [`SourceChange`] touches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceChangeSummary {
+    /// The edited files with the number of edits in each, ordered by file.
+    pub edited_files: Vec<(FileId, usize)>,
+    /// The number of files and directories that are created, moved or deleted.
+    pub file_system_edits: usize,
+}
+
+impl SourceChangeSummary {
+    pub fn files_touched(&self) -> usize {
+        self.edited_files.len() + self.file_system_edits
+    }
 }
 
 impl Extend<(FileId, TextEdit)> for SourceChange {
@@ -117,7 +156,7 @@ impl From<IntMap<FileId, TextEdit>> for SourceChange {
     fn from(source_file_edits: IntMap<FileId, TextEdit>) -> SourceChange {
         let source_file_edits =
             source_file_edits.into_iter().map(|(file_id, edit)| (file_id, (edit, None))).collect();
-        SourceChange { source_file_edits, file_system_edits: Vec::new(), is_snippet: false }
+        SourceChange { source_file_edits, ..Default::default() }
     }
 }
 
@@ -296,6 +335,16 @@ pub fn replace(&mut self, range: TextRange, replace_with: impl Into<String>) {
     pub fn replace_ast<N: AstNode>(&mut self, old: N, new: N) {
         algo::diff(old.syntax(), new.syntax()).into_text_edit(&mut self.edit)
     }
+    /// Applies all edits recorded by `editor` to the current file.
+    ///
+    /// The editor must have been created for a node of the current file's
+    /// (immutable) tree. Conflicting edits are reported and dropped.
+    pub fn add_syntax_editor(&mut self, editor: SyntaxEditor) {
+        match editor.finish() {
+            Ok(edit) => algo::diff(edit.old_root(), edit.new_root()).into_text_edit(&mut self.edit),
+            Err(conflict) => never!("{}", conflict),
+        }
+    }
     pub fn create_file(&mut self, dst: AnchoredPathBuf, content: impl Into<String>) {
         let file_system_edit = FileSystemEdit::CreateFile { dst, initial_contents: content.into() };
         self.source_change.push_file_system_edit(file_system_edit);
@@ -304,6 +353,14 @@ pub fn move_file(&mut self, src: FileId, dst: AnchoredPathBuf) {
         let file_system_edit = FileSystemEdit::MoveFile { src, dst };
         self.source_change.push_file_system_edit(file_system_edit);
     }
+    /// Attaches `annotation` to the edit of `range` in the current file.
+    pub fn annotate(&mut self, range: TextRange, annotation: ChangeAnnotation) {
+        self.source_change.annotations.push((self.file_id, range, annotation));
+    }
+    pub fn delete_file(&mut self, src: FileId) {
+        let file_system_edit = FileSystemEdit::DeleteFile { src };
+        self.source_change.push_file_system_edit(file_system_edit);
+    }
     pub fn trigger_signature_help(&mut self) {
         self.trigger_signature_help = true;
     }
@@ -338,6 +395,12 @@ pub fn add_placeholder_snippet(&mut self, _cap: SnippetCap, node: impl AstNode)
         self.add_snippet(PlaceSnippet::Over(node.syntax().clone().into()))
     }
 
+    /// Adds a snippet to move the cursor selected over `token`
+    pub fn add_placeholder_snippet_token(&mut self, _cap: SnippetCap, token: SyntaxToken) {
+        assert!(token.parent().is_some());
+        self.add_snippet(PlaceSnippet::Over(token.into()))
+    }
+
     /// Adds a snippet to move the cursor selected over `nodes`
     ///
     /// This allows for renaming newly generated items without having to go
@@ -349,6 +412,30 @@ pub fn add_placeholder_snippet_group(&mut self, _cap: SnippetCap, nodes: Vec<Syn
         ))
     }
 
+    /// Adds a group of linked placeholder snippets over `tokens`
+    ///
+    /// Like [`Self::add_placeholder_snippet_group`], for when the occurrences of a new name are
+    /// spread over different kinds of nodes, like the `Name` of an item and the `NameRef`s of the
+    /// paths to it.
+    pub fn add_placeholder_snippet_group_tokens(
+        &mut self,
+        _cap: SnippetCap,
+        tokens: Vec<SyntaxToken>,
+    ) {
+        assert!(tokens.iter().all(|token| token.parent().is_some()));
+        self.add_snippet(PlaceSnippet::OverGroup(
+            tokens.into_iter().map(|token| token.into()).collect(),
+        ))
+    }
+
+    /// Adds a group of linked placeholder snippets over `ranges`
+    ///
+    /// Unlike the other snippet methods, `ranges` refer to the text of the current file after all
+    /// of its edits are applied. This is meant for assists that build their edits from strings.
+    pub fn add_placeholder_snippet_group_at(&mut self, _cap: SnippetCap, ranges: Vec<TextRange>) {
+        self.add_snippet(PlaceSnippet::OverRanges(ranges))
+    }
+
     fn add_snippet(&mut self, snippet: PlaceSnippet) {
         let snippet_builder = self.snippet_builder.get_or_insert(SnippetBuilder { places: vec![] });
         snippet_builder.places.push(snippet);
@@ -376,6 +463,7 @@ pub enum FileSystemEdit {
     CreateFile { dst: AnchoredPathBuf, initial_contents: String },
     MoveFile { src: FileId, dst: AnchoredPathBuf },
     MoveDir { src: AnchoredPathBuf, src_id: FileId, dst: AnchoredPathBuf },
+    DeleteFile { src: FileId },
 }
 
 impl From<FileSystemEdit> for SourceChange {
@@ -383,7 +471,7 @@ fn from(edit: FileSystemEdit) -> SourceChange {
         SourceChange {
             source_file_edits: Default::default(),
             file_system_edits: vec![edit],
-            is_snippet: false,
+            ..Default::default()
         }
     }
 }
@@ -412,6 +500,8 @@ enum PlaceSnippet {
     /// Place a group of placeholder snippets which are linked together
     /// in place of the elements
     OverGroup(Vec<SyntaxElement>),
+    /// Place a group of linked placeholder snippets over ranges of the edited text
+    OverRanges(Vec<TextRange>),
 }
 
 impl PlaceSnippet {
@@ -423,6 +513,43 @@ fn finalize_position(self) -> Vec<Snippet> {
             PlaceSnippet::OverGroup(it) => {
                 vec![Snippet::PlaceholderGroup(it.into_iter().map(|it| it.text_range()).collect())]
             }
+            PlaceSnippet::OverRanges(it) => vec![Snippet::PlaceholderGroup(it)],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deletions(ranges: &[(u32, u32)]) -> TextEdit {
+        let mut builder = TextEdit::builder();
+        for &(start, end) in ranges {
+            builder.delete(TextRange::new(start.into(), end.into()));
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn summary_of_change_to_several_files() {
+        let (first, second) = (FileId::from_raw(0), FileId::from_raw(1));
+        let mut change = SourceChange::from_text_edit(second, deletions(&[(0, 1), (4, 6)]));
+        change.insert_source_edit(first, deletions(&[(2, 3)]));
+        change.push_file_system_edit(FileSystemEdit::CreateFile {
+            dst: AnchoredPathBuf { anchor: first, path: "bar.rs".to_owned() },
+            initial_contents: String::new(),
+        });
+
+        let summary = change.summary();
+        assert_eq!(summary.edited_files, vec![(first, 1), (second, 2)]);
+        assert_eq!(summary.file_system_edits, 1);
+        assert_eq!(summary.files_touched(), 3);
+    }
+
+    #[test]
+    fn summary_of_empty_change() {
+        let summary = SourceChange::default().summary();
+        assert!(summary.edited_files.is_empty());
+        assert_eq!(summary.files_touched(), 0);
+    }
+}
@@ -0,0 +1,337 @@
+//! Utilities for inserting a new item into a module at a conventional position.
+use hir::{CfgAtom, CfgExpr};
+use syntax::{
+    ast::{self, edit::IndentLevel, edit_in_place::Indent, make, HasAttrs, HasModuleItem},
+    ted::{self, Position},
+    AstNode, SyntaxKind, T,
+};
+
+/// Where a new item should go among the items of a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemPlacement {
+    /// Imports go after the other imports, constants and statics are grouped together after
+    /// the imports, and everything else goes to the end of the module, but before a trailing
+    /// `#[cfg(test)]` module. With [`add_item_to_module`], module declarations like `mod m;` go
+    /// after the first group of them, or first in the module.
+    Conventional,
+    /// Right after the imports of the module, or at its start if there are none.
+    AfterImports,
+    /// At the end of the module, but before a trailing `#[cfg(test)]` module.
+    End,
+}
+
+/// The position of a new item relative to the existing items of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemInsertPosition {
+    After(ast::Item),
+    Before(ast::Item),
+    /// The module has no items yet.
+    Empty,
+}
+
+/// Computes where an item of the given `kind` should be inserted among `items`.
+pub fn item_insert_position(
+    items: impl Iterator<Item = ast::Item>,
+    kind: SyntaxKind,
+    placement: ItemPlacement,
+) -> ItemInsertPosition {
+    let items: Vec<_> = items.collect();
+    let (Some(first), Some(last)) = (items.first(), items.last()) else {
+        return ItemInsertPosition::Empty;
+    };
+
+    let after_last_matching = |pred: &dyn Fn(&ast::Item) -> bool| {
+        items.iter().rev().find(|it| pred(it)).cloned().map(ItemInsertPosition::After)
+    };
+    let after_imports = || {
+        after_last_matching(&is_import).unwrap_or_else(|| ItemInsertPosition::Before(first.clone()))
+    };
+    let at_end = || {
+        if is_tests_module(last) {
+            match items.iter().rev().nth(1) {
+                Some(prev) => ItemInsertPosition::After(prev.clone()),
+                None => ItemInsertPosition::Before(last.clone()),
+            }
+        } else {
+            ItemInsertPosition::After(last.clone())
+        }
+    };
+
+    match placement {
+        ItemPlacement::AfterImports => after_imports(),
+        ItemPlacement::End => at_end(),
+        ItemPlacement::Conventional => match kind {
+            SyntaxKind::USE | SyntaxKind::EXTERN_CRATE => after_imports(),
+            SyntaxKind::CONST | SyntaxKind::STATIC => after_last_matching(&|it: &ast::Item| {
+                matches!(it, ast::Item::Const(_) | ast::Item::Static(_))
+            })
+            .unwrap_or_else(after_imports),
+            _ => at_end(),
+        },
+    }
+}
+
+/// Inserts `item` into the mutable `module`, separating it from its neighbours with a blank line,
+/// or a single newline from the imports or module declarations it's grouped with.
+///
+/// `module` is expected to be either an [`ast::SourceFile`] or an [`ast::ItemList`].
+pub fn add_item_to_module(module: &impl HasModuleItem, item: ast::Item, placement: ItemPlacement) {
+    let position = if placement == ItemPlacement::Conventional && is_mod_declaration(&item) {
+        mod_declaration_position(module.items())
+    } else {
+        item_insert_position(module.items(), item.syntax().kind(), placement)
+    };
+    // Gives `item` the indentation of `anchor`, and returns the whitespace to put between them.
+    let separator = |anchor: &ast::Item| {
+        let grouped = is_import(anchor) && is_import(&item)
+            || is_mod_declaration(anchor) && is_mod_declaration(&item);
+        let indent = IndentLevel::from_node(anchor.syntax());
+        item.reindent_to(indent);
+        let newlines = if grouped { "\n" } else { "\n\n" };
+        make::tokens::whitespace(&format!("{newlines}{indent}")).into()
+    };
+    match position {
+        ItemInsertPosition::After(anchor) => {
+            cov_mark::hit!(add_item_after_anchor);
+            let separator = separator(&anchor);
+            ted::insert_all_raw(
+                Position::after(anchor.syntax()),
+                vec![separator, item.syntax().clone().into()],
+            );
+        }
+        ItemInsertPosition::Before(anchor) => {
+            cov_mark::hit!(add_item_before_anchor);
+            let separator = separator(&anchor);
+            ted::insert_all_raw(
+                Position::before(anchor.syntax()),
+                vec![item.syntax().clone().into(), separator],
+            );
+        }
+        ItemInsertPosition::Empty => {
+            cov_mark::hit!(add_item_to_empty_module);
+            let l_curly = module
+                .syntax()
+                .children_with_tokens()
+                .filter_map(|it| it.into_token())
+                .find(|it| it.kind() == T!['{']);
+            let Some(l_curly) = l_curly else {
+                // A file without items, which may still have comments.
+                ted::append_child_raw(module.syntax(), item.syntax());
+                ted::append_child_raw(module.syntax(), make::tokens::single_newline());
+                return;
+            };
+            let outer_indent = IndentLevel::from_node(module.syntax());
+            let indent = outer_indent + 1;
+            item.reindent_to(indent);
+            // Drop whatever whitespace was between the braces, e.g. in `mod foo { }`.
+            if let Some(ws) = l_curly
+                .next_token()
+                .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+                .filter(|it| it.parent().as_ref() == Some(module.syntax()))
+            {
+                ted::remove(ws);
+            }
+            ted::insert_all_raw(
+                Position::after(l_curly),
+                vec![
+                    make::tokens::whitespace(&format!("\n{indent}")).into(),
+                    item.syntax().clone().into(),
+                    make::tokens::whitespace(&format!("\n{outer_indent}")).into(),
+                ],
+            );
+        }
+    }
+}
+
+/// After the first group of module declarations, or before the first item if there are none.
+fn mod_declaration_position(items: impl Iterator<Item = ast::Item>) -> ItemInsertPosition {
+    let items: Vec<_> = items.collect();
+    let declarations = items.iter().skip_while(|it| !is_mod_declaration(it));
+    match declarations.take_while(|it| is_mod_declaration(it)).last() {
+        Some(last) => ItemInsertPosition::After(last.clone()),
+        None => {
+            items.first().cloned().map_or(ItemInsertPosition::Empty, ItemInsertPosition::Before)
+        }
+    }
+}
+
+fn is_mod_declaration(item: &ast::Item) -> bool {
+    matches!(item, ast::Item::Module(it) if it.item_list().is_none())
+}
+
+fn is_import(item: &ast::Item) -> bool {
+    matches!(item, ast::Item::Use(_) | ast::Item::ExternCrate(_))
+}
+
+fn is_tests_module(item: &ast::Item) -> bool {
+    let ast::Item::Module(module) = item else { return false };
+    module.attrs().any(|attr| {
+        let Some((name, args)) = attr.as_simple_call() else { return false };
+        name == "cfg"
+            && matches!(
+                CfgExpr::parse_from_ast(&args),
+                CfgExpr::Atom(CfgAtom::Flag(flag)) if flag == "test"
+            )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use syntax::{Edition, SourceFile};
+
+    use super::*;
+
+    fn check(placement: ItemPlacement, before: &str, kind: SyntaxKind, expected: &str) {
+        let file = SourceFile::parse(before, Edition::CURRENT).tree();
+        let position = match item_insert_position(file.items(), kind, placement) {
+            ItemInsertPosition::After(item) => format!("after `{item}`"),
+            ItemInsertPosition::Before(item) => format!("before `{item}`"),
+            ItemInsertPosition::Empty => "empty".to_owned(),
+        };
+        assert_eq!(position, expected);
+    }
+
+    fn check_add(placement: ItemPlacement, before: &str, new_item: &str, after: &str) {
+        check_add_with(before, new_item, after, |file, item| {
+            add_item_to_module(file, item, placement)
+        });
+    }
+
+    fn check_add_in_module(placement: ItemPlacement, before: &str, new_item: &str, after: &str) {
+        check_add_with(before, new_item, after, |file, item| {
+            let item_list = file.syntax().descendants().find_map(ast::ItemList::cast).unwrap();
+            add_item_to_module(&item_list, item, placement)
+        });
+    }
+
+    fn check_add_with(
+        before: &str,
+        new_item: &str,
+        after: &str,
+        add: impl FnOnce(&SourceFile, ast::Item),
+    ) {
+        let file = SourceFile::parse(before, Edition::CURRENT).tree().clone_for_update();
+        let item = SourceFile::parse(new_item, Edition::CURRENT)
+            .tree()
+            .items()
+            .next()
+            .unwrap()
+            .clone_subtree()
+            .clone_for_update();
+        add(&file, item);
+        assert_eq!(file.syntax().to_string(), after);
+    }
+
+    #[test]
+    fn const_grouped_with_consts() {
+        check(
+            ItemPlacement::Conventional,
+            "use foo::Bar;\n\nconst A: u8 = 0;\n\nfn f() {}\n",
+            SyntaxKind::CONST,
+            "after `const A: u8 = 0;`",
+        );
+    }
+
+    #[test]
+    fn const_after_imports() {
+        check(
+            ItemPlacement::Conventional,
+            "use foo::Bar;\n\nfn f() {}\n",
+            SyntaxKind::CONST,
+            "after `use foo::Bar;`",
+        );
+    }
+
+    #[test]
+    fn fn_before_tests_module() {
+        check(
+            ItemPlacement::Conventional,
+            "fn f() {}\n\n#[cfg(test)]\nmod tests {}\n",
+            SyntaxKind::FN,
+            "after `fn f() {}`",
+        );
+    }
+
+    #[test]
+    fn use_without_imports() {
+        check(ItemPlacement::Conventional, "fn f() {}\n", SyntaxKind::USE, "before `fn f() {}`");
+    }
+
+    #[test]
+    fn fn_into_empty_module() {
+        check(ItemPlacement::End, "", SyntaxKind::FN, "empty");
+    }
+
+    #[test]
+    fn fn_before_tests_module_with_spaced_cfg() {
+        check(
+            ItemPlacement::End,
+            "fn f() {}\n\n#[cfg( test )]\nmod tests {}\n",
+            SyntaxKind::FN,
+            "after `fn f() {}`",
+        );
+        check(
+            ItemPlacement::End,
+            "fn f() {}\n\n#[cfg(not(test))]\nmod imp {}\n",
+            SyntaxKind::FN,
+            "after `#[cfg(not(test))]\nmod imp {}`",
+        );
+    }
+
+    #[test]
+    fn add_const_grouped_with_consts() {
+        check_add(
+            ItemPlacement::Conventional,
+            "use foo::Bar;\n\nconst A: u8 = 0;\n\nfn f() {}\n",
+            "const B: u8 = 1;",
+            "use foo::Bar;\n\nconst A: u8 = 0;\n\nconst B: u8 = 1;\n\nfn f() {}\n",
+        );
+    }
+
+    #[test]
+    fn add_fn_before_tests_module() {
+        check_add(
+            ItemPlacement::Conventional,
+            "fn f() {}\n\n#[cfg(test)]\nmod tests {}\n",
+            "fn g() {}",
+            "fn f() {}\n\nfn g() {}\n\n#[cfg(test)]\nmod tests {}\n",
+        );
+    }
+
+    #[test]
+    fn add_use_into_inline_module() {
+        check_add_in_module(
+            ItemPlacement::Conventional,
+            "mod m {\n    use foo::Bar;\n\n    fn f() {}\n}\n",
+            "use foo::Baz;",
+            "mod m {\n    use foo::Bar;\n    use foo::Baz;\n\n    fn f() {}\n}\n",
+        );
+    }
+
+    #[test]
+    fn add_mod_declaration_after_mod_declarations() {
+        check_add(
+            ItemPlacement::Conventional,
+            "use foo::Bar;\n\nmod a;\nmod b;\n\nfn f() {}\n\nmod c;\n",
+            "mod d;",
+            "use foo::Bar;\n\nmod a;\nmod b;\nmod d;\n\nfn f() {}\n\nmod c;\n",
+        );
+        check_add(
+            ItemPlacement::Conventional,
+            "use foo::Bar;\n",
+            "mod d;",
+            "mod d;\n\nuse foo::Bar;\n",
+        );
+    }
+
+    #[test]
+    fn add_fn_into_empty_module() {
+        check_add_in_module(
+            ItemPlacement::End,
+            "mod m { }\n",
+            "fn f() {}",
+            "mod m {\n    fn f() {}\n}\n",
+        );
+        check_add(ItemPlacement::End, "// empty\n", "fn f() {}", "// empty\nfn f() {}\n");
+    }
+}
@@ -0,0 +1,303 @@
+use hir::{InFile, Semantics};
+use ide_db::{
+    assists::{Assist, AssistResolveStrategy},
+    base_db::{FileId, FileRange},
+    defs::Definition,
+    source_change::SourceChange,
+    FxHashSet, RootDatabase,
+};
+use syntax::{
+    ast::{self, HasName},
+    AstNode, SyntaxKind, SyntaxNode, SyntaxNodePtr, TextRange,
+};
+
+use crate::{unresolved_fix, Diagnostic, DiagnosticCode, DiagnosticsConfig, Severity};
+
+// Diagnostic: enum-variant-name-stutter
+//
+// This diagnostic is triggered when the name of an enum variant starts or ends with the name of
+// its enum, like `Error::ParseError` does. The enum name is already part of every path to the
+// variant, so `Error::Parse` reads better. Enums are only reported once at least
+// `rust-analyzer.diagnostics.enumVariantNameStutter.threshold` of their variants stutter.
+//
+// The fixes rename the variant, or all stuttering variants of the enum at once, together with
+// all their usages.
+pub(crate) fn enum_variant_name_stutter(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+    resolve: &AssistResolveStrategy,
+) -> Option<()> {
+    if !config.style_lints {
+        return None;
+    }
+    let enum_ = ast::Enum::cast(node.clone())?;
+    let enum_name = enum_.name()?.text().to_string();
+    let variants: Vec<(ast::Variant, ast::Name)> = enum_
+        .variant_list()?
+        .variants()
+        .filter_map(|variant| Some((variant.clone(), variant.name()?)))
+        .collect();
+
+    let stuttering: Vec<_> = variants
+        .iter()
+        .filter_map(|(variant, name)| {
+            let new_name = strip_enum_name(&enum_name, name.text().as_str())?.to_owned();
+            Some((variant, name, new_name))
+        })
+        .collect();
+    if stuttering.is_empty() || stuttering.len() < config.enum_variant_name_stutter_threshold {
+        return None;
+    }
+
+    // Renaming must neither clash with an existing variant nor merge two stuttering ones.
+    let existing_names: FxHashSet<String> =
+        variants.iter().map(|(_, name)| name.text().to_string()).collect();
+    let is_renameable = |new_name: &str| {
+        !existing_names.contains(new_name)
+            && stuttering.iter().filter(|(_, _, it)| it == new_name).count() == 1
+    };
+    let renames: Vec<(Definition, &str)> = stuttering
+        .iter()
+        .filter(|(_, _, new_name)| is_renameable(new_name))
+        .filter_map(|(variant, _, new_name)| {
+            Some((Definition::Variant(sema.to_def(*variant)?), new_name.as_str()))
+        })
+        .collect();
+
+    for (_, name, new_name) in &stuttering {
+        let range = name.syntax().text_range();
+        let mut fixes = Vec::new();
+        if let Some(&(def, _)) = renames.iter().find(|(_, it)| it == new_name) {
+            let mut fix = unresolved_fix(
+                "rename_stuttering_variant",
+                &format!("Rename to `{new_name}`"),
+                range,
+            );
+            // A rename that fails can't be applied, so the fix is dropped once resolved.
+            if resolve.should_resolve(&fix.id) {
+                fix.source_change = def.rename(sema, new_name).ok();
+            }
+            if !resolve.should_resolve(&fix.id) || fix.source_change.is_some() {
+                fixes.push(fix);
+            }
+
+            if renames.len() > 1 {
+                fixes.extend(rename_all_fix(sema, &enum_name, &renames, range, resolve));
+            }
+        }
+
+        acc.push(
+            Diagnostic::new(
+                DiagnosticCode::Ra("enum-variant-name-stutter", Severity::WeakWarning),
+                format!("variant name `{name}` repeats the name of enum `{enum_name}`"),
+                FileRange { file_id, range },
+            )
+            .with_fixes((!fixes.is_empty()).then_some(fixes))
+            .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(name.syntax()))),
+        );
+    }
+
+    Some(())
+}
+
+fn rename_all_fix(
+    sema: &Semantics<'_, RootDatabase>,
+    enum_name: &str,
+    renames: &[(Definition, &str)],
+    range: TextRange,
+    resolve: &AssistResolveStrategy,
+) -> Option<Assist> {
+    let mut fix = unresolved_fix(
+        "rename_all_stuttering_variants",
+        &format!("Rename all variants repeating `{enum_name}`"),
+        range,
+    );
+    if resolve.should_resolve(&fix.id) {
+        let source_change = renames
+            .iter()
+            .map(|(def, new_name)| def.rename(sema, new_name))
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?
+            .into_iter()
+            .fold(SourceChange::default(), SourceChange::merge);
+        fix.source_change = Some(source_change);
+    }
+    Some(fix)
+}
+
+/// Returns what is left of `variant_name` once `enum_name` is removed from its start or end, as
+/// long as that is still a camel case identifier on its own.
+fn strip_enum_name<'a>(enum_name: &str, variant_name: &'a str) -> Option<&'a str> {
+    let rest = variant_name
+        .strip_prefix(enum_name)
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+        .or_else(|| {
+            variant_name
+                .strip_suffix(enum_name)
+                .filter(|_| enum_name.starts_with(|c: char| c.is_ascii_uppercase()))
+        })?;
+    if !rest.starts_with(|c: char| c.is_ascii_uppercase())
+        || SyntaxKind::from_keyword(rest).is_some()
+    {
+        return None;
+    }
+    Some(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config, check_fix, check_fixes},
+        DiagnosticsConfig,
+    };
+
+    #[test]
+    fn prefix_and_suffix() {
+        check_diagnostics(
+            r#"
+enum Error {
+    ParseError,
+  //^^^^^^^^^^ 💡 weak: variant name `ParseError` repeats the name of enum `Error`
+    ErrorIo(i32),
+  //^^^^^^^ 💡 weak: variant name `ErrorIo` repeats the name of enum `Error`
+    Timeout,
+    Errors,
+    Terror,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn clashing_or_keyword_names() {
+        check_diagnostics(
+            r#"
+enum Token {
+    Ident,
+    IdentToken,
+  //^^^^^^^^^^ weak: variant name `IdentToken` repeats the name of enum `Token`
+    TokenSelf,
+    CommaToken,
+  //^^^^^^^^^^ weak: variant name `CommaToken` repeats the name of enum `Token`
+    TokenComma,
+  //^^^^^^^^^^ weak: variant name `TokenComma` repeats the name of enum `Token`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn configurable_threshold() {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.enum_variant_name_stutter_threshold = 2;
+        check_diagnostics_with_config(
+            config,
+            r#"
+enum Error {
+    ParseError,
+    Timeout,
+}
+enum Kind {
+    StructKind,
+  //^^^^^^^^^^ 💡 weak: variant name `StructKind` repeats the name of enum `Kind`
+    EnumKind,
+  //^^^^^^^^ 💡 weak: variant name `EnumKind` repeats the name of enum `Kind`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn renames_usages() {
+        check_fix(
+            r#"
+mod error {
+    pub enum Error {
+        ParseError$0 { line: u32 },
+        Timeout,
+    }
+}
+use error::Error;
+
+fn f(e: Error) -> u32 {
+    match e {
+        Error::ParseError { line } => line,
+        Error::Timeout => 0,
+    }
+}
+fn g() -> Error {
+    error::Error::ParseError { line: 1 }
+}
+"#,
+            r#"
+mod error {
+    pub enum Error {
+        Parse { line: u32 },
+        Timeout,
+    }
+}
+use error::Error;
+
+fn f(e: Error) -> u32 {
+    match e {
+        Error::Parse { line } => line,
+        Error::Timeout => 0,
+    }
+}
+fn g() -> Error {
+    error::Error::Parse { line: 1 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn renames_all_variants() {
+        check_fixes(
+            r#"
+enum Error {
+    IoError(i32),
+    ParseError$0,
+}
+
+fn f(e: Error) {
+    match e {
+        Error::IoError(_) => {}
+        Error::ParseError => {}
+    }
+}
+"#,
+            vec![
+                r#"
+enum Error {
+    IoError(i32),
+    Parse,
+}
+
+fn f(e: Error) {
+    match e {
+        Error::IoError(_) => {}
+        Error::Parse => {}
+    }
+}
+"#,
+                r#"
+enum Error {
+    Io(i32),
+    Parse,
+}
+
+fn f(e: Error) {
+    match e {
+        Error::Io(_) => {}
+        Error::Parse => {}
+    }
+}
+"#,
+            ],
+        );
+    }
+}
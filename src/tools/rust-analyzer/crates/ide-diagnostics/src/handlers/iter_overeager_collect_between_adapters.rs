@@ -0,0 +1,313 @@
+use hir::{AsAssocItem, InFile, Semantics};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    defs::Definition,
+    famous_defs::FamousDefs,
+    source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, HasArgList, HasLoopBody, HasName},
+    match_ast, AstNode, SyntaxNode, SyntaxNodePtr, TextRange,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Assist, Diagnostic, DiagnosticCode, DiagnosticsConfig, Severity};
+
+// Diagnostic: iter-overeager-collect-between-adapters
+//
+// This diagnostic is triggered when an iterator is collected into a `Vec` only to be iterated over
+// again, either right away as in `.collect::<Vec<_>>().into_iter()`, or through a local that is
+// used exactly once for that. The intermediate allocation can usually be removed.
+pub(crate) fn iter_overeager_collect_between_adapters(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+) -> Option<()> {
+    if !config.style_lints {
+        return None;
+    }
+    if let Some(iter_call) = ast::MethodCallExpr::cast(node.clone()) {
+        let ast::Expr::MethodCallExpr(collect_call) = iter_call.receiver()? else { return None };
+        let into_iter = iterates_by_value(&iter_call)?;
+        if !is_collect_into_vec(sema, &collect_call) {
+            return None;
+        }
+
+        // `iter.collect::<Vec<_>>().into_iter()` -> `iter`
+        let fixes = into_iter.then(|| {
+            let dot = collect_call.dot_token()?;
+            let range = TextRange::new(dot.text_range().start(), node.text_range().end());
+            Some(vec![remove_collect_fix(file_id, TextEdit::delete(range), range)])
+        });
+        acc.push(
+            Diagnostic::new(
+                DiagnosticCode::Ra(
+                    "iter-overeager-collect-between-adapters",
+                    Severity::WeakWarning,
+                ),
+                "collecting into a `Vec` only to iterate over it again",
+                FileRange { file_id, range: collect_range(&collect_call)? },
+            )
+            .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(node)))
+            .with_fixes(fixes.flatten()),
+        );
+    } else if let Some(let_stmt) = ast::LetStmt::cast(node.clone()) {
+        let ast::Pat::IdentPat(ident_pat) = let_stmt.pat()? else { return None };
+        if ident_pat.ref_token().is_some() || ident_pat.pat().is_some() {
+            return None;
+        }
+        let ast::Expr::MethodCallExpr(collect_call) = let_stmt.initializer()? else { return None };
+        if !is_collect_into_vec(sema, &collect_call) {
+            return None;
+        }
+
+        // The local must be iterated over exactly once, and not repeatedly in a loop or a closure.
+        let local = sema.to_def(&ident_pat)?;
+        let usages = Definition::Local(local).usages(sema).all();
+        let (usage_file_id, references) = usages.iter().next()?;
+        let [reference] = references else { return None };
+        if usages.len() != 1 || *usage_file_id != file_id {
+            return None;
+        }
+        let name_ref = reference.name.as_name_ref()?;
+        let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+        let iter_call = ast::MethodCallExpr::cast(path_expr.syntax().parent()?)?;
+        if iter_call.receiver()?.syntax() != path_expr.syntax() {
+            return None;
+        }
+        let into_iter = iterates_by_value(&iter_call)?;
+        let stmt_list = let_stmt.syntax().parent()?;
+        let usage_range = path_expr.syntax().text_range();
+        let repeated = path_expr.syntax().ancestors().take_while(|it| *it != stmt_list).any(|it| {
+            match_ast! {
+                match it {
+                    // The iterable of a `for` loop is only evaluated once.
+                    ast::ForExpr(it) => it
+                        .loop_body()
+                        .is_some_and(|body| body.syntax().text_range().contains_range(usage_range)),
+                    ast::WhileExpr(_) => true,
+                    ast::LoopExpr(_) => true,
+                    ast::ClosureExpr(_) => true,
+                    ast::Fn(_) => true,
+                    _ => false,
+                }
+            }
+        });
+        if repeated {
+            return None;
+        }
+
+        // `let v: Vec<_> = iter.collect(); v.into_iter()` -> `iter`
+        let fixes = into_iter.then(|| {
+            let iter = collect_call.receiver()?;
+            let mut edit = TextEdit::builder();
+            edit.delete(let_stmt_range(&let_stmt));
+            edit.replace(iter_call.syntax().text_range(), iter.syntax().text().to_string());
+            let range = let_stmt.syntax().text_range();
+            Some(vec![remove_collect_fix(file_id, edit.finish(), range)])
+        });
+        acc.push(
+            Diagnostic::new(
+                DiagnosticCode::Ra(
+                    "iter-overeager-collect-between-adapters",
+                    Severity::WeakWarning,
+                ),
+                format!(
+                    "collecting into a `Vec` only to iterate over `{}` once",
+                    ident_pat.name()?
+                ),
+                FileRange { file_id, range: collect_range(&collect_call)? },
+            )
+            .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(node)))
+            .with_fixes(fixes.flatten()),
+        );
+    }
+
+    Some(())
+}
+
+/// Whether `call` is an `Iterator::collect` producing a `Vec`.
+fn is_collect_into_vec(sema: &Semantics<'_, RootDatabase>, call: &ast::MethodCallExpr) -> bool {
+    (|| {
+        let famous_defs = FamousDefs(sema, sema.scope(call.syntax())?.krate());
+        let func = sema.resolve_method_call(call)?;
+        let is_collect = func.name(sema.db).to_smol_str() == "collect"
+            && func.as_assoc_item(sema.db)?.container_trait(sema.db)?
+                == famous_defs.core_iter_Iterator()?;
+        let ty = sema.type_of_expr(&call.clone().into())?.original;
+        Some(is_collect && ty.as_adt() == Some(famous_defs.alloc_vec_Vec()?.into()))
+    })()
+    .unwrap_or(false)
+}
+
+/// `Some(true)` for `.into_iter()`, `Some(false)` for `.iter()`.
+fn iterates_by_value(call: &ast::MethodCallExpr) -> Option<bool> {
+    if call.arg_list()?.args().next().is_some() {
+        return None;
+    }
+    match call.name_ref()?.text().as_str() {
+        "into_iter" => Some(true),
+        "iter" => Some(false),
+        _ => None,
+    }
+}
+
+/// The `collect::<Vec<_>>()` part of the call.
+fn collect_range(call: &ast::MethodCallExpr) -> Option<TextRange> {
+    Some(TextRange::new(
+        call.name_ref()?.syntax().text_range().start(),
+        call.syntax().text_range().end(),
+    ))
+}
+
+/// The `let` statement along with the whitespace in front of it.
+fn let_stmt_range(let_stmt: &ast::LetStmt) -> TextRange {
+    let range = let_stmt.syntax().text_range();
+    match let_stmt.syntax().prev_sibling_or_token() {
+        Some(ws) if ws.kind() == syntax::SyntaxKind::WHITESPACE => {
+            TextRange::new(ws.text_range().start(), range.end())
+        }
+        _ => range,
+    }
+}
+
+fn remove_collect_fix(file_id: FileId, edit: TextEdit, range: TextRange) -> Assist {
+    fix(
+        "remove_intermediate_collect",
+        "Remove the intermediate `collect`",
+        SourceChange::from_text_edit(file_id, edit),
+        range,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    const VEC: &str = r#"
+//- /alloc.rs crate:alloc
+pub mod vec {
+    pub struct Vec<T>(T);
+    impl<T> Vec<T> {
+        pub fn iter(&self) -> core::iter::Repeat<&T> { loop {} }
+        pub fn len(&self) -> usize { 0 }
+    }
+    impl<T> core::iter::FromIterator<T> for Vec<T> {
+        fn from_iter<I: core::iter::IntoIterator<Item = T>>(_iter: I) -> Self { loop {} }
+    }
+    impl<T> core::iter::IntoIterator for Vec<T> {
+        type Item = T;
+        type IntoIter = core::iter::Repeat<T>;
+        fn into_iter(self) -> Self::IntoIter { loop {} }
+    }
+}
+"#;
+
+    fn check(ra_fixture: &str) {
+        check_diagnostics(&format!("//- minicore: iterators, from_iterator\n{VEC}{ra_fixture}"));
+    }
+
+    fn check_remove_collect(before: &str, after: &str) {
+        check_fix(&format!("//- minicore: iterators, from_iterator\n{VEC}{before}"), after);
+    }
+
+    #[test]
+    fn collect_then_iterate() {
+        check(
+            r#"
+//- /main.rs crate:main deps:alloc
+use alloc::vec::Vec;
+fn f(it: core::iter::Repeat<i32>) {
+    let _ = it.collect::<Vec<_>>().into_iter().take(1);
+             //^^^^^^^^^^^^^^^^^^^ 💡 weak: collecting into a `Vec` only to iterate over it again
+}
+fn g(it: core::iter::Repeat<i32>) {
+    let _ = it.collect::<Vec<i32>>().iter().take(1);
+             //^^^^^^^^^^^^^^^^^^^^^ weak: collecting into a `Vec` only to iterate over it again
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn collected_local_iterated_once() {
+        check(
+            r#"
+//- /main.rs crate:main deps:alloc
+use alloc::vec::Vec;
+fn f(it: core::iter::Repeat<i32>) {
+    let v: Vec<_> = it.collect();
+                     //^^^^^^^^^ 💡 weak: collecting into a `Vec` only to iterate over `v` once
+    let _ = v.into_iter().take(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn collected_local_used_otherwise() {
+        check(
+            r#"
+//- /main.rs crate:main deps:alloc
+use alloc::vec::Vec;
+fn f(it: core::iter::Repeat<i32>) {
+    let v: Vec<_> = it.collect();
+    let _ = v.len();
+    let _ = v.into_iter().take(1);
+}
+fn g(it: core::iter::Repeat<i32>) {
+    let v: Vec<_> = it.collect();
+    loop {
+        let _ = v.iter().take(1);
+    }
+}
+fn h(it: core::iter::Repeat<i32>) {
+    let v: Vec<_> = it.collect();
+    let _ = v;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_collect_in_chain() {
+        check_remove_collect(
+            r#"
+//- /main.rs crate:main deps:alloc
+use alloc::vec::Vec;
+fn f(it: core::iter::Repeat<i32>) {
+    let _ = it.collect::<Vec<_>>$0().into_iter().take(1);
+}
+"#,
+            r#"
+use alloc::vec::Vec;
+fn f(it: core::iter::Repeat<i32>) {
+    let _ = it.take(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_collect_into_local() {
+        check_remove_collect(
+            r#"
+//- /main.rs crate:main deps:alloc
+use alloc::vec::Vec;
+fn f(it: core::iter::Repeat<i32>) {
+    let v: Vec<_> = it.col$0lect();
+    let _ = v.into_iter().take(1);
+}
+"#,
+            r#"
+use alloc::vec::Vec;
+fn f(it: core::iter::Repeat<i32>) {
+    let _ = it.take(1);
+}
+"#,
+        );
+    }
+}
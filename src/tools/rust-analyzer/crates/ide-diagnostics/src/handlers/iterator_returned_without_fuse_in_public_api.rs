@@ -0,0 +1,329 @@
+use hir::{HasVisibility, InFile, Semantics};
+use ide_db::{
+    assists::Assist,
+    base_db::{FileId, FileRange},
+    famous_defs::FamousDefs,
+    source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, HasDocComments},
+    match_ast, AstNode, AstToken, SyntaxNode, SyntaxNodePtr, TextRange,
+};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticCode, DiagnosticsConfig, Severity};
+
+// Diagnostic: iterator-returned-without-fuse-in-public-api
+//
+// This diagnostic is triggered by a public function returning `impl Iterator` whose concrete type
+// is an iterator of the crate that may yield items again after it returned `None`: its `next`
+// changes the state of the iterator on a path that ends in `None`. Callers can't rely on the
+// iterator being fused, so it should be returned as `.fuse()`, implement `FusedIterator`, or
+// its behaviour be mentioned in the documentation of the function.
+pub(crate) fn iterator_returned_without_fuse_in_public_api(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+) -> Option<()> {
+    if !config.style_lints {
+        return None;
+    }
+    let func = ast::Fn::cast(node.clone())?;
+    let ret_ty = func.ret_type()?.ty()?;
+    if !matches!(ret_ty, ast::Type::ImplTraitType(_)) {
+        return None;
+    }
+    let def = sema.to_def(&func)?;
+    if def.visibility(sema.db) != hir::Visibility::Public {
+        return None;
+    }
+    // The function already tells its callers what to expect.
+    if func.doc_comments().any(|comment| comment.text().to_lowercase().contains("fuse")) {
+        return None;
+    }
+
+    let tail = func.body()?.tail_expr()?;
+    let ty = sema.type_of_expr(&tail)?.original;
+    let adt = ty.as_adt()?;
+    let krate = def.module(sema.db).krate();
+    if adt.module(sema.db).krate() != krate {
+        return None;
+    }
+    let famous_defs = FamousDefs(sema, krate);
+    let iterator = famous_defs.core_iter_Iterator()?;
+    if famous_defs.core_iter_FusedIterator().is_some_and(|it| ty.impls_trait(sema.db, it, &[])) {
+        return None;
+    }
+    let next = hir::Impl::all_for_type(sema.db, ty)
+        .into_iter()
+        .filter(|impl_| impl_.trait_(sema.db) == Some(iterator))
+        .flat_map(|impl_| impl_.items(sema.db))
+        .find_map(|item| match item {
+            hir::AssocItem::Function(it) if it.name(sema.db).as_str() == Some("next") => Some(it),
+            _ => None,
+        })?;
+    let next = sema.source(next)?.value;
+    if !may_resume_after_none(&next) {
+        return None;
+    }
+
+    let range = ret_ty.syntax().text_range();
+    acc.push(
+        Diagnostic::new(
+            DiagnosticCode::Ra(
+                "iterator-returned-without-fuse-in-public-api",
+                Severity::WeakWarning,
+            ),
+            format!(
+                "`{}` may yield items again after returning `None`",
+                adt.name(sema.db).display(sema.db)
+            ),
+            FileRange { file_id, range },
+        )
+        .with_fixes(fuse_fix(file_id, &tail, range).map(|it| vec![it]))
+        .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(ret_ty.syntax()))),
+    );
+
+    Some(())
+}
+
+fn fuse_fix(file_id: FileId, tail: &ast::Expr, target: TextRange) -> Option<Assist> {
+    if !matches!(
+        tail,
+        ast::Expr::CallExpr(_)
+            | ast::Expr::FieldExpr(_)
+            | ast::Expr::MethodCallExpr(_)
+            | ast::Expr::ParenExpr(_)
+            | ast::Expr::PathExpr(_)
+            | ast::Expr::RecordExpr(_)
+    ) {
+        return None;
+    }
+    let edit = TextEdit::insert(tail.syntax().text_range().end(), ".fuse()".to_owned());
+    let source_change = SourceChange::from_text_edit(file_id, edit);
+    Some(fix("fuse_returned_iterator", "Return the iterator `.fuse()`d", source_change, target))
+}
+
+/// Whether `next` can return `None` after it changed the iterator in the same call, which may
+/// leave it in a state to yield items again. Returning `None` without touching `self` is safe,
+/// as is changing `self` only after the last `None`.
+fn may_resume_after_none(next: &ast::Fn) -> bool {
+    let Some(body) = next.body() else { return false };
+    body.syntax()
+        .descendants()
+        .filter_map(ast::PathExpr::cast)
+        .filter(|it| it.path().is_some_and(|path| path.to_string() == "None"))
+        .filter(|it| is_returned(it.syntax(), &body))
+        .any(|none| changes_self_before(none.syntax(), &body))
+}
+
+/// Whether the value of `expr` is returned from the function with the given `body`.
+fn is_returned(expr: &SyntaxNode, body: &ast::BlockExpr) -> bool {
+    if expr == body.syntax() {
+        return true;
+    }
+    let Some(parent) = expr.parent() else { return false };
+    match_ast! {
+        match parent {
+            ast::ReturnExpr(_) => true,
+            ast::ParenExpr(_) => is_returned(&parent, body),
+            ast::StmtList(it) => {
+                it.tail_expr().is_some_and(|tail| tail.syntax() == expr)
+                    && parent.parent().is_some_and(|block| is_returned(&block, body))
+            },
+            ast::IfExpr(it) => {
+                it.condition().map_or(true, |cond| cond.syntax() != expr)
+                    && is_returned(&parent, body)
+            },
+            ast::MatchArm(it) => {
+                it.expr().is_some_and(|arm| arm.syntax() == expr)
+                    && parent
+                        .ancestors()
+                        .find_map(ast::MatchExpr::cast)
+                        .is_some_and(|match_| is_returned(match_.syntax(), body))
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Whether a statement executed before `expr` on its way out of `body` assigns to `self`.
+fn changes_self_before(expr: &SyntaxNode, body: &ast::BlockExpr) -> bool {
+    expr.ancestors()
+        .take_while(|it| it != body.syntax())
+        .filter_map(|it| Some((it.clone(), ast::StmtList::cast(it.parent()?)?)))
+        .flat_map(|(child, stmt_list)| {
+            stmt_list.statements().take_while(move |stmt| stmt.syntax() != &child)
+        })
+        .any(|stmt| stmt.syntax().descendants().filter_map(ast::BinExpr::cast).any(assigns_self))
+}
+
+fn assigns_self(expr: ast::BinExpr) -> bool {
+    if !matches!(expr.op_kind(), Some(ast::BinaryOp::Assignment { .. })) {
+        return false;
+    }
+    let mut place = expr.lhs();
+    loop {
+        place = match place {
+            Some(ast::Expr::FieldExpr(it)) => it.expr(),
+            Some(ast::Expr::IndexExpr(it)) => it.base(),
+            Some(ast::Expr::ParenExpr(it)) => it.expr(),
+            Some(ast::Expr::PrefixExpr(it)) => it.expr(),
+            Some(ast::Expr::PathExpr(it)) => {
+                return it
+                    .path()
+                    .and_then(|path| path.as_single_segment())
+                    .is_some_and(|segment| segment.self_token().is_some());
+            }
+            _ => return false,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn resuming_iterator() {
+        check_diagnostics(
+            r#"
+//- minicore: iterator, fused_iterator
+pub struct Cycle {
+    n: u32,
+}
+impl Iterator for Cycle {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> {
+        self.n += 1;
+        if self.n % 3 == 0 {
+            None
+        } else {
+            Some(self.n)
+        }
+    }
+}
+
+pub struct Rewind {
+    pos: usize,
+}
+impl Iterator for Rewind {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        if self.pos == 3 {
+            self.pos = 0;
+            return None;
+        }
+        self.pos += 1;
+        Some(self.pos)
+    }
+}
+
+pub fn cycle() -> impl Iterator<Item = u32> {
+                //^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 weak: `Cycle` may yield items again after returning `None`
+    Cycle { n: 0 }
+}
+
+pub fn rewind() -> impl Iterator<Item = usize> {
+                 //^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 weak: `Rewind` may yield items again after returning `None`
+    Rewind { pos: 0 }
+}
+
+fn private_cycle() -> impl Iterator<Item = u32> {
+    Cycle { n: 0 }
+}
+
+/// Starts over after every third item, see [`Iterator::fuse`].
+pub fn documented_cycle() -> impl Iterator<Item = u32> {
+    Cycle { n: 0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fused_iterators() {
+        check_diagnostics(
+            r#"
+//- minicore: iterator, fused_iterator
+use core::iter::FusedIterator;
+
+pub struct Countdown {
+    n: u32,
+}
+impl Iterator for Countdown {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> {
+        if self.n == 0 {
+            return None;
+        }
+        self.n -= 1;
+        Some(self.n)
+    }
+}
+
+pub struct Marked {
+    n: u32,
+}
+impl Iterator for Marked {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> {
+        self.n += 1;
+        None
+    }
+}
+impl FusedIterator for Marked {}
+
+pub fn countdown() -> impl Iterator<Item = u32> {
+    Countdown { n: 3 }
+}
+
+pub fn marked() -> impl Iterator<Item = u32> {
+    Marked { n: 0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fuses_returned_iterator() {
+        check_fix(
+            r#"
+//- minicore: iterator, fused_iterator
+pub struct Cycle {
+    n: u32,
+}
+impl Iterator for Cycle {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> {
+        self.n += 1;
+        if self.n % 3 == 0 { None } else { Some(self.n) }
+    }
+}
+
+pub fn cycle() -> impl Iterator$0<Item = u32> {
+    Cycle { n: 0 }
+}
+"#,
+            r#"
+pub struct Cycle {
+    n: u32,
+}
+impl Iterator for Cycle {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> {
+        self.n += 1;
+        if self.n % 3 == 0 { None } else { Some(self.n) }
+    }
+}
+
+pub fn cycle() -> impl Iterator<Item = u32> {
+    Cycle { n: 0 }.fuse()
+}
+"#,
+        );
+    }
+}
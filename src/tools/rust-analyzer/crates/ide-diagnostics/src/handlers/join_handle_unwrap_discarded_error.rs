@@ -0,0 +1,252 @@
+use hir::{Adt, InFile, Semantics};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    famous_defs::FamousDefs,
+    RootDatabase,
+};
+use syntax::{
+    ast::{self, HasArgList},
+    AstNode, SyntaxNode, SyntaxNodePtr, TextRange,
+};
+
+use crate::{is_test_code, Diagnostic, DiagnosticCode, DiagnosticsConfig, Severity};
+
+// Diagnostic: join-handle-unwrap-discarded-error
+//
+// This diagnostic is triggered when the result of joining a thread through its
+// `std::thread::JoinHandle` or of awaiting a task through its `tokio::task::JoinHandle` is
+// unwrapped, as in `handle.join().unwrap()` or `handle.await.unwrap()`. The panic payload or the
+// error of the task is lost that way, it should rather be propagated or logged. Test code is
+// exempt.
+pub(crate) fn join_handle_unwrap_discarded_error(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+) -> Option<()> {
+    if !config.restriction_lints {
+        return None;
+    }
+    let unwrap_call = ast::MethodCallExpr::cast(node.clone())?;
+    let unwrap = unwrap_call.name_ref()?;
+    if !matches!(unwrap.text().as_str(), "unwrap" | "expect") {
+        return None;
+    }
+    let (handle, awaited) = match unwrap_call.receiver()? {
+        ast::Expr::MethodCallExpr(join_call) => {
+            if join_call.name_ref()?.text() != "join"
+                || join_call.arg_list()?.args().next().is_some()
+            {
+                return None;
+            }
+            (join_call.receiver()?, false)
+        }
+        ast::Expr::AwaitExpr(await_expr) => (await_expr.expr()?, true),
+        _ => return None,
+    };
+    let handle_ty = sema.type_of_expr(&handle)?.original.strip_references();
+    let handle = handle_ty.as_adt()?;
+    let is_join_handle = if awaited {
+        is_tokio_join_handle(sema, handle)
+    } else {
+        let famous_defs = FamousDefs(sema, sema.scope(node)?.krate());
+        [famous_defs.std_thread_JoinHandle(), famous_defs.std_thread_ScopedJoinHandle()]
+            .into_iter()
+            .flatten()
+            .any(|it| Adt::from(it) == handle)
+    };
+    if !is_join_handle {
+        return None;
+    }
+    if is_test_code(sema, node) {
+        return None;
+    }
+
+    let range = TextRange::new(unwrap.syntax().text_range().start(), node.text_range().end());
+    acc.push(
+        Diagnostic::new(
+            DiagnosticCode::Ra("join-handle-unwrap-discarded-error", Severity::WeakWarning),
+            format!(
+                "`{}` discards the error of the joined task, consider propagating or logging it",
+                unwrap.text()
+            ),
+            FileRange { file_id, range },
+        )
+        .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(node))),
+    );
+
+    Some(())
+}
+
+fn is_tokio_join_handle(sema: &Semantics<'_, RootDatabase>, adt: Adt) -> bool {
+    let db = sema.db;
+    let krate = adt.module(db).krate();
+    adt.name(db).to_smol_str() == "JoinHandle"
+        && krate.display_name(db).is_some_and(|it| it.canonical_name() == "tokio")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config},
+        DiagnosticsConfig,
+    };
+
+    #[test]
+    fn unwrap_joined_thread() {
+        check_diagnostics(
+            r#"
+//- minicore: sized
+//- /main.rs crate:main deps:std
+use std::thread::{JoinHandle, ScopedJoinHandle};
+fn f(handle: JoinHandle<i32>) -> i32 {
+    handle.join().unwrap()
+                //^^^^^^^^ weak: `unwrap` discards the error of the joined task, consider propagating or logging it
+}
+fn g(handle: ScopedJoinHandle<'_, i32>) -> i32 {
+    handle.join().expect("thread panicked")
+                //^^^^^^^^^^^^^^^^^^^^^^^^^ weak: `expect` discards the error of the joined task, consider propagating or logging it
+}
+//- /std.rs crate:std
+pub mod prelude { pub mod rust_2021 { pub use core::prelude::rust_2021::*; } }
+pub mod thread {
+    pub struct Result<T>(T);
+    impl<T> Result<T> {
+        pub fn unwrap(self) -> T { loop {} }
+        pub fn expect(self, _msg: &str) -> T { loop {} }
+    }
+    pub struct JoinHandle<T>(T);
+    impl<T> JoinHandle<T> {
+        pub fn join(self) -> Result<T> { loop {} }
+    }
+    pub struct ScopedJoinHandle<'scope, T>(&'scope T);
+    impl<T> ScopedJoinHandle<'_, T> {
+        pub fn join(self) -> Result<T> { loop {} }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unwrap_awaited_task() {
+        check_diagnostics(
+            r#"
+//- minicore: future
+//- /main.rs crate:main deps:tokio
+async fn f(handle: tokio::task::JoinHandle<i32>) -> i32 {
+    handle.await.unwrap()
+               //^^^^^^^^ weak: `unwrap` discards the error of the joined task, consider propagating or logging it
+}
+//- /tokio.rs crate:tokio
+pub mod task {
+    pub struct JoinResult<T>(T);
+    impl<T> JoinResult<T> {
+        pub fn unwrap(self) -> T { loop {} }
+    }
+    pub struct JoinHandle<T>(T);
+    impl<T> core::future::Future for JoinHandle<T> {
+        type Output = JoinResult<T>;
+        fn poll(
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
+        ) -> core::task::Poll<Self::Output> {
+            loop {}
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_other_join_handles() {
+        check_diagnostics(
+            r#"
+struct Joined;
+impl Joined {
+    fn unwrap(self) -> i32 { 0 }
+}
+struct JoinHandle;
+impl JoinHandle {
+    fn join(self) -> Joined { Joined }
+}
+fn f(handle: JoinHandle) -> i32 {
+    handle.join().unwrap()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_test_code() {
+        check_diagnostics(
+            r#"
+//- minicore: sized, test
+//- /main.rs crate:main deps:std cfg:test
+use std::thread::JoinHandle;
+#[test]
+fn f(handle: JoinHandle<i32>) -> i32 {
+    handle.join().unwrap()
+}
+#[cfg(test)]
+mod tests {
+    fn g(handle: super::JoinHandle<i32>) -> i32 {
+        handle.join().unwrap()
+    }
+}
+//- /std.rs crate:std
+pub mod prelude { pub mod rust_2021 { pub use core::prelude::rust_2021::*; } }
+pub mod thread {
+    pub struct Result<T>(T);
+    impl<T> Result<T> {
+        pub fn unwrap(self) -> T { loop {} }
+        pub fn expect(self, _msg: &str) -> T { loop {} }
+    }
+    pub struct JoinHandle<T>(T);
+    impl<T> JoinHandle<T> {
+        pub fn join(self) -> Result<T> { loop {} }
+    }
+    pub struct ScopedJoinHandle<'scope, T>(&'scope T);
+    impl<T> ScopedJoinHandle<'_, T> {
+        pub fn join(self) -> Result<T> { loop {} }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn needs_restriction_lints() {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.restriction_lints = false;
+        check_diagnostics_with_config(
+            config,
+            r#"
+//- minicore: sized
+//- /main.rs crate:main deps:std
+fn f(handle: std::thread::JoinHandle<i32>) -> i32 {
+    handle.join().unwrap()
+}
+//- /std.rs crate:std
+pub mod prelude { pub mod rust_2021 { pub use core::prelude::rust_2021::*; } }
+pub mod thread {
+    pub struct Result<T>(T);
+    impl<T> Result<T> {
+        pub fn unwrap(self) -> T { loop {} }
+        pub fn expect(self, _msg: &str) -> T { loop {} }
+    }
+    pub struct JoinHandle<T>(T);
+    impl<T> JoinHandle<T> {
+        pub fn join(self) -> Result<T> { loop {} }
+    }
+    pub struct ScopedJoinHandle<'scope, T>(&'scope T);
+    impl<T> ScopedJoinHandle<'_, T> {
+        pub fn join(self) -> Result<T> { loop {} }
+    }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use hir::{HasAttrs, HirDisplay, InFile, Semantics};
+use ide_db::{
+    assists::Assist,
+    base_db::{FileId, FileRange},
+    famous_defs::FamousDefs,
+    source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::{ast, AstNode, SyntaxNode, SyntaxNodePtr, TextRange};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticCode, DiagnosticsConfig, Severity};
+
+// Diagnostic: let-underscore-must-use
+//
+// This diagnostic is triggered by `let _ = expr;` when `expr` is `#[must_use]`, either because
+// its type is or because it is the result of a `#[must_use]` function. Assigning to `_` silences
+// the compiler's `unused_must_use` lint, which hides errors that were never looked at. The
+// diagnostic is only enabled together with `rust-analyzer.diagnostics.restrictionLints.enable`.
+//
+// The fixes propagate a `Result` or `Option` with `?`, or handle it with `expect`.
+pub(crate) fn let_underscore_must_use(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+) -> Option<()> {
+    if !config.restriction_lints {
+        return None;
+    }
+    let let_stmt = ast::LetStmt::cast(node.clone())?;
+    if !matches!(let_stmt.pat()?, ast::Pat::WildcardPat(_)) || let_stmt.let_else().is_some() {
+        return None;
+    }
+    let expr = let_stmt.initializer()?;
+    let ty = sema.type_of_expr(&expr)?.original;
+    let message = match must_use_callee(sema, &expr) {
+        Some(func) => format!(
+            "`let _ =` discards the `#[must_use]` return value of `{}`",
+            func.name(sema.db).display(sema.db)
+        ),
+        None if ty.as_adt().is_some_and(|adt| adt.attrs(sema.db).by_key("must_use").exists()) => {
+            format!("`let _ =` discards a `#[must_use]` value of type `{}`", ty.display(sema.db))
+        }
+        None => return None,
+    };
+
+    let range = TextRange::new(
+        let_stmt.syntax().text_range().start(),
+        let_stmt.eq_token()?.text_range().end(),
+    );
+    acc.push(
+        Diagnostic::new(
+            DiagnosticCode::Ra("let-underscore-must-use", Severity::WeakWarning),
+            message,
+            FileRange { file_id, range },
+        )
+        .with_fixes(fixes(sema, file_id, &let_stmt, &expr, &ty))
+        .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(node))),
+    );
+
+    Some(())
+}
+
+/// Returns the function called by `expr` if it is `#[must_use]`.
+fn must_use_callee(sema: &Semantics<'_, RootDatabase>, expr: &ast::Expr) -> Option<hir::Function> {
+    let func = match expr {
+        ast::Expr::MethodCallExpr(call) => sema.resolve_method_call(call)?,
+        ast::Expr::CallExpr(call) => {
+            match sema.type_of_expr(&call.expr()?)?.original.as_callable(sema.db)?.kind() {
+                hir::CallableKind::Function(it) => it,
+                _ => return None,
+            }
+        }
+        ast::Expr::AwaitExpr(await_expr) => return must_use_callee(sema, &await_expr.expr()?),
+        _ => return None,
+    };
+    func.attrs(sema.db).by_key("must_use").exists().then_some(func)
+}
+
+fn fixes(
+    sema: &Semantics<'_, RootDatabase>,
+    file_id: FileId,
+    let_stmt: &ast::LetStmt,
+    expr: &ast::Expr,
+    ty: &hir::Type,
+) -> Option<Vec<Assist>> {
+    let famous_defs = FamousDefs(sema, sema.scope(let_stmt.syntax())?.krate());
+    let adt = ty.as_adt()?;
+    let expect_msg = if Some(adt) == famous_defs.core_result_Result().map(hir::Adt::from) {
+        "should not fail"
+    } else if Some(adt) == famous_defs.core_option_Option().map(hir::Adt::from) {
+        "should be `Some`"
+    } else {
+        return None;
+    };
+
+    let range = let_stmt.syntax().text_range();
+    let mut fixes = Vec::new();
+    // `?` only works if the surrounding function returns the same kind of value, and for a
+    // `Result` one whose error the value's error converts into.
+    let db = sema.db;
+    let can_propagate = let_stmt
+        .syntax()
+        .ancestors()
+        .take_while(|it| !ast::ClosureExpr::can_cast(it.kind()))
+        .find_map(ast::Fn::cast)
+        .and_then(|func| sema.to_def(&func))
+        .map(|func| func.ret_type(db))
+        .is_some_and(|ret_ty| {
+            ret_ty.as_adt() == Some(adt)
+                && match (ty.type_arguments().nth(1), ret_ty.type_arguments().nth(1)) {
+                    (Some(err), Some(ret_err)) => {
+                        err.could_unify_with(db, &ret_err)
+                            || famous_defs
+                                .core_convert_From()
+                                .is_some_and(|from| ret_err.impls_trait(db, from, &[err]))
+                    }
+                    (err, ret_err) => err.is_none() && ret_err.is_none(),
+                }
+        });
+    if can_propagate {
+        let edit = TextEdit::replace(range, format!("{expr}?;"));
+        fixes.push(fix(
+            "propagate_must_use_value",
+            "Propagate the value with `?`",
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        ));
+    }
+    let edit = TextEdit::replace(range, format!("{expr}.expect(\"{expect_msg}\");"));
+    fixes.push(fix(
+        "expect_must_use_value",
+        "Handle the value with `expect`",
+        SourceChange::from_text_edit(file_id, edit),
+        range,
+    ));
+    Some(fixes)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config, check_fix, check_fixes},
+        DiagnosticsConfig,
+    };
+
+    #[test]
+    fn must_use_values_and_calls() {
+        check_diagnostics(
+            r#"
+//- minicore: result, option
+#[must_use]
+struct Guard;
+
+struct S;
+impl S {
+    #[must_use]
+    fn checked(&self) -> Option<i32> { None }
+    fn unchecked(&self) -> Option<i32> { None }
+}
+
+#[must_use]
+fn parse() -> Result<i32, ()> { Ok(0) }
+fn lock() -> Guard { Guard }
+
+fn f(s: S) {
+    let _ = parse();
+  //^^^^^^^ 💡 weak: `let _ =` discards the `#[must_use]` return value of `parse`
+    let _ = s.checked();
+  //^^^^^^^ 💡 weak: `let _ =` discards the `#[must_use]` return value of `checked`
+    let _ = lock();
+  //^^^^^^^ weak: `let _ =` discards a `#[must_use]` value of type `Guard`
+    let _ = s.unchecked();
+    let _guard = lock();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn needs_restriction_lints() {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.restriction_lints = false;
+        check_diagnostics_with_config(
+            config,
+            r#"
+//- minicore: result
+#[must_use]
+fn parse() -> Result<i32, ()> { Ok(0) }
+fn f() {
+    let _ = parse();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn propagates_or_expects_value() {
+        check_fixes(
+            r#"
+//- minicore: result
+#[must_use]
+fn parse() -> Result<i32, ()> { Ok(0) }
+fn f() -> Result<(), ()> {
+    let _$0 = parse();
+    Ok(())
+}
+"#,
+            vec![
+                r#"
+#[must_use]
+fn parse() -> Result<i32, ()> { Ok(0) }
+fn f() -> Result<(), ()> {
+    parse()?;
+    Ok(())
+}
+"#,
+                r#"
+#[must_use]
+fn parse() -> Result<i32, ()> { Ok(0) }
+fn f() -> Result<(), ()> {
+    parse().expect("should not fail");
+    Ok(())
+}
+"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn propagates_errors_that_convert() {
+        check_fix(
+            r#"
+//- minicore: result, from
+struct ParseError;
+struct AppError;
+impl From<ParseError> for AppError {
+    fn from(_: ParseError) -> AppError { AppError }
+}
+#[must_use]
+fn parse() -> Result<i32, ParseError> { Ok(0) }
+fn f() -> Result<(), AppError> {
+    let _$0 = parse();
+    Ok(())
+}
+"#,
+            r#"
+struct ParseError;
+struct AppError;
+impl From<ParseError> for AppError {
+    fn from(_: ParseError) -> AppError { AppError }
+}
+#[must_use]
+fn parse() -> Result<i32, ParseError> { Ok(0) }
+fn f() -> Result<(), AppError> {
+    parse()?;
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn only_expects_errors_that_do_not_convert() {
+        check_fix(
+            r#"
+//- minicore: result, from
+struct ParseError;
+struct AppError;
+#[must_use]
+fn parse() -> Result<i32, ParseError> { Ok(0) }
+fn f() -> Result<(), AppError> {
+    let _$0 = parse();
+    Ok(())
+}
+"#,
+            r#"
+struct ParseError;
+struct AppError;
+#[must_use]
+fn parse() -> Result<i32, ParseError> { Ok(0) }
+fn f() -> Result<(), AppError> {
+    parse().expect("should not fail");
+    Ok(())
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,481 @@
+use hir::{AsAssocItem, AssocItemContainer, InFile, Local, PathResolution, Semantics, Struct};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    famous_defs::FamousDefs,
+    RootDatabase,
+};
+use syntax::{ast, match_ast, AstNode, SyntaxNode, SyntaxNodePtr, WalkEvent};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsConfig, Severity};
+
+// Diagnostic: lock-acquired-twice-in-scope
+//
+// This diagnostic is triggered when a `Mutex` or `RwLock` is locked while a guard of an earlier
+// lock of the same place is still alive in the enclosing scopes, as in
+// `let _guard = m.lock(); m.lock();`. Locking it again blocks forever.
+pub(crate) fn lock_acquired_twice_in_scope(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+) -> Option<()> {
+    if config.disable_experimental {
+        return None;
+    }
+    let body = if let Some(func) = ast::Fn::cast(node.clone()) {
+        ast::Expr::BlockExpr(func.body()?)
+    } else if let Some(closure) = ast::ClosureExpr::cast(node.clone()) {
+        closure.body()?
+    } else {
+        return None;
+    };
+    let krate = sema.scope(node)?.krate();
+    let famous_defs = FamousDefs(sema, krate);
+    let (mutex, rw_lock) = (famous_defs.std_sync_Mutex(), famous_defs.std_sync_RwLock());
+    if mutex.is_none() && rw_lock.is_none() {
+        return None;
+    }
+    let mut ctx = LockCtx { sema, mutex, rw_lock, file_id, acc, moved: Vec::new() };
+    match body {
+        ast::Expr::BlockExpr(block) => ctx.visit_block(&block.stmt_list()?, Vec::new()),
+        body => ctx.visit_expr(body.syntax(), &[], &mut Vec::new()),
+    }
+
+    Some(())
+}
+
+/// A lock that is held at some point of the body.
+#[derive(Clone)]
+struct Guard {
+    place: Place,
+    exclusive: bool,
+    /// The local the guard is bound to, `None` for temporaries.
+    local: Option<Local>,
+}
+
+impl Guard {
+    fn conflicts_with(&self, other: &Guard) -> bool {
+        self.place.root == other.place.root
+            && self.place.fields == other.place.fields
+            && (self.exclusive || other.exclusive)
+    }
+}
+
+/// A place holding a lock, like `self.state`: the local or static it starts at, followed by the
+/// names of the fields.
+#[derive(Clone)]
+struct Place {
+    root: PathResolution,
+    fields: Vec<String>,
+    /// The place as written, without whitespace.
+    text: String,
+}
+
+struct LockCtx<'a, 'db> {
+    sema: &'a Semantics<'db, RootDatabase>,
+    mutex: Option<Struct>,
+    rw_lock: Option<Struct>,
+    file_id: FileId,
+    acc: &'a mut Vec<Diagnostic>,
+    /// Locals of guards that were moved, into `drop` or any other function.
+    moved: Vec<Local>,
+}
+
+impl LockCtx<'_, '_> {
+    /// Guards bound to locals live until the end of the block or until they are moved,
+    /// temporaries until the end of their statement.
+    fn visit_block(&mut self, stmt_list: &ast::StmtList, mut live: Vec<Guard>) {
+        for stmt in stmt_list.statements() {
+            match stmt {
+                ast::Stmt::LetStmt(let_stmt) => {
+                    self.visit_expr(let_stmt.syntax(), &live, &mut Vec::new());
+                    if let Some(guard) = self.bound_guard(&let_stmt) {
+                        live.push(guard);
+                    }
+                }
+                ast::Stmt::ExprStmt(expr_stmt) => {
+                    self.visit_expr(expr_stmt.syntax(), &live, &mut Vec::new());
+                }
+                ast::Stmt::Item(_) => (),
+            }
+        }
+        if let Some(tail) = stmt_list.tail_expr() {
+            self.visit_expr(tail.syntax(), &live, &mut Vec::new());
+        }
+    }
+
+    /// Checks the locks in `node` in evaluation order. Nested blocks see the guards of the
+    /// enclosing scopes and the temporaries of the statement they are part of, except for those
+    /// of `if` and `while` conditions, which are dropped before the body runs.
+    fn visit_expr(&mut self, node: &SyntaxNode, live: &[Guard], temporaries: &mut Vec<Guard>) {
+        // The conditions being visited, with the number of temporaries created before them.
+        let mut conditions = Vec::new();
+        let mut preorder = node.preorder();
+        while let Some(event) = preorder.next() {
+            let node = match event {
+                WalkEvent::Enter(node) => {
+                    // Closures, async blocks and nested items run some other time.
+                    if ast::ClosureExpr::can_cast(node.kind()) || ast::Item::can_cast(node.kind()) {
+                        preorder.skip_subtree();
+                    } else if let Some(block) = ast::BlockExpr::cast(node.clone()) {
+                        preorder.skip_subtree();
+                        if block.async_token().is_none() && block.const_token().is_none() {
+                            if let Some(stmt_list) = block.stmt_list() {
+                                let live = self.live(live).chain(temporaries.iter()).cloned();
+                                self.visit_block(&stmt_list, live.collect());
+                            }
+                        }
+                    } else if is_temporary_scope(&node) {
+                        conditions.push((node, temporaries.len()));
+                    }
+                    continue;
+                }
+                WalkEvent::Leave(node) => node,
+            };
+            if conditions.last().is_some_and(|(condition, _)| *condition == node) {
+                let (_, len) = conditions.pop().unwrap();
+                temporaries.truncate(len);
+                continue;
+            }
+            if let Some(path) = ast::PathExpr::cast(node.clone()) {
+                self.record_move(&path);
+                continue;
+            }
+            let Some(call) = ast::MethodCallExpr::cast(node) else { continue };
+            let Some(guard) = self.lock(&call) else { continue };
+            if self.live(live).chain(temporaries.iter()).any(|it| it.conflicts_with(&guard)) {
+                self.acc.push(
+                    Diagnostic::new(
+                        DiagnosticCode::Ra("lock-acquired-twice-in-scope", Severity::Warning),
+                        format!(
+                            "`{}` is locked again while a guard of an earlier lock is still alive, this will deadlock",
+                            guard.place.text
+                        ),
+                        FileRange { file_id: self.file_id, range: call.syntax().text_range() },
+                    )
+                    .with_main_node(InFile::new(
+                        self.file_id.into(),
+                        SyntaxNodePtr::new(call.syntax()),
+                    ))
+                    .experimental(),
+                );
+            }
+            temporaries.push(guard);
+        }
+    }
+
+    /// The guards of `live` that were not moved away.
+    fn live<'g>(&'g self, live: &'g [Guard]) -> impl Iterator<Item = &'g Guard> + 'g {
+        live.iter()
+            .filter(move |guard| !guard.local.is_some_and(|local| self.moved.contains(&local)))
+    }
+
+    /// Records that the local named by `path` is moved if it is passed to a function by value.
+    fn record_move(&mut self, path: &ast::PathExpr) {
+        if !path.syntax().parent().is_some_and(|it| ast::ArgList::can_cast(it.kind())) {
+            return;
+        }
+        if let Some(PathResolution::Local(local)) =
+            path.path().and_then(|it| self.sema.resolve_path(&it))
+        {
+            self.moved.push(local);
+        }
+    }
+
+    /// The guard held by `call` if it is `Mutex::lock`, `RwLock::read` or `RwLock::write`.
+    fn lock(&self, call: &ast::MethodCallExpr) -> Option<Guard> {
+        let db = self.sema.db;
+        let func = self.sema.resolve_method_call(call)?;
+        let AssocItemContainer::Impl(impl_) = func.as_assoc_item(db)?.container(db) else {
+            return None;
+        };
+        let adt = impl_.self_ty(db).as_adt()?;
+        let is = |it: Option<Struct>| it.is_some_and(|it| adt == it.into());
+        let exclusive = match func.name(db).to_smol_str().as_str() {
+            "lock" if is(self.mutex) => true,
+            "write" if is(self.rw_lock) => true,
+            "read" if is(self.rw_lock) => false,
+            _ => return None,
+        };
+        Some(Guard { place: self.place(&call.receiver()?)?, exclusive, local: None })
+    }
+
+    /// The guard `let_stmt` binds to a local, like in `let guard = m.lock().unwrap();`.
+    fn bound_guard(&self, let_stmt: &ast::LetStmt) -> Option<Guard> {
+        let ast::Pat::IdentPat(ident_pat) = let_stmt.pat()? else { return None };
+        if ident_pat.ref_token().is_some() || ident_pat.pat().is_some() {
+            return None;
+        }
+        let mut expr = let_stmt.initializer()?;
+        loop {
+            expr = match expr {
+                ast::Expr::TryExpr(it) => it.expr()?,
+                ast::Expr::MethodCallExpr(call) => match self.lock(&call) {
+                    Some(guard) => {
+                        let local = self.sema.to_def(&ident_pat)?;
+                        return Some(Guard { local: Some(local), ..guard });
+                    }
+                    None if matches!(call.name_ref()?.text().as_str(), "unwrap" | "expect") => {
+                        call.receiver()?
+                    }
+                    None => return None,
+                },
+                _ => return None,
+            };
+        }
+    }
+
+    /// The place `expr` names, like `self.state` or `*lock`. Anything else, including constants,
+    /// may evaluate to a different lock each time.
+    fn place(&self, expr: &ast::Expr) -> Option<Place> {
+        match expr {
+            ast::Expr::PathExpr(it) => {
+                let path = it.path()?;
+                let root = self.sema.resolve_path(&path)?;
+                match root {
+                    PathResolution::Local(_) | PathResolution::Def(hir::ModuleDef::Static(_)) => (),
+                    _ => return None,
+                }
+                let text = path.syntax().text().to_string().replace(' ', "");
+                Some(Place { root, fields: Vec::new(), text })
+            }
+            ast::Expr::FieldExpr(it) => {
+                let mut place = self.place(&it.expr()?)?;
+                let field = it.name_ref()?.text().to_string();
+                place.text = format!("{}.{field}", place.text);
+                place.fields.push(field);
+                Some(place)
+            }
+            ast::Expr::ParenExpr(it) => self.place(&it.expr()?),
+            ast::Expr::RefExpr(it) => self.place(&it.expr()?),
+            ast::Expr::PrefixExpr(it) if it.op_kind()? == ast::UnaryOp::Deref => {
+                self.place(&it.expr()?)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether `node` is the condition of an `if` or `while` expression, whose temporaries are dropped
+/// before the body runs. The scrutinee of `if let` and `while let` lives until the end instead.
+fn is_temporary_scope(node: &SyntaxNode) -> bool {
+    if ast::LetExpr::can_cast(node.kind()) {
+        return false;
+    }
+    let Some(parent) = node.parent() else { return false };
+    let condition = match_ast! {
+        match parent {
+            ast::IfExpr(it) => it.condition(),
+            ast::WhileExpr(it) => it.condition(),
+            _ => None,
+        }
+    };
+    condition.is_some_and(|it| it.syntax() == node)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::check_diagnostics_with_config, DiagnosticsConfig};
+
+    /// The parts of `std::sync` the diagnostic knows about.
+    const STD: &str = r#"
+//- /std.rs crate:std
+pub mod prelude { pub mod rust_2021 { pub use core::prelude::rust_2021::*; } }
+pub mod sync {
+    pub struct LockResult<Guard>(Guard);
+    impl<Guard> LockResult<Guard> {
+        pub fn unwrap(self) -> Guard { loop {} }
+    }
+    pub struct Mutex<T>(T);
+    pub struct MutexGuard<'a, T>(&'a T);
+    impl<T> Mutex<T> {
+        pub fn lock(&self) -> LockResult<MutexGuard<'_, T>> { loop {} }
+    }
+    impl<T> core::ops::Deref for MutexGuard<'_, T> {
+        type Target = T;
+        fn deref(&self) -> &T { self.0 }
+    }
+    pub struct RwLock<T>(T);
+    pub struct RwLockReadGuard<'a, T>(&'a T);
+    pub struct RwLockWriteGuard<'a, T>(&'a T);
+    impl<T> RwLock<T> {
+        pub fn read(&self) -> LockResult<RwLockReadGuard<'_, T>> { loop {} }
+        pub fn write(&self) -> LockResult<RwLockWriteGuard<'_, T>> { loop {} }
+    }
+}
+"#;
+
+    #[track_caller]
+    fn check(ra_fixture: &str) {
+        check_with_config(DiagnosticsConfig::test_sample(), ra_fixture);
+    }
+
+    #[track_caller]
+    fn check_with_config(config: DiagnosticsConfig, ra_fixture: &str) {
+        check_diagnostics_with_config(
+            config,
+            &format!("//- minicore: copy, deref, drop\n//- /main.rs crate:main deps:std{ra_fixture}{STD}"),
+        );
+    }
+
+    #[test]
+    fn locked_while_guard_alive() {
+        check(
+            r#"
+use std::sync::Mutex;
+fn f(m: &Mutex<i32>) {
+    let _guard = m.lock().unwrap();
+    let _ = m.lock();
+          //^^^^^^^^ warn: `m` is locked again while a guard of an earlier lock is still alive, this will deadlock
+}
+struct S { state: Mutex<i32> }
+impl S {
+    fn f(&self) {
+        let _guard = self.state.lock().unwrap();
+        if true {
+            let _ = self.state.lock();
+                  //^^^^^^^^^^^^^^^^^ warn: `self.state` is locked again while a guard of an earlier lock is still alive, this will deadlock
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn locked_twice_in_one_statement() {
+        check(
+            r#"
+use std::sync::Mutex;
+struct Pair { a: i32, b: i32 }
+fn f(m: &Mutex<Pair>) -> i32 {
+    m.lock().unwrap().a + m.lock().unwrap().b
+                        //^^^^^^^^ warn: `m` is locked again while a guard of an earlier lock is still alive, this will deadlock
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn guard_dropped_before_locking_again() {
+        check(
+            r#"
+use std::sync::Mutex;
+fn f(m: &Mutex<i32>, n: &Mutex<i32>) {
+    let guard = m.lock().unwrap();
+    drop(guard);
+    let _guard = m.lock().unwrap();
+    let _ = n.lock();
+}
+fn g(m: &Mutex<i32>) {
+    {
+        let _guard = m.lock().unwrap();
+    }
+    let _ = m.lock();
+    let _ = m.lock();
+}
+fn h(m: &Mutex<i32>) {
+    let _guard = m.lock().unwrap();
+    let _ = move || m.lock();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn guard_moved_into_function() {
+        check(
+            r#"
+use std::sync::{Mutex, MutexGuard};
+fn release(_guard: MutexGuard<'_, i32>) {}
+fn inspect(_guard: &MutexGuard<'_, i32>) {}
+fn f(m: &Mutex<i32>) {
+    let guard = m.lock().unwrap();
+    release(guard);
+    let _ = m.lock();
+}
+fn g(m: &Mutex<i32>) {
+    let guard = m.lock().unwrap();
+    inspect(&guard);
+    let _ = m.lock();
+          //^^^^^^^^ warn: `m` is locked again while a guard of an earlier lock is still alive, this will deadlock
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn shadowed_locks_are_different() {
+        check(
+            r#"
+use std::sync::Mutex;
+fn f(m: &Mutex<i32>, n: &Mutex<i32>) {
+    let _guard = m.lock().unwrap();
+    let m = n;
+    let _ = m.lock();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn scrutinee_temporaries_live_in_the_body() {
+        check(
+            r#"
+use std::sync::Mutex;
+struct State { ready: bool, count: i32 }
+fn f(m: &Mutex<State>) {
+    if let 0 = m.lock().unwrap().count {
+        let _ = m.lock();
+              //^^^^^^^^ warn: `m` is locked again while a guard of an earlier lock is still alive, this will deadlock
+    }
+    match m.lock().unwrap().count {
+        0 => {
+            let _ = m.lock();
+                  //^^^^^^^^ warn: `m` is locked again while a guard of an earlier lock is still alive, this will deadlock
+        }
+        _ => {}
+    }
+    if m.lock().unwrap().ready {
+        let _ = m.lock();
+    }
+    while m.lock().unwrap().ready {
+        let _ = m.lock();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn rw_lock_readers_can_share() {
+        check(
+            r#"
+use std::sync::RwLock;
+fn f(l: &RwLock<i32>) {
+    let _a = l.read().unwrap();
+    let _b = l.read().unwrap();
+    let _ = l.write();
+          //^^^^^^^^^ warn: `l` is locked again while a guard of an earlier lock is still alive, this will deadlock
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn disabled_with_experimental_diagnostics() {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.disable_experimental = true;
+        check_with_config(
+            config,
+            r#"
+use std::sync::Mutex;
+fn f(m: &Mutex<i32>) {
+    let _guard = m.lock().unwrap();
+    let _ = m.lock();
+}
+"#,
+        );
+    }
+}
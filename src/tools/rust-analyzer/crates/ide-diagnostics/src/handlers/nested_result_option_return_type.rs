@@ -0,0 +1,145 @@
+use hir::{Adt, AsAssocItem, Enum, InFile, Semantics, Type};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    famous_defs::FamousDefs,
+    RootDatabase,
+};
+use syntax::{ast, AstNode, SyntaxNode, SyntaxNodePtr};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsConfig, Severity};
+
+// Diagnostic: nested-result-option-return-type
+//
+// This diagnostic is triggered when the return type of a function nests `Result` and `Option`
+// deeper than `rust-analyzer.diagnostics.nestedResultOption.maxDepth`, like
+// `Result<Option<Result<T, E>>, E2>` does. Such types are easy to mishandle at call sites,
+// flattening them or introducing a named enum for the possible outcomes is usually clearer.
+pub(crate) fn nested_result_option_return_type(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+) -> Option<()> {
+    if !config.style_lints {
+        return None;
+    }
+    let fn_ = ast::Fn::cast(node.clone())?;
+    let ret_ty = fn_.ret_type()?.ty()?;
+    let func = sema.to_def(&fn_)?;
+    // The signature of a trait impl method is dictated by the trait.
+    if func.as_assoc_item(sema.db).and_then(|it| it.implemented_trait(sema.db)).is_some() {
+        return None;
+    }
+
+    let famous_defs = FamousDefs(sema, func.module(sema.db).krate());
+    let wrappers: Vec<Enum> = [famous_defs.core_option_Option(), famous_defs.core_result_Result()]
+        .into_iter()
+        .flatten()
+        .collect();
+    let depth = nesting_depth(&wrappers, &func.ret_type(sema.db));
+    if depth <= config.nested_result_option_max_depth {
+        return None;
+    }
+
+    acc.push(
+        Diagnostic::new(
+            DiagnosticCode::Ra("nested-result-option-return-type", Severity::WeakWarning),
+            format!(
+                "return type nests `Result` and `Option` {depth} levels deep, consider flattening it or introducing a named enum"
+            ),
+            FileRange { file_id, range: ret_ty.syntax().text_range() },
+        )
+        .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(node))),
+    );
+
+    Some(())
+}
+
+/// How many `Option`s and `Result`s are directly nested into each other in `ty`.
+fn nesting_depth(wrappers: &[Enum], ty: &Type) -> usize {
+    match ty.as_adt() {
+        Some(Adt::Enum(it)) if wrappers.contains(&it) => {
+            1 + ty.type_arguments().map(|arg| nesting_depth(wrappers, &arg)).max().unwrap_or(0)
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config},
+        DiagnosticsConfig,
+    };
+
+    #[test]
+    fn deeply_nested_return_type() {
+        check_diagnostics(
+            r#"
+//- minicore: option, result
+fn f() -> Result<Option<Result<i32, ()>>, ()> { loop {} }
+        //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ weak: return type nests `Result` and `Option` 3 levels deep, consider flattening it or introducing a named enum
+fn g() -> Option<Option<Option<Option<i32>>>> { loop {} }
+        //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ weak: return type nests `Result` and `Option` 4 levels deep, consider flattening it or introducing a named enum
+"#,
+        );
+    }
+
+    #[test]
+    fn shallow_or_interrupted_nesting() {
+        check_diagnostics(
+            r#"
+//- minicore: option, result
+struct Wrapper<T>(T);
+fn f() -> Result<Option<i32>, ()> { loop {} }
+fn g() -> Option<Wrapper<Option<Option<i32>>>> { loop {} }
+fn h() -> Result<Option<i32>, Option<()>> { loop {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn resolves_type_aliases() {
+        check_diagnostics(
+            r#"
+//- minicore: option, result
+type Maybe<T> = Option<Result<T, ()>>;
+fn f() -> Option<Maybe<i32>> { loop {} }
+        //^^^^^^^^^^^^^^^^^^ weak: return type nests `Result` and `Option` 3 levels deep, consider flattening it or introducing a named enum
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_trait_impls() {
+        check_diagnostics(
+            r#"
+//- minicore: option, result
+trait Trait {
+    fn f() -> Option<Option<Option<i32>>>;
+            //^^^^^^^^^^^^^^^^^^^^^^^^^^^ weak: return type nests `Result` and `Option` 3 levels deep, consider flattening it or introducing a named enum
+}
+struct S;
+impl Trait for S {
+    fn f() -> Option<Option<Option<i32>>> { loop {} }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn configurable_depth() {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.nested_result_option_max_depth = 1;
+        check_diagnostics_with_config(
+            config,
+            r#"
+//- minicore: option, result
+fn f() -> Result<Option<i32>, ()> { loop {} }
+        //^^^^^^^^^^^^^^^^^^^^^^^ weak: return type nests `Result` and `Option` 2 levels deep, consider flattening it or introducing a named enum
+fn g() -> Option<i32> { loop {} }
+"#,
+        );
+    }
+}
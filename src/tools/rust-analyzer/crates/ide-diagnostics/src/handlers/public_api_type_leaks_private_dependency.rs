@@ -0,0 +1,274 @@
+use hir::{
+    AsAssocItem, AssocItemContainer, Crate, HasVisibility, InFile, Module, ModuleDef, ScopeDef,
+    Semantics, Type, Visibility,
+};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    FxHashMap, FxHashSet, RootDatabase,
+};
+use syntax::{
+    ast::{self, HasName},
+    AstNode, SyntaxNode, SyntaxNodePtr,
+};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsConfig, Severity};
+
+// Diagnostic: public-api-type-leaks-private-dependency
+//
+// This diagnostic is triggered when a public function or struct of a library exposes a type
+// defined in one of its dependencies, while that dependency is neither re-exported by the library
+// nor listed in `rust-analyzer.diagnostics.publicDependencies`. Upgrading such a dependency
+// silently becomes a breaking change of the library.
+pub(crate) fn public_api_type_leaks_private_dependency(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+    reexports: &mut FxHashMap<Crate, FxHashSet<ModuleDef>>,
+) -> Option<()> {
+    let db = sema.db;
+    let (name, module, types) = if let Some(it) = ast::Fn::cast(node.clone()) {
+        let func = sema.to_def(&it)?;
+        let exported = match func.as_assoc_item(db).map(|it| it.container(db)) {
+            None => is_exported(db, func.visibility(db), func.module(db)),
+            Some(AssocItemContainer::Trait(trait_)) => {
+                is_exported(db, trait_.visibility(db), trait_.module(db))
+            }
+            Some(AssocItemContainer::Impl(impl_)) => {
+                let adt = impl_.self_ty(db).as_adt()?;
+                impl_.trait_(db).is_none()
+                    && is_exported(db, func.visibility(db), func.module(db))
+                    && is_exported(db, adt.visibility(db), adt.module(db))
+            }
+        };
+        if !exported {
+            return None;
+        }
+        let mut types: Vec<Type> =
+            func.assoc_fn_params(db).into_iter().map(|param| param.ty().clone()).collect();
+        types.push(func.ret_type(db));
+        (it.name()?, func.module(db), types)
+    } else if let Some(it) = ast::Struct::cast(node.clone()) {
+        let strukt = sema.to_def(&it)?;
+        if !is_exported(db, strukt.visibility(db), strukt.module(db)) {
+            return None;
+        }
+        let types = strukt
+            .fields(db)
+            .into_iter()
+            .filter(|field| field.visibility(db) == Visibility::Public)
+            .map(|field| field.ty(db))
+            .collect();
+        (it.name()?, strukt.module(db), types)
+    } else {
+        return None;
+    };
+
+    let krate = module.krate();
+    if !krate.origin(db).is_local() || krate.is_executable(db) {
+        return None;
+    }
+
+    let private_deps: Vec<_> = krate
+        .dependencies(db)
+        .into_iter()
+        .filter(|dep| !dep.krate.is_builtin(db))
+        .filter(|dep| {
+            let dep_name = dep.name.to_smol_str();
+            !config
+                .public_dependencies
+                .iter()
+                .any(|public| public.replace('-', "_") == dep_name.as_str())
+        })
+        .collect();
+    if private_deps.is_empty() {
+        return None;
+    }
+
+    let mut leaked = Vec::new();
+    for ty in &types {
+        ty.walk(db, |ty| {
+            let Some(adt) = ty.as_adt() else { return };
+            let Some(dep) = private_deps.iter().find(|dep| dep.krate == adt.module(db).krate())
+            else {
+                return;
+            };
+            if !leaked.iter().any(|(it, _)| *it == adt) {
+                leaked.push((adt, dep.name.clone()));
+            }
+        });
+    }
+    if leaked.is_empty() {
+        return None;
+    }
+
+    let reexports = reexports.entry(krate).or_insert_with(|| public_reexports(db, krate));
+    for (adt, dep_name) in leaked {
+        let dep_root = adt.module(db).krate().root_module();
+        if reexports.contains(&ModuleDef::Adt(adt))
+            || reexports.contains(&ModuleDef::Module(dep_root))
+        {
+            continue;
+        }
+        acc.push(
+            Diagnostic::new(
+                DiagnosticCode::Ra(
+                    "public-api-type-leaks-private-dependency",
+                    Severity::WeakWarning,
+                ),
+                format!(
+                    "`{}` from private dependency `{}` is exposed in the public API of `{}`",
+                    adt.name(db).display(db),
+                    dep_name.display(db),
+                    name.text(),
+                ),
+                FileRange { file_id, range: name.syntax().text_range() },
+            )
+            .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(node)))
+            .experimental(),
+        );
+    }
+
+    Some(())
+}
+
+/// Whether an item with visibility `vis` defined in `module` can be named from other crates.
+fn is_exported(db: &RootDatabase, vis: Visibility, module: Module) -> bool {
+    vis == Visibility::Public
+        && module.path_to_root(db).into_iter().all(|module| {
+            module.is_crate_root()
+                || module.name(db).is_some() && module.visibility(db) == Visibility::Public
+        })
+}
+
+/// Collects the items (and extern crates) that `krate` re-exports from its public modules. This
+/// walks the whole crate, so the result is shared by all items checked in a diagnostics run.
+fn public_reexports(db: &RootDatabase, krate: Crate) -> FxHashSet<ModuleDef> {
+    // Any module of another crate only sees the `pub` entries of the scope.
+    let Some(outside) = krate.dependencies(db).first().map(|dep| dep.krate.root_module()) else {
+        return FxHashSet::default();
+    };
+    krate
+        .modules(db)
+        .into_iter()
+        .filter(|&module| module.is_crate_root() || is_exported(db, Visibility::Public, module))
+        .flat_map(|module| module.scope(db, Some(outside)))
+        .filter_map(|(_, def)| match def {
+            ScopeDef::ModuleDef(def) => Some(def),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config},
+        DiagnosticsConfig,
+    };
+
+    #[test]
+    fn leaks_in_function_signature() {
+        check_diagnostics(
+            r#"
+//- /dep.rs crate:dep
+pub struct Config;
+pub struct Error;
+//- /lib.rs crate:lib deps:dep
+pub fn load(_config: dep::Config) {}
+     //^^^^ weak: `Config` from private dependency `dep` is exposed in the public API of `load`
+pub fn store() -> Result<(), dep::Error> { loop {} }
+     //^^^^^ weak: `Error` from private dependency `dep` is exposed in the public API of `store`
+pub enum Result<T, E> { Ok(T), Err(E) }
+"#,
+        );
+    }
+
+    #[test]
+    fn leaks_in_public_fields_and_methods() {
+        check_diagnostics(
+            r#"
+//- /dep.rs crate:dep
+pub struct Handle;
+//- /lib.rs crate:lib deps:dep
+pub struct Client {
+         //^^^^^^ weak: `Handle` from private dependency `dep` is exposed in the public API of `Client`
+    pub handle: dep::Handle,
+    inner: dep::Handle,
+}
+impl Client {
+    pub fn handle(&self) -> &dep::Handle { &self.inner }
+         //^^^^^^ weak: `Handle` from private dependency `dep` is exposed in the public API of `handle`
+    fn inner(&self) -> &dep::Handle { &self.inner }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_private_items_and_binaries() {
+        check_diagnostics(
+            r#"
+//- /dep.rs crate:dep
+pub struct Handle;
+//- /lib.rs crate:lib deps:dep
+mod private {
+    pub fn handle() -> dep::Handle { dep::Handle }
+}
+pub(crate) fn handle() -> dep::Handle { dep::Handle }
+pub struct Client {
+    handle: dep::Handle,
+}
+//- /main.rs crate:main deps:dep env:CARGO_BIN_NAME=main
+pub fn handle() -> dep::Handle { dep::Handle }
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn library_with_main_function() {
+        check_diagnostics(
+            r#"
+//- /dep.rs crate:dep
+pub struct Handle;
+//- /lib.rs crate:lib deps:dep
+pub fn handle() -> dep::Handle { dep::Handle }
+     //^^^^^^ weak: `Handle` from private dependency `dep` is exposed in the public API of `handle`
+pub fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_reexported_types_and_dependencies() {
+        check_diagnostics(
+            r#"
+//- /dep.rs crate:dep
+pub struct Handle;
+//- /other.rs crate:other
+pub struct Config;
+//- /lib.rs crate:lib deps:dep,other
+pub use dep::Handle;
+pub use other;
+pub fn handle(_config: other::Config) -> dep::Handle { dep::Handle }
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_configured_public_dependencies() {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.public_dependencies = vec!["my-dep".to_owned()];
+        check_diagnostics_with_config(
+            config,
+            r#"
+//- /dep.rs crate:my_dep
+pub struct Handle;
+//- /lib.rs crate:lib deps:my_dep
+pub fn handle() -> my_dep::Handle { my_dep::Handle }
+"#,
+        );
+    }
+}
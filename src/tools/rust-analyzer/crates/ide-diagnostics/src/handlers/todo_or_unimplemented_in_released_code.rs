@@ -0,0 +1,170 @@
+use hir::{InFile, Semantics};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    RootDatabase,
+};
+use syntax::{ast, AstNode, SyntaxNode, SyntaxNodePtr, TextRange};
+
+use crate::{is_test_code, Diagnostic, DiagnosticCode, DiagnosticsConfig, Severity};
+
+// Diagnostic: todo-or-unimplemented-in-released-code
+//
+// This diagnostic is triggered by `todo!()`, `unimplemented!()` and `dbg!()` outside of test
+// code. They are handy while working on the code, but shouldn't be left in a release. The
+// diagnostic is off unless `rust-analyzer.diagnostics.releasedCode.level` is set to `warn` or
+// `deny`, each of the macros can be exempted with its own `enable` setting.
+pub(crate) fn todo_or_unimplemented_in_released_code(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+    config: &DiagnosticsConfig,
+) -> Option<()> {
+    if config.released_code_severity == Severity::Allow {
+        return None;
+    }
+    let macro_call = ast::MacroCall::cast(node.clone())?;
+    let mac = sema.resolve_macro_call(&macro_call)?;
+    // Only the macros of the standard library, under whatever name they are imported.
+    if !mac.module(sema.db).krate().is_builtin(sema.db) {
+        return None;
+    }
+    let name = mac.name(sema.db);
+    let name = config.released_code_macros.iter().find(|&&it| name.as_str() == Some(it))?;
+    if is_test_code(sema, node) {
+        return None;
+    }
+
+    let range = TextRange::new(
+        macro_call.syntax().text_range().start(),
+        macro_call.excl_token()?.text_range().end(),
+    );
+    acc.push(
+        Diagnostic::new(
+            DiagnosticCode::Ra(
+                "todo-or-unimplemented-in-released-code",
+                config.released_code_severity,
+            ),
+            format!("`{name}!` should not be left in released code"),
+            FileRange { file_id, range },
+        )
+        .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(node))),
+    );
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::check_diagnostics_with_config, DiagnosticsConfig, Severity};
+
+    fn check(config: DiagnosticsConfig, ra_fixture: &str) {
+        check_diagnostics_with_config(
+            config,
+            &format!("//- minicore: todo, unimplemented, dbg, test\n{ra_fixture}"),
+        );
+    }
+
+    fn config(severity: Severity) -> DiagnosticsConfig {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.released_code_severity = severity;
+        config
+    }
+
+    #[test]
+    fn flags_scaffolding_macros() {
+        check(
+            config(Severity::Warning),
+            r#"
+use core::{dbg, todo, unimplemented as unimpl};
+fn f(x: i32) -> i32 {
+    dbg!(x);
+  //^^^^ warn: `dbg!` should not be left in released code
+    match x {
+        0 => todo!(),
+           //^^^^^ warn: `todo!` should not be left in released code
+        _ => unimpl!("non-zero numbers"),
+           //^^^^^^^ warn: `unimplemented!` should not be left in released code
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn configurable_level() {
+        check(
+            config(Severity::Error),
+            r#"
+fn f() -> i32 {
+    core::todo!()
+  //^^^^^^^^^^^ error: `todo!` should not be left in released code
+}
+"#,
+        );
+        check(
+            config(Severity::Allow),
+            r#"
+fn f() -> i32 {
+    core::todo!()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn per_macro_toggles() {
+        let mut config = config(Severity::Warning);
+        config.released_code_macros = vec!["todo"];
+        check(
+            config,
+            r#"
+fn f(x: i32) -> i32 {
+    core::dbg!(x);
+    match x {
+        0 => core::todo!(),
+           //^^^^^^^^^^^ warn: `todo!` should not be left in released code
+        _ => core::unimplemented!(),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn ignores_test_code_and_other_macros() {
+        check(
+            config(Severity::Warning),
+            r#"
+//- /main.rs crate:main cfg:test,unix
+macro_rules! todo {
+    () => { 0 };
+}
+fn f() -> i32 {
+    todo!()
+}
+#[test]
+fn g() {
+    core::dbg!(1);
+}
+#[cfg(test)]
+mod tests {
+    fn h() -> i32 {
+        core::unimplemented!()
+    }
+}
+#[cfg(all(test, unix))]
+mod unix_tests {
+    fn h() -> i32 {
+        core::todo!()
+    }
+}
+#[cfg(any(test, feature = "bench"))]
+fn bench() -> i32 {
+    core::todo!()
+  //^^^^^^^^^^^ warn: `todo!` should not be left in released code
+}
+"#,
+        );
+    }
+}
@@ -108,6 +108,7 @@ fn quickfix_for_redundant_assoc_item(
         target: range,
         source_change: Some(source_change_builder.finish()),
         trigger_signature_help: false,
+        parameter_prompt: None,
     }])
 }
 
@@ -75,6 +75,7 @@ fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::TypedHole) -> Option<Vec<Assist>
                 TextEdit::replace(original_range.range, code),
             )),
             trigger_signature_help: false,
+            parameter_prompt: None,
         })
         .collect();
 
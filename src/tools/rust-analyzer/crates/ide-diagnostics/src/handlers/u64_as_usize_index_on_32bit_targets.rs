@@ -0,0 +1,289 @@
+use hir::{db::HirDatabase, InFile, Semantics};
+use ide_db::{
+    base_db::{FileId, FileRange},
+    famous_defs::FamousDefs,
+    source_change::SourceChange,
+    RootDatabase,
+};
+use syntax::{ast, match_ast, AstNode, Edition, SyntaxNode, SyntaxNodePtr};
+use text_edit::TextEdit;
+
+use crate::{fix, Diagnostic, DiagnosticCode, Severity};
+
+/// Methods whose `usize` argument is an index into or a length of the container.
+const INDEX_OR_LEN_METHODS: &[&str] = &[
+    "get",
+    "get_mut",
+    "get_unchecked",
+    "get_unchecked_mut",
+    "insert",
+    "remove",
+    "reserve",
+    "reserve_exact",
+    "resize",
+    "split_at",
+    "split_at_mut",
+    "split_off",
+    "swap",
+    "swap_remove",
+    "truncate",
+    "with_capacity",
+];
+
+// Diagnostic: u64-as-usize-index-on-32bit-targets
+//
+// This diagnostic is triggered when a `u64` or `i64` is converted with `as usize` and used as an
+// index or a length, as in `v[offset as usize]`, while the crate is built for a target with
+// pointers narrower than 64 bits. There the cast silently drops the upper bits and indexes the
+// wrong element.
+//
+// The fixes replace the cast with `usize::try_from`, either propagating the error with `?` out of
+// a function returning a `Result`, or panicking.
+pub(crate) fn u64_as_usize_index_on_32bit_targets(
+    sema: &Semantics<'_, RootDatabase>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let cast = ast::CastExpr::cast(node.clone())?;
+    if !is_index_or_len(&cast) {
+        return None;
+    }
+    let expr = cast.expr()?;
+    let source_ty = sema.type_of_expr(&expr)?.original.as_builtin()?.name();
+    if !matches!(source_ty.as_str(), Some("u64" | "i64")) {
+        return None;
+    }
+    let source_ty = source_ty.display(sema.db);
+    if !sema.type_of_expr(&ast::Expr::CastExpr(cast.clone()))?.original.is_usize() {
+        return None;
+    }
+    let krate = sema.scope(node)?.krate();
+    let pointer_size = sema.db.target_data_layout(krate.into()).ok()?.pointer_size;
+    if pointer_size.bits() >= 64 {
+        return None;
+    }
+
+    let range = cast.syntax().text_range();
+    // `TryFrom` is only part of the prelude since edition 2021.
+    let fixes = (krate.edition(sema.db) >= Edition::Edition2021).then(|| {
+        let replace = |replacement: String| {
+            SourceChange::from_text_edit(file_id, TextEdit::replace(range, replacement))
+        };
+        let mut fixes = Vec::new();
+        if returns_result(sema, &cast) {
+            fixes.push(fix(
+                "use_usize_try_from_and_propagate",
+                "Use `usize::try_from`, returning the error if the value doesn't fit",
+                replace(format!("usize::try_from({expr})?")),
+                range,
+            ));
+        }
+        fixes.push(fix(
+            "use_usize_try_from",
+            "Use `usize::try_from`, panicking if the value doesn't fit",
+            replace(format!("usize::try_from({expr}).expect(\"index does not fit into `usize`\")")),
+            range,
+        ));
+        fixes
+    });
+    acc.push(
+        Diagnostic::new(
+            DiagnosticCode::Ra("u64-as-usize-index-on-32bit-targets", Severity::Warning),
+            format!(
+                "casting `{source_ty}` to `usize` truncates on {}-bit targets",
+                pointer_size.bits()
+            ),
+            FileRange { file_id, range },
+        )
+        .with_fixes(fixes)
+        .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(node))),
+    );
+
+    Some(())
+}
+
+/// Whether `?` in place of `cast` would return from a function returning a `Result`, rather than
+/// from a closure or an async block.
+fn returns_result(sema: &Semantics<'_, RootDatabase>, cast: &ast::CastExpr) -> bool {
+    let Some(func) = cast.syntax().ancestors().find_map(|node| {
+        match_ast! {
+            match node {
+                ast::Fn(it) => Some(Some(it)),
+                ast::ClosureExpr(_) => Some(None),
+                ast::BlockExpr(it) => it.async_token().is_some().then_some(None),
+                _ => None,
+            }
+        }
+    }) else {
+        return false;
+    };
+    let Some(func) = func.and_then(|it| sema.to_def(&it)) else { return false };
+    let result = sema
+        .scope(cast.syntax())
+        .and_then(|scope| FamousDefs(sema, scope.krate()).core_result_Result());
+    let ret_ty = func.ret_type(sema.db);
+    result.is_some_and(|result| ret_ty.as_adt() == Some(hir::Adt::Enum(result)))
+}
+
+/// Whether the value of `cast` is used directly as an index, a range bound of an index, or as the
+/// argument of a method taking an index or a length.
+fn is_index_or_len(cast: &ast::CastExpr) -> bool {
+    let mut expr = cast.syntax().clone();
+    let mut parent = expr.parent();
+    while let Some(node) = parent
+        .clone()
+        .filter(|it| ast::ParenExpr::can_cast(it.kind()) || ast::RangeExpr::can_cast(it.kind()))
+    {
+        expr = node;
+        parent = expr.parent();
+    }
+    let Some(parent) = parent else { return false };
+    if let Some(index_expr) = ast::IndexExpr::cast(parent.clone()) {
+        return index_expr.index().map_or(false, |it| it.syntax() == &expr);
+    }
+    let Some(name) = ast::ArgList::cast(parent).and_then(|arg_list| {
+        match arg_list.syntax().parent().and_then(ast::Expr::cast)? {
+            ast::Expr::MethodCallExpr(call) => call.name_ref(),
+            ast::Expr::CallExpr(call) => match call.expr()? {
+                ast::Expr::PathExpr(path) => path.path()?.segment()?.name_ref(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }) else {
+        return false;
+    };
+    INDEX_OR_LEN_METHODS.contains(&name.text().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{check_diagnostics, check_diagnostics_with_config, check_fix, check_fixes},
+        DiagnosticsConfig,
+    };
+
+    #[test]
+    fn index_and_length_casts() {
+        check_diagnostics(
+            r#"
+//- target_data_layout: e-m:e-p:32:32-i64:64-n32-S128
+//- minicore: index, slice, range
+struct Buf;
+impl Buf {
+    fn get(&self, _index: usize) {}
+}
+fn f(v: &[u8], buf: Buf, offset: u64, delta: i64, len: u32) {
+    let _ = &v[offset as usize..];
+             //^^^^^^^^^^^^^^^ 💡 warn: casting `u64` to `usize` truncates on 32-bit targets
+    buf.get((delta as usize));
+           //^^^^^^^^^^^^^^ 💡 warn: casting `i64` to `usize` truncates on 32-bit targets
+    let _ = v[len as usize];
+    let _ = offset as usize + 1;
+    let _ = v[offset as usize];
+            //^^^^^^^^^^^^^^^ 💡 warn: casting `u64` to `usize` truncates on 32-bit targets
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_diagnostic_on_64bit_targets() {
+        check_diagnostics(
+            r#"
+//- minicore: index, slice
+fn f(v: &[u8], offset: u64) {
+    let _ = v[offset as usize];
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_a_restriction_lint() {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.restriction_lints = false;
+        check_diagnostics_with_config(
+            config,
+            r#"
+//- target_data_layout: e-m:e-p:32:32-i64:64-n32-S128
+//- minicore: index, slice
+fn f(v: &[u8], offset: u64) {
+    let _ = v[offset as usize];
+            //^^^^^^^^^^^^^^^ 💡 warn: casting `u64` to `usize` truncates on 32-bit targets
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replaces_cast_with_try_from() {
+        check_fix(
+            r#"
+//- target_data_layout: e-m:e-p:32:32-i64:64-n32-S128
+//- minicore: index, slice
+fn f(v: &[u8], offset: u64) {
+    let _ = v[offset as usize$0];
+}
+"#,
+            r#"
+fn f(v: &[u8], offset: u64) {
+    let _ = v[usize::try_from(offset).expect("index does not fit into `usize`")];
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn propagates_the_error_out_of_functions_returning_result() {
+        check_fixes(
+            r#"
+//- target_data_layout: e-m:e-p:32:32-i64:64-n32-S128
+//- minicore: copy, index, slice, result
+struct Error;
+fn f(v: &[u8], offset: u64) -> Result<u8, Error> {
+    Ok(v[offset as usize$0])
+}
+"#,
+            vec![
+                r#"
+struct Error;
+fn f(v: &[u8], offset: u64) -> Result<u8, Error> {
+    Ok(v[usize::try_from(offset)?])
+}
+"#,
+                r#"
+struct Error;
+fn f(v: &[u8], offset: u64) -> Result<u8, Error> {
+    Ok(v[usize::try_from(offset).expect("index does not fit into `usize`")])
+}
+"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn closures_only_panic() {
+        check_fixes(
+            r#"
+//- target_data_layout: e-m:e-p:32:32-i64:64-n32-S128
+//- minicore: copy, index, slice, result
+struct Error;
+fn f(v: &[u8], offset: u64) -> Result<(), Error> {
+    let _ = || v[offset as usize$0];
+    Ok(())
+}
+"#,
+            vec![
+                r#"
+struct Error;
+fn f(v: &[u8], offset: u64) -> Result<(), Error> {
+    let _ = || v[usize::try_from(offset).expect("index does not fit into `usize`")];
+    Ok(())
+}
+"#,
+            ],
+        );
+    }
+}
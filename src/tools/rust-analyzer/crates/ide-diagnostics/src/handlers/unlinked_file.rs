@@ -5,15 +5,15 @@
 use hir::{db::DefDatabase, DefMap, InFile, ModuleSource};
 use ide_db::{
     base_db::{FileId, FileLoader, FileRange, SourceDatabase, SourceDatabaseExt},
-    source_change::SourceChange,
+    source_change::SourceChangeBuilder,
+    syntax_helpers::insert_item::{add_item_to_module, ItemPlacement},
     RootDatabase,
 };
 use paths::Utf8Component;
 use syntax::{
-    ast::{self, edit::IndentLevel, HasModuleItem, HasName},
+    ast::{self, edit_in_place::HasVisibilityEdit, make, HasModuleItem, HasName},
     AstNode, TextRange,
 };
-use text_edit::TextEdit;
 
 use crate::{fix, Assist, Diagnostic, DiagnosticCode, DiagnosticsContext, Severity};
 
@@ -169,17 +169,7 @@ fn make_fixes(
     new_mod_name: &str,
     added_file_id: FileId,
 ) -> Option<Vec<Assist>> {
-    fn is_outline_mod(item: &ast::Item) -> bool {
-        matches!(item, ast::Item::Module(m) if m.item_list().is_none())
-    }
-
-    let mod_decl = format!("mod {new_mod_name};");
-    let pub_mod_decl = format!("pub mod {new_mod_name};");
-
-    let mut mod_decl_builder = TextEdit::builder();
-    let mut pub_mod_decl_builder = TextEdit::builder();
-
-    let mut items = match &source {
+    let items = match &source {
         ModuleSource::SourceFile(it) => it.items(),
         ModuleSource::Module(it) => it.item_list()?.items(),
         ModuleSource::BlockExpr(_) => return None,
@@ -187,7 +177,7 @@ fn is_outline_mod(item: &ast::Item) -> bool {
 
     // If there's an existing `mod m;` statement matching the new one, don't emit a fix (it's
     // probably `#[cfg]`d out).
-    for item in items.clone() {
+    for item in items {
         if let ast::Item::Module(m) = item {
             if let Some(name) = m.name() {
                 if m.item_list().is_none() && name.to_string() == new_mod_name {
@@ -198,58 +188,37 @@ fn is_outline_mod(item: &ast::Item) -> bool {
         }
     }
 
-    // If there are existing `mod m;` items, append after them (after the first group of them, rather).
-    match items.clone().skip_while(|item| !is_outline_mod(item)).take_while(is_outline_mod).last() {
-        Some(last) => {
-            cov_mark::hit!(unlinked_file_append_to_existing_mods);
-            let offset = last.syntax().text_range().end();
-            let indent = IndentLevel::from_node(last.syntax());
-            mod_decl_builder.insert(offset, format!("\n{indent}{mod_decl}"));
-            pub_mod_decl_builder.insert(offset, format!("\n{indent}{pub_mod_decl}"));
+    // The declaration goes after the existing ones (after the first group of them, rather), or
+    // first in the module.
+    let add_mod_decl = |visibility: Option<ast::Visibility>| {
+        let mod_decl = make::mod_(make::name(new_mod_name), None).clone_for_update();
+        mod_decl.set_visibility(visibility.map(|it| it.clone_for_update()));
+        let mut builder = SourceChangeBuilder::new(parent_file_id);
+        match &source {
+            ModuleSource::SourceFile(it) => add_item_to_module(
+                &builder.make_mut(it.clone()),
+                mod_decl.clone().into(),
+                ItemPlacement::Conventional,
+            ),
+            ModuleSource::Module(it) => add_item_to_module(
+                &builder.make_mut(it.item_list()?),
+                mod_decl.clone().into(),
+                ItemPlacement::Conventional,
+            ),
+            ModuleSource::BlockExpr(_) => return None,
         }
-        None => {
-            // Prepend before the first item in the file.
-            match items.next() {
-                Some(first) => {
-                    cov_mark::hit!(unlinked_file_prepend_before_first_item);
-                    let offset = first.syntax().text_range().start();
-                    let indent = IndentLevel::from_node(first.syntax());
-                    mod_decl_builder.insert(offset, format!("{mod_decl}\n\n{indent}"));
-                    pub_mod_decl_builder.insert(offset, format!("{pub_mod_decl}\n\n{indent}"));
-                }
-                None => {
-                    // No items in the file, so just append at the end.
-                    cov_mark::hit!(unlinked_file_empty_file);
-                    let mut indent = IndentLevel::from(0);
-                    let offset = match &source {
-                        ModuleSource::SourceFile(it) => it.syntax().text_range().end(),
-                        ModuleSource::Module(it) => {
-                            indent = IndentLevel::from_node(it.syntax()) + 1;
-                            it.item_list()?.r_curly_token()?.text_range().start()
-                        }
-                        ModuleSource::BlockExpr(it) => {
-                            it.stmt_list()?.r_curly_token()?.text_range().start()
-                        }
-                    };
-                    mod_decl_builder.insert(offset, format!("{indent}{mod_decl}\n"));
-                    pub_mod_decl_builder.insert(offset, format!("{indent}{pub_mod_decl}\n"));
-                }
-            }
-        }
-    }
+        Some((mod_decl.to_string(), builder.finish()))
+    };
+    let (mod_decl, mod_decl_change) = add_mod_decl(None)?;
+    let (pub_mod_decl, pub_mod_decl_change) = add_mod_decl(Some(make::visibility_pub()))?;
 
     let trigger_range = db.parse(added_file_id).tree().syntax().text_range();
     Some(vec![
-        fix(
-            "add_mod_declaration",
-            &format!("Insert `{mod_decl}`"),
-            SourceChange::from_text_edit(parent_file_id, mod_decl_builder.finish()),
-            trigger_range,
-        ),
+        fix("add_mod_declaration", &format!("Insert `{mod_decl}`"), mod_decl_change, trigger_range),
         fix(
             "add_pub_mod_declaration",
             &format!("Insert `{pub_mod_decl}`"),
-            SourceChange::from_text_edit(parent_file_id, pub_mod_decl_builder.finish()),
+            pub_mod_decl_change,
             trigger_range,
         ),
     ])
@@ -261,7 +230,7 @@ mod tests {
 
     #[test]
     fn unlinked_file_prepend_first_item() {
-        cov_mark::check!(unlinked_file_prepend_before_first_item);
+        cov_mark::check!(add_item_before_anchor);
         // Only tests the first one for `pub mod` since the rest are the same
         check_fixes(
             r#"
@@ -287,7 +256,7 @@ fn f() {}
 
     #[test]
     fn unlinked_file_append_mod() {
-        cov_mark::check!(unlinked_file_append_to_existing_mods);
+        cov_mark::check!(add_item_after_anchor);
         check_fix(
             r#"
 //- /main.rs
@@ -320,7 +289,7 @@ fn unlinked_file_append_mod() {
 
     #[test]
     fn unlinked_file_insert_in_empty_file() {
-        cov_mark::check!(unlinked_file_empty_file);
+        cov_mark::check!(add_item_to_empty_module);
         check_fix(
             r#"
 //- /main.rs
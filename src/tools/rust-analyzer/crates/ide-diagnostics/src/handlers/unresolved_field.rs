@@ -131,6 +131,7 @@ fn add_variant_to_union(
         target: error_range.range,
         source_change: Some(src_change_builder.finish()),
         trigger_signature_help: false,
+        parameter_prompt: None,
     })
 }
 
@@ -174,6 +175,7 @@ fn add_field_to_struct_fix(
                 target: error_range.range,
                 source_change: Some(src_change_builder.finish()),
                 trigger_signature_help: false,
+                parameter_prompt: None,
             })
         }
         None => {
@@ -205,6 +207,7 @@ fn add_field_to_struct_fix(
                 target: error_range.range,
                 source_change: Some(src_change_builder.finish()),
                 trigger_signature_help: false,
+                parameter_prompt: None,
             })
         }
         Some(FieldList::TupleFieldList(_tuple)) => {
@@ -267,6 +270,7 @@ fn method_fix(
             TextEdit::insert(range.end(), "()".to_owned()),
         )),
         trigger_signature_help: false,
+        parameter_prompt: None,
     })
 }
 #[cfg(test)]
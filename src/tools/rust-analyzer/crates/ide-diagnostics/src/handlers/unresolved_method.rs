@@ -109,6 +109,7 @@ fn field_fix(
             (file_id, TextEdit::insert(range.end(), ")".to_owned())),
         ])),
         trigger_signature_help: false,
+        parameter_prompt: None,
     })
 }
 
@@ -194,6 +195,7 @@ fn assoc_func_fix(ctx: &DiagnosticsContext<'_>, d: &hir::UnresolvedMethodCall) -
                 TextEdit::replace(range, assoc_func_call_expr_string),
             )),
             trigger_signature_help: false,
+            parameter_prompt: None,
         })
     } else {
         None
@@ -74,6 +74,7 @@ fn fixes(
             TextEdit::replace(name_range, format!("_{}", var_name.display(db))),
         )),
         trigger_signature_help: false,
+        parameter_prompt: None,
     }])
 }
 
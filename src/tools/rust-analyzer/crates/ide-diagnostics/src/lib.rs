@@ -68,8 +68,18 @@ mod handlers {
     pub(crate) mod unused_variables;
 
     // The handlers below are unusual, the implement the diagnostics as well.
+    pub(crate) mod enum_variant_name_stutter;
     pub(crate) mod field_shorthand;
+    pub(crate) mod iter_overeager_collect_between_adapters;
+    pub(crate) mod iterator_returned_without_fuse_in_public_api;
+    pub(crate) mod join_handle_unwrap_discarded_error;
     pub(crate) mod json_is_not_rust;
+    pub(crate) mod let_underscore_must_use;
+    pub(crate) mod lock_acquired_twice_in_scope;
+    pub(crate) mod nested_result_option_return_type;
+    pub(crate) mod public_api_type_leaks_private_dependency;
+    pub(crate) mod todo_or_unimplemented_in_released_code;
+    pub(crate) mod u64_as_usize_index_on_32bit_targets;
     pub(crate) mod unlinked_file;
     pub(crate) mod useless_braces;
 }
@@ -77,7 +87,8 @@ mod handlers {
 #[cfg(test)]
 mod tests;
 
-use hir::{diagnostics::AnyDiagnostic, InFile, Semantics};
+use cfg::{CfgAtom, CfgExpr};
+use hir::{diagnostics::AnyDiagnostic, HasAttrs as _, InFile, Semantics};
 use ide_db::{
     assists::{Assist, AssistId, AssistKind, AssistResolveStrategy},
     base_db::{FileId, FileRange, SourceDatabase},
@@ -228,16 +239,28 @@ pub struct DiagnosticsConfig {
     pub disabled: FxHashSet<String>,
     pub expr_fill_default: ExprFillDefaultMode,
     pub style_lints: bool,
+    /// Whether lints flagging code that is only unwanted in some code bases are enabled.
+    pub restriction_lints: bool,
+    /// How many variants of an enum need to repeat its name before they are reported.
+    pub enum_variant_name_stutter_threshold: usize,
+    /// Severity of `todo-or-unimplemented-in-released-code`, which is off with `Severity::Allow`.
+    pub released_code_severity: Severity,
+    /// Names of the macros `todo-or-unimplemented-in-released-code` reports.
+    pub released_code_macros: Vec<&'static str>,
+    /// How deep `Result`s and `Option`s may be nested in a return type before it is reported.
+    pub nested_result_option_max_depth: usize,
     // FIXME: We may want to include a whole `AssistConfig` here
     pub insert_use: InsertUseConfig,
     pub prefer_no_std: bool,
     pub prefer_prelude: bool,
+    /// Dependencies whose types may appear in the public API of a library.
+    pub public_dependencies: Vec<String>,
 }
 
 impl DiagnosticsConfig {
     pub fn test_sample() -> Self {
         use hir::PrefixKind;
-        use ide_db::imports::insert_use::ImportGranularity;
+        use ide_db::imports::insert_use::{ImportGranularity, ImportGroupStyle};
 
         Self {
             enabled: true,
@@ -247,15 +270,22 @@ pub fn test_sample() -> Self {
             disabled: Default::default(),
             expr_fill_default: Default::default(),
             style_lints: true,
+            restriction_lints: true,
+            enum_variant_name_stutter_threshold: 1,
+            released_code_severity: Severity::Allow,
+            released_code_macros: vec!["dbg", "todo", "unimplemented"],
+            nested_result_option_max_depth: 2,
             insert_use: InsertUseConfig {
                 granularity: ImportGranularity::Preserve,
                 enforce_granularity: false,
                 prefix_kind: PrefixKind::Plain,
                 group: false,
+                group_style: ImportGroupStyle::Default,
                 skip_glob_imports: false,
             },
             prefer_no_std: false,
             prefer_prelude: true,
+            public_dependencies: Vec::new(),
         }
     }
 }
@@ -311,10 +341,41 @@ pub fn diagnostics(
 
     let parse = sema.parse(file_id);
 
+    let mut public_reexports = FxHashMap::default();
     for node in parse.syntax().descendants() {
         handlers::useless_braces::useless_braces(&mut res, file_id, &node);
+        handlers::enum_variant_name_stutter::enum_variant_name_stutter(
+            &sema, &mut res, file_id, &node, config, resolve,
+        );
         handlers::field_shorthand::field_shorthand(&mut res, file_id, &node);
         handlers::json_is_not_rust::json_in_items(&sema, &mut res, file_id, &node, config);
+        handlers::join_handle_unwrap_discarded_error::join_handle_unwrap_discarded_error(
+            &sema, &mut res, file_id, &node, config,
+        );
+        handlers::iter_overeager_collect_between_adapters::iter_overeager_collect_between_adapters(
+            &sema, &mut res, file_id, &node, config,
+        );
+        handlers::iterator_returned_without_fuse_in_public_api::iterator_returned_without_fuse_in_public_api(
+            &sema, &mut res, file_id, &node, config,
+        );
+        handlers::let_underscore_must_use::let_underscore_must_use(
+            &sema, &mut res, file_id, &node, config,
+        );
+        handlers::lock_acquired_twice_in_scope::lock_acquired_twice_in_scope(
+            &sema, &mut res, file_id, &node, config,
+        );
+        handlers::nested_result_option_return_type::nested_result_option_return_type(
+            &sema, &mut res, file_id, &node, config,
+        );
+        handlers::public_api_type_leaks_private_dependency::public_api_type_leaks_private_dependency(
+            &sema, &mut res, file_id, &node, config, &mut public_reexports,
+        );
+        handlers::todo_or_unimplemented_in_released_code::todo_or_unimplemented_in_released_code(
+            &sema, &mut res, file_id, &node, config,
+        );
+        handlers::u64_as_usize_index_on_32bit_targets::u64_as_usize_index_on_32bit_targets(
+            &sema, &mut res, file_id, &node,
+        );
     }
 
     let module = sema.file_to_module_def(file_id);
@@ -601,6 +662,38 @@ fn unresolved_fix(id: &'static str, label: &str, target: TextRange) -> Assist {
         target,
         source_change: None,
         trigger_signature_help: false,
+        parameter_prompt: None,
+    }
+}
+
+/// Whether `node` is part of a `#[test]` function or of an item that is only compiled for tests,
+/// like with `#[cfg(test)]` or `#[cfg(all(test, unix))]`, but not `#[cfg(any(test, unix))]`.
+fn is_test_code(sema: &Semantics<'_, RootDatabase>, node: &SyntaxNode) -> bool {
+    let db = sema.db;
+    node.ancestors().filter_map(ast::Item::cast).any(|item| {
+        let attrs = match item {
+            ast::Item::Fn(it) => match sema.to_def(&it) {
+                Some(func) if func.is_test(db) => return true,
+                func => func.map(|it| it.attrs(db)),
+            },
+            ast::Item::Module(it) => sema.to_def(&it).map(|it| it.attrs(db)),
+            ast::Item::Impl(it) => sema.to_def(&it).map(|it| it.attrs(db)),
+            ast::Item::Trait(it) => sema.to_def(&it).map(|it| it.attrs(db)),
+            ast::Item::Const(it) => sema.to_def(&it).map(|it| it.attrs(db)),
+            ast::Item::Static(it) => sema.to_def(&it).map(|it| it.attrs(db)),
+            _ => None,
+        };
+        attrs.is_some_and(|attrs| attrs.cfgs().any(|cfg| implies_test(&cfg)))
+    })
+}
+
+/// Whether `cfg` can only be enabled together with `test`.
+fn implies_test(cfg: &CfgExpr) -> bool {
+    match cfg {
+        CfgExpr::Atom(CfgAtom::Flag(flag)) => flag == "test",
+        CfgExpr::All(cfgs) => cfgs.iter().any(implies_test),
+        CfgExpr::Any(cfgs) => !cfgs.is_empty() && cfgs.iter().all(implies_test),
+        CfgExpr::Atom(CfgAtom::KeyValue { .. }) | CfgExpr::Invalid | CfgExpr::Not(_) => false,
     }
 }
 
@@ -34,6 +34,8 @@ pub struct HoverConfig {
     pub format: HoverDocFormat,
     pub max_trait_assoc_items_count: Option<usize>,
     pub max_struct_field_count: Option<usize>,
+    /// Whether to show what the hovered `?`, `.await`, `for` or operator desugars to.
+    pub desugaring: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -354,6 +356,20 @@ fn hover_simple(
             Some(res)
         });
 
+    // append what `?`, `.await`, `for` loops and overloaded operators desugar to
+    let desugaring = config
+        .desugaring
+        .then(|| descended().find_map(|token| render::desugaring(sema, token)))
+        .flatten();
+    let result = match (result, desugaring) {
+        (Some(mut res), Some(desugaring)) => {
+            res.actions.extend(desugaring.actions);
+            res.markup = Markup::from(format!("{}\n---\n{}", res.markup, desugaring.markup));
+            Some(res)
+        }
+        (result, desugaring) => result.or(desugaring),
+    };
+
     result.map(|mut res: HoverResult| {
         res.actions = dedupe_or_merge_hover_actions(res.actions);
         RangeInfo::new(original_token.text_range(), res)
@@ -26,8 +26,9 @@
 use crate::{
     doc_links::{remove_links, rewrite_links},
     hover::{notable_traits, walk_and_push_ty},
-    HoverAction, HoverConfig, HoverResult, Markup, MemoryLayoutHoverConfig,
-    MemoryLayoutHoverRenderKind,
+    navigation_target::UpmappingResult,
+    FilePosition, HoverAction, HoverConfig, HoverResult, Markup, MemoryLayoutHoverConfig,
+    MemoryLayoutHoverRenderKind, TryToNav,
 };
 
 pub(super) fn type_info_of(
@@ -204,6 +205,138 @@ pub(super) fn deref_expr(
     Some(res)
 }
 
+/// Renders what `?`, `.await`, a `for` loop or an overloaded operator desugars to, together with
+/// the trait method the desugaring calls.
+pub(super) fn desugaring(
+    sema: &Semantics<'_, RootDatabase>,
+    token: &SyntaxToken,
+) -> Option<HoverResult> {
+    let db = sema.db;
+    let parent = token.parent()?;
+    let (desugared, func) = match_ast! {
+        match parent {
+            ast::TryExpr(it) => {
+                if token.kind() != T![?] {
+                    return None;
+                }
+                let func = sema.resolve_try_expr(&it)?;
+                let desugared = format!(
+                    "match Try::branch({}) {{\n    ControlFlow::Continue(val) => val,\n    ControlFlow::Break(residual) => return FromResidual::from_residual(residual),\n}}",
+                    it.expr()?
+                );
+                (desugared, func)
+            },
+            ast::AwaitExpr(it) => {
+                if token.kind() != T![await] {
+                    return None;
+                }
+                let func = sema.resolve_await_to_poll(&it)?;
+                let desugared = format!(
+                    "match IntoFuture::into_future({}) {{\n    mut pinned => loop {{\n        match Future::poll(unsafe {{ Pin::new_unchecked(&mut pinned) }}, cx) {{\n            Poll::Ready(result) => break result,\n            Poll::Pending => yield (),\n        }}\n    }},\n}}",
+                    it.expr()?
+                );
+                (desugared, func)
+            },
+            ast::ForExpr(it) => {
+                if token.kind() != T![for] {
+                    return None;
+                }
+                let func = sema.resolve_for_loop_into_iter(&it)?;
+                let desugared = format!(
+                    "match IntoIterator::into_iter({}) {{\n    mut iter => loop {{\n        match Iterator::next(&mut iter) {{\n            Some({}) => {{ /* loop body */ }}\n            None => break,\n        }}\n    }},\n}}",
+                    it.iterable()?,
+                    it.pat()?
+                );
+                (desugared, func)
+            },
+            ast::BinExpr(it) => {
+                let (op_token, op_kind) = it.op_details()?;
+                if op_token != *token {
+                    return None;
+                }
+                let func = sema.resolve_bin_expr(&it)?;
+                let (lhs, rhs) = (it.lhs()?, it.rhs()?);
+                let args = match op_kind {
+                    ast::BinaryOp::CmpOp(_) => format!("&{lhs}, &{rhs}"),
+                    ast::BinaryOp::Assignment { op: Some(_) } => format!("&mut {lhs}, {rhs}"),
+                    _ => format!("{lhs}, {rhs}"),
+                };
+                let desugared = format!("{}({args})", trait_method_path(db, func)?);
+                (desugared, func)
+            },
+            ast::PrefixExpr(it) => {
+                if it.op_token()? != *token {
+                    return None;
+                }
+                let func = sema.resolve_prefix_expr(&it)?;
+                let expr = it.expr()?;
+                let path = trait_method_path(db, func)?;
+                let desugared = match it.op_kind()? {
+                    ast::UnaryOp::Deref => {
+                        // dereferencing references and raw pointers is built into the language
+                        let ty = sema.type_of_expr(&expr)?.original;
+                        if ty.is_reference() || ty.is_raw_ptr() {
+                            return None;
+                        }
+                        format!("*{path}({}{expr})", receiver_ref(db, func))
+                    }
+                    ast::UnaryOp::Not | ast::UnaryOp::Neg => format!("{path}({expr})"),
+                };
+                (desugared, func)
+            },
+            ast::IndexExpr(it) => {
+                if !matches!(token.kind(), T!['['] | T![']']) {
+                    return None;
+                }
+                let func = sema.resolve_index_expr(&it)?;
+                let desugared = format!(
+                    "*{}({}{}, {})",
+                    trait_method_path(db, func)?,
+                    receiver_ref(db, func),
+                    it.base()?,
+                    it.index()?
+                );
+                (desugared, func)
+            },
+            _ => return None,
+        }
+    };
+
+    let mut res = HoverResult::default();
+    let mut markup = format!("```rust\n{desugared}\n```\nCalls `{}`", trait_method_path(db, func)?);
+    if let Some(hir::AssocItemContainer::Impl(impl_)) =
+        func.as_assoc_item(db).map(|it| it.container(db))
+    {
+        let trait_ = impl_.trait_(db)?;
+        let impl_label =
+            format!("impl {} for {}", trait_.name(db).display(db), impl_.self_ty(db).display(db));
+        format_to!(markup, " of `{impl_label}`");
+        // Lists the implementations of the trait method, among which the one called here.
+        if let Some(nav) = func.try_to_nav(db).map(UpmappingResult::call_site) {
+            res.actions.push(HoverAction::Implementation(FilePosition {
+                file_id: nav.file_id,
+                offset: nav.focus_or_full_range().start(),
+            }));
+        }
+    }
+    res.markup = markup.into();
+    Some(res)
+}
+
+/// `Trait::method` of an operator trait method, whether `func` is the trait's declaration or the
+/// implementation of it.
+fn trait_method_path(db: &RootDatabase, func: hir::Function) -> Option<String> {
+    let trait_ = func.as_assoc_item(db)?.container_or_implemented_trait(db)?;
+    Some(format!("{}::{}", trait_.name(db).display(db), func.name(db).display(db)))
+}
+
+fn receiver_ref(db: &RootDatabase, func: hir::Function) -> &'static str {
+    match func.self_param(db).map(|it| it.access(db)) {
+        Some(hir::Access::Exclusive) => "&mut ",
+        _ => "&",
+    }
+}
+
 pub(super) fn underscore(
     sema: &Semantics<'_, RootDatabase>,
     config: &HoverConfig,
@@ -19,6 +19,7 @@
     keywords: true,
     max_trait_assoc_items_count: None,
     max_struct_field_count: None,
+    desugaring: false,
 };
 
 fn check_hover_no_result(ra_fixture: &str) {
@@ -94,6 +95,22 @@ fn check_assoc_count(count: usize, ra_fixture: &str, expect: Expect) {
     expect.assert_eq(&actual)
 }
 
+#[track_caller]
+fn check_hover_desugaring(ra_fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(ra_fixture);
+    let hover = analysis
+        .hover(
+            &HoverConfig { desugaring: true, ..HOVER_BASE_CONFIG },
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
+        )
+        .unwrap()
+        .unwrap();
+
+    // the desugaring is always the last section of the hover
+    let desugaring = hover.info.markup.as_str().rsplit("\n---\n").next().unwrap();
+    expect.assert_eq(&format!("{desugaring}\n"))
+}
+
 fn check_hover_no_links(ra_fixture: &str, expect: Expect) {
     let (analysis, position) = fixture::position(ra_fixture);
     let hover = analysis
@@ -452,8 +469,8 @@ fn main() {
                                 file_id: FileId(
                                     1,
                                 ),
-                                full_range: 632..867,
-                                focus_range: 693..699,
+                                full_range: 633..868,
+                                focus_range: 694..700,
                                 name: "FnOnce",
                                 kind: Trait,
                                 container_name: "function",
@@ -6292,6 +6309,142 @@ fn foo() -> NotResult<(), Short> {
     );
 }
 
+#[test]
+fn hover_desugaring_try() {
+    check_hover_desugaring(
+        r#"
+//- minicore: try, option
+fn foo(x: Option<i32>) -> Option<i32> {
+    let y = x?$0;
+    Some(y)
+}
+"#,
+        expect![[r#"
+            ```rust
+            match Try::branch(x) {
+                ControlFlow::Continue(val) => val,
+                ControlFlow::Break(residual) => return FromResidual::from_residual(residual),
+            }
+            ```
+            Calls `Try::branch` of `impl Try for Option<T>`
+        "#]],
+    );
+}
+
+#[test]
+fn hover_desugaring_for_loop() {
+    check_hover_desugaring(
+        r#"
+//- minicore: iterator
+fn foo() {
+    f$0or x in [1, 2, 3] {}
+}
+"#,
+        expect![[r#"
+            ```rust
+            match IntoIterator::into_iter([1, 2, 3]) {
+                mut iter => loop {
+                    match Iterator::next(&mut iter) {
+                        Some(x) => { /* loop body */ }
+                        None => break,
+                    }
+                },
+            }
+            ```
+            Calls `IntoIterator::into_iter` of `impl IntoIterator for [T; N]`
+        "#]],
+    );
+}
+
+#[test]
+fn hover_desugaring_overloaded_operator() {
+    check_hover_desugaring(
+        r#"
+//- minicore: add
+struct Meters(u32);
+impl core::ops::Add for Meters {
+    type Output = Meters;
+    fn add(self, rhs: Meters) -> Meters {
+        Meters(self.0 + rhs.0)
+    }
+}
+fn foo(a: Meters, b: Meters) -> Meters {
+    a +$0 b
+}
+"#,
+        expect![[r#"
+            ```rust
+            Add::add(a, b)
+            ```
+            Calls `Add::add` of `impl Add for Meters`
+        "#]],
+    );
+}
+
+#[test]
+fn hover_desugaring_links_the_called_impl() {
+    let (analysis, position) = fixture::position(
+        r#"
+//- minicore: add
+struct Meters(u32);
+impl core::ops::Add for Meters {
+    type Output = Meters;
+    fn add(self, rhs: Meters) -> Meters {
+        Meters(self.0 + rhs.0)
+    }
+}
+fn foo(a: Meters, b: Meters) -> Meters {
+    a +$0 b
+}
+"#,
+    );
+    let hover = analysis
+        .hover(
+            &HoverConfig { desugaring: true, ..HOVER_BASE_CONFIG },
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) },
+        )
+        .unwrap()
+        .unwrap();
+    expect![[r#"
+        [
+            Reference(
+                FilePosition {
+                    file_id: FileId(
+                        0,
+                    ),
+                    offset: 86,
+                },
+            ),
+            Implementation(
+                FilePosition {
+                    file_id: FileId(
+                        0,
+                    ),
+                    offset: 86,
+                },
+            ),
+            GoToType(
+                [
+                    HoverGotoTypeData {
+                        mod_path: "test::Meters",
+                        nav: NavigationTarget {
+                            file_id: FileId(
+                                0,
+                            ),
+                            full_range: 0..19,
+                            focus_range: 7..13,
+                            name: "Meters",
+                            kind: Struct,
+                            description: "struct Meters(u32)",
+                        },
+                    },
+                ],
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&hover.info.actions);
+}
+
 #[test]
 fn hover_try_expr_option() {
     cov_mark::check!(hover_try_expr_opt_opt);
@@ -7864,8 +8017,8 @@ impl Iterator for S {
                                 file_id: FileId(
                                     1,
                                 ),
-                                full_range: 6290..6498,
-                                focus_range: 6355..6361,
+                                full_range: 6291..6499,
+                                focus_range: 6356..6362,
                                 name: "Future",
                                 kind: Trait,
                                 container_name: "future",
@@ -7878,8 +8031,8 @@ impl Iterator for S {
                                 file_id: FileId(
                                     1,
                                 ),
-                                full_range: 7128..7594,
-                                focus_range: 7172..7180,
+                                full_range: 7129..7595,
+                                focus_range: 7173..7181,
                                 name: "Iterator",
                                 kind: Trait,
                                 container_name: "iterator",
@@ -29,6 +29,7 @@
 mod closure_ret;
 mod discriminant;
 mod fn_lifetime_fn;
+mod future_size;
 mod implicit_drop;
 mod implicit_static;
 mod param_name;
@@ -48,6 +49,7 @@ pub struct InlayHintsConfig {
     pub closure_capture_hints: bool,
     pub binding_mode_hints: bool,
     pub implicit_drop_hints: bool,
+    pub future_size_hints: bool,
     pub lifetime_elision_hints: LifetimeElisionHints,
     pub param_names_for_lifetime_elision_hints: bool,
     pub hide_named_constructor_hints: bool,
@@ -124,6 +126,7 @@ pub enum InlayKind {
     ClosingBrace,
     ClosureCapture,
     Discriminant,
+    FutureSize,
     GenericParamList,
     Lifetime,
     Parameter,
@@ -541,6 +544,7 @@ fn hints(
                         closure_ret::hints(hints, famous_defs, config, file_id, it)
                     },
                     ast::Expr::RangeExpr(it) => range_exclusive::hints(hints, config, it),
+                    ast::Expr::BlockExpr(it) => future_size::block_hints(hints, sema, config, &it),
                     _ => None,
                 }
             },
@@ -562,6 +566,7 @@ fn hints(
                 ast::Item::Impl(_) => None,
                 ast::Item::Fn(it) => {
                     implicit_drop::hints(hints, sema, config, &it);
+                    future_size::fn_hints(hints, sema, config, &it);
                     fn_lifetime_fn::hints(hints, config, it)
                 },
                 // static type elisions
@@ -651,6 +656,7 @@ mod tests {
         closing_brace_hints_min_lines: None,
         fields_to_resolve: InlayFieldsToResolve::empty(),
         implicit_drop_hints: false,
+        future_size_hints: false,
         range_exclusive_hints: false,
     };
     pub(super) const TEST_CONFIG: InlayHintsConfig = InlayHintsConfig {
@@ -0,0 +1,102 @@
+//! Implementation of "future size" inlay hints:
+//! ```ignore
+//! async/* size ≤ 16 */ fn foo(x: u64) {}
+//! let fut = async/* size ≤ 1 */ {};
+//! ```
+use hir::Semantics;
+use ide_db::RootDatabase;
+use syntax::{ast, SyntaxToken};
+
+use crate::{InlayHint, InlayHintLabel, InlayHintPosition, InlayHintsConfig, InlayKind};
+
+pub(super) fn fn_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    func: &ast::Fn,
+) -> Option<()> {
+    if !config.future_size_hints {
+        return None;
+    }
+    let async_token = func.async_token()?;
+    let func = sema.to_def(func)?;
+    let layout = func.ret_type(sema.db).layout_upper_bound(sema.db).ok()?;
+    acc.push(inlay_hint(async_token, layout.size()));
+    Some(())
+}
+
+pub(super) fn block_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<'_, RootDatabase>,
+    config: &InlayHintsConfig,
+    block: &ast::BlockExpr,
+) -> Option<()> {
+    if !config.future_size_hints {
+        return None;
+    }
+    let async_token = block.async_token()?;
+    let ty = sema.type_of_expr(&ast::Expr::BlockExpr(block.clone()))?.original;
+    let layout = ty.layout_upper_bound(sema.db).ok()?;
+    acc.push(inlay_hint(async_token, layout.size()));
+    Some(())
+}
+
+fn inlay_hint(async_token: SyntaxToken, size: u64) -> InlayHint {
+    InlayHint {
+        range: async_token.text_range(),
+        position: InlayHintPosition::After,
+        pad_left: true,
+        pad_right: false,
+        kind: InlayKind::FutureSize,
+        label: InlayHintLabel::from(format!("size ≤ {size}")),
+        text_edit: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        inlay_hints::tests::{check_with_config, DISABLED_CONFIG},
+        InlayHintsConfig,
+    };
+
+    #[test]
+    fn async_fn() {
+        check_with_config(
+            InlayHintsConfig { future_size_hints: true, ..DISABLED_CONFIG },
+            r#"
+//- minicore: future
+mod m {
+    async fn foo(x: i64) -> i64 {
+  //^^^^^size ≤ 16
+        x
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn async_blocks() {
+        check_with_config(
+            InlayHintsConfig { future_size_hints: true, ..DISABLED_CONFIG },
+            r#"
+//- minicore: future
+fn foo() {
+    let x = 5i32;
+    let by_ref = async { x };
+               //^^^^^size ≤ 16
+    let by_value = async move { x };
+                 //^^^^^size ≤ 8
+    let held = async {
+             //^^^^^size ≤ 16
+        let y = 5i64;
+        async {}.await;
+      //^^^^^size ≤ 1
+        y
+    };
+}
+"#,
+        );
+    }
+}
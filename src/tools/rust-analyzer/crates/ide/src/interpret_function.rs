@@ -8,6 +8,10 @@
 
 // Feature: Interpret Function
 //
+// Evaluates the function at the cursor with the MIR interpreter, without building the crate, and
+// shows the value it returns or where the evaluation failed. The arguments of the function are
+// created with `Default::default()`.
+//
 // |===
 // | Editor  | Action Name
 //
@@ -48,3 +52,71 @@ fn find_and_interpret(db: &RootDatabase, position: FilePosition) -> Option<Strin
     };
     Some(def.eval(db, span_formatter))
 }
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{expect, Expect};
+
+    use crate::fixture;
+
+    use super::find_and_interpret;
+
+    fn check(ra_fixture: &str, expect: Expect) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let result = find_and_interpret(&analysis.db, position);
+        expect.assert_eq(result.as_deref().unwrap_or("Not inside a function body"));
+    }
+
+    #[test]
+    fn not_in_function() {
+        check(
+            r#"
+struct S$0;
+"#,
+            expect!["Not inside a function body"],
+        );
+    }
+
+    #[test]
+    fn unit_result() {
+        check(
+            r#"
+fn main() {
+    let x = 2$0;
+}
+"#,
+            expect!["pass"],
+        );
+    }
+
+    #[test]
+    fn returned_value() {
+        check(
+            r#"
+fn answer() -> i32 {
+    6 * 7$0
+}
+"#,
+            expect![[r#"
+                pass
+                --------- result ---------
+                42"#]],
+        );
+    }
+
+    #[test]
+    fn default_arguments() {
+        check(
+            r#"
+//- minicore: default, builtin_impls
+fn twice(x: i32, y: u8) -> i32 {
+    x + 2 * y as i32$0
+}
+"#,
+            expect![[r#"
+                pass
+                --------- result ---------
+                0"#]],
+        );
+    }
+}
@@ -113,7 +113,8 @@
 };
 pub use hir::Semantics;
 pub use ide_assists::{
-    Assist, AssistConfig, AssistId, AssistKind, AssistResolveStrategy, SingleResolve,
+    Assist, AssistConfig, AssistId, AssistKind, AssistResolveStrategy, ExtractModuleVisibility,
+    MatchArmFields, ModuleFileLayout, SingleResolve,
 };
 pub use ide_completion::{
     CallableSnippets, CompletionConfig, CompletionItem, CompletionItemKind, CompletionRelevance,
@@ -129,7 +130,7 @@
     line_index::{LineCol, LineIndex},
     prime_caches::ParallelPrimeCachesProgress,
     search::{ReferenceCategory, SearchScope},
-    source_change::{FileSystemEdit, SnippetEdit, SourceChange},
+    source_change::{ChangeAnnotation, FileSystemEdit, SnippetEdit, SourceChange},
     symbol_index::Query,
     RootDatabase, SymbolKind,
 };
@@ -179,6 +180,11 @@ pub fn update_lru_capacities(&mut self, lru_capacities: &FxHashMap<Box<str>, usi
         self.db.update_lru_capacities(lru_capacities);
     }
 
+    /// Evicts the less recently used entries of the LRU caches to release memory.
+    pub fn trim_lru_caches(&mut self) {
+        self.db.trim_lru_caches();
+    }
+
     /// Returns a snapshot of the current state, which you can query for
     /// semantic information.
     pub fn analysis(&self) -> Analysis {
@@ -277,11 +283,19 @@ pub fn source_root(&self, file_id: FileId) -> Cancellable<SourceRootId> {
         self.with_db(|db| db.file_source_root(file_id))
     }
 
-    pub fn parallel_prime_caches<F>(&self, num_worker_threads: u8, cb: F) -> Cancellable<()>
+    pub fn parallel_prime_caches<F, Y>(
+        &self,
+        num_worker_threads: u8,
+        cb: F,
+        should_yield: Y,
+    ) -> Cancellable<()>
     where
         F: Fn(ParallelPrimeCachesProgress) + Sync + std::panic::UnwindSafe,
+        Y: Fn() -> bool + std::panic::UnwindSafe,
     {
-        self.with_db(move |db| prime_caches::parallel_prime_caches(db, num_worker_threads, &cb))
+        self.with_db(move |db| {
+            prime_caches::parallel_prime_caches(db, num_worker_threads, &cb, &should_yield)
+        })
     }
 
     /// Gets the text of the source file.
@@ -696,11 +710,14 @@ pub fn assists_with_fixes(
                 Vec::new()
             };
             let ssr_assists = ssr::ssr_assists(db, &resolve, frange);
+            let bulk_assists =
+                ide_assists::assists_in_file(db, assist_config, &resolve, frange.file_id);
             let assists = ide_assists::assists(db, assist_config, resolve, frange);
 
             let mut res = diagnostic_assists;
             res.extend(ssr_assists);
             res.extend(assists);
+            res.extend(bulk_assists);
 
             res
         })
@@ -566,6 +566,27 @@ pub struct St {
         );
     }
 
+    #[test]
+    fn moniker_for_reexported_item() {
+        check_moniker(
+            r#"
+//- /lib.rs crate:main deps:foo
+use foo::func;
+fn main() {
+    func$0();
+}
+//- /foo/lib.rs crate:foo@0.1.0,https://a.b/foo.git library
+mod module {
+    pub fn func() {}
+}
+pub use module::func;
+"#,
+            "foo::module::func",
+            r#"PackageInformation { name: "foo", repo: Some("https://a.b/foo.git"), version: Some("0.1.0") }"#,
+            MonikerKind::Import,
+        );
+    }
+
     #[test]
     fn no_moniker_for_local() {
         no_moniker(
@@ -46,6 +46,7 @@ pub(crate) fn ssr_assists(
             target: comment_range,
             source_change,
             trigger_signature_help: false,
+            parameter_prompt: None,
         };
 
         ssr_assists.push(assist);
@@ -141,9 +142,11 @@ fn bar() { 2 }
                         },
                         file_system_edits: [],
                         is_snippet: false,
+                        annotations: [],
                     },
                 ),
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&apply_in_file_assist);
@@ -194,9 +197,11 @@ fn bar() { 2 }
                         },
                         file_system_edits: [],
                         is_snippet: false,
+                        annotations: [],
                     },
                 ),
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&apply_in_workspace_assist);
@@ -237,6 +242,7 @@ fn bar() { 2 }
                 target: 10..21,
                 source_change: None,
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&apply_in_file_assist);
@@ -257,6 +263,7 @@ fn bar() { 2 }
                 target: 10..21,
                 source_change: None,
                 trigger_signature_help: false,
+                parameter_prompt: None,
             }
         "#]]
         .assert_debug_eq(&apply_in_workspace_assist);
@@ -148,6 +148,7 @@ fn add_file(&mut self, file_id: FileId) {
                     closing_brace_hints_min_lines: Some(25),
                     fields_to_resolve: InlayFieldsToResolve::empty(),
                     range_exclusive_hints: false,
+                    future_size_hints: false,
                 },
                 file_id,
                 None,
@@ -168,6 +169,7 @@ fn add_file(&mut self, file_id: FileId) {
             format: crate::HoverDocFormat::Markdown,
             max_trait_assoc_items_count: None,
             max_struct_field_count: None,
+            desugaring: false,
         };
         let tokens = tokens.filter(|token| {
             matches!(
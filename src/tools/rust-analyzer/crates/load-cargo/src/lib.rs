@@ -122,7 +122,7 @@ pub fn load_workspace(
     );
 
     if load_config.prefill_caches {
-        prime_caches::parallel_prime_caches(&db, 1, &|_| ());
+        prime_caches::parallel_prime_caches(&db, 1, &|_| (), &|| false);
     }
     Ok((db, vfs, proc_macro_server.ok()))
 }
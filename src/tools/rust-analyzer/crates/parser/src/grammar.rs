@@ -217,7 +217,7 @@ pub(crate) fn reparser(
         MATCH_ARM_LIST => items::match_arm_list,
         USE_TREE_LIST => items::use_tree_list,
         EXTERN_ITEM_LIST => items::extern_item_list,
-        TOKEN_TREE if first_child? == T!['{'] => items::token_tree,
+        TOKEN_TREE if matches!(first_child?, T!['{'] | T!['('] | T!['[']) => items::token_tree,
         ASSOC_ITEM_LIST => match parent? {
             IMPL | TRAIT => items::assoc_item_list,
             _ => return None,
@@ -176,7 +176,8 @@ pub fn parse(&self, input: &Input, edition: Edition) -> Output {
 pub struct Reparser(fn(&mut parser::Parser<'_>));
 
 impl Reparser {
-    /// If the node is a braced block, return the corresponding `Reparser`.
+    /// If the node is a braced block or a delimited token tree, return the corresponding
+    /// `Reparser`.
     pub fn for_node(
         node: SyntaxKind,
         first_child: Option<SyntaxKind>,
@@ -187,8 +188,8 @@ pub fn for_node(
 
     /// Re-parse given tokens using this `Reparser`.
     ///
-    /// Tokens must start with `{`, end with `}` and form a valid brace
-    /// sequence.
+    /// Tokens must start with an opening delimiter, end with the matching
+    /// closing one and form a valid delimiter sequence.
     pub fn parse(self, tokens: &Input, edition: Edition) -> Output {
         let Reparser(r) = self;
         let mut p = parser::Parser::new(tokens, edition);
@@ -214,6 +214,8 @@ fn code_action_capabilities(client_caps: &ClientCapabilities) -> CodeActionProvi
                     CodeActionKind::REFACTOR_EXTRACT,
                     CodeActionKind::REFACTOR_INLINE,
                     CodeActionKind::REFACTOR_REWRITE,
+                    CodeActionKind::SOURCE,
+                    CodeActionKind::SOURCE_FIX_ALL,
                 ]),
                 resolve_provider: Some(true),
                 work_done_progress_options: Default::default(),
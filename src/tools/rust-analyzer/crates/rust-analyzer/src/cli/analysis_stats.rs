@@ -17,7 +17,7 @@
 use hir_ty::{Interner, Substitution, TyExt, TypeFlags};
 use ide::{
     Analysis, AnalysisHost, AnnotationConfig, DiagnosticsConfig, InlayFieldsToResolve,
-    InlayHintsConfig, LineCol, RootDatabase,
+    InlayHintsConfig, LineCol, RootDatabase, Severity,
 };
 use ide_db::{
     base_db::{
@@ -981,11 +981,18 @@ fn run_ide_things(&self, analysis: Analysis, mut file_ids: Vec<FileId>) {
                         enforce_granularity: true,
                         prefix_kind: hir::PrefixKind::ByCrate,
                         group: true,
+                        group_style: ide_db::imports::insert_use::ImportGroupStyle::Default,
                         skip_glob_imports: true,
                     },
                     prefer_no_std: false,
                     prefer_prelude: true,
                     style_lints: false,
+                    restriction_lints: false,
+                    enum_variant_name_stutter_threshold: 1,
+                    released_code_severity: Severity::Allow,
+                    released_code_macros: Vec::new(),
+                    nested_result_option_max_depth: 2,
+                    public_dependencies: Vec::new(),
                 },
                 ide::AssistResolveStrategy::All,
                 file_id,
@@ -1006,6 +1013,7 @@ fn run_ide_things(&self, analysis: Analysis, mut file_ids: Vec<FileId>) {
                     closure_capture_hints: true,
                     binding_mode_hints: true,
                     implicit_drop_hints: true,
+                    future_size_hints: true,
                     lifetime_elision_hints: ide::LifetimeElisionHints::Always,
                     param_names_for_lifetime_elision_hints: true,
                     hide_named_constructor_hints: false,
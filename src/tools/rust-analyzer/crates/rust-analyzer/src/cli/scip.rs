@@ -3,8 +3,8 @@
 use std::{path::PathBuf, time::Instant};
 
 use ide::{
-    AnalysisHost, LineCol, MonikerDescriptorKind, MonikerResult, StaticIndex, StaticIndexedFile,
-    SymbolInformationKind, TextRange, TokenId,
+    AnalysisHost, FileId, LineCol, MonikerDescriptorKind, MonikerResult, RootDatabase, StaticIndex,
+    StaticIndexedFile, SymbolInformationKind, TextRange, TokenId,
 };
 use ide_db::LineIndexDatabase;
 use load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice};
@@ -68,133 +68,13 @@ pub fn run(self) -> anyhow::Result<()> {
             text_document_encoding: scip_types::TextEncoding::UTF8.into(),
             special_fields: Default::default(),
         };
-        let mut documents = Vec::new();
-
-        let mut symbols_emitted: FxHashSet<TokenId> = FxHashSet::default();
-        let mut tokens_to_symbol: FxHashMap<TokenId, String> = FxHashMap::default();
-        let mut tokens_to_enclosing_symbol: FxHashMap<TokenId, Option<String>> =
-            FxHashMap::default();
-
-        for StaticIndexedFile { file_id, tokens, .. } in si.files {
-            let mut local_count = 0;
-            let mut new_local_symbol = || {
-                let new_symbol = scip::types::Symbol::new_local(local_count);
-                local_count += 1;
-
-                new_symbol
-            };
-
-            let relative_path = match get_relative_filepath(&vfs, &root, file_id) {
-                Some(relative_path) => relative_path,
-                None => continue,
-            };
-
-            let line_index = LineIndex {
-                index: db.line_index(file_id),
-                encoding: PositionEncoding::Utf8,
-                endings: LineEndings::Unix,
-            };
-
-            let mut occurrences = Vec::new();
-            let mut symbols = Vec::new();
-
-            tokens.into_iter().for_each(|(text_range, id)| {
-                let token = si.tokens.get(id).unwrap();
-
-                let range = text_range_to_scip_range(&line_index, text_range);
-                let symbol = tokens_to_symbol
-                    .entry(id)
-                    .or_insert_with(|| {
-                        let symbol = token
-                            .moniker
-                            .as_ref()
-                            .map(moniker_to_symbol)
-                            .unwrap_or_else(&mut new_local_symbol);
-                        scip::symbol::format_symbol(symbol)
-                    })
-                    .clone();
-                let enclosing_symbol = tokens_to_enclosing_symbol
-                    .entry(id)
-                    .or_insert_with(|| {
-                        token
-                            .enclosing_moniker
-                            .as_ref()
-                            .map(moniker_to_symbol)
-                            .map(scip::symbol::format_symbol)
-                    })
-                    .clone();
-
-                let mut symbol_roles = Default::default();
-
-                if let Some(def) = token.definition {
-                    if def.range == text_range {
-                        symbol_roles |= scip_types::SymbolRole::Definition as i32;
-                    }
-
-                    if symbols_emitted.insert(id) {
-                        let documentation = match &token.documentation {
-                            Some(doc) => vec![doc.as_str().to_owned()],
-                            None => vec![],
-                        };
-
-                        let position_encoding =
-                            scip_types::PositionEncoding::UTF8CodeUnitOffsetFromLineStart.into();
-                        let signature_documentation =
-                            token.signature.clone().map(|text| scip_types::Document {
-                                relative_path: relative_path.clone(),
-                                language: "rust".to_owned(),
-                                text,
-                                position_encoding,
-                                ..Default::default()
-                            });
-                        let symbol_info = scip_types::SymbolInformation {
-                            symbol: symbol.clone(),
-                            documentation,
-                            relationships: Vec::new(),
-                            special_fields: Default::default(),
-                            kind: symbol_kind(token.kind).into(),
-                            display_name: token.display_name.clone().unwrap_or_default(),
-                            signature_documentation: signature_documentation.into(),
-                            enclosing_symbol: enclosing_symbol.unwrap_or_default(),
-                        };
-
-                        symbols.push(symbol_info)
-                    }
-                }
-
-                occurrences.push(scip_types::Occurrence {
-                    range,
-                    symbol,
-                    symbol_roles,
-                    override_documentation: Vec::new(),
-                    syntax_kind: Default::default(),
-                    diagnostics: Vec::new(),
-                    special_fields: Default::default(),
-                    enclosing_range: Vec::new(),
-                });
-            });
-
-            if occurrences.is_empty() {
-                continue;
-            }
-
-            let position_encoding =
-                scip_types::PositionEncoding::UTF8CodeUnitOffsetFromLineStart.into();
-            documents.push(scip_types::Document {
-                relative_path,
-                language: "rust".to_owned(),
-                occurrences,
-                symbols,
-                text: String::new(),
-                position_encoding,
-                special_fields: Default::default(),
-            });
-        }
+        let (documents, external_symbols) =
+            index_documents(db, si, |file_id| get_relative_filepath(&vfs, &root, file_id));
 
         let index = scip_types::Index {
             metadata: Some(metadata).into(),
             documents,
-            external_symbols: Vec::new(),
+            external_symbols,
             special_fields: Default::default(),
         };
 
@@ -207,10 +87,157 @@ pub fn run(self) -> anyhow::Result<()> {
     }
 }
 
+/// Builds the documents of the indexed files along with the symbols of the dependency items they
+/// refer to.
+fn index_documents(
+    db: &RootDatabase,
+    si: StaticIndex<'_>,
+    relative_path: impl Fn(FileId) -> Option<String>,
+) -> (Vec<scip_types::Document>, Vec<scip_types::SymbolInformation>) {
+    let mut documents = Vec::new();
+    // Items of dependencies are only described in the index, their definitions are left to
+    // the indexes of the dependencies themselves, which refer to them by the same symbols.
+    let mut external_symbols = Vec::new();
+    let indexed_files: FxHashSet<FileId> = si.files.iter().map(|it| it.file_id).collect();
+
+    let mut symbols_emitted: FxHashSet<TokenId> = FxHashSet::default();
+    let mut tokens_to_symbol: FxHashMap<TokenId, String> = FxHashMap::default();
+    let mut tokens_to_enclosing_symbol: FxHashMap<TokenId, Option<String>> = FxHashMap::default();
+
+    for StaticIndexedFile { file_id, tokens, .. } in si.files {
+        let mut local_count = 0;
+        let mut new_local_symbol = || {
+            let new_symbol = scip::types::Symbol::new_local(local_count);
+            local_count += 1;
+
+            new_symbol
+        };
+
+        let relative_path = match relative_path(file_id) {
+            Some(relative_path) => relative_path,
+            None => continue,
+        };
+
+        let line_index = LineIndex {
+            index: db.line_index(file_id),
+            encoding: PositionEncoding::Utf8,
+            endings: LineEndings::Unix,
+        };
+
+        let mut occurrences = Vec::new();
+        let mut symbols = Vec::new();
+
+        tokens.into_iter().for_each(|(text_range, id)| {
+            let token = si.tokens.get(id).unwrap();
+
+            let range = text_range_to_scip_range(&line_index, text_range);
+            let symbol = tokens_to_symbol
+                .entry(id)
+                .or_insert_with(|| {
+                    let symbol = token
+                        .moniker
+                        .as_ref()
+                        .map(moniker_to_symbol)
+                        .unwrap_or_else(&mut new_local_symbol);
+                    scip::symbol::format_symbol(symbol)
+                })
+                .clone();
+            let enclosing_symbol = tokens_to_enclosing_symbol
+                .entry(id)
+                .or_insert_with(|| {
+                    token
+                        .enclosing_moniker
+                        .as_ref()
+                        .map(moniker_to_symbol)
+                        .map(scip::symbol::format_symbol)
+                })
+                .clone();
+
+            let mut symbol_roles = Default::default();
+
+            if let Some(def) = token.definition {
+                if def.range == text_range {
+                    symbol_roles |= scip_types::SymbolRole::Definition as i32;
+                }
+
+                // Symbols are described by the document defining them, or by the index for
+                // items of dependencies. Local symbols only mean something in their document.
+                let is_external = token.moniker.is_some() && !indexed_files.contains(&def.file_id);
+                if (def.file_id == file_id || is_external) && symbols_emitted.insert(id) {
+                    let documentation = match &token.documentation {
+                        Some(doc) => vec![doc.as_str().to_owned()],
+                        None => vec![],
+                    };
+
+                    let position_encoding =
+                        scip_types::PositionEncoding::UTF8CodeUnitOffsetFromLineStart.into();
+                    // The defining document of an external symbol is not part of this index.
+                    let signature_documentation =
+                        token.signature.clone().map(|text| scip_types::Document {
+                            relative_path: if is_external {
+                                String::new()
+                            } else {
+                                relative_path.clone()
+                            },
+                            language: "rust".to_owned(),
+                            text,
+                            position_encoding,
+                            ..Default::default()
+                        });
+                    let symbol_info = scip_types::SymbolInformation {
+                        symbol: symbol.clone(),
+                        documentation,
+                        relationships: Vec::new(),
+                        special_fields: Default::default(),
+                        kind: symbol_kind(token.kind).into(),
+                        display_name: token.display_name.clone().unwrap_or_default(),
+                        signature_documentation: signature_documentation.into(),
+                        enclosing_symbol: enclosing_symbol.unwrap_or_default(),
+                    };
+
+                    if is_external {
+                        external_symbols.push(symbol_info);
+                    } else {
+                        symbols.push(symbol_info);
+                    }
+                }
+            }
+
+            occurrences.push(scip_types::Occurrence {
+                range,
+                symbol,
+                symbol_roles,
+                override_documentation: Vec::new(),
+                syntax_kind: Default::default(),
+                diagnostics: Vec::new(),
+                special_fields: Default::default(),
+                enclosing_range: Vec::new(),
+            });
+        });
+
+        if occurrences.is_empty() {
+            continue;
+        }
+
+        let position_encoding =
+            scip_types::PositionEncoding::UTF8CodeUnitOffsetFromLineStart.into();
+        documents.push(scip_types::Document {
+            relative_path,
+            language: "rust".to_owned(),
+            occurrences,
+            symbols,
+            text: String::new(),
+            position_encoding,
+            special_fields: Default::default(),
+        });
+    }
+    (documents, external_symbols)
+}
+
 fn get_relative_filepath(
     vfs: &vfs::Vfs,
     rootpath: &vfs::AbsPathBuf,
-    file_id: ide::FileId,
+    file_id: FileId,
 ) -> Option<String> {
     Some(vfs.file_path(file_id).as_path()?.strip_prefix(rootpath)?.as_str().to_owned())
 }
@@ -613,4 +640,48 @@ fn documentation_matches_doc_comment() {
 
         assert_eq!(token.documentation.as_ref().map(|d| d.as_str()), Some("foo"));
     }
+
+    #[test]
+    fn dependency_items_are_described_by_the_index() {
+        let mut host = AnalysisHost::default();
+        let change_fixture = ChangeFixture::parse(
+            r#"
+//- /lib.rs crate:main deps:foo
+use foo::example_mod::func;
+pub fn local() {
+    func();
+    func();
+}
+//- /foo/lib.rs crate:foo@0.1.0,https://a.b/foo.git new_source_root:library
+pub mod example_mod {
+    pub fn func() {}
+}
+"#,
+        );
+        host.raw_database_mut().apply_change(change_fixture.change);
+
+        let analysis = host.analysis();
+        let si = StaticIndex::compute(&analysis);
+        let (documents, external_symbols) =
+            index_documents(host.raw_database(), si, |_| Some("lib.rs".to_owned()));
+
+        let [document] = &documents[..] else { panic!("expected one document {documents:?}") };
+        assert_eq!(document.relative_path, "lib.rs");
+        let symbols: Vec<_> = document.symbols.iter().map(|it| it.symbol.as_str()).collect();
+        assert_eq!(symbols, ["rust-analyzer cargo main . local()."]);
+        let local = &document.symbols[0];
+        assert_eq!(local.signature_documentation.relative_path, "lib.rs");
+
+        let func = "rust-analyzer cargo foo 0.1.0 example_mod/func().";
+        assert!(documents.iter().flat_map(|it| &it.symbols).all(|it| it.symbol != func));
+        let [func] =
+            &external_symbols.iter().filter(|it| it.symbol == func).collect::<Vec<_>>()[..]
+        else {
+            panic!("expected `func` once in {external_symbols:?}")
+        };
+        assert_eq!(func.signature_documentation.text, "pub fn func()");
+        assert!(external_symbols
+            .iter()
+            .all(|it| it.signature_documentation.relative_path.is_empty()));
+    }
 }
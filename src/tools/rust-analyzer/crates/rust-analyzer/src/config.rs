@@ -6,18 +6,20 @@
 //! Of particular interest is the `feature_flags` hash map: while other fields
 //! configure the server itself, feature flags are passed into analysis, and
 //! tweak things like automatic insertion of `()` in completions.
-use std::{fmt, iter, ops::Not};
+use std::{fmt, iter, ops::Not, time::Duration};
 
 use cfg::{CfgAtom, CfgDiff};
 use flycheck::{CargoOptions, FlycheckConfig};
+use hir::db::MacroExpansionLimits;
 use ide::{
     AssistConfig, CallableSnippets, CompletionConfig, DiagnosticsConfig, ExprFillDefaultMode,
-    HighlightConfig, HighlightRelatedConfig, HoverConfig, HoverDocFormat, InlayFieldsToResolve,
-    InlayHintsConfig, JoinLinesConfig, MemoryLayoutHoverConfig, MemoryLayoutHoverRenderKind,
-    Snippet, SnippetScope, SourceRootId,
+    ExtractModuleVisibility, HighlightConfig, HighlightRelatedConfig, HoverConfig, HoverDocFormat,
+    InlayFieldsToResolve, InlayHintsConfig, JoinLinesConfig, MatchArmFields,
+    MemoryLayoutHoverConfig, MemoryLayoutHoverRenderKind, ModuleFileLayout, Severity, Snippet,
+    SnippetScope, SourceRootId,
 };
 use ide_db::{
-    imports::insert_use::{ImportGranularity, InsertUseConfig, PrefixKind},
+    imports::insert_use::{ImportGranularity, ImportGroupStyle, InsertUseConfig, PrefixKind},
     SnippetCap,
 };
 use indexmap::IndexMap;
@@ -72,6 +74,20 @@
         assist_emitMustUse: bool               = false,
         /// Placeholder expression to use for missing expressions in assists.
         assist_expressionFillDefault: ExprFillDefaultDef              = ExprFillDefaultDef::Todo,
+        /// Visibility given by the "Extract Module" assist to the moved items that are used
+        /// outside of the new module.
+        assist_extractModule_visibility: ExtractModuleVisibilityDef   = ExtractModuleVisibilityDef::PubSuper,
+        /// How the "Fill match arms" assist writes the fields of the variants in the arms it adds.
+        assist_fillMatchArms_fields: MatchArmFieldsDef = MatchArmFieldsDef::BindRecordFields,
+        /// Whether to reformat the code generated by assists with rustfmt before applying it.
+        /// Only takes effect for files that are already formatted, and for assists that don't
+        /// produce snippets.
+        assist_formatGeneratedCode_enable: bool = false,
+        /// Whether the "Implement missing members" assists copy the doc comments of the trait items
+        /// into the generated items.
+        assist_implMembers_copyDocs: bool = false,
+        /// The file the "Extract module to file" assist moves a module `foo` into.
+        assist_moveModuleToFile_layout: ModuleFileLayoutDef = ModuleFileLayoutDef::File,
 
         /// Warm up caches on project load.
         cachePriming_enable: bool = true,
@@ -254,12 +270,33 @@
         diagnostics_disabled: FxHashSet<String> = FxHashSet::default(),
         /// Whether to show native rust-analyzer diagnostics.
         diagnostics_enable: bool                = true,
+        /// How many variants of an enum need to repeat the name of the enum before the
+        /// `enum-variant-name-stutter` style lint reports them.
+        diagnostics_enumVariantNameStutter_threshold: usize = 1,
         /// Whether to show experimental rust-analyzer diagnostics that might
         /// have more false positives than usual.
         diagnostics_experimental_enable: bool    = false,
+        /// How deep `Result` and `Option` may be nested in a return type before the
+        /// `nested-result-option-return-type` style lint reports it.
+        diagnostics_nestedResultOption_maxDepth: usize = 2,
+        /// List of dependencies whose types are allowed to appear in the public API of a
+        /// library without being reported by `public-api-type-leaks-private-dependency`.
+        diagnostics_publicDependencies: Vec<String> = vec![],
+        /// Whether `todo-or-unimplemented-in-released-code` reports `dbg!()`.
+        diagnostics_releasedCode_dbg_enable: bool = true,
+        /// Level of the `todo-or-unimplemented-in-released-code` lint, which reports `todo!()`,
+        /// `unimplemented!()` and `dbg!()` outside of test code.
+        diagnostics_releasedCode_level: ReleasedCodeLevelDef = ReleasedCodeLevelDef::Allow,
+        /// Whether `todo-or-unimplemented-in-released-code` reports `todo!()`.
+        diagnostics_releasedCode_todo_enable: bool = true,
+        /// Whether `todo-or-unimplemented-in-released-code` reports `unimplemented!()`.
+        diagnostics_releasedCode_unimplemented_enable: bool = true,
         /// Map of prefixes to be substituted when parsing diagnostic file paths.
         /// This should be the reverse mapping of what is passed to `rustc` as `--remap-path-prefix`.
         diagnostics_remapPrefix: FxHashMap<String, String> = FxHashMap::default(),
+        /// Whether to run additional restriction lints, which flag code that is only unwanted in
+        /// some code bases.
+        diagnostics_restrictionLints_enable: bool = false,
         /// Whether to run additional style lints.
         diagnostics_styleLints_enable: bool =    false,
         /// List of warnings that should be displayed with hint severity.
@@ -297,6 +334,9 @@
         /// `#rust-analyzer.hover.actions.enable#` is set.
         hover_actions_run_enable: bool             = true,
 
+        /// Whether to show what `?`, `.await`, `for` loops and overloaded operators desugar to
+        /// on hover, along with the trait implementation they use.
+        hover_desugaring_enable: bool = false,
         /// Whether to show documentation on hover.
         hover_documentation_enable: bool           = true,
         /// Whether to show keyword hover popups. Only applies when
@@ -361,9 +401,22 @@
 
         /// Number of syntax trees rust-analyzer keeps in memory. Defaults to 128.
         lru_capacity: Option<usize>                 = None,
-        /// Sets the LRU capacity of the specified queries.
+        /// Number of seconds without any activity after which rust-analyzer evicts the least
+        /// recently used syntax trees, macro expansions and item trees from its caches, releasing
+        /// their memory. The default `null`, like `0`, disables trimming.
+        lru_idleTrimDelay: Option<usize>            = None,
+        /// Sets the LRU capacity of the specified queries, e.g. `ParseMacroExpansionQuery` or
+        /// `FileItemTreeQuery`.
         lru_query_capacities: FxHashMap<Box<str>, usize> = FxHashMap::default(),
 
+        /// How deeply macro calls in item position may be nested in each other's expansions before
+        /// rust-analyzer stops expanding them and reports an error at the offending call. Macro calls
+        /// in function bodies follow the crate's `#![recursion_limit]` instead.
+        macroExpansion_depthLimit: usize = 128,
+        /// The maximum number of tokens a single macro call may expand to. Larger expansions are
+        /// discarded and reported as an error.
+        macroExpansion_tokenLimit: usize = 1_048_576,
+
         /// Whether to show `can't find Cargo.toml` error message.
         notifications_cargoTomlNotFound: bool      = true,
 
@@ -436,6 +489,8 @@
         /// Whether to insert closing angle brackets when typing an opening angle bracket of a generic argument list.
         typing_autoClosingAngleBrackets_enable: bool = false,
 
+        /// Whether to load the crates of the open files and their dependencies first, and the rest of the workspace in the background afterwards.
+        workspace_loadIncrementally: bool = false,
         /// Workspace symbol search kind.
         workspace_symbol_search_kind: WorkspaceSymbolSearchKindDef = WorkspaceSymbolSearchKindDef::OnlyTypes,
         /// Limits the number of items returned from a workspace symbol search (Defaults to 128).
@@ -512,6 +567,9 @@
         }"#).unwrap(),
         /// Whether to enable term search based snippets like `Some(foo.bar().baz())`.
         completion_termSearch_enable: bool = false,
+        /// Whether to keep completions whose name only matches the typed identifier once a few
+        /// typos, like swapped characters, are corrected. These are ranked below the other completions.
+        completion_typoTolerance_enable: bool = false,
 
         /// Enables highlighting of related references while the cursor is on `break`, `loop`, `while`, or `for` keywords.
         highlightRelated_breakPoints_enable: bool = true,
@@ -530,12 +588,17 @@
         imports_granularity_group: ImportGranularityDef  = ImportGranularityDef::Crate,
         /// Group inserted imports by the https://rust-analyzer.github.io/manual.html#auto-import[following order]. Groups are separated by newlines.
         imports_group_enable: bool                           = true,
+        /// Which imports are put into the same group when grouping inserted imports.
+        imports_group_style: ImportGroupStyleDef             = ImportGroupStyleDef::Default,
         /// Whether to allow import insertion to merge new imports into single path glob imports like `use std::fmt::*;`.
         imports_merge_glob: bool           = true,
         /// Prefer to unconditionally use imports of the core and alloc crate, over the std crate.
         imports_preferNoStd | imports_prefer_no_std: bool = false,
          /// Whether to prefer import paths containing a `prelude` module.
         imports_preferPrelude: bool                       = false,
+        /// When suggesting imports from other crates, prefer items whose import path through a re-export is
+        /// shorter than the path to the module they are defined in.
+        imports_preferReexports: bool                     = true,
         /// The path structure for newly inserted paths to use.
         imports_prefix: ImportPrefixDef               = ImportPrefixDef::Plain,
 
@@ -563,6 +626,8 @@
         inlayHints_expressionAdjustmentHints_hideOutsideUnsafe: bool = false,
         /// Whether to show inlay hints as postfix ops (`.*` instead of `*`, etc).
         inlayHints_expressionAdjustmentHints_mode: AdjustmentHintsModeDef = AdjustmentHintsModeDef::Prefix,
+        /// Whether to show an upper bound of the size of the future generated for async functions and blocks.
+        inlayHints_futureSizeHints_enable: bool                    = false,
         /// Whether to show implicit drop hints.
         inlayHints_implicitDrops_enable: bool                      = false,
         /// Whether to show inlay type hints for elided lifetimes in function signatures.
@@ -1012,9 +1077,30 @@ pub fn assist(&self, source_root: Option<SourceRootId>) -> AssistConfig {
             prefer_no_std: self.imports_preferNoStd(source_root).to_owned(),
             assist_emit_must_use: self.assist_emitMustUse().to_owned(),
             prefer_prelude: self.imports_preferPrelude(source_root).to_owned(),
+            prefer_reexports: self.imports_preferReexports(source_root).to_owned(),
+            extract_module_visibility: match self.assist_extractModule_visibility() {
+                ExtractModuleVisibilityDef::PubSuper => ExtractModuleVisibility::PubSuper,
+                ExtractModuleVisibilityDef::PubCrate => ExtractModuleVisibility::PubCrate,
+                ExtractModuleVisibilityDef::Pub => ExtractModuleVisibility::Pub,
+                ExtractModuleVisibilityDef::Reexport => ExtractModuleVisibility::Reexport,
+            },
+            match_arm_fields: match self.assist_fillMatchArms_fields() {
+                MatchArmFieldsDef::BindRecordFields => MatchArmFields::BindRecordFields,
+                MatchArmFieldsDef::BindAll => MatchArmFields::BindAll,
+                MatchArmFieldsDef::Ignore => MatchArmFields::Ignore,
+            },
+            impl_members_copy_docs: self.assist_implMembers_copyDocs().to_owned(),
+            module_file_layout: match self.assist_moveModuleToFile_layout() {
+                ModuleFileLayoutDef::File => ModuleFileLayout::File,
+                ModuleFileLayoutDef::ModRs => ModuleFileLayout::ModRs,
+            },
         }
     }
 
+    pub fn assist_format_generated_code(&self) -> bool {
+        *self.assist_formatGeneratedCode_enable()
+    }
+
     pub fn completion(&self, source_root: Option<SourceRootId>) -> CompletionConfig {
         CompletionConfig {
             enable_postfix_completions: self.completion_postfix_enable(source_root).to_owned(),
@@ -1045,6 +1131,7 @@ pub fn completion(&self, source_root: Option<SourceRootId>) -> CompletionConfig
             snippets: self.snippets.clone().to_vec(),
             limit: self.completion_limit(source_root).to_owned(),
             enable_term_search: self.completion_termSearch_enable(source_root).to_owned(),
+            enable_typo_tolerance: self.completion_typoTolerance_enable(source_root).to_owned(),
             prefer_prelude: self.imports_preferPrelude(source_root).to_owned(),
         }
     }
@@ -1064,6 +1151,27 @@ pub fn diagnostics(&self, source_root: Option<SourceRootId>) -> DiagnosticsConfi
             prefer_no_std: self.imports_preferNoStd(source_root).to_owned(),
             prefer_prelude: self.imports_preferPrelude(source_root).to_owned(),
             style_lints: self.diagnostics_styleLints_enable().to_owned(),
+            restriction_lints: self.diagnostics_restrictionLints_enable().to_owned(),
+            enum_variant_name_stutter_threshold: self
+                .diagnostics_enumVariantNameStutter_threshold()
+                .to_owned(),
+            released_code_severity: match self.diagnostics_releasedCode_level() {
+                ReleasedCodeLevelDef::Allow => Severity::Allow,
+                ReleasedCodeLevelDef::Warn => Severity::Warning,
+                ReleasedCodeLevelDef::Deny => Severity::Error,
+            },
+            released_code_macros: [
+                ("dbg", *self.diagnostics_releasedCode_dbg_enable()),
+                ("todo", *self.diagnostics_releasedCode_todo_enable()),
+                ("unimplemented", *self.diagnostics_releasedCode_unimplemented_enable()),
+            ]
+            .into_iter()
+            .filter_map(|(name, enabled)| enabled.then_some(name))
+            .collect(),
+            nested_result_option_max_depth: self
+                .diagnostics_nestedResultOption_maxDepth()
+                .to_owned(),
+            public_dependencies: self.diagnostics_publicDependencies().clone(),
         }
     }
     pub fn expand_proc_attr_macros(&self) -> bool {
@@ -1126,6 +1234,7 @@ pub fn hover(&self) -> HoverConfig {
             keywords: self.hover_documentation_keywords_enable().to_owned(),
             max_trait_assoc_items_count: self.hover_show_traitAssocItems().to_owned(),
             max_struct_field_count: self.hover_show_structFields().to_owned(),
+            desugaring: self.hover_desugaring_enable().to_owned(),
         }
     }
 
@@ -1222,6 +1331,7 @@ pub fn inlay_hints(&self, source_root: Option<SourceRootId>) -> InlayHintsConfig
                 resolve_label_command: client_capability_fields.contains("label.command"),
             },
             implicit_drop_hints: self.inlayHints_implicitDrops_enable(source_root).to_owned(),
+            future_size_hints: self.inlayHints_futureSizeHints_enable(source_root).to_owned(),
             range_exclusive_hints: self
                 .inlayHints_rangeExclusiveHints_enable(source_root)
                 .to_owned(),
@@ -1244,6 +1354,10 @@ fn insert_use_config(&self, source_root: Option<SourceRootId>) -> InsertUseConfi
                 ImportPrefixDef::BySelf => PrefixKind::BySelf,
             },
             group: self.imports_group_enable(source_root).to_owned(),
+            group_style: match self.imports_group_style(source_root) {
+                ImportGroupStyleDef::Default => ImportGroupStyle::Default,
+                ImportGroupStyleDef::StdExternalCrate => ImportGroupStyle::StdExternalCrate,
+            },
             skip_glob_imports: !self.imports_merge_glob(source_root),
         }
     }
@@ -1355,6 +1469,10 @@ pub fn prefill_caches(&self) -> bool {
         self.cachePriming_enable().to_owned()
     }
 
+    pub fn load_incrementally(&self) -> bool {
+        self.workspace_loadIncrementally().to_owned()
+    }
+
     pub fn location_link(&self) -> bool {
         try_or_def!(self.caps.text_document.as_ref()?.definition?.link_support?)
     }
@@ -1467,6 +1585,12 @@ pub fn code_action_group(&self) -> bool {
         self.experimental("codeActionGroup")
     }
 
+    /// Whether the client can ask the user for the parameter of a code action that needs one
+    /// to be resolved.
+    pub fn code_action_parameters(&self) -> bool {
+        self.code_action_resolve() && self.experimental("codeActionParameters")
+    }
+
     pub fn local_docs(&self) -> bool {
         self.experimental("localDocs")
     }
@@ -1525,10 +1649,23 @@ pub fn lru_parse_query_capacity(&self) -> Option<usize> {
         self.lru_capacity().to_owned()
     }
 
+    pub fn lru_idle_trim_delay(&self) -> Option<Duration> {
+        self.lru_idleTrimDelay()
+            .filter(|&secs| secs != 0)
+            .map(|secs| Duration::from_secs(secs as u64))
+    }
+
     pub fn lru_query_capacities_config(&self) -> Option<&FxHashMap<Box<str>, usize>> {
         self.lru_query_capacities().is_empty().not().then(|| self.lru_query_capacities())
     }
 
+    pub fn macro_expansion_limits(&self) -> MacroExpansionLimits {
+        MacroExpansionLimits {
+            depth: *self.macroExpansion_depthLimit(),
+            token_count: *self.macroExpansion_tokenLimit(),
+        }
+    }
+
     pub fn proc_macro_srv(&self) -> Option<AbsPathBuf> {
         let path = self.procMacro_server().clone()?;
         Some(AbsPathBuf::try_from(path).unwrap_or_else(|path| self.root_path.join(path)))
@@ -2090,6 +2227,38 @@ enum ExprFillDefaultDef {
     Default,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum ExtractModuleVisibilityDef {
+    PubSuper,
+    PubCrate,
+    Pub,
+    Reexport,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum MatchArmFieldsDef {
+    BindRecordFields,
+    BindAll,
+    Ignore,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum ModuleFileLayoutDef {
+    File,
+    ModRs,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum ReleasedCodeLevelDef {
+    Allow,
+    Warn,
+    Deny,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 enum ImportGranularityDef {
@@ -2100,6 +2269,13 @@ enum ImportGranularityDef {
     One,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum ImportGroupStyleDef {
+    Default,
+    StdExternalCrate,
+}
+
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
 #[serde(rename_all = "snake_case")]
 enum CallableCompletionDef {
@@ -2708,6 +2884,42 @@ macro_rules! set {
                 "Fill missing expressions with reasonable defaults, `new` or `default` constructors."
             ],
         },
+        "ExtractModuleVisibilityDef" => set! {
+            "type": "string",
+            "enum": ["pub_super", "pub_crate", "pub", "reexport"],
+            "enumDescriptions": [
+                "Make the items `pub(super)`, or `pub(crate)` if they are used outside of the parent module.",
+                "Make the items `pub(crate)`.",
+                "Make the items `pub`.",
+                "Re-export the items next to the new module, so that their usages don't change."
+            ],
+        },
+        "MatchArmFieldsDef" => set! {
+            "type": "string",
+            "enum": ["bind_record_fields", "bind_all", "ignore"],
+            "enumDescriptions": [
+                "Bind the fields of record variants, and match the ones of tuple variants with `_`.",
+                "Bind all fields, naming the ones of tuple variants after their types.",
+                "Ignore the fields with `..`, without expanding nested patterns."
+            ],
+        },
+        "ModuleFileLayoutDef" => set! {
+            "type": "string",
+            "enum": ["file", "mod_rs"],
+            "enumDescriptions": [
+                "Move the module into `foo.rs`.",
+                "Move the module into `foo/mod.rs`."
+            ],
+        },
+        "ReleasedCodeLevelDef" => set! {
+            "type": "string",
+            "enum": ["allow", "warn", "deny"],
+            "enumDescriptions": [
+                "Don't report leftover macros.",
+                "Report leftover macros as warnings.",
+                "Report leftover macros as errors."
+            ],
+        },
         "ImportGranularityDef" => set! {
             "type": "string",
             "enum": ["preserve", "crate", "module", "item", "one"],
@@ -2719,6 +2931,14 @@ macro_rules! set {
                 "Merge all imports into a single use statement as long as they have the same visibility and attributes."
             ],
         },
+        "ImportGroupStyleDef" => set! {
+            "type": "string",
+            "enum": ["default", "std_external_crate"],
+            "enumDescriptions": [
+                "`std` and `core`, other crates, `crate`, `self` and `super` imports are in separate groups.",
+                "`std`, `core` and `alloc`, other crates, and imports of the current crate are in separate groups, like rustfmt's `group_imports = \"StdExternalCrate\"`."
+            ],
+        },
         "ImportPrefixDef" => set! {
             "type": "string",
             "enum": [
@@ -126,6 +126,11 @@ pub(crate) struct GlobalState {
     pub(crate) workspaces: Arc<Vec<ProjectWorkspace>>,
     pub(crate) crate_graph_file_dependencies: FxHashSet<vfs::VfsPath>,
     pub(crate) detached_files: FxHashSet<vfs::AbsPathBuf>,
+    /// Whether only the crates of the open files have been loaded so far, the rest of the
+    /// workspaces being loaded once they have been analyzed, see `workspace.loadIncrementally`.
+    pub(crate) crate_graph_deferred: bool,
+    /// Whether the crates of all of the workspaces have been loaded at least once.
+    pub(crate) full_crate_graph_loaded: bool,
 
     // op queues
     pub(crate) fetch_workspaces_queue:
@@ -134,6 +139,9 @@ pub(crate) struct GlobalState {
         OpQueue<(), (Arc<Vec<ProjectWorkspace>>, Vec<anyhow::Result<WorkspaceBuildScripts>>)>,
     pub(crate) fetch_proc_macros_queue: OpQueue<Vec<ProcMacroPaths>, bool>,
     pub(crate) prime_caches_queue: OpQueue,
+    /// Whether the LRU caches have been trimmed since the last activity, see
+    /// `lru.idleTrimDelay`.
+    pub(crate) lru_caches_trimmed: bool,
 
     /// A deferred task queue.
     ///
@@ -235,11 +243,14 @@ pub(crate) fn new(sender: Sender<lsp_server::Message>, config: Config) -> Global
             workspaces: Arc::from(Vec::new()),
             crate_graph_file_dependencies: FxHashSet::default(),
             detached_files: FxHashSet::default(),
+            crate_graph_deferred: false,
+            full_crate_graph_loaded: false,
             fetch_workspaces_queue: OpQueue::default(),
             fetch_build_data_queue: OpQueue::default(),
             fetch_proc_macros_queue: OpQueue::default(),
 
             prime_caches_queue: OpQueue::default(),
+            lru_caches_trimmed: false,
 
             deferred_task_queue: task_queue,
         };
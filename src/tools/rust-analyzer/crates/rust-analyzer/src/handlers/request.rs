@@ -1236,6 +1236,9 @@ pub(crate) fn handle_code_action(
         frange,
     )?;
     for (index, assist) in assists.into_iter().enumerate() {
+        if assist.parameter_prompt.is_some() && !snap.config.code_action_parameters() {
+            continue;
+        }
         let resolve_data =
             if code_action_resolve_cap { Some((index, params.clone())) } else { None };
         let code_action = to_proto::code_action(&snap, assist, resolve_data)?;
@@ -1294,7 +1297,7 @@ pub(crate) fn handle_code_action_resolve(
         .only
         .map(|it| it.into_iter().filter_map(from_proto::assist_kind).collect());
 
-    let (assist_index, assist_resolve) = match parse_action_id(&params.id) {
+    let (assist_index, mut assist_resolve) = match parse_action_id(&params.id) {
         Ok(parsed_data) => parsed_data,
         Err(e) => {
             return Err(invalid_params_error(format!(
@@ -1305,6 +1308,7 @@ pub(crate) fn handle_code_action_resolve(
         }
     };
 
+    assist_resolve.parameter = params.parameter;
     let expected_assist_id = assist_resolve.assist_id.clone();
     let expected_kind = assist_resolve.assist_kind;
 
@@ -1330,7 +1334,11 @@ pub(crate) fn handle_code_action_resolve(
         ))
         .into());
     }
-    let ca = to_proto::code_action(&snap, assist.clone(), None)?;
+    let mut assist = assist.clone();
+    if let Some(source_change) = assist.source_change.as_mut() {
+        format_assist_source_change(&snap, source_change)?;
+    }
+    let ca = to_proto::code_action(&snap, assist, None)?;
     code_action.edit = ca.edit;
     code_action.command = ca.command;
 
@@ -1356,7 +1364,14 @@ fn parse_action_id(action_id: &str) -> anyhow::Result<(usize, SingleResolve), St
                 Ok(index) => index,
                 Err(e) => return Err(format!("Incorrect index string: {e}")),
             };
-            Ok((index, SingleResolve { assist_id: assist_id_string.to_string(), assist_kind }))
+            Ok((
+                index,
+                SingleResolve {
+                    assist_id: assist_id_string.to_string(),
+                    assist_kind,
+                    parameter: None,
+                },
+            ))
         }
         _ => Err("Action id contains incorrect number of segments".to_owned()),
     }
@@ -2018,6 +2033,87 @@ fn run_rustfmt(
 ) -> anyhow::Result<Option<Vec<lsp_types::TextEdit>>> {
     let file_id = from_proto::file_id(snap, &text_document.uri)?;
     let file = snap.analysis.file_text(file_id)?;
+    let line_index = snap.file_line_index(file_id)?;
+
+    let Some(captured_stdout) = rustfmt_text(snap, &text_document, range, &file)? else {
+        return Ok(None);
+    };
+
+    let (new_text, new_line_endings) = LineEndings::normalize(captured_stdout);
+
+    if line_index.endings != new_line_endings {
+        // If line endings are different, send the entire file.
+        // Diffing would not work here, as the line endings might be the only
+        // difference.
+        Ok(Some(to_proto::text_edit_vec(
+            &line_index,
+            TextEdit::replace(TextRange::up_to(TextSize::of(&*file)), new_text),
+        )))
+    } else if *file == new_text {
+        // The document is already formatted correctly -- no edits needed.
+        Ok(None)
+    } else {
+        Ok(Some(to_proto::text_edit_vec(&line_index, diff(&file, &new_text))))
+    }
+}
+
+/// Reformats the edits of a resolved assist with the project's rustfmt, if the user opted into
+/// it.
+///
+/// Only files that are already formatted before the assist applies are touched, so that the
+/// resulting edit is limited to the code generated by the assist. Snippet edits are left alone,
+/// as their tabstops would no longer line up with the reformatted text. If rustfmt fails, the
+/// edit is kept as is.
+fn format_assist_source_change(
+    snap: &GlobalStateSnapshot,
+    source_change: &mut SourceChange,
+) -> Cancellable<()> {
+    if !snap.config.assist_format_generated_code() || source_change.is_snippet {
+        return Ok(());
+    }
+
+    for (&file_id, (edit, _)) in source_change.source_file_edits.iter_mut() {
+        let text_document = TextDocumentIdentifier::new(to_proto::url(snap, file_id));
+        let file = snap.analysis.file_text(file_id)?;
+        let mut edited = file.to_string();
+        edit.apply(&mut edited);
+        match rustfmt_edited_text(snap, &text_document, &file, &edited) {
+            Ok(Some(formatted)) if formatted != edited => *edit = diff(&file, &formatted),
+            Ok(_) => (),
+            Err(err) => tracing::error!("failed to format the edit of an assist: {err:#}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs rustfmt over the `edited` version of `file`, returning `None` if `file` isn't formatted
+/// to begin with or rustfmt failed to parse either of them.
+fn rustfmt_edited_text(
+    snap: &GlobalStateSnapshot,
+    text_document: &TextDocumentIdentifier,
+    file: &str,
+    edited: &str,
+) -> anyhow::Result<Option<String>> {
+    let is_formatted = rustfmt_text(snap, text_document, None, file)?
+        .is_some_and(|it| LineEndings::normalize(it).0 == file);
+    if !is_formatted {
+        return Ok(None);
+    }
+    let formatted = rustfmt_text(snap, text_document, None, edited)?;
+    Ok(formatted.map(|it| LineEndings::normalize(it).0))
+}
+
+/// Runs the configured rustfmt over `text`, returning the formatted output.
+///
+/// Returns `Ok(None)` when rustfmt failed to parse the text.
+fn rustfmt_text(
+    snap: &GlobalStateSnapshot,
+    text_document: &TextDocumentIdentifier,
+    range: Option<lsp_types::Range>,
+    text: &str,
+) -> anyhow::Result<Option<String>> {
+    let file_id = from_proto::file_id(snap, &text_document.uri)?;
 
     // Determine the edition of the crate the file belongs to (if there's multiple, we pick the
     // highest edition).
@@ -2033,7 +2129,6 @@ fn run_rustfmt(
     let edition = editions.iter().copied().max();
 
     let line_index = snap.file_line_index(file_id)?;
-
     let mut command = match snap.config.rustfmt() {
         RustfmtConfig::Rustfmt { extra_args, enable_range_formatting } => {
             // FIXME: Set RUSTUP_TOOLCHAIN
@@ -2059,7 +2154,7 @@ fn run_rustfmt(
                     .into());
                 }
 
-                let frange = from_proto::file_range(snap, &text_document, range)?;
+                let frange = from_proto::file_range(snap, text_document, range)?;
                 let start_line = line_index.index.line_col(frange.range.start()).line;
                 let end_line = line_index.index.line_col(frange.range.end()).line;
 
@@ -2129,7 +2224,7 @@ fn run_rustfmt(
         .spawn()
         .context(format!("Failed to spawn {command:?}"))?;
 
-    rustfmt.stdin.as_mut().unwrap().write_all(file.as_bytes())?;
+    rustfmt.stdin.as_mut().unwrap().write_all(text.as_bytes())?;
 
     let output = rustfmt.wait_with_output()?;
     let captured_stdout = String::from_utf8(output.stdout)?;
@@ -2170,22 +2265,7 @@ fn run_rustfmt(
         };
     }
 
-    let (new_text, new_line_endings) = LineEndings::normalize(captured_stdout);
-
-    if line_index.endings != new_line_endings {
-        // If line endings are different, send the entire file.
-        // Diffing would not work here, as the line endings might be the only
-        // difference.
-        Ok(Some(to_proto::text_edit_vec(
-            &line_index,
-            TextEdit::replace(TextRange::up_to(TextSize::of(&*file)), new_text),
-        )))
-    } else if *file == new_text {
-        // The document is already formatted correctly -- no edits needed.
-        Ok(None)
-    } else {
-        Ok(Some(to_proto::text_edit_vec(&line_index, diff(&file, &new_text))))
-    }
+    Ok(Some(captured_stdout))
 }
 
 pub(crate) fn fetch_dependency_list(
@@ -12,10 +12,11 @@
 
 use hir::ChangeWithProcMacros;
 use ide::{
-    AnalysisHost, CallableSnippets, CompletionConfig, DiagnosticsConfig, FilePosition, TextSize,
+    AnalysisHost, CallableSnippets, CompletionConfig, DiagnosticsConfig, FilePosition, Severity,
+    TextSize,
 };
 use ide_db::{
-    imports::insert_use::{ImportGranularity, InsertUseConfig},
+    imports::insert_use::{ImportGranularity, ImportGroupStyle, InsertUseConfig},
     SnippetCap,
 };
 use project_model::CargoConfig;
@@ -139,6 +140,7 @@ fn integrated_completion_benchmark() {
             enable_self_on_the_fly: true,
             enable_private_editable: true,
             enable_term_search: true,
+            enable_typo_tolerance: false,
             full_function_signatures: false,
             callable: Some(CallableSnippets::FillArguments),
             snippet_cap: SnippetCap::new(true),
@@ -147,6 +149,7 @@ fn integrated_completion_benchmark() {
                 prefix_kind: hir::PrefixKind::ByCrate,
                 enforce_granularity: true,
                 group: true,
+                group_style: ImportGroupStyle::Default,
                 skip_glob_imports: true,
             },
             snippets: Vec::new(),
@@ -183,6 +186,7 @@ fn integrated_completion_benchmark() {
             enable_self_on_the_fly: true,
             enable_private_editable: true,
             enable_term_search: true,
+            enable_typo_tolerance: false,
             full_function_signatures: false,
             callable: Some(CallableSnippets::FillArguments),
             snippet_cap: SnippetCap::new(true),
@@ -191,6 +195,7 @@ fn integrated_completion_benchmark() {
                 prefix_kind: hir::PrefixKind::ByCrate,
                 enforce_granularity: true,
                 group: true,
+                group_style: ImportGroupStyle::Default,
                 skip_glob_imports: true,
             },
             snippets: Vec::new(),
@@ -225,6 +230,7 @@ fn integrated_completion_benchmark() {
             enable_self_on_the_fly: true,
             enable_private_editable: true,
             enable_term_search: true,
+            enable_typo_tolerance: false,
             full_function_signatures: false,
             callable: Some(CallableSnippets::FillArguments),
             snippet_cap: SnippetCap::new(true),
@@ -233,6 +239,7 @@ fn integrated_completion_benchmark() {
                 prefix_kind: hir::PrefixKind::ByCrate,
                 enforce_granularity: true,
                 group: true,
+                group_style: ImportGroupStyle::Default,
                 skip_glob_imports: true,
             },
             snippets: Vec::new(),
@@ -286,15 +293,22 @@ fn integrated_diagnostics_benchmark() {
         disabled: Default::default(),
         expr_fill_default: Default::default(),
         style_lints: false,
+        restriction_lints: false,
+        enum_variant_name_stutter_threshold: 1,
+        released_code_severity: Severity::Allow,
+        released_code_macros: Vec::new(),
+        nested_result_option_max_depth: 2,
         insert_use: InsertUseConfig {
             granularity: ImportGranularity::Crate,
             enforce_granularity: false,
             prefix_kind: hir::PrefixKind::ByCrate,
             group: true,
+            group_style: ImportGroupStyle::Default,
             skip_glob_imports: true,
         },
         prefer_no_std: false,
         prefer_prelude: false,
+        public_dependencies: Vec::new(),
     };
     host.analysis()
         .diagnostics(&diagnostics_config, ide::AssistResolveStrategy::None, file_id)
@@ -548,6 +548,12 @@ pub struct CodeAction {
 pub struct CodeActionData {
     pub code_action_params: lsp_types::CodeActionParams,
     pub id: String,
+    /// Set if the action can only be resolved with a `parameter`, describes it to the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter_prompt: Option<String>,
+    /// Supplied by the client when resolving an action with a `parameter_prompt`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<String>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
@@ -97,6 +97,8 @@ pub(crate) fn assist_kind(kind: lsp_types::CodeActionKind) -> Option<AssistKind>
         k if k == &lsp_types::CodeActionKind::REFACTOR_EXTRACT => AssistKind::RefactorExtract,
         k if k == &lsp_types::CodeActionKind::REFACTOR_INLINE => AssistKind::RefactorInline,
         k if k == &lsp_types::CodeActionKind::REFACTOR_REWRITE => AssistKind::RefactorRewrite,
+        k if k == &lsp_types::CodeActionKind::SOURCE => AssistKind::Source,
+        k if k == &lsp_types::CodeActionKind::SOURCE_FIX_ALL => AssistKind::SourceFixAll,
         _ => return None,
     };
 
@@ -6,7 +6,7 @@
 };
 
 use ide::{
-    Annotation, AnnotationKind, Assist, AssistKind, Cancellable, CompletionItem,
+    Annotation, AnnotationKind, Assist, AssistKind, Cancellable, ChangeAnnotation, CompletionItem,
     CompletionItemKind, CompletionRelevance, Documentation, FileId, FileRange, FileSystemEdit,
     Fold, FoldKind, Highlight, HlMod, HlOperator, HlPunct, HlRange, HlTag, Indel,
     InlayFieldsToResolve, InlayHint, InlayHintLabel, InlayHintLabelPart, InlayKind, Markup,
@@ -252,7 +252,7 @@ fn completion_item(
 ) {
     let insert_replace_support = config.insert_replace_support().then_some(tdpp.position);
     let ref_match = item.ref_match();
-    let lookup = item.lookup().to_owned();
+    let filter_text = item.typo_match.clone().unwrap_or_else(|| item.lookup().into());
 
     let mut additional_text_edits = Vec::new();
 
@@ -295,7 +295,7 @@ fn completion_item(
     let mut lsp_item = lsp_types::CompletionItem {
         label: item.label.to_string(),
         detail: item.detail,
-        filter_text: Some(lookup),
+        filter_text: Some(filter_text.to_string()),
         kind: Some(completion_item_kind(item.kind)),
         text_edit: Some(text_edit),
         additional_text_edits: Some(additional_text_edits),
@@ -945,6 +945,24 @@ fn outside_workspace_annotation_id() -> String {
     String::from("OutsideWorkspace")
 }
 
+fn edited_file_annotation_id(file_id: FileId) -> String {
+    format!("EditedFile{}", file_id.index())
+}
+
+fn edit_annotation_id(index: usize) -> String {
+    format!("Edit{index}")
+}
+
+/// The annotation grouping the `edits` to the file at `path` in a preview of a change to several
+/// files. Applying the change doesn't need another confirmation for them.
+fn edited_file_annotation(path: &str, edits: usize) -> lsp_types::ChangeAnnotation {
+    lsp_types::ChangeAnnotation {
+        label: format!("{path}: {edits} edit{}", if edits == 1 { "" } else { "s" }),
+        needs_confirmation: Some(false),
+        description: None,
+    }
+}
+
 fn merge_text_and_snippet_edits(
     line_index: &LineIndex,
     edit: TextEdit,
@@ -1171,6 +1189,22 @@ pub(crate) fn snippet_text_document_ops(
                 rename_file,
             )))
         }
+        FileSystemEdit::DeleteFile { src } => {
+            let uri = snap.file_id_to_url(src);
+            let mut delete_file = lsp_types::DeleteFile { uri, options: None };
+            if snap.analysis.is_library_file(src).ok() == Some(true)
+                && snap.config.change_annotation_support()
+            {
+                delete_file.options = Some(lsp_types::DeleteFileOptions {
+                    recursive: None,
+                    ignore_if_not_exists: None,
+                    annotation_id: Some(outside_workspace_annotation_id()),
+                })
+            }
+            ops.push(lsp_ext::SnippetDocumentChangeOperation::Op(lsp_types::ResourceOp::Delete(
+                delete_file,
+            )))
+        }
     }
     Ok(ops)
 }
@@ -1180,6 +1214,12 @@ pub(crate) fn snippet_workspace_edit(
     mut source_change: SourceChange,
 ) -> Cancellable<lsp_ext::SnippetWorkspaceEdit> {
     let mut document_changes: Vec<lsp_ext::SnippetDocumentChangeOperation> = Vec::new();
+    // Changes to several files are annotated per file, so that the user can review them first.
+    let summary = source_change.summary();
+    let preview = snap.config.change_annotation_support() && summary.files_touched() > 1;
+    let annotations = mem::take(&mut source_change.annotations);
+    // The distinct annotations of single edits, their ids are their indices.
+    let mut edit_annotations: Vec<ChangeAnnotation> = Vec::new();
 
     for op in &mut source_change.file_system_edits {
         if let FileSystemEdit::CreateFile { dst, initial_contents } = op {
@@ -1193,13 +1233,34 @@ pub(crate) fn snippet_workspace_edit(
         }
     }
     for (file_id, (edit, snippet_edit)) in source_change.source_file_edits {
-        let edit = snippet_text_document_edit(
+        let mut edit = snippet_text_document_edit(
             snap,
             source_change.is_snippet,
             file_id,
             edit,
             snippet_edit,
         )?;
+        if snap.config.change_annotation_support() {
+            let line_index = snap.file_line_index(file_id)?;
+            for (_, annotated, annotation) in annotations.iter().filter(|(it, ..)| *it == file_id) {
+                let annotated = range(&line_index, *annotated);
+                let index = match edit_annotations.iter().position(|it| it == annotation) {
+                    Some(index) => index,
+                    None => {
+                        edit_annotations.push(annotation.clone());
+                        edit_annotations.len() - 1
+                    }
+                };
+                for edit in edit.edits.iter_mut().filter(|it| it.range == annotated) {
+                    edit.annotation_id.get_or_insert_with(|| edit_annotation_id(index));
+                }
+            }
+        }
+        if preview {
+            for edit in &mut edit.edits {
+                edit.annotation_id.get_or_insert_with(|| edited_file_annotation_id(file_id));
+            }
+        }
         document_changes.push(lsp_ext::SnippetDocumentChangeOperation::Edit(edit));
     }
     for op in source_change.file_system_edits {
@@ -1214,6 +1275,16 @@ pub(crate) fn snippet_workspace_edit(
         change_annotations: None,
     };
     if snap.config.change_annotation_support() {
+        let edited_files = if preview { summary.edited_files } else { Vec::new() };
+        let edited_files = edited_files.into_iter().map(|(file_id, edits)| {
+            let path = snap.file_id_to_file_path(file_id);
+            let path = match path.as_path().and_then(|it| it.strip_prefix(snap.config.root_path()))
+            {
+                Some(relative) => relative.as_str().to_owned(),
+                None => path.to_string(),
+            };
+            (edited_file_annotation_id(file_id), edited_file_annotation(&path, edits))
+        });
         workspace_edit.change_annotations = Some(
             once((
                 outside_workspace_annotation_id(),
@@ -1225,6 +1296,15 @@ pub(crate) fn snippet_workspace_edit(
                     )),
                 },
             ))
+            .chain(edited_files)
+            .chain(edit_annotations.into_iter().enumerate().map(|(index, annotation)| {
+                let annotation = lsp_types::ChangeAnnotation {
+                    label: annotation.label,
+                    needs_confirmation: Some(annotation.needs_confirmation),
+                    description: None,
+                };
+                (edit_annotation_id(index), annotation)
+            }))
             .collect(),
         )
     }
@@ -1312,6 +1392,8 @@ pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
         AssistKind::RefactorExtract => lsp_types::CodeActionKind::REFACTOR_EXTRACT,
         AssistKind::RefactorInline => lsp_types::CodeActionKind::REFACTOR_INLINE,
         AssistKind::RefactorRewrite => lsp_types::CodeActionKind::REFACTOR_REWRITE,
+        AssistKind::Source => lsp_types::CodeActionKind::SOURCE,
+        AssistKind::SourceFixAll => lsp_types::CodeActionKind::SOURCE_FIX_ALL,
     }
 }
 
@@ -1340,6 +1422,8 @@ pub(crate) fn code_action(
             res.data = Some(lsp_ext::CodeActionData {
                 id: format!("{}:{}:{index}", assist.id.0, assist.id.1.name()),
                 code_action_params,
+                parameter_prompt: assist.parameter_prompt,
+                parameter: None,
             });
         }
         (None, None) => {
@@ -1754,6 +1838,16 @@ fn bar(_: usize) {}
         assert!(!docs.contains("use crate::bar"));
     }
 
+    #[test]
+    fn edited_file_annotations() {
+        let annotation = edited_file_annotation("src/foo/bar.rs", 1);
+        assert_eq!(annotation.label, "src/foo/bar.rs: 1 edit");
+        assert_eq!(annotation.needs_confirmation, Some(false));
+
+        let annotation = edited_file_annotation("src/lib.rs", 3);
+        assert_eq!(annotation.label, "src/lib.rs: 3 edits");
+    }
+
     #[track_caller]
     fn check_rendered_snippets(edit: TextEdit, snippets: SnippetEdit, expect: Expect) {
         check_rendered_snippets_in_source(
@@ -60,6 +60,8 @@ enum Event {
     Vfs(vfs::loader::Message),
     Flycheck(flycheck::Message),
     TestResult(flycheck::CargoTestMessage),
+    /// Nothing happened for `lru.idleTrimDelay`.
+    Idle,
 }
 
 impl fmt::Display for Event {
@@ -71,6 +73,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             Event::Flycheck(_) => write!(f, "Event::Flycheck"),
             Event::QueuedTask(_) => write!(f, "Event::QueuedTask"),
             Event::TestResult(_) => write!(f, "Event::TestResult"),
+            Event::Idle => write!(f, "Event::Idle"),
         }
     }
 }
@@ -132,6 +135,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             Event::Vfs(it) => fmt::Debug::fmt(it, f),
             Event::Flycheck(it) => fmt::Debug::fmt(it, f),
             Event::TestResult(it) => fmt::Debug::fmt(it, f),
+            Event::Idle => f.write_str("Idle"),
         }
     }
 }
@@ -201,6 +205,11 @@ fn register_did_save_capability(&mut self) {
     }
 
     fn next_event(&self, inbox: &Receiver<lsp_server::Message>) -> Option<Event> {
+        let idle_timeout = match self.config.lru_idle_trim_delay() {
+            Some(delay) if !self.lru_caches_trimmed => crossbeam_channel::after(delay),
+            _ => crossbeam_channel::never(),
+        };
+
         select! {
             recv(inbox) -> msg =>
                 msg.ok().map(Event::Lsp),
@@ -223,6 +232,8 @@ fn next_event(&self, inbox: &Receiver<lsp_server::Message>) -> Option<Event> {
             recv(self.test_run_receiver) -> task =>
                 Some(Event::TestResult(task.unwrap())),
 
+            recv(idle_timeout) -> _ =>
+                Some(Event::Idle),
         }
     }
 
@@ -242,6 +253,9 @@ fn handle_event(&mut self, event: Event) -> anyhow::Result<()> {
         }
 
         let was_quiescent = self.is_quiescent();
+        if !matches!(event, Event::Idle) {
+            self.lru_caches_trimmed = false;
+        }
         match event {
             Event::Lsp(msg) => match msg {
                 lsp_server::Message::Request(req) => self.on_new_request(loop_start, req),
@@ -341,9 +355,28 @@ fn handle_event(&mut self, event: Event) -> anyhow::Result<()> {
                     self.handle_cargo_test_msg(message);
                 }
             }
+            Event::Idle => {
+                // Don't throw away what is still being computed, e.g. by cache priming.
+                // Otherwise the next idle period tries again.
+                if self.is_quiescent() && !self.prime_caches_queue.op_in_progress() {
+                    self.analysis_host.trim_lru_caches();
+                    self.lru_caches_trimmed = true;
+                }
+            }
         }
         let event_handling_duration = loop_start.elapsed();
 
+        // The rest of the workspace is loaded once the crates of the open files have been
+        // analyzed.
+        if self.crate_graph_deferred
+            && was_quiescent
+            && self.is_quiescent()
+            && !self.prime_caches_queue.op_in_progress()
+            && !self.prime_caches_queue.op_requested()
+        {
+            self.load_deferred_crate_graph();
+        }
+
         let state_changed = self.process_changes();
         let memdocs_added_or_removed = self.mem_docs.take_changes();
 
@@ -466,12 +499,18 @@ fn prime_caches(&mut self, cause: String) {
 
         self.task_pool.handle.spawn_with_sender(ThreadIntent::Worker, {
             let analysis = self.snapshot().analysis;
+            // Requests for the files the user is looking at go first.
+            let should_yield = self.task_pool.handle.latency_sensitive_probe();
             move |sender| {
                 sender.send(Task::PrimeCaches(PrimeCachesProgress::Begin)).unwrap();
-                let res = analysis.parallel_prime_caches(num_worker_threads, |progress| {
-                    let report = PrimeCachesProgress::Report(progress);
-                    sender.send(Task::PrimeCaches(report)).unwrap();
-                });
+                let res = analysis.parallel_prime_caches(
+                    num_worker_threads,
+                    |progress| {
+                        let report = PrimeCachesProgress::Report(progress);
+                        sender.send(Task::PrimeCaches(report)).unwrap();
+                    },
+                    should_yield,
+                );
                 sender
                     .send(Task::PrimeCaches(PrimeCachesProgress::End { cancelled: res.is_err() }))
                     .unwrap();
@@ -480,13 +519,14 @@ fn prime_caches(&mut self, cause: String) {
     }
 
     fn update_diagnostics(&mut self) {
+        let touched_paths = self.mem_docs.take_touched();
         let db = self.analysis_host.raw_database();
-        let subscriptions = {
+        let (touched, untouched): (Vec<_>, Vec<_>) = {
             let vfs = &self.vfs.read().0;
             self.mem_docs
                 .iter()
-                .map(|path| vfs.file_id(path).unwrap())
-                .filter(|&file_id| {
+                .map(|path| (path, vfs.file_id(path).unwrap()))
+                .filter(|&(_, file_id)| {
                     let source_root = db.file_source_root(file_id);
                     // Only publish diagnostics for files in the workspace, not from crates.io deps
                     // or the sysroot.
@@ -495,16 +535,25 @@ fn update_diagnostics(&mut self) {
                     // forever if we emitted them here.
                     !db.source_root(source_root).is_library
                 })
-                .collect::<Vec<_>>()
+                .partition(|(path, _)| touched_paths.contains(*path))
         };
-        tracing::trace!("updating notifications for {:?}", subscriptions);
+        tracing::trace!("updating notifications for {:?} and {:?}", touched, untouched);
 
-        // Diagnostics are triggered by the user typing
-        // so we run them on a latency sensitive thread.
-        self.task_pool.handle.spawn(ThreadIntent::LatencySensitive, {
-            let snapshot = self.snapshot();
-            move || Task::Diagnostics(fetch_native_diagnostics(snapshot, subscriptions))
-        });
+        // Diagnostics are triggered by the user typing so we run them on a latency sensitive
+        // thread. The documents just opened or edited are likely the visible ones, so they get
+        // their diagnostics first and the other open documents wait for a worker.
+        for (intent, subscriptions) in
+            [(ThreadIntent::LatencySensitive, touched), (ThreadIntent::Worker, untouched)]
+        {
+            if subscriptions.is_empty() {
+                continue;
+            }
+            let subscriptions = subscriptions.into_iter().map(|(_, file_id)| file_id).collect();
+            self.task_pool.handle.spawn(intent, {
+                let snapshot = self.snapshot();
+                move || Task::Diagnostics(fetch_native_diagnostics(snapshot, subscriptions))
+            });
+        }
     }
 
     fn update_tests(&mut self) {
@@ -932,6 +981,9 @@ fn on_request(&mut self, req: Request) {
             .on_latency_sensitive::<lsp_request::SemanticTokensRangeRequest>(
                 handlers::handle_semantic_tokens_range,
             )
+            .on_latency_sensitive_no_retry::<lsp_request::InlayHintRequest>(
+                handlers::handle_inlay_hints,
+            )
             // All other request handlers
             .on::<lsp_ext::FetchDependencyList>(handlers::fetch_dependency_list)
             .on::<lsp_ext::AnalyzerStatus>(handlers::handle_analyzer_status)
@@ -959,7 +1011,6 @@ fn on_request(&mut self, req: Request) {
             .on::<lsp_request::GotoDeclaration>(handlers::handle_goto_declaration)
             .on::<lsp_request::GotoImplementation>(handlers::handle_goto_implementation)
             .on::<lsp_request::GotoTypeDefinition>(handlers::handle_goto_type_definition)
-            .on_no_retry::<lsp_request::InlayHintRequest>(handlers::handle_inlay_hints)
             .on::<lsp_request::InlayHintResolveRequest>(handlers::handle_inlay_hints_resolve)
             .on::<lsp_request::CodeLensRequest>(handlers::handle_code_lens)
             .on::<lsp_request::CodeLensResolve>(handlers::handle_code_lens_resolve)
@@ -2,7 +2,7 @@
 
 use std::mem;
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use vfs::VfsPath;
 
 /// Holds the set of in-memory documents.
@@ -13,6 +13,9 @@
 pub(crate) struct MemDocs {
     mem_docs: FxHashMap<VfsPath, DocumentData>,
     added_or_removed: bool,
+    /// The documents opened or edited since the last [`MemDocs::take_touched`], which are most
+    /// likely the ones the user is looking at.
+    touched: FxHashSet<VfsPath>,
 }
 
 impl MemDocs {
@@ -22,6 +25,7 @@ pub(crate) fn contains(&self, path: &VfsPath) -> bool {
 
     pub(crate) fn insert(&mut self, path: VfsPath, data: DocumentData) -> Result<(), ()> {
         self.added_or_removed = true;
+        self.touched.insert(path.clone());
         match self.mem_docs.insert(path, data) {
             Some(_) => Err(()),
             None => Ok(()),
@@ -30,6 +34,7 @@ pub(crate) fn insert(&mut self, path: VfsPath, data: DocumentData) -> Result<(),
 
     pub(crate) fn remove(&mut self, path: &VfsPath) -> Result<(), ()> {
         self.added_or_removed = true;
+        self.touched.remove(path);
         match self.mem_docs.remove(path) {
             Some(_) => Ok(()),
             None => Err(()),
@@ -43,6 +48,7 @@ pub(crate) fn get(&self, path: &VfsPath) -> Option<&DocumentData> {
     pub(crate) fn get_mut(&mut self, path: &VfsPath) -> Option<&mut DocumentData> {
         // NB: don't set `self.added_or_removed` here, as that purposefully only
         // tracks changes to the key set.
+        self.touched.insert(path.clone());
         self.mem_docs.get_mut(path)
     }
 
@@ -53,6 +59,10 @@ pub(crate) fn iter(&self) -> impl Iterator<Item = &VfsPath> {
     pub(crate) fn take_changes(&mut self) -> bool {
         mem::replace(&mut self.added_or_removed, false)
     }
+
+    pub(crate) fn take_touched(&mut self) -> FxHashSet<VfsPath> {
+        mem::take(&mut self.touched)
+    }
 }
 
 /// Information about a document that the Language Client
@@ -16,11 +16,14 @@
 use std::{iter, mem};
 
 use flycheck::{FlycheckConfig, FlycheckHandle};
-use hir::{db::DefDatabase, ChangeWithProcMacros, ProcMacros};
+use hir::{
+    db::{DefDatabase, ExpandDatabase},
+    ChangeWithProcMacros, ProcMacros,
+};
 use ide::CrateId;
 use ide_db::{
     base_db::{salsa::Durability, CrateGraph, ProcMacroPaths, Version},
-    FxHashMap,
+    FxHashMap, FxHashSet,
 };
 use itertools::Itertools;
 use load_cargo::{load_proc_macro, ProjectFolders};
@@ -38,6 +41,11 @@
     op_queue::Cause,
 };
 
+/// A crate graph along with its proc-macro paths and the target data layouts and toolchains of
+/// its crates, as built by [`ws_to_crate_graph`].
+pub(crate) type CrateGraphParts =
+    (CrateGraph, Vec<ProcMacroPaths>, Vec<Result<Arc<str>, Arc<str>>>, Vec<Option<Version>>);
+
 #[derive(Debug)]
 pub(crate) enum ProjectWorkspaceProgress {
     Begin,
@@ -96,6 +104,15 @@ pub(crate) fn update_configuration(&mut self, config: Config) {
                 Durability::HIGH,
             );
         }
+
+        if self.analysis_host.raw_database().macro_expansion_limits()
+            != self.config.macro_expansion_limits()
+        {
+            self.analysis_host.raw_database_mut().set_macro_expansion_limits_with_durability(
+                self.config.macro_expansion_limits(),
+                Durability::HIGH,
+            );
+        }
     }
 
     pub(crate) fn current_status(&self) -> lsp_ext::ServerStatusParams {
@@ -560,18 +577,42 @@ fn recreate_crate_graph(&mut self, cause: String) {
             })
             .collect();
 
-        let (crate_graph, proc_macro_paths, layouts, toolchains) = {
-            // Create crate graph from all the workspaces
-            let vfs = &mut self.vfs.write().0;
+        // Until the crates of all of the workspaces have been loaded once, only the ones of the
+        // open files are, also when the graph is recreated in the meantime, e.g. for build data.
+        let only_open = self.config.load_incrementally() && !self.full_crate_graph_loaded;
+        let (parts, deferred) = self.build_crate_graph(only_open);
+        self.crate_graph_deferred = deferred;
+        self.full_crate_graph_loaded |= !deferred;
+        self.set_crate_graph(cause, parts);
+        self.report_progress(
+            "Building CrateGraph",
+            crate::lsp::utils::Progress::End,
+            None,
+            None,
+            None,
+        );
 
-            let load = |path: &AbsPath| {
-                let vfs_path = vfs::VfsPath::from(path.to_path_buf());
-                self.crate_graph_file_dependencies.insert(vfs_path.clone());
-                vfs.file_id(&vfs_path)
-            };
+        self.process_changes();
+        self.reload_flycheck();
+    }
 
-            ws_to_crate_graph(&self.workspaces, self.config.extra_env(), load)
-        };
+    /// Loads the crates left out by the last crate graph construction, see
+    /// [`Self::build_crate_graph`].
+    pub(crate) fn load_deferred_crate_graph(&mut self) {
+        if !mem::take(&mut self.crate_graph_deferred) {
+            return;
+        }
+        let (parts, _) = self.build_crate_graph(false);
+        self.full_crate_graph_loaded = true;
+        let cause = "loading the rest of the workspace".to_owned();
+        self.set_crate_graph(cause.clone(), parts);
+        if self.config.prefill_caches() {
+            self.prime_caches_queue.request_op(cause, ());
+        }
+    }
+
+    fn set_crate_graph(&mut self, cause: String, parts: CrateGraphParts) {
+        let (crate_graph, proc_macro_paths, layouts, toolchains) = parts;
         let mut change = ChangeWithProcMacros::new();
         if self.config.expand_proc_macros() {
             change.set_proc_macros(
@@ -586,16 +627,86 @@ fn recreate_crate_graph(&mut self, cause: String) {
         change.set_target_data_layouts(layouts);
         change.set_toolchains(toolchains);
         self.analysis_host.apply_change(change);
-        self.report_progress(
-            "Building CrateGraph",
-            crate::lsp::utils::Progress::End,
-            None,
-            None,
-            None,
-        );
+    }
 
-        self.process_changes();
-        self.reload_flycheck();
+    /// Builds the crate graph of the workspaces. With `only_open`, the workspaces without open
+    /// files aren't looked at and only the crates of the open files and their dependencies are
+    /// kept, the returned flag telling whether anything was left out.
+    ///
+    /// The crates of the open files come first, so that they keep their ids once the full graph
+    /// is loaded and the caches computed for them stay valid.
+    fn build_crate_graph(&mut self, only_open: bool) -> (CrateGraphParts, bool) {
+        let included =
+            self.workspaces.iter().map(|ws| !only_open || self.has_open_files(ws)).collect_vec();
+        if only_open && !included.contains(&true) {
+            return self.build_crate_graph(false);
+        }
+        let mut parts = {
+            let vfs = &mut self.vfs.write().0;
+
+            let load = |path: &AbsPath| {
+                let vfs_path = vfs::VfsPath::from(path.to_path_buf());
+                self.crate_graph_file_dependencies.insert(vfs_path.clone());
+                vfs.file_id(&vfs_path)
+            };
+
+            let workspaces =
+                self.workspaces.iter().zip(&included).filter(|(_, &it)| it).map(|(ws, _)| ws);
+            ws_to_crate_graph(workspaces, self.config.extra_env(), load)
+        };
+        if !self.config.load_incrementally() {
+            return (parts, false);
+        }
+
+        let open_crates = self.open_crates(&parts.0);
+        if only_open && open_crates.is_empty() {
+            return self.build_crate_graph(false);
+        }
+        move_crates_to_front(&mut parts, &open_crates);
+        if !only_open || open_crates.len() == parts.0.len() && !included.contains(&false) {
+            return (parts, false);
+        }
+
+        let (mut crate_graph, proc_macro_paths, mut layouts, mut toolchains) = parts;
+        let num_open_crates = open_crates.len();
+        let is_open = |id: CrateId| (id.into_raw().into_u32() as usize) < num_open_crates;
+        let kept = crate_graph.iter().filter(|&id| is_open(id)).collect_vec();
+        crate_graph.remove_crates_except(&kept);
+        let proc_macro_paths = proc_macro_paths
+            .into_iter()
+            .map(|paths| paths.into_iter().filter(|(id, _)| is_open(*id)).collect())
+            .collect();
+        layouts.truncate(num_open_crates);
+        toolchains.truncate(num_open_crates);
+        ((crate_graph, proc_macro_paths, layouts, toolchains), true)
+    }
+
+    /// Whether one of the open files is in a local package root of the workspace.
+    fn has_open_files(&self, ws: &ProjectWorkspace) -> bool {
+        let roots = ws.to_roots();
+        self.mem_docs.iter().filter_map(|path| path.as_path()).any(|path| {
+            roots.iter().filter(|root| root.is_local).any(|root| {
+                root.include.iter().any(|dir| path.starts_with(dir))
+                    && !root.exclude.iter().any(|dir| path.starts_with(dir))
+            })
+        })
+    }
+
+    /// The crates whose root file is in the source root of one of the open files, along with
+    /// their dependencies.
+    fn open_crates(&self, crate_graph: &CrateGraph) -> FxHashSet<CrateId> {
+        let vfs = &self.vfs.read().0;
+        let fsc = &self.source_root_config.fsc;
+        let open_roots: FxHashSet<usize> =
+            self.mem_docs.iter().filter_map(|path| fsc.file_set_of(path)).collect();
+        crate_graph
+            .iter()
+            .filter(|&krate| {
+                fsc.file_set_of(vfs.file_path(crate_graph[krate].root_file_id))
+                    .is_some_and(|root| open_roots.contains(&root))
+            })
+            .flat_map(|krate| crate_graph.transitive_deps(krate))
+            .collect()
     }
 
     pub(super) fn fetch_workspace_error(&self) -> Result<(), String> {
@@ -703,8 +814,8 @@ fn reload_flycheck(&mut self) {
 }
 
 // FIXME: Move this into load-cargo?
-pub fn ws_to_crate_graph(
-    workspaces: &[ProjectWorkspace],
+pub fn ws_to_crate_graph<'a>(
+    workspaces: impl IntoIterator<Item = &'a ProjectWorkspace>,
     extra_env: &FxHashMap<String, String>,
     mut load: impl FnMut(&AbsPath) -> Option<vfs::FileId>,
 ) -> (
@@ -761,6 +872,32 @@ pub fn ws_to_crate_graph(
     (crate_graph, proc_macro_paths, layouts, toolchains)
 }
 
+/// Moves `crates` to the front of the crate graph, keeping the proc-macro paths and the side
+/// tables in sync.
+fn move_crates_to_front(parts: &mut CrateGraphParts, crates: &FxHashSet<CrateId>) {
+    let (crate_graph, proc_macro_paths, layouts, toolchains) = parts;
+    let id_map = crate_graph.move_to_front(crates);
+    let new_id = |id: CrateId| id_map[id.into_raw().into_u32() as usize];
+    permute_crate_side_table(layouts, &id_map);
+    permute_crate_side_table(toolchains, &id_map);
+    for paths in proc_macro_paths {
+        *paths = mem::take(paths).into_iter().map(|(id, path)| (new_id(id), path)).collect();
+    }
+}
+
+/// Reorders a side table indexed by crate ids according to the `id_map` returned by
+/// [`CrateGraph::move_to_front`]. Tables that don't cover all crates are left as is.
+fn permute_crate_side_table<T: Clone>(side_table: &mut Vec<T>, id_map: &[CrateId]) {
+    if side_table.len() != id_map.len() {
+        return;
+    }
+    let mut permuted = side_table.clone();
+    for (old_id, new_id) in id_map.iter().enumerate() {
+        permuted[new_id.into_raw().into_u32() as usize] = side_table[old_id].clone();
+    }
+    *side_table = permuted;
+}
+
 pub(crate) fn should_refresh_for_change(path: &AbsPath, change_kind: ChangeKind) -> bool {
     const IMPLICIT_TARGET_FILES: &[&str] = &["build.rs", "src/main.rs", "src/lib.rs"];
     const IMPLICIT_TARGET_DIRS: &[&str] = &["src/bin", "examples", "tests", "benches"];
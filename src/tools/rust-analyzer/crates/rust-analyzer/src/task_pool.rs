@@ -41,6 +41,11 @@ pub(crate) fn spawn_with_sender<F>(&mut self, intent: ThreadIntent, task: F)
     pub(crate) fn len(&self) -> usize {
         self.pool.len()
     }
+
+    /// See [`Pool::latency_sensitive_probe`].
+    pub(crate) fn latency_sensitive_probe(&self) -> impl Fn() -> bool + Send + Sync + 'static {
+        self.pool.latency_sensitive_probe()
+    }
 }
 
 /// `TaskQueue`, like its name suggests, queues tasks.
@@ -206,6 +206,12 @@ impl<Q, MP> LruQueryStorageOps for DerivedStorage<Q, MP>
     fn set_lru_capacity(&self, new_capacity: usize) {
         self.lru_list.set_lru_capacity(new_capacity);
     }
+
+    fn trim_lru(&self) {
+        for evicted in self.lru_list.trim() {
+            evicted.evict();
+        }
+    }
 }
 
 impl<Q, MP> DerivedQueryStorageOps<Q> for DerivedStorage<Q, MP>
@@ -580,6 +580,19 @@ pub fn set_lru_capacity(&self, cap: usize)
         self.storage.set_lru_capacity(cap);
     }
 
+    /// Evicts the values of all but the most recently used entries of the
+    /// LRU cache of this query table, without changing its capacity.
+    ///
+    /// This is useful to release memory while the database is idle; the
+    /// evicted values are recomputed on their next access. Does nothing if
+    /// LRU caching is disabled for this query.
+    pub fn trim_lru(&self)
+    where
+        Q::Storage: plumbing::LruQueryStorageOps,
+    {
+        self.storage.trim_lru();
+    }
+
     /// Marks the computed value as outdated.
     ///
     /// This causes salsa to re-execute the query function on the next access to
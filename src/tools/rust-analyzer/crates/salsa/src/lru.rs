@@ -125,6 +125,16 @@ pub(crate) fn record_use(&self, node: &Arc<Node>) -> Option<Arc<Node>> {
         self.data.lock().record_use(node)
     }
 
+    /// Drops every node that is not in the green zone from the list,
+    /// returning them so that their values can be evicted. The
+    /// capacity of the list is left untouched.
+    pub(crate) fn trim(&self) -> Vec<Arc<Node>> {
+        if self.green_zone.load(Ordering::Acquire) == 0 {
+            return Vec::new();
+        }
+        self.data.lock().trim()
+    }
+
     pub(crate) fn purge(&self) {
         self.green_zone.store(0, Ordering::SeqCst);
         *self.data.lock() = LruData::with_seed(LRU_SEED);
@@ -172,6 +182,16 @@ fn resize(&mut self, len_green_zone: usize, len_yellow_zone: usize, len_red_zone
         }
     }
 
+    fn trim(&mut self) -> Vec<Arc<Node>> {
+        let keep = std::cmp::min(self.end_green_zone, self.entries.len());
+        let trimmed = self.entries.split_off(keep);
+        for entry in &trimmed {
+            entry.lru_index().clear();
+        }
+        tracing::debug!("trimmed {} nodes", trimmed.len());
+        trimmed
+    }
+
     /// Records that a node was used. If it is already a member of the
     /// LRU list, it is promoted to the green zone (unless it's
     /// already there). Otherwise, it is added to the list first and
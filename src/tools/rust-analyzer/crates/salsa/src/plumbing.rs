@@ -229,6 +229,9 @@ pub trait InputQueryStorageOps<Q>
 /// is set independently.
 pub trait LruQueryStorageOps {
     fn set_lru_capacity(&self, new_capacity: usize);
+
+    /// Evicts the values of all but the most recently used entries.
+    fn trim_lru(&self);
 }
 
 pub trait DerivedQueryStorageOps<Q>
@@ -1,4 +1,5 @@
 //! Test setting LRU actually limits the number of things in the database;
+use salsa::debug::{DebugQueryTable, TableEntry};
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc,
@@ -100,3 +101,34 @@ fn lru_doesnt_break_volatile_queries() {
         assert_eq!(x, i)
     }
 }
+
+#[test]
+fn lru_trim_keeps_green_zone() {
+    let mut db = Database::default();
+    GetQuery.in_db_mut(&mut db).set_lru_capacity(40);
+    let memoized = |db: &Database| {
+        GetQuery
+            .in_db(db)
+            .entries::<Vec<TableEntry<_, _>>>()
+            .into_iter()
+            .filter(|entry| entry.value.is_some())
+            .count()
+    };
+
+    for i in 0..128u32 {
+        let p = db.get(i);
+        assert_eq!(p.0, i)
+    }
+    assert_eq!(memoized(&db), 40);
+
+    // Only the green zone, the hottest 10% of the capacity, survives.
+    GetQuery.in_db_mut(&mut db).trim_lru();
+    assert_eq!(memoized(&db), 4);
+
+    // The capacity itself is unchanged.
+    for i in 0..128u32 {
+        let p = db.get(i);
+        assert_eq!(p.0, i)
+    }
+    assert_eq!(memoized(&db), 40);
+}
@@ -2,7 +2,8 @@
 //! inspired by the [`threadpool` crate](http://docs.rs/threadpool).
 //! When you spawn a task you specify a thread intent
 //! so the pool can schedule it to run on a thread with that intent.
-//! rust-analyzer uses this to prioritize work based on latency requirements.
+//! rust-analyzer uses this to prioritize work based on latency requirements:
+//! latency sensitive jobs are always picked up before the other queued ones.
 //!
 //! The thread pool is implemented entirely using
 //! the threading utilities in [`crate::thread`].
@@ -12,7 +13,7 @@
     Arc,
 };
 
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{select, Receiver, Sender};
 
 use super::{Builder, JoinHandle, ThreadIntent};
 
@@ -25,8 +26,11 @@ pub struct Pool {
     // so that the channel is actually closed
     // before we join the worker threads!
     job_sender: Sender<Job>,
+    latency_sensitive_job_sender: Sender<Job>,
     _handles: Vec<JoinHandle>,
     extant_tasks: Arc<AtomicUsize>,
+    /// The latency sensitive jobs that are queued or running.
+    latency_sensitive_tasks: Arc<AtomicUsize>,
 }
 
 struct Job {
@@ -40,7 +44,10 @@ pub fn new(threads: usize) -> Pool {
         const INITIAL_INTENT: ThreadIntent = ThreadIntent::Worker;
 
         let (job_sender, job_receiver) = crossbeam_channel::unbounded();
+        let (latency_sensitive_job_sender, latency_sensitive_job_receiver) =
+            crossbeam_channel::unbounded();
         let extant_tasks = Arc::new(AtomicUsize::new(0));
+        let latency_sensitive_tasks = Arc::new(AtomicUsize::new(0));
 
         let mut handles = Vec::with_capacity(threads);
         for _ in 0..threads {
@@ -49,14 +56,24 @@ pub fn new(threads: usize) -> Pool {
                 .name("Worker".into())
                 .spawn({
                     let extant_tasks = Arc::clone(&extant_tasks);
+                    let latency_sensitive_tasks = Arc::clone(&latency_sensitive_tasks);
                     let job_receiver: Receiver<Job> = job_receiver.clone();
+                    let latency_sensitive_job_receiver: Receiver<Job> =
+                        latency_sensitive_job_receiver.clone();
                     move || {
                         let mut current_intent = INITIAL_INTENT;
-                        for job in job_receiver {
+                        while let Some(job) =
+                            next_job(&latency_sensitive_job_receiver, &job_receiver)
+                        {
                             if job.requested_intent != current_intent {
                                 job.requested_intent.apply_to_current_thread();
                                 current_intent = job.requested_intent;
                             }
+                            // Also counted down if the job panics, so that the background work
+                            // giving way to it doesn't wait forever.
+                            let _latency_sensitive = (job.requested_intent
+                                == ThreadIntent::LatencySensitive)
+                                .then(|| DecrementOnDrop(&latency_sensitive_tasks));
                             extant_tasks.fetch_add(1, Ordering::SeqCst);
                             (job.f)();
                             extant_tasks.fetch_sub(1, Ordering::SeqCst);
@@ -68,7 +85,13 @@ pub fn new(threads: usize) -> Pool {
             handles.push(handle);
         }
 
-        Pool { _handles: handles, extant_tasks, job_sender }
+        Pool {
+            _handles: handles,
+            extant_tasks,
+            latency_sensitive_tasks,
+            job_sender,
+            latency_sensitive_job_sender,
+        }
     }
 
     pub fn spawn<F>(&self, intent: ThreadIntent, f: F)
@@ -83,10 +106,105 @@ pub fn spawn<F>(&self, intent: ThreadIntent, f: F)
         });
 
         let job = Job { requested_intent: intent, f };
-        self.job_sender.send(job).unwrap();
+        match intent {
+            ThreadIntent::LatencySensitive => {
+                self.latency_sensitive_tasks.fetch_add(1, Ordering::SeqCst);
+                self.latency_sensitive_job_sender.send(job).unwrap();
+            }
+            ThreadIntent::Worker => self.job_sender.send(job).unwrap(),
+        }
     }
 
     pub fn len(&self) -> usize {
         self.extant_tasks.load(Ordering::SeqCst)
     }
+
+    /// Returns a function telling whether latency sensitive jobs are queued or running, long
+    /// running background work can check it to give way to them.
+    pub fn latency_sensitive_probe(&self) -> impl Fn() -> bool + Send + Sync + 'static {
+        let latency_sensitive_tasks = Arc::clone(&self.latency_sensitive_tasks);
+        move || latency_sensitive_tasks.load(Ordering::SeqCst) > 0
+    }
+}
+
+struct DecrementOnDrop<'a>(&'a AtomicUsize);
+
+impl Drop for DecrementOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Waits for the next job, preferring latency sensitive ones. Returns `None` once the pool is
+/// dropped and both queues are drained.
+fn next_job(latency_sensitive: &Receiver<Job>, other: &Receiver<Job>) -> Option<Job> {
+    if let Ok(job) = latency_sensitive.try_recv() {
+        return Some(job);
+    }
+    let job = select! {
+        recv(latency_sensitive) -> job => job,
+        recv(other) -> job => job,
+    };
+    // `select!` may pick a closed channel while the other one still has jobs queued.
+    job.or_else(|_| latency_sensitive.recv()).or_else(|_| other.recv()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic, thread,
+        time::{Duration, Instant},
+    };
+
+    use super::*;
+
+    fn job(requested_intent: ThreadIntent) -> Job {
+        Job { requested_intent, f: Box::new(|| ()) }
+    }
+
+    #[test]
+    fn next_job_drains_both_queues() {
+        let (latency_sensitive_sender, latency_sensitive) = crossbeam_channel::unbounded();
+        let (other_sender, other) = crossbeam_channel::unbounded();
+        for _ in 0..16 {
+            other_sender.send(job(ThreadIntent::Worker)).unwrap();
+        }
+        latency_sensitive_sender.send(job(ThreadIntent::LatencySensitive)).unwrap();
+        drop((latency_sensitive_sender, other_sender));
+
+        let intents: Vec<_> = std::iter::from_fn(|| next_job(&latency_sensitive, &other))
+            .map(|job| job.requested_intent)
+            .collect();
+        assert_eq!(intents.len(), 17);
+        assert_eq!(intents[0], ThreadIntent::LatencySensitive);
+        assert!(intents[1..].iter().all(|&it| it == ThreadIntent::Worker));
+    }
+
+    #[test]
+    fn pool_runs_queued_jobs() {
+        let ran = Arc::new(AtomicUsize::new(0));
+        let pool = Pool::new(2);
+        for intent in [ThreadIntent::Worker, ThreadIntent::LatencySensitive].repeat(8) {
+            let ran = Arc::clone(&ran);
+            pool.spawn(intent, move || {
+                ran.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        drop(pool);
+        assert_eq!(ran.load(Ordering::SeqCst), 16);
+    }
+
+    #[test]
+    fn panicking_latency_sensitive_job_is_counted_down() {
+        let pool = Pool::new(1);
+        let probe = pool.latency_sensitive_probe();
+        pool.spawn(ThreadIntent::LatencySensitive, || panic!("job panicked"));
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while probe() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!probe());
+        // Joining the worker the job panicked on passes its panic on.
+        let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| drop(pool)));
+    }
 }
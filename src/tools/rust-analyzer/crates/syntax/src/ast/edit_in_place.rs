@@ -192,25 +192,12 @@ fn create_generic_param_list(position: Position) -> ast::GenericParamList {
 
 pub trait AttrsOwnerEdit: ast::HasAttrs {
     fn remove_attrs_and_docs(&self) {
-        remove_attrs_and_docs(self.syntax());
-
-        fn remove_attrs_and_docs(node: &SyntaxNode) {
-            let mut remove_next_ws = false;
-            for child in node.children_with_tokens() {
-                match child.kind() {
-                    ATTR | COMMENT => {
-                        remove_next_ws = true;
-                        child.detach();
-                        continue;
-                    }
-                    WHITESPACE if remove_next_ws => {
-                        child.detach();
-                    }
-                    _ => (),
-                }
-                remove_next_ws = false;
-            }
-        }
+        remove_attrs(self.syntax(), false);
+    }
+
+    /// Removes the attributes and non-doc comments, keeping doc comments and `#[doc]` attributes.
+    fn remove_attrs_keeping_docs(&self) {
+        remove_attrs(self.syntax(), true);
     }
 
     fn add_attr(&self, attr: ast::Attr) {
@@ -238,6 +225,32 @@ fn add_attr(node: &SyntaxNode, attr: ast::Attr) {
 
 impl<T: ast::HasAttrs> AttrsOwnerEdit for T {}
 
+fn remove_attrs(node: &SyntaxNode, keep_docs: bool) {
+    let is_doc = |child: &SyntaxElement| match child {
+        SyntaxElement::Node(node) => ast::Attr::cast(node.clone())
+            .and_then(|attr| attr.simple_name())
+            .is_some_and(|name| name == "doc"),
+        SyntaxElement::Token(token) => {
+            ast::Comment::cast(token.clone()).is_some_and(|comment| comment.is_doc())
+        }
+    };
+    let mut remove_next_ws = false;
+    for child in node.children_with_tokens() {
+        match child.kind() {
+            ATTR | COMMENT if !(keep_docs && is_doc(&child)) => {
+                remove_next_ws = true;
+                child.detach();
+                continue;
+            }
+            WHITESPACE if remove_next_ws => {
+                child.detach();
+            }
+            _ => (),
+        }
+        remove_next_ws = false;
+    }
+}
+
 impl ast::GenericParamList {
     pub fn add_generic_param(&self, generic_param: ast::GenericParam) {
         match self.generic_params().last() {
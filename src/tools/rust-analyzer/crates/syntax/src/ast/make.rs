@@ -202,6 +202,15 @@ pub fn assoc_item_list() -> ast::AssocItemList {
     ast_from_text("impl C for D {}")
 }
 
+pub fn item_list() -> ast::ItemList {
+    ast_from_text("mod C {}")
+}
+
+pub fn mod_(name: ast::Name, body: Option<ast::ItemList>) -> ast::Module {
+    let body = body.map_or_else(|| ";".to_owned(), |body| format!(" {body}"));
+    ast_from_text(&format!("mod {name}{body}"))
+}
+
 fn merge_gen_params(
     ps: Option<ast::GenericParamList>,
     bs: Option<ast::GenericParamList>,
@@ -393,6 +402,10 @@ pub fn use_tree(
     ast_from_text(&buf)
 }
 
+pub fn rename(name: ast::Name) -> ast::Rename {
+    ast_from_text(&format!("use foo as {name};"))
+}
+
 pub fn use_tree_list(use_trees: impl IntoIterator<Item = ast::UseTree>) -> ast::UseTreeList {
     let use_trees = use_trees.into_iter().map(|it| it.syntax().clone()).join(", ");
     ast_from_text(&format!("use {{{use_trees}}};"))
@@ -583,6 +596,10 @@ pub fn expr_loop(block: ast::BlockExpr) -> ast::Expr {
     expr_from_text(&format!("loop {block}"))
 }
 
+pub fn expr_unsafe(block: ast::BlockExpr) -> ast::Expr {
+    expr_from_text(&format!("unsafe {block}"))
+}
+
 pub fn expr_prefix(op: SyntaxKind, expr: ast::Expr) -> ast::Expr {
     let token = token(op);
     expr_from_text(&format!("{token}{expr}"))
@@ -616,6 +633,9 @@ pub fn expr_field(receiver: ast::Expr, field: &str) -> ast::Expr {
 pub fn expr_paren(expr: ast::Expr) -> ast::Expr {
     expr_from_text(&format!("({expr})"))
 }
+pub fn expr_cast(expr: ast::Expr, ty: ast::Type) -> ast::Expr {
+    expr_from_text(&format!("{expr} as {ty}"))
+}
 pub fn expr_tuple(elements: impl IntoIterator<Item = ast::Expr>) -> ast::Expr {
     let expr = elements.into_iter().format(", ");
     expr_from_text(&format!("({expr})"))
@@ -984,10 +1004,18 @@ pub fn visibility_pub_crate() -> ast::Visibility {
     ast_from_text("pub(crate) struct S")
 }
 
+pub fn visibility_pub_super() -> ast::Visibility {
+    ast_from_text("pub(super) struct S")
+}
+
 pub fn visibility_pub() -> ast::Visibility {
     ast_from_text("pub struct S")
 }
 
+pub fn visibility_pub_in(path: ast::Path) -> ast::Visibility {
+    ast_from_text(&format!("pub(in {path}) struct S"))
+}
+
 pub fn tuple_field_list(fields: impl IntoIterator<Item = ast::TupleField>) -> ast::TupleFieldList {
     let fields = fields.into_iter().join(", ");
     ast_from_text(&format!("struct f({fields});"))
@@ -1191,6 +1219,12 @@ pub fn whitespace(text: &str) -> SyntaxToken {
         sf.syntax().clone_for_update().first_child_or_token().unwrap().into_token().unwrap()
     }
 
+    pub fn comment(text: &str) -> SyntaxToken {
+        assert!(text.trim_start().starts_with("//") || text.trim_start().starts_with("/*"));
+        let sf = SourceFile::parse(text, Edition::CURRENT).ok().unwrap();
+        sf.syntax().clone_for_update().first_child_or_token().unwrap().into_token().unwrap()
+    }
+
     pub fn doc_comment(text: &str) -> SyntaxToken {
         assert!(!text.trim().is_empty());
         let sf = SourceFile::parse(text, Edition::CURRENT).ok().unwrap();
@@ -2,9 +2,10 @@
 //!
 //! We use two simple strategies for this:
 //!   - if the edit modifies only a single token (like changing an identifier's
-//!     letter), we replace only this token.
-//!   - otherwise, we search for the nearest `{}` block which contains the edit
-//!     and try to parse only this block.
+//!     letter or a literal), we replace only this token.
+//!   - otherwise, we search for the nearest `{}` block or delimited token tree
+//!     (attribute arguments, macro call arguments) which contains the edit and
+//!     try to parse only this node.
 
 use parser::Reparser;
 use text_edit::Indel;
@@ -41,7 +42,8 @@ fn reparse_token(
     let prev_token = root.covering_element(edit.delete).as_token()?.clone();
     let prev_token_kind = prev_token.kind();
     match prev_token_kind {
-        WHITESPACE | COMMENT | IDENT | STRING | BYTE_STRING | C_STRING => {
+        WHITESPACE | COMMENT | IDENT | STRING | BYTE_STRING | C_STRING | INT_NUMBER | CHAR
+        | BYTE | LIFETIME_IDENT => {
             if prev_token_kind == WHITESPACE || prev_token_kind == COMMENT {
                 // removing a new line may extends previous token
                 let deleted_range = edit.delete - prev_token.text_range().start();
@@ -130,9 +132,17 @@ fn find_reparsable_node(node: &SyntaxNode, range: TextRange) -> Option<(SyntaxNo
 }
 
 fn is_balanced(lexed: &parser::LexedStr<'_>) -> bool {
-    if lexed.is_empty() || lexed.kind(0) != T!['{'] || lexed.kind(lexed.len() - 1) != T!['}'] {
+    if lexed.is_empty() {
         return false;
     }
+    match (lexed.kind(0), lexed.kind(lexed.len() - 1)) {
+        (T!['{'], T!['}']) => is_balanced_braces(lexed),
+        (T!['('], T![')']) | (T!['['], T![']']) => is_balanced_delimiters(lexed),
+        _ => false,
+    }
+}
+
+fn is_balanced_braces(lexed: &parser::LexedStr<'_>) -> bool {
     let mut balance = 0usize;
     for i in 1..lexed.len() - 1 {
         match lexed.kind(i) {
@@ -149,6 +159,29 @@ fn is_balanced(lexed: &parser::LexedStr<'_>) -> bool {
     balance == 0
 }
 
+/// Checks that all kinds of delimiters are properly nested, and that the first
+/// delimiter is only closed by the very last token.
+fn is_balanced_delimiters(lexed: &parser::LexedStr<'_>) -> bool {
+    let mut stack = Vec::new();
+    for i in 0..lexed.len() {
+        match lexed.kind(i) {
+            T!['{'] => stack.push(T!['}']),
+            T!['('] => stack.push(T![')']),
+            T!['['] => stack.push(T![']']),
+            kind @ (T!['}'] | T![')'] | T![']']) => {
+                if stack.pop() != Some(kind) {
+                    return false;
+                }
+                if stack.is_empty() && i != lexed.len() - 1 {
+                    return false;
+                }
+            }
+            _ => (),
+        }
+    }
+    stack.is_empty()
+}
+
 fn merge_errors(
     old_errors: impl IntoIterator<Item = SyntaxError>,
     new_errors: Vec<SyntaxError>,
@@ -250,7 +283,7 @@ fn foo {
 }
 ",
             "62",
-            31, // FIXME: reparse only int literal here
+            2,
         );
         do_check(
             r"
@@ -304,7 +337,24 @@ fn foo() {
 }
 ",
             "123",
-            14,
+            2,
+        );
+        do_check(
+            r"
+#[cfg($0foo$0)]
+struct S;
+",
+            "all(foo, bar)",
+            5,
+        );
+        do_check(
+            r"
+fn foo() {
+    println!($0$0);
+}
+",
+            "\"{}\", 92",
+            2,
         );
         do_check(
             r"
@@ -407,6 +457,20 @@ enum Foo {
             "Clone",
             4,
         );
+        do_check(
+            r"
+fn foo() -> u32 { $092$0 }
+",
+            "1_000u32",
+            2,
+        );
+        do_check(
+            r"
+fn foo() -> char { '$0a$0' }
+",
+            "b",
+            3,
+        );
     }
 
     #[test]
@@ -429,7 +493,7 @@ fn reparse_block_with_error_in_middle_unchanged() {
                 if {}
             }"#,
             "23",
-            105,
+            1,
         )
     }
 
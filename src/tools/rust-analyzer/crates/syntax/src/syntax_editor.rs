@@ -0,0 +1,305 @@
+//! Batch editor for syntax trees.
+//!
+//! Unlike [`ted`](crate::ted), which mutates a `clone_for_update` copy of the
+//! tree in place, [`SyntaxEditor`] only records the intended edits against
+//! elements of the original, immutable tree. They are all applied at once by
+//! [`SyntaxEditor::finish`], which reports conflicting edits (e.g. replacing
+//! both a node and one of its descendants) instead of panicking halfway
+//! through the mutation.
+use std::fmt;
+
+use crate::{ted, NodeOrToken, SyntaxElement, SyntaxNode, TextRange};
+
+pub use crate::ted::Element;
+
+#[derive(Debug)]
+pub struct SyntaxEditor {
+    root: SyntaxNode,
+    changes: Vec<Change>,
+}
+
+/// A position relative to an element of the tree being edited.
+#[derive(Debug, Clone)]
+pub enum Position {
+    Before(SyntaxElement),
+    After(SyntaxElement),
+    FirstChildOf(SyntaxNode),
+    LastChildOf(SyntaxNode),
+}
+
+impl Position {
+    pub fn before(elem: impl Element) -> Position {
+        Position::Before(elem.syntax_element())
+    }
+
+    pub fn after(elem: impl Element) -> Position {
+        Position::After(elem.syntax_element())
+    }
+
+    pub fn first_child_of(node: &(impl Into<SyntaxNode> + Clone)) -> Position {
+        Position::FirstChildOf(node.clone().into())
+    }
+
+    pub fn last_child_of(node: &(impl Into<SyntaxNode> + Clone)) -> Position {
+        Position::LastChildOf(node.clone().into())
+    }
+}
+
+#[derive(Debug)]
+enum Change {
+    Insert(Position, Vec<SyntaxElement>),
+    /// Replaces the element with the given ones, an empty list deletes it.
+    Replace(SyntaxElement, Vec<SyntaxElement>),
+}
+
+/// The result of applying all edits of a [`SyntaxEditor`].
+#[derive(Debug, Clone)]
+pub struct SyntaxEdit {
+    old_root: SyntaxNode,
+    new_root: SyntaxNode,
+}
+
+impl SyntaxEdit {
+    /// The node the editor was created for.
+    pub fn old_root(&self) -> &SyntaxNode {
+        &self.old_root
+    }
+
+    /// A mutable, detached copy of the old root with all edits applied.
+    pub fn new_root(&self) -> &SyntaxNode {
+        &self.new_root
+    }
+}
+
+/// Two recorded edits that can't both be applied, e.g. because one of them
+/// replaces a node the other one edits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditConflict {
+    pub first: TextRange,
+    pub second: TextRange,
+}
+
+impl fmt::Display for EditConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "conflicting edits at {:?} and {:?}", self.first, self.second)
+    }
+}
+
+impl std::error::Error for EditConflict {}
+
+impl SyntaxEditor {
+    /// Creates an editor for `root` and its descendants. `root` doesn't have
+    /// to be the root of the whole tree.
+    pub fn new(root: SyntaxNode) -> SyntaxEditor {
+        SyntaxEditor { root, changes: Vec::new() }
+    }
+
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    pub fn insert(&mut self, position: Position, element: impl Element) {
+        self.insert_all(position, vec![element.syntax_element()])
+    }
+
+    pub fn insert_all(&mut self, position: Position, elements: Vec<SyntaxElement>) {
+        self.changes.push(Change::Insert(position, elements))
+    }
+
+    pub fn delete(&mut self, element: impl Element) {
+        self.changes.push(Change::Replace(element.syntax_element(), Vec::new()))
+    }
+
+    pub fn replace(&mut self, old: impl Element, new: impl Element) {
+        self.replace_with_many(old, vec![new.syntax_element()])
+    }
+
+    pub fn replace_with_many(&mut self, old: impl Element, new: Vec<SyntaxElement>) {
+        self.changes.push(Change::Replace(old.syntax_element(), new))
+    }
+
+    /// Applies all recorded edits to a copy of the root.
+    pub fn finish(self) -> Result<SyntaxEdit, EditConflict> {
+        let SyntaxEditor { root, changes } = self;
+
+        let path_of = |element: &SyntaxElement| path_from(&root, element);
+        let replaced: Vec<(Vec<usize>, TextRange)> = changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Replace(old, _) => Some((path_of(old), old.text_range())),
+                Change::Insert(..) => None,
+            })
+            .collect();
+
+        // Replacing a node swallows every other edit inside of it, so these are conflicts.
+        for (i, (path, range)) in replaced.iter().enumerate() {
+            for (other_path, other_range) in &replaced[i + 1..] {
+                if path.starts_with(other_path) || other_path.starts_with(path) {
+                    return Err(EditConflict { first: *range, second: *other_range });
+                }
+            }
+        }
+        for change in &changes {
+            let Change::Insert(position, _) = change else { continue };
+            let (anchor, range, inside) = match position {
+                Position::Before(it) | Position::After(it) => (path_of(it), it.text_range(), false),
+                Position::FirstChildOf(it) | Position::LastChildOf(it) => {
+                    (path_of(&it.clone().into()), it.text_range(), true)
+                }
+            };
+            for (path, replaced_range) in &replaced {
+                let conflicts = if inside {
+                    anchor.starts_with(path)
+                } else {
+                    anchor.len() > path.len() && anchor.starts_with(path)
+                };
+                if conflicts {
+                    return Err(EditConflict { first: *replaced_range, second: range });
+                }
+            }
+        }
+
+        let new_root = root.clone_subtree().clone_for_update();
+        // Resolve all anchors before mutating anything, as the mutations shift child indices.
+        let resolve = |element: &SyntaxElement| resolve_path(&new_root, &path_of(element));
+        let changes: Vec<_> = changes
+            .into_iter()
+            .map(|change| match change {
+                Change::Insert(position, elements) => {
+                    let position = match position {
+                        Position::Before(it) => Position::Before(resolve(&it)),
+                        Position::After(it) => Position::After(resolve(&it)),
+                        Position::FirstChildOf(it) => {
+                            Position::FirstChildOf(resolve(&it.into()).into_node().unwrap())
+                        }
+                        Position::LastChildOf(it) => {
+                            Position::LastChildOf(resolve(&it.into()).into_node().unwrap())
+                        }
+                    };
+                    Change::Insert(position, elements.into_iter().map(make_insertable).collect())
+                }
+                Change::Replace(old, new) => {
+                    Change::Replace(resolve(&old), new.into_iter().map(make_insertable).collect())
+                }
+            })
+            .collect();
+
+        // Insertions go first so that they can still be anchored to replaced elements. Inserting
+        // several batches after the same element (or as the first child of the same node) would
+        // reverse them, so those are applied back to front.
+        let (forward, backward): (Vec<_>, Vec<_>) = changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Insert(position, elements) => Some((position, elements)),
+                Change::Replace(..) => None,
+            })
+            .partition(|(position, _)| {
+                matches!(position, Position::Before(_) | Position::LastChildOf(_))
+            });
+        for (position, elements) in forward.into_iter().chain(backward.into_iter().rev()) {
+            let position = match position {
+                Position::Before(it) => ted::Position::before(it),
+                Position::After(it) => ted::Position::after(it),
+                Position::FirstChildOf(it) => ted::Position::first_child_of(it),
+                Position::LastChildOf(it) => ted::Position::last_child_of(it),
+            };
+            ted::insert_all_raw(position, elements.clone());
+        }
+        for change in changes {
+            if let Change::Replace(old, new) = change {
+                if new.is_empty() {
+                    ted::remove(old);
+                } else {
+                    ted::replace_with_many(old, new);
+                }
+            }
+        }
+
+        Ok(SyntaxEdit { old_root: root, new_root })
+    }
+}
+
+/// Child indices leading from `root` to `element`.
+fn path_from(root: &SyntaxNode, element: &SyntaxElement) -> Vec<usize> {
+    let mut path = Vec::new();
+    let mut current = element.clone();
+    while current.as_node() != Some(root) {
+        path.push(current.index());
+        current = match current.parent() {
+            Some(parent) => parent.into(),
+            None => panic!("element {element:?} is not a descendant of the edited root"),
+        };
+    }
+    path.reverse();
+    path
+}
+
+fn resolve_path(root: &SyntaxNode, path: &[usize]) -> SyntaxElement {
+    let mut current: SyntaxElement = root.clone().into();
+    for &index in path {
+        current = current
+            .as_node()
+            .and_then(|node| node.children_with_tokens().nth(index))
+            .expect("path should exist in the cloned tree");
+    }
+    current
+}
+
+/// New nodes may come straight from `make`, so they are copied into a detached, mutable tree.
+/// New tokens are expected to be mutable already, e.g. created with `make::tokens`.
+fn make_insertable(element: SyntaxElement) -> SyntaxElement {
+    match element {
+        NodeOrToken::Node(node) => node.clone_subtree().clone_for_update().into(),
+        NodeOrToken::Token(token) => token.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::{
+        ast::{self, make, HasName},
+        AstNode,
+    };
+
+    use super::*;
+
+    #[test]
+    fn batch_edits() {
+        let file =
+            ast::SourceFile::parse("fn foo() {}\nfn bar() {}\n", crate::Edition::CURRENT).tree();
+        let mut fns = file.syntax().descendants().filter_map(ast::Fn::cast);
+        let (foo, bar) = (fns.next().unwrap(), fns.next().unwrap());
+
+        let mut editor = SyntaxEditor::new(file.syntax().clone());
+        editor.insert_all(
+            Position::before(foo.fn_token().unwrap()),
+            vec![
+                make::visibility_pub_crate().syntax().clone().into(),
+                make::tokens::single_space().into(),
+            ],
+        );
+        editor.replace(bar.name().unwrap().syntax(), make::name("baz").syntax());
+        let edit = editor.finish().unwrap();
+
+        expect![[r#"
+            pub(crate) fn foo() {}
+            fn baz() {}
+        "#]]
+        .assert_eq(&edit.new_root().to_string());
+        // The original tree is left untouched.
+        assert_eq!(file.syntax().to_string(), "fn foo() {}\nfn bar() {}\n");
+    }
+
+    #[test]
+    fn conflicting_edits() {
+        let file = ast::SourceFile::parse("fn foo() {}\n", crate::Edition::CURRENT).tree();
+        let foo = file.syntax().descendants().find_map(ast::Fn::cast).unwrap();
+
+        let mut editor = SyntaxEditor::new(file.syntax().clone());
+        editor.replace(foo.name().unwrap().syntax(), make::name("bar").syntax());
+        editor.delete(foo.syntax());
+        assert!(editor.finish().is_err());
+    }
+}
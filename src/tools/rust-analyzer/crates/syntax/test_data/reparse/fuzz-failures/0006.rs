@@ -0,0 +1,5 @@
+6
+3
+all(foo, bar)
+#[cfg(foo)]
+struct S;
@@ -0,0 +1,4 @@
+13
+0
+0, 
+let v = vec![1, 2];
@@ -0,0 +1,4 @@
+8
+2
+1_000
+let x = 92;
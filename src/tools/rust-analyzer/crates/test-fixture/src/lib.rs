@@ -345,7 +345,7 @@ pub fn parse_with_proc_macros(
     }
 }
 
-fn default_test_proc_macros() -> [(String, ProcMacro); 5] {
+fn default_test_proc_macros() -> [(String, ProcMacro); 6] {
     [
         (
             r#"
@@ -379,6 +379,21 @@ pub fn derive_identity(item: TokenStream) -> TokenStream {
         ),
         (
             r#"
+#[proc_macro_derive(DeriveMarker)]
+pub fn derive_marker(item: TokenStream) -> TokenStream {
+    loop {}
+}
+"#
+            .into(),
+            ProcMacro {
+                name: "DeriveMarker".into(),
+                kind: ProcMacroKind::CustomDerive,
+                expander: sync::Arc::new(DeriveMarkerProcMacroExpander),
+                disabled: false,
+            },
+        ),
+        (
+            r#"
 #[proc_macro_attribute]
 pub fn input_replace(attr: TokenStream, _item: TokenStream) -> TokenStream {
     attr
@@ -546,6 +561,51 @@ fn expand(
     }
 }
 
+// Implements the `Marker` trait of the calling crate for the annotated ADT
+#[derive(Debug)]
+struct DeriveMarkerProcMacroExpander;
+impl ProcMacroExpander for DeriveMarkerProcMacroExpander {
+    fn expand(
+        &self,
+        subtree: &Subtree<Span>,
+        _: Option<&Subtree<Span>>,
+        _: &Env,
+        _: Span,
+        call_site: Span,
+        _: Span,
+    ) -> Result<Subtree<Span>, ProcMacroExpansionError> {
+        let name = subtree
+            .token_trees
+            .iter()
+            .skip_while(|tt| {
+                !matches!(tt, TokenTree::Leaf(Leaf::Ident(it)) if matches!(&*it.text, "struct" | "enum" | "union"))
+            })
+            .nth(1);
+        let Some(TokenTree::Leaf(Leaf::Ident(name))) = name else {
+            return Err(ProcMacroExpansionError::Panic("Expected an ADT".into()));
+        };
+        let ident = |text: &str| TokenTree::Leaf(Leaf::Ident(tt::Ident::new(text, call_site)));
+        let body = Subtree {
+            delimiter: tt::Delimiter {
+                open: call_site,
+                close: call_site,
+                kind: tt::DelimiterKind::Brace,
+            },
+            token_trees: Box::new([]),
+        };
+        Ok(Subtree {
+            delimiter: tt::Delimiter::invisible_spanned(call_site),
+            token_trees: Box::new([
+                ident("impl"),
+                ident("Marker"),
+                ident("for"),
+                TokenTree::Leaf(Leaf::Ident(name.clone())),
+                TokenTree::Subtree(body),
+            ]),
+        })
+    }
+}
+
 // Pastes the attribute input as its output
 #[derive(Debug)]
 struct AttributeInputReplaceProcMacroExpander;
@@ -19,6 +19,7 @@
 //!     coerce_unsized: unsize
 //!     concat:
 //!     copy: clone
+//!     dbg:
 //!     default: sized
 //!     deref_mut: deref
 //!     deref: sized
@@ -31,6 +32,8 @@
 //!     fmt: option, result, transmute, coerce_unsized
 //!     fn:
 //!     from: sized
+//!     from_iterator: iterator
+//!     fused_iterator: iterator
 //!     future: pin
 //!     coroutine: pin
 //!     hash:
@@ -56,8 +59,10 @@
 //!     sized:
 //!     slice:
 //!     sync: sized
+//!     test:
 //!     transmute:
 //!     try: infallible
+//!     try_from: from, result
 //!     unpin: sized
 //!     unsize: sized
 //!     todo: panic
@@ -319,6 +324,27 @@ fn from(t: T) -> T {
     }
     // endregion:from
 
+    // region:try_from
+    pub trait TryFrom<T>: Sized {
+        type Error;
+        fn try_from(value: T) -> crate::result::Result<Self, Self::Error>;
+    }
+    pub trait TryInto<T>: Sized {
+        type Error;
+        fn try_into(self) -> crate::result::Result<T, Self::Error>;
+    }
+
+    impl<T, U> TryInto<U> for T
+    where
+        U: TryFrom<T>,
+    {
+        type Error = U::Error;
+        fn try_into(self) -> crate::result::Result<U, U::Error> {
+            U::try_from(self)
+        }
+    }
+    // endregion:try_from
+
     // region:as_ref
     pub trait AsRef<T: ?Sized> {
         fn as_ref(&self) -> &T;
@@ -1301,6 +1327,14 @@ fn filter_map<B, F>(self, _f: F) -> crate::iter::FilterMap<Self, F>
                     loop {}
                 }
                 // endregion:iterators
+                // region:from_iterator
+                fn collect<B: crate::iter::FromIterator<Self::Item>>(self) -> B
+                where
+                    Self: Sized,
+                {
+                    loop {}
+                }
+                // endregion:from_iterator
             }
             impl<I: Iterator + ?Sized> Iterator for &mut I {
                 type Item = I::Item;
@@ -1318,6 +1352,11 @@ pub trait IntoIterator {
                 #[lang = "into_iter"]
                 fn into_iter(self) -> Self::IntoIter;
             }
+            // region:from_iterator
+            pub trait FromIterator<A>: Sized {
+                fn from_iter<T: IntoIterator<Item = A>>(iter: T) -> Self;
+            }
+            // endregion:from_iterator
             impl<I: Iterator> IntoIterator for I {
                 type Item = I::Item;
                 type IntoIter = I;
@@ -1348,8 +1387,24 @@ fn next(&mut self) -> Option<T> {
             }
         }
         pub use self::collect::IntoIterator;
+        // region:from_iterator
+        pub use self::collect::FromIterator;
+        // endregion:from_iterator
+
+        // region:fused_iterator
+        mod marker {
+            pub trait FusedIterator: super::Iterator {}
+        }
+        pub use self::marker::FusedIterator;
+        // endregion:fused_iterator
     }
     pub use self::traits::{IntoIterator, Iterator};
+    // region:from_iterator
+    pub use self::traits::FromIterator;
+    // endregion:from_iterator
+    // region:fused_iterator
+    pub use self::traits::FusedIterator;
+    // endregion:fused_iterator
 }
 // endregion:iterator
 
@@ -1470,6 +1525,15 @@ macro_rules! unimplemented {
     }
     // endregion:unimplemented
 
+    // region:dbg
+    // Part of std, declared in here for the lints on leftover `dbg!` calls.
+    #[macro_export]
+    macro_rules! dbg {
+        ($e:expr) => {
+            $e
+        };
+    }
+    // endregion:dbg
 
     // region:derive
     pub(crate) mod builtin {
@@ -1485,6 +1549,15 @@ pub(crate) mod builtin {
     }
     // endregion:derive
 
+    // region:test
+    pub(crate) mod test {
+        #[rustc_builtin_macro]
+        pub macro test($item:item) {
+            /* compiler built-in */
+        }
+    }
+    // endregion:test
+
     // region:include
     #[rustc_builtin_macro]
     #[macro_export]
@@ -1569,9 +1642,11 @@ pub mod v1 {
             cmp::{Ord, PartialOrd},                  // :ord
             convert::AsRef,                          // :as_ref
             convert::{From, Into},                   // :from
+            convert::{TryFrom, TryInto},             // :try_from
             default::Default,                        // :default
             iter::{IntoIterator, Iterator},          // :iterator
             macros::builtin::{derive, derive_const}, // :derive
+            macros::test::test,                      // :test
             marker::Copy,                            // :copy
             marker::Send,                            // :send
             marker::Sized,                           // :sized
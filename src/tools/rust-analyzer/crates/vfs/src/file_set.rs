@@ -118,6 +118,13 @@ pub fn partition(&self, vfs: &Vfs) -> Vec<FileSet> {
         res
     }
 
+    /// Returns the index of the set `path` is put into by [`Self::partition`], or `None` if it
+    /// isn't part of any of the defined sets.
+    pub fn file_set_of(&self, path: &VfsPath) -> Option<usize> {
+        let set = self.classify(path, &mut Vec::new());
+        (set + 1 < self.len()).then_some(set)
+    }
+
     /// Number of sets that `self` can partition a [`Vfs`] into.
     fn len(&self) -> usize {
         self.n_file_sets
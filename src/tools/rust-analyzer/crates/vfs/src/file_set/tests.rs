@@ -63,3 +63,17 @@ fn name_prefix_partially_matches() {
 
     assert_eq!(partition, vec![2, 1, 0]);
 }
+
+#[test]
+fn file_set_of() {
+    let mut file_set = FileSetConfig::builder();
+    file_set.add_file_set(vec![VfsPath::new_virtual_path("/foo".into())]);
+    file_set.add_file_set(vec![VfsPath::new_virtual_path("/foo/bar".into())]);
+    let file_set = file_set.build();
+
+    let set_of = |path: &str| file_set.file_set_of(&VfsPath::new_virtual_path(path.into()));
+    assert_eq!(set_of("/foo/src/lib.rs"), Some(0));
+    assert_eq!(set_of("/foo/bar/src/lib.rs"), Some(1));
+    assert_eq!(set_of("/foo/bar_baz.rs"), Some(0));
+    assert_eq!(set_of("/quux/lib.rs"), None);
+}
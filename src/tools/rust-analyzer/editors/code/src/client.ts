@@ -394,6 +394,7 @@ class ExperimentalFeatures implements lc.StaticFeature {
         capabilities.experimental = {
             snippetTextEdit: true,
             codeActionGroup: true,
+            codeActionParameters: true,
             hoverActions: true,
             serverStatusNotification: true,
             colorDiagnosticOutput: true,
@@ -1005,6 +1005,14 @@ export function resolveCodeAction(ctx: CtxInit): Cmd {
     return async (params: lc.CodeAction) => {
         const client = ctx.client;
         params.command = undefined;
+        const prompt = params.data?.parameterPrompt;
+        if (prompt) {
+            const parameter = await vscode.window.showInputBox({ prompt });
+            if (parameter === undefined) {
+                return;
+            }
+            params.data = { ...params.data, parameter };
+        }
         const item = await client.sendRequest(lc.CodeActionResolveRequest.type, params);
         if (!item?.edit) {
             return;